@@ -0,0 +1,69 @@
+// Buffer pool benchmarks: fresh allocations against pooled buffers
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::buffers::{self, BUFFERS};
+use aws_lambda_mcp::http::vcr::Fixture;
+use aws_lambda_mcp::metrics::estimate_tokens;
+use criterion::{Criterion, criterion_group, criterion_main};
+use serde_json::Value;
+use std::hint::black_box;
+
+/// A recorded forecast, the largest response most tools return
+const FORECAST: &str =
+    include_str!("../tests/fixtures/http/api.open-meteo.com-3844fbaa552a4f30.json");
+
+/// Size of the chunks an upstream body arrives in
+const CHUNK: usize = 16 * 1024;
+
+fn forecast() -> (Value, Vec<u8>) {
+    let fixture: Fixture = serde_json::from_str(FORECAST).unwrap();
+    let body = fixture.body.into_bytes();
+    (serde_json::from_slice(&body).unwrap(), body)
+}
+
+fn measure_response(c: &mut Criterion) {
+    let (response, _) = forecast();
+    let mut group = c.benchmark_group("measure_response");
+    group.bench_function("fresh", |b| {
+        b.iter(|| {
+            let output = black_box(&response).to_string();
+            estimate_tokens(&output)
+        });
+    });
+    group.bench_function("pooled", |b| {
+        b.iter(|| {
+            let output = buffers::to_json(black_box(&response)).unwrap();
+            estimate_tokens(output.as_str())
+        });
+    });
+    group.finish();
+}
+
+fn read_body(c: &mut Criterion) {
+    let (_, body) = forecast();
+    // Repeated to the size of a long feed
+    let body = body.repeat(8);
+    let mut group = c.benchmark_group("read_body");
+    group.bench_function("fresh", |b| {
+        b.iter(|| {
+            let mut read = Vec::new();
+            for chunk in black_box(&body).chunks(CHUNK) {
+                read.extend_from_slice(chunk);
+            }
+            read.len()
+        });
+    });
+    group.bench_function("pooled", |b| {
+        b.iter(|| {
+            let mut read = BUFFERS.take();
+            for chunk in black_box(&body).chunks(CHUNK) {
+                read.extend_from_slice(chunk);
+            }
+            read.len()
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, measure_response, read_body);
+criterion_main!(benches);
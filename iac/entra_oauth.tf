@@ -33,6 +33,54 @@ resource "random_string" "project_suffix" {
 # Generate a stable UUID for the OAuth scope
 resource "random_uuid" "oauth_scope" {}
 
+# Key used by the tool Lambda to sign pagination cursors
+resource "random_password" "pagination_secret" {
+  length  = 48
+  special = false
+}
+
+# Key used by the tool Lambda to sign confirmation tokens of destructive calls
+resource "random_password" "confirmation_secret" {
+  length  = 48
+  special = false
+}
+
+# KMS key for envelope encryption of sensitive tool arguments (such as the caller's token)
+resource "aws_kms_key" "tool_arguments" {
+  description         = "${local.project_name_with_suffix} tool argument encryption"
+  enable_key_rotation = true
+  tags                = var.common_tags
+}
+
+resource "aws_kms_alias" "tool_arguments" {
+  name          = "alias/${local.project_name_with_suffix}-tool-arguments"
+  target_key_id = aws_kms_key.tool_arguments.key_id
+}
+
+# Initial key used by the interceptor to sign identity contexts for the tool Lambda
+resource "random_password" "identity_context_key" {
+  length  = 48
+  special = false
+}
+
+# Identity signing key ring; rotate by adding a key and pointing "current" at it
+resource "aws_secretsmanager_secret" "identity_context_keys" {
+  name = "${local.project_name_with_suffix}-identity-context-keys"
+  tags = var.common_tags
+}
+
+resource "aws_secretsmanager_secret_version" "identity_context_keys" {
+  secret_id = aws_secretsmanager_secret.identity_context_keys.id
+  secret_string = jsonencode({
+    current = "v1"
+    keys    = { v1 = random_password.identity_context_key.result }
+  })
+
+  lifecycle {
+    ignore_changes = [secret_string]
+  }
+}
+
 # Create Entra ID Application Registration for AgentCore Gateway
 resource "azuread_application" "agentcore_app" {
   display_name                   = local.project_name_with_suffix
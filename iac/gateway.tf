@@ -68,7 +68,8 @@ resource "aws_bedrockagentcore_gateway" "main" {
 }
 
 # Amazon Bedrock AgentCore Gateway Target (Lambda)
-# Tool schemas loaded directly from programmatically generated tool_schema.json
+# Created with the tool schemas in tool_schema.json; `make register-tools`
+# pushes them on every deploy after that
 resource "aws_bedrockagentcore_gateway_target" "lambda" {
   name               = "${local.project_name_with_suffix}-target"
   gateway_identifier = aws_bedrockagentcore_gateway.main.gateway_id
@@ -128,6 +129,12 @@ resource "aws_bedrockagentcore_gateway_target" "lambda" {
   credential_provider_configuration {
     gateway_iam_role {}
   }
+
+  # `make register-tools` keeps the tool schemas in step with the deployed
+  # code through the AgentCore API, with nesting this flattened copy loses
+  lifecycle {
+    ignore_changes = [target_configuration[0].mcp[0].lambda[0].tool_schema]
+  }
 }
 
 # Lambda permission for Amazon Bedrock AgentCore Gateway to invoke main Lambda
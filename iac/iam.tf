@@ -72,6 +72,422 @@ resource "aws_iam_role_policy" "lambda_sqs_dlq" {
   })
 }
 
+# Bedrock model invocation for the summarize_text tool
+# Inference profiles route to foundation models in several regions, so both ARN types are needed
+resource "aws_iam_role_policy" "lambda_bedrock_invoke" {
+  name = "${local.project_name_with_suffix}-lambda-bedrock-invoke"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "bedrock:InvokeModel"
+      ]
+      Resource = [
+        "arn:aws:bedrock:*::foundation-model/*",
+        "arn:aws:bedrock:*:${data.aws_caller_identity.current.account_id}:inference-profile/*",
+      ]
+    }]
+  })
+}
+
+# SNS publish for the send_notification tool, scoped to the allowlisted topics
+resource "aws_iam_role_policy" "lambda_sns_publish" {
+  count = length(var.notification_sns_topic_arns) > 0 ? 1 : 0
+  name  = "${local.project_name_with_suffix}-lambda-sns-publish"
+  role  = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "sns:Publish"
+      ]
+      Resource = var.notification_sns_topic_arns
+    }]
+  })
+}
+
+# SES send for the send_notification tool, restricted to the configured sender
+resource "aws_iam_role_policy" "lambda_ses_send" {
+  count = var.notification_ses_from_address != "" ? 1 : 0
+  name  = "${local.project_name_with_suffix}-lambda-ses-send"
+  role  = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "ses:SendEmail"
+      ]
+      Resource = "arn:aws:ses:*:${data.aws_caller_identity.current.account_id}:identity/*"
+      Condition = {
+        StringEquals = {
+          "ses:FromAddress" = var.notification_ses_from_address
+        }
+      }
+    }]
+  })
+}
+
+# Logs Insights queries for the query_logs tool, scoped to the allowlisted log groups
+# GetQueryResults and StopQuery do not support resource-level permissions
+resource "aws_iam_role_policy" "lambda_logs_query" {
+  count = length(var.query_logs_group_allowlist) > 0 ? 1 : 0
+  name  = "${local.project_name_with_suffix}-lambda-logs-query"
+  role  = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Effect = "Allow"
+        Action = [
+          "logs:StartQuery"
+        ]
+        Resource = [
+          for group in var.query_logs_group_allowlist :
+          "arn:aws:logs:*:${data.aws_caller_identity.current.account_id}:log-group:${group}:*"
+        ]
+      },
+      {
+        Effect = "Allow"
+        Action = [
+          "logs:GetQueryResults",
+          "logs:StopQuery"
+        ]
+        Resource = "*"
+      }
+    ]
+  })
+}
+
+# Read-only Cost Explorer access for the get_aws_costs tool
+# Cost Explorer does not support resource-level permissions
+resource "aws_iam_role_policy" "lambda_cost_explorer_read" {
+  name = "${local.project_name_with_suffix}-lambda-cost-explorer-read"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "ce:GetCostAndUsage"
+      ]
+      Resource = "*"
+    }]
+  })
+}
+
+# Step Functions access for start_workflow / get_workflow_status, scoped to allowlisted workflows
+resource "aws_iam_role_policy" "lambda_step_functions" {
+  count = length(var.workflows) > 0 ? 1 : 0
+  name  = "${local.project_name_with_suffix}-lambda-step-functions"
+  role  = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Effect = "Allow"
+        Action = [
+          "states:StartExecution"
+        ]
+        Resource = local.workflow_state_machine_arns
+      },
+      {
+        Effect = "Allow"
+        Action = [
+          "states:DescribeExecution"
+        ]
+        Resource = local.workflow_execution_arns
+      }
+    ]
+  })
+}
+
+# Read user preferences and record consent on them
+resource "aws_iam_role_policy" "lambda_user_preferences" {
+  name = "${local.project_name_with_suffix}-lambda-user-preferences"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "dynamodb:GetItem",
+        "dynamodb:UpdateItem"
+      ]
+      Resource = concat([aws_dynamodb_table.user_preferences.arn], local.residency_table_arns.preferences)
+    }]
+  })
+}
+
+# Remember and recall each user's latest interaction
+resource "aws_iam_role_policy" "lambda_user_memory" {
+  name = "${local.project_name_with_suffix}-lambda-user-memory"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "dynamodb:GetItem",
+        "dynamodb:PutItem"
+      ]
+      Resource = concat([aws_dynamodb_table.user_memory.arn], local.residency_table_arns.memory)
+    }]
+  })
+}
+
+# Record and read tool invocations for replay
+resource "aws_iam_role_policy" "lambda_invocation_audit" {
+  name = "${local.project_name_with_suffix}-lambda-invocation-audit"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "dynamodb:GetItem",
+        "dynamodb:PutItem"
+      ]
+      Resource = concat([aws_dynamodb_table.invocation_audit.arn], local.residency_table_arns.audit)
+    }]
+  })
+}
+
+# Record and list tool response shapes
+resource "aws_iam_role_policy" "lambda_response_shapes" {
+  name = "${local.project_name_with_suffix}-lambda-response-shapes"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "dynamodb:PutItem",
+        "dynamodb:Query"
+      ]
+      Resource = aws_dynamodb_table.response_shapes.arn
+    }]
+  })
+}
+
+# Add to and report tool usage per tenant
+resource "aws_iam_role_policy" "lambda_tool_usage" {
+  name = "${local.project_name_with_suffix}-lambda-tool-usage"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "dynamodb:UpdateItem",
+        "dynamodb:Query",
+        "dynamodb:Scan"
+      ]
+      Resource = aws_dynamodb_table.tool_usage.arn
+    }]
+  })
+}
+
+# Claim, complete and release side effects in the journal
+resource "aws_iam_role_policy" "lambda_effect_journal" {
+  name = "${local.project_name_with_suffix}-lambda-effect-journal"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "dynamodb:GetItem",
+        "dynamodb:PutItem",
+        "dynamodb:DeleteItem"
+      ]
+      Resource = aws_dynamodb_table.effect_journal.arn
+    }]
+  })
+}
+
+# Record spent confirmation tokens
+resource "aws_iam_role_policy" "lambda_confirmations" {
+  name = "${local.project_name_with_suffix}-lambda-confirmations"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect   = "Allow"
+      Action   = ["dynamodb:PutItem"]
+      Resource = aws_dynamodb_table.confirmations.arn
+    }]
+  })
+}
+
+# Receive asynchronous calls, then record and replay those that fail
+resource "aws_iam_role_policy" "lambda_async_calls" {
+  name = "${local.project_name_with_suffix}-lambda-async-calls"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Effect = "Allow"
+        Action = [
+          "sqs:ReceiveMessage",
+          "sqs:DeleteMessage",
+          "sqs:GetQueueAttributes"
+        ]
+        Resource = aws_sqs_queue.async_calls.arn
+      },
+      {
+        Effect = "Allow"
+        Action = [
+          "dynamodb:PutItem",
+          "dynamodb:GetItem",
+          "dynamodb:DeleteItem",
+          "dynamodb:Scan"
+        ]
+        Resource = aws_dynamodb_table.failed_calls.arn
+      },
+      {
+        Effect   = "Allow"
+        Action   = ["sns:Publish"]
+        Resource = aws_sns_topic.failed_calls.arn
+      }
+    ]
+  })
+}
+
+# Write captured invocation traces
+resource "aws_iam_role_policy" "lambda_trace_captures" {
+  name = "${local.project_name_with_suffix}-lambda-trace-captures"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "s3:PutObject"
+      ]
+      Resource = "${aws_s3_bucket.trace_captures.arn}/*"
+    }]
+  })
+}
+
+# Read the identity signing key ring
+resource "aws_iam_role_policy" "lambda_identity_keys" {
+  name = "${local.project_name_with_suffix}-lambda-identity-keys"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "secretsmanager:GetSecretValue"
+      ]
+      Resource = aws_secretsmanager_secret.identity_context_keys.arn
+    }]
+  })
+}
+
+# Read the configuration secret
+resource "aws_iam_role_policy" "lambda_config_secret" {
+  name = "${local.project_name_with_suffix}-lambda-config-secret"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "secretsmanager:GetSecretValue"
+      ]
+      Resource = aws_secretsmanager_secret.config.arn
+    }]
+  })
+}
+
+# Read tenants' upstream API keys
+resource "aws_iam_role_policy" "lambda_tenant_api_keys" {
+  count = length(local.tenant_api_key_secret_arns) > 0 ? 1 : 0
+  name  = "${local.project_name_with_suffix}-lambda-tenant-api-keys"
+  role  = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "secretsmanager:GetSecretValue"
+      ]
+      Resource = local.tenant_api_key_secret_arns
+    }]
+  })
+}
+
+# Read the client certificate and CA bundle for outbound mTLS
+resource "aws_iam_role_policy" "lambda_http_tls" {
+  count = length(local.http_tls_secret_arns) > 0 ? 1 : 0
+  name  = "${local.project_name_with_suffix}-lambda-http-tls"
+  role  = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "secretsmanager:GetSecretValue"
+      ]
+      Resource = local.http_tls_secret_arns
+    }]
+  })
+}
+
+# Manage network interfaces when the tool Lambda is attached to a VPC
+resource "aws_iam_role_policy_attachment" "lambda_vpc_access" {
+  count      = var.lambda_vpc_config != null ? 1 : 0
+  role       = aws_iam_role.lambda_execution.name
+  policy_arn = "arn:aws:iam::aws:policy/service-role/AWSLambdaVPCAccessExecutionRole"
+}
+
+# Decrypt data keys for encrypted tool arguments
+resource "aws_iam_role_policy" "lambda_argument_decrypt" {
+  name = "${local.project_name_with_suffix}-lambda-argument-decrypt"
+  role = aws_iam_role.lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "kms:Decrypt"
+      ]
+      Resource = aws_kms_key.tool_arguments.arn
+      Condition = {
+        StringEquals = {
+          "kms:EncryptionContext:purpose" = "tool-argument"
+        }
+      }
+    }]
+  })
+}
+
 # X-Ray Tracing Policy (disabled to reduce costs)
 # resource "aws_iam_role_policy_attachment" "lambda_xray" {
 #   role       = aws_iam_role.lambda_execution.name
@@ -139,3 +555,42 @@ resource "aws_iam_role_policy" "interceptor_lambda_sqs_dlq" {
     }]
   })
 }
+
+# Read the identity signing key ring
+resource "aws_iam_role_policy" "interceptor_lambda_identity_keys" {
+  name = "${local.project_name_with_suffix}-interceptor-lambda-identity-keys"
+  role = aws_iam_role.interceptor_lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "secretsmanager:GetSecretValue"
+      ]
+      Resource = aws_secretsmanager_secret.identity_context_keys.arn
+    }]
+  })
+}
+
+# Generate data keys to encrypt tool arguments
+resource "aws_iam_role_policy" "interceptor_lambda_argument_encrypt" {
+  name = "${local.project_name_with_suffix}-interceptor-lambda-argument-encrypt"
+  role = aws_iam_role.interceptor_lambda_execution.id
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [{
+      Effect = "Allow"
+      Action = [
+        "kms:GenerateDataKey"
+      ]
+      Resource = aws_kms_key.tool_arguments.arn
+      Condition = {
+        StringEquals = {
+          "kms:EncryptionContext:purpose" = "tool-argument"
+        }
+      }
+    }]
+  })
+}
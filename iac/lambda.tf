@@ -14,6 +14,225 @@ resource "aws_sqs_queue" "lambda_dlq" {
   tags = var.common_tags
 }
 
+# Configuration variables that override the Lambda's environment, read at
+# cold start and by admin_reload_config; edit the secret to change settings
+# without a deployment
+resource "aws_secretsmanager_secret" "config" {
+  name = "${local.project_name_with_suffix}-config"
+  tags = var.common_tags
+}
+
+resource "aws_secretsmanager_secret_version" "config" {
+  secret_id     = aws_secretsmanager_secret.config.id
+  secret_string = jsonencode({})
+
+  lifecycle {
+    ignore_changes = [secret_string]
+  }
+}
+
+# User preferences (home city, country) read by daily_briefing
+resource "aws_dynamodb_table" "user_preferences" {
+  name         = "${local.project_name_with_suffix}-user-preferences"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "user_id"
+
+  attribute {
+    name = "user_id"
+    type = "S"
+  }
+
+  tags = var.common_tags
+}
+
+# Latest interaction per user, recalled by get_personalized_greeting
+resource "aws_dynamodb_table" "user_memory" {
+  name         = "${local.project_name_with_suffix}-user-memory"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "user_id"
+
+  attribute {
+    name = "user_id"
+    type = "S"
+  }
+
+  ttl {
+    attribute_name = "expires_at"
+    enabled        = true
+  }
+
+  tags = var.common_tags
+}
+
+# Recorded tool invocations, replayed by admin_replay_invocation
+resource "aws_dynamodb_table" "invocation_audit" {
+  name         = "${local.project_name_with_suffix}-invocation-audit"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "invocation_id"
+
+  attribute {
+    name = "invocation_id"
+    type = "S"
+  }
+
+  ttl {
+    attribute_name = "expires_at"
+    enabled        = true
+  }
+
+  tags = var.common_tags
+}
+
+# Response shapes per tool and the deployment that introduced them, read by admin_tool_versions
+resource "aws_dynamodb_table" "response_shapes" {
+  name         = "${local.project_name_with_suffix}-response-shapes"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "tool"
+  range_key    = "fingerprint"
+
+  attribute {
+    name = "tool"
+    type = "S"
+  }
+
+  attribute {
+    name = "fingerprint"
+    type = "S"
+  }
+
+  tags = var.common_tags
+}
+
+# Tool calls and upstream requests per tenant, tool and day, for charge-back
+resource "aws_dynamodb_table" "tool_usage" {
+  name         = "${local.project_name_with_suffix}-tool-usage"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "tenant"
+  range_key    = "usage_key"
+
+  attribute {
+    name = "tenant"
+    type = "S"
+  }
+
+  attribute {
+    name = "usage_key"
+    type = "S"
+  }
+
+  tags = var.common_tags
+}
+
+# Side effects of notification and workflow tools by idempotency key, so retried calls are not repeated
+resource "aws_dynamodb_table" "effect_journal" {
+  name         = "${local.project_name_with_suffix}-effect-journal"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "effect_key"
+
+  attribute {
+    name = "effect_key"
+    type = "S"
+  }
+
+  ttl {
+    attribute_name = "expires_at"
+    enabled        = true
+  }
+
+  tags = var.common_tags
+}
+
+# Confirmation tokens spent on destructive tool calls, so each confirms one call
+resource "aws_dynamodb_table" "confirmations" {
+  name         = "${local.project_name_with_suffix}-confirmations"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "token_id"
+
+  attribute {
+    name = "token_id"
+    type = "S"
+  }
+
+  ttl {
+    attribute_name = "expires_at"
+    enabled        = true
+  }
+
+  tags = var.common_tags
+}
+
+# Asynchronous tool calls, one {"tool", "arguments"} per message. The Lambda
+# leaves calls failing with retryable errors on the queue and records the rest
+# itself; only messages it never answers, e.g. on timeouts, reach the DLQ
+resource "aws_sqs_queue" "async_calls" {
+  name                       = "${local.project_name_with_suffix}-async-calls"
+  message_retention_seconds  = 345600                 # 4 days
+  visibility_timeout_seconds = var.lambda_timeout * 6 # as AWS recommends for event sources
+
+  redrive_policy = jsonencode({
+    deadLetterTargetArn = aws_sqs_queue.lambda_dlq.arn
+    maxReceiveCount     = var.async_max_attempts + 1
+  })
+
+  tags = var.common_tags
+}
+
+# Asynchronous calls that failed for good, replayed by admin_replay_failed
+resource "aws_dynamodb_table" "failed_calls" {
+  name         = "${local.project_name_with_suffix}-failed-calls"
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "failure_id"
+
+  attribute {
+    name = "failure_id"
+    type = "S"
+  }
+
+  ttl {
+    attribute_name = "expires_at"
+    enabled        = true
+  }
+
+  tags = var.common_tags
+}
+
+# Each failed asynchronous call, with its payload, error code and attempts
+resource "aws_sns_topic" "failed_calls" {
+  name = "${local.project_name_with_suffix}-failed-calls"
+  tags = var.common_tags
+}
+
+# Invocation traces captured for offline debugging, expired after the retention period
+resource "aws_s3_bucket" "trace_captures" {
+  bucket = "${local.project_name_with_suffix}-trace-captures"
+
+  tags = var.common_tags
+}
+
+resource "aws_s3_bucket_public_access_block" "trace_captures" {
+  bucket = aws_s3_bucket.trace_captures.id
+
+  block_public_acls       = true
+  block_public_policy     = true
+  ignore_public_acls      = true
+  restrict_public_buckets = true
+}
+
+resource "aws_s3_bucket_lifecycle_configuration" "trace_captures" {
+  bucket = aws_s3_bucket.trace_captures.id
+
+  rule {
+    id     = "expire-traces"
+    status = "Enabled"
+
+    filter {}
+
+    expiration {
+      days = var.trace_capture_retention_days
+    }
+  }
+}
+
 # CloudWatch Log Group for main Lambda
 resource "aws_cloudwatch_log_group" "lambda_logs" {
   name              = "/aws/lambda/${local.project_name_with_suffix}"
@@ -23,6 +242,144 @@ resource "aws_cloudwatch_log_group" "lambda_logs" {
   # kms_key_id = var.cloudwatch_kms_key_arn
 }
 
+# Estimated output tokens per tool response, from the "Tool content size" log
+resource "aws_cloudwatch_log_metric_filter" "tool_output_tokens" {
+  name           = "${local.project_name_with_suffix}-tool-output-tokens"
+  log_group_name = aws_cloudwatch_log_group.lambda_logs.name
+  pattern        = "{ $.fields.message = \"Tool content size\" }"
+
+  metric_transformation {
+    name      = "ToolOutputTokens"
+    namespace = local.project_name_with_suffix
+    value     = "$.fields.output_tokens"
+    unit      = "Count"
+  }
+}
+
+# Fires when any tool response exceeds the oversized output threshold
+resource "aws_cloudwatch_metric_alarm" "tool_output_tokens" {
+  alarm_name          = "${local.project_name_with_suffix}-oversized-tool-output"
+  alarm_description   = "A tool response exceeded ${var.output_token_alert_threshold} estimated tokens"
+  namespace           = local.project_name_with_suffix
+  metric_name         = aws_cloudwatch_log_metric_filter.tool_output_tokens.metric_transformation[0].name
+  statistic           = "Maximum"
+  period              = 300
+  evaluation_periods  = 1
+  threshold           = var.output_token_alert_threshold
+  comparison_operator = "GreaterThanThreshold"
+  treat_missing_data  = "notBreaching"
+
+  tags = var.common_tags
+}
+
+# Error budget burned per SLO event, from the "SLO event" log lines; the
+# average over a window is the rate each tool burns its budget at
+resource "aws_cloudwatch_log_metric_filter" "slo_burn" {
+  for_each       = toset(["availability", "latency"])
+  name           = "${local.project_name_with_suffix}-slo-${each.key}-burn"
+  log_group_name = aws_cloudwatch_log_group.lambda_logs.name
+  pattern        = "{ $.fields.message = \"SLO event\" && $.fields.sli = \"${each.key}\" }"
+
+  metric_transformation {
+    name      = "Slo${title(each.key)}Burn"
+    namespace = local.project_name_with_suffix
+    value     = "$.fields.burn"
+    unit      = "None"
+    dimensions = {
+      Tool = "$.fields.tool"
+    }
+  }
+}
+
+# Fires when a tool burns its availability or latency error budget faster
+# than slo_burn_rate_threshold over the last hour
+resource "aws_cloudwatch_metric_alarm" "slo_burn" {
+  for_each = {
+    for pair in setproduct([for tool in jsondecode(file(local.tool_schema_path)) : tool.name], ["availability", "latency"]) :
+    "${pair[0]}-${pair[1]}" => { tool = pair[0], sli = pair[1] }
+  }
+
+  alarm_name          = "${local.project_name_with_suffix}-slo-${each.key}"
+  alarm_description   = "${each.value.tool} is burning its ${each.value.sli} error budget more than ${var.slo_burn_rate_threshold} times too fast"
+  namespace           = local.project_name_with_suffix
+  metric_name         = aws_cloudwatch_log_metric_filter.slo_burn[each.value.sli].metric_transformation[0].name
+  dimensions          = { Tool = each.value.tool }
+  statistic           = "Average"
+  period              = 3600
+  evaluation_periods  = 1
+  threshold           = var.slo_burn_rate_threshold
+  comparison_operator = "GreaterThanThreshold"
+  treat_missing_data  = "notBreaching"
+
+  tags = var.common_tags
+}
+
+# Invocations shed because the container was saturated
+resource "aws_cloudwatch_log_metric_filter" "shed_invocations" {
+  name           = "${local.project_name_with_suffix}-shed-invocations"
+  log_group_name = aws_cloudwatch_log_group.lambda_logs.name
+  pattern        = "{ $.fields.message = \"Shedding invocation, container saturated\" }"
+
+  metric_transformation {
+    name          = "ShedInvocations"
+    namespace     = local.project_name_with_suffix
+    value         = "1"
+    default_value = "0"
+    unit          = "Count"
+  }
+}
+
+# Secrets redacted from tool responses by the outbound secret scanner
+resource "aws_cloudwatch_log_metric_filter" "secrets_redacted" {
+  name           = "${local.project_name_with_suffix}-secrets-redacted"
+  log_group_name = aws_cloudwatch_log_group.lambda_logs.name
+  pattern        = "{ $.fields.message = \"Secrets redacted from tool response\" }"
+
+  metric_transformation {
+    name          = "SecretsRedacted"
+    namespace     = local.project_name_with_suffix
+    value         = "$.fields.count"
+    default_value = "0"
+    unit          = "Count"
+  }
+}
+
+# Tool calls per tenant and tool, from the "Tool usage" lines logged at each flush
+resource "aws_cloudwatch_log_metric_filter" "tool_usage_invocations" {
+  name           = "${local.project_name_with_suffix}-tool-usage-invocations"
+  log_group_name = aws_cloudwatch_log_group.lambda_logs.name
+  pattern        = "{ $.fields.message = \"Tool usage\" }"
+
+  metric_transformation {
+    name      = "TenantToolInvocations"
+    namespace = local.project_name_with_suffix
+    value     = "$.fields.invocations"
+    unit      = "Count"
+    dimensions = {
+      Tenant = "$.fields.tenant"
+      Tool   = "$.fields.tool"
+    }
+  }
+}
+
+# Upstream API requests per tenant and tool
+resource "aws_cloudwatch_log_metric_filter" "tool_usage_upstream_calls" {
+  name           = "${local.project_name_with_suffix}-tool-usage-upstream-calls"
+  log_group_name = aws_cloudwatch_log_group.lambda_logs.name
+  pattern        = "{ $.fields.message = \"Tool usage\" }"
+
+  metric_transformation {
+    name      = "TenantUpstreamCalls"
+    namespace = local.project_name_with_suffix
+    value     = "$.fields.upstream_calls"
+    unit      = "Count"
+    dimensions = {
+      Tenant = "$.fields.tenant"
+      Tool   = "$.fields.tool"
+    }
+  }
+}
+
 # CloudWatch Log Group for Interceptor Lambda
 resource "aws_cloudwatch_log_group" "interceptor_lambda_logs" {
   name              = "/aws/lambda/${local.project_name_with_suffix}-interceptor"
@@ -43,11 +400,23 @@ resource "aws_lambda_function" "bedrock_agentcore_gateway_main_lambda" {
   memory_size = var.lambda_memory_size
   timeout     = var.lambda_timeout
 
+  # Layers carrying sandboxed tool modules, mounted under /opt
+  layers = var.sandbox_tools_layer_arns
+
   # Dead Letter Queue configuration
   dead_letter_config {
     target_arn = aws_sqs_queue.lambda_dlq.arn
   }
 
+  # Attach to a VPC to reach internal services behind private ALBs
+  dynamic "vpc_config" {
+    for_each = var.lambda_vpc_config != null ? [var.lambda_vpc_config] : []
+    content {
+      subnet_ids         = vpc_config.value.subnet_ids
+      security_group_ids = vpc_config.value.security_group_ids
+    }
+  }
+
   # X-Ray tracing disabled to reduce costs (can enable if needed)
   # tracing_config {
   #   mode = "Active"
@@ -60,7 +429,7 @@ resource "aws_lambda_function" "bedrock_agentcore_gateway_main_lambda" {
   }
 
   environment {
-    variables = merge(local.common_lambda_env_vars, var.additional_env_vars)
+    variables = merge(local.common_lambda_env_vars, local.main_lambda_env_vars, var.additional_env_vars)
   }
 
   depends_on = [
@@ -68,6 +437,15 @@ resource "aws_lambda_function" "bedrock_agentcore_gateway_main_lambda" {
   ]
 }
 
+# Deliver asynchronous calls to the main Lambda, which reports the messages
+# to deliver again rather than failing the whole batch
+resource "aws_lambda_event_source_mapping" "async_calls" {
+  event_source_arn        = aws_sqs_queue.async_calls.arn
+  function_name           = aws_lambda_function.bedrock_agentcore_gateway_main_lambda.arn
+  batch_size              = 10
+  function_response_types = ["ReportBatchItemFailures"]
+}
+
 # Interceptor Lambda Function - JWT processing and HCM person ID resolution
 resource "aws_lambda_function" "gateway_interceptor" {
   function_name = "${local.project_name_with_suffix}-interceptor"
@@ -94,7 +472,7 @@ resource "aws_lambda_function" "gateway_interceptor" {
   }
 
   environment {
-    variables = merge(local.common_lambda_env_vars, var.additional_env_vars)
+    variables = merge(local.common_lambda_env_vars, local.interceptor_lambda_env_vars, var.additional_env_vars)
   }
 
   depends_on = [
@@ -22,6 +22,18 @@ locals {
     visual_studio    = "872cd9fa-d31f-45e0-9eab-6e460a02d1f1"
   }
 
+  # Tables holding the personal data of each data residency, per store
+  residency_table_arns = {
+    for store, table in {
+      preferences = aws_dynamodb_table.user_preferences.name
+      memory      = aws_dynamodb_table.user_memory.name
+      audit       = aws_dynamodb_table.invocation_audit.name
+      } : store => [
+      for residency in values(var.residency_regions) :
+      "arn:aws:dynamodb:${residency.region}:${data.aws_caller_identity.current.account_id}:table/${lookup(residency.tables, store, table)}"
+    ]
+  }
+
   # Derived paths (binary name matches Cargo package name)
   lambda_binary_path             = "../target/lambda/aws-lambda-mcp/bootstrap"
   interceptor_lambda_binary_path = "../target/lambda/interceptor/bootstrap"
@@ -130,6 +142,95 @@ locals {
 
   # Common environment variables for Lambda functions
   common_lambda_env_vars = {
-    RUST_LOG = var.rust_log_level
+    RUST_LOG            = var.rust_log_level
+    IDENTITY_SECRET_ARN = aws_secretsmanager_secret.identity_context_keys.arn
+    # Pinned: the CloudWatch metric filters in lambda.tf read the `lambda`
+    # layout (`$.fields.*`) and match nothing in the `otel` one
+    LOG_FORMAT = "lambda"
+  }
+
+  # Allowlisted Step Functions state machines and their execution ARN patterns
+  workflow_state_machine_arns = [for workflow in values(var.workflows) : workflow.state_machine_arn]
+  workflow_execution_arns = [
+    for arn in local.workflow_state_machine_arns : "${replace(arn, ":stateMachine:", ":execution:")}:*"
+  ]
+
+  # Secrets holding tenants' upstream API keys
+  tenant_api_key_secret_arns = distinct(flatten([
+    for overlay in values(var.tenant_overlays) : values(try(overlay.api_keys, {}))
+  ]))
+
+  # Secrets holding the client certificate and CA bundle for outbound mTLS
+  http_tls_secret_arns = compact([var.http_client_cert_secret_arn, var.http_ca_bundle_secret_arn])
+
+  # Environment variables only needed by the main tool Lambda
+  main_lambda_env_vars = {
+    CONFIG_SECRET_ARN                = aws_secretsmanager_secret.config.arn
+    BEDROCK_MODEL_ID                 = var.bedrock_model_id
+    SUMMARIZE_MAX_INPUT_CHARS        = tostring(var.summarize_max_input_chars)
+    SUMMARIZE_MAX_OUTPUT_TOKENS      = tostring(var.summarize_max_output_tokens)
+    NOTIFY_SNS_TOPIC_ALLOWLIST       = join(",", var.notification_sns_topic_arns)
+    NOTIFY_EMAIL_ALLOWLIST           = join(",", var.notification_email_allowlist)
+    NOTIFY_SES_FROM_ADDRESS          = var.notification_ses_from_address
+    QUERY_LOGS_GROUP_ALLOWLIST       = join(",", var.query_logs_group_allowlist)
+    QUERY_LOGS_MAX_RANGE_HOURS       = tostring(var.query_logs_max_range_hours)
+    WORKFLOWS                        = jsonencode(var.workflows)
+    SANDBOX_TOOLS                    = jsonencode(var.sandbox_tools)
+    DECLARATIVE_TOOLS                = jsonencode(var.declarative_tools)
+    RESPONSE_TRANSFORMS              = jsonencode(var.response_transforms)
+    TOOL_SCOPE_POLICY                = var.tool_scope_policy
+    ADMIN_SCOPES                     = join(",", var.admin_scopes)
+    DISABLED_TOOLS                   = join(",", var.disabled_tools)
+    PAGINATION_SECRET                = random_password.pagination_secret.result
+    RESPONSE_METRICS                 = tostring(var.response_metrics)
+    RESPONSE_SOURCES                 = tostring(var.response_sources)
+    OUTPUT_TOKEN_ALERT_THRESHOLD     = tostring(var.output_token_alert_threshold)
+    TOOL_CONCURRENCY_LIMITS          = var.tool_concurrency_limits
+    TOOL_CONCURRENCY_DEFAULT         = tostring(var.tool_concurrency_default)
+    TOOL_QUEUE_TIMEOUT_MS            = tostring(var.tool_queue_timeout_ms)
+    MAX_IN_FLIGHT                    = tostring(var.max_in_flight)
+    IDENTITY_CONTEXT_REQUIRED        = "true"
+    EGRESS_ALLOWLIST                 = join(",", var.egress_allowlist)
+    EGRESS_DENYLIST                  = join(",", var.egress_denylist)
+    EGRESS_ALLOW_PRIVATE             = tostring(var.egress_allow_private)
+    USER_PREFERENCES_TABLE           = aws_dynamodb_table.user_preferences.name
+    TOOL_DEFAULT_ARGS                = jsonencode(var.tool_default_args)
+    CONSENT_REQUIRED                 = tostring(var.consent_required)
+    RESIDENCY_CLAIM                  = var.residency_claim
+    RESIDENCY_REGIONS                = jsonencode(var.residency_regions)
+    USER_MEMORY                      = tostring(var.user_memory)
+    USER_MEMORY_TABLE                = aws_dynamodb_table.user_memory.name
+    USER_MEMORY_TTL_DAYS             = tostring(var.user_memory_ttl_days)
+    INVOCATION_AUDIT                 = tostring(var.invocation_audit)
+    INVOCATION_AUDIT_TABLE           = aws_dynamodb_table.invocation_audit.name
+    INVOCATION_AUDIT_RETENTION_HOURS = tostring(var.invocation_audit_retention_hours)
+    RESPONSE_SHAPES_TABLE            = aws_dynamodb_table.response_shapes.name
+    DEPLOYMENT_VERSION               = substr(data.archive_file.lambda_zip.output_sha256, 0, 12)
+    TENANT_OVERLAYS                  = jsonencode(var.tenant_overlays)
+    USAGE_TABLE                      = aws_dynamodb_table.tool_usage.name
+    USAGE_FLUSH_INTERVAL_SECS        = tostring(var.usage_flush_interval_secs)
+    WEBHOOK_URLS                     = join(",", var.webhook_urls)
+    WEBHOOK_SECRET                   = var.webhook_secret
+    EFFECT_JOURNAL_TABLE             = aws_dynamodb_table.effect_journal.name
+    DRY_RUN                          = tostring(var.dry_run)
+    CONFIRMATION_SECRET              = random_password.confirmation_secret.result
+    CONFIRMATION_TABLE               = aws_dynamodb_table.confirmations.name
+    CONFIRMATION_TTL_SECS            = tostring(var.confirmation_ttl_secs)
+    ASYNC_MAX_ATTEMPTS               = tostring(var.async_max_attempts)
+    FAILED_CALLS_TABLE               = aws_dynamodb_table.failed_calls.name
+    FAILED_CALLS_TOPIC_ARN           = aws_sns_topic.failed_calls.arn
+    TRACE_CAPTURE_BUCKET             = aws_s3_bucket.trace_captures.bucket
+    HTTP_CLIENT_CERT_SECRET_ARN      = var.http_client_cert_secret_arn
+    HTTP_CA_BUNDLE_SECRET_ARN        = var.http_ca_bundle_secret_arn
+    HTTP_PROXY_URL                   = var.http_proxy_url
+    HTTP_NO_PROXY                    = join(",", var.http_no_proxy)
+  }
+
+  # Environment variables only needed by the interceptor Lambda
+  interceptor_lambda_env_vars = {
+    PASSTHROUGH_MODE          = tostring(var.interceptor_passthrough_mode)
+    TOOL_SCOPE_POLICY         = var.tool_scope_policy
+    ADMIN_SCOPES              = join(",", var.admin_scopes)
+    ARG_ENCRYPTION_KMS_KEY_ID = aws_kms_key.tool_arguments.arn
   }
 }
@@ -27,6 +27,16 @@ output "lambda_function_version" {
 }
 
 
+output "async_calls_queue_url" {
+  description = "URL of the SQS queue taking asynchronous tool calls"
+  value       = aws_sqs_queue.async_calls.url
+}
+
+output "failed_calls_topic_arn" {
+  description = "ARN of the SNS topic failed asynchronous calls are published to"
+  value       = aws_sns_topic.failed_calls.arn
+}
+
 output "lambda_role_arn" {
   description = "ARN of the Lambda execution role"
   value       = aws_iam_role.lambda_execution.arn
@@ -39,10 +39,331 @@ variable "rust_log_level" {
   }
 }
 
+variable "bedrock_model_id" {
+  description = "Amazon Bedrock model or inference profile ID used by the summarize_text tool"
+  type        = string
+  default     = "apac.amazon.nova-lite-v1:0"
+}
+
+variable "summarize_max_input_chars" {
+  description = "Maximum input size (characters) accepted by summarize_text, as a cost guardrail"
+  type        = number
+  default     = 50000
+}
+
+variable "summarize_max_output_tokens" {
+  description = "Maximum summary length (tokens) summarize_text may request from the model"
+  type        = number
+  default     = 1024
+}
+
+variable "notification_sns_topic_arns" {
+  description = "SNS topic ARNs the send_notification tool may publish to (allowlist)"
+  type        = list(string)
+  default     = []
+}
+
+variable "notification_email_allowlist" {
+  description = "Email addresses or @domain suffixes the send_notification tool may email"
+  type        = list(string)
+  default     = []
+}
+
+variable "notification_ses_from_address" {
+  description = "Verified SES identity used as the sender for send_notification emails (empty disables email)"
+  type        = string
+  default     = ""
+}
+
+variable "query_logs_group_allowlist" {
+  description = "Log group names (or prefix* patterns) the query_logs tool may query"
+  type        = list(string)
+  default     = []
+}
+
+variable "query_logs_max_range_hours" {
+  description = "Widest time range, in hours, a query_logs call may cover"
+  type        = number
+  default     = 24
+}
+
+variable "workflows" {
+  description = "Step Functions state machines start_workflow may run, keyed by workflow name, with an optional JSON Schema for the input"
+  type = map(object({
+    state_machine_arn = string
+    description       = optional(string)
+    input_schema      = optional(any)
+  }))
+  default = {}
+}
+
+variable "sandbox_tools" {
+  description = "WASM tools run_sandboxed_tool may run, keyed by tool name, with the module path under the layer's sandbox-tools directory and optional schema and limits"
+  type = map(object({
+    module           = string
+    description      = optional(string)
+    input_schema     = optional(any)
+    max_memory_bytes = optional(number)
+    fuel             = optional(number)
+  }))
+  default = {}
+}
+
+variable "sandbox_tools_layer_arns" {
+  description = "Lambda layers holding the sandboxed tool modules under sandbox-tools/"
+  type        = list(string)
+  default     = []
+}
+
+variable "declarative_tools" {
+  description = "REST-wrapping tools run_declarative_tool may call, keyed by tool name, each with a request template and an optional JMESPath response expression"
+  type        = any
+  default     = {}
+}
+
+variable "response_transforms" {
+  description = "JMESPath expressions reshaping tool responses, keyed by tool name; results must still match the tool's output schema"
+  type        = map(string)
+  default     = {}
+}
+
+variable "tool_scope_policy" {
+  description = "Overrides for tool scope requirements, e.g. \"send_notification=Notifications.Send|Admin\" (empty keeps defaults)"
+  type        = string
+  default     = ""
+}
+
+variable "admin_scopes" {
+  description = "Scopes or roles allowed to call the admin_* tools (empty keeps the default, \"Admin\")"
+  type        = list(string)
+  default     = []
+}
+
+variable "disabled_tools" {
+  description = "Tools switched off at container start; admin_toggle_tool can switch them on per container"
+  type        = list(string)
+  default     = []
+}
+
+variable "egress_allowlist" {
+  description = "Domains (and their subdomains) tools may send HTTP requests to; empty allows all"
+  type        = list(string)
+  default     = []
+}
+
+variable "egress_denylist" {
+  description = "Domains tools may never send HTTP requests to; takes precedence over the allowlist"
+  type        = list(string)
+  default     = ["169.254.169.254", "169.254.170.2", "localhost"]
+}
+
+variable "egress_allow_private" {
+  description = "Let tools send HTTP requests to loopback, private and link-local addresses, e.g. upstreams inside the VPC"
+  type        = bool
+  default     = false
+}
+
+variable "lambda_vpc_config" {
+  description = "Subnets and security groups to attach the tool Lambda to, for reaching internal services behind private ALBs; null keeps it outside any VPC"
+  type = object({
+    subnet_ids         = list(string)
+    security_group_ids = list(string)
+  })
+  default = null
+}
+
+variable "http_client_cert_secret_arn" {
+  description = "Secrets Manager secret holding the PEM client certificate and private key tools present to mTLS upstreams"
+  type        = string
+  default     = ""
+}
+
+variable "http_ca_bundle_secret_arn" {
+  description = "Secrets Manager secret holding PEM CA certificates tools trust in addition to the system roots"
+  type        = string
+  default     = ""
+}
+
+variable "http_proxy_url" {
+  description = "Proxy tools send outbound HTTP requests through, e.g. http://proxy.internal:3128"
+  type        = string
+  default     = ""
+}
+
+variable "http_no_proxy" {
+  description = "Hosts, domains and CIDR ranges tools reach without the proxy"
+  type        = list(string)
+  default     = []
+}
+
+variable "interceptor_passthrough_mode" {
+  description = "Log what the interceptor would inject or block without changing requests"
+  type        = bool
+  default     = false
+}
+
+variable "response_sources" {
+  description = "List the upstreams and caches each tool response's data came from in its _meta.sources"
+  type        = bool
+  default     = true
+}
+
+variable "response_metrics" {
+  description = "Return content size and estimated token metrics in each tool response's _meta"
+  type        = bool
+  default     = false
+}
+
+variable "output_token_alert_threshold" {
+  description = "Estimated output tokens above which a tool response is logged as oversized and the alarm fires"
+  type        = number
+  default     = 8000
+}
+
+variable "slo_burn_rate_threshold" {
+  description = "Hourly error budget burn rate above which a tool's SLO alarm fires (14.4 spends 2% of a 30-day budget in an hour)"
+  type        = number
+  default     = 14.4
+}
+
+variable "tool_concurrency_limits" {
+  description = "Concurrent calls allowed per tool, e.g. \"read_feed=4;query_logs=2\" (empty for no per-tool limits)"
+  type        = string
+  default     = ""
+}
+
+variable "tool_concurrency_default" {
+  description = "Concurrent calls allowed for tools not listed in tool_concurrency_limits (0 for unlimited)"
+  type        = number
+  default     = 0
+}
+
+variable "tool_queue_timeout_ms" {
+  description = "How long a tool call waits for a free concurrency slot before failing with TooBusy"
+  type        = number
+  default     = 2000
+}
+
+variable "tool_default_args" {
+  description = "Default arguments per tool, used when neither the caller nor their preferences supply them, e.g. { get_weather = { response_version = \"2\" } }"
+  type        = any
+  default     = {}
+}
+
+variable "tenant_overlays" {
+  description = "Per-tenant overlays keyed by Entra ID tenant ID, e.g. { \"<tid>\" = { enabled_tools = [\"get_weather\"], tool_concurrency = { get_weather = 4 }, api_keys = { open_meteo = \"<secret ARN>\" }, units = \"imperial\", quota = { daily = 500, monthly = 10000 }, geofence = { countries = [\"AU\", \"NZ\"] } } }"
+  type        = any
+  default     = {}
+}
+
+variable "consent_required" {
+  description = "Only use callers' names, user IDs, preferences and past calls once they have consented with record_consent"
+  type        = bool
+  default     = false
+}
+
+variable "residency_regions" {
+  description = "Region, and optionally table per store (preferences, memory, audit), holding the personal data of callers of each data residency, e.g. { eu = { region = \"eu-central-1\" } }. The tables are not created here; deploy them in each region under the same names, or name them in tables"
+  type = map(object({
+    region = string
+    tables = optional(map(string), {})
+  }))
+  default = {}
+}
+
+variable "residency_claim" {
+  description = "Token claim naming the caller's data residency, matching a key of residency_regions"
+  type        = string
+  default     = "residency"
+}
+
+variable "user_memory" {
+  description = "Remember each signed-in user's latest tool call and mention it in get_personalized_greeting"
+  type        = bool
+  default     = false
+}
+
+variable "user_memory_ttl_days" {
+  description = "How long a user's latest tool call is remembered"
+  type        = number
+  default     = 30
+}
+
+variable "invocation_audit" {
+  description = "Record tool invocations so administrators can replay them with admin_replay_invocation"
+  type        = bool
+  default     = false
+}
+
+variable "invocation_audit_retention_hours" {
+  description = "How long recorded tool invocations are kept"
+  type        = number
+  default     = 168
+}
+
+variable "trace_capture_retention_days" {
+  description = "How long captured invocation traces are kept in S3"
+  type        = number
+  default     = 7
+}
+
+variable "usage_flush_interval_secs" {
+  description = "How often each container flushes its per-tenant tool usage counts to DynamoDB and the logs"
+  type        = number
+  default     = 60
+}
+
+variable "webhook_urls" {
+  description = "HTTPS endpoints tool lifecycle events (failure spikes, quota crossings, circuit breaker changes) are POSTed to; their hosts must pass the egress allowlist"
+  type        = list(string)
+  default     = []
+}
+
+variable "webhook_secret" {
+  description = "Key webhook payloads are signed with (HMAC-SHA256 in X-Webhook-Signature); payloads are unsigned if empty"
+  type        = string
+  default     = ""
+  sensitive   = true
+}
+
+variable "dry_run" {
+  description = "Plan every side effect of send_notification, start_workflow, record_consent and run_workflow instead of performing it, as if each call passed dry_run"
+  type        = bool
+  default     = false
+}
+
+variable "confirmation_ttl_secs" {
+  description = "Seconds a confirmation token of a start_workflow call stays valid"
+  type        = number
+  default     = 300
+}
+
+variable "async_max_attempts" {
+  description = "Deliveries of an asynchronous call failing with a retryable error before it is recorded as failed"
+  type        = number
+  default     = 3
+
+  validation {
+    condition     = var.async_max_attempts >= 1
+    error_message = "At least one attempt is required."
+  }
+}
+
+variable "max_in_flight" {
+  description = "In-flight invocations per container before new ones are shed with a retryable Overloaded error (0 disables shedding)"
+  type        = number
+  default     = 0
+}
+
 variable "additional_env_vars" {
   description = "Additional environment variables for Lambda function"
   type        = map(string)
   default     = {}
+
+  validation {
+    condition     = !contains(keys(var.additional_env_vars), "LOG_FORMAT")
+    error_message = "LOG_FORMAT is pinned to lambda, the layout the CloudWatch metric filters read"
+  }
 }
 
 variable "log_retention_days" {
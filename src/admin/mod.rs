@@ -0,0 +1,108 @@
+//! Runtime administration of the server.
+//!
+//! Tools whose names start with `admin_` form a separate tier: they are
+//! registered and routed like any other tool, but the authorization layer
+//! only admits callers holding one of the admin scopes (`ADMIN_SCOPES`,
+//! `Admin` by default), and `TOOL_SCOPE_POLICY` cannot relax that (see
+//! [`crate::auth::ToolPolicy`]). They let operators flush caches, reload
+//! configuration and switch tools off and on without console access.
+//!
+//! This state lives in the container, and Lambda runs many containers, so
+//! an admin call only affects the container that serves it. Tools that must
+//! stay off everywhere belong in `DISABLED_TOOLS`, which every container
+//! reads when it starts.
+
+use crate::cache::ManagedCache;
+use crate::config::config;
+use crate::crypto::{DECRYPT_KEYS, ENCRYPT_KEYS};
+use crate::http::dns::DNS_CACHE;
+use crate::identity::KEY_RING_CACHE;
+use crate::memo::SESSION_CACHE;
+use crate::preferences::PREFERENCES_CACHE;
+use crate::roots::ROOTS_CACHE;
+use crate::tenants::TENANT_CACHE;
+#[cfg(feature = "aws-costs")]
+use crate::tools::costs::COSTS_CACHE;
+use crate::tools::feed::FEED_CACHE;
+use crate::tools::holidays::HOLIDAY_CACHE;
+#[cfg(feature = "query-logs")]
+use crate::tools::logs::RESULTS_CACHE;
+use crate::tools::pollen::POLLEN_CACHE;
+use crate::tools::weather::{FORECAST_CACHE, GEOCODE_CACHE, RAW_FORECAST_CACHE};
+use crate::usage::USAGE_CACHE;
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// Name prefix of admin tools
+pub const ADMIN_TOOL_PREFIX: &str = "admin_";
+
+/// Tools switched off in this container
+static DISABLED_TOOLS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(config().admin.disabled_tools.iter().cloned().collect()));
+
+/// Returns `true` if `tool` belongs to the admin tier.
+#[must_use]
+pub fn is_admin_tool(tool: &str) -> bool {
+    tool.starts_with(ADMIN_TOOL_PREFIX)
+}
+
+/// Returns `true` if `tool` is switched off in this container.
+#[must_use]
+pub fn is_disabled(tool: &str) -> bool {
+    DISABLED_TOOLS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains(tool)
+}
+
+/// Switches `tool` on or off in this container, returning whether it was
+/// enabled before.
+pub fn set_enabled(tool: &str, enabled: bool) -> bool {
+    let mut disabled = DISABLED_TOOLS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if enabled {
+        !disabled.remove(tool)
+    } else {
+        disabled.insert(tool.to_string())
+    }
+}
+
+/// Every cache in the process.
+#[must_use]
+pub fn caches() -> Vec<&'static dyn ManagedCache> {
+    let caches: &[&'static dyn ManagedCache] = &[
+        &*GEOCODE_CACHE,
+        &*FORECAST_CACHE,
+        &*RAW_FORECAST_CACHE,
+        &*POLLEN_CACHE,
+        &*FEED_CACHE,
+        #[cfg(feature = "aws-costs")]
+        &*COSTS_CACHE,
+        #[cfg(feature = "query-logs")]
+        &*RESULTS_CACHE,
+        &*HOLIDAY_CACHE,
+        &*PREFERENCES_CACHE,
+        &*TENANT_CACHE,
+        &*USAGE_CACHE,
+        &*ROOTS_CACHE,
+        &*SESSION_CACHE,
+        &*DNS_CACHE,
+        &*KEY_RING_CACHE,
+        &*ENCRYPT_KEYS,
+        &*DECRYPT_KEYS,
+    ];
+    caches.to_vec()
+}
+
+/// Caches holding values derived from secrets, which a configuration reload
+/// should fetch again.
+#[must_use]
+pub fn secret_caches() -> [&'static dyn ManagedCache; 4] {
+    [
+        &*KEY_RING_CACHE,
+        &*ENCRYPT_KEYS,
+        &*DECRYPT_KEYS,
+        &*TENANT_CACHE,
+    ]
+}
@@ -0,0 +1,224 @@
+//! Schema-aware repair of argument types models commonly get wrong.
+//!
+//! Models often send `"3"` for an integer, `"yes"` for a boolean, or
+//! `"a, b"` for a list. Before a tool deserializes its arguments, each
+//! declared argument whose value does not have the type its schema asks for
+//! is converted when the conversion is unambiguous:
+//!
+//! - numeric strings to integers or numbers, and whole floats to integers
+//! - `true`/`false`, `yes`/`no`, `y`/`n`, `on`/`off` and `1`/`0` to booleans
+//! - numbers and booleans to strings
+//! - comma-separated lists and JSON array text to arrays, and single values
+//!   to one-item arrays
+//! - JSON object text to objects
+//!
+//! Nested objects and array items are repaired the same way. Values that
+//! cannot be converted are left for deserialization to reject. Every
+//! conversion is counted per tool and field (see [`crate::metrics`]), so
+//! descriptions that keep being misread can be improved.
+
+use serde_json::{Map, Number, Value};
+
+/// A conversion applied to one argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coercion {
+    /// Path of the argument, e.g. `days`, `steps[].id`
+    pub field: String,
+    /// What was converted, e.g. `string_to_integer`
+    pub kind: &'static str,
+}
+
+/// Converts the arguments in `args` to the types `schema` declares,
+/// returning the conversions made.
+pub fn coerce(schema: &Value, args: &mut Value) -> Vec<Coercion> {
+    let mut coercions = Vec::new();
+    if let Value::Object(fields) = args {
+        coerce_fields(schema, schema, fields, "", &mut coercions);
+    }
+    coercions
+}
+
+fn coerce_fields(
+    schema: &Value,
+    root: &Value,
+    fields: &mut Map<String, Value>,
+    path: &str,
+    coercions: &mut Vec<Coercion>,
+) {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    for (name, property) in properties {
+        if let Some(value) = fields.get_mut(name) {
+            let path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{path}.{name}")
+            };
+            coerce_value(property, root, value, &path, coercions);
+        }
+    }
+}
+
+fn coerce_value(
+    schema: &Value,
+    root: &Value,
+    value: &mut Value,
+    path: &str,
+    coercions: &mut Vec<Coercion>,
+) {
+    let schema = effective(schema, root);
+    let types = types(schema);
+    if !value.is_null()
+        && !types.iter().any(|t| matches_type(t, value))
+        && let Some((converted, kind)) = types.iter().find_map(|t| convert(value, t))
+    {
+        *value = converted;
+        coercions.push(Coercion {
+            field: path.to_string(),
+            kind,
+        });
+    }
+    match value {
+        Value::Object(fields) => coerce_fields(schema, root, fields, path, coercions),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                let path = format!("{path}[]");
+                for item in items {
+                    coerce_value(item_schema, root, item, &path, coercions);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The schema a value must satisfy, following `$ref`s into `$defs` and
+/// picking the non-null branch of `Option` unions.
+fn effective<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    let mut schema = schema;
+    // Bounded, in case definitions refer to each other in a cycle
+    for _ in 0..8 {
+        if let Some(target) = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+            .and_then(|pointer| root.pointer(pointer))
+        {
+            schema = target;
+            continue;
+        }
+        let branches = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array);
+        if let Some(branch) = branches.and_then(|branches| {
+            branches
+                .iter()
+                .find(|branch| branch.get("type").and_then(Value::as_str) != Some("null"))
+        }) {
+            schema = branch;
+            continue;
+        }
+        break;
+    }
+    schema
+}
+
+/// Non-null types `schema` allows; an object schema without `type` counts as
+/// an object.
+fn types(schema: &Value) -> Vec<&str> {
+    let mut types = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if types.is_empty() && schema.get("properties").is_some() {
+        types.push("object");
+    }
+    types.retain(|t| *t != "null");
+    types
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// `value` converted to `target`, with the kind of conversion.
+fn convert(value: &Value, target: &str) -> Option<(Value, &'static str)> {
+    match (target, value) {
+        ("integer", Value::String(s)) => integer(s.trim()).map(|n| (n, "string_to_integer")),
+        // Only whole floats such as `3.0`, and only within the integer range
+        ("integer", Value::Number(n)) => n
+            .as_f64()
+            .filter(|f| f.fract() == 0.0)
+            .and_then(|f| integer(&format!("{f:.0}")))
+            .map(|n| (n, "float_to_integer")),
+        ("number", Value::String(s)) => {
+            let s = s.trim();
+            integer(s)
+                .or_else(|| {
+                    s.parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .map(Value::Number)
+                })
+                .map(|n| (n, "string_to_number"))
+        }
+        ("boolean", Value::String(s)) => {
+            boolean(&s.trim().to_ascii_lowercase()).map(|b| (Value::Bool(b), "string_to_boolean"))
+        }
+        ("boolean", Value::Number(n)) => {
+            boolean(&n.to_string()).map(|b| (Value::Bool(b), "number_to_boolean"))
+        }
+        ("string", Value::Number(n)) => Some((Value::String(n.to_string()), "number_to_string")),
+        ("string", Value::Bool(b)) => Some((Value::String(b.to_string()), "boolean_to_string")),
+        ("array", Value::String(s)) => {
+            let s = s.trim();
+            if s.starts_with('[') {
+                return serde_json::from_str::<Value>(s)
+                    .ok()
+                    .filter(Value::is_array)
+                    .map(|array| (array, "json_to_array"));
+            }
+            let items = s
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect();
+            Some((Value::Array(items), "list_to_array"))
+        }
+        ("array", Value::Number(_) | Value::Bool(_)) => {
+            Some((Value::Array(vec![value.clone()]), "scalar_to_array"))
+        }
+        ("object", Value::String(s)) => serde_json::from_str::<Value>(s.trim())
+            .ok()
+            .filter(Value::is_object)
+            .map(|object| (object, "json_to_object")),
+        _ => None,
+    }
+}
+
+fn integer(s: &str) -> Option<Value> {
+    s.parse::<i64>()
+        .map(Value::from)
+        .or_else(|_| s.parse::<u64>().map(Value::from))
+        .ok()
+}
+
+fn boolean(s: &str) -> Option<bool> {
+    match s {
+        "true" | "yes" | "y" | "on" | "1" => Some(true),
+        "false" | "no" | "n" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,135 @@
+//! Strict or lenient parsing of tool arguments, and repair of their types.
+//!
+//! Tools ignore arguments they do not declare, so a client that misspells
+//! an optional argument silently gets the default instead. In strict mode
+//! such arguments are rejected, which lets staging catch client integration
+//! bugs that production tolerates. The mode is set per environment
+//! (`ARGUMENT_PARSING`) and can be overridden per tool
+//! (`ARGUMENT_PARSING_TOOLS`, e.g. `get_weather=strict;read_feed=lenient`).
+//!
+//! Arguments injected by the gateway interceptor are accepted by every tool
+//! in either mode.
+//!
+//! Declared arguments sent with the wrong type, such as a number as a
+//! string, are repaired first unless `ARGUMENT_COERCION=false` (see
+//! [`coercion`]).
+
+pub mod coercion;
+
+pub use coercion::Coercion;
+
+use crate::auth::AUTH_TOKEN_ARG;
+use crate::config::config;
+use crate::context::SESSION_ID_ARG;
+use crate::identity::{IDENTITY_CONTEXT_ARG, USER_ID_ARG, USER_NAME_ARG};
+use crate::metrics::observe_coercion;
+use crate::models::error::AppError;
+use crate::models::versioning::RESPONSE_VERSION_ARG;
+use crate::registry::ToolDescriptor;
+use lambda_runtime::tracing::{info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Arguments the interceptor may add to any tool call
+const INJECTED_ARGS: &[&str] = &[
+    USER_ID_ARG,
+    USER_NAME_ARG,
+    SESSION_ID_ARG,
+    AUTH_TOKEN_ARG,
+    IDENTITY_CONTEXT_ARG,
+    RESPONSE_VERSION_ARG,
+];
+
+/// How arguments a tool does not declare are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParsingMode {
+    /// Ignore them (the default)
+    #[default]
+    Lenient,
+    /// Reject the call
+    Strict,
+}
+
+impl ParsingMode {
+    /// Parses `lenient` or `strict`, case-insensitively.
+    #[must_use]
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "lenient" => Some(Self::Lenient),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+/// Parses per-tool modes in the form `tool=strict;other_tool=lenient`.
+///
+/// Malformed rules are skipped with a warning.
+#[must_use]
+pub fn parse_tool_modes(spec: &str) -> HashMap<String, ParsingMode> {
+    spec.split(';')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .filter_map(|rule| {
+            let parsed = rule.split_once('=').and_then(|(tool, mode)| {
+                Some((tool.trim().to_string(), ParsingMode::parse(mode)?))
+            });
+            if parsed.is_none() {
+                warn!(rule, "Ignoring malformed argument parsing rule");
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Arguments in `args` that `tool` does not declare and the interceptor
+/// does not inject, in alphabetical order.
+#[must_use]
+pub fn unknown_arguments(tool: &ToolDescriptor, args: &Value) -> Vec<String> {
+    let schema = (tool.input_schema)();
+    let declared = schema.get("properties").and_then(Value::as_object);
+    args.as_object()
+        .into_iter()
+        .flat_map(|args| args.keys())
+        .filter(|name| !INJECTED_ARGS.contains(&name.as_str()))
+        .filter(|name| declared.is_none_or(|declared| !declared.contains_key(*name)))
+        .cloned()
+        .collect()
+}
+
+/// Rejects arguments `tool` does not declare when it is parsed strictly.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` naming the unknown arguments.
+pub fn check(tool: &ToolDescriptor, args: &Value) -> Result<(), AppError> {
+    if config().arguments.mode_for(tool.name) == ParsingMode::Lenient {
+        return Ok(());
+    }
+    let unknown = unknown_arguments(tool, args);
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::ValidationError(format!(
+        "Unknown arguments for {}: {}",
+        tool.name,
+        unknown.join(", ")
+    )))
+}
+
+/// Converts `tool`'s declared arguments in `args` to the types its schema
+/// declares, counting each conversion, unless coercion is disabled.
+pub fn coerce(tool: &ToolDescriptor, args: &mut Value) {
+    if !config().arguments.coerce {
+        return;
+    }
+    for coercion in coercion::coerce((tool.input_schema)().as_value(), args) {
+        info!(
+            tool = tool.name,
+            field = %coercion.field,
+            kind = coercion.kind,
+            "Coerced tool argument"
+        );
+        observe_coercion(tool.name, &coercion.field, coercion.kind);
+    }
+}
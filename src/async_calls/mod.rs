@@ -0,0 +1,443 @@
+//! Tool calls delivered asynchronously, and the record of those that fail.
+//!
+//! Besides synchronous calls through the gateway, the tool Lambda accepts
+//! calls from an SQS queue, through an event source mapping that reports
+//! batch item failures, and from `EventBridge` rules targeting it. Each SQS
+//! message body, or each event's `detail`, is one call:
+//! `{"tool": "get_weather", "arguments": {"location": "Paris"}}`. Calls go
+//! through [`route_tool`] like any other but carry no caller: the arguments
+//! only the interceptor may set are removed, so only tools that require no
+//! scope can be called this way.
+//!
+//! A call from SQS that fails with an upstream or capacity error (`E2xxx` or
+//! `E3xxx`, see [`is_retryable`]) is left on the queue to be delivered
+//! again, until it has been attempted `ASYNC_MAX_ATTEMPTS` times. A call that
+//! fails for good, or arrives through `EventBridge`, which does not deliver
+//! a handled event again, is recorded as a [`FailedCall`] with its original
+//! payload, error code and attempt count: published to the SNS topic
+//! `FAILED_CALLS_TOPIC_ARN` names, kept in the container and, when
+//! `FAILED_CALLS_TABLE` names a `DynamoDB` table, there too, so any
+//! container can find it; either way for `FAILED_CALLS_RETENTION_HOURS`.
+//!
+//! `admin_replay_failed` runs recorded calls again with [`replay`]. Those
+//! that succeed are removed from the record; the others are kept with the
+//! new error and attempt count.
+
+use crate::aws::sdk_config;
+use crate::cache::CacheStore;
+use crate::catalog;
+use crate::config::config;
+use crate::handler::route_tool;
+use crate::interceptor::RESERVED_ARGS;
+use crate::logging;
+use crate::models::error::AppError;
+use crate::models::{InvocationOutcome, ReplayedCall};
+use crate::residency;
+use aws_lc_rs::rand;
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::Utc;
+use lambda_runtime::Diagnostic;
+use lambda_runtime::tracing::{Instrument, debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::fmt::Write;
+use std::sync::LazyLock;
+use tokio::sync::OnceCell;
+
+/// Partition key of the failed calls table
+pub const FAILURE_ID_KEY: &str = "failure_id";
+
+/// Failed calls replayed at once when none are named
+pub const DEFAULT_REPLAY_LIMIT: usize = 25;
+
+/// Failed calls recorded in this container, by ID
+static FAILED: LazyLock<CacheStore<FailedCall>> = LazyLock::new(|| {
+    let settings = &config().async_calls;
+    CacheStore::new("failed_calls", settings.retention, settings.max_entries)
+});
+
+static SNS_CLIENT: OnceCell<aws_sdk_sns::Client> = OnceCell::const_new();
+
+/// Where an asynchronous call came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Sqs,
+    EventBridge,
+}
+
+/// An asynchronous call that failed for good.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedCall {
+    pub failure_id: String,
+    /// Tool the call named, empty if it named none
+    pub tool: String,
+    /// The call as it was delivered
+    pub payload: Value,
+    pub source: Source,
+    /// Catalog code of the error, e.g. `E2002`
+    pub error_code: Option<String>,
+    pub error_type: String,
+    pub error_message: String,
+    /// Attempts made at the call, including replays
+    pub attempts: u32,
+    /// When the call last failed (RFC 3339)
+    pub failed_at: String,
+}
+
+impl FailedCall {
+    /// Records `payload` from `source` failing with `diagnostic` on attempt
+    /// `attempts`, under a new failure ID.
+    #[must_use]
+    pub fn new(payload: Value, source: Source, diagnostic: &Diagnostic, attempts: u32) -> Self {
+        let tool = payload
+            .get("tool")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let mut call = Self {
+            failure_id: new_failure_id(),
+            tool,
+            payload,
+            source,
+            error_code: None,
+            error_type: String::new(),
+            error_message: String::new(),
+            attempts,
+            failed_at: String::new(),
+        };
+        call.failed_with(diagnostic);
+        call
+    }
+
+    fn failed_with(&mut self, diagnostic: &Diagnostic) {
+        self.error_code = error_code(diagnostic);
+        self.error_type.clone_from(&diagnostic.error_type);
+        self.error_message.clone_from(&diagnostic.error_message);
+        self.failed_at = Utc::now().to_rfc3339();
+    }
+}
+
+/// A call as delivered in an SQS message body or an event's `detail`.
+#[derive(Deserialize)]
+struct AsyncCall {
+    tool: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+/// Whether `event` is a batch of SQS messages or an `EventBridge` event,
+/// rather than a call through the gateway.
+#[must_use]
+pub fn is_async_event(event: &Value) -> bool {
+    let from_sqs = event
+        .get("Records")
+        .and_then(Value::as_array)
+        .is_some_and(|records| {
+            !records.is_empty()
+                && records.iter().all(|record| {
+                    record.get("eventSource").and_then(Value::as_str) == Some("aws:sqs")
+                })
+        });
+    let from_eventbridge = event.get("detail-type").is_some() && event.get("detail").is_some();
+    from_sqs || from_eventbridge
+}
+
+/// Runs the calls in an asynchronous `event`.
+///
+/// For a batch of SQS messages, returns the batch item failures: the
+/// messages to deliver again. For an `EventBridge` event, returns the tool's
+/// response, or the ID the failure was recorded under.
+pub async fn handle(event: &Value) -> Value {
+    let Some(messages) = event.get("Records").and_then(Value::as_array) else {
+        let payload = event.get("detail").cloned().unwrap_or_default();
+        let correlation_id = event.get("id").and_then(Value::as_str).unwrap_or_default();
+        return match run(&payload, correlation_id).await {
+            Ok(response) => response,
+            Err(diagnostic) => {
+                let call = FailedCall::new(payload, Source::EventBridge, &diagnostic, 1);
+                let failure_id = call.failure_id.clone();
+                record(call).await;
+                json!({ FAILURE_ID_KEY: failure_id })
+            }
+        };
+    };
+    let mut failures = Vec::new();
+    for message in messages {
+        if handle_message(message).await {
+            let id = message.get("messageId").cloned().unwrap_or_default();
+            failures.push(json!({ "itemIdentifier": id }));
+        }
+    }
+    json!({ "batchItemFailures": failures })
+}
+
+/// Runs the call in an SQS `message`, returning whether it should be
+/// delivered again.
+async fn handle_message(message: &Value) -> bool {
+    let message_id = message
+        .get("messageId")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let attempts = message
+        .pointer("/attributes/ApproximateReceiveCount")
+        .and_then(Value::as_str)
+        .and_then(|count| count.parse::<u32>().ok())
+        .unwrap_or(1);
+    let body = message.get("body").cloned().unwrap_or_default();
+    let payload = body
+        .as_str()
+        .and_then(|body| serde_json::from_str(body).ok())
+        .unwrap_or(body);
+
+    let Err(diagnostic) = run(&payload, message_id).await else {
+        return false;
+    };
+    if is_retryable(&diagnostic) && attempts < config().async_calls.max_attempts {
+        warn!(message_id, attempts, error_type = %diagnostic.error_type, "Asynchronous call failed, leaving it to be delivered again");
+        return true;
+    }
+    record(FailedCall::new(payload, Source::Sqs, &diagnostic, attempts)).await;
+    false
+}
+
+/// Routes the call in `payload` without a caller.
+async fn run(payload: &Value, correlation_id: &str) -> Result<Value, Diagnostic> {
+    let call: AsyncCall = serde_json::from_value(payload.clone()).map_err(|e| {
+        catalog::INVALID_INPUT
+            .diagnostic("InvalidInput", &format!("Invalid asynchronous call: {e}"))
+    })?;
+    let request = json!({
+        "params": { "name": call.tool, "arguments": without_caller(call.arguments) },
+    });
+    route_tool(&call.tool, request)
+        .instrument(logging::invocation_span(&call.tool, correlation_id))
+        .await
+}
+
+/// `arguments` without the arguments only the interceptor may set.
+fn without_caller(arguments: Value) -> Value {
+    match arguments {
+        Value::Null => Value::Object(Map::new()),
+        Value::Object(mut fields) => {
+            for name in RESERVED_ARGS {
+                fields.remove(*name);
+            }
+            Value::Object(fields)
+        }
+        arguments => arguments,
+    }
+}
+
+/// Whether a call that failed with `diagnostic` may succeed if tried again:
+/// it failed with an upstream or capacity error.
+#[must_use]
+pub fn is_retryable(diagnostic: &Diagnostic) -> bool {
+    error_code(diagnostic).is_some_and(|code| code.starts_with("E2") || code.starts_with("E3"))
+}
+
+/// The catalog code `diagnostic`'s message starts with.
+fn error_code(diagnostic: &Diagnostic) -> Option<String> {
+    let (code, _) = diagnostic
+        .error_message
+        .strip_prefix('[')?
+        .split_once(']')?;
+    Some(code.to_string())
+}
+
+/// Records `call`, publishing it to the failed calls topic.
+///
+/// A call that cannot be published or written to the table is still kept in
+/// the container; the failure is logged.
+pub async fn record(call: FailedCall) {
+    warn!(
+        failure_id = %call.failure_id,
+        tool = %call.tool,
+        error_code = ?call.error_code,
+        attempts = call.attempts,
+        "Asynchronous call failed"
+    );
+    if let Some(topic_arn) = config().async_calls.topic_arn.as_deref()
+        && let Err(e) = publish(topic_arn, &call).await
+    {
+        warn!(failure_id = %call.failure_id, error = %e, "Failed to publish failed call");
+    }
+    store(call).await;
+}
+
+/// Keeps `call` in the container and the table.
+async fn store(call: FailedCall) {
+    if let Some(table) = config().async_calls.table.clone()
+        && let Err(e) = put_item(&table, &call).await
+    {
+        warn!(failure_id = %call.failure_id, error = %e, "Failed to write failed call to its table");
+    }
+    FAILED.insert(call.failure_id.clone(), call);
+}
+
+/// Finds the failed call `failure_id`, if it has not expired.
+///
+/// # Errors
+///
+/// Returns `AppError::FailedCallsError` if the table cannot be read or holds
+/// a malformed record.
+pub async fn find(failure_id: &str) -> Result<Option<FailedCall>, AppError> {
+    if let Some(call) = FAILED.get(failure_id) {
+        return Ok(Some(call));
+    }
+    let Some(table) = config().async_calls.table.clone() else {
+        return Ok(None);
+    };
+    debug!(table = %table, failure_id, "Reading failed call");
+    let output = residency::dynamodb_client(None)
+        .await
+        .get_item()
+        .table_name(&table)
+        .key(FAILURE_ID_KEY, AttributeValue::S(failure_id.to_string()))
+        .send()
+        .await
+        .map_err(|e| AppError::FailedCallsError(format!("DynamoDB GetItem failed: {e}")))?;
+    output.item().map_or(Ok(None), parse_item)
+}
+
+/// Up to `limit` recorded failed calls, oldest first.
+///
+/// # Errors
+///
+/// Returns `AppError::FailedCallsError` if the table cannot be read or holds
+/// a malformed record.
+pub async fn list(limit: usize) -> Result<Vec<FailedCall>, AppError> {
+    let mut calls = match config().async_calls.table.clone() {
+        Some(table) => scan(&table, limit).await?,
+        None => FAILED.values(),
+    };
+    calls.sort_by(|a, b| a.failed_at.cmp(&b.failed_at));
+    calls.truncate(limit);
+    Ok(calls)
+}
+
+/// Runs `call` again. It is removed from the record if it succeeds, and
+/// kept with the new error and attempt count otherwise.
+pub async fn replay(mut call: FailedCall) -> ReplayedCall {
+    let result = run(&call.payload, &call.failure_id).await;
+    call.attempts = call.attempts.saturating_add(1);
+    match &result {
+        Ok(_) => forget(&call.failure_id).await,
+        Err(diagnostic) => {
+            call.failed_with(diagnostic);
+            store(call.clone()).await;
+        }
+    }
+    ReplayedCall {
+        failure_id: call.failure_id,
+        tool: call.tool,
+        attempts: call.attempts,
+        succeeded: result.is_ok(),
+        outcome: InvocationOutcome::from(&result),
+    }
+}
+
+/// Removes the failed call `failure_id` from the record.
+async fn forget(failure_id: &str) {
+    FAILED.invalidate(failure_id);
+    let Some(table) = config().async_calls.table.clone() else {
+        return;
+    };
+    let deleted = residency::dynamodb_client(None)
+        .await
+        .delete_item()
+        .table_name(&table)
+        .key(FAILURE_ID_KEY, AttributeValue::S(failure_id.to_string()))
+        .send()
+        .await;
+    if let Err(e) = deleted {
+        warn!(failure_id, error = %e, "Failed to remove replayed call from its table");
+    }
+}
+
+async fn publish(topic_arn: &str, call: &FailedCall) -> Result<(), AppError> {
+    let message = serde_json::to_string(call)
+        .map_err(|e| AppError::FailedCallsError(format!("Failed to serialize failed call: {e}")))?;
+    SNS_CLIENT
+        .get_or_init(|| async { aws_sdk_sns::Client::new(sdk_config().await) })
+        .await
+        .publish()
+        .topic_arn(topic_arn)
+        .subject("Asynchronous tool call failed")
+        .message(message)
+        .send()
+        .await
+        .map_err(|e| AppError::FailedCallsError(format!("SNS publish failed: {e}")))?;
+    Ok(())
+}
+
+async fn put_item(table: &str, call: &FailedCall) -> Result<(), AppError> {
+    let serialized = serde_json::to_string(call)
+        .map_err(|e| AppError::FailedCallsError(format!("Failed to serialize failed call: {e}")))?;
+    let retention = i64::try_from(config().async_calls.retention.as_secs()).unwrap_or(i64::MAX);
+    let expires_at = Utc::now().timestamp().saturating_add(retention);
+    residency::dynamodb_client(None)
+        .await
+        .put_item()
+        .table_name(table)
+        .item(FAILURE_ID_KEY, AttributeValue::S(call.failure_id.clone()))
+        .item("record", AttributeValue::S(serialized))
+        .item("expires_at", AttributeValue::N(expires_at.to_string()))
+        .send()
+        .await
+        .map_err(|e| AppError::FailedCallsError(format!("DynamoDB PutItem failed: {e}")))?;
+    Ok(())
+}
+
+async fn scan(table: &str, limit: usize) -> Result<Vec<FailedCall>, AppError> {
+    debug!(table, limit, "Listing failed calls");
+    let output = residency::dynamodb_client(None)
+        .await
+        .scan()
+        .table_name(table)
+        .limit(i32::try_from(limit).unwrap_or(i32::MAX))
+        .send()
+        .await
+        .map_err(|e| AppError::FailedCallsError(format!("DynamoDB Scan failed: {e}")))?;
+    let mut calls = Vec::new();
+    for item in output.items() {
+        calls.extend(parse_item(item)?);
+    }
+    Ok(calls)
+}
+
+/// The failed call in a table item, unless it has expired.
+fn parse_item(
+    item: &std::collections::HashMap<String, AttributeValue>,
+) -> Result<Option<FailedCall>, AppError> {
+    // Expired items linger until DynamoDB gets round to deleting them
+    let expired = item
+        .get("expires_at")
+        .and_then(|value| value.as_n().ok())
+        .and_then(|value| value.parse::<i64>().ok())
+        .is_some_and(|expires_at| expires_at <= Utc::now().timestamp());
+    if expired {
+        return Ok(None);
+    }
+    let record = item
+        .get("record")
+        .and_then(|value| value.as_s().ok())
+        .ok_or_else(|| AppError::FailedCallsError("Failed call item has no record".to_string()))?;
+    serde_json::from_str(record)
+        .map(Some)
+        .map_err(|e| AppError::FailedCallsError(format!("Malformed failed call record: {e}")))
+}
+
+/// 128 random bits, hex encoded.
+fn new_failure_id() -> String {
+    let mut bytes = [0u8; 16];
+    if rand::fill(&mut bytes).is_err() {
+        warn!("Failed to generate random failure ID");
+    }
+    bytes
+        .iter()
+        .fold(String::with_capacity(32), |mut id, byte| {
+            let _ = write!(id, "{byte:02x}");
+            id
+        })
+}
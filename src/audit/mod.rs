@@ -0,0 +1,330 @@
+//! Recorded tool invocations, replayed by administrators.
+//!
+//! With `INVOCATION_AUDIT` enabled the routing layer records every call to a
+//! non-admin tool: the arguments it ran with, the caller and what it
+//! returned, under an invocation ID passed back in the response's
+//! `_meta.invocationId`. Records are kept in the container and, when
+//! `INVOCATION_AUDIT_TABLE` names a `DynamoDB` table, there too, so any
+//! container can find them; either way they expire after
+//! `INVOCATION_AUDIT_RETENTION_HOURS`. Calls by a caller with a data
+//! residency are recorded in the table of its region (see
+//! [`crate::residency`]), and looked up in every region. The caller's bearer
+//! token is never recorded.
+//!
+//! `admin_replay_invocation` runs a recorded call again with [`replay`] and
+//! compares the outcomes with [`diff`]. Replays run in sandbox mode (see
+//! [`ToolContext::sandbox`]): tools that act on the outside world, such as
+//! sending notifications or starting workflows, refuse through
+//! [`refuse_in_sandbox`] instead, so a replay of them reports the refusal as
+//! a difference. Tools that call AWS on the caller's behalf run without
+//! their token.
+
+use crate::auth::AUTH_TOKEN_ARG;
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::context::{self, ToolContext};
+use crate::metrics::META_KEY;
+use crate::models::error::AppError;
+use crate::models::{InvocationDifference, InvocationOutcome};
+use crate::registry::ToolDescriptor;
+use crate::residency::{self, Placement, Store};
+use crate::secrets;
+use aws_lc_rs::rand;
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::Utc;
+use lambda_runtime::tracing::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+/// Partition key of the audit table
+pub const INVOCATION_ID_KEY: &str = "invocation_id";
+
+/// Most differences reported for one replay
+pub const MAX_DIFFERENCES: usize = 50;
+
+/// Invocations recorded in this container, by ID
+static RECORDS: LazyLock<CacheStore<InvocationRecord>> = LazyLock::new(|| {
+    let settings = &config().audit;
+    CacheStore::new("invocations", settings.retention, settings.max_entries)
+});
+
+/// A tool call and what it returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvocationRecord {
+    pub invocation_id: String,
+    /// Registered name of the tool
+    pub tool: String,
+    /// Arguments the tool ran with, after defaults, without the bearer token
+    pub arguments: Value,
+    pub user_id: Option<String>,
+    /// Caller's data residency, which decides the region the record is kept in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub residency: Option<String>,
+    pub session_id: Option<String>,
+    /// Whether the call was a dry run (see [`crate::dry_run`])
+    #[serde(default)]
+    pub dry_run: bool,
+    /// When the call finished (RFC 3339)
+    pub recorded_at: String,
+    pub outcome: InvocationOutcome,
+}
+
+impl InvocationRecord {
+    /// Records the call to `tool` that `context` describes, under a new
+    /// invocation ID.
+    #[must_use]
+    pub fn new(
+        tool: &str,
+        mut arguments: Value,
+        context: &ToolContext,
+        outcome: InvocationOutcome,
+    ) -> Self {
+        if let Some(arguments) = arguments.as_object_mut() {
+            arguments.remove(AUTH_TOKEN_ARG);
+        }
+        Self {
+            invocation_id: new_invocation_id(),
+            tool: tool.to_string(),
+            arguments,
+            user_id: context.user_id.clone(),
+            residency: context.residency.clone(),
+            session_id: context.session_id.clone(),
+            dry_run: context.dry_run,
+            recorded_at: Utc::now().to_rfc3339(),
+            outcome,
+        }
+    }
+}
+
+/// Stores `record` and returns its invocation ID.
+///
+/// A record that cannot be written to the audit table is still kept in the
+/// container; the failure is logged rather than failing the call.
+pub async fn record(record: InvocationRecord) -> String {
+    let invocation_id = record.invocation_id.clone();
+    debug!(invocation_id = %invocation_id, tool = %record.tool, "Recording invocation");
+    let written = match residency::place_write(Store::Audit, record.residency.as_deref()) {
+        Ok(Some(placement)) => put_item(&placement, &record).await,
+        Ok(None) => Ok(()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = written {
+        warn!(invocation_id = %invocation_id, error = %e, "Failed to write invocation to the audit table");
+    }
+    RECORDS.insert(invocation_id.clone(), record);
+    invocation_id
+}
+
+/// Finds the recorded invocation `invocation_id`, if it has not expired.
+///
+/// # Errors
+///
+/// Returns `AppError::AuditError` if the audit table cannot be read or holds
+/// a malformed record.
+pub async fn find(invocation_id: &str) -> Result<Option<InvocationRecord>, AppError> {
+    if let Some(record) = RECORDS.get(invocation_id) {
+        return Ok(Some(record));
+    }
+    for placement in residency::placements(Store::Audit) {
+        if let Some(record) = get_item(&placement, invocation_id).await? {
+            return Ok(Some(record));
+        }
+    }
+    Ok(None)
+}
+
+async fn get_item(
+    placement: &Placement,
+    invocation_id: &str,
+) -> Result<Option<InvocationRecord>, AppError> {
+    debug!(table = %placement.table, region = ?placement.region, invocation_id, "Reading recorded invocation");
+    let output = residency::dynamodb_client(placement.region.as_deref())
+        .await
+        .get_item()
+        .table_name(&placement.table)
+        .key(
+            INVOCATION_ID_KEY,
+            AttributeValue::S(invocation_id.to_string()),
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::AuditError(format!("DynamoDB GetItem failed: {e}")))?;
+
+    // Expired items linger until DynamoDB gets round to deleting them
+    let Some(item) = output.item().filter(|item| {
+        item.get("expires_at")
+            .and_then(|value| value.as_n().ok())
+            .and_then(|value| value.parse::<i64>().ok())
+            .is_none_or(|expires_at| expires_at > Utc::now().timestamp())
+    }) else {
+        return Ok(None);
+    };
+    let record = item
+        .get("record")
+        .and_then(|value| value.as_s().ok())
+        .ok_or_else(|| AppError::AuditError("Audit item has no record".to_string()))?;
+    serde_json::from_str(record)
+        .map(Some)
+        .map_err(|e| AppError::AuditError(format!("Malformed audit record: {e}")))
+}
+
+/// Runs the recorded call again in sandbox mode and returns its outcome.
+///
+/// The tool runs with the recorded arguments as they are; defaults are not
+/// applied again, so the replay sees what the original call saw.
+pub async fn replay(tool: &ToolDescriptor, record: &InvocationRecord) -> InvocationOutcome {
+    let mut context = ToolContext::from_args(tool.name, &record.arguments);
+    context.residency.clone_from(&record.residency);
+    context.sandbox = true;
+    context.dry_run = record.dry_run;
+    let mut result = context::scope(context, (tool.handler)(record.arguments.clone())).await;
+    if config().secrets.scan_responses
+        && let Ok(response) = &mut result
+    {
+        secrets::scrub_response(tool.name, response);
+    }
+    InvocationOutcome::from(&result)
+}
+
+/// Refuses `action` while a recorded call is being replayed.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` in sandbox mode.
+pub fn refuse_in_sandbox(action: &str) -> Result<(), AppError> {
+    if context::current().sandbox {
+        return Err(AppError::ValidationError(format!(
+            "{action} is not performed while replaying a recorded invocation"
+        )));
+    }
+    Ok(())
+}
+
+/// Values that differ between `recorded` and `replayed`, in path order, up
+/// to [`MAX_DIFFERENCES`].
+#[must_use]
+pub fn diff(
+    recorded: &InvocationOutcome,
+    replayed: &InvocationOutcome,
+) -> Vec<InvocationDifference> {
+    let mut differences = Vec::new();
+    compare(
+        "",
+        &serde_json::to_value(recorded).unwrap_or_default(),
+        &serde_json::to_value(replayed).unwrap_or_default(),
+        &mut differences,
+    );
+    differences
+}
+
+/// Adds the invocation ID to an object response under
+/// `_meta.invocationId`, keeping any other metadata.
+///
+/// Non-object responses are left unchanged.
+pub fn attach_id(response: &mut Value, invocation_id: &str) {
+    let Value::Object(fields) = response else {
+        return;
+    };
+    let meta = fields
+        .entry(META_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(meta) = meta {
+        meta.insert("invocationId".to_string(), json!(invocation_id));
+    }
+}
+
+fn compare(
+    path: &str,
+    recorded: &Value,
+    replayed: &Value,
+    differences: &mut Vec<InvocationDifference>,
+) {
+    match (recorded, replayed) {
+        (Value::Object(recorded), Value::Object(replayed)) => {
+            let keys: BTreeSet<&String> = recorded.keys().chain(replayed.keys()).collect();
+            for key in keys {
+                let path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                compare_entries(&path, recorded.get(key), replayed.get(key), differences);
+            }
+        }
+        (Value::Array(recorded), Value::Array(replayed)) => {
+            for index in 0..recorded.len().max(replayed.len()) {
+                compare_entries(
+                    &format!("{path}/{index}"),
+                    recorded.get(index),
+                    replayed.get(index),
+                    differences,
+                );
+            }
+        }
+        _ if recorded != replayed => {
+            push(path, Some(recorded), Some(replayed), differences);
+        }
+        _ => {}
+    }
+}
+
+fn compare_entries(
+    path: &str,
+    recorded: Option<&Value>,
+    replayed: Option<&Value>,
+    differences: &mut Vec<InvocationDifference>,
+) {
+    match (recorded, replayed) {
+        (Some(recorded), Some(replayed)) => compare(path, recorded, replayed, differences),
+        _ => push(path, recorded, replayed, differences),
+    }
+}
+
+fn push(
+    path: &str,
+    recorded: Option<&Value>,
+    replayed: Option<&Value>,
+    differences: &mut Vec<InvocationDifference>,
+) {
+    if differences.len() < MAX_DIFFERENCES {
+        differences.push(InvocationDifference {
+            path: path.to_string(),
+            recorded: recorded.cloned(),
+            replayed: replayed.cloned(),
+        });
+    }
+}
+
+/// 128 random bits, hex encoded.
+fn new_invocation_id() -> String {
+    let mut bytes = [0u8; 16];
+    if rand::fill(&mut bytes).is_err() {
+        warn!("Failed to generate random invocation ID");
+    }
+    bytes
+        .iter()
+        .fold(String::with_capacity(32), |mut id, byte| {
+            let _ = write!(id, "{byte:02x}");
+            id
+        })
+}
+
+async fn put_item(placement: &Placement, record: &InvocationRecord) -> Result<(), AppError> {
+    let serialized = serde_json::to_string(record)
+        .map_err(|e| AppError::AuditError(format!("Failed to serialize record: {e}")))?;
+    let retention = i64::try_from(config().audit.retention.as_secs()).unwrap_or(i64::MAX);
+    let expires_at = Utc::now().timestamp().saturating_add(retention);
+    residency::dynamodb_client(placement.region.as_deref())
+        .await
+        .put_item()
+        .table_name(&placement.table)
+        .item(
+            INVOCATION_ID_KEY,
+            AttributeValue::S(record.invocation_id.clone()),
+        )
+        .item("record", AttributeValue::S(serialized))
+        .item("expires_at", AttributeValue::N(expires_at.to_string()))
+        .send()
+        .await
+        .map_err(|e| AppError::AuditError(format!("DynamoDB PutItem failed: {e}")))?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Scope-based authorization for tool calls.
+//!
+//! The gateway validates the caller's JWT before any request reaches this
+//! Lambda, and the interceptor forwards the raw token as the `auth_token`
+//! argument after removing any copy the client sent, in passthrough mode
+//! too. The only token a tool can receive is therefore the one the gateway
+//! validated, so the claims are decoded here without re-validating the
+//! signature, and only used to decide whether the caller holds the scopes a
+//! tool requires. The tool Lambda must only be invoked through the gateway
+//! and its interceptor.
+
+use crate::admin::is_admin_tool;
+use crate::config::config;
+use crate::models::error::AppError;
+use jsonwebtoken::dangerous::insecure_decode;
+use lambda_runtime::tracing::{info, warn};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Argument the interceptor uses to forward the caller's bearer token
+pub const AUTH_TOKEN_ARG: &str = "auth_token";
+
+/// Claims relevant to authorization decisions and profile hints.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Claims {
+    pub sub: Option<String>,
+    pub exp: Option<u64>,
+    /// Delegated scopes, space-delimited (Entra ID `scp`)
+    #[serde(default)]
+    pub scp: Option<String>,
+    /// Delegated scopes, space-delimited (OAuth 2.0 `scope`)
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Application roles (Entra ID `roles`)
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Preferred locale (OIDC `locale`), e.g. "en-AU"
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Preferred IANA timezone (OIDC `zoneinfo`)
+    #[serde(default)]
+    pub zoneinfo: Option<String>,
+    /// Every other claim, including custom ones
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+impl Claims {
+    /// All scopes and roles granted to the caller.
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scp
+            .iter()
+            .chain(self.scope.iter())
+            .flat_map(|s| s.split_whitespace())
+            .chain(self.roles.iter().map(String::as_str))
+    }
+
+    /// Returns `true` if the caller holds `scope`.
+    #[must_use]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes().any(|granted| granted == scope)
+    }
+
+    fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.exp.is_some_and(|exp| exp < now)
+    }
+}
+
+/// Scope required for admin tools unless `ADMIN_SCOPES` says otherwise
+pub const DEFAULT_ADMIN_SCOPE: &str = "Admin";
+
+/// Maps tool names to the scopes that may invoke them.
+///
+/// A tool with no entry is available to every caller. A tool with an entry
+/// requires the caller to hold at least one of the listed scopes. Admin
+/// tools (see [`crate::admin`]) always require one of the admin scopes,
+/// whatever the entries say.
+#[derive(Debug, Clone)]
+pub struct ToolPolicy {
+    required: HashMap<String, Vec<String>>,
+    admin_scopes: Vec<String>,
+}
+
+impl Default for ToolPolicy {
+    fn default() -> Self {
+        Self {
+            required: HashMap::from([
+                (
+                    "send_notification".to_string(),
+                    vec!["Notifications.Send".to_string()],
+                ),
+                (
+                    "start_workflow".to_string(),
+                    vec!["Workflows.Start".to_string()],
+                ),
+            ]),
+            admin_scopes: vec![DEFAULT_ADMIN_SCOPE.to_string()],
+        }
+    }
+}
+
+impl ToolPolicy {
+    /// Builds the default policy, with per-tool overrides from `spec`.
+    ///
+    /// The format is `tool=ScopeA|ScopeB;other_tool=ScopeC`. An empty scope
+    /// list removes the requirement for that tool.
+    #[must_use]
+    pub fn parse(spec: &str) -> Self {
+        let mut policy = Self::default();
+        for rule in spec.split(';').map(str::trim).filter(|r| !r.is_empty()) {
+            let Some((tool, scopes)) = rule.split_once('=') else {
+                warn!(rule, "Ignoring malformed tool scope rule");
+                continue;
+            };
+            if is_admin_tool(tool.trim()) {
+                warn!(
+                    rule,
+                    "Ignoring scope rule for an admin tool; set ADMIN_SCOPES instead"
+                );
+                continue;
+            }
+            let scopes: Vec<String> = scopes
+                .split('|')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect();
+            if scopes.is_empty() {
+                policy.required.remove(tool.trim());
+            } else {
+                policy.required.insert(tool.trim().to_string(), scopes);
+            }
+        }
+        policy
+    }
+
+    /// Replaces the scopes accepted for admin tools; an empty list keeps the
+    /// current ones, so admin tools can never be opened to every caller.
+    #[must_use]
+    pub fn with_admin_scopes(mut self, scopes: Vec<String>) -> Self {
+        if !scopes.is_empty() {
+            self.admin_scopes = scopes;
+        }
+        self
+    }
+
+    /// Scopes accepted for admin tools.
+    #[must_use]
+    pub fn admin_scopes(&self) -> &[String] {
+        &self.admin_scopes
+    }
+
+    /// Scopes accepted for `tool`, or `None` if the tool is unrestricted.
+    #[must_use]
+    pub fn required_scopes(&self, tool: &str) -> Option<&[String]> {
+        if is_admin_tool(tool) {
+            return Some(&self.admin_scopes);
+        }
+        self.required.get(tool).map(Vec::as_slice)
+    }
+
+    /// Whether a caller holding `claims` may invoke `tool`, without logging
+    /// the decision; `None` stands for an unauthenticated caller.
+    #[must_use]
+    pub fn permits(&self, tool: &str, claims: Option<&Claims>) -> bool {
+        self.required_scopes(tool).is_none_or(|required| {
+            claims.is_some_and(|claims| required.iter().any(|scope| claims.has_scope(scope)))
+        })
+    }
+
+    /// Checks whether the caller identified by `token` may invoke `tool`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Unauthorized` if the tool is restricted and the
+    /// token is missing, undecodable, expired, or lacks every accepted scope.
+    pub fn authorize(&self, tool: &str, token: Option<&str>) -> Result<(), AppError> {
+        let Some(required) = self.required_scopes(tool) else {
+            return Ok(());
+        };
+
+        let token = token.ok_or_else(|| {
+            AppError::Unauthorized(format!("{tool} requires an authenticated caller"))
+        })?;
+        let claims = decode_claims(token)?;
+
+        if required.iter().any(|scope| claims.has_scope(scope)) {
+            info!(tool, sub = ?claims.sub, "Caller authorized for restricted tool");
+            Ok(())
+        } else {
+            warn!(tool, sub = ?claims.sub, "Caller lacks required scope");
+            Err(AppError::Unauthorized(format!(
+                "{tool} requires one of the scopes: {}",
+                required.join(", ")
+            )))
+        }
+    }
+}
+
+/// Decodes JWT claims without signature validation and rejects expired tokens.
+///
+/// Only call this on the `auth_token` argument the interceptor injected;
+/// see the module documentation.
+///
+/// # Errors
+///
+/// Returns `AppError::Unauthorized` if the token cannot be decoded or has expired.
+pub fn decode_claims(token: &str) -> Result<Claims, AppError> {
+    let token = token.strip_prefix("Bearer ").unwrap_or(token);
+    let claims = insecure_decode::<Claims>(token)
+        .map(|data| data.claims)
+        .map_err(|e| AppError::Unauthorized(format!("Invalid auth token: {e}")))?;
+
+    if claims.is_expired() {
+        return Err(AppError::Unauthorized("Auth token has expired".to_string()));
+    }
+    Ok(claims)
+}
+
+/// Authorizes a tool call against the configured policy, reading the token
+/// from the tool arguments.
+///
+/// # Errors
+///
+/// Returns `AppError::Unauthorized` if the policy rejects the caller.
+pub fn authorize_tool_call(tool: &str, tool_args: &Value) -> Result<(), AppError> {
+    let token = tool_args.get(AUTH_TOKEN_ARG).and_then(Value::as_str);
+    config().auth.policy.authorize(tool, token)
+}
@@ -0,0 +1,16 @@
+//! Shared AWS SDK configuration.
+//!
+//! Loading credentials and region is comparatively expensive, so it happens
+//! once per container and every service client is built from the same config.
+
+use aws_config::{BehaviorVersion, SdkConfig};
+use tokio::sync::OnceCell;
+
+static SDK_CONFIG: OnceCell<SdkConfig> = OnceCell::const_new();
+
+/// Returns the shared SDK configuration, loading it from the environment on first use.
+pub async fn sdk_config() -> &'static SdkConfig {
+    SDK_CONFIG
+        .get_or_init(|| aws_config::load_defaults(BehaviorVersion::latest()))
+        .await
+}
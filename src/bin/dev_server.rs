@@ -0,0 +1,127 @@
+//! Local development server.
+//!
+//! Runs the tool handler behind a plain HTTP listener so tools can be
+//! exercised and load tested without deploying:
+//!
+//! - `POST /invoke` takes the same MCP `tools/call` or `completion/complete`
+//!   payload as the Lambda
+//! - `GET /metrics` serves the in-process counters in Prometheus text format
+//!
+//! Listens on `DEV_SERVER_ADDR` (default `127.0.0.1:9000`).
+
+use aws_lambda_mcp::completion;
+use aws_lambda_mcp::config;
+use aws_lambda_mcp::declarative;
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::http::connections::spawn_keepalive;
+use aws_lambda_mcp::http::transport;
+use aws_lambda_mcp::logging;
+use aws_lambda_mcp::metrics::{PROMETHEUS_CONTENT_TYPE, render_prometheus};
+use aws_lambda_mcp::models::completion::COMPLETE_METHOD;
+use aws_lambda_mcp::transform;
+use aws_lambda_mcp::utils::strip_gateway_prefix;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{CONTENT_TYPE, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use lambda_runtime::Error;
+use lambda_runtime::tracing::{Instrument, info, warn};
+use serde_json::{Value, json};
+use std::env;
+use std::io::stdout;
+use tokio::net::TcpListener;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:9000";
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    logging::init(stdout);
+    config::load().await?;
+    transport::load().await?;
+    declarative::load()?;
+    transform::load()?;
+    spawn_keepalive();
+
+    let addr = env::var("DEV_SERVER_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+    let listener = TcpListener::bind(&addr).await?;
+    info!("Dev server listening on http://{addr} (POST /invoke, GET /metrics)");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service_fn(handle))
+                .await
+            {
+                warn!(error = %e, "Dev server connection failed");
+            }
+        });
+    }
+}
+
+async fn handle(request: Request<Incoming>) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => Ok(respond(
+            StatusCode::OK,
+            PROMETHEUS_CONTENT_TYPE,
+            render_prometheus(),
+        )),
+        (&Method::POST, "/invoke") => invoke(request).await,
+        _ => Ok(respond(
+            StatusCode::NOT_FOUND,
+            "text/plain",
+            "Not found\n".to_string(),
+        )),
+    }
+}
+
+// Routes a tools/call or completion payload like the Lambda handler, mapping diagnostics to HTTP 500
+async fn invoke(request: Request<Incoming>) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    let body = request.into_body().collect().await?.to_bytes();
+    let Ok(payload) = serde_json::from_slice::<Value>(&body) else {
+        return Ok(respond(
+            StatusCode::BAD_REQUEST,
+            "text/plain",
+            "Request body must be JSON\n".to_string(),
+        ));
+    };
+    let tool_name = payload
+        .pointer("/params/name")
+        .and_then(Value::as_str)
+        .map_or_else(|| "unknown".to_string(), strip_gateway_prefix);
+
+    // The JSON-RPC request ID stands in for the Lambda request ID
+    let correlation_id = payload.get("id").map_or_else(String::new, |id| {
+        id.as_str().map_or_else(|| id.to_string(), String::from)
+    });
+
+    let result = if payload.get("method").and_then(Value::as_str) == Some(COMPLETE_METHOD) {
+        completion::handle(&payload)
+    } else {
+        let span = logging::invocation_span(&tool_name, &correlation_id);
+        route_tool(&tool_name, payload).instrument(span).await
+    };
+    let (status, body) = match result {
+        Ok(response) => (StatusCode::OK, response),
+        Err(diagnostic) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            json!({
+                "errorType": diagnostic.error_type,
+                "errorMessage": diagnostic.error_message,
+            }),
+        ),
+    };
+    Ok(respond(status, "application/json", body.to_string()))
+}
+
+fn respond(status: StatusCode, content_type: &'static str, body: String) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
@@ -0,0 +1,49 @@
+//! gRPC server for the tools.
+//!
+//! Serves every registered tool over gRPC (see [`aws_lambda_mcp::grpc`]) for
+//! consumers that are not Lambda functions, e.g. services in the same VPC
+//! when run as an ECS task. Listens on `GRPC_SERVER_ADDR` (default
+//! `127.0.0.1:50051`); with `--proto`, prints the generated protobuf
+//! definitions instead. Callers' tokens are verified against the issuer at
+//! `GRPC_OIDC_DISCOVERY_URL` for `GRPC_TOKEN_AUDIENCES`, and the server does
+//! not start without them.
+
+use aws_lambda_mcp::config;
+use aws_lambda_mcp::declarative;
+use aws_lambda_mcp::grpc::{ProtoFile, TokenVerifier, ToolService};
+use aws_lambda_mcp::http::connections::spawn_keepalive;
+use aws_lambda_mcp::http::transport;
+use aws_lambda_mcp::logging;
+use aws_lambda_mcp::registry::tools;
+use aws_lambda_mcp::transform;
+use lambda_runtime::Error;
+use lambda_runtime::tracing::info;
+use std::env;
+use std::io::stdout;
+use std::net::SocketAddr;
+use tonic::transport::Server;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:50051";
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    if env::args().any(|arg| arg == "--proto") {
+        print!("{}", ProtoFile::from_tools(tools())?.render());
+        return Ok(());
+    }
+
+    logging::init(stdout);
+    config::load().await?;
+    transport::load().await?;
+    declarative::load()?;
+    transform::load()?;
+    spawn_keepalive();
+
+    let addr: SocketAddr = env::var("GRPC_SERVER_ADDR")
+        .unwrap_or_else(|_| DEFAULT_ADDR.to_string())
+        .parse()?;
+    let service = ToolService::new(TokenVerifier::from_env().await?)?;
+    info!("gRPC server listening on {addr}");
+    Server::builder().add_service(service).serve(addr).await?;
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Tool registration with the Amazon Bedrock `AgentCore` Gateway.
+//!
+//! Pushes the schema of every registered tool to the gateway's Lambda target
+//! through the `AgentCore` control API, so the gateway lists exactly the
+//! tools of the code being deployed without `tool_schema.json` being copied
+//! into the target by hand.
+//!
+//! The gateway is read from `GATEWAY_ID`, and the target from
+//! `GATEWAY_TARGET_ID` or, failing that, found by `GATEWAY_TARGET_NAME`. An
+//! existing target keeps its name, credentials and Lambda, unless
+//! `LAMBDA_ARN` names another Lambda; a target that does not exist yet is
+//! created for `LAMBDA_ARN`, invoked with the gateway's IAM role. With
+//! `--dry-run`, lists the tools that would be registered instead.
+
+use anyhow::{Context, Result, bail};
+use aws_lambda_mcp::aws::sdk_config;
+use aws_lambda_mcp::registry::{target_configuration, target_lambda_arn, tool_definitions};
+use aws_sdk_bedrockagentcorecontrol::Client;
+use aws_sdk_bedrockagentcorecontrol::types::{
+    CredentialProviderConfiguration, CredentialProviderType, ToolDefinition,
+};
+use std::env;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let tools = tool_definitions()?;
+    if env::args().any(|arg| arg == "--dry-run") {
+        for tool in &tools {
+            println!("{}", tool.name());
+        }
+        println!("✅ {} tool(s) would be registered", tools.len());
+        return Ok(());
+    }
+
+    let gateway_id = var("GATEWAY_ID").context("GATEWAY_ID is not set")?;
+    let client = Client::new(sdk_config().await);
+    let count = tools.len();
+    let target_id = match var("GATEWAY_TARGET_ID") {
+        Some(target_id) => Some(target_id),
+        None => find_target(&client, &gateway_id, &target_name()?).await?,
+    };
+    let target_id = match target_id {
+        Some(target_id) => update_target(&client, &gateway_id, &target_id, tools).await?,
+        None => create_target(&client, &gateway_id, tools).await?,
+    };
+    println!("✅ Registered {count} tool(s) with gateway target {target_id}");
+    Ok(())
+}
+
+/// The ID of the target of `gateway_id` named `name`, if there is one.
+async fn find_target(client: &Client, gateway_id: &str, name: &str) -> Result<Option<String>> {
+    let mut next_token = None;
+    loop {
+        let page = client
+            .list_gateway_targets()
+            .gateway_identifier(gateway_id)
+            .set_next_token(next_token)
+            .send()
+            .await
+            .context("Failed to list gateway targets")?;
+        if let Some(target) = page.items().iter().find(|target| target.name() == name) {
+            return Ok(Some(target.target_id().to_string()));
+        }
+        next_token = page.next_token().map(String::from);
+        if next_token.is_none() {
+            return Ok(None);
+        }
+    }
+}
+
+/// Replaces the tools of an existing target, returning its ID.
+async fn update_target(
+    client: &Client,
+    gateway_id: &str,
+    target_id: &str,
+    tools: Vec<ToolDefinition>,
+) -> Result<String> {
+    let target = client
+        .get_gateway_target()
+        .gateway_identifier(gateway_id)
+        .target_id(target_id)
+        .send()
+        .await
+        .with_context(|| format!("Failed to read gateway target {target_id}"))?;
+    let lambda_arn = match var("LAMBDA_ARN") {
+        Some(lambda_arn) => lambda_arn,
+        None => target
+            .target_configuration()
+            .and_then(target_lambda_arn)
+            .map(String::from)
+            .with_context(|| format!("Gateway target {target_id} is not a Lambda target"))?,
+    };
+    client
+        .update_gateway_target()
+        .gateway_identifier(gateway_id)
+        .target_id(target_id)
+        .name(target.name())
+        .set_description(target.description().map(String::from))
+        .target_configuration(target_configuration(&lambda_arn, tools)?)
+        .set_credential_provider_configurations(Some(
+            target.credential_provider_configurations().to_vec(),
+        ))
+        .send()
+        .await
+        .with_context(|| format!("Failed to update gateway target {target_id}"))?;
+    Ok(target_id.to_string())
+}
+
+/// Creates a target for `LAMBDA_ARN` with `tools`, returning its ID.
+async fn create_target(
+    client: &Client,
+    gateway_id: &str,
+    tools: Vec<ToolDefinition>,
+) -> Result<String> {
+    let Some(lambda_arn) = var("LAMBDA_ARN") else {
+        bail!("The gateway target does not exist yet; set LAMBDA_ARN to create it");
+    };
+    let credentials = CredentialProviderConfiguration::builder()
+        .credential_provider_type(CredentialProviderType::GatewayIamRole)
+        .build()?;
+    let target = client
+        .create_gateway_target()
+        .gateway_identifier(gateway_id)
+        .name(target_name()?)
+        .description("Lambda target with MCP tools registered by register-tools")
+        .target_configuration(target_configuration(&lambda_arn, tools)?)
+        .credential_provider_configurations(credentials)
+        .send()
+        .await
+        .context("Failed to create gateway target")?;
+    Ok(target.target_id().to_string())
+}
+
+fn target_name() -> Result<String> {
+    var("GATEWAY_TARGET_NAME").context("Set GATEWAY_TARGET_ID or GATEWAY_TARGET_NAME")
+}
+
+/// The value of the environment variable `key`, unless it is unset or blank.
+fn var(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
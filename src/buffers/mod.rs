@@ -0,0 +1,136 @@
+//! Byte buffers reused across invocations.
+//!
+//! Every tool call serializes its arguments and response at least once to
+//! measure them (see [`crate::metrics::ContentMetrics`]), again to compress
+//! large responses, and reads upstream bodies before parsing them. Each of
+//! these would otherwise allocate a buffer, grow it by doubling and free it
+//! before the invocation ends. [`BUFFERS`] keeps the freed buffers instead,
+//! so a warm container serializes into memory it has already allocated.
+//!
+//! A buffer returns to the pool when its [`Buffer`] is dropped, cleared but
+//! with its capacity kept. Buffers that grew past [`MAX_RETAINED_CAPACITY`]
+//! are freed rather than pooled, so one oversized response does not pin
+//! megabytes for the lifetime of the container, and at most [`MAX_POOLED`]
+//! are kept.
+
+use serde::Serialize;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, PoisonError};
+
+/// Buffers kept for reuse
+pub const MAX_POOLED: usize = 8;
+
+/// Largest capacity a buffer may have and still be pooled
+pub const MAX_RETAINED_CAPACITY: usize = 1024 * 1024;
+
+/// Capacity of buffers allocated when the pool is empty, unless the pool
+/// keeps only smaller ones
+const INITIAL_CAPACITY: usize = 4 * 1024;
+
+/// The process-wide pool.
+pub static BUFFERS: BufferPool = BufferPool::new(MAX_POOLED, MAX_RETAINED_CAPACITY);
+
+/// A pool of byte buffers.
+#[derive(Debug)]
+pub struct BufferPool {
+    free: Mutex<Vec<Vec<u8>>>,
+    max_pooled: usize,
+    max_capacity: usize,
+}
+
+impl BufferPool {
+    /// Creates an empty pool keeping up to `max_pooled` buffers of at most
+    /// `max_capacity` bytes.
+    #[must_use]
+    pub const fn new(max_pooled: usize, max_capacity: usize) -> Self {
+        Self {
+            free: Mutex::new(Vec::new()),
+            max_pooled,
+            max_capacity,
+        }
+    }
+
+    /// Takes an empty buffer, reusing a pooled one if there is one.
+    #[must_use]
+    pub fn take(&self) -> Buffer<'_> {
+        let bytes = self
+            .free
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(INITIAL_CAPACITY.min(self.max_capacity)));
+        Buffer { bytes, pool: self }
+    }
+
+    /// Number of buffers waiting to be reused.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.free
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Whether no buffers are waiting to be reused.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn give_back(&self, mut bytes: Vec<u8>) {
+        if bytes.capacity() > self.max_capacity {
+            return;
+        }
+        let mut free = self.free.lock().unwrap_or_else(PoisonError::into_inner);
+        if free.len() < self.max_pooled {
+            bytes.clear();
+            free.push(bytes);
+        }
+    }
+}
+
+/// A buffer taken from a [`BufferPool`], returned to it when dropped.
+#[derive(Debug)]
+pub struct Buffer<'a> {
+    bytes: Vec<u8>,
+    pool: &'a BufferPool,
+}
+
+impl Buffer<'_> {
+    /// The contents as text, or `""` if they are not valid UTF-8.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes).unwrap_or_default()
+    }
+}
+
+impl Deref for Buffer<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.bytes
+    }
+}
+
+impl DerefMut for Buffer<'_> {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.bytes
+    }
+}
+
+impl Drop for Buffer<'_> {
+    fn drop(&mut self) {
+        self.pool.give_back(std::mem::take(&mut self.bytes));
+    }
+}
+
+/// Serializes `value` as JSON into a pooled buffer.
+///
+/// # Errors
+///
+/// Returns an error if `value` cannot be serialized.
+pub fn to_json(value: &impl Serialize) -> serde_json::Result<Buffer<'static>> {
+    let mut buffer = BUFFERS.take();
+    serde_json::to_writer(&mut *buffer, value)?;
+    Ok(buffer)
+}
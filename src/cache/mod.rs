@@ -0,0 +1,399 @@
+//! In-memory caching for warm Lambda invocations.
+//!
+//! Lambda containers are reused between invocations, so a process-wide cache
+//! lets repeated requests for the same upstream resource skip the network
+//! entirely until the entry expires.
+//!
+//! Caches can also be read with stale-while-revalidate semantics (see
+//! [`CacheStore::lookup`]): an entry slightly past its TTL is still served
+//! while the caller refreshes it in the background.
+//!
+//! Entries may carry the upstream `ETag`, so expired entries can be
+//! revalidated with a conditional request instead of refetched (see
+//! [`Fetched`]).
+//!
+//! Every cache counts its reads and removals (see [`CacheStats`]) and can be
+//! managed without knowing its value type through [`ManagedCache`], which the
+//! admin tools use to inspect, invalidate and flush caches at runtime.
+
+use crate::metrics::{CacheResult, observe_cache};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// A cached value and the time it was stored.
+#[derive(Debug, Clone)]
+struct CacheEntry<V> {
+    value: V,
+    etag: Option<String>,
+    inserted_at: Instant,
+}
+
+/// Outcome of a conditional upstream request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched<V> {
+    /// Upstream returned a new representation
+    Modified { value: V, etag: Option<String> },
+    /// Upstream confirmed the cached representation is current (HTTP 304)
+    NotModified,
+}
+
+/// Result of a stale-while-revalidate [`CacheStore::lookup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lookup<V> {
+    /// Entry is within its TTL
+    Fresh(V),
+    /// Entry is past its TTL but within the allowed staleness; refresh it
+    Stale(V),
+    /// No usable entry
+    Miss,
+}
+
+/// Point-in-time statistics of a cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStats {
+    pub name: &'static str,
+    /// Stored entries, including expired ones not yet purged
+    pub entries: usize,
+    /// Stored entries past their TTL
+    pub expired: usize,
+    pub max_entries: usize,
+    pub ttl: Duration,
+    /// Reads served a fresh entry
+    pub hits: u64,
+    /// Reads served an entry past its TTL (see [`CacheStore::lookup`])
+    pub stale_hits: u64,
+    pub misses: u64,
+    /// Entries removed to make room for new ones
+    pub evictions: u64,
+    /// Entries removed by [`CacheStore::invalidate`] or [`CacheStore::clear`]
+    pub invalidations: u64,
+}
+
+impl CacheStats {
+    /// Share of reads served from the cache, fresh or stale, or `None`
+    /// before the first read.
+    #[must_use]
+    pub fn hit_rate(&self) -> Option<f64> {
+        let as_f64 = |count: u64| f64::from(u32::try_from(count).unwrap_or(u32::MAX));
+        let served = self.hits + self.stale_hits;
+        let reads = served + self.misses;
+        (reads > 0).then(|| as_f64(served) / as_f64(reads))
+    }
+}
+
+/// Read and removal counters of a cache.
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    stale_hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+/// Thread-safe TTL cache with a bounded number of entries.
+///
+/// When the cache is full, expired entries are purged first and then the
+/// oldest entry is evicted to make room.
+#[derive(Debug)]
+pub struct CacheStore<V> {
+    name: &'static str,
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, CacheEntry<V>>>,
+    refreshing: Mutex<HashSet<String>>,
+    counters: Counters,
+}
+
+impl<V: Clone> CacheStore<V> {
+    /// Creates an empty cache.
+    #[must_use]
+    pub fn new(name: &'static str, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            name,
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+            refreshing: Mutex::new(HashSet::new()),
+            counters: Counters::default(),
+        }
+    }
+
+    /// Name used to identify this cache in logs.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns a clone of the cached value if present and not expired.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<V> {
+        let value = self
+            .entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .filter(|entry| entry.inserted_at.elapsed() < self.ttl)
+            .map(|entry| entry.value.clone());
+        let result = if value.is_some() {
+            CacheResult::Hit
+        } else {
+            CacheResult::Miss
+        };
+        self.observe(result);
+        value
+    }
+
+    /// Returns a clone of the cached value even if it has expired.
+    ///
+    /// Expired entries linger until evicted, so this can serve a fallback
+    /// when the upstream is unavailable.
+    #[must_use]
+    pub fn get_stale(&self, key: &str) -> Option<V> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    /// Looks up `key`, accepting entries up to `max_stale` past their TTL.
+    #[must_use]
+    pub fn lookup(&self, key: &str, max_stale: Duration) -> Lookup<V> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let (lookup, result) = match entries.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl => {
+                (Lookup::Fresh(entry.value.clone()), CacheResult::Hit)
+            }
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl + max_stale => {
+                (Lookup::Stale(entry.value.clone()), CacheResult::Stale)
+            }
+            _ => (Lookup::Miss, CacheResult::Miss),
+        };
+        drop(entries);
+        self.observe(result);
+        lookup
+    }
+
+    /// Marks `key` as being refreshed.
+    ///
+    /// Returns `false` if a refresh is already in progress, so concurrent
+    /// stale reads trigger only one upstream request.
+    pub fn try_begin_refresh(&self, key: &str) -> bool {
+        self.refreshing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key.to_string())
+    }
+
+    /// Clears the refresh marker set by [`Self::try_begin_refresh`].
+    pub fn end_refresh(&self, key: &str) {
+        self.refreshing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key);
+    }
+
+    /// Stores a value, evicting old entries if the cache is full.
+    pub fn insert(&self, key: impl Into<String>, value: V) {
+        self.insert_with_etag(key, value, None);
+    }
+
+    /// `ETag` stored with `key`, even if the entry has expired.
+    #[must_use]
+    pub fn etag(&self, key: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.get(key).and_then(|entry| entry.etag.clone())
+    }
+
+    /// Applies the outcome of a conditional request for `key`.
+    ///
+    /// A modified value is stored; `NotModified` renews the existing entry's
+    /// TTL. Returns the current value, or `None` if the upstream reported
+    /// `NotModified` but the entry has since been evicted.
+    pub fn store_fetched(&self, key: &str, fetched: Fetched<V>) -> Option<V> {
+        match fetched {
+            Fetched::Modified { value, etag } => {
+                self.insert_with_etag(key, value.clone(), etag);
+                Some(value)
+            }
+            Fetched::NotModified => {
+                let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+                entries.get_mut(key).map(|entry| {
+                    entry.inserted_at = Instant::now();
+                    entry.value.clone()
+                })
+            }
+        }
+    }
+
+    /// Stores a value with the upstream `ETag` it was served with.
+    pub fn insert_with_etag(&self, key: impl Into<String>, value: V, etag: Option<String>) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let key = key.into();
+
+        if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            let before = entries.len();
+            entries.retain(|_, entry| entry.inserted_at.elapsed() < self.ttl);
+            if entries.len() >= self.max_entries
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.inserted_at)
+                    .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+            count(&self.counters.evictions, before - entries.len());
+        }
+
+        entries.insert(
+            key,
+            CacheEntry {
+                value,
+                etag,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Clones of every value that has not expired, in no particular order.
+    #[must_use]
+    pub fn values(&self) -> Vec<V> {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .filter(|entry| entry.inserted_at.elapsed() < self.ttl)
+            .map(|entry| entry.value.clone())
+            .collect()
+    }
+
+    /// Number of stored entries, including any that have expired but not yet been purged.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns `true` if the cache holds no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every entry, returning how many were removed.
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let removed = entries.len();
+        entries.clear();
+        drop(entries);
+        count(&self.counters.invalidations, removed);
+        removed
+    }
+
+    /// Removes every entry whose key starts with `prefix`, returning how many
+    /// were removed.
+    ///
+    /// Useful to purge entries cached from a bad upstream answer, such as a
+    /// location geocoded to the wrong place.
+    pub fn invalidate(&self, prefix: &str) -> usize {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let before = entries.len();
+        entries.retain(|key, _| !key.starts_with(prefix));
+        let removed = before - entries.len();
+        drop(entries);
+        count(&self.counters.invalidations, removed);
+        removed
+    }
+
+    /// Returns the current size of the cache and its counters.
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        let (entries, expired) = {
+            let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            let expired = entries
+                .values()
+                .filter(|entry| entry.inserted_at.elapsed() >= self.ttl)
+                .count();
+            (entries.len(), expired)
+        };
+        let counters = &self.counters;
+        CacheStats {
+            name: self.name,
+            entries,
+            expired,
+            max_entries: self.max_entries,
+            ttl: self.ttl,
+            hits: counters.hits.load(Ordering::Relaxed),
+            stale_hits: counters.stale_hits.load(Ordering::Relaxed),
+            misses: counters.misses.load(Ordering::Relaxed),
+            evictions: counters.evictions.load(Ordering::Relaxed),
+            invalidations: counters.invalidations.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Counts a read in this cache and in the process metrics.
+    fn observe(&self, result: CacheResult) {
+        let counter = match result {
+            CacheResult::Hit => &self.counters.hits,
+            CacheResult::Stale => &self.counters.stale_hits,
+            CacheResult::Miss => &self.counters.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        observe_cache(self.name, result);
+    }
+}
+
+fn count(counter: &AtomicU64, removed: usize) {
+    counter.fetch_add(
+        u64::try_from(removed).unwrap_or(u64::MAX),
+        Ordering::Relaxed,
+    );
+}
+
+/// Operations on a cache that do not depend on its value type.
+pub trait ManagedCache: Sync {
+    /// Name used to identify the cache in logs and admin tools.
+    fn name(&self) -> &'static str;
+
+    /// Number of stored entries, including expired ones not yet purged.
+    fn len(&self) -> usize;
+
+    /// Whether the cache holds no entries at all.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every entry, returning how many were removed.
+    fn clear(&self) -> usize;
+
+    /// Removes every entry whose key starts with `prefix`, returning how
+    /// many were removed.
+    fn invalidate(&self, prefix: &str) -> usize;
+
+    /// Current size and counters.
+    fn stats(&self) -> CacheStats;
+}
+
+impl<V: Clone + Send> ManagedCache for CacheStore<V> {
+    fn name(&self) -> &'static str {
+        Self::name(self)
+    }
+
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+
+    fn clear(&self) -> usize {
+        Self::clear(self)
+    }
+
+    fn invalidate(&self, prefix: &str) -> usize {
+        Self::invalidate(self, prefix)
+    }
+
+    fn stats(&self) -> CacheStats {
+        Self::stats(self)
+    }
+}
@@ -0,0 +1,521 @@
+//! Invocation trace capture, for debugging misbehaving agent calls offline.
+//!
+//! Logs show what the server decided for a call, but not what it was given
+//! or what the upstreams answered. A captured trace records the whole
+//! pipeline of one Lambda invocation: the raw event, the tool name extracted
+//! from it, the arguments the tool ran with after defaults, every upstream
+//! request and response, when each routing stage finished, and the outcome.
+//! It is written as one JSON document to the S3 bucket named by
+//! `TRACE_CAPTURE_BUCKET`, under `TRACE_CAPTURE_PREFIX` (default `traces/`),
+//! keyed by UTC date, tool and Lambda request ID (see [`InvocationTrace::key`]).
+//!
+//! Capture is off unless the bucket is set. It then applies to calls that ask
+//! for it with a truthy `X-Trace-Capture` header, which the interceptor
+//! passes on as the `trace_capture` argument, and to every call from a
+//! tenant whose overlay sets `trace_capture` (see [`crate::tenants`]).
+//!
+//! Traces are redacted before they leave the container. Values named like
+//! credentials, such as the bearer token, `Authorization` headers and
+//! `apikey` query parameters, or like the caller's identity, such as
+//! `user_id`, are replaced with [`REDACTED`]; other strings
+//! are scrubbed for secret patterns (see [`crate::secrets`]); upstream
+//! bodies are cut to [`MAX_BODY_BYTES`].
+
+use crate::aws::sdk_config;
+use crate::config::config;
+use crate::models::InvocationOutcome;
+use crate::secrets;
+use aws_sdk_s3::primitives::ByteStream;
+use chrono::{DateTime, Utc};
+use lambda_runtime::Diagnostic;
+use lambda_runtime::tracing::{debug, info, warn};
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response, Url};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Tool argument asking for the call to be captured
+pub const TRACE_CAPTURE_ARG: &str = "trace_capture";
+
+/// Gateway request header asking for the call to be captured
+pub const TRACE_CAPTURE_HEADER: &str = "X-Trace-Capture";
+
+/// Placeholder for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Longest upstream body kept in a trace
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Names of arguments, headers and query parameters holding credentials or
+/// identifying the caller, matched case-insensitively
+const SENSITIVE_NAMES: &[&str] = &[
+    "auth_token",
+    "identity_context",
+    "user_id",
+    "user_name",
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "api_key",
+    "apikey",
+    "key",
+    "token",
+    "access_token",
+    "secret",
+    "password",
+    "signature",
+];
+
+static S3_CLIENT: OnceCell<aws_sdk_s3::Client> = OnceCell::const_new();
+
+tokio::task_local! {
+    static RECORDER: Recorder;
+}
+
+/// Everything recorded about one invocation.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InvocationTrace {
+    /// Lambda request ID of the invocation
+    pub request_id: String,
+    /// Tool name extracted from the event
+    pub tool: String,
+    /// Caller's tenant, once known
+    pub tenant: Option<String>,
+    /// When the invocation started (RFC 3339)
+    pub started_at: String,
+    /// Lambda event as received
+    pub event: Value,
+    /// Arguments the tool ran with, after defaults
+    pub arguments: Option<Value>,
+    /// Upstream requests, in the order they were sent
+    pub upstream: Vec<UpstreamExchange>,
+    /// Routing stages, in the order they finished
+    pub stages: Vec<Stage>,
+    pub outcome: Option<InvocationOutcome>,
+    /// Time the invocation took, in milliseconds
+    pub duration_ms: u64,
+}
+
+impl InvocationTrace {
+    /// Object key of the trace under `prefix`:
+    /// `<prefix><yyyy>/<mm>/<dd>/<tool>/<request ID>.json`.
+    #[must_use]
+    pub fn key(&self, prefix: &str) -> String {
+        let date = DateTime::parse_from_rfc3339(&self.started_at)
+            .map_or_else(|_| Utc::now(), |started| started.with_timezone(&Utc));
+        let separator = if prefix.is_empty() || prefix.ends_with('/') {
+            ""
+        } else {
+            "/"
+        };
+        format!(
+            "{prefix}{separator}{}/{}/{}.json",
+            date.format("%Y/%m/%d"),
+            self.tool,
+            self.request_id
+        )
+    }
+}
+
+/// A routing stage and when it finished.
+#[derive(Debug, Clone, Serialize)]
+pub struct Stage {
+    pub name: &'static str,
+    /// Milliseconds since the invocation started
+    pub elapsed_ms: u64,
+}
+
+/// An upstream request and what it returned.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpstreamExchange {
+    pub method: String,
+    pub url: String,
+    pub request_headers: BTreeMap<String, String>,
+    pub request_body: Option<String>,
+    /// Status code, unless the request failed
+    pub status: Option<u16>,
+    pub response_headers: BTreeMap<String, String>,
+    pub response_body: Option<String>,
+    /// Why the request failed, if it did
+    pub error: Option<String>,
+    /// Milliseconds after the invocation started that it was sent
+    pub sent_ms: u64,
+    /// Milliseconds until the response body was read
+    pub latency_ms: u64,
+}
+
+/// An upstream request sent while a trace is captured, waiting for its
+/// response (see [`exchange`]).
+#[derive(Debug)]
+pub struct PendingExchange {
+    exchange: UpstreamExchange,
+    sent: Instant,
+}
+
+/// Collects the trace of the current invocation.
+///
+/// Stages are recorded from the start; arguments, upstream exchanges and
+/// the outcome only once capture is switched on with [`activate`].
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    started: Instant,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    active: bool,
+    trace: InvocationTrace,
+}
+
+impl Recorder {
+    /// Starts the trace of invocation `request_id` of `tool`, triggered by
+    /// `event`.
+    #[must_use]
+    pub fn new(request_id: &str, tool: &str, event: &Value) -> Self {
+        let mut event = event.clone();
+        redact_event(&mut event);
+        Self {
+            started: Instant::now(),
+            state: Arc::new(Mutex::new(State {
+                active: false,
+                trace: InvocationTrace {
+                    request_id: request_id.to_string(),
+                    tool: tool.to_string(),
+                    started_at: Utc::now().to_rfc3339(),
+                    event,
+                    ..InvocationTrace::default()
+                },
+            })),
+        }
+    }
+
+    /// Whether capture was switched on for the invocation.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.lock().active
+    }
+
+    /// The trace so far.
+    #[must_use]
+    pub fn trace(&self) -> InvocationTrace {
+        self.lock().trace.clone()
+    }
+
+    /// Records the outcome and, if capture was switched on, writes the trace
+    /// to the bucket.
+    ///
+    /// A trace that cannot be written is logged rather than failing the call.
+    pub async fn finish(&self, result: &Result<Value, Diagnostic>) {
+        let trace = {
+            let mut state = self.lock();
+            if !state.active {
+                return;
+            }
+            let mut outcome = InvocationOutcome::from(result);
+            if let Some(response) = &mut outcome.response {
+                redact(response);
+            }
+            state.trace.outcome = Some(outcome);
+            state.trace.duration_ms = millis(self.started.elapsed());
+            state.trace.clone()
+        };
+        let config = config();
+        let Some(bucket) = config.capture.bucket.as_deref() else {
+            return;
+        };
+        let key = trace.key(&config.capture.prefix);
+        match put_object(bucket, &key, &trace).await {
+            Ok(()) => info!(bucket, key = %key, "Captured invocation trace"),
+            Err(e) => warn!(bucket, key = %key, error = %e, "Failed to write invocation trace"),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        millis(self.started.elapsed())
+    }
+}
+
+/// Whether traces can be captured, i.e. `TRACE_CAPTURE_BUCKET` is set.
+#[must_use]
+pub fn is_enabled() -> bool {
+    config().capture.bucket.is_some()
+}
+
+/// Runs `future` with `recorder` collecting its trace, or without one if
+/// `recorder` is `None`.
+pub async fn scope<F: Future>(recorder: Option<Recorder>, future: F) -> F::Output {
+    match recorder {
+        Some(recorder) => RECORDER.scope(recorder, future).await,
+        None => future.await,
+    }
+}
+
+/// Takes the `trace_capture` argument out of `args`, returning whether it
+/// asks for the call to be captured: `true`, or a string such as `"1"`.
+pub fn take_flag(args: &mut Value) -> bool {
+    match args
+        .as_object_mut()
+        .and_then(|args| args.remove(TRACE_CAPTURE_ARG))
+    {
+        Some(Value::Bool(flag)) => flag,
+        Some(Value::String(flag)) => is_truthy(&flag),
+        _ => false,
+    }
+}
+
+/// Whether a header or argument value asks for capture: `true`, `1`, `yes`
+/// or `on`, case-insensitively.
+#[must_use]
+pub fn is_truthy(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "true" | "1" | "yes" | "on"
+    )
+}
+
+/// Switches capture on for the current invocation, noting the caller's
+/// `tenant` if it is known.
+///
+/// Does nothing outside a [`scope`].
+pub fn activate(tenant: Option<&str>) {
+    with_recorder(|recorder| {
+        let mut state = recorder.lock();
+        if !state.active {
+            debug!(tool = %state.trace.tool, "Capturing invocation trace");
+        }
+        state.active = true;
+        if let Some(tenant) = tenant {
+            state.trace.tenant = Some(tenant.to_string());
+        }
+    });
+}
+
+/// Whether the current invocation is being captured.
+#[must_use]
+pub fn is_active() -> bool {
+    RECORDER.try_with(Recorder::is_active).unwrap_or_default()
+}
+
+/// Records that routing stage `name` finished.
+pub fn mark(name: &'static str) {
+    with_recorder(|recorder| {
+        let elapsed_ms = recorder.elapsed_ms();
+        recorder
+            .lock()
+            .trace
+            .stages
+            .push(Stage { name, elapsed_ms });
+    });
+}
+
+/// Records the arguments the tool runs with, if the invocation is being
+/// captured.
+pub fn record_arguments(args: &Value) {
+    with_recorder(|recorder| {
+        let mut state = recorder.lock();
+        if state.active {
+            let mut args = args.clone();
+            redact(&mut args);
+            state.trace.arguments = Some(args);
+        }
+    });
+}
+
+/// Starts recording the upstream `request`, if the invocation is being
+/// captured.
+#[must_use]
+pub fn request(request: &Request) -> Option<PendingExchange> {
+    RECORDER
+        .try_with(|recorder| {
+            recorder.is_active().then(|| PendingExchange {
+                exchange: UpstreamExchange {
+                    method: request.method().to_string(),
+                    url: redact_url(request.url()),
+                    request_headers: redact_headers(request.headers()),
+                    request_body: request
+                        .body()
+                        .and_then(reqwest::Body::as_bytes)
+                        .map(body_text),
+                    sent_ms: recorder.elapsed_ms(),
+                    ..UpstreamExchange::default()
+                },
+                sent: Instant::now(),
+            })
+        })
+        .ok()
+        .flatten()
+}
+
+/// Records the outcome of `pending` and passes it on.
+///
+/// The response body is read to record it, so the response returned is a
+/// copy holding the same status, headers and body.
+///
+/// # Errors
+///
+/// Returns the error of `result`, or one reading the response body.
+pub async fn exchange(
+    pending: PendingExchange,
+    result: anyhow::Result<Response>,
+) -> anyhow::Result<Response> {
+    let PendingExchange { mut exchange, sent } = pending;
+    let outcome = match result {
+        Ok(response) => {
+            let status = response.status();
+            let version = response.version();
+            let headers = response.headers().clone();
+            exchange.status = Some(status.as_u16());
+            exchange.response_headers = redact_headers(&headers);
+            response.bytes().await.map(|body| {
+                exchange.response_body = Some(body_text(&body));
+                let mut copy = http::Response::new(body);
+                *copy.status_mut() = status;
+                *copy.version_mut() = version;
+                *copy.headers_mut() = headers;
+                Response::from(copy)
+            })
+        }
+        Err(e) => {
+            exchange.error = Some(e.to_string());
+            exchange.latency_ms = millis(sent.elapsed());
+            push(exchange);
+            return Err(e);
+        }
+    };
+    if let Err(e) = &outcome {
+        exchange.error = Some(e.to_string());
+    }
+    exchange.latency_ms = millis(sent.elapsed());
+    push(exchange);
+    outcome.map_err(anyhow::Error::from)
+}
+
+/// Redacts credentials and identifiers in `value`: values named in [`SENSITIVE_NAMES`] are
+/// replaced with [`REDACTED`], and other strings scrubbed for secrets.
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if is_sensitive(name) {
+                    *field = json!(REDACTED);
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        Value::String(text) => {
+            if let Some((scrubbed, _)) = secrets::redact(text) {
+                *text = scrubbed;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Redacts `event`, including a JSON request body carried as a string by
+/// API Gateway events.
+fn redact_event(event: &mut Value) {
+    if let Some(body) = event.get_mut("body")
+        && let Some(mut parsed) = body
+            .as_str()
+            .and_then(|text| serde_json::from_str::<Value>(text).ok())
+    {
+        redact(&mut parsed);
+        *body = json!(parsed.to_string());
+    }
+    redact(event);
+}
+
+fn is_sensitive(name: &str) -> bool {
+    SENSITIVE_NAMES
+        .iter()
+        .any(|sensitive| sensitive.eq_ignore_ascii_case(name))
+}
+
+fn redact_url(url: &Url) -> String {
+    let mut url = url.clone();
+    if url.query().is_some() {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if is_sensitive(&name) {
+                    REDACTED.to_string()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+fn redact_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+/// A body as text, cut to [`MAX_BODY_BYTES`] and scrubbed for secrets.
+fn body_text(body: &[u8]) -> String {
+    let kept = body.get(..MAX_BODY_BYTES).unwrap_or(body);
+    let mut text = String::from_utf8_lossy(kept).into_owned();
+    if kept.len() < body.len() {
+        let _ = write!(text, " [{} more bytes]", body.len() - kept.len());
+    }
+    secrets::redact(&text).map_or(text, |(scrubbed, _)| scrubbed)
+}
+
+fn push(exchange: UpstreamExchange) {
+    with_recorder(|recorder| recorder.lock().trace.upstream.push(exchange));
+}
+
+fn with_recorder(f: impl FnOnce(&Recorder)) {
+    let _ = RECORDER.try_with(f);
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+async fn put_object(bucket: &str, key: &str, trace: &InvocationTrace) -> anyhow::Result<()> {
+    let body = serde_json::to_vec(trace)?;
+    s3_client()
+        .await
+        .put_object()
+        .bucket(bucket)
+        .key(key)
+        .content_type("application/json")
+        .body(ByteStream::from(body))
+        .send()
+        .await?;
+    Ok(())
+}
+
+async fn s3_client() -> &'static aws_sdk_s3::Client {
+    S3_CLIENT
+        .get_or_init(|| async { aws_sdk_s3::Client::new(sdk_config().await) })
+        .await
+}
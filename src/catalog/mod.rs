@@ -0,0 +1,509 @@
+//! Error catalog with stable codes and remediation hints.
+//!
+//! Every error returned to a caller maps to a catalog entry with a stable
+//! code (e.g. `E2001`), a message that is safe to show to end users, and a
+//! hint on how to recover. Client teams can key error handling off the code
+//! instead of parsing messages. Full error detail is only logged, except for
+//! entries whose detail is meant for the caller (such as validation
+//! failures, which tell the agent how to fix its arguments). Exposed detail
+//! is passed through [`sanitize`] so URLs, tokens and internal hostnames from
+//! upstream errors never reach the caller.
+
+mod sanitize;
+
+pub use sanitize::sanitize;
+
+use crate::models::error::AppError;
+use lambda_runtime::Diagnostic;
+
+/// A catalogued error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogEntry {
+    /// Stable code, never reused or renumbered
+    pub code: &'static str,
+    /// Stable symbolic name
+    pub name: &'static str,
+    /// User-safe description
+    pub message: &'static str,
+    /// How the caller can recover
+    pub remediation: &'static str,
+    /// Whether the error detail is safe to return to the caller
+    pub expose_detail: bool,
+}
+
+impl CatalogEntry {
+    /// Renders the outward-facing message, e.g.
+    /// `[E1001] Invalid tool arguments: missing field `location`. Hint: ...`.
+    ///
+    /// Exposed detail is sanitized first.
+    #[must_use]
+    pub fn render(&self, detail: &str) -> String {
+        let detail = sanitize(detail.trim());
+        if self.expose_detail && !detail.is_empty() {
+            format!(
+                "[{}] {}: {detail}. Hint: {}",
+                self.code, self.message, self.remediation
+            )
+        } else {
+            format!(
+                "[{}] {}. Hint: {}",
+                self.code, self.message, self.remediation
+            )
+        }
+    }
+
+    /// Builds a `Diagnostic` of `error_type` for this entry.
+    #[must_use]
+    pub fn diagnostic(&self, error_type: &str, detail: &str) -> Diagnostic {
+        Diagnostic {
+            error_type: error_type.to_string(),
+            error_message: self.render(detail),
+        }
+    }
+}
+
+const fn entry(
+    code: &'static str,
+    name: &'static str,
+    expose_detail: bool,
+    message: &'static str,
+    remediation: &'static str,
+) -> CatalogEntry {
+    CatalogEntry {
+        code,
+        name,
+        message,
+        remediation,
+        expose_detail,
+    }
+}
+
+// E1xxx: request errors
+pub const INVALID_INPUT: CatalogEntry = entry(
+    "E1001",
+    "INVALID_INPUT",
+    true,
+    "Invalid tool arguments",
+    "Check the arguments against the tool's input schema",
+);
+pub const UNKNOWN_TOOL: CatalogEntry = entry(
+    "E1002",
+    "UNKNOWN_TOOL",
+    true,
+    "Unknown tool",
+    "List the available tools and use one of their names",
+);
+pub const VALIDATION_FAILED: CatalogEntry = entry(
+    "E1003",
+    "VALIDATION_FAILED",
+    true,
+    "The request was rejected",
+    "Adjust the arguments as described and retry",
+);
+pub const UNAUTHORIZED: CatalogEntry = entry(
+    "E1004",
+    "UNAUTHORIZED",
+    true,
+    "Not authorized to use this tool",
+    "Sign in with an account that has the required scope",
+);
+pub const EGRESS_DENIED: CatalogEntry = entry(
+    "E1005",
+    "EGRESS_DENIED",
+    true,
+    "The destination is not permitted",
+    "Use a destination on the server's egress allowlist",
+);
+pub const TOOL_DISABLED: CatalogEntry = entry(
+    "E1006",
+    "TOOL_DISABLED",
+    true,
+    "This tool is temporarily disabled",
+    "Try again later or use another tool",
+);
+pub const LOCATION_NOT_FOUND: CatalogEntry = entry(
+    "E1007",
+    "LOCATION_NOT_FOUND",
+    true,
+    "No place matches the location",
+    "Check the spelling, or use one of the suggested names",
+);
+pub const BODY_REJECTED: CatalogEntry = entry(
+    "E1008",
+    "BODY_REJECTED",
+    true,
+    "The request body is too large or too deeply nested",
+    "Send fewer or smaller arguments with less nesting",
+);
+pub const RESIDENCY_DENIED: CatalogEntry = entry(
+    "E1009",
+    "RESIDENCY_DENIED",
+    true,
+    "The caller's data cannot be stored in their region",
+    "Ask an administrator to configure a region for your data residency",
+);
+pub const CONFIRMATION_INVALID: CatalogEntry = entry(
+    "E1010",
+    "CONFIRMATION_INVALID",
+    true,
+    "The call was not confirmed",
+    "Call the tool again without confirmation_token, confirm the returned summary with the user, then repeat the call with the new token",
+);
+pub const GEOFENCE_DENIED: CatalogEntry = entry(
+    "E1011",
+    "GEOFENCE_DENIED",
+    true,
+    "The location is outside the regions your tenant may serve",
+    "Ask about a location in one of your tenant's licensed markets, or ask an administrator to extend its geofence",
+);
+
+// E2xxx: upstream errors
+pub const GEOCODING_FAILED: CatalogEntry = entry(
+    "E2001",
+    "GEOCODING_FAILED",
+    false,
+    "Could not look up the requested location",
+    "Check the spelling of the location or try a nearby city",
+);
+pub const WEATHER_UNAVAILABLE: CatalogEntry = entry(
+    "E2002",
+    "WEATHER_UNAVAILABLE",
+    false,
+    "Weather data is unavailable",
+    "Retry in a few minutes",
+);
+pub const FEED_UNAVAILABLE: CatalogEntry = entry(
+    "E2003",
+    "FEED_UNAVAILABLE",
+    false,
+    "The feed could not be read",
+    "Check that the URL points to a public RSS or Atom feed",
+);
+pub const SUMMARIZE_FAILED: CatalogEntry = entry(
+    "E2004",
+    "SUMMARIZE_FAILED",
+    false,
+    "The text could not be summarized",
+    "Retry shortly, or shorten the text",
+);
+pub const NOTIFICATION_FAILED: CatalogEntry = entry(
+    "E2005",
+    "NOTIFICATION_FAILED",
+    false,
+    "The notification could not be sent",
+    "Retry shortly; contact an administrator if it keeps failing",
+);
+pub const LOGS_QUERY_FAILED: CatalogEntry = entry(
+    "E2006",
+    "LOGS_QUERY_FAILED",
+    false,
+    "The log query did not complete",
+    "Narrow the time range or simplify the query and retry",
+);
+pub const COSTS_UNAVAILABLE: CatalogEntry = entry(
+    "E2007",
+    "COSTS_UNAVAILABLE",
+    false,
+    "Cost data is unavailable",
+    "Retry in a few minutes",
+);
+pub const WORKFLOW_FAILED: CatalogEntry = entry(
+    "E2008",
+    "WORKFLOW_FAILED",
+    false,
+    "The workflow request failed",
+    "Check the workflow or execution and retry",
+);
+pub const USER_LOOKUP_FAILED: CatalogEntry = entry(
+    "E2009",
+    "USER_LOOKUP_FAILED",
+    false,
+    "Could not determine the user",
+    "Retry the request with the user's name",
+);
+pub const RATE_LIMITED: CatalogEntry = entry(
+    "E2010",
+    "RATE_LIMITED",
+    true,
+    "An upstream service is rate limiting requests",
+    "Wait for the indicated time before retrying",
+);
+pub const HOLIDAYS_UNAVAILABLE: CatalogEntry = entry(
+    "E2011",
+    "HOLIDAYS_UNAVAILABLE",
+    false,
+    "Public holiday data is unavailable",
+    "Check the country code and retry",
+);
+pub const PREFERENCES_UNAVAILABLE: CatalogEntry = entry(
+    "E2012",
+    "PREFERENCES_UNAVAILABLE",
+    false,
+    "User preferences could not be read",
+    "Pass the values explicitly, or retry shortly",
+);
+pub const AUDIT_UNAVAILABLE: CatalogEntry = entry(
+    "E2013",
+    "AUDIT_UNAVAILABLE",
+    false,
+    "The invocation audit log could not be read",
+    "Retry shortly",
+);
+pub const TOOL_VERSIONS_UNAVAILABLE: CatalogEntry = entry(
+    "E2014",
+    "TOOL_VERSIONS_UNAVAILABLE",
+    false,
+    "The history of tool response shapes could not be read",
+    "Retry shortly",
+);
+pub const TENANT_UNAVAILABLE: CatalogEntry = entry(
+    "E2015",
+    "TENANT_UNAVAILABLE",
+    false,
+    "Settings for the caller's tenant could not be loaded",
+    "Retry shortly",
+);
+pub const USAGE_UNAVAILABLE: CatalogEntry = entry(
+    "E2016",
+    "USAGE_UNAVAILABLE",
+    false,
+    "Usage records could not be read",
+    "Retry shortly",
+);
+pub const EFFECT_JOURNAL_UNAVAILABLE: CatalogEntry = entry(
+    "E2017",
+    "EFFECT_JOURNAL_UNAVAILABLE",
+    false,
+    "The outcome of an earlier attempt at this call could not be read",
+    "Retry with a new idempotency key only if the earlier attempt is known to have failed",
+);
+pub const SANDBOXED_TOOL_FAILED: CatalogEntry = entry(
+    "E2018",
+    "SANDBOXED_TOOL_FAILED",
+    true,
+    "The sandboxed tool failed",
+    "Check the tool's arguments; if it ran out of fuel or memory, ask for less work per call",
+);
+pub const DECLARATIVE_TOOL_FAILED: CatalogEntry = entry(
+    "E2019",
+    "DECLARATIVE_TOOL_FAILED",
+    false,
+    "The configured tool's upstream request failed",
+    "Retry shortly",
+);
+pub const FAILED_CALLS_UNAVAILABLE: CatalogEntry = entry(
+    "E2020",
+    "FAILED_CALLS_UNAVAILABLE",
+    false,
+    "The record of failed asynchronous calls could not be read",
+    "Retry shortly",
+);
+
+// E3xxx: capacity errors
+pub const TOO_BUSY: CatalogEntry = entry(
+    "E3001",
+    "TOO_BUSY",
+    true,
+    "The tool is busy",
+    "Retry shortly",
+);
+pub const OVERLOADED: CatalogEntry = entry(
+    "E3002",
+    "OVERLOADED",
+    true,
+    "The server is overloaded",
+    "Retry shortly with backoff",
+);
+pub const DEADLINE_EXCEEDED: CatalogEntry = entry(
+    "E3003",
+    "DEADLINE_EXCEEDED",
+    false,
+    "The request ran out of time",
+    "Retry, or request less data",
+);
+pub const QUOTA_EXCEEDED: CatalogEntry = entry(
+    "E3004",
+    "QUOTA_EXCEEDED",
+    true,
+    "Your team's usage quota is used up",
+    "Retry after the quota resets",
+);
+
+// E9xxx: internal errors
+pub const SERIALIZATION_FAILED: CatalogEntry = entry(
+    "E9001",
+    "SERIALIZATION_FAILED",
+    false,
+    "The tool response could not be encoded",
+    "Retry; report the error code if it persists",
+);
+pub const ENCRYPTION_FAILED: CatalogEntry = entry(
+    "E9002",
+    "ENCRYPTION_FAILED",
+    false,
+    "A protected argument could not be processed",
+    "Retry; report the error code if it persists",
+);
+pub const CONTENT_REJECTED: CatalogEntry = entry(
+    "E9003",
+    "CONTENT_REJECTED",
+    false,
+    "The tool produced content that cannot be returned",
+    "Request a smaller result; report the error code if it persists",
+);
+pub const RESPONSE_TRANSFORM_FAILED: CatalogEntry = entry(
+    "E9004",
+    "RESPONSE_TRANSFORM_FAILED",
+    false,
+    "The tool response could not be reshaped",
+    "Retry; report the error code if it persists",
+);
+pub const INTERNAL: CatalogEntry = entry(
+    "E9999",
+    "INTERNAL",
+    false,
+    "An unexpected error occurred",
+    "Retry; report the error code if it persists",
+);
+
+/// Every catalogued error, in code order.
+pub const ALL: &[CatalogEntry] = &[
+    INVALID_INPUT,
+    UNKNOWN_TOOL,
+    VALIDATION_FAILED,
+    UNAUTHORIZED,
+    EGRESS_DENIED,
+    TOOL_DISABLED,
+    LOCATION_NOT_FOUND,
+    BODY_REJECTED,
+    RESIDENCY_DENIED,
+    CONFIRMATION_INVALID,
+    GEOFENCE_DENIED,
+    GEOCODING_FAILED,
+    WEATHER_UNAVAILABLE,
+    FEED_UNAVAILABLE,
+    SUMMARIZE_FAILED,
+    NOTIFICATION_FAILED,
+    LOGS_QUERY_FAILED,
+    COSTS_UNAVAILABLE,
+    WORKFLOW_FAILED,
+    USER_LOOKUP_FAILED,
+    RATE_LIMITED,
+    HOLIDAYS_UNAVAILABLE,
+    PREFERENCES_UNAVAILABLE,
+    AUDIT_UNAVAILABLE,
+    TOOL_VERSIONS_UNAVAILABLE,
+    TENANT_UNAVAILABLE,
+    USAGE_UNAVAILABLE,
+    EFFECT_JOURNAL_UNAVAILABLE,
+    SANDBOXED_TOOL_FAILED,
+    DECLARATIVE_TOOL_FAILED,
+    FAILED_CALLS_UNAVAILABLE,
+    TOO_BUSY,
+    OVERLOADED,
+    DEADLINE_EXCEEDED,
+    QUOTA_EXCEEDED,
+    SERIALIZATION_FAILED,
+    ENCRYPTION_FAILED,
+    CONTENT_REJECTED,
+    RESPONSE_TRANSFORM_FAILED,
+    INTERNAL,
+];
+
+impl AppError {
+    /// Catalog entry for this error.
+    #[must_use]
+    pub const fn catalog_entry(&self) -> &'static CatalogEntry {
+        match self {
+            Self::GeocodingError(_) => &GEOCODING_FAILED,
+            Self::LocationNotFound { .. } => &LOCATION_NOT_FOUND,
+            Self::WeatherApiError(_) => &WEATHER_UNAVAILABLE,
+            Self::UserExtractionError(_) => &USER_LOOKUP_FAILED,
+            Self::FeedError(_) => &FEED_UNAVAILABLE,
+            Self::BedrockError(_) => &SUMMARIZE_FAILED,
+            Self::ValidationError(_) => &VALIDATION_FAILED,
+            Self::NotificationError(_) => &NOTIFICATION_FAILED,
+            Self::LogsQueryError(_) => &LOGS_QUERY_FAILED,
+            Self::CostExplorerError(_) => &COSTS_UNAVAILABLE,
+            Self::WorkflowError(_) => &WORKFLOW_FAILED,
+            Self::HolidayError(_) => &HOLIDAYS_UNAVAILABLE,
+            Self::PreferencesError(_) => &PREFERENCES_UNAVAILABLE,
+            Self::AuditError(_) => &AUDIT_UNAVAILABLE,
+            Self::ShapesError(_) => &TOOL_VERSIONS_UNAVAILABLE,
+            Self::TenantError(_) => &TENANT_UNAVAILABLE,
+            Self::UsageError(_) => &USAGE_UNAVAILABLE,
+            Self::EffectJournalError(_) => &EFFECT_JOURNAL_UNAVAILABLE,
+            Self::FailedCallsError(_) => &FAILED_CALLS_UNAVAILABLE,
+            Self::SandboxError(_) => &SANDBOXED_TOOL_FAILED,
+            Self::DeclarativeToolError(_) => &DECLARATIVE_TOOL_FAILED,
+            Self::Unauthorized(_) => &UNAUTHORIZED,
+            Self::RateLimited { .. } => &RATE_LIMITED,
+            Self::TooBusy(_) => &TOO_BUSY,
+            Self::Overloaded(_) => &OVERLOADED,
+            Self::QuotaExceeded { .. } => &QUOTA_EXCEEDED,
+            Self::DeadlineExceeded(_) => &DEADLINE_EXCEEDED,
+            Self::EncryptionError(_) => &ENCRYPTION_FAILED,
+            Self::EgressDenied(_) => &EGRESS_DENIED,
+            Self::ResidencyDenied(_) => &RESIDENCY_DENIED,
+            Self::ConfirmationInvalid(_) => &CONFIRMATION_INVALID,
+            Self::GeofenceDenied(_) => &GEOFENCE_DENIED,
+            Self::ContentError(_) => &CONTENT_REJECTED,
+            Self::TransformError(_) => &RESPONSE_TRANSFORM_FAILED,
+            Self::GenericError(_) => &INTERNAL,
+        }
+    }
+
+    /// Error detail without the category prefix used by `Display`.
+    #[must_use]
+    pub fn detail(&self) -> String {
+        match self {
+            Self::GeocodingError(msg)
+            | Self::WeatherApiError(msg)
+            | Self::UserExtractionError(msg)
+            | Self::FeedError(msg)
+            | Self::BedrockError(msg)
+            | Self::ValidationError(msg)
+            | Self::NotificationError(msg)
+            | Self::LogsQueryError(msg)
+            | Self::CostExplorerError(msg)
+            | Self::WorkflowError(msg)
+            | Self::HolidayError(msg)
+            | Self::PreferencesError(msg)
+            | Self::AuditError(msg)
+            | Self::ShapesError(msg)
+            | Self::TenantError(msg)
+            | Self::UsageError(msg)
+            | Self::EffectJournalError(msg)
+            | Self::FailedCallsError(msg)
+            | Self::SandboxError(msg)
+            | Self::DeclarativeToolError(msg)
+            | Self::Unauthorized(msg)
+            | Self::TooBusy(msg)
+            | Self::Overloaded(msg)
+            | Self::DeadlineExceeded(msg)
+            | Self::EncryptionError(msg)
+            | Self::EgressDenied(msg)
+            | Self::ResidencyDenied(msg)
+            | Self::ConfirmationInvalid(msg)
+            | Self::GeofenceDenied(msg)
+            | Self::ContentError(msg)
+            | Self::TransformError(msg)
+            | Self::GenericError(msg) => msg.clone(),
+            Self::LocationNotFound {
+                location,
+                suggestions,
+            } if suggestions.is_empty() => location.clone(),
+            Self::LocationNotFound {
+                location,
+                suggestions,
+            } => format!("{location}; did you mean: {}", suggestions.join(", ")),
+            Self::RateLimited { .. } | Self::QuotaExceeded { .. } => self.to_string(),
+        }
+    }
+
+    /// Builds the outward-facing `Diagnostic` of `error_type` for this error.
+    #[must_use]
+    pub fn to_diagnostic(&self, error_type: &str) -> Diagnostic {
+        self.catalog_entry().diagnostic(error_type, &self.detail())
+    }
+}
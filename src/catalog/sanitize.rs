@@ -0,0 +1,141 @@
+//! Scrubs sensitive fragments from error text before it leaves the server.
+//!
+//! Upstream errors often quote the failing request: full URLs with query
+//! parameters, bearer tokens, or the address of an internal service. The
+//! catalog runs every outward-facing detail through [`sanitize`]; the
+//! original text is still logged in full.
+
+/// Replacement for URLs.
+const URL: &str = "<url>";
+/// Replacement for credentials and token-like strings.
+const REDACTED: &str = "<redacted>";
+/// Replacement for internal hostnames and IP addresses.
+const HOST: &str = "<host>";
+
+/// Query or field names whose values are always redacted.
+const SECRET_KEYS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "signature",
+    "key",
+    "credential",
+    "authorization",
+];
+
+/// Domain suffixes that only resolve inside a private network.
+const INTERNAL_SUFFIXES: &[&str] = &[".internal", ".local", ".localdomain", ".lan", ".corp"];
+
+/// Shortest run of key-like characters treated as a secret.
+const MIN_SECRET_LEN: usize = 32;
+
+/// Replaces URLs, tokens and internal hostnames in `text` with placeholders.
+#[must_use]
+pub fn sanitize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars() {
+        if is_separator(c) {
+            push_word(&mut out, &word);
+            word.clear();
+            out.push(c);
+        } else {
+            word.push(c);
+        }
+    }
+    push_word(&mut out, &word);
+    out
+}
+
+const fn is_separator(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '"' | '\'' | '`' | '(' | ')' | '<' | '>' | '[' | ']' | '{' | '}' | ',' | ';'
+        )
+}
+
+/// Appends `word` to `out`, scrubbed, keeping sentence punctuation.
+fn push_word(out: &mut String, word: &str) {
+    let core = word.trim_end_matches(['.', ':', '!', '?']);
+    let trailing = word.get(core.len()..).unwrap_or_default();
+    out.push_str(&scrub(core));
+    out.push_str(trailing);
+}
+
+fn scrub(word: &str) -> String {
+    if let Some(pos) = word.find("://") {
+        let before = word.get(..pos).unwrap_or_default();
+        let scheme_start = before
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')))
+            .map_or(0, |i| i + 1);
+        let prefix = before.get(..scheme_start).unwrap_or_default();
+        return format!("{prefix}{URL}");
+    }
+    if let Some((key, _)) = word.split_once('=') {
+        let key_lower = key.to_ascii_lowercase();
+        if SECRET_KEYS.iter().any(|secret| key_lower.ends_with(secret)) {
+            return format!("{key}={REDACTED}");
+        }
+    }
+    if is_internal_host(word) {
+        return HOST.to_string();
+    }
+    if is_jwt(word) || is_access_key_id(word) || is_opaque_secret(word) {
+        return REDACTED.to_string();
+    }
+    word.to_string()
+}
+
+/// IPv4 addresses, `localhost` and private domain names, with optional port.
+fn is_internal_host(word: &str) -> bool {
+    let host = match word.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => host,
+        _ => word,
+    };
+    let host = host.to_ascii_lowercase();
+    if host == "localhost" {
+        return true;
+    }
+    let octets: Vec<&str> = host.split('.').collect();
+    if octets.len() == 4 && octets.iter().all(|o| o.parse::<u8>().is_ok()) {
+        return true;
+    }
+    host.contains('.')
+        && INTERNAL_SUFFIXES
+            .iter()
+            .any(|suffix| host.ends_with(suffix))
+}
+
+/// JSON Web Tokens: three base64url segments, the header starting `eyJ`.
+fn is_jwt(word: &str) -> bool {
+    let segments: Vec<&str> = word.split('.').collect();
+    segments.len() == 3
+        && word.starts_with("eyJ")
+        && segments.iter().all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_'))
+        })
+}
+
+/// AWS access key IDs (`AKIA...`, `ASIA...`).
+fn is_access_key_id(word: &str) -> bool {
+    word.len() == 20
+        && (word.starts_with("AKIA") || word.starts_with("ASIA"))
+        && word
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+/// Long mixed-case alphanumeric runs, such as API keys and session tokens.
+fn is_opaque_secret(word: &str) -> bool {
+    word.len() >= MIN_SECRET_LEN
+        && word
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'+' | b'/' | b'='))
+        && word.bytes().any(|b| b.is_ascii_digit())
+        && word.bytes().any(|b| b.is_ascii_uppercase())
+        && word.bytes().any(|b| b.is_ascii_lowercase())
+}
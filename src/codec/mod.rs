@@ -0,0 +1,183 @@
+//! Binary serialization of tool payloads for Lambda-to-Lambda federation.
+//!
+//! Other Lambdas can invoke tools directly with a proxy-shaped event instead
+//! of going through the gateway. For large structured responses JSON is
+//! costly to produce and parse, so with `BINARY_PAYLOADS=true` such callers
+//! can send and receive CBOR or `MessagePack` instead:
+//!
+//! - a base64 `body` (`"isBase64Encoded": true`) whose `Content-Type` is
+//!   `application/cbor` or `application/msgpack` is decoded into the request
+//! - an `Accept` header naming one of those types gets the response back
+//!   encoded in it, as a base64 proxy response with a matching
+//!   `Content-Type`
+//!
+//! JSON stays the default in both directions, and gateway calls are never
+//! affected. A binary response is not compressed further.
+
+use crate::models::error::AppError;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use lambda_runtime::tracing::{debug, warn};
+use serde_json::{Value, json};
+
+/// A payload serialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl Format {
+    /// Media type sent in `Content-Type`.
+    #[must_use]
+    pub const fn media_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// The format of a `Content-Type` or `Accept` media type, ignoring
+    /// parameters.
+    #[must_use]
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let essence = media_type.split(';').next().unwrap_or_default().trim();
+        match essence.to_ascii_lowercase().as_str() {
+            "application/json" => Some(Self::Json),
+            "application/cbor" => Some(Self::Cbor),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Self::MessagePack)
+            }
+            _ => None,
+        }
+    }
+
+    /// Serializes `value` in this format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` cannot be serialized.
+    pub fn encode(self, value: &Value) -> Result<Vec<u8>, AppError> {
+        match self {
+            Self::Json => serde_json::to_vec(value)
+                .map_err(|e| AppError::GenericError(format!("JSON encoding failed: {e}"))),
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map_err(|e| AppError::GenericError(format!("CBOR encoding failed: {e}")))?;
+                Ok(bytes)
+            }
+            Self::MessagePack => rmp_serde::to_vec(value)
+                .map_err(|e| AppError::GenericError(format!("MessagePack encoding failed: {e}"))),
+        }
+    }
+
+    /// Deserializes `bytes` written in this format.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if `bytes` are not a valid payload in this
+    /// format.
+    pub fn decode(self, bytes: &[u8]) -> Result<Value, AppError> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes)
+                .map_err(|e| AppError::ValidationError(format!("Invalid JSON body: {e}"))),
+            Self::Cbor => ciborium::from_reader(bytes)
+                .map_err(|e| AppError::ValidationError(format!("Invalid CBOR body: {e}"))),
+            Self::MessagePack => rmp_serde::from_slice(bytes)
+                .map_err(|e| AppError::ValidationError(format!("Invalid MessagePack body: {e}"))),
+        }
+    }
+}
+
+/// Decodes the body of `event` if it is a base64 CBOR or `MessagePack`
+/// payload; other events give `None` and are parsed as before.
+///
+/// # Errors
+///
+/// Returns a validation error if the body is not valid base64 or not a valid
+/// payload in its declared format.
+pub fn decode_request(event: &Value) -> Result<Option<Value>, AppError> {
+    let Some(format) = header(event, "content-type").and_then(Format::from_media_type) else {
+        return Ok(None);
+    };
+    let encoded = event.get("isBase64Encoded").and_then(Value::as_bool) == Some(true);
+    if format == Format::Json || !encoded {
+        return Ok(None);
+    }
+    let body = event
+        .get("body")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let bytes = STANDARD
+        .decode(body)
+        .map_err(|e| AppError::ValidationError(format!("Invalid base64 body: {e}")))?;
+    debug!(
+        format = format.media_type(),
+        bytes = bytes.len(),
+        "Decoding binary request"
+    );
+    format.decode(&bytes).map(Some)
+}
+
+/// Picks the binary format the caller accepts, in the order it lists them.
+///
+/// Events without an `Accept` header, and callers that list neither CBOR
+/// nor `MessagePack` before JSON, get `None`. Types listed with `q=0` are
+/// treated as refused.
+#[must_use]
+pub fn negotiate(event: &Value) -> Option<Format> {
+    header(event, "accept")?
+        .split(',')
+        .filter(|media_type| {
+            !media_type.split(';').skip(1).map(str::trim).any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            })
+        })
+        .find_map(Format::from_media_type)
+        .filter(|format| *format != Format::Json)
+}
+
+/// Wraps `response` in a base64 proxy response encoded in `format`.
+///
+/// Responses that fail to encode are returned unchanged, as JSON.
+#[must_use]
+pub fn encode_response(response: Value, format: Format) -> Value {
+    let body = match format.encode(&response) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!(error = %e, format = format.media_type(), "Failed to encode response, sending JSON");
+            return response;
+        }
+    };
+    debug!(
+        format = format.media_type(),
+        bytes = body.len(),
+        "Encoded binary response"
+    );
+    json!({
+        "statusCode": 200,
+        "headers": {
+            "content-type": format.media_type(),
+            "vary": "accept",
+        },
+        "body": STANDARD.encode(body),
+        "isBase64Encoded": true,
+    })
+}
+
+/// Value of header `name` in a proxy-shaped `event`, ignoring case.
+fn header<'a>(event: &'a Value, name: &str) -> Option<&'a str> {
+    event
+        .get("headers")?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))?
+        .1
+        .as_str()
+}
@@ -0,0 +1,118 @@
+//! Argument completion for interactive MCP clients.
+//!
+//! A `completion/complete` request names a tool and one of its arguments,
+//! with what the user has typed so far. `location` arguments are completed
+//! from the embedded city list (see [`crate::gazetteer`]), and arguments
+//! whose published schema lists allowed values, such as `units`, from those
+//! values. Other arguments get no suggestions. Requests arrive through the
+//! same Lambda entry point as tool calls (see [`crate::handler`]).
+
+use crate::catalog;
+use crate::gazetteer;
+use crate::models::{
+    CompleteParams, CompleteResult, Completion, CompletionArgument, CompletionRef,
+};
+use crate::registry::{self, bedrock_tool_schema};
+use lambda_runtime::Diagnostic;
+use lambda_runtime::tracing::{debug, error};
+use serde_json::Value;
+
+/// Most values returned in one completion, as the MCP specification allows
+const MAX_VALUES: usize = 100;
+
+/// Argument completed from the embedded city list
+const LOCATION_ARG: &str = "location";
+
+/// Answers a `completion/complete` request payload.
+///
+/// # Errors
+///
+/// Returns an `InvalidInput` diagnostic if the request parameters cannot be
+/// parsed.
+pub fn handle(payload: &Value) -> Result<Value, Diagnostic> {
+    let params = payload.get("params").cloned().unwrap_or_default();
+    let params: CompleteParams = serde_json::from_value(params).map_err(|e| {
+        error!(error = %e, code = catalog::INVALID_INPUT.code, "Failed to parse completion request");
+        catalog::INVALID_INPUT.diagnostic("InvalidInput", &e.to_string())
+    })?;
+    serde_json::to_value(complete(&params))
+        .map_err(|e| catalog::SERIALIZATION_FAILED.diagnostic("SerializationError", &e.to_string()))
+}
+
+/// Suggests values for the argument in `params`.
+#[must_use]
+pub fn complete(params: &CompleteParams) -> CompleteResult {
+    let CompletionRef::Tool { name } = &params.reference else {
+        return CompleteResult {
+            completion: Completion::default(),
+        };
+    };
+    let CompletionArgument {
+        name: argument,
+        value,
+    } = &params.argument;
+    let candidates = candidates(name, argument, value);
+    debug!(tool = %name, argument = %argument, matches = candidates.len(), "Completed argument");
+
+    let total = candidates.len();
+    let values: Vec<String> = candidates.into_iter().take(MAX_VALUES).collect();
+    CompleteResult {
+        completion: Completion {
+            has_more: total > values.len(),
+            total: Some(total),
+            values,
+        },
+    }
+}
+
+/// Every value of `argument` to `tool` that matches `typed`.
+fn candidates(tool: &str, argument: &str, typed: &str) -> Vec<String> {
+    let Some(property) = registry::find(tool)
+        .and_then(|tool| bedrock_tool_schema(tool).ok())
+        .and_then(|schema| {
+            schema
+                .pointer(&format!("/inputSchema/properties/{argument}"))
+                .cloned()
+        })
+    else {
+        return Vec::new();
+    };
+
+    if argument == LOCATION_ARG {
+        return gazetteer::names_starting_with(typed)
+            .into_iter()
+            .map(String::from)
+            .collect();
+    }
+    let typed = typed.trim().to_lowercase();
+    allowed_values(&property)
+        .into_iter()
+        .filter(|value| value.to_lowercase().starts_with(&typed))
+        .collect()
+}
+
+/// Values listed by `enum` or `const` in a property schema, including under
+/// `anyOf`/`oneOf` alternatives.
+fn allowed_values(schema: &Value) -> Vec<String> {
+    let mut values: Vec<String> = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .chain(schema.get("const"))
+        .filter_map(Value::as_str)
+        .map(String::from)
+        .collect();
+    for keyword in ["anyOf", "oneOf"] {
+        for alternative in schema
+            .get(keyword)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            values.extend(allowed_values(alternative));
+        }
+    }
+    values.dedup();
+    values
+}
@@ -0,0 +1,133 @@
+//! Compression of tool responses for HTTP-origin events.
+//!
+//! Lambda caps response payloads at 6 MB, and API Gateway at 10 MB, which
+//! long forecasts or fetched documents can approach. When an event arrives
+//! through an HTTP integration and its `Accept-Encoding` header allows it,
+//! responses above `RESPONSE_COMPRESSION_MIN_BYTES` are gzip- or
+//! deflate-compressed and returned as a base64 proxy response with a
+//! `Content-Encoding` header. Direct invocations are never compressed.
+
+use crate::buffers;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::Compression;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use lambda_runtime::tracing::{info, warn};
+use serde_json::{Value, json};
+use std::io::Write;
+
+/// A content coding we can produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// Token used in `Accept-Encoding` and `Content-Encoding`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+}
+
+/// Picks an encoding the client accepts, preferring gzip.
+///
+/// `event` must be an HTTP-origin event (API Gateway or function URL); other
+/// events have no headers and get `None`. Codings listed with `q=0` are
+/// treated as refused.
+#[must_use]
+pub fn negotiate(event: &Value) -> Option<Encoding> {
+    let accept = event
+        .get("headers")?
+        .as_object()?
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("accept-encoding"))?
+        .1
+        .as_str()?;
+
+    let accepted: Vec<&str> = accept
+        .split(',')
+        .filter_map(|coding| {
+            let mut params = coding.split(';').map(str::trim);
+            let name = params.next()?;
+            let refused = params.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            });
+            (!refused).then_some(name)
+        })
+        .collect();
+
+    [Encoding::Gzip, Encoding::Deflate]
+        .into_iter()
+        .find(|encoding| {
+            accepted
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(encoding.as_str()) || *name == "*")
+        })
+}
+
+/// Compresses `bytes` with `encoding`.
+///
+/// # Errors
+///
+/// Returns an error if the encoder fails.
+pub fn compress(bytes: &[u8], encoding: Encoding) -> std::io::Result<Vec<u8>> {
+    match encoding {
+        Encoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+        Encoding::Deflate => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+    }
+}
+
+/// Wraps `response` in a compressed proxy response if it is at least
+/// `min_bytes` when serialized.
+///
+/// Smaller responses, and responses that fail to compress, are returned
+/// unchanged. The response is serialized into a pooled buffer (see
+/// [`crate::buffers`]).
+#[must_use]
+pub fn compress_response(response: Value, encoding: Encoding, min_bytes: usize) -> Value {
+    let Ok(body) = buffers::to_json(&response) else {
+        return response;
+    };
+    if body.len() < min_bytes {
+        return response;
+    }
+    let compressed = match compress(&body, encoding) {
+        Ok(compressed) => compressed,
+        Err(e) => {
+            warn!(error = %e, "Failed to compress response, sending it uncompressed");
+            return response;
+        }
+    };
+    info!(
+        encoding = encoding.as_str(),
+        original_bytes = body.len(),
+        compressed_bytes = compressed.len(),
+        "Compressed tool response"
+    );
+    json!({
+        "statusCode": 200,
+        "headers": {
+            "content-type": "application/json",
+            "content-encoding": encoding.as_str(),
+            "vary": "accept-encoding",
+        },
+        "body": STANDARD.encode(compressed),
+        "isBase64Encoded": true,
+    })
+}
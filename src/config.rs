@@ -0,0 +1,1441 @@
+//! Application configuration loaded from environment variables.
+//!
+//! All tunables are read once per container on first use. Missing or
+//! unparsable values fall back to defaults so a misconfigured variable never
+//! prevents the Lambda from starting.
+//!
+//! A Lambda's environment cannot change while its containers run, so
+//! settings meant to change without a deployment are kept in the Secrets
+//! Manager secret `CONFIG_SECRET_ARN` names instead: a JSON object of
+//! variable names and values, which take precedence over the environment.
+//! [`load`] reads it when the container starts, and [`reload_config`] reads
+//! it again and swaps in the configuration built from it; callers of
+//! [`config`] holding the previous one keep it until they drop it.
+
+use crate::arguments::{ParsingMode, parse_tool_modes};
+use crate::auth::ToolPolicy;
+use crate::aws::sdk_config;
+use crate::guards::BodyLimits;
+use crate::http::{FaultProfile, VcrMode};
+use crate::limits::parse_tool_limits;
+use crate::models::error::AppError;
+use crate::residency::ResidencyRegion;
+use crate::tenants::TenantOverlay;
+use lambda_runtime::tracing::warn;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+/// Default Bedrock model used by `summarize_text`
+const DEFAULT_BEDROCK_MODEL_ID: &str = "apac.amazon.nova-lite-v1:0";
+
+/// Default layout of the `daily_briefing` text
+pub const DEFAULT_BRIEFING_TEMPLATE: &str = "{{greeting}} {{weather}} {{holidays}}";
+
+/// Environment variable naming the configuration secret
+pub const CONFIG_SECRET_ARN: &str = "CONFIG_SECRET_ARN";
+
+/// Process-wide configuration, initialized on first access and replaced by
+/// [`reload_config`].
+static CONFIG: LazyLock<RwLock<Arc<AppConfig>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(AppConfig::from_env())));
+
+/// Variables read from the configuration secret
+static OVERRIDES: LazyLock<RwLock<HashMap<String, String>>> = LazyLock::new(RwLock::default);
+
+static SECRETS_CLIENT: OnceCell<aws_sdk_secretsmanager::Client> = OnceCell::const_new();
+
+/// Returns the process-wide configuration.
+#[must_use]
+pub fn config() -> Arc<AppConfig> {
+    Arc::clone(&CONFIG.read().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Applies the configuration secret, if one is configured, when the
+/// container starts.
+///
+/// # Errors
+///
+/// Returns `AppError::GenericError` if the secret cannot be read or is not
+/// a JSON object.
+pub async fn load() -> Result<(), AppError> {
+    reload_config().await.map(drop)
+}
+
+/// Re-reads the configuration secret and rebuilds the configuration from it
+/// and the environment.
+///
+/// Settings captured when first used, such as cache sizes and endpoint
+/// pools, keep their original values until the container is recycled.
+///
+/// # Errors
+///
+/// Returns `AppError::GenericError` if the secret cannot be read or is not
+/// a JSON object; the current configuration is then kept.
+pub async fn reload_config() -> Result<Arc<AppConfig>, AppError> {
+    let overrides = read_overrides().await?;
+    *OVERRIDES.write().unwrap_or_else(PoisonError::into_inner) = overrides;
+    let reloaded = Arc::new(AppConfig::from_env());
+    *CONFIG.write().unwrap_or_else(PoisonError::into_inner) = Arc::clone(&reloaded);
+    Ok(reloaded)
+}
+
+/// The variables in the configuration secret, or none without one.
+async fn read_overrides() -> Result<HashMap<String, String>, AppError> {
+    let Some(secret_arn) = env::var(CONFIG_SECRET_ARN)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+    else {
+        return Ok(HashMap::new());
+    };
+    let client = SECRETS_CLIENT
+        .get_or_init(|| async { aws_sdk_secretsmanager::Client::new(sdk_config().await) })
+        .await;
+    let output = client
+        .get_secret_value()
+        .secret_id(&secret_arn)
+        .send()
+        .await
+        .map_err(|e| AppError::GenericError(format!("Failed to read configuration secret: {e}")))?;
+    let secret = output.secret_string().ok_or_else(|| {
+        AppError::GenericError(format!("Secret {secret_arn} has no string value"))
+    })?;
+    parse_overrides(secret)
+}
+
+/// The variables in a configuration secret: strings as they are, other
+/// values as JSON, such as the object `WORKFLOWS` holds.
+///
+/// # Errors
+///
+/// Returns `AppError::GenericError` if `secret` is not a JSON object.
+pub fn parse_overrides(secret: &str) -> Result<HashMap<String, String>, AppError> {
+    let variables: Map<String, Value> = serde_json::from_str(secret).map_err(|e| {
+        AppError::GenericError(format!("Configuration secret is not a JSON object: {e}"))
+    })?;
+    Ok(variables
+        .into_iter()
+        .map(|(name, value)| match value {
+            Value::String(value) => (name, value),
+            value => (name, value.to_string()),
+        })
+        .collect())
+}
+
+/// A configuration variable, from the configuration secret or else the
+/// environment.
+fn var(key: &str) -> Result<String, env::VarError> {
+    OVERRIDES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(key)
+        .cloned()
+        .map_or_else(|| env::var(key), Ok)
+}
+
+/// Top-level application configuration.
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub weather: WeatherConfig,
+    pub summarize: SummarizeConfig,
+    pub notifications: NotificationConfig,
+    pub logs: LogsQueryConfig,
+    pub workflows: WorkflowConfig,
+    pub compose: ComposeConfig,
+    pub sandbox: SandboxConfig,
+    pub declarative: DeclarativeConfig,
+    pub transforms: TransformConfig,
+    pub holidays: HolidaysConfig,
+    pub briefing: BriefingConfig,
+    pub preferences: PreferencesConfig,
+    pub memory: UserMemoryConfig,
+    pub consent: ConsentConfig,
+    pub residency: ResidencyConfig,
+    pub defaults: DefaultsConfig,
+    pub profile: ProfileConfig,
+    pub pagination: PaginationConfig,
+    pub metrics: MetricsConfig,
+    pub concurrency: ConcurrencyConfig,
+    pub timeouts: TimeoutConfig,
+    pub http: HttpConfig,
+    pub auth: AuthConfig,
+    pub admin: AdminConfig,
+    pub interceptor: InterceptorConfig,
+    pub identity: IdentityConfig,
+    pub crypto: CryptoConfig,
+    pub secrets: SecretScanConfig,
+    pub compression: CompressionConfig,
+    pub content: ContentConfig,
+    pub deprecation: DeprecationConfig,
+    pub sampling: SamplingConfig,
+    pub roots: RootsConfig,
+    pub arguments: ArgumentsConfig,
+    pub audit: AuditConfig,
+    pub shapes: ShapesConfig,
+    pub tenants: TenantConfig,
+    pub usage: UsageConfig,
+    pub webhooks: WebhookConfig,
+    pub effects: EffectJournalConfig,
+    pub dry_run: DryRunConfig,
+    pub confirmation: ConfirmationConfig,
+    pub provenance: ProvenanceConfig,
+    pub memo: SessionCacheConfig,
+    pub logging: LoggingConfig,
+    pub capture: TraceCaptureConfig,
+    pub codec: CodecConfig,
+    pub async_calls: AsyncCallsConfig,
+}
+
+/// Settings for the `get_weather` tool.
+#[derive(Debug, Clone)]
+pub struct WeatherConfig {
+    /// Serve expired cache entries while Open-Meteo is rate limiting (`WEATHER_SERVE_STALE`)
+    pub serve_stale: bool,
+    /// How long past their TTL cached entries are served while being
+    /// refreshed in the background, `0` to disable (`WEATHER_CACHE_MAX_STALE_SECS`)
+    pub max_stale: Duration,
+    /// Forecast API base URLs, tried in order of health and latency (`OPEN_METEO_FORECAST_URLS`)
+    pub forecast_urls: Vec<String>,
+    /// Geocoding API base URLs, tried in order of health and latency (`OPEN_METEO_GEOCODING_URLS`)
+    pub geocoding_urls: Vec<String>,
+    /// Air quality API base URLs, for pollen forecasts (`OPEN_METEO_AIR_QUALITY_URLS`)
+    pub air_quality_urls: Vec<String>,
+    /// Other geocoding matches returned with a forecast (`WEATHER_GEOCODE_ALTERNATES`, max 10)
+    pub geocode_alternates: usize,
+    /// Resolve major cities from the embedded gazetteer instead of the geocoding API (`WEATHER_OFFLINE_GEOCODING`)
+    pub offline_geocoding: bool,
+}
+
+/// Settings for the Bedrock-backed `summarize_text` tool.
+#[derive(Debug, Clone)]
+pub struct SummarizeConfig {
+    /// Bedrock model or inference profile ID (`BEDROCK_MODEL_ID`)
+    pub model_id: String,
+    /// Largest input accepted, in characters (`SUMMARIZE_MAX_INPUT_CHARS`)
+    pub max_input_chars: usize,
+    /// Upper bound on generated tokens (`SUMMARIZE_MAX_OUTPUT_TOKENS`)
+    pub max_output_tokens: u32,
+}
+
+/// Settings for the `send_notification` tool.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationConfig {
+    /// SNS topic ARNs messages may be published to (`NOTIFY_SNS_TOPIC_ALLOWLIST`)
+    pub sns_topic_allowlist: Vec<String>,
+    /// Email addresses, or `@domain` suffixes, that may receive mail (`NOTIFY_EMAIL_ALLOWLIST`)
+    pub email_allowlist: Vec<String>,
+    /// Verified SES identity used as the sender (`NOTIFY_SES_FROM_ADDRESS`)
+    pub ses_from_address: Option<String>,
+}
+
+/// Settings for the `query_logs` tool.
+#[derive(Debug, Clone)]
+pub struct LogsQueryConfig {
+    /// Log group names, or `prefix*` patterns, that may be queried (`QUERY_LOGS_GROUP_ALLOWLIST`)
+    pub log_group_allowlist: Vec<String>,
+    /// Widest time range a query may cover, in hours (`QUERY_LOGS_MAX_RANGE_HOURS`)
+    pub max_range_hours: u32,
+    /// Upper bound on rows returned per query (`QUERY_LOGS_MAX_ROWS`)
+    pub max_rows: u32,
+    /// How long to wait for a query to complete, in seconds (`QUERY_LOGS_TIMEOUT_SECS`)
+    pub timeout_secs: u64,
+}
+
+/// Step Functions state machines exposed through `start_workflow`.
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowConfig {
+    /// Allowlisted workflows keyed by name (`WORKFLOWS`, a JSON object)
+    pub workflows: HashMap<String, WorkflowDefinition>,
+}
+
+/// A state machine that callers may start, and the input it accepts.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowDefinition {
+    pub state_machine_arn: String,
+    /// Human-readable summary shown to agents
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema the execution input must satisfy
+    #[serde(default)]
+    pub input_schema: Option<Value>,
+}
+
+impl WorkflowConfig {
+    fn from_env() -> Self {
+        Self {
+            workflows: env_json("WORKFLOWS").unwrap_or_default(),
+        }
+    }
+
+    /// Looks up a workflow by name or state machine ARN.
+    #[must_use]
+    pub fn find(&self, workflow: &str) -> Option<(&str, &WorkflowDefinition)> {
+        self.workflows
+            .get_key_value(workflow)
+            .or_else(|| {
+                self.workflows
+                    .iter()
+                    .find(|(_, def)| def.state_machine_arn == workflow)
+            })
+            .map(|(name, def)| (name.as_str(), def))
+    }
+}
+
+/// Limits for tool compositions run by `run_workflow`.
+#[derive(Debug, Clone)]
+pub struct ComposeConfig {
+    /// Most steps a workflow may declare (`RUN_WORKFLOW_MAX_STEPS`)
+    pub max_steps: usize,
+    /// Time limit for steps that set none (`RUN_WORKFLOW_STEP_TIMEOUT_MS`)
+    pub step_timeout: Duration,
+}
+
+/// Community tools run as WASM modules by `run_sandboxed_tool`.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxConfig {
+    /// Allowlisted tools keyed by name (`SANDBOX_TOOLS`, a JSON object)
+    pub tools: HashMap<String, SandboxToolDefinition>,
+    /// Directory module paths are relative to, usually a Lambda layer (`SANDBOX_TOOLS_DIR`)
+    pub modules_dir: String,
+    /// Linear memory a module may grow to, for tools that set none (`SANDBOX_MAX_MEMORY_BYTES`)
+    pub max_memory_bytes: usize,
+    /// Fuel, roughly one unit per instruction, a call may burn, for tools that set none (`SANDBOX_FUEL`)
+    pub fuel: u64,
+    /// Largest result a module may return, in bytes (`SANDBOX_MAX_OUTPUT_BYTES`)
+    pub max_output_bytes: usize,
+}
+
+/// A sandboxed tool that callers may run, and the limits it runs under.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SandboxToolDefinition {
+    /// Path of the `.wasm` module, relative to the modules directory
+    pub module: String,
+    /// Human-readable summary shown to agents
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema the arguments must satisfy
+    #[serde(default)]
+    pub input_schema: Option<Value>,
+    /// Overrides the default memory limit
+    #[serde(default)]
+    pub max_memory_bytes: Option<usize>,
+    /// Overrides the default fuel
+    #[serde(default)]
+    pub fuel: Option<u64>,
+}
+
+/// REST-wrapping tools defined in configuration, run by `run_declarative_tool`.
+#[derive(Debug, Clone, Default)]
+pub struct DeclarativeConfig {
+    /// Tools keyed by name (`DECLARATIVE_TOOLS`, a JSON object)
+    pub tools: HashMap<String, DeclarativeToolDefinition>,
+}
+
+/// A tool that fills an HTTP request template from its arguments and maps
+/// the JSON response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeclarativeToolDefinition {
+    /// Human-readable summary shown to agents
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema the arguments must satisfy; when set, templates may only use the properties it declares
+    #[serde(default)]
+    pub input_schema: Option<Value>,
+    pub request: RequestTemplate,
+    /// `JMESPath` expression applied to the JSON response (default: the whole response)
+    #[serde(default)]
+    pub response: Option<String>,
+}
+
+/// An HTTP request with `{{argument}}` placeholders.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestTemplate {
+    /// HTTP method (default: `GET`)
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// URL; placeholders may only appear in the path and query
+    pub url: String,
+    /// Header values; names are literal
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// JSON body; a string that is only a placeholder takes the argument's JSON value
+    #[serde(default)]
+    pub body: Option<Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// `JMESPath` reshaping of tool responses (see [`crate::transform`]).
+#[derive(Debug, Clone, Default)]
+pub struct TransformConfig {
+    /// Expressions keyed by tool name (`RESPONSE_TRANSFORMS`, a JSON object)
+    pub transforms: HashMap<String, String>,
+}
+
+/// Settings for the `get_public_holidays` tool.
+#[derive(Debug, Clone)]
+pub struct HolidaysConfig {
+    /// Base URL of the Nager.Date public holidays API (`HOLIDAYS_API_URL`)
+    pub api_url: String,
+}
+
+/// Settings for the `daily_briefing` tool.
+#[derive(Debug, Clone)]
+pub struct BriefingConfig {
+    /// Template for the briefing text, with `{{greeting}}`, `{{weather}}`,
+    /// `{{holidays}}`, `{{location}}` and `{{date}}` placeholders (`BRIEFING_TEMPLATE`)
+    pub template: String,
+}
+
+/// Where user preferences are stored.
+#[derive(Debug, Clone, Default)]
+pub struct PreferencesConfig {
+    /// `DynamoDB` table keyed by `user_id` (`USER_PREFERENCES_TABLE`)
+    pub table: Option<String>,
+}
+
+/// What signed-in users last asked about, recalled by greetings.
+#[derive(Debug, Clone)]
+pub struct UserMemoryConfig {
+    /// Remember each user's latest call and mention it in greetings (`USER_MEMORY`)
+    pub enabled: bool,
+    /// `DynamoDB` table keyed by `user_id`; without one, memories stay in the container (`USER_MEMORY_TABLE`)
+    pub table: Option<String>,
+    /// How long a memory is kept (`USER_MEMORY_TTL_DAYS`)
+    pub ttl: Duration,
+    /// Most users remembered in the container (`USER_MEMORY_MAX_ENTRIES`)
+    pub max_entries: usize,
+}
+
+/// Whether personal data is only used with the caller's consent.
+#[derive(Debug, Clone, Default)]
+pub struct ConsentConfig {
+    /// Use callers' names, preferences and memories only once they consent (`CONSENT_REQUIRED`)
+    pub required: bool,
+}
+
+/// Regions callers' personal data is stored in.
+#[derive(Debug, Clone, Default)]
+pub struct ResidencyConfig {
+    /// Token claim naming the caller's data residency, e.g. "eu" (`RESIDENCY_CLAIM`, default `residency`)
+    pub claim: String,
+    /// Where each residency's data is stored (`RESIDENCY_REGIONS`, a JSON object)
+    pub regions: HashMap<String, ResidencyRegion>,
+}
+
+/// Default arguments filled in for tool calls.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultsConfig {
+    /// Arguments per tool name, used when neither the caller nor their
+    /// preferences supply them (`TOOL_DEFAULT_ARGS`, a JSON object)
+    pub tool_args: HashMap<String, Map<String, Value>>,
+}
+
+/// Which token claims carry profile hints for tools.
+#[derive(Debug, Clone)]
+pub struct ProfileConfig {
+    /// Custom claim holding the caller's unit preference, `metric` or
+    /// `imperial` (`PROFILE_UNITS_CLAIM`)
+    pub units_claim: String,
+}
+
+/// Settings for signed pagination cursors.
+#[derive(Debug, Clone, Default)]
+pub struct PaginationConfig {
+    /// HMAC key for signing cursors (`PAGINATION_SECRET`)
+    pub secret: Option<String>,
+}
+
+/// Settings for tool content size metrics.
+#[derive(Debug, Clone)]
+pub struct MetricsConfig {
+    /// Return size metrics in each response's `_meta` (`RESPONSE_METRICS`)
+    pub include_in_response: bool,
+    /// Estimated output tokens above which a response is logged as oversized
+    /// (`OUTPUT_TOKEN_ALERT_THRESHOLD`)
+    pub output_token_alert_threshold: usize,
+}
+
+/// Per-tool concurrency limits and load shedding.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyConfig {
+    /// Concurrent calls allowed per tool (`TOOL_CONCURRENCY_LIMITS`, e.g. `read_feed=4;query_logs=2`)
+    pub tool_limits: HashMap<String, usize>,
+    /// Limit for tools without an entry, `0` for unlimited (`TOOL_CONCURRENCY_DEFAULT`)
+    pub default_limit: usize,
+    /// How long a call waits for a free slot (`TOOL_QUEUE_TIMEOUT_MS`)
+    pub queue_timeout: Duration,
+    /// In-flight invocations per container before new ones are shed, `0` to
+    /// disable shedding (`MAX_IN_FLIGHT`)
+    pub max_in_flight: usize,
+}
+
+/// Upstream timeout settings.
+#[derive(Debug, Clone)]
+pub struct TimeoutConfig {
+    /// Longest an upstream request may take (`UPSTREAM_TIMEOUT_MS`)
+    pub upstream: Duration,
+    /// Time reserved before the invocation deadline for serialization and
+    /// logging (`DEADLINE_SAFETY_MARGIN_MS`)
+    pub safety_margin: Duration,
+}
+
+/// Settings for upstream HTTP calls.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Record or replay upstream responses (`HTTP_VCR_MODE`: `off`, `record` or `replay`)
+    pub vcr_mode: VcrMode,
+    /// Directory holding recorded fixtures (`HTTP_VCR_DIR`)
+    pub vcr_dir: String,
+    /// Domains outbound requests may reach; empty allows all (`EGRESS_ALLOWLIST`)
+    pub egress_allowlist: Vec<String>,
+    /// Domains outbound requests may never reach (`EGRESS_DENYLIST`)
+    pub egress_denylist: Vec<String>,
+    /// Let outbound requests reach loopback, private and link-local addresses (`EGRESS_ALLOW_PRIVATE`)
+    pub egress_allow_private: bool,
+    /// Send a second copy of slow idempotent requests (`HTTP_HEDGING`)
+    pub hedging: bool,
+    /// Shortest wait before a request is hedged (`HTTP_HEDGE_MIN_DELAY_MS`)
+    pub hedge_min_delay: Duration,
+    /// Resolve host names asynchronously and cache the answers (`DNS_CACHE`)
+    pub dns_cache: bool,
+    /// How long resolved addresses are reused (`DNS_CACHE_TTL_SECS`)
+    pub dns_cache_ttl: Duration,
+    /// Host names kept in the DNS cache (`DNS_CACHE_SIZE`)
+    pub dns_cache_size: usize,
+    /// Upstream faults injected in builds with the `chaos` feature (`HTTP_FAULTS`, `HTTP_FAULT_DELAY_MS`, `HTTP_FAULT_SEED`)
+    pub faults: FaultProfile,
+    /// Interval between keepalive pings to recently called hosts, `0` to disable (`HTTP_KEEPALIVE_PING_SECS`)
+    pub keepalive_ping: Duration,
+    /// Secrets Manager secret holding the client certificate and key for mTLS (`HTTP_CLIENT_CERT_SECRET_ARN`)
+    pub client_cert_secret_arn: Option<String>,
+    /// Secrets Manager secret holding extra PEM CA certificates to trust (`HTTP_CA_BUNDLE_SECRET_ARN`)
+    pub ca_bundle_secret_arn: Option<String>,
+    /// Proxy every outbound request is sent through (`HTTP_PROXY_URL`)
+    pub proxy_url: Option<String>,
+    /// Hosts, domains and CIDR ranges reached without the proxy (`HTTP_NO_PROXY`)
+    pub no_proxy: Vec<String>,
+}
+
+/// Authorization settings.
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    /// Tool-to-scope policy (`TOOL_SCOPE_POLICY` overrides the defaults,
+    /// `ADMIN_SCOPES` sets the scopes admin tools accept)
+    pub policy: ToolPolicy,
+}
+
+/// Runtime administration settings.
+#[derive(Debug, Clone, Default)]
+pub struct AdminConfig {
+    /// Tools switched off when a container starts (`DISABLED_TOOLS`)
+    pub disabled_tools: Vec<String>,
+}
+
+/// Settings for the gateway interceptor Lambda.
+#[derive(Debug, Clone, Default)]
+pub struct InterceptorConfig {
+    /// Log what would be injected or blocked without injecting it (`PASSTHROUGH_MODE`);
+    /// client-sent credentials are still stripped
+    pub passthrough: bool,
+    /// Request headers read for the conversation's session ID, in order (`SESSION_ID_HEADERS`)
+    pub session_headers: Vec<String>,
+    /// Largest and most deeply nested request body forwarded (`INTERCEPTOR_MAX_BODY_BYTES`, `INTERCEPTOR_MAX_BODY_DEPTH`)
+    pub body_limits: BodyLimits,
+}
+
+/// Settings for signed identity contexts passed from the interceptor.
+#[derive(Debug, Clone)]
+pub struct IdentityConfig {
+    /// Secrets Manager secret holding the signing key ring (`IDENTITY_SECRET_ARN`)
+    pub secret_arn: Option<String>,
+    /// Signing key or key ring, used when no secret ARN is set (`IDENTITY_CONTEXT_SECRET`)
+    pub secret: Option<String>,
+    /// Drop unsigned `user_id`/`user_name` arguments (`IDENTITY_CONTEXT_REQUIRED`)
+    pub required: bool,
+    /// How long a signed context stays valid (`IDENTITY_CONTEXT_TTL_SECS`)
+    pub ttl: Duration,
+    /// How often the key ring is re-read to pick up rotations (`IDENTITY_KEY_REFRESH_SECS`)
+    pub key_refresh: Duration,
+}
+
+/// Settings for envelope encryption of sensitive tool arguments.
+#[derive(Debug, Clone)]
+pub struct CryptoConfig {
+    /// KMS key that data keys are generated under (`ARG_ENCRYPTION_KMS_KEY_ID`)
+    pub kms_key_id: Option<String>,
+    /// How long a data key is reused before a new one is generated
+    /// (`ARG_ENCRYPTION_DATA_KEY_TTL_SECS`)
+    pub data_key_ttl: Duration,
+}
+
+/// Settings for secret scanning of tool responses.
+#[derive(Debug, Clone)]
+pub struct SecretScanConfig {
+    /// Redact secrets found in tool responses (`RESPONSE_SECRET_SCAN`)
+    pub scan_responses: bool,
+}
+
+/// Settings for compressing responses to HTTP-origin events.
+#[derive(Debug, Clone)]
+pub struct CompressionConfig {
+    /// Compress responses when the client accepts it (`RESPONSE_COMPRESSION`)
+    pub enabled: bool,
+    /// Smallest serialized response that is compressed (`RESPONSE_COMPRESSION_MIN_BYTES`)
+    pub min_bytes: usize,
+}
+
+/// Limits on binary content blocks in tool responses.
+#[derive(Debug, Clone)]
+pub struct ContentConfig {
+    /// Largest binary payload a content block may carry, before base64 (`MAX_BINARY_CONTENT_BYTES`)
+    pub max_binary_bytes: usize,
+}
+
+/// Settings for calls to deprecated tools.
+#[derive(Debug, Clone, Default)]
+pub struct DeprecationConfig {
+    /// Return a deprecation notice in the response's `_meta` (`DEPRECATION_NOTICES`)
+    pub notices: bool,
+}
+
+/// Settings for asking the MCP client's model to write text.
+#[derive(Debug, Clone)]
+pub struct SamplingConfig {
+    /// Use the client's model when the transport supports sampling (`MCP_SAMPLING`)
+    pub enabled: bool,
+    /// Longest wait for the client's reply before falling back (`MCP_SAMPLING_TIMEOUT_MS`)
+    pub timeout: Duration,
+    /// Most tokens the client is asked to generate (`MCP_SAMPLING_MAX_TOKENS`)
+    pub max_tokens: u32,
+}
+
+/// Settings for reading the MCP client's workspace roots.
+#[derive(Debug, Clone)]
+pub struct RootsConfig {
+    /// Longest wait for the client to list its roots (`MCP_ROOTS_TIMEOUT_MS`)
+    pub timeout: Duration,
+    /// How long a session's roots are reused without a change notification (`MCP_ROOTS_CACHE_TTL_SECS`)
+    pub cache_ttl: Duration,
+}
+
+/// How strictly tool arguments are parsed.
+#[derive(Debug, Clone, Default)]
+pub struct ArgumentsConfig {
+    /// Mode for tools without an override (`ARGUMENT_PARSING`: `lenient` or `strict`)
+    pub mode: ParsingMode,
+    /// Modes per tool (`ARGUMENT_PARSING_TOOLS`, e.g. `get_weather=strict;read_feed=lenient`)
+    pub tool_modes: HashMap<String, ParsingMode>,
+    /// Convert declared arguments sent with the wrong type, e.g. `"3"` for an integer (`ARGUMENT_COERCION`)
+    pub coerce: bool,
+}
+
+impl ArgumentsConfig {
+    fn from_env() -> Self {
+        Self {
+            mode: var("ARGUMENT_PARSING").map_or(ParsingMode::Lenient, |raw| {
+                ParsingMode::parse(&raw).unwrap_or_else(|| {
+                    warn!(value = %raw, "Ignoring unknown ARGUMENT_PARSING");
+                    ParsingMode::Lenient
+                })
+            }),
+            tool_modes: parse_tool_modes(&var("ARGUMENT_PARSING_TOOLS").unwrap_or_default()),
+            coerce: env_or("ARGUMENT_COERCION", true),
+        }
+    }
+
+    /// Mode `tool`'s arguments are parsed in.
+    #[must_use]
+    pub fn mode_for(&self, tool: &str) -> ParsingMode {
+        self.tool_modes.get(tool).copied().unwrap_or(self.mode)
+    }
+}
+
+/// Where tool invocations are recorded for replay.
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    /// Record tool invocations so administrators can replay them (`INVOCATION_AUDIT`)
+    pub enabled: bool,
+    /// `DynamoDB` table keyed by `invocation_id`; without one, records stay in the container (`INVOCATION_AUDIT_TABLE`)
+    pub table: Option<String>,
+    /// How long records are kept (`INVOCATION_AUDIT_RETENTION_HOURS`)
+    pub retention: Duration,
+    /// Most records kept in the container (`INVOCATION_AUDIT_MAX_ENTRIES`)
+    pub max_entries: usize,
+}
+
+/// Where the shapes of tool responses are tracked across deployments.
+#[derive(Debug, Clone)]
+pub struct ShapesConfig {
+    /// Fingerprint the structure of tool responses (`RESPONSE_SHAPE_TRACKING`)
+    pub enabled: bool,
+    /// `DynamoDB` table keyed by `tool` and `fingerprint`; without one, shapes are only tracked in the container (`RESPONSE_SHAPES_TABLE`)
+    pub table: Option<String>,
+    /// Deployment new shapes are attributed to (`DEPLOYMENT_VERSION`, default the crate version)
+    pub version: String,
+}
+
+/// Per-tenant overlays on this configuration.
+#[derive(Debug, Clone)]
+pub struct TenantConfig {
+    /// Token claim naming the caller's tenant (`TENANT_CLAIM`, default the Entra ID `tid`)
+    pub claim: String,
+    /// Overlays by tenant ID (`TENANT_OVERLAYS`, a JSON object)
+    pub overlays: HashMap<String, TenantOverlay>,
+    /// How long a tenant's resolved settings are reused (`TENANT_CACHE_TTL_SECS`)
+    pub cache_ttl: Duration,
+}
+
+/// Accounting of tool usage per tenant for charge-back.
+#[derive(Debug, Clone)]
+pub struct UsageConfig {
+    /// Count calls and upstream requests per tenant and tool (`USAGE_ACCOUNTING`)
+    pub enabled: bool,
+    /// `DynamoDB` table keyed by `tenant` and `usage_key`; without one, usage is only kept in the container (`USAGE_TABLE`)
+    pub table: Option<String>,
+    /// How often counts are flushed to the table and logs (`USAGE_FLUSH_INTERVAL_SECS`)
+    pub flush_interval: Duration,
+}
+
+/// Outbound webhooks for tool lifecycle events.
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// HTTPS endpoints events are posted to (`WEBHOOK_URLS`)
+    pub urls: Vec<String>,
+    /// Key payloads are signed with using HMAC-SHA256; unsigned if unset (`WEBHOOK_SECRET`)
+    pub secret: Option<String>,
+    /// Delivery attempts per event and endpoint before it is dropped (`WEBHOOK_MAX_ATTEMPTS`)
+    pub max_attempts: u32,
+    /// Failed calls to one tool within the window that raise an event, `0` to disable (`WEBHOOK_FAILURE_SPIKE_THRESHOLD`)
+    pub failure_spike_threshold: usize,
+    /// Window failures are counted over (`WEBHOOK_FAILURE_SPIKE_WINDOW_SECS`)
+    pub failure_spike_window: Duration,
+}
+
+/// Journal of side effects, so retried calls are not repeated.
+#[derive(Debug, Clone)]
+pub struct EffectJournalConfig {
+    /// Deduplicate retried calls to side-effecting tools (`EFFECT_JOURNAL`)
+    pub enabled: bool,
+    /// `DynamoDB` table keyed by `effect_key`; without one, the journal is only kept in the container (`EFFECT_JOURNAL_TABLE`)
+    pub table: Option<String>,
+    /// How long a completed effect is remembered (`EFFECT_JOURNAL_TTL_SECS`)
+    pub ttl: Duration,
+}
+
+/// Simulation of side-effecting tool calls.
+#[derive(Debug, Clone, Default)]
+pub struct DryRunConfig {
+    /// Plan every side effect instead of performing it, as if each call passed `dry_run` (`DRY_RUN`)
+    pub enabled: bool,
+}
+
+/// Two-phase confirmation of calls to destructive tools.
+#[derive(Debug, Clone)]
+pub struct ConfirmationConfig {
+    /// HMAC key for signing confirmation tokens (`CONFIRMATION_SECRET`)
+    pub secret: Option<String>,
+    /// How long a confirmation token may be used for (`CONFIRMATION_TTL_SECS`)
+    pub ttl: Duration,
+    /// `DynamoDB` table keyed by `token_id` recording spent tokens; without one, tokens are only spent in the container (`CONFIRMATION_TABLE`)
+    pub table: Option<String>,
+}
+
+/// Reporting of where responses' data came from.
+#[derive(Debug, Clone, Default)]
+pub struct ProvenanceConfig {
+    /// List the upstreams and caches each response drew on in its `_meta` (`RESPONSE_SOURCES`, default true)
+    pub enabled: bool,
+}
+
+/// Memoization of repeated tool calls within a conversation.
+#[derive(Debug, Clone)]
+pub struct SessionCacheConfig {
+    /// Answer identical calls in a session from the first call's response (`SESSION_CACHE`)
+    pub enabled: bool,
+    /// How long a response is reused (`SESSION_CACHE_TTL_SECS`)
+    pub ttl: Duration,
+    /// Responses kept per container, across sessions (`SESSION_CACHE_MAX_ENTRIES`)
+    pub max_entries: usize,
+}
+
+/// Log verbosity.
+#[derive(Debug, Clone, Default)]
+pub struct LoggingConfig {
+    /// Level directives per module, e.g. `info,aws_lambda_mcp::tools::weather=debug` (`LOG_FILTER`, else `RUST_LOG`)
+    pub filter: String,
+}
+
+/// Where invocation traces are written (see [`crate::capture`]).
+#[derive(Debug, Clone, Default)]
+pub struct TraceCaptureConfig {
+    /// S3 bucket traces are written to; capture is off without one (`TRACE_CAPTURE_BUCKET`)
+    pub bucket: Option<String>,
+    /// Key prefix of traces in the bucket (`TRACE_CAPTURE_PREFIX`)
+    pub prefix: String,
+}
+
+/// Binary payloads for direct invocations (see [`crate::codec`]).
+#[derive(Debug, Clone, Default)]
+pub struct CodecConfig {
+    /// Accept and return CBOR or `MessagePack` bodies when the caller asks (`BINARY_PAYLOADS`)
+    pub binary_payloads: bool,
+}
+
+/// Tool calls delivered by SQS or `EventBridge` (see [`crate::async_calls`]).
+#[derive(Debug, Clone)]
+pub struct AsyncCallsConfig {
+    /// Attempts at a call from SQS before it is recorded as failed (`ASYNC_MAX_ATTEMPTS`)
+    pub max_attempts: u32,
+    /// `DynamoDB` table keyed by `failure_id`; without one, failed calls stay in the container (`FAILED_CALLS_TABLE`)
+    pub table: Option<String>,
+    /// SNS topic each failed call is published to (`FAILED_CALLS_TOPIC_ARN`)
+    pub topic_arn: Option<String>,
+    /// How long failed calls are kept (`FAILED_CALLS_RETENTION_HOURS`)
+    pub retention: Duration,
+    /// Most failed calls kept in the container (`FAILED_CALLS_MAX_ENTRIES`)
+    pub max_entries: usize,
+}
+
+impl AppConfig {
+    /// Builds configuration from environment variables, applying defaults.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self {
+            weather: WeatherConfig::from_env(),
+            summarize: SummarizeConfig::from_env(),
+            notifications: NotificationConfig::from_env(),
+            logs: LogsQueryConfig::from_env(),
+            workflows: WorkflowConfig::from_env(),
+            compose: ComposeConfig::from_env(),
+            sandbox: SandboxConfig::from_env(),
+            declarative: DeclarativeConfig::from_env(),
+            transforms: TransformConfig::from_env(),
+            holidays: HolidaysConfig::from_env(),
+            briefing: BriefingConfig::from_env(),
+            preferences: PreferencesConfig::from_env(),
+            memory: UserMemoryConfig::from_env(),
+            consent: ConsentConfig::from_env(),
+            residency: ResidencyConfig::from_env(),
+            defaults: DefaultsConfig::from_env(),
+            profile: ProfileConfig::from_env(),
+            pagination: PaginationConfig::from_env(),
+            metrics: MetricsConfig::from_env(),
+            concurrency: ConcurrencyConfig::from_env(),
+            timeouts: TimeoutConfig::from_env(),
+            http: HttpConfig::from_env(),
+            auth: AuthConfig::from_env(),
+            admin: AdminConfig::from_env(),
+            interceptor: InterceptorConfig::from_env(),
+            identity: IdentityConfig::from_env(),
+            crypto: CryptoConfig::from_env(),
+            secrets: SecretScanConfig::from_env(),
+            compression: CompressionConfig::from_env(),
+            content: ContentConfig::from_env(),
+            deprecation: DeprecationConfig::from_env(),
+            sampling: SamplingConfig::from_env(),
+            roots: RootsConfig::from_env(),
+            arguments: ArgumentsConfig::from_env(),
+            audit: AuditConfig::from_env(),
+            shapes: ShapesConfig::from_env(),
+            tenants: TenantConfig::from_env(),
+            usage: UsageConfig::from_env(),
+            webhooks: WebhookConfig::from_env(),
+            effects: EffectJournalConfig::from_env(),
+            dry_run: DryRunConfig::from_env(),
+            confirmation: ConfirmationConfig::from_env(),
+            provenance: ProvenanceConfig::from_env(),
+            memo: SessionCacheConfig::from_env(),
+            logging: LoggingConfig::from_env(),
+            capture: TraceCaptureConfig::from_env(),
+            codec: CodecConfig::from_env(),
+            async_calls: AsyncCallsConfig::from_env(),
+        }
+    }
+}
+
+impl WeatherConfig {
+    fn from_env() -> Self {
+        Self {
+            serve_stale: env_or("WEATHER_SERVE_STALE", true),
+            max_stale: Duration::from_secs(env_or("WEATHER_CACHE_MAX_STALE_SECS", 300)),
+            forecast_urls: env_list_or("OPEN_METEO_FORECAST_URLS", "https://api.open-meteo.com"),
+            geocoding_urls: env_list_or(
+                "OPEN_METEO_GEOCODING_URLS",
+                "https://geocoding-api.open-meteo.com",
+            ),
+            air_quality_urls: env_list_or(
+                "OPEN_METEO_AIR_QUALITY_URLS",
+                "https://air-quality-api.open-meteo.com",
+            ),
+            geocode_alternates: env_or("WEATHER_GEOCODE_ALTERNATES", 3),
+            offline_geocoding: env_or("WEATHER_OFFLINE_GEOCODING", true),
+        }
+    }
+}
+
+impl SummarizeConfig {
+    fn from_env() -> Self {
+        Self {
+            model_id: var("BEDROCK_MODEL_ID")
+                .unwrap_or_else(|_| DEFAULT_BEDROCK_MODEL_ID.to_string()),
+            max_input_chars: env_or("SUMMARIZE_MAX_INPUT_CHARS", 50_000),
+            max_output_tokens: env_or("SUMMARIZE_MAX_OUTPUT_TOKENS", 1_024),
+        }
+    }
+}
+
+impl NotificationConfig {
+    fn from_env() -> Self {
+        Self {
+            sns_topic_allowlist: env_list("NOTIFY_SNS_TOPIC_ALLOWLIST"),
+            email_allowlist: env_list("NOTIFY_EMAIL_ALLOWLIST"),
+            ses_from_address: var("NOTIFY_SES_FROM_ADDRESS")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+        }
+    }
+}
+
+impl LogsQueryConfig {
+    fn from_env() -> Self {
+        Self {
+            log_group_allowlist: env_list("QUERY_LOGS_GROUP_ALLOWLIST"),
+            max_range_hours: env_or("QUERY_LOGS_MAX_RANGE_HOURS", 24),
+            max_rows: env_or("QUERY_LOGS_MAX_ROWS", 1_000),
+            timeout_secs: env_or("QUERY_LOGS_TIMEOUT_SECS", 20),
+        }
+    }
+}
+
+impl ComposeConfig {
+    fn from_env() -> Self {
+        Self {
+            max_steps: env_or("RUN_WORKFLOW_MAX_STEPS", 16),
+            step_timeout: Duration::from_millis(env_or("RUN_WORKFLOW_STEP_TIMEOUT_MS", 10_000)),
+        }
+    }
+}
+
+impl SandboxConfig {
+    fn from_env() -> Self {
+        Self {
+            tools: env_json("SANDBOX_TOOLS").unwrap_or_default(),
+            modules_dir: var("SANDBOX_TOOLS_DIR")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "/opt/sandbox-tools".to_string()),
+            max_memory_bytes: env_or("SANDBOX_MAX_MEMORY_BYTES", 16 * 1024 * 1024),
+            fuel: env_or("SANDBOX_FUEL", 100_000_000),
+            max_output_bytes: env_or("SANDBOX_MAX_OUTPUT_BYTES", 1024 * 1024),
+        }
+    }
+}
+
+impl DeclarativeConfig {
+    fn from_env() -> Self {
+        Self {
+            tools: env_json("DECLARATIVE_TOOLS").unwrap_or_default(),
+        }
+    }
+}
+
+impl TransformConfig {
+    fn from_env() -> Self {
+        Self {
+            transforms: env_json("RESPONSE_TRANSFORMS").unwrap_or_default(),
+        }
+    }
+}
+
+impl HolidaysConfig {
+    fn from_env() -> Self {
+        Self {
+            api_url: var("HOLIDAYS_API_URL")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "https://date.nager.at".to_string()),
+        }
+    }
+}
+
+impl BriefingConfig {
+    fn from_env() -> Self {
+        Self {
+            template: var("BRIEFING_TEMPLATE")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_BRIEFING_TEMPLATE.to_string()),
+        }
+    }
+}
+
+impl PreferencesConfig {
+    fn from_env() -> Self {
+        Self {
+            table: var("USER_PREFERENCES_TABLE")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+        }
+    }
+}
+
+impl UserMemoryConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("USER_MEMORY", false),
+            table: var("USER_MEMORY_TABLE")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            ttl: Duration::from_hours(env_or::<u64>("USER_MEMORY_TTL_DAYS", 30).saturating_mul(24)),
+            max_entries: env_or("USER_MEMORY_MAX_ENTRIES", 1024),
+        }
+    }
+}
+
+impl ConsentConfig {
+    fn from_env() -> Self {
+        Self {
+            required: env_or("CONSENT_REQUIRED", false),
+        }
+    }
+}
+
+impl ResidencyConfig {
+    fn from_env() -> Self {
+        Self {
+            claim: var("RESIDENCY_CLAIM")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "residency".to_string()),
+            regions: env_json::<HashMap<String, ResidencyRegion>>("RESIDENCY_REGIONS")
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(residency, region)| (residency.to_ascii_lowercase(), region))
+                .collect(),
+        }
+    }
+}
+
+impl DefaultsConfig {
+    fn from_env() -> Self {
+        Self {
+            tool_args: env_json("TOOL_DEFAULT_ARGS").unwrap_or_default(),
+        }
+    }
+}
+
+impl ProfileConfig {
+    fn from_env() -> Self {
+        Self {
+            units_claim: var("PROFILE_UNITS_CLAIM")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "units".to_string()),
+        }
+    }
+}
+
+impl PaginationConfig {
+    fn from_env() -> Self {
+        Self {
+            secret: var("PAGINATION_SECRET").ok().filter(|v| !v.is_empty()),
+        }
+    }
+}
+
+impl MetricsConfig {
+    fn from_env() -> Self {
+        Self {
+            include_in_response: env_or("RESPONSE_METRICS", false),
+            output_token_alert_threshold: env_or("OUTPUT_TOKEN_ALERT_THRESHOLD", 8_000),
+        }
+    }
+}
+
+impl ConcurrencyConfig {
+    fn from_env() -> Self {
+        Self {
+            tool_limits: parse_tool_limits(&var("TOOL_CONCURRENCY_LIMITS").unwrap_or_default()),
+            default_limit: env_or("TOOL_CONCURRENCY_DEFAULT", 0),
+            queue_timeout: Duration::from_millis(env_or("TOOL_QUEUE_TIMEOUT_MS", 2_000)),
+            max_in_flight: env_or("MAX_IN_FLIGHT", 0),
+        }
+    }
+}
+
+impl TimeoutConfig {
+    fn from_env() -> Self {
+        Self {
+            upstream: Duration::from_millis(env_or("UPSTREAM_TIMEOUT_MS", 30_000)),
+            safety_margin: Duration::from_millis(env_or("DEADLINE_SAFETY_MARGIN_MS", 500)),
+        }
+    }
+}
+
+impl HttpConfig {
+    fn from_env() -> Self {
+        Self {
+            vcr_mode: var("HTTP_VCR_MODE").map_or(VcrMode::Off, |raw| {
+                VcrMode::parse(&raw).unwrap_or_else(|| {
+                    warn!(value = %raw, "Ignoring unknown HTTP_VCR_MODE");
+                    VcrMode::Off
+                })
+            }),
+            vcr_dir: var("HTTP_VCR_DIR").unwrap_or_else(|_| "tests/fixtures/http".to_string()),
+            egress_allowlist: env_list("EGRESS_ALLOWLIST"),
+            egress_denylist: env_list("EGRESS_DENYLIST"),
+            egress_allow_private: env_or("EGRESS_ALLOW_PRIVATE", false),
+            hedging: env_or("HTTP_HEDGING", false),
+            hedge_min_delay: Duration::from_millis(env_or("HTTP_HEDGE_MIN_DELAY_MS", 250)),
+            dns_cache: env_or("DNS_CACHE", true),
+            dns_cache_ttl: Duration::from_secs(env_or("DNS_CACHE_TTL_SECS", 60)),
+            dns_cache_size: env_or("DNS_CACHE_SIZE", 64),
+            faults: FaultProfile {
+                delay: Duration::from_millis(env_or("HTTP_FAULT_DELAY_MS", 2_000)),
+                seed: env_or("HTTP_FAULT_SEED", 0),
+                ..var("HTTP_FAULTS").map_or_else(
+                    |_| FaultProfile::default(),
+                    |raw| {
+                        FaultProfile::parse(&raw).unwrap_or_else(|e| {
+                            warn!(value = %raw, error = %e, "Ignoring invalid HTTP_FAULTS");
+                            FaultProfile::default()
+                        })
+                    },
+                )
+            },
+            keepalive_ping: Duration::from_secs(env_or("HTTP_KEEPALIVE_PING_SECS", 0)),
+            client_cert_secret_arn: var("HTTP_CLIENT_CERT_SECRET_ARN")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            ca_bundle_secret_arn: var("HTTP_CA_BUNDLE_SECRET_ARN")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            proxy_url: var("HTTP_PROXY_URL").ok().filter(|v| !v.trim().is_empty()),
+            no_proxy: env_list("HTTP_NO_PROXY"),
+        }
+    }
+}
+
+impl AuthConfig {
+    fn from_env() -> Self {
+        Self {
+            policy: ToolPolicy::parse(&var("TOOL_SCOPE_POLICY").unwrap_or_default())
+                .with_admin_scopes(env_list("ADMIN_SCOPES")),
+        }
+    }
+}
+
+impl AdminConfig {
+    fn from_env() -> Self {
+        Self {
+            disabled_tools: env_list("DISABLED_TOOLS"),
+        }
+    }
+}
+
+impl InterceptorConfig {
+    fn from_env() -> Self {
+        Self {
+            passthrough: env_or("PASSTHROUGH_MODE", false),
+            session_headers: env_list_or(
+                "SESSION_ID_HEADERS",
+                "X-Amzn-Bedrock-AgentCore-Runtime-Session-Id,Mcp-Session-Id",
+            ),
+            body_limits: BodyLimits {
+                max_bytes: env_or("INTERCEPTOR_MAX_BODY_BYTES", 1024 * 1024),
+                max_depth: env_or("INTERCEPTOR_MAX_BODY_DEPTH", 32),
+            },
+        }
+    }
+}
+
+impl IdentityConfig {
+    fn from_env() -> Self {
+        Self {
+            secret_arn: var("IDENTITY_SECRET_ARN")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            secret: var("IDENTITY_CONTEXT_SECRET")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            required: env_or("IDENTITY_CONTEXT_REQUIRED", false),
+            ttl: Duration::from_secs(env_or("IDENTITY_CONTEXT_TTL_SECS", 300)),
+            key_refresh: Duration::from_secs(env_or("IDENTITY_KEY_REFRESH_SECS", 300)),
+        }
+    }
+}
+
+impl CryptoConfig {
+    fn from_env() -> Self {
+        Self {
+            kms_key_id: var("ARG_ENCRYPTION_KMS_KEY_ID")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            data_key_ttl: Duration::from_secs(env_or("ARG_ENCRYPTION_DATA_KEY_TTL_SECS", 300)),
+        }
+    }
+}
+
+impl SecretScanConfig {
+    fn from_env() -> Self {
+        Self {
+            scan_responses: env_or("RESPONSE_SECRET_SCAN", true),
+        }
+    }
+}
+
+impl CompressionConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("RESPONSE_COMPRESSION", false),
+            min_bytes: env_or("RESPONSE_COMPRESSION_MIN_BYTES", 8_192),
+        }
+    }
+}
+
+impl ContentConfig {
+    fn from_env() -> Self {
+        Self {
+            max_binary_bytes: env_or("MAX_BINARY_CONTENT_BYTES", 1_048_576),
+        }
+    }
+}
+
+impl DeprecationConfig {
+    fn from_env() -> Self {
+        Self {
+            notices: env_or("DEPRECATION_NOTICES", false),
+        }
+    }
+}
+
+impl SamplingConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("MCP_SAMPLING", true),
+            timeout: Duration::from_millis(env_or("MCP_SAMPLING_TIMEOUT_MS", 10_000)),
+            max_tokens: env_or("MCP_SAMPLING_MAX_TOKENS", 300),
+        }
+    }
+}
+
+impl RootsConfig {
+    fn from_env() -> Self {
+        Self {
+            timeout: Duration::from_millis(env_or("MCP_ROOTS_TIMEOUT_MS", 5_000)),
+            cache_ttl: Duration::from_secs(env_or("MCP_ROOTS_CACHE_TTL_SECS", 3_600)),
+        }
+    }
+}
+
+impl AuditConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("INVOCATION_AUDIT", false),
+            table: var("INVOCATION_AUDIT_TABLE")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            retention: Duration::from_hours(env_or("INVOCATION_AUDIT_RETENTION_HOURS", 168)),
+            max_entries: env_or("INVOCATION_AUDIT_MAX_ENTRIES", 256),
+        }
+    }
+}
+
+impl ShapesConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("RESPONSE_SHAPE_TRACKING", true),
+            table: var("RESPONSE_SHAPES_TABLE")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            version: var("DEPLOYMENT_VERSION")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string()),
+        }
+    }
+}
+
+impl TenantConfig {
+    fn from_env() -> Self {
+        Self {
+            claim: var("TENANT_CLAIM")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "tid".to_string()),
+            overlays: env_json("TENANT_OVERLAYS").unwrap_or_default(),
+            cache_ttl: Duration::from_secs(env_or("TENANT_CACHE_TTL_SECS", 300)),
+        }
+    }
+}
+
+impl UsageConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("USAGE_ACCOUNTING", true),
+            table: var("USAGE_TABLE").ok().filter(|v| !v.trim().is_empty()),
+            flush_interval: Duration::from_secs(env_or("USAGE_FLUSH_INTERVAL_SECS", 60)),
+        }
+    }
+}
+
+impl WebhookConfig {
+    fn from_env() -> Self {
+        Self {
+            urls: env_list("WEBHOOK_URLS"),
+            secret: var("WEBHOOK_SECRET").ok().filter(|v| !v.trim().is_empty()),
+            max_attempts: env_or("WEBHOOK_MAX_ATTEMPTS", 5),
+            failure_spike_threshold: env_or("WEBHOOK_FAILURE_SPIKE_THRESHOLD", 5),
+            failure_spike_window: Duration::from_secs(env_or(
+                "WEBHOOK_FAILURE_SPIKE_WINDOW_SECS",
+                60,
+            )),
+        }
+    }
+}
+
+impl EffectJournalConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("EFFECT_JOURNAL", true),
+            table: var("EFFECT_JOURNAL_TABLE")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            ttl: Duration::from_secs(env_or("EFFECT_JOURNAL_TTL_SECS", 86_400)),
+        }
+    }
+}
+
+impl DryRunConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("DRY_RUN", false),
+        }
+    }
+}
+
+impl ConfirmationConfig {
+    fn from_env() -> Self {
+        Self {
+            secret: var("CONFIRMATION_SECRET").ok().filter(|v| !v.is_empty()),
+            ttl: Duration::from_secs(env_or("CONFIRMATION_TTL_SECS", 300)),
+            table: var("CONFIRMATION_TABLE")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+        }
+    }
+}
+
+impl ProvenanceConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("RESPONSE_SOURCES", true),
+        }
+    }
+}
+
+impl SessionCacheConfig {
+    fn from_env() -> Self {
+        Self {
+            enabled: env_or("SESSION_CACHE", true),
+            ttl: Duration::from_secs(env_or("SESSION_CACHE_TTL_SECS", 600)),
+            max_entries: env_or("SESSION_CACHE_MAX_ENTRIES", 512),
+        }
+    }
+}
+
+impl LoggingConfig {
+    fn from_env() -> Self {
+        Self {
+            filter: var("LOG_FILTER")
+                .or_else(|_| var("RUST_LOG"))
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        }
+    }
+}
+
+impl TraceCaptureConfig {
+    fn from_env() -> Self {
+        Self {
+            bucket: var("TRACE_CAPTURE_BUCKET")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            prefix: var("TRACE_CAPTURE_PREFIX").unwrap_or_else(|_| "traces/".to_string()),
+        }
+    }
+}
+
+impl CodecConfig {
+    fn from_env() -> Self {
+        Self {
+            binary_payloads: env_or("BINARY_PAYLOADS", false),
+        }
+    }
+}
+
+impl AsyncCallsConfig {
+    fn from_env() -> Self {
+        Self {
+            max_attempts: env_or("ASYNC_MAX_ATTEMPTS", 3_u32).max(1),
+            table: var("FAILED_CALLS_TABLE")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            topic_arn: var("FAILED_CALLS_TOPIC_ARN")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            retention: Duration::from_hours(env_or("FAILED_CALLS_RETENTION_HOURS", 336)),
+            max_entries: env_or("FAILED_CALLS_MAX_ENTRIES", 256),
+        }
+    }
+}
+
+/// Reads and parses an environment variable, falling back to `default`.
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    match var(key) {
+        Ok(raw) => raw.trim().parse().unwrap_or_else(|_| {
+            warn!(key, value = %raw, "Ignoring unparsable configuration value");
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// Reads and deserializes a JSON environment variable, warning if it is invalid.
+fn env_json<T: serde::de::DeserializeOwned>(key: &str) -> Option<T> {
+    let raw = var(key).ok().filter(|v| !v.trim().is_empty())?;
+    serde_json::from_str(&raw)
+        .inspect_err(|e| warn!(key, error = %e, "Ignoring invalid JSON configuration value"))
+        .ok()
+}
+
+/// Reads a comma-separated environment variable into a list, skipping blanks.
+fn env_list(key: &str) -> Vec<String> {
+    var(key)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Reads a comma-separated environment variable, or the comma-separated
+/// `default` if it is unset or empty.
+fn env_list_or(key: &str, default: &str) -> Vec<String> {
+    let list = env_list(key);
+    if list.is_empty() {
+        default.split(',').map(String::from).collect()
+    } else {
+        list
+    }
+}
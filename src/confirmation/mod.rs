@@ -0,0 +1,335 @@
+//! Two-phase confirmation of calls to destructive tools.
+//!
+//! Tools marked `destructive` in their descriptor, such as `start_workflow`,
+//! only act once the caller has confirmed the exact call. A call without a
+//! `confirmation_token` runs as a dry run (see [`crate::dry_run`]): it is
+//! checked like a real call but performs nothing, and its response carries
+//! `_meta.confirmation` with a token, a human-readable summary of what the
+//! call would do and when the token expires. The agent shows the summary to
+//! the user and, once they agree, repeats the call with the same arguments
+//! and the token as `confirmation_token`, which performs it.
+//!
+//! Tokens are HMAC-signed with `CONFIRMATION_SECRET` and bound to the tool,
+//! the caller's user ID and the call's arguments, so they confirm nothing
+//! else and no one else. Without a secret a random key is generated per
+//! container, so tokens only work in the container that issued them; if no
+//! key can be generated, calls to destructive tools are refused. Tokens
+//! expire after `CONFIRMATION_TTL_SECS` and are single-use: one is spent
+//! when its call starts, in the container and, when `CONFIRMATION_TABLE`
+//! names a `DynamoDB` table, there too, where a conditional write lets only
+//! one container spend it. A token that cannot be recorded as spent is
+//! refused rather than risk the call running twice.
+
+use crate::auth::AUTH_TOKEN_ARG;
+use crate::config::config;
+use crate::context::{SESSION_ID_ARG, ToolContext};
+use crate::identity::{IDENTITY_CONTEXT_ARG, USER_ID_ARG, USER_NAME_ARG};
+use crate::metrics::META_KEY;
+use crate::models::error::AppError;
+use crate::registry::ToolDescriptor;
+use crate::residency;
+use aws_lc_rs::{digest, hmac, rand};
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use lambda_runtime::tracing::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// Tool argument carrying the token that confirms a call
+pub const CONFIRMATION_TOKEN_ARG: &str = "confirmation_token";
+
+/// Partition key of the confirmation table
+pub const TOKEN_ID_KEY: &str = "token_id";
+
+/// Arguments a confirmation is not bound to: the caller is bound separately,
+/// and tokens and sessions may change between the two calls
+const UNBOUND_ARGS: &[&str] = &[
+    AUTH_TOKEN_ARG,
+    IDENTITY_CONTEXT_ARG,
+    SESSION_ID_ARG,
+    USER_ID_ARG,
+    USER_NAME_ARG,
+];
+
+/// Spent tokens kept in a container before expired ones are purged
+const MAX_SPENT: usize = 1024;
+
+static SIGNING_KEY: LazyLock<Option<hmac::Key>> = LazyLock::new(|| {
+    if let Some(secret) = config().confirmation.secret.as_deref() {
+        return Some(hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()));
+    }
+    warn!("CONFIRMATION_SECRET is not set; confirmation tokens only work in the issuing container");
+    let mut secret = [0u8; 32];
+    if rand::fill(&mut secret).is_err() {
+        error!("Failed to generate random confirmation key; destructive tools are disabled");
+        return None;
+    }
+    Some(hmac::Key::new(hmac::HMAC_SHA256, &secret))
+});
+
+/// IDs of tokens spent in this container, with when they expire
+static SPENT: LazyLock<Mutex<HashMap<String, i64>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Decoded contents of a confirmation token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TokenClaims {
+    /// Token ID, so the token can be spent once
+    #[serde(rename = "i")]
+    id: String,
+    /// Registered name of the tool
+    #[serde(rename = "t")]
+    tool: String,
+    /// Digest of the caller and arguments
+    #[serde(rename = "d")]
+    call: String,
+    /// Expiry, in seconds since the Unix epoch
+    #[serde(rename = "e")]
+    exp: i64,
+}
+
+/// A token confirming a call, with what the call would do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Confirmation {
+    /// Pass back as `confirmation_token` to perform the call
+    pub token: String,
+    /// What the call would do, e.g. "Start a Step Functions execution: arn:..."
+    pub summary: String,
+    /// When the token expires (RFC 3339)
+    pub expires_at: String,
+}
+
+/// A call to a destructive tool that has not been confirmed, and so runs as
+/// a dry run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingConfirmation {
+    tool: &'static str,
+    call: String,
+}
+
+impl PendingConfirmation {
+    /// Issues a token confirming this call, summarized from the `plan` in
+    /// `response`, the response of its dry run; `None` if no token ID can be
+    /// generated or there is no signing key.
+    #[must_use]
+    pub fn issue(&self, response: &Value) -> Option<Confirmation> {
+        let ttl = i64::try_from(config().confirmation.ttl.as_secs()).unwrap_or(i64::MAX);
+        let exp = Utc::now().timestamp().saturating_add(ttl);
+        let claims = TokenClaims {
+            id: new_token_id()?,
+            tool: self.tool.to_string(),
+            call: self.call.clone(),
+            exp,
+        };
+        Some(Confirmation {
+            token: sign(&claims)?,
+            summary: summary(self.tool, response),
+            expires_at: DateTime::from_timestamp(exp, 0)
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Attaches `confirmation` to an object response under
+/// `_meta.confirmation`.
+///
+/// Non-object responses are left unchanged.
+pub fn attach(response: &mut Value, confirmation: &Confirmation) {
+    let Value::Object(fields) = response else {
+        return;
+    };
+    let meta = fields
+        .entry(META_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(meta) = meta {
+        meta.insert("confirmation".to_string(), json!(confirmation));
+    }
+}
+
+/// Takes the `confirmation_token` argument out of `args` for a call to
+/// `tool`.
+///
+/// Tools that are not destructive leave the argument to strict argument
+/// parsing.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the argument is not a string.
+pub fn take(tool: &ToolDescriptor, args: &mut Value) -> Result<Option<String>, AppError> {
+    if !tool.destructive() {
+        return Ok(None);
+    }
+    match args
+        .as_object_mut()
+        .and_then(|args| args.remove(CONFIRMATION_TOKEN_ARG))
+    {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(token)) => Ok(Some(token.trim().to_string()).filter(|t| !t.is_empty())),
+        Some(_) => Err(AppError::ValidationError(format!(
+            "{CONFIRMATION_TOKEN_ARG} must be a string"
+        ))),
+    }
+}
+
+/// Checks the confirmation of a call to `tool` with `args` by the caller
+/// `context` describes, spending `token` if it confirms the call.
+///
+/// Returns the pending confirmation if the call must run as a dry run: it
+/// has no token, or is a dry run anyway. Calls to tools that are not
+/// destructive need no confirmation.
+///
+/// # Errors
+///
+/// Returns `AppError::ConfirmationInvalid` if there is no signing key to
+/// confirm calls with, or `token` was not issued for this call, has expired
+/// or was already spent, or cannot be recorded as spent.
+pub async fn check(
+    tool: &ToolDescriptor,
+    token: Option<&str>,
+    args: &Value,
+    context: &ToolContext,
+) -> Result<Option<PendingConfirmation>, AppError> {
+    if !tool.destructive() {
+        return Ok(None);
+    }
+    signing_key()?;
+    let pending = PendingConfirmation {
+        tool: tool.name,
+        call: call_digest(context.user_id.as_deref(), args),
+    };
+    let Some(token) = token.filter(|_| !context.dry_run) else {
+        return Ok(Some(pending));
+    };
+    let claims = verify(token)?;
+    if claims.tool != pending.tool || claims.call != pending.call {
+        return Err(AppError::ConfirmationInvalid(
+            "The token confirms a different call; repeat the confirmed call with the same arguments"
+                .to_string(),
+        ));
+    }
+    if claims.exp <= Utc::now().timestamp() {
+        return Err(AppError::ConfirmationInvalid(
+            "The token has expired".to_string(),
+        ));
+    }
+    spend(&claims).await?;
+    info!(tool = tool.name, "Running confirmed call");
+    Ok(None)
+}
+
+/// A one-line description of the call that returned `response`, e.g.
+/// "Start a Step Functions execution: arn:aws:states:...".
+fn summary(tool: &str, response: &Value) -> String {
+    let plan = response.get("plan");
+    let text = |field: &str| {
+        plan.and_then(|plan| plan.get(field))
+            .and_then(Value::as_str)
+    };
+    match (text("action"), text("target")) {
+        (Some(action), Some(target)) => format!("{action}: {target}"),
+        (Some(action), None) => action.to_string(),
+        _ => format!("Call {tool}"),
+    }
+}
+
+/// Digest binding a token to the caller and the arguments of their call.
+fn call_digest(user_id: Option<&str>, args: &Value) -> String {
+    let mut args = args.clone();
+    if let Some(args) = args.as_object_mut() {
+        for name in UNBOUND_ARGS {
+            args.remove(*name);
+        }
+    }
+    let caller = user_id.unwrap_or_default();
+    let hash = digest::digest(&digest::SHA256, format!("{caller}\0{args}").as_bytes());
+    URL_SAFE_NO_PAD.encode(hash.as_ref())
+}
+
+/// The key tokens are signed with; without one, destructive calls are
+/// refused rather than confirmed with a guessable key.
+fn signing_key() -> Result<&'static hmac::Key, AppError> {
+    SIGNING_KEY.as_ref().ok_or_else(|| {
+        AppError::ConfirmationInvalid(
+            "Destructive tools are disabled: no confirmation signing key".to_string(),
+        )
+    })
+}
+
+fn sign(claims: &TokenClaims) -> Option<String> {
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap_or_default());
+    let tag = hmac::sign(signing_key().ok()?, payload.as_bytes());
+    Some(format!(
+        "{payload}.{}",
+        URL_SAFE_NO_PAD.encode(tag.as_ref())
+    ))
+}
+
+fn verify(token: &str) -> Result<TokenClaims, AppError> {
+    let invalid = || {
+        AppError::ConfirmationInvalid("The token is malformed or was not issued here".to_string())
+    };
+    let (payload, tag) = token.split_once('.').ok_or_else(invalid)?;
+    let tag = URL_SAFE_NO_PAD.decode(tag).map_err(|_| invalid())?;
+    hmac::verify(signing_key()?, payload.as_bytes(), &tag).map_err(|_| invalid())?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+    serde_json::from_slice(&bytes).map_err(|_| invalid())
+}
+
+/// Records the token as spent, unless it already was.
+async fn spend(claims: &TokenClaims) -> Result<(), AppError> {
+    let spent_before = || AppError::ConfirmationInvalid("The token was already used".to_string());
+    {
+        let mut spent = SPENT.lock().unwrap_or_else(PoisonError::into_inner);
+        if spent.len() >= MAX_SPENT {
+            let now = Utc::now().timestamp();
+            spent.retain(|_, exp| *exp > now);
+        }
+        if spent.insert(claims.id.clone(), claims.exp).is_some() {
+            return Err(spent_before());
+        }
+    }
+    let Some(table) = config().confirmation.table.clone() else {
+        return Ok(());
+    };
+    let result = residency::dynamodb_client(None)
+        .await
+        .put_item()
+        .table_name(table)
+        .item(TOKEN_ID_KEY, AttributeValue::S(claims.id.clone()))
+        .item("tool", AttributeValue::S(claims.tool.clone()))
+        .item("expires_at", AttributeValue::N(claims.exp.to_string()))
+        .condition_expression("attribute_not_exists(#id)")
+        .expression_attribute_names("#id", TOKEN_ID_KEY)
+        .send()
+        .await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(e)
+            if e.as_service_error()
+                .is_some_and(PutItemError::is_conditional_check_failed_exception) =>
+        {
+            Err(spent_before())
+        }
+        Err(e) => {
+            warn!(error = %e, "Refusing a confirmation token that could not be recorded as spent");
+            Err(AppError::ConfirmationInvalid(format!(
+                "The token could not be recorded as used: {e}"
+            )))
+        }
+    }
+}
+
+fn new_token_id() -> Option<String> {
+    let mut bytes = [0u8; 16];
+    if rand::fill(&mut bytes).is_err() {
+        warn!("Failed to generate random confirmation token ID");
+        return None;
+    }
+    Some(URL_SAFE_NO_PAD.encode(bytes))
+}
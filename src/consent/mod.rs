@@ -0,0 +1,117 @@
+//! Consent to the use of personal data.
+//!
+//! With `CONSENT_REQUIRED` enabled, a caller's personal data is only used
+//! once they have consented. Until then the routing layer removes their
+//! name and user ID from every tool's arguments, so
+//! `get_personalized_greeting` greets them anonymously; their stored
+//! preferences are not used as defaults (see [`crate::defaults`]); and
+//! nothing about them is remembered or recalled (see [`crate::memory`]).
+//! Their verified identity is still used to authorize, meter and audit the
+//! call, as it is for every caller.
+//!
+//! Callers grant or withdraw consent with the `record_consent` tool. It is
+//! stored as `consent_granted_at` on the caller's item in the preferences
+//! table when `USER_PREFERENCES_TABLE` is set (see [`crate::preferences`]),
+//! in the region of their data residency (see [`crate::residency`]),
+//! and kept in the container for a day, where it is the only record without
+//! a table. A caller whose consent cannot be read is treated as not having
+//! consented. Without `CONSENT_REQUIRED` every caller is treated as having
+//! consented.
+
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::context::ToolContext;
+use crate::identity::{USER_ID_ARG, USER_NAME_ARG};
+use crate::models::error::AppError;
+use crate::preferences::{self, PREFERENCES_CACHE, USER_ID_KEY, UserPreferences};
+use crate::residency::{self, Store};
+use aws_sdk_dynamodb::types::{AttributeValue, ReturnValue};
+use chrono::{DateTime, Utc};
+use lambda_runtime::tracing::{debug, warn};
+use serde_json::Value;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Tool arguments carrying personal data, removed without consent
+pub const PERSONAL_ARGS: &[&str] = &[USER_ID_ARG, USER_NAME_ARG];
+
+/// Consent recorded in this container per user ID: when it was granted, or
+/// `None` if it was withdrawn
+static CONSENTS: LazyLock<CacheStore<Option<DateTime<Utc>>>> =
+    LazyLock::new(|| CacheStore::new("consent", Duration::from_hours(24), 1024));
+
+/// Whether the caller `context` describes consented to their personal data
+/// being used; always true unless `CONSENT_REQUIRED` is set, and false for
+/// anonymous callers.
+pub async fn granted(context: &ToolContext) -> bool {
+    if !config().consent.required {
+        return true;
+    }
+    let Some(user_id) = context.user_id.as_deref() else {
+        return false;
+    };
+    if let Some(granted_at) = CONSENTS.get(user_id) {
+        return granted_at.is_some();
+    }
+    match preferences::load(user_id, context.residency.as_deref()).await {
+        Ok(preferences) => preferences.consent_granted_at.is_some(),
+        Err(e) => {
+            warn!(error = %e, "Treating caller as not consenting, consent could not be read");
+            false
+        }
+    }
+}
+
+/// Removes the caller's personal data from `args` and `context`, keeping the
+/// user ID in `context` to authorize, meter and audit the call.
+pub fn anonymize(args: &mut Value, context: &mut ToolContext) {
+    if let Some(args) = args.as_object_mut() {
+        for name in PERSONAL_ARGS {
+            args.remove(*name);
+        }
+    }
+    context.user_name = None;
+    context.consent = false;
+}
+
+/// Records that `user_id`, with data `residency`, granted consent, or
+/// withdrew it, returning when consent was granted.
+///
+/// # Errors
+///
+/// Returns `AppError::PreferencesError` if the preferences table cannot be
+/// updated, or `AppError::ResidencyDenied` if it is outside the region of
+/// `residency`.
+pub async fn record(
+    user_id: &str,
+    residency: Option<&str>,
+    granted: bool,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let granted_at = granted.then(Utc::now);
+    if let Some(placement) = residency::place_write(Store::Preferences, residency)? {
+        debug!(table = %placement.table, region = ?placement.region, granted, "Recording consent");
+        let update = residency::dynamodb_client(placement.region.as_deref())
+            .await
+            .update_item()
+            .table_name(&placement.table)
+            .key(USER_ID_KEY, AttributeValue::S(user_id.to_string()))
+            .return_values(ReturnValue::AllNew);
+        let update = match granted_at {
+            Some(at) => update
+                .update_expression("SET consent_granted_at = :at")
+                .expression_attribute_values(":at", AttributeValue::S(at.to_rfc3339())),
+            None => update.update_expression("REMOVE consent_granted_at"),
+        };
+        let output = update
+            .send()
+            .await
+            .map_err(|e| AppError::PreferencesError(format!("DynamoDB UpdateItem failed: {e}")))?;
+        let preferences = output
+            .attributes()
+            .map(UserPreferences::from_item)
+            .unwrap_or_default();
+        PREFERENCES_CACHE.insert(user_id, preferences);
+    }
+    CONSENTS.insert(user_id, granted_at);
+    Ok(granted_at)
+}
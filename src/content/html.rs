@@ -0,0 +1,794 @@
+//! Minimal HTML tokenizer and markdown/plaintext renderer.
+//!
+//! This is intentionally not a spec-compliant HTML parser. It handles the
+//! well-formed-enough markup served by typical web pages and focuses on
+//! producing compact, readable text for LLM consumption.
+
+use std::fmt::Write;
+
+/// Elements whose entire subtree is dropped as boilerplate or non-content.
+const SKIPPED_ELEMENTS: [&str; 17] = [
+    "script", "style", "noscript", "template", "head", "nav", "header", "footer", "aside", "form",
+    "iframe", "svg", "canvas", "button", "select", "textarea", "dialog",
+];
+
+/// ARIA roles that mark navigation/chrome rather than page content.
+const SKIPPED_ROLES: [&str; 5] = [
+    "navigation",
+    "banner",
+    "contentinfo",
+    "complementary",
+    "search",
+];
+
+/// Elements that never have a closing tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is raw text and must not be tokenized as markup.
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["script", "style", "textarea", "title"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Token {
+    Text(String),
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    End(String),
+}
+
+impl Token {
+    fn is_start_of(&self, tag: &str) -> bool {
+        matches!(self, Self::Start { name, .. } if name == tag)
+    }
+}
+
+/// Splits an HTML document into text, start-tag and end-tag tokens.
+pub(super) fn tokenize(html: &str) -> Vec<Token> {
+    let chars: Vec<char> = html.chars().collect();
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] != '<' {
+            text.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        // Comments, doctype and processing instructions are dropped
+        if starts_with_at(&chars, i, "<!--") {
+            i = find_from(&chars, i + 4, "-->").map_or(chars.len(), |end| end + 3);
+            continue;
+        }
+        if matches!(chars.get(i + 1), Some('!' | '?')) {
+            i = find_char_from(&chars, i, '>').map_or(chars.len(), |end| end + 1);
+            continue;
+        }
+
+        let (token, next) = match parse_tag(&chars, i) {
+            Tag::Complete(token, next) => (token, next),
+            // A stray '<' that does not start a tag is literal text
+            Tag::Literal => {
+                text.push('<');
+                i += 1;
+                continue;
+            }
+            // Later '<'s could only run to the end as well, so rather than
+            // rescanning from each of them the rest is kept as text
+            Tag::Unclosed => {
+                text.extend(chars.get(i..).unwrap_or_default());
+                break;
+            }
+        };
+
+        flush_text(&mut text, &mut tokens);
+        i = next;
+
+        if let Token::Start {
+            name,
+            self_closing: false,
+            ..
+        } = &token
+            && RAW_TEXT_ELEMENTS.contains(&name.as_str())
+        {
+            let closing = format!("</{name}");
+            let end = find_from_ignore_case(&chars, i, &closing).unwrap_or(chars.len());
+            let raw: String = chars.get(i..end).unwrap_or_default().iter().collect();
+            let name = name.clone();
+            tokens.push(token);
+            tokens.push(Token::Text(raw));
+            tokens.push(Token::End(name));
+            i = find_char_from(&chars, end, '>').map_or(chars.len(), |close| close + 1);
+            continue;
+        }
+
+        tokens.push(token);
+    }
+
+    flush_text(&mut text, &mut tokens);
+    tokens
+}
+
+fn flush_text(text: &mut String, tokens: &mut Vec<Token>) {
+    if !text.is_empty() {
+        tokens.push(Token::Text(decode_entities(text)));
+        text.clear();
+    }
+}
+
+/// What [`parse_tag`] found at a `<`.
+enum Tag {
+    /// A tag, and the index just past its closing `>`
+    Complete(Token, usize),
+    /// A `<` that does not start a tag, as in `a < b`
+    Literal,
+    /// A tag still open at the end of the input
+    Unclosed,
+}
+
+/// Parses a start or end tag beginning at `start` (which points at `<`).
+fn parse_tag(chars: &[char], start: usize) -> Tag {
+    let mut i = start + 1;
+    let is_end = chars.get(i) == Some(&'/');
+    if is_end {
+        i += 1;
+    }
+
+    if !chars.get(i).is_some_and(char::is_ascii_alphabetic) {
+        return Tag::Literal;
+    }
+
+    let name = read_while(chars, &mut i, |c| c.is_ascii_alphanumeric() || c == '-');
+    let name = name.to_ascii_lowercase();
+
+    if is_end {
+        return find_char_from(chars, i, '>').map_or(Tag::Unclosed, |close| {
+            Tag::Complete(Token::End(name), close + 1)
+        });
+    }
+
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+    loop {
+        skip_whitespace(chars, &mut i);
+        match chars.get(i) {
+            None => return Tag::Unclosed,
+            Some('>') => {
+                i += 1;
+                break;
+            }
+            Some('/') => {
+                self_closing = true;
+                i += 1;
+            }
+            Some(_) => {
+                let attr_name = read_while(chars, &mut i, |c| {
+                    !c.is_whitespace() && c != '=' && c != '>' && c != '/'
+                });
+                if attr_name.is_empty() {
+                    i += 1;
+                    continue;
+                }
+                skip_whitespace(chars, &mut i);
+                let value = if chars.get(i) == Some(&'=') {
+                    i += 1;
+                    skip_whitespace(chars, &mut i);
+                    read_attr_value(chars, &mut i)
+                } else {
+                    String::new()
+                };
+                attrs.push((attr_name.to_ascii_lowercase(), decode_entities(&value)));
+            }
+        }
+    }
+
+    let self_closing = self_closing || VOID_ELEMENTS.contains(&name.as_str());
+    Tag::Complete(
+        Token::Start {
+            name,
+            attrs,
+            self_closing,
+        },
+        i,
+    )
+}
+
+fn read_attr_value(chars: &[char], i: &mut usize) -> String {
+    match chars.get(*i) {
+        Some(&quote @ ('"' | '\'')) => {
+            *i += 1;
+            let value = read_while(chars, i, |c| c != quote);
+            *i += 1;
+            value
+        }
+        _ => read_while(chars, i, |c| !c.is_whitespace() && c != '>'),
+    }
+}
+
+fn read_while(chars: &[char], i: &mut usize, predicate: impl Fn(char) -> bool) -> String {
+    let mut value = String::new();
+    while let Some(&c) = chars.get(*i) {
+        if !predicate(c) {
+            break;
+        }
+        value.push(c);
+        *i += 1;
+    }
+    value
+}
+
+fn skip_whitespace(chars: &[char], i: &mut usize) {
+    while chars.get(*i).is_some_and(|c| c.is_whitespace()) {
+        *i += 1;
+    }
+}
+
+fn starts_with_at(chars: &[char], at: usize, needle: &str) -> bool {
+    needle
+        .chars()
+        .enumerate()
+        .all(|(offset, c)| chars.get(at + offset) == Some(&c))
+}
+
+fn find_from(chars: &[char], from: usize, needle: &str) -> Option<usize> {
+    (from..chars.len()).find(|&at| starts_with_at(chars, at, needle))
+}
+
+fn find_from_ignore_case(chars: &[char], from: usize, needle: &str) -> Option<usize> {
+    (from..chars.len()).find(|&at| {
+        needle.chars().enumerate().all(|(offset, c)| {
+            chars
+                .get(at + offset)
+                .is_some_and(|h| h.eq_ignore_ascii_case(&c))
+        })
+    })
+}
+
+fn find_char_from(chars: &[char], from: usize, needle: char) -> Option<usize> {
+    (from..chars.len()).find(|&at| chars[at] == needle)
+}
+
+/// Decodes the named and numeric character references commonly found in web pages.
+#[must_use]
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((before, after)) = rest.split_once('&') {
+        decoded.push_str(before);
+        let entity = after
+            .split_once(';')
+            .filter(|(name, _)| name.len() <= 10 && !name.contains(char::is_whitespace));
+
+        if let Some((c, tail)) =
+            entity.and_then(|(name, tail)| resolve_entity(name).map(|c| (c, tail)))
+        {
+            decoded.push(c);
+            rest = tail;
+        } else {
+            decoded.push('&');
+            rest = after;
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn resolve_entity(name: &str) -> Option<char> {
+    if let Some(numeric) = name.strip_prefix('#') {
+        let code = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => numeric.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "middot" => '·',
+        "bull" => '•',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// Returns the document `<title>` text, if present.
+pub(super) fn extract_title(tokens: &[Token]) -> Option<String> {
+    let start = tokens.iter().position(|t| t.is_start_of("title"))?;
+    match tokens.get(start + 1) {
+        Some(Token::Text(title)) => {
+            let title = collapse_whitespace(&decode_entities(title));
+            (!title.is_empty()).then_some(title)
+        }
+        _ => None,
+    }
+}
+
+/// Narrows the token stream to the main content region when the page marks one.
+///
+/// Prefers `<main>`, then `<article>`; falls back to the whole document.
+pub(super) fn main_content(tokens: &[Token]) -> &[Token] {
+    ["main", "article"]
+        .iter()
+        .find_map(|tag| {
+            let start = tokens.iter().position(|t| t.is_start_of(tag))?;
+            let mut depth = 0usize;
+            for (offset, token) in tokens.iter().enumerate().skip(start) {
+                match token {
+                    Token::Start {
+                        name,
+                        self_closing: false,
+                        ..
+                    } if name == tag => depth += 1,
+                    Token::End(name) if name == tag => {
+                        depth = depth.saturating_sub(1);
+                        if depth == 0 {
+                            return tokens.get(start..=offset);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            tokens.get(start..)
+        })
+        .unwrap_or(tokens)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// An inline element whose opening marker may need to be rolled back if empty.
+struct OpenInline {
+    tag: String,
+    content_start: usize,
+    marker_len: usize,
+    closing: String,
+}
+
+struct ListState {
+    ordered: bool,
+    counter: usize,
+}
+
+/// Output flavour selected by the caller.
+#[derive(Clone, Copy)]
+struct Style {
+    markdown: bool,
+    preserve_links: bool,
+}
+
+/// Per-table bookkeeping for markdown header separators.
+#[derive(Default)]
+struct TableState {
+    cells: usize,
+    has_header: bool,
+    rows: usize,
+}
+
+/// Streaming renderer that turns tokens into markdown (or plain text).
+pub(super) struct Renderer {
+    style: Style,
+    out: String,
+    pending_space: bool,
+    skip_stack: Vec<String>,
+    inline_stack: Vec<OpenInline>,
+    lists: Vec<ListState>,
+    quote_depth: usize,
+    in_pre: bool,
+    table: TableState,
+}
+
+impl Renderer {
+    pub(super) const fn new(markdown: bool, preserve_links: bool) -> Self {
+        Self {
+            style: Style {
+                markdown,
+                preserve_links,
+            },
+            out: String::new(),
+            pending_space: false,
+            skip_stack: Vec::new(),
+            inline_stack: Vec::new(),
+            lists: Vec::new(),
+            quote_depth: 0,
+            in_pre: false,
+            table: TableState {
+                cells: 0,
+                has_header: false,
+                rows: 0,
+            },
+        }
+    }
+
+    pub(super) fn render(mut self, tokens: &[Token]) -> String {
+        for token in tokens {
+            if self.skipping(token) {
+                continue;
+            }
+            match token {
+                Token::Text(text) => self.text(text),
+                Token::Start {
+                    name,
+                    attrs,
+                    self_closing,
+                } => self.start(name, attrs, *self_closing),
+                Token::End(name) => self.end(name),
+            }
+        }
+        finalize(&self.out)
+    }
+
+    /// Tracks skipped subtrees; returns `true` when `token` must be ignored.
+    fn skipping(&mut self, token: &Token) -> bool {
+        if let Some(top) = self.skip_stack.last() {
+            match token {
+                Token::Start {
+                    name,
+                    self_closing: false,
+                    ..
+                } if name == top => self.skip_stack.push(name.clone()),
+                Token::End(name) if name == top => {
+                    self.skip_stack.pop();
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Token::Start {
+            name,
+            attrs,
+            self_closing: false,
+        } = token
+            && is_boilerplate(name, attrs)
+        {
+            self.skip_stack.push(name.clone());
+            return true;
+        }
+        false
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.in_pre {
+            self.out.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.pending_space = true;
+                continue;
+            }
+            if self.at_line_start() {
+                self.pending_space = false;
+                self.push_line_prefix();
+            } else if self.pending_space {
+                self.pending_space = false;
+                if !self.out.ends_with(char::is_whitespace) {
+                    self.out.push(' ');
+                }
+            }
+            self.out.push(c);
+        }
+    }
+
+    fn start(&mut self, name: &str, attrs: &[(String, String)], self_closing: bool) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.blank_line();
+                if self.style.markdown {
+                    let level = name.get(1..).and_then(|l| l.parse().ok()).unwrap_or(1);
+                    self.out.push_str(&"#".repeat(level));
+                    self.out.push(' ');
+                }
+            }
+            "p" | "div" | "section" | "article" | "main" | "figure" | "dl" | "table" => {
+                self.blank_line();
+            }
+            "ul" | "ol" => {
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+                self.lists.push(ListState {
+                    ordered: name == "ol",
+                    counter: 0,
+                });
+            }
+            "li" => self.list_item(),
+            "dt" | "dd" | "figcaption" => self.newline(),
+            "blockquote" => {
+                self.blank_line();
+                self.quote_depth += 1;
+            }
+            "pre" => {
+                self.blank_line();
+                if self.style.markdown {
+                    self.out.push_str("```\n");
+                }
+                self.in_pre = true;
+            }
+            "br" => {
+                self.out.push('\n');
+                self.pending_space = false;
+            }
+            "hr" => {
+                self.blank_line();
+                self.out.push_str("---");
+                self.blank_line();
+            }
+            "tr" => self.start_row(),
+            "td" | "th" => self.start_cell(name == "th"),
+            "img" => self.image(attrs),
+            "a" if !self_closing => self.open_link(attrs),
+            "strong" | "b" if !self_closing => self.open_inline(name, "**"),
+            "em" | "i" if !self_closing => self.open_inline(name, "_"),
+            "code" if !self.in_pre && !self_closing => self.open_inline(name, "`"),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "div" | "section" | "article"
+            | "main" | "figure" | "dl" | "table" => self.blank_line(),
+            "ul" | "ol" => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                } else {
+                    self.newline();
+                }
+            }
+            "blockquote" => {
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.blank_line();
+            }
+            "pre" => {
+                self.in_pre = false;
+                self.newline();
+                if self.style.markdown {
+                    self.out.push_str("```");
+                }
+                self.blank_line();
+            }
+            "tr" => self.end_row(),
+            "td" | "th" => {
+                if self.style.markdown {
+                    self.out.push_str(" |");
+                }
+                self.pending_space = false;
+            }
+            "a" | "strong" | "b" | "em" | "i" | "code" => self.close_inline(name),
+            _ => {}
+        }
+    }
+
+    fn list_item(&mut self) {
+        self.newline();
+        let depth = self.lists.len().saturating_sub(1);
+        let marker = self.lists.last_mut().map_or_else(
+            || "- ".to_string(),
+            |list| {
+                list.counter += 1;
+                if list.ordered {
+                    format!("{}. ", list.counter)
+                } else {
+                    "- ".to_string()
+                }
+            },
+        );
+        self.push_line_prefix();
+        self.out.push_str(&"  ".repeat(depth));
+        self.out.push_str(&marker);
+        self.pending_space = false;
+    }
+
+    fn start_row(&mut self) {
+        self.newline();
+        self.table.cells = 0;
+        self.table.has_header = false;
+        if self.style.markdown {
+            self.out.push('|');
+        }
+    }
+
+    fn start_cell(&mut self, header: bool) {
+        self.table.has_header |= header;
+        if self.style.markdown {
+            self.out.push(' ');
+        } else if self.table.cells > 0 {
+            self.out.push_str(" | ");
+        }
+        self.table.cells += 1;
+        self.pending_space = false;
+    }
+
+    fn end_row(&mut self) {
+        let table = &self.table;
+        if self.style.markdown && table.has_header && table.rows == 0 && table.cells > 0 {
+            self.out.push_str("\n|");
+            self.out.push_str(&" --- |".repeat(table.cells));
+        }
+        self.table.rows += 1;
+        self.newline();
+    }
+
+    fn image(&mut self, attrs: &[(String, String)]) {
+        let alt = attr(attrs, "alt")
+            .map(collapse_whitespace)
+            .unwrap_or_default();
+        if alt.is_empty() {
+            return;
+        }
+        self.flush_space();
+        match attr(attrs, "src").filter(|_| self.style.markdown && self.style.preserve_links) {
+            Some(src) => {
+                let _ = write!(self.out, "![{alt}]({src})");
+            }
+            None => {
+                let _ = write!(self.out, "[image: {alt}]");
+            }
+        }
+    }
+
+    fn open_link(&mut self, attrs: &[(String, String)]) {
+        let href = attr(attrs, "href")
+            .filter(|_| self.style.preserve_links)
+            .filter(|href| is_followable(href));
+
+        let Some(href) = href else {
+            // Still track the element so the matching end tag is balanced
+            self.inline_stack.push(OpenInline {
+                tag: "a".to_string(),
+                content_start: self.out.len(),
+                marker_len: 0,
+                closing: String::new(),
+            });
+            return;
+        };
+
+        let (open, closing) = if self.style.markdown {
+            ("[", format!("]({href})"))
+        } else {
+            ("", format!(" ({href})"))
+        };
+        self.open_with_marker("a", open, closing);
+    }
+
+    fn open_inline(&mut self, tag: &str, marker: &str) {
+        if !self.style.markdown {
+            return;
+        }
+        self.open_with_marker(tag, marker, marker.to_string());
+    }
+
+    fn open_with_marker(&mut self, tag: &str, open: &str, closing: String) {
+        self.flush_space();
+        if self.at_line_start() {
+            self.push_line_prefix();
+        }
+        self.out.push_str(open);
+        self.inline_stack.push(OpenInline {
+            tag: tag.to_string(),
+            content_start: self.out.len(),
+            marker_len: open.len(),
+            closing,
+        });
+    }
+
+    fn close_inline(&mut self, tag: &str) {
+        let Some(position) = self.inline_stack.iter().rposition(|open| open.tag == tag) else {
+            return;
+        };
+        let open = self.inline_stack.remove(position);
+        if self.out.len() == open.content_start {
+            // Nothing was written inside the element; drop the dangling opener
+            self.out
+                .truncate(open.content_start.saturating_sub(open.marker_len));
+            return;
+        }
+        self.out.push_str(&open.closing);
+    }
+
+    fn flush_space(&mut self) {
+        if self.pending_space && !self.at_line_start() && !self.out.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+        self.pending_space = false;
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn push_line_prefix(&mut self) {
+        if self.style.markdown && self.quote_depth > 0 {
+            self.out.push_str(&"> ".repeat(self.quote_depth));
+        }
+    }
+
+    fn newline(&mut self) {
+        trim_trailing_spaces(&mut self.out);
+        if !self.at_line_start() {
+            self.out.push('\n');
+        }
+        self.pending_space = false;
+    }
+
+    fn blank_line(&mut self) {
+        self.newline();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+}
+
+fn is_boilerplate(name: &str, attrs: &[(String, String)]) -> bool {
+    SKIPPED_ELEMENTS.contains(&name)
+        || attr(attrs, "role").is_some_and(|role| SKIPPED_ROLES.contains(&role))
+        || attr(attrs, "aria-hidden") == Some("true")
+        || attrs.iter().any(|(key, _)| key == "hidden")
+}
+
+fn is_followable(href: &str) -> bool {
+    let href = href.trim();
+    !href.is_empty()
+        && !href.starts_with('#')
+        && !href.to_ascii_lowercase().starts_with("javascript:")
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn trim_trailing_spaces(out: &mut String) {
+    let trimmed = out.trim_end_matches([' ', '\t']).len();
+    out.truncate(trimmed);
+}
+
+/// Normalizes line endings and collapses runs of blank lines.
+fn finalize(out: &str) -> String {
+    let mut result = String::with_capacity(out.len());
+    let mut blank_run = 0;
+    for line in out.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result.trim().to_string()
+}
@@ -0,0 +1,155 @@
+//! Content processing for fetched documents.
+//!
+//! Converts HTML responses into compact markdown or plain text that can be
+//! handed straight to an LLM: boilerplate (navigation, scripts, footers) is
+//! removed, links are preserved inline, and output is truncated to a token
+//! budget so a single document cannot blow the agent's context window.
+
+mod html;
+
+pub use html::decode_entities;
+
+use serde::{Deserialize, Serialize};
+
+/// Rough characters-per-token ratio used for budget calculations.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Default token budget for extracted content.
+pub const DEFAULT_MAX_TOKENS: usize = 4_000;
+
+/// Marker appended when content is cut to fit the token budget.
+const TRUNCATION_MARKER: &str = "[Content truncated]";
+
+/// Output format for extracted content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    #[default]
+    Markdown,
+    Text,
+}
+
+/// Options controlling content extraction.
+#[derive(Debug, Clone)]
+pub struct ExtractOptions {
+    pub format: ContentFormat,
+    /// Keep hyperlinks (as `[text](url)` in markdown, `text (url)` in plain text)
+    pub preserve_links: bool,
+    /// Approximate token budget; `None` disables truncation
+    pub max_tokens: Option<usize>,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            format: ContentFormat::Markdown,
+            preserve_links: true,
+            max_tokens: Some(DEFAULT_MAX_TOKENS),
+        }
+    }
+}
+
+/// Result of content extraction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractedContent {
+    pub title: Option<String>,
+    pub content: String,
+    pub truncated: bool,
+    pub estimated_tokens: usize,
+}
+
+/// Converts an HTML document into markdown or plain text.
+///
+/// When the page marks a `<main>` or `<article>` region only that region is
+/// rendered; otherwise the whole body is used with boilerplate elements removed.
+#[must_use]
+pub fn extract_html(html: &str, options: &ExtractOptions) -> ExtractedContent {
+    let tokens = html::tokenize(html);
+    let title = html::extract_title(&tokens);
+    let rendered = html::Renderer::new(
+        options.format == ContentFormat::Markdown,
+        options.preserve_links,
+    )
+    .render(html::main_content(&tokens));
+
+    finish(title, rendered, options.max_tokens)
+}
+
+/// Processes a fetched response body based on its `Content-Type`.
+///
+/// HTML is converted via [`extract_html`]; any other textual content (JSON,
+/// plain text, markdown) is passed through and only subject to truncation.
+#[must_use]
+pub fn extract_body(
+    content_type: Option<&str>,
+    body: &str,
+    options: &ExtractOptions,
+) -> ExtractedContent {
+    let is_html = content_type.map_or_else(
+        || body.trim_start().starts_with('<'),
+        |ct| {
+            let ct = ct.to_ascii_lowercase();
+            ct.contains("text/html") || ct.contains("application/xhtml")
+        },
+    );
+
+    if is_html {
+        extract_html(body, options)
+    } else {
+        finish(None, body.trim().to_string(), options.max_tokens)
+    }
+}
+
+fn finish(title: Option<String>, content: String, max_tokens: Option<usize>) -> ExtractedContent {
+    let (content, truncated) = match max_tokens {
+        Some(max_tokens) => truncate_to_tokens(&content, max_tokens),
+        None => (content, false),
+    };
+    let estimated_tokens = content.chars().count().div_ceil(CHARS_PER_TOKEN);
+
+    ExtractedContent {
+        title,
+        content,
+        truncated,
+        estimated_tokens,
+    }
+}
+
+/// Truncates text to an approximate token budget, preferring block boundaries.
+///
+/// Whole paragraphs are kept while they fit; if even the first paragraph is too
+/// long it is cut at the last word boundary within budget.
+#[must_use]
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> (String, bool) {
+    let budget = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+    if text.chars().count() <= budget {
+        return (text.to_string(), false);
+    }
+
+    let mut kept = String::new();
+    let mut used = 0;
+    for block in text.split("\n\n") {
+        let len = block.chars().count() + 2;
+        if used + len > budget {
+            break;
+        }
+        if !kept.is_empty() {
+            kept.push_str("\n\n");
+        }
+        kept.push_str(block);
+        used += len;
+    }
+
+    if kept.is_empty() {
+        let prefix: String = text.chars().take(budget).collect();
+        kept = prefix
+            .rsplit_once(char::is_whitespace)
+            .map_or(prefix.as_str(), |(head, _)| head)
+            .trim_end()
+            .to_string();
+    }
+
+    kept.push_str("\n\n");
+    kept.push_str(TRUNCATION_MARKER);
+    (kept, true)
+}
@@ -0,0 +1,331 @@
+//! Per-call context available to tools.
+//!
+//! Tool requests are parsed into typed structs, which only carry what the
+//! caller may supply. Facts established by the routing layer, such as who
+//! the caller is, travel in a [`ToolContext`] instead: [`scope`] makes it
+//! available to everything the tool awaits, the same way the invocation
+//! deadline is (see [`crate::deadline`]), and [`current`] reads it.
+//!
+//! The context also carries [`ProfileHints`] from the caller's token: their
+//! locale, timezone and unit preference, and the tenant they signed in
+//! through. The routing layer uses them as defaults for arguments the caller
+//! left out (see [`crate::defaults`]).
+//!
+//! Calls made in the same agent conversation share a session ID, which the
+//! interceptor reads from the gateway request headers (`SESSION_ID_HEADERS`)
+//! and passes on as the `session_id` argument. It lets logs and per-caller
+//! state be keyed on the conversation rather than only the user.
+
+use crate::auth::{AUTH_TOKEN_ARG, Claims, decode_claims};
+use crate::config::config;
+use crate::consent::PERSONAL_ARGS;
+use crate::identity::{IdentityContext, USER_ID_ARG, USER_NAME_ARG};
+use crate::memory::UserMemory;
+use crate::models::{Headers, UnitSystem};
+use crate::residency;
+use crate::roots::RootsProvider;
+use crate::sampling::Sampler;
+use crate::tenants::tenant_id;
+use crate::utils::is_timezone_name;
+use serde_json::{Map, Value, json};
+use std::fmt;
+use std::sync::Arc;
+
+/// Tool argument carrying the conversation's session ID
+pub const SESSION_ID_ARG: &str = "session_id";
+
+/// Longest session ID accepted
+const MAX_SESSION_ID_LEN: usize = 256;
+
+tokio::task_local! {
+    static CONTEXT: ToolContext;
+}
+
+/// What the routing layer knows about a tool call.
+#[derive(Clone, Default)]
+pub struct ToolContext {
+    /// Registered name of the tool being called
+    pub tool: &'static str,
+    /// Caller's user ID, once identity arguments have been verified
+    pub user_id: Option<String>,
+    /// Caller's display name, once identity arguments have been verified
+    pub user_name: Option<String>,
+    /// Caller's subject (`sub` claim), from the token the interceptor forwarded
+    pub subject: Option<String>,
+    /// Caller's user ID, when it came from a signed identity context (see
+    /// [`crate::identity`])
+    pub verified_user_id: Option<String>,
+    /// Agent conversation the call belongs to, if the gateway identified one
+    pub session_id: Option<String>,
+    /// Caller's profile, from their token claims
+    pub hints: ProfileHints,
+    /// Caller's tenant, from their token claims (see [`crate::tenants`])
+    pub tenant: Option<String>,
+    /// Caller's data residency, from their token claims or tenant (see
+    /// [`crate::residency`])
+    pub residency: Option<String>,
+    /// Set while an administrator replays a recorded call; tools must not
+    /// act on the outside world (see [`crate::audit`])
+    pub sandbox: bool,
+    /// Whether the caller's personal data may be used (see [`crate::consent`])
+    pub consent: bool,
+    /// Set for a dry run; tools with side effects must plan them instead of
+    /// performing them (see [`crate::dry_run`])
+    pub dry_run: bool,
+    /// Caller's bearer token, decrypted
+    auth_token: Option<String>,
+    /// Channel to the client's model, when the transport supports sampling
+    sampler: Option<Arc<dyn Sampler>>,
+    /// Channel for listing the client's workspace roots, when the transport supports it
+    roots_provider: Option<Arc<dyn RootsProvider>>,
+}
+
+/// Profile hints read from the caller's token claims.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileHints {
+    /// Preferred locale (`locale` claim), e.g. "en-AU"
+    pub locale: Option<String>,
+    /// Preferred IANA timezone (`zoneinfo` claim)
+    pub timezone: Option<String>,
+    /// Preferred units (the claim named by `PROFILE_UNITS_CLAIM`)
+    pub units: Option<UnitSystem>,
+}
+
+impl ProfileHints {
+    /// Reads the hints from `claims`, ignoring values that are not valid.
+    #[must_use]
+    pub fn from_claims(claims: &Claims) -> Self {
+        let text = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+        Self {
+            locale: text(claims.locale.as_deref()),
+            timezone: text(claims.zoneinfo.as_deref()).filter(|zone| is_timezone_name(zone)),
+            units: claims
+                .other
+                .get(&config().profile.units_claim)
+                .and_then(Value::as_str)
+                .and_then(UnitSystem::parse),
+        }
+    }
+
+    /// The tool arguments these hints supply: `locale`, `timezone` and `units`.
+    #[must_use]
+    pub fn arguments(&self) -> Map<String, Value> {
+        let mut arguments = Map::new();
+        if let Some(locale) = &self.locale {
+            arguments.insert("locale".to_string(), json!(locale));
+        }
+        if let Some(timezone) = &self.timezone {
+            arguments.insert("timezone".to_string(), json!(timezone));
+        }
+        if let Some(units) = self.units {
+            arguments.insert("units".to_string(), json!(units));
+        }
+        arguments
+    }
+}
+
+impl ToolContext {
+    /// Builds the context for a call to `tool` from its routed arguments.
+    ///
+    /// `args` must already have been decrypted and had their identity
+    /// verified (see [`crate::handler::route_tool`]).
+    #[must_use]
+    pub fn from_args(tool: &'static str, args: &Value) -> Self {
+        let text = |name: &str| {
+            args.get(name)
+                .and_then(Value::as_str)
+                .filter(|value| !value.trim().is_empty())
+                .map(String::from)
+        };
+        let auth_token = text(AUTH_TOKEN_ARG);
+        // The gateway has validated the token; one that cannot be decoded
+        // just carries no hints or tenant
+        let claims = auth_token
+            .as_deref()
+            .and_then(|token| decode_claims(token).ok());
+        let hints = claims
+            .as_ref()
+            .map(ProfileHints::from_claims)
+            .unwrap_or_default();
+        let tenant = claims.as_ref().and_then(tenant_id);
+        Self {
+            tool,
+            user_id: text(USER_ID_ARG),
+            user_name: text(USER_NAME_ARG),
+            subject: claims.as_ref().and_then(|claims| claims.sub.clone()),
+            verified_user_id: None,
+            session_id: args
+                .get(SESSION_ID_ARG)
+                .and_then(Value::as_str)
+                .and_then(parse_session_id),
+            hints,
+            residency: residency::resolve(claims.as_ref(), tenant.as_deref()),
+            tenant,
+            sandbox: false,
+            consent: !config().consent.required,
+            dry_run: false,
+            auth_token,
+            sampler: None,
+            roots_provider: None,
+        }
+    }
+
+    /// Caller's bearer token, if the interceptor forwarded one.
+    #[must_use]
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// Returns this context with the user ID of `identity`, once the
+    /// handler has verified it, as the verified user ID.
+    #[must_use]
+    pub fn with_identity(self, identity: Option<IdentityContext>) -> Self {
+        Self {
+            verified_user_id: identity.map(|identity| identity.user_id),
+            ..self
+        }
+    }
+
+    /// Returns this context with `sampler` as the channel for
+    /// `sampling/createMessage` requests (see [`crate::sampling`]).
+    #[must_use]
+    pub fn with_sampler(self, sampler: Arc<dyn Sampler>) -> Self {
+        Self {
+            sampler: Some(sampler),
+            ..self
+        }
+    }
+
+    /// Channel to the client's model, if the transport provides one.
+    #[must_use]
+    pub fn sampler(&self) -> Option<Arc<dyn Sampler>> {
+        self.sampler.clone()
+    }
+
+    /// Returns this context with `provider` as the channel for `roots/list`
+    /// requests (see [`crate::roots`]).
+    #[must_use]
+    pub fn with_roots_provider(self, provider: Arc<dyn RootsProvider>) -> Self {
+        Self {
+            roots_provider: Some(provider),
+            ..self
+        }
+    }
+
+    /// Channel for listing the client's roots, if the transport provides one.
+    #[must_use]
+    pub fn roots_provider(&self) -> Option<Arc<dyn RootsProvider>> {
+        self.roots_provider.clone()
+    }
+
+    /// The caller's memory of past calls (see [`crate::memory`]), if
+    /// memories are enabled, the caller consented to them and the
+    /// interceptor forwarded their token.
+    ///
+    /// Memory is keyed on the token's subject rather than the `user_id`
+    /// argument, so no argument can reach another user's memory.
+    #[must_use]
+    pub fn memory(&self) -> Option<UserMemory> {
+        if !config().memory.enabled || !self.consent {
+            return None;
+        }
+        Some(UserMemory::new(
+            self.subject.clone()?,
+            self.residency.clone(),
+            self.sandbox,
+        ))
+    }
+
+    /// Returns this context for a nested call to `tool` made on the
+    /// caller's behalf.
+    #[must_use]
+    pub fn for_tool(&self, tool: &'static str) -> Self {
+        Self {
+            tool,
+            ..self.clone()
+        }
+    }
+
+    /// Arguments carrying the caller's identity, session and token into a
+    /// nested tool call, so it is authorized and personalized like a direct
+    /// one; the caller's name and user ID are left out unless they consented.
+    #[must_use]
+    pub fn identity_args(&self) -> Map<String, Value> {
+        [
+            (USER_ID_ARG, &self.user_id),
+            (USER_NAME_ARG, &self.user_name),
+            (SESSION_ID_ARG, &self.session_id),
+            (AUTH_TOKEN_ARG, &self.auth_token),
+        ]
+        .into_iter()
+        .filter(|(name, _)| self.consent || !PERSONAL_ARGS.contains(name))
+        .filter_map(|(name, value)| Some((name.to_string(), json!(value.as_ref()?))))
+        .collect()
+    }
+}
+
+// The token is a credential, so it is never logged
+impl fmt::Debug for ToolContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolContext")
+            .field("tool", &self.tool)
+            .field("user_id", &self.user_id)
+            .field("user_name", &self.user_name)
+            .field("subject", &self.subject)
+            .field("verified_user_id", &self.verified_user_id)
+            .field("session_id", &self.session_id)
+            .field("hints", &self.hints)
+            .field("tenant", &self.tenant)
+            .field("residency", &self.residency)
+            .field("sandbox", &self.sandbox)
+            .field("consent", &self.consent)
+            .field("dry_run", &self.dry_run)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("sampler", &self.sampler.is_some())
+            .field("roots_provider", &self.roots_provider.is_some())
+            .finish()
+    }
+}
+
+/// Reads a session ID, or `None` if it is empty, too long or contains
+/// characters other than ASCII letters, digits and `-_.:`.
+#[must_use]
+pub fn parse_session_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    ((1..=MAX_SESSION_ID_LEN).contains(&value.len())
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':')))
+    .then(|| value.to_string())
+}
+
+/// The first valid session ID among the values of `names` in `headers`,
+/// trying names in order.
+#[must_use]
+pub fn session_id_from_headers(headers: &Headers, names: &[String]) -> Option<String> {
+    names.iter().find_map(|name| {
+        headers
+            .get_all(name)
+            .iter()
+            .find_map(|value| parse_session_id(value))
+    })
+}
+
+/// Runs `future` with `context` as the current tool context.
+pub async fn scope<F: Future>(context: ToolContext, future: F) -> F::Output {
+    CONTEXT.scope(context, future).await
+}
+
+/// The current tool context, or an anonymous one outside a [`scope`].
+#[must_use]
+pub fn current() -> ToolContext {
+    CONTEXT.try_with(Clone::clone).unwrap_or_default()
+}
@@ -0,0 +1,236 @@
+//! KMS envelope encryption for sensitive tool arguments.
+//!
+//! The gateway can see every tool argument, so secrets the interceptor places
+//! into arguments (such as the caller's bearer token) are encrypted first. A
+//! data key is generated under the KMS key named by `ARG_ENCRYPTION_KMS_KEY_ID`,
+//! the value is sealed locally with AES-256-GCM, and the KMS-encrypted data key
+//! travels alongside it:
+//!
+//! `enc:v1:<encrypted data key>.<nonce>.<ciphertext>` (each part base64url)
+//!
+//! The field name is bound as associated data, so a sealed value cannot be
+//! moved to a different argument. Plaintext data keys are cached for
+//! `ARG_ENCRYPTION_DATA_KEY_TTL_SECS` to keep KMS calls off the hot path.
+//! Once `ARG_ENCRYPTION_KMS_KEY_ID` is set on the tool Lambda, values without
+//! the `enc:v1:` prefix are refused rather than trusted, so enable encryption
+//! on the interceptor first; without it they pass through unchanged.
+
+use crate::aws::sdk_config;
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::models::error::AppError;
+use aws_lc_rs::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use aws_lc_rs::rand;
+use aws_sdk_kms::primitives::Blob;
+use aws_sdk_kms::types::DataKeySpec;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use lambda_runtime::tracing::debug;
+use serde_json::Value;
+use std::sync::{Arc, LazyLock};
+use tokio::sync::OnceCell;
+
+/// Prefix marking an envelope-encrypted value
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// KMS encryption context binding data keys to this use
+const CONTEXT_KEY: &str = "purpose";
+const CONTEXT_VALUE: &str = "tool-argument";
+
+/// Cache key for the data key used to encrypt
+const CURRENT_KEY: &str = "current";
+
+static KMS_CLIENT: OnceCell<aws_sdk_kms::Client> = OnceCell::const_new();
+
+/// Data key used for new encryptions, rotated every TTL
+pub(crate) static ENCRYPT_KEYS: LazyLock<CacheStore<Arc<DataKey>>> =
+    LazyLock::new(|| CacheStore::new("data-key", config().crypto.data_key_ttl, 1));
+
+/// Decrypted data keys by encrypted blob
+pub(crate) static DECRYPT_KEYS: LazyLock<CacheStore<Arc<DataKey>>> =
+    LazyLock::new(|| CacheStore::new("data-key-decrypt", config().crypto.data_key_ttl, 64));
+
+/// A plaintext data key and its KMS-encrypted form.
+pub(crate) struct DataKey {
+    plaintext: Vec<u8>,
+    encrypted: String,
+}
+
+/// Returns `true` if `value` is an envelope-encrypted value.
+#[must_use]
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Whether argument encryption is configured (`ARG_ENCRYPTION_KMS_KEY_ID`).
+#[must_use]
+pub fn is_enabled() -> bool {
+    config().crypto.kms_key_id.is_some()
+}
+
+/// Encrypts `plaintext` for the argument `field`.
+///
+/// # Errors
+///
+/// Returns `AppError::EncryptionError` if no KMS key is configured, a data
+/// key cannot be generated, or sealing fails.
+pub async fn encrypt_field(field: &str, plaintext: &str) -> Result<String, AppError> {
+    let data_key = current_data_key().await?;
+
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::fill(&mut nonce)
+        .map_err(|_| AppError::EncryptionError("Failed to generate nonce".to_string()))?;
+    let mut sealed = plaintext.as_bytes().to_vec();
+    sealing_key(&data_key.plaintext)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(field.as_bytes()),
+            &mut sealed,
+        )
+        .map_err(|_| AppError::EncryptionError("Failed to encrypt value".to_string()))?;
+
+    Ok(format!(
+        "{ENCRYPTED_PREFIX}{}.{}.{}",
+        data_key.encrypted,
+        URL_SAFE_NO_PAD.encode(nonce),
+        URL_SAFE_NO_PAD.encode(sealed)
+    ))
+}
+
+/// Decrypts a value produced by [`encrypt_field`] for the same `field`.
+///
+/// # Errors
+///
+/// Returns `AppError::EncryptionError` if the value is malformed, KMS
+/// refuses to decrypt its data key, or it was sealed for another field.
+pub async fn decrypt_field(field: &str, value: &str) -> Result<String, AppError> {
+    let invalid = || AppError::EncryptionError("Malformed encrypted value".to_string());
+
+    let envelope = value.strip_prefix(ENCRYPTED_PREFIX).ok_or_else(invalid)?;
+    let mut parts = envelope.split('.');
+    let (Some(encrypted_key), Some(nonce), Some(sealed), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+
+    let nonce: [u8; NONCE_LEN] = URL_SAFE_NO_PAD
+        .decode(nonce)
+        .ok()
+        .and_then(|n| n.try_into().ok())
+        .ok_or_else(invalid)?;
+    let mut sealed = URL_SAFE_NO_PAD.decode(sealed).map_err(|_| invalid())?;
+
+    let data_key = decrypt_data_key(encrypted_key).await?;
+    let plaintext = sealing_key(&data_key.plaintext)?
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(field.as_bytes()),
+            &mut sealed,
+        )
+        .map_err(|_| AppError::EncryptionError("Failed to decrypt value".to_string()))?;
+
+    String::from_utf8(plaintext.to_vec())
+        .map_err(|_| AppError::EncryptionError("Decrypted value is not UTF-8".to_string()))
+}
+
+/// Decrypts the listed string fields of `args` in place.
+///
+/// Absent fields are left out. Without argument encryption configured,
+/// fields that are not encrypted are left unchanged; with it, they are
+/// refused, since only the interceptor can have sealed them.
+///
+/// # Errors
+///
+/// Returns `AppError::EncryptionError` if an encrypted field cannot be
+/// decrypted, or a field is in plaintext although encryption is configured.
+pub async fn decrypt_args(args: &mut Value, fields: &[&str]) -> Result<(), AppError> {
+    for field in fields {
+        let Some(value) = args.get_mut(*field) else {
+            continue;
+        };
+        let Some(encrypted) = value.as_str().filter(|v| is_encrypted(v)) else {
+            if is_enabled() {
+                return Err(AppError::EncryptionError(format!(
+                    "{field} must be encrypted"
+                )));
+            }
+            continue;
+        };
+        *value = Value::String(decrypt_field(field, encrypted).await?);
+        debug!(field, "Decrypted tool argument");
+    }
+    Ok(())
+}
+
+fn sealing_key(key: &[u8]) -> Result<LessSafeKey, AppError> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| AppError::EncryptionError("Invalid data key".to_string()))
+}
+
+async fn kms_client() -> &'static aws_sdk_kms::Client {
+    KMS_CLIENT
+        .get_or_init(|| async { aws_sdk_kms::Client::new(sdk_config().await) })
+        .await
+}
+
+async fn current_data_key() -> Result<Arc<DataKey>, AppError> {
+    if let Some(data_key) = ENCRYPT_KEYS.get(CURRENT_KEY) {
+        return Ok(data_key);
+    }
+    let key_id = config().crypto.kms_key_id.clone().ok_or_else(|| {
+        AppError::EncryptionError("No KMS key is configured for argument encryption".to_string())
+    })?;
+
+    let output = kms_client()
+        .await
+        .generate_data_key()
+        .key_id(key_id)
+        .key_spec(DataKeySpec::Aes256)
+        .encryption_context(CONTEXT_KEY, CONTEXT_VALUE)
+        .send()
+        .await
+        .map_err(|e| AppError::EncryptionError(format!("KMS GenerateDataKey failed: {e}")))?;
+
+    let (Some(plaintext), Some(encrypted)) = (output.plaintext(), output.ciphertext_blob()) else {
+        return Err(AppError::EncryptionError(
+            "KMS returned an incomplete data key".to_string(),
+        ));
+    };
+    let data_key = Arc::new(DataKey {
+        plaintext: plaintext.as_ref().to_vec(),
+        encrypted: URL_SAFE_NO_PAD.encode(encrypted.as_ref()),
+    });
+    ENCRYPT_KEYS.insert(CURRENT_KEY, Arc::clone(&data_key));
+    DECRYPT_KEYS.insert(data_key.encrypted.clone(), Arc::clone(&data_key));
+    Ok(data_key)
+}
+
+async fn decrypt_data_key(encrypted: &str) -> Result<Arc<DataKey>, AppError> {
+    if let Some(data_key) = DECRYPT_KEYS.get(encrypted) {
+        return Ok(data_key);
+    }
+    let blob = URL_SAFE_NO_PAD
+        .decode(encrypted)
+        .map_err(|_| AppError::EncryptionError("Malformed encrypted data key".to_string()))?;
+
+    let output = kms_client()
+        .await
+        .decrypt()
+        .ciphertext_blob(Blob::new(blob))
+        .encryption_context(CONTEXT_KEY, CONTEXT_VALUE)
+        .send()
+        .await
+        .map_err(|e| AppError::EncryptionError(format!("KMS Decrypt failed: {e}")))?;
+
+    let plaintext = output.plaintext().ok_or_else(|| {
+        AppError::EncryptionError("KMS returned no plaintext data key".to_string())
+    })?;
+    let data_key = Arc::new(DataKey {
+        plaintext: plaintext.as_ref().to_vec(),
+        encrypted: encrypted.to_string(),
+    });
+    DECRYPT_KEYS.insert(encrypted, Arc::clone(&data_key));
+    Ok(data_key)
+}
@@ -0,0 +1,448 @@
+//! Relative date phrases, such as "tomorrow" or "next Tuesday".
+//!
+//! Agents often pass the user's own words for when they want a forecast.
+//! [`RelativeDate::parse`] reads such a phrase in the caller's language,
+//! and [`RelativeDate::resolve`] turns it into the concrete days it means,
+//! counted from today at the place being forecast. Understood phrases are:
+//!
+//! - `today` (or `tonight`), `tomorrow` and `the day after tomorrow`
+//! - `in 3 days`, up to a year ahead
+//! - `this weekend` (Saturday and Sunday; only Sunday on a Sunday) and
+//!   `next weekend`, the one after
+//! - `this week` (today to Sunday) and `next week` (Monday to Sunday)
+//! - a weekday, optionally preceded by `this`, meaning its next occurrence
+//!   from today on, and `next Tuesday`, meaning its next occurrence after
+//!   today
+//! - an ISO 8601 date such as `2026-10-17`
+//!
+//! English, German, French and Spanish are understood, picked by the
+//! language of the caller's locale (e.g. `de-AT`); English phrases are
+//! always understood too. Case, accents where commonly dropped, surrounding
+//! whitespace and trailing punctuation do not matter.
+
+use crate::models::error::AppError;
+use chrono::{Datelike, Days, FixedOffset, NaiveDate, Utc, Weekday};
+use std::fmt;
+
+/// Furthest ahead `in <n> days` may reach
+const MAX_DAYS_AHEAD: u64 = 366;
+
+/// Weekdays in the order lexicons list their names
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// A date phrase, before it is resolved against today's date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelativeDate {
+    Today,
+    Tomorrow,
+    DayAfterTomorrow,
+    /// The day this many days from today
+    InDays(u64),
+    ThisWeekend,
+    NextWeekend,
+    ThisWeek,
+    NextWeek,
+    /// The next occurrence of `weekday`; with `next`, never today
+    Weekday {
+        weekday: Weekday,
+        next: bool,
+    },
+    Date(NaiveDate),
+}
+
+/// The days a phrase resolved to, from `first` to `last` inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl DateRange {
+    const fn day(date: NaiveDate) -> Self {
+        Self {
+            first: date,
+            last: date,
+        }
+    }
+
+    /// Whether `date` is one of the days.
+    #[must_use]
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.first..=self.last).contains(&date)
+    }
+}
+
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{} to {}", self.first, self.last)
+        }
+    }
+}
+
+impl RelativeDate {
+    /// Parses `phrase` in the language of `locale`, or English.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ValidationError` if the phrase is not understood.
+    pub fn parse(phrase: &str, locale: Option<&str>) -> Result<Self, AppError> {
+        let normalized = normalize(phrase);
+        if let Ok(date) = NaiveDate::parse_from_str(&normalized, "%Y-%m-%d") {
+            return Ok(Self::Date(date));
+        }
+        let language = locale.map(language);
+        LEXICONS
+            .iter()
+            .filter(|lexicon| Some(lexicon.language) == language.as_deref())
+            .chain(std::iter::once(&ENGLISH))
+            .find_map(|lexicon| lexicon.parse(&normalized))
+            .ok_or_else(|| {
+                AppError::ValidationError(format!(
+                    "Date not understood: \"{}\"; use e.g. \"today\", \"tomorrow\", \"this weekend\", \
+                     \"next Tuesday\", \"in 3 days\" or a date such as 2026-10-17",
+                    phrase.trim()
+                ))
+            })
+    }
+
+    /// The days the phrase means when today is `today`.
+    #[must_use]
+    pub fn resolve(self, today: NaiveDate) -> DateRange {
+        let ahead = |days: u32| today + Days::new(u64::from(days));
+        let weekday = today.weekday().num_days_from_monday();
+        match self {
+            Self::Today => DateRange::day(today),
+            Self::Tomorrow => DateRange::day(ahead(1)),
+            Self::DayAfterTomorrow => DateRange::day(ahead(2)),
+            Self::InDays(days) => DateRange::day(today + Days::new(days)),
+            Self::ThisWeekend if today.weekday() == Weekday::Sun => DateRange::day(today),
+            Self::ThisWeekend => DateRange {
+                first: ahead(5 - weekday),
+                last: ahead(6 - weekday),
+            },
+            Self::NextWeekend => DateRange {
+                first: ahead(12 - weekday),
+                last: ahead(13 - weekday),
+            },
+            Self::ThisWeek => DateRange {
+                first: today,
+                last: ahead(6 - weekday),
+            },
+            Self::NextWeek => DateRange {
+                first: ahead(7 - weekday),
+                last: ahead(13 - weekday),
+            },
+            Self::Weekday {
+                weekday: target,
+                next,
+            } => {
+                let days = (target.num_days_from_monday() + 7 - weekday) % 7;
+                DateRange::day(ahead(if next && days == 0 { 7 } else { days }))
+            }
+            Self::Date(date) => DateRange::day(date),
+        }
+    }
+}
+
+/// Today's date at a place `utc_offset_seconds` ahead of UTC.
+#[must_use]
+pub fn today_at(utc_offset_seconds: i32) -> NaiveDate {
+    let now = Utc::now();
+    FixedOffset::east_opt(utc_offset_seconds).map_or_else(
+        || now.date_naive(),
+        |offset| now.with_timezone(&offset).date_naive(),
+    )
+}
+
+/// Phrases in one language, all lowercase.
+struct Lexicon {
+    /// ISO 639-1 code, e.g. "de"
+    language: &'static str,
+    today: &'static [&'static str],
+    tomorrow: &'static [&'static str],
+    day_after_tomorrow: &'static [&'static str],
+    this_weekend: &'static [&'static str],
+    next_weekend: &'static [&'static str],
+    this_week: &'static [&'static str],
+    next_week: &'static [&'static str],
+    /// Names of each weekday, Monday first
+    weekdays: [&'static [&'static str]; 7],
+    /// Words before a weekday that keep its meaning, e.g. "this"
+    this: &'static [&'static str],
+    /// Words before a weekday meaning its occurrence after today, e.g. "next"
+    next_before: &'static [&'static str],
+    /// Words after a weekday meaning its occurrence after today, e.g. "prochain"
+    next_after: &'static [&'static str],
+    /// Words before a number of days from today, e.g. "in"
+    in_days_before: &'static [&'static str],
+    /// Words after a number of days from today, e.g. "days"
+    in_days_after: &'static [&'static str],
+}
+
+const ENGLISH: Lexicon = Lexicon {
+    language: "en",
+    today: &[
+        "today",
+        "tonight",
+        "this morning",
+        "this afternoon",
+        "this evening",
+    ],
+    tomorrow: &["tomorrow"],
+    day_after_tomorrow: &["the day after tomorrow", "day after tomorrow", "overmorrow"],
+    this_weekend: &[
+        "this weekend",
+        "the weekend",
+        "weekend",
+        "over the weekend",
+        "on the weekend",
+        "at the weekend",
+    ],
+    next_weekend: &["next weekend"],
+    this_week: &["this week", "the rest of the week", "rest of the week"],
+    next_week: &["next week"],
+    weekdays: [
+        &["monday", "mon"],
+        &["tuesday", "tue", "tues"],
+        &["wednesday", "wed"],
+        &["thursday", "thu", "thurs"],
+        &["friday", "fri"],
+        &["saturday", "sat"],
+        &["sunday", "sun"],
+    ],
+    this: &["this", "this coming", "coming", "on"],
+    next_before: &["next", "on next"],
+    next_after: &[],
+    in_days_before: &["in"],
+    in_days_after: &["days", "day"],
+};
+
+const GERMAN: Lexicon = Lexicon {
+    language: "de",
+    today: &["heute", "heute abend", "heute nacht"],
+    tomorrow: &["morgen"],
+    day_after_tomorrow: &["übermorgen", "uebermorgen"],
+    this_weekend: &["dieses wochenende", "am wochenende", "wochenende"],
+    next_weekend: &[
+        "nächstes wochenende",
+        "naechstes wochenende",
+        "am nächsten wochenende",
+        "am naechsten wochenende",
+        "kommendes wochenende",
+    ],
+    this_week: &["diese woche"],
+    next_week: &["nächste woche", "naechste woche", "kommende woche"],
+    weekdays: [
+        &["montag"],
+        &["dienstag"],
+        &["mittwoch"],
+        &["donnerstag"],
+        &["freitag"],
+        &["samstag", "sonnabend"],
+        &["sonntag"],
+    ],
+    this: &["am", "diesen"],
+    next_before: &[
+        "nächsten",
+        "naechsten",
+        "kommenden",
+        "am nächsten",
+        "am naechsten",
+        "am kommenden",
+    ],
+    next_after: &[],
+    in_days_before: &["in"],
+    in_days_after: &["tagen", "tag"],
+};
+
+const FRENCH: Lexicon = Lexicon {
+    language: "fr",
+    today: &["aujourd'hui", "aujourdhui", "ce soir", "cette nuit"],
+    tomorrow: &["demain"],
+    day_after_tomorrow: &[
+        "après-demain",
+        "apres-demain",
+        "après demain",
+        "apres demain",
+    ],
+    this_weekend: &["ce week-end", "ce weekend", "ce week end"],
+    next_weekend: &[
+        "le week-end prochain",
+        "le weekend prochain",
+        "week-end prochain",
+        "weekend prochain",
+    ],
+    this_week: &["cette semaine"],
+    next_week: &["la semaine prochaine", "semaine prochaine"],
+    weekdays: [
+        &["lundi"],
+        &["mardi"],
+        &["mercredi"],
+        &["jeudi"],
+        &["vendredi"],
+        &["samedi"],
+        &["dimanche"],
+    ],
+    this: &["ce", "le"],
+    next_before: &[],
+    next_after: &["prochain"],
+    in_days_before: &["dans"],
+    in_days_after: &["jours", "jour"],
+};
+
+const SPANISH: Lexicon = Lexicon {
+    language: "es",
+    today: &["hoy", "esta noche", "esta tarde"],
+    tomorrow: &["mañana", "manana"],
+    day_after_tomorrow: &["pasado mañana", "pasado manana"],
+    this_weekend: &["este fin de semana", "el fin de semana", "fin de semana"],
+    next_weekend: &[
+        "el próximo fin de semana",
+        "el proximo fin de semana",
+        "próximo fin de semana",
+        "proximo fin de semana",
+        "el fin de semana que viene",
+    ],
+    this_week: &["esta semana"],
+    next_week: &[
+        "la próxima semana",
+        "la proxima semana",
+        "la semana que viene",
+        "próxima semana",
+        "proxima semana",
+    ],
+    weekdays: [
+        &["lunes"],
+        &["martes"],
+        &["miércoles", "miercoles"],
+        &["jueves"],
+        &["viernes"],
+        &["sábado", "sabado"],
+        &["domingo"],
+    ],
+    this: &["el", "este"],
+    next_before: &["el próximo", "el proximo", "próximo", "proximo"],
+    next_after: &["que viene", "próximo", "proximo"],
+    in_days_before: &["en", "dentro de"],
+    in_days_after: &["días", "dias", "día", "dia"],
+};
+
+/// Lexicons besides English, which is always tried
+const LEXICONS: [Lexicon; 3] = [GERMAN, FRENCH, SPANISH];
+
+impl Lexicon {
+    fn parse(&self, phrase: &str) -> Option<RelativeDate> {
+        let fixed = [
+            (self.today, RelativeDate::Today),
+            (self.tomorrow, RelativeDate::Tomorrow),
+            (self.day_after_tomorrow, RelativeDate::DayAfterTomorrow),
+            (self.this_weekend, RelativeDate::ThisWeekend),
+            (self.next_weekend, RelativeDate::NextWeekend),
+            (self.this_week, RelativeDate::ThisWeek),
+            (self.next_week, RelativeDate::NextWeek),
+        ];
+        fixed
+            .into_iter()
+            .find(|(phrases, _)| phrases.contains(&phrase))
+            .map(|(_, date)| date)
+            .or_else(|| self.in_days(phrase))
+            .or_else(|| self.weekday(phrase))
+    }
+
+    fn in_days(&self, phrase: &str) -> Option<RelativeDate> {
+        self.in_days_before
+            .iter()
+            .filter_map(|word| after_word(phrase, word))
+            .flat_map(|rest| {
+                self.in_days_after
+                    .iter()
+                    .filter_map(move |word| before_word(rest, word))
+            })
+            .find_map(|count| count.parse::<u64>().ok())
+            .filter(|&days| days <= MAX_DAYS_AHEAD)
+            .map(RelativeDate::InDays)
+    }
+
+    fn weekday(&self, phrase: &str) -> Option<RelativeDate> {
+        let named = |name: &str| {
+            self.weekdays
+                .iter()
+                .zip(WEEK)
+                .find(|(names, _)| names.contains(&name))
+                .map(|(_, weekday)| weekday)
+        };
+        // A weekday name, optionally after a word such as "this"
+        let day = |rest: &str| {
+            named(rest).or_else(|| {
+                self.this
+                    .iter()
+                    .filter_map(|word| after_word(rest, word))
+                    .find_map(named)
+            })
+        };
+        let next = self
+            .next_before
+            .iter()
+            .filter_map(|word| after_word(phrase, word))
+            .chain(
+                self.next_after
+                    .iter()
+                    .filter_map(|word| before_word(phrase, word)),
+            )
+            .find_map(day)
+            .map(|weekday| RelativeDate::Weekday {
+                weekday,
+                next: true,
+            });
+        next.or_else(|| {
+            day(phrase).map(|weekday| RelativeDate::Weekday {
+                weekday,
+                next: false,
+            })
+        })
+    }
+}
+
+/// What follows `word` and a space at the start of `phrase`
+fn after_word<'a>(phrase: &'a str, word: &str) -> Option<&'a str> {
+    phrase.strip_prefix(word)?.strip_prefix(' ')
+}
+
+/// What precedes a space and `word` at the end of `phrase`
+fn before_word<'a>(phrase: &'a str, word: &str) -> Option<&'a str> {
+    phrase.strip_suffix(word)?.strip_suffix(' ')
+}
+
+/// Lowercases `phrase`, collapses its whitespace and drops trailing
+/// punctuation and typographic apostrophes
+fn normalize(phrase: &str) -> String {
+    phrase
+        .to_lowercase()
+        .replace('\u{2019}', "'")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', ',', '!', '?'])
+        .to_string()
+}
+
+/// The language subtag of `locale`, e.g. "de" for "de-AT"
+fn language(locale: &str) -> String {
+    locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
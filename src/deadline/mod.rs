@@ -0,0 +1,73 @@
+//! Invocation deadline tracking.
+//!
+//! The Lambda context carries the time at which the invocation will be
+//! killed. [`scope`] makes that deadline available to everything the tool
+//! awaits, and [`upstream_timeout`] caps upstream request timeouts so they
+//! finish, leaving a safety margin for serialization and logging, before the
+//! function times out. This turns an opaque Lambda timeout into a tool error
+//! the agent can act on.
+
+use crate::config::config;
+use crate::models::error::AppError;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Converts a Lambda context deadline (milliseconds since the Unix epoch)
+/// into an [`Instant`].
+///
+/// Returns `None` for `0`, which means the deadline is unknown (e.g. a
+/// default context in local testing).
+#[must_use]
+pub fn from_epoch_millis(deadline_ms: u64) -> Option<Instant> {
+    if deadline_ms == 0 {
+        return None;
+    }
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+    Some(Instant::now() + Duration::from_millis(deadline_ms.saturating_sub(now_ms)))
+}
+
+/// Runs `future` with `deadline` as the invocation deadline, or without one
+/// if `deadline` is `None`.
+pub async fn scope<F: Future>(deadline: Option<Instant>, future: F) -> F::Output {
+    match deadline {
+        Some(deadline) => DEADLINE.scope(deadline, future).await,
+        None => future.await,
+    }
+}
+
+/// Time left before the invocation deadline, after the safety margin.
+///
+/// Returns `None` outside a deadline [`scope`].
+#[must_use]
+pub fn remaining() -> Option<Duration> {
+    let margin = config().timeouts.safety_margin;
+    DEADLINE
+        .try_with(|deadline| {
+            deadline
+                .saturating_duration_since(Instant::now())
+                .saturating_sub(margin)
+        })
+        .ok()
+}
+
+/// Timeout for an upstream request: the configured upstream timeout, capped
+/// by the time remaining before the invocation deadline.
+///
+/// # Errors
+///
+/// Returns `AppError::DeadlineExceeded` if no time remains for the request.
+pub fn upstream_timeout() -> Result<Duration, AppError> {
+    let default = config().timeouts.upstream;
+    match remaining() {
+        Some(remaining) if remaining.is_zero() => Err(AppError::DeadlineExceeded(
+            "No time left before the invocation deadline".to_string(),
+        )),
+        Some(remaining) => Ok(remaining.min(default)),
+        None => Ok(default),
+    }
+}
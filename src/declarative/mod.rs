@@ -0,0 +1,399 @@
+//! Tools defined in configuration instead of code.
+//!
+//! Each entry in `DECLARATIVE_TOOLS` wraps one REST endpoint: an HTTP
+//! request template whose `{{argument}}` placeholders are filled from the
+//! call's arguments, and an optional `JMESPath` expression that shapes the
+//! JSON response. `run_declarative_tool` calls them by name, so a simple
+//! tool can be added by changing the configuration, without a deploy.
+//!
+//! Definitions are compiled by [`load`] when the container starts, so a
+//! malformed template or expression stops the container instead of failing
+//! every call. Arguments can never change the shape of the request:
+//!
+//! - placeholders may only appear in the URL's path and query, never in its
+//!   scheme or host, and values are percent-encoded so they cannot add path
+//!   segments or query parameters
+//! - header names are literal, and values containing control characters
+//!   (such as CR or LF) are refused
+//! - body placeholders are substituted into the parsed JSON, never into its
+//!   text, so values cannot add fields
+//! - when the tool has an input schema, templates may only use the
+//!   properties it declares
+//!
+//! Requests go through the shared client, so the egress policy applies.
+
+mod template;
+
+pub use template::Template;
+
+use crate::config::{DeclarativeToolDefinition, config};
+use crate::deadline::upstream_timeout;
+use crate::http::{HTTP_CLIENT, read_body, retry_after, send, send_error};
+use crate::models::error::AppError;
+use crate::transform::Transform;
+use lambda_runtime::tracing::{info, warn};
+use reqwest::header::HeaderName;
+use reqwest::{Method, StatusCode, Url};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Compiled tools keyed by name.
+static TOOLS: OnceLock<HashMap<String, DeclarativeTool>> = OnceLock::new();
+
+/// Headers set by the client, which a template may not override
+const RESERVED_HEADERS: &[&str] = &["host", "content-length", "transfer-encoding", "connection"];
+
+/// A compiled tool definition.
+pub struct DeclarativeTool {
+    name: String,
+    method: Method,
+    url: Template,
+    host: String,
+    headers: Vec<(HeaderName, Template)>,
+    body: Option<Value>,
+    response: Option<Transform>,
+    input_schema: Option<Value>,
+}
+
+/// A request filled in from a call's arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: Vec<(HeaderName, String)>,
+    pub body: Option<Value>,
+}
+
+impl DeclarativeTool {
+    /// Compiles `definition`, checking its templates and expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the method, URL, a header, the body or the
+    /// response expression is invalid, or a template uses an argument the
+    /// input schema does not declare.
+    pub fn new(name: &str, definition: &DeclarativeToolDefinition) -> Result<Self, AppError> {
+        let invalid = |e: String| AppError::GenericError(format!("Declarative tool {name}: {e}"));
+        let request = &definition.request;
+
+        let method = Method::from_bytes(request.method.trim().to_ascii_uppercase().as_bytes())
+            .map_err(|_| invalid(format!("invalid method {}", request.method)))?;
+        let url = Template::parse(&request.url).map_err(&invalid)?;
+        let host = check_url(&url).map_err(&invalid)?;
+        let headers = request
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let header = HeaderName::from_bytes(name.trim().as_bytes())
+                    .map_err(|_| invalid(format!("invalid header name {name}")))?;
+                if RESERVED_HEADERS.contains(&header.as_str()) {
+                    return Err(invalid(format!("header {header} cannot be set")));
+                }
+                Ok((header, Template::parse(value).map_err(&invalid)?))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut arguments = url.arguments().map(String::from).collect::<Vec<_>>();
+        for (_, value) in &headers {
+            arguments.extend(value.arguments().map(String::from));
+        }
+        if let Some(body) = &request.body {
+            body_arguments(body, &mut arguments).map_err(&invalid)?;
+        }
+        if let Some(declared) = definition
+            .input_schema
+            .as_ref()
+            .and_then(|schema| schema.get("properties"))
+            .and_then(Value::as_object)
+            && let Some(undeclared) = arguments.iter().find(|a| !declared.contains_key(*a))
+        {
+            return Err(invalid(format!(
+                "template uses {undeclared}, which the input schema does not declare"
+            )));
+        }
+        let response = definition
+            .response
+            .as_deref()
+            .map(|expression| {
+                Transform::compile(expression)
+                    .map_err(|e| invalid(format!("response expression: {e}")))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            name: name.to_string(),
+            method,
+            url,
+            host,
+            headers,
+            body: request.body.clone(),
+            response,
+            input_schema: definition.input_schema.clone(),
+        })
+    }
+
+    /// JSON Schema the arguments must satisfy, if any.
+    #[must_use]
+    pub const fn input_schema(&self) -> Option<&Value> {
+        self.input_schema.as_ref()
+    }
+
+    /// Fills in the request from `arguments`, a JSON object.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if an argument used in the URL or a header
+    /// is an array or object, or a header value contains a control
+    /// character.
+    pub fn render(&self, arguments: &Value) -> Result<RenderedRequest, AppError> {
+        let empty = Map::new();
+        let arguments = arguments.as_object().unwrap_or(&empty);
+
+        let url = self.url.render(|name| {
+            let value = text(arguments, name)?;
+            // `.` and `..` would be resolved as path segments
+            if !value.is_empty() && value.chars().all(|c| c == '.') {
+                return Err(AppError::ValidationError(format!(
+                    "Argument {name} may not be a relative path segment"
+                )));
+            }
+            Ok(urlencoding::encode(&value).into_owned())
+        })?;
+        let url = Url::parse(&url).map_err(|e| {
+            AppError::ValidationError(format!("Arguments make an invalid URL: {e}"))
+        })?;
+        // Encoding keeps arguments out of the host; this guards the invariant
+        if url.host_str() != Some(self.host.as_str()) {
+            return Err(AppError::ValidationError(
+                "Arguments may not change the request's host".to_string(),
+            ));
+        }
+
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, template)| {
+                let value = template.render(|argument| text(arguments, argument))?;
+                if value.chars().any(char::is_control) {
+                    return Err(AppError::ValidationError(format!(
+                        "Header {name} may not contain control characters"
+                    )));
+                }
+                Ok((name.clone(), value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let body = self
+            .body
+            .as_ref()
+            .map(|body| render_body(body, arguments))
+            .transpose()?;
+
+        Ok(RenderedRequest {
+            method: self.method.clone(),
+            url,
+            headers,
+            body,
+        })
+    }
+
+    /// Applies the response expression to `body`, or returns it unchanged if
+    /// the tool has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression fails to evaluate.
+    pub fn map_response(&self, body: Value) -> Result<Value, AppError> {
+        match &self.response {
+            Some(transform) => transform.apply(body),
+            None => Ok(body),
+        }
+    }
+
+    /// Sends the request filled in from `arguments` and maps the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request cannot be rendered or sent, the
+    /// upstream does not answer with success, or its response is not JSON.
+    pub async fn call(&self, arguments: &Value) -> Result<Value, AppError> {
+        let request = self.render(arguments)?;
+        let mut builder = HTTP_CLIENT
+            .request(request.method, request.url)
+            .timeout(upstream_timeout()?);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
+
+        info!(tool = %self.name, "Calling declarative tool upstream");
+        let response = send(builder).await.map_err(|e| {
+            send_error(e, |e| {
+                AppError::DeclarativeToolError(format!("Request failed: {e}"))
+            })
+        })?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(AppError::RateLimited {
+                upstream: self.name.clone(),
+                retry_after: retry_after(response.headers()),
+            });
+        }
+        if !status.is_success() {
+            return Err(AppError::DeclarativeToolError(format!(
+                "Upstream returned {status}"
+            )));
+        }
+        let body = read_body(response)
+            .await
+            .map_err(|e| AppError::DeclarativeToolError(format!("Failed to read response: {e}")))?;
+        let body = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&body)
+                .map_err(|e| AppError::DeclarativeToolError(format!("Response is not JSON: {e}")))?
+        };
+        self.map_response(body)
+    }
+}
+
+impl fmt::Debug for DeclarativeTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeclarativeTool")
+            .field("name", &self.name)
+            .field("method", &self.method)
+            .field("host", &self.host)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compiles every configured tool and keeps them for [`find`]; call once at
+/// startup.
+///
+/// # Errors
+///
+/// Returns an error naming the first definition that does not compile.
+pub fn load() -> Result<(), AppError> {
+    let tools = config()
+        .declarative
+        .tools
+        .iter()
+        .map(|(name, definition)| Ok((name.clone(), DeclarativeTool::new(name, definition)?)))
+        .collect::<Result<HashMap<_, _>, AppError>>()?;
+    if !tools.is_empty() {
+        info!(tools = tools.len(), "Loaded declarative tools");
+    }
+    // A second call keeps the first set, which calls may already be using
+    let _ = TOOLS.set(tools);
+    Ok(())
+}
+
+/// The configured tool `name`.
+///
+/// If [`load`] has not run, definitions are compiled on first use and those
+/// that fail are skipped with a warning.
+#[must_use]
+pub fn find(name: &str) -> Option<&'static DeclarativeTool> {
+    TOOLS
+        .get_or_init(|| {
+            config()
+                .declarative
+                .tools
+                .iter()
+                .filter_map(|(name, definition)| {
+                    DeclarativeTool::new(name, definition)
+                        .inspect_err(|e| warn!(error = %e, "Skipping declarative tool"))
+                        .ok()
+                        .map(|tool| (name.clone(), tool))
+                })
+                .collect()
+        })
+        .get(name)
+}
+
+/// Checks that placeholders only appear after the URL's host, returning the
+/// host.
+fn check_url(url: &Template) -> Result<String, String> {
+    let prefix = url.literal_prefix();
+    let authority_ends = prefix
+        .split_once("://")
+        .is_some_and(|(_, rest)| rest.contains(['/', '?']));
+    if url.arguments().next().is_some() && !authority_ends {
+        return Err("placeholders may only appear in the URL's path or query".to_string());
+    }
+    let sample = url
+        .render(|_| Ok::<_, AppError>("x".to_string()))
+        .map_err(|e| e.to_string())?;
+    let parsed = Url::parse(&sample).map_err(|e| format!("invalid URL: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("unsupported URL scheme {}", parsed.scheme()));
+    }
+    parsed
+        .host_str()
+        .map(String::from)
+        .ok_or_else(|| "URL has no host".to_string())
+}
+
+/// Collects the arguments used by the strings in a body template.
+fn body_arguments(body: &Value, arguments: &mut Vec<String>) -> Result<(), String> {
+    match body {
+        Value::String(s) => {
+            arguments.extend(Template::parse(s)?.arguments().map(String::from));
+        }
+        Value::Array(items) => {
+            for item in items {
+                body_arguments(item, arguments)?;
+            }
+        }
+        Value::Object(fields) => {
+            for value in fields.values() {
+                body_arguments(value, arguments)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Fills in a body template: a string that is only a placeholder takes the
+/// argument's JSON value (or `null`), other strings take its text.
+fn render_body(body: &Value, arguments: &Map<String, Value>) -> Result<Value, AppError> {
+    Ok(match body {
+        Value::String(s) => {
+            let template = Template::parse(s).map_err(AppError::GenericError)?;
+            match template.whole_argument() {
+                Some(name) => arguments.get(name).cloned().unwrap_or(Value::Null),
+                None => Value::String(template.render(|name| text(arguments, name))?),
+            }
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_body(item, arguments))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), render_body(value, arguments)?)))
+                .collect::<Result<_, AppError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// The text of a scalar argument; missing and `null` arguments are empty.
+fn text(arguments: &Map<String, Value>, name: &str) -> Result<String, AppError> {
+    match arguments.get(name) {
+        None | Some(Value::Null) => Ok(String::new()),
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(Value::Number(n)) => Ok(n.to_string()),
+        Some(Value::Bool(b)) => Ok(b.to_string()),
+        Some(_) => Err(AppError::ValidationError(format!(
+            "Argument {name} must be a string, number or boolean here"
+        ))),
+    }
+}
@@ -0,0 +1,94 @@
+//! `{{argument}}` templates.
+
+use crate::models::error::AppError;
+
+/// Text with `{{argument}}` placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Argument(String),
+}
+
+impl Template {
+    /// Parses `template`. Argument names are letters, digits and `_`, and
+    /// may be padded with spaces (`{{ city }}`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a placeholder is not closed or its name is
+    /// invalid.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some((text, placeholder)) = rest.split_once("{{") {
+            if !text.is_empty() {
+                segments.push(Segment::Text(text.to_string()));
+            }
+            let (name, after) = placeholder
+                .split_once("}}")
+                .ok_or_else(|| format!("unclosed placeholder in {template:?}"))?;
+            let name = name.trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!(
+                    "invalid placeholder {{{{{name}}}}} in {template:?}"
+                ));
+            }
+            segments.push(Segment::Argument(name.to_string()));
+            rest = after;
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Names of the arguments used, in order.
+    pub fn arguments(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Argument(name) => Some(name.as_str()),
+            Segment::Text(_) => None,
+        })
+    }
+
+    /// The argument the template consists of, if it is a single placeholder.
+    #[must_use]
+    pub fn whole_argument(&self) -> Option<&str> {
+        match self.segments.as_slice() {
+            [Segment::Argument(name)] => Some(name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The text before the first placeholder.
+    #[must_use]
+    pub fn literal_prefix(&self) -> &str {
+        match self.segments.first() {
+            Some(Segment::Text(text)) => text.as_str(),
+            _ => "",
+        }
+    }
+
+    /// Fills in each placeholder with `value` of its argument name.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error `value` returns.
+    pub fn render(
+        &self,
+        mut value: impl FnMut(&str) -> Result<String, AppError>,
+    ) -> Result<String, AppError> {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => rendered.push_str(text),
+                Segment::Argument(name) => rendered.push_str(&value(name)?),
+            }
+        }
+        Ok(rendered)
+    }
+}
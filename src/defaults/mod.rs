@@ -0,0 +1,116 @@
+//! Default arguments for tool calls.
+//!
+//! Before a tool parses its request, the routing layer fills in arguments
+//! the caller left out. Explicit arguments always win; next come the
+//! caller's stored preferences (see [`crate::preferences`]), then the
+//! profile hints in their token (see [`crate::context::ProfileHints`]), then
+//! their tenant's defaults (see [`crate::tenants`]), then
+//! the per-tool defaults in `TOOL_DEFAULT_ARGS`, a JSON object keyed by tool
+//! name such as `{"get_weather": {"response_version": "2"}}`. Only arguments
+//! the tool's input schema declares are filled in, and an argument passed as
+//! `null` counts as left out.
+
+use crate::config::config;
+use crate::context::ToolContext;
+use crate::preferences::{self, PREFERENCE_ARGS};
+use crate::registry::ToolDescriptor;
+use crate::tenants;
+use lambda_runtime::tracing::{debug, warn};
+use serde_json::{Map, Value};
+
+/// Fills in the arguments of a call to `tool` that `args` leaves out.
+///
+/// Preferences are only read when the tool declares an argument they could
+/// supply, the caller's user ID is known and they consented to their
+/// personal data being used (see [`crate::consent`]). If they cannot be read
+/// the call goes ahead with the other defaults.
+pub async fn apply(tool: &ToolDescriptor, args: &mut Value, context: &ToolContext) {
+    let config = config();
+    let configured = config.defaults.tool_args.get(tool.name);
+    let hints = context.hints.arguments();
+    if configured.is_none()
+        && hints.is_empty()
+        && context.user_id.is_none()
+        && context.tenant.is_none()
+    {
+        return;
+    }
+    let Some(args) = args.as_object_mut() else {
+        return;
+    };
+    let declared = declared_arguments(tool);
+
+    let wants_preferences = PREFERENCE_ARGS
+        .iter()
+        .any(|name| declared.iter().any(|d| d == *name) && is_missing(args, name));
+    let preferences = match context.user_id.as_deref() {
+        Some(user_id) if wants_preferences && context.consent => {
+            match preferences::load(user_id, context.residency.as_deref()).await {
+                Ok(preferences) => preferences.arguments(),
+                Err(e) => {
+                    warn!(tool = tool.name, error = %e, "Filling defaults without user preferences");
+                    Map::new()
+                }
+            }
+        }
+        _ => Map::new(),
+    };
+
+    let tenant = match tenants::settings(context.tenant.as_deref()).await {
+        Ok(settings) => settings
+            .map(|settings| settings.arguments())
+            .unwrap_or_default(),
+        Err(e) => {
+            warn!(tool = tool.name, error = %e, "Filling defaults without tenant settings");
+            Map::new()
+        }
+    };
+
+    let empty = Map::new();
+    let filled = merge_defaults(
+        args,
+        &declared,
+        &[&preferences, &hints, &tenant, configured.unwrap_or(&empty)],
+    );
+    if !filled.is_empty() {
+        debug!(tool = tool.name, arguments = ?filled, "Filled in default arguments");
+    }
+}
+
+/// Copies into `args` each `declared` argument it leaves out from the first
+/// of `layers` that has it, so earlier layers take precedence.
+///
+/// Returns the names of the arguments filled in.
+pub fn merge_defaults(
+    args: &mut Map<String, Value>,
+    declared: &[String],
+    layers: &[&Map<String, Value>],
+) -> Vec<String> {
+    let mut filled = Vec::new();
+    for name in declared {
+        if !is_missing(args, name) {
+            continue;
+        }
+        if let Some(value) = layers
+            .iter()
+            .find_map(|layer| layer.get(name).filter(|value| !value.is_null()))
+        {
+            args.insert(name.clone(), value.clone());
+            filled.push(name.clone());
+        }
+    }
+    filled
+}
+
+/// Names of the arguments in `tool`'s input schema.
+fn declared_arguments(tool: &ToolDescriptor) -> Vec<String> {
+    (tool.input_schema)()
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn is_missing(args: &Map<String, Value>, name: &str) -> bool {
+    args.get(name).is_none_or(Value::is_null)
+}
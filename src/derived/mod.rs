@@ -0,0 +1,164 @@
+//! Metrics derived from a forecast after it is fetched.
+//!
+//! Open-Meteo reports temperatures, snowfall, precipitation and humidity,
+//! but none of them directly answers "will roads be icy tomorrow morning".
+//! Each forecast day is given [`RoadConditions`]:
+//!
+//! - Freezing risk grows as the low nears and passes 0 °C: low within 2 °C
+//!   of freezing, moderate at or below it, high at -2 °C or colder.
+//! - Snow accumulation is the day's snowfall, unless the low stays above
+//!   2 °C and it melts on contact.
+//! - Black ice needs a low of 1 °C or less and moisture, either precipitation
+//!   or humidity of 90% or more. It is high when the day also thaws above
+//!   freezing, so meltwater refreezes overnight, moderate otherwise, and low
+//!   when the air is dry.
+//!
+//! Snow accumulation is reported in cm, or inches for imperial callers.
+//! Forecasts cached before snowfall, precipitation and humidity were
+//! requested get no conditions.
+//!
+//! Each day is also given a [`ConvectionRisk`] of thunderstorms, lightning
+//! and other severe convection, for planning outdoor events. It is the
+//! higher of two levels read from the hourly forecast: the day's peak CAPE
+//! (convective available potential energy) is low from 300 J/kg, moderate
+//! from 1000 and high from 2500, and its lowest lifted index is low below 0,
+//! moderate from -3 and high from -6. Both numbers are returned with the
+//! level, and forecasts cached without hourly data get no convection risk.
+
+use crate::models::open_meteo::{Daily, Hourly};
+use crate::models::{ConvectionRisk, RiskLevel, RoadConditions, UnitSystem};
+
+/// Low, in °C, up to which snow settles and freezing is a risk
+const NEAR_FREEZING: f64 = 2.0;
+
+/// Low, in °C, up to which damp roads may ice over
+const BLACK_ICE_LOW: f64 = 1.0;
+
+/// Low, in °C, at or below which freezing is a high risk
+const HARD_FREEZE: f64 = -2.0;
+
+/// Relative humidity, in percent, from which air is moist enough for ice
+const HUMID: f64 = 90.0;
+
+/// Peak CAPE, in J/kg, from which convection risk is low, moderate and high
+const CAPE_LEVELS: [f64; 3] = [300.0, 1000.0, 2500.0];
+
+/// Lowest lifted index below which convection risk is low, and from which
+/// (that is, at or below) it is moderate and high
+const LIFTED_INDEX_LEVELS: [f64; 3] = [0.0, -3.0, -6.0];
+
+/// Road conditions for each day of `daily`, with snow in `units`, or none if
+/// the forecast lacks the variables they are derived from.
+#[must_use]
+pub fn road_conditions(daily: &Daily, units: UnitSystem) -> Vec<RoadConditions> {
+    let days = daily.time.len();
+    let complete = [
+        daily.temperature_2m_max.len(),
+        daily.temperature_2m_min.len(),
+        daily.snowfall_sum.len(),
+        daily.precipitation_sum.len(),
+        daily.relative_humidity_2m_max.len(),
+    ]
+    .iter()
+    .all(|&len| len == days);
+    if !complete {
+        return Vec::new();
+    }
+
+    (0..days)
+        .map(|day| {
+            let low = daily.temperature_2m_min[day].get();
+            let high = daily.temperature_2m_max[day].get();
+            let snowfall = daily.snowfall_sum[day];
+            let moist = daily.precipitation_sum[day].get() > 0.0
+                || daily.relative_humidity_2m_max[day] >= HUMID;
+            RoadConditions {
+                snow_accumulation: match units {
+                    _ if low > NEAR_FREEZING => 0.0,
+                    UnitSystem::Metric => snowfall.get(),
+                    UnitSystem::Imperial => snowfall.inches(),
+                },
+                freezing_risk: freezing_risk(low),
+                black_ice_risk: black_ice_risk(low, high, moist),
+            }
+        })
+        .collect()
+}
+
+/// Severe convection risk for each day of `daily`, from the hourly CAPE and
+/// lifted index, or none if the forecast has no hourly data.
+#[must_use]
+pub fn convection_risk(daily: &Daily, hourly: Option<&Hourly>) -> Vec<ConvectionRisk> {
+    let Some(hourly) = hourly else {
+        return Vec::new();
+    };
+    daily
+        .time
+        .iter()
+        .map(|date| {
+            let hours: Vec<usize> = hourly
+                .time
+                .iter()
+                .enumerate()
+                .filter(|(_, time)| time.date() == *date)
+                .map(|(hour, _)| hour)
+                .collect();
+            let peak = |series: &[Option<f64>], pick: fn(f64, f64) -> f64| {
+                hours
+                    .iter()
+                    .filter_map(|&hour| series.get(hour).copied().flatten())
+                    .reduce(pick)
+            };
+            let max_cape = peak(&hourly.cape, f64::max).unwrap_or_default();
+            let min_lifted_index = peak(&hourly.lifted_index, f64::min);
+
+            let cape_risk = level(CAPE_LEVELS.map(|threshold| max_cape >= threshold));
+            let [low, moderate, high] = LIFTED_INDEX_LEVELS;
+            let lifted_index_risk = min_lifted_index.map_or(RiskLevel::None, |index| {
+                level([index < low, index <= moderate, index <= high])
+            });
+            ConvectionRisk {
+                risk: cape_risk.max(lifted_index_risk),
+                max_cape,
+                min_lifted_index,
+            }
+        })
+        .collect()
+}
+
+/// The highest of the low, moderate and high levels that is reached
+const fn level([low, moderate, high]: [bool; 3]) -> RiskLevel {
+    if high {
+        RiskLevel::High
+    } else if moderate {
+        RiskLevel::Moderate
+    } else if low {
+        RiskLevel::Low
+    } else {
+        RiskLevel::None
+    }
+}
+
+fn freezing_risk(low: f64) -> RiskLevel {
+    if low <= HARD_FREEZE {
+        RiskLevel::High
+    } else if low <= 0.0 {
+        RiskLevel::Moderate
+    } else if low <= NEAR_FREEZING {
+        RiskLevel::Low
+    } else {
+        RiskLevel::None
+    }
+}
+
+fn black_ice_risk(low: f64, high: f64, moist: bool) -> RiskLevel {
+    if low > BLACK_ICE_LOW {
+        RiskLevel::None
+    } else if !moist {
+        RiskLevel::Low
+    } else if high > 0.0 {
+        RiskLevel::High
+    } else {
+        RiskLevel::Moderate
+    }
+}
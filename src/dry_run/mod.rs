@@ -0,0 +1,63 @@
+//! Dry runs of tools with side effects.
+//!
+//! Tools that act on the outside world, such as sending a notification or
+//! starting a workflow, declare `side_effects` in their descriptor and
+//! accept a `dry_run` argument. The routing layer takes it before the tool
+//! runs and marks the call's [`ToolContext`](crate::context::ToolContext);
+//! with `DRY_RUN` enabled every call to such a tool is marked. A marked call
+//! checks its arguments, allowlists and caller exactly as a real one would,
+//! then instead of performing its effect returns `side_effect_status:
+//! "planned"` and a [`DryRunPlan`](crate::models::DryRunPlan) of what it
+//! would have done, so an agent can confirm the action with the user before
+//! calling again without `dry_run`.
+//!
+//! Steps of `run_workflow` inherit the mark, so a dry run of a composition
+//! plans its side effects without performing any. Dry runs are not journaled
+//! (see [`crate::effects`]), so planning a call does not turn the real call
+//! into a duplicate, and act on nothing, so they are allowed while a recorded
+//! call is replayed (see [`crate::audit`]).
+
+use crate::config::config;
+use crate::context;
+use crate::models::error::AppError;
+use crate::registry::ToolDescriptor;
+use serde_json::Value;
+
+/// Tool argument asking for a dry run
+pub const DRY_RUN_ARG: &str = "dry_run";
+
+/// Takes the `dry_run` argument out of `args` for a call to `tool`,
+/// returning whether the call is a dry run: because the caller asked, or
+/// `DRY_RUN` is enabled.
+///
+/// Tools without side effects leave the argument to strict argument
+/// parsing.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the argument is not a boolean.
+pub fn take(tool: &ToolDescriptor, args: &mut Value) -> Result<bool, AppError> {
+    if !tool.side_effects() {
+        return Ok(false);
+    }
+    let requested = match args
+        .as_object_mut()
+        .and_then(|args| args.remove(DRY_RUN_ARG))
+    {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(flag)) => flag,
+        Some(Value::String(flag)) => flag.trim().parse::<bool>().map_err(|_| invalid_flag())?,
+        Some(_) => return Err(invalid_flag()),
+    };
+    Ok(requested || config().dry_run.enabled)
+}
+
+/// Whether the current call is a dry run.
+#[must_use]
+pub fn is_active() -> bool {
+    context::current().dry_run
+}
+
+fn invalid_flag() -> AppError {
+    AppError::ValidationError(format!("{DRY_RUN_ARG} must be true or false"))
+}
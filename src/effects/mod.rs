@@ -0,0 +1,380 @@
+//! Journal of side effects, so retried tool calls do not repeat them.
+//!
+//! Bedrock retries a tool call whose response it did not receive, and a
+//! retry of `send_notification` or `start_workflow` would otherwise send the
+//! message or start the execution a second time. These tools run their
+//! effect through [`run_once`], which first claims the call's key in the
+//! journal:
+//! - an unclaimed key runs the effect and journals its response, returned
+//!   with `side_effect_status: "executed"`
+//! - a key whose effect completed returns the journaled response without
+//!   acting again, with `side_effect_status: "deduplicated"`
+//! - a key whose effect is still running, because the original call is in
+//!   flight when the retry arrives, is refused with `TooBusy` so the caller
+//!   retries once it has finished
+//!
+//! Callers may pass their own `idempotency_key`. Otherwise the key is derived
+//! from the arguments and the conversation's session ID, so a retry within
+//! the conversation is recognised without client changes; calls with
+//! neither are not journaled. Keys are scoped to the tool and the caller.
+//!
+//! Entries are kept in the container and, when `EFFECT_JOURNAL_TABLE` names
+//! a `DynamoDB` table, there too, where a conditional write makes the claim
+//! atomic across containers. Completed entries expire after
+//! `EFFECT_JOURNAL_TTL_SECS`. A claim whose effect failed is released so the
+//! call can be retried, and a claim left by a container that stopped
+//! mid-effect lapses after [`CLAIM_LEASE`]. If the table cannot be written
+//! the effect runs anyway, so a journal outage does not take the tools down
+//! with it.
+
+use crate::aws::sdk_config;
+use crate::config::config;
+use crate::context;
+use crate::models::SideEffectStatus;
+use crate::models::error::AppError;
+use aws_lc_rs::digest;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::Utc;
+use lambda_runtime::tracing::{debug, info, warn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Partition key of the journal table
+pub const EFFECT_KEY: &str = "effect_key";
+
+/// How long a claim holds while its effect runs, longer than any invocation
+pub const CLAIM_LEASE: Duration = Duration::from_mins(15);
+
+/// Entries kept in a container before expired ones are purged
+const MAX_ENTRIES: usize = 1024;
+
+const RUNNING: &str = "running";
+const COMPLETED: &str = "completed";
+
+static DYNAMODB_CLIENT: OnceCell<aws_sdk_dynamodb::Client> = OnceCell::const_new();
+
+/// Journal entries in this container, by effect key
+static JOURNAL: LazyLock<Mutex<HashMap<String, Entry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct Entry {
+    state: State,
+    expires: Instant,
+}
+
+#[derive(Debug, Clone)]
+enum State {
+    Running,
+    /// The response the effect returned
+    Completed(Value),
+}
+
+/// Outcome of claiming an effect key.
+#[derive(Debug)]
+enum Claim {
+    Acquired,
+    Running,
+    Completed(Value),
+}
+
+/// Runs `effect` unless an earlier call to `tool` with the same key already
+/// did, in which case that call's response is returned instead.
+///
+/// `arguments` are the tool's arguments, from which the key is derived when
+/// the caller gave no `idempotency_key`. Returns the response with whether
+/// the effect ran.
+///
+/// # Errors
+///
+/// Returns the error of `effect`, `AppError::TooBusy` if an earlier call
+/// with the same key is still running, and `AppError::EffectJournalError`
+/// if that call's response cannot be read back.
+pub async fn run_once<T, F, Fut>(
+    tool: &str,
+    idempotency_key: Option<&str>,
+    arguments: &(impl Serialize + Sync),
+    effect: F,
+) -> Result<(T, SideEffectStatus), AppError>
+where
+    T: Serialize + DeserializeOwned + Send + Sync,
+    F: FnOnce() -> Fut + Send,
+    Fut: Future<Output = Result<T, AppError>> + Send,
+{
+    let key = if config().effects.enabled {
+        effect_key(tool, idempotency_key, arguments)
+    } else {
+        None
+    };
+    let Some(key) = key else {
+        return Ok((effect().await?, SideEffectStatus::Executed));
+    };
+
+    match claim(&key).await {
+        Claim::Acquired => match effect().await {
+            Ok(response) => {
+                complete(&key, &response).await;
+                Ok((response, SideEffectStatus::Executed))
+            }
+            Err(e) => {
+                release(&key).await;
+                Err(e)
+            }
+        },
+        Claim::Running => {
+            info!(tool, effect_key = %key, "Refusing a repeated call while the first is running");
+            Err(AppError::TooBusy(format!(
+                "An identical {tool} call is still running, retry shortly"
+            )))
+        }
+        Claim::Completed(response) => {
+            info!(tool, effect_key = %key, "Returning the journaled response of a repeated call");
+            let response = serde_json::from_value(response).map_err(|e| {
+                AppError::EffectJournalError(format!("Malformed journal entry: {e}"))
+            })?;
+            Ok((response, SideEffectStatus::Deduplicated))
+        }
+    }
+}
+
+/// Journal key of a call to `tool`, or `None` if it has neither an
+/// idempotency key nor a session to derive one from.
+fn effect_key(
+    tool: &str,
+    idempotency_key: Option<&str>,
+    arguments: &impl Serialize,
+) -> Option<String> {
+    let context = context::current();
+    let scope = if let Some(key) = idempotency_key.map(str::trim).filter(|key| !key.is_empty()) {
+        format!("key\0{key}")
+    } else {
+        let session = context.session_id.as_deref()?;
+        let arguments = serde_json::to_value(arguments).ok()?;
+        format!("session\0{session}\0{arguments}")
+    };
+    let caller = context.user_id.as_deref().unwrap_or_default();
+    let hash = digest::digest(&digest::SHA256, format!("{caller}\0{scope}").as_bytes());
+    Some(format!("{tool}#{}", hex(hash.as_ref())))
+}
+
+/// Claims `key` for a new run of its effect, unless it is already claimed.
+async fn claim(key: &str) -> Claim {
+    {
+        let mut journal = JOURNAL.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        if journal.len() >= MAX_ENTRIES {
+            journal.retain(|_, entry| entry.expires > now);
+        }
+        if let Some(entry) = journal.get(key).filter(|entry| entry.expires > now) {
+            return match &entry.state {
+                State::Running => Claim::Running,
+                State::Completed(response) => Claim::Completed(response.clone()),
+            };
+        }
+        journal.insert(
+            key.to_string(),
+            Entry {
+                state: State::Running,
+                expires: now + CLAIM_LEASE,
+            },
+        );
+    }
+
+    let Some(table) = config().effects.table.clone() else {
+        return Claim::Acquired;
+    };
+    let claim = match put_claim(&table, key).await {
+        Ok(true) => return Claim::Acquired,
+        Ok(false) => get_entry(&table, key).await,
+        Err(e) => {
+            warn!(effect_key = %key, error = %e, "Effect journal unavailable, running the effect without it");
+            return Claim::Acquired;
+        }
+    };
+    let mut journal = JOURNAL.lock().unwrap_or_else(PoisonError::into_inner);
+    let claim = match claim {
+        // Remember another container's response, so later retries need no read
+        Ok(Some(response)) => {
+            journal.insert(
+                key.to_string(),
+                Entry {
+                    state: State::Completed(response.clone()),
+                    expires: Instant::now() + config().effects.ttl,
+                },
+            );
+            Claim::Completed(response)
+        }
+        Ok(None) => {
+            journal.remove(key);
+            Claim::Running
+        }
+        Err(e) => {
+            journal.remove(key);
+            warn!(effect_key = %key, error = %e, "Failed to read an effect journal entry");
+            Claim::Running
+        }
+    };
+    drop(journal);
+    claim
+}
+
+/// Journals the response of the effect claimed under `key`.
+async fn complete(key: &str, response: &(impl Serialize + Sync)) {
+    let response = match serde_json::to_value(response) {
+        Ok(response) => response,
+        Err(e) => {
+            warn!(effect_key = %key, error = %e, "Failed to journal an effect response");
+            return;
+        }
+    };
+    let ttl = config().effects.ttl;
+    if let Some(table) = config().effects.table.as_deref()
+        && let Err(e) = put_completed(table, key, &response, ttl).await
+    {
+        warn!(effect_key = %key, error = %e, "Failed to write an effect response to the journal table");
+    }
+    JOURNAL
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(
+            key.to_string(),
+            Entry {
+                state: State::Completed(response),
+                expires: Instant::now() + ttl,
+            },
+        );
+}
+
+/// Releases the claim on `key` after its effect failed, so it can be retried.
+async fn release(key: &str) {
+    JOURNAL
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(key);
+    if let Some(table) = config().effects.table.as_deref()
+        && let Err(e) = delete_claim(table, key).await
+    {
+        warn!(effect_key = %key, error = %e, "Failed to release an effect journal claim");
+    }
+}
+
+/// Writes a running claim unless the key holds an entry that has not
+/// expired, i.e. a claim within its lease or a journaled response; returns
+/// whether the claim was written.
+async fn put_claim(table: &str, key: &str) -> Result<bool, AppError> {
+    let now = Utc::now().timestamp();
+    let lease = i64::try_from(CLAIM_LEASE.as_secs()).unwrap_or(i64::MAX);
+    let result = dynamodb_client()
+        .await
+        .put_item()
+        .table_name(table)
+        .item(EFFECT_KEY, AttributeValue::S(key.to_string()))
+        .item("state", AttributeValue::S(RUNNING.to_string()))
+        .item(
+            "expires_at",
+            AttributeValue::N(now.saturating_add(lease).to_string()),
+        )
+        .condition_expression("attribute_not_exists(#key) OR expires_at < :now")
+        .expression_attribute_names("#key", EFFECT_KEY)
+        .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
+        .send()
+        .await;
+    match result {
+        Ok(_) => Ok(true),
+        Err(e)
+            if e.as_service_error()
+                .is_some_and(PutItemError::is_conditional_check_failed_exception) =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(AppError::EffectJournalError(format!(
+            "DynamoDB PutItem failed: {e}"
+        ))),
+    }
+}
+
+async fn put_completed(
+    table: &str,
+    key: &str,
+    response: &Value,
+    ttl: Duration,
+) -> Result<(), AppError> {
+    let ttl = i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX);
+    let expires_at = Utc::now().timestamp().saturating_add(ttl);
+    dynamodb_client()
+        .await
+        .put_item()
+        .table_name(table)
+        .item(EFFECT_KEY, AttributeValue::S(key.to_string()))
+        .item("state", AttributeValue::S(COMPLETED.to_string()))
+        .item("response", AttributeValue::S(response.to_string()))
+        .item("expires_at", AttributeValue::N(expires_at.to_string()))
+        .send()
+        .await
+        .map_err(|e| AppError::EffectJournalError(format!("DynamoDB PutItem failed: {e}")))?;
+    Ok(())
+}
+
+/// The journaled response under `key`, or `None` if its effect is still
+/// running.
+async fn get_entry(table: &str, key: &str) -> Result<Option<Value>, AppError> {
+    debug!(table, effect_key = %key, "Reading effect journal entry");
+    let output = dynamodb_client()
+        .await
+        .get_item()
+        .table_name(table)
+        .key(EFFECT_KEY, AttributeValue::S(key.to_string()))
+        .consistent_read(true)
+        .send()
+        .await
+        .map_err(|e| AppError::EffectJournalError(format!("DynamoDB GetItem failed: {e}")))?;
+    let Some(item) = output.item() else {
+        return Ok(None);
+    };
+    let text = |name: &str| item.get(name).and_then(|value| value.as_s().ok());
+    if text("state").map(String::as_str) != Some(COMPLETED) {
+        return Ok(None);
+    }
+    let response = text("response")
+        .ok_or_else(|| AppError::EffectJournalError("Journal entry has no response".to_string()))?;
+    serde_json::from_str(response)
+        .map(Some)
+        .map_err(|e| AppError::EffectJournalError(format!("Malformed journal entry: {e}")))
+}
+
+async fn delete_claim(table: &str, key: &str) -> Result<(), AppError> {
+    dynamodb_client()
+        .await
+        .delete_item()
+        .table_name(table)
+        .key(EFFECT_KEY, AttributeValue::S(key.to_string()))
+        .condition_expression("#state = :running")
+        .expression_attribute_names("#state", "state")
+        .expression_attribute_values(":running", AttributeValue::S(RUNNING.to_string()))
+        .send()
+        .await
+        .map_err(|e| AppError::EffectJournalError(format!("DynamoDB DeleteItem failed: {e}")))?;
+    Ok(())
+}
+
+async fn dynamodb_client() -> &'static aws_sdk_dynamodb::Client {
+    DYNAMODB_CLIENT
+        .get_or_init(|| async { aws_sdk_dynamodb::Client::new(sdk_config().await) })
+        .await
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
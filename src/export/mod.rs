@@ -0,0 +1,205 @@
+//! Export of tabular tool responses as CSV or NDJSON.
+//!
+//! Tools whose responses hold a table, such as forecast days or log query
+//! rows, declare the fields holding it in their descriptor and accept an
+//! `output_format` argument of `json` (the default), `csv` or `ndjson`.
+//! The routing layer takes the argument before the tool runs, and once the
+//! response is built replaces the table with its serialization as a single
+//! string, so analytics agents get rows they parse reliably while the rest
+//! of the response, such as `next_cursor`, is unchanged.
+//!
+//! Rows are read from a list of objects or, for columnar fields such as the
+//! version 1 weather `daily`, from an object of parallel lists. CSV columns
+//! are the row fields in first-seen order, nested values are written as
+//! JSON, and fields are quoted as RFC 4180 requires. Tools without a table
+//! leave the argument to strict argument parsing.
+
+use crate::models::error::AppError;
+use crate::registry::ToolDescriptor;
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+/// Tool argument selecting how rows are returned
+pub const OUTPUT_FORMAT_ARG: &str = "output_format";
+
+/// Serialization of a tool's rows requested by the caller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Rows stay structured JSON
+    #[default]
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl OutputFormat {
+    /// Every format, default first
+    pub const ALL: [Self; 3] = [Self::Json, Self::Csv, Self::Ndjson];
+
+    /// The format as sent in `output_format`, e.g. `csv`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+
+    /// Parses a format name such as `csv`, ignoring case.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str().eq_ignore_ascii_case(value))
+    }
+
+    /// Serializes `rows` in this format; JSON gives them back as a list.
+    #[must_use]
+    pub fn serialize(self, rows: Vec<Map<String, Value>>) -> Value {
+        match self {
+            Self::Json => Value::Array(rows.into_iter().map(Value::Object).collect()),
+            Self::Csv => Value::String(to_csv(&rows)),
+            Self::Ndjson => {
+                Value::String(rows.into_iter().fold(String::new(), |mut ndjson, row| {
+                    let _ = writeln!(ndjson, "{}", Value::Object(row));
+                    ndjson
+                }))
+            }
+        }
+    }
+}
+
+/// Takes the `output_format` argument out of `args` for a call to `tool`.
+///
+/// Returns `None` if the tool returns no table or the caller asks for JSON.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the format is not one of
+/// [`OutputFormat::ALL`].
+pub fn take(tool: &ToolDescriptor, args: &mut Value) -> Result<Option<OutputFormat>, AppError> {
+    if tool.rows.is_empty() {
+        return Ok(None);
+    }
+    let Some(format) = args
+        .as_object_mut()
+        .and_then(|args| args.remove(OUTPUT_FORMAT_ARG))
+    else {
+        return Ok(None);
+    };
+    let format = match &format {
+        Value::Null => OutputFormat::Json,
+        Value::String(name) => OutputFormat::parse(name).ok_or_else(invalid_format)?,
+        _ => return Err(invalid_format()),
+    };
+    Ok((format != OutputFormat::Json).then_some(format))
+}
+
+/// Replaces the first of `fields` present in `response` with its rows
+/// serialized as `format`.
+pub fn apply(format: OutputFormat, fields: &[&str], response: &mut Value) {
+    let Some(table) = fields
+        .iter()
+        .find(|field| response.get(**field).is_some())
+        .and_then(|field| response.get_mut(*field))
+    else {
+        return;
+    };
+    if let Some(rows) = rows(table) {
+        *table = format.serialize(rows);
+    }
+}
+
+fn invalid_format() -> AppError {
+    let formats: Vec<&str> = OutputFormat::ALL
+        .into_iter()
+        .map(OutputFormat::as_str)
+        .collect();
+    AppError::ValidationError(format!(
+        "`{OUTPUT_FORMAT_ARG}` must be one of: {}",
+        formats.join(", ")
+    ))
+}
+
+/// The rows of `table`, a list of objects or an object of parallel lists.
+fn rows(table: &Value) -> Option<Vec<Map<String, Value>>> {
+    match table {
+        Value::Array(items) => Some(
+            items
+                .iter()
+                .map(|item| match item {
+                    Value::Object(fields) => fields.clone(),
+                    value => Map::from_iter([("value".to_string(), value.clone())]),
+                })
+                .collect(),
+        ),
+        Value::Object(columns) => {
+            let lengths = columns
+                .values()
+                .map(|column| column.as_array().map(Vec::len))
+                .collect::<Option<Vec<_>>>()?;
+            let count = lengths.into_iter().max().unwrap_or_default();
+            Some(
+                (0..count)
+                    .map(|index| {
+                        columns
+                            .iter()
+                            .map(|(name, column)| {
+                                (name.clone(), column.get(index).cloned().unwrap_or_default())
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn to_csv(rows: &[Map<String, Value>]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for name in rows.iter().flat_map(Map::keys) {
+        if !columns.contains(&name.as_str()) {
+            columns.push(name);
+        }
+    }
+    if columns.is_empty() {
+        return String::new();
+    }
+
+    let mut csv = line(columns.iter().map(|column| quote(column)));
+    for row in rows {
+        csv.push_str(&line(
+            columns
+                .iter()
+                .map(|column| row.get(*column).map(cell).unwrap_or_default()),
+        ));
+    }
+    csv
+}
+
+fn line(fields: impl Iterator<Item = String>) -> String {
+    let mut line = fields.collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => quote(text),
+        value => quote(&value.to_string()),
+    }
+}
+
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,153 @@
+//! Local emulation of the Bedrock `AgentCore` Gateway flow.
+//!
+//! In production a `tools/call` request reaches the tool Lambda in three
+//! hops: the gateway passes it to the interceptor Lambda, forwards the
+//! transformed request to the tool Lambda's target with the prefixed tool
+//! name in the client context and the arguments as the payload, and wraps
+//! the result in a JSON-RPC response. [`call`] runs the same hops in process,
+//! through [`crate::interceptor::handle`] and
+//! [`crate::handler::function_handler`], so tests and local tooling cover the
+//! full path without deploying.
+
+use crate::handler::function_handler;
+use crate::interceptor;
+use crate::models::Headers;
+use crate::models::interceptor::{GatewayRequest, GatewayResponse};
+use crate::utils::strip_gateway_prefix;
+use lambda_runtime::{Context, Error, LambdaEvent};
+use serde_json::{Value, json};
+
+/// Name of the emulated gateway target; tool names reach the tool Lambda
+/// prefixed with it, as `local___get_weather`.
+pub const TARGET_NAME: &str = "local";
+
+/// JSON-RPC error code for requests the emulation does not forward
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// What happened to a request on its way through the emulated gateway.
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    /// The request the interceptor forwarded, if it did not answer itself
+    pub forwarded: Option<GatewayRequest>,
+    /// The response the caller receives
+    pub response: GatewayResponse,
+}
+
+impl Exchange {
+    /// The JSON-RPC `result` of the response, if it has one.
+    #[must_use]
+    pub fn result(&self) -> Option<&Value> {
+        self.response.body.as_ref()?.get("result")
+    }
+
+    /// The tool's output, parsed from the text the gateway wraps it in.
+    #[must_use]
+    pub fn output(&self) -> Option<Value> {
+        let text = self.result()?.pointer("/content/0/text")?.as_str()?;
+        serde_json::from_str(text).ok()
+    }
+
+    /// Whether the tool call failed.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.result()
+            .and_then(|result| result.get("isError"))
+            .and_then(Value::as_bool)
+            .unwrap_or(true)
+    }
+}
+
+/// Sends the MCP request `body` with `headers` through the interceptor and
+/// the tool handler, as the gateway would.
+///
+/// Only `tools/call` requests are forwarded to the tool handler; other
+/// methods are answered with a JSON-RPC error after interception.
+///
+/// # Errors
+///
+/// Returns an error if the interceptor fails or forwards a request without
+/// a body.
+pub async fn call(headers: Headers, body: Value) -> Result<Exchange, Error> {
+    let event = json!({
+        "interceptorInputVersion": "1.0",
+        "mcp": {"gatewayRequest": {"headers": headers, "body": body}}
+    });
+    let intercepted = interceptor::handle(event).await?.mcp;
+    if let Some(response) = intercepted.transformed_gateway_response {
+        return Ok(Exchange {
+            forwarded: None,
+            response,
+        });
+    }
+    let forwarded = intercepted
+        .transformed_gateway_request
+        .ok_or("Interceptor neither forwarded nor answered the request")?;
+    let request = forwarded
+        .body
+        .clone()
+        .ok_or("Interceptor forwarded a request without a body")?;
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+
+    if request.get("method").and_then(Value::as_str) != Some("tools/call") {
+        return Ok(Exchange {
+            forwarded: Some(forwarded),
+            response: rpc_response(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {
+                    "code": METHOD_NOT_FOUND,
+                    "message": "Only tools/call is emulated"
+                }
+            })),
+        });
+    }
+
+    let tool = request
+        .pointer("/params/name")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let arguments = request
+        .pointer("/params/arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let result = function_handler(LambdaEvent::new(arguments, target_context(tool, &id))).await;
+    let (text, is_error) = match result {
+        Ok(output) => (output.to_string(), false),
+        Err(diagnostic) => (diagnostic.error_message, true),
+    };
+    Ok(Exchange {
+        forwarded: Some(forwarded),
+        response: rpc_response(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "content": [{"type": "text", "text": text}],
+                "isError": is_error
+            }
+        })),
+    })
+}
+
+/// The context the gateway invokes the tool Lambda with, carrying the tool
+/// name under the target prefix.
+fn target_context(tool: &str, id: &Value) -> Context {
+    let tool_name = format!("{TARGET_NAME}___{}", strip_gateway_prefix(tool));
+    let mut context = Context::default();
+    context.request_id = format!(
+        "gateway-{}",
+        id.as_str().map_or_else(|| id.to_string(), String::from)
+    );
+    // The runtime does not export the client context type, so it is built
+    // from the JSON the runtime would receive
+    context.client_context =
+        serde_json::from_value(json!({"custom": {"bedrockAgentCoreToolName": tool_name}})).ok();
+    context
+}
+
+fn rpc_response(body: Value) -> GatewayResponse {
+    GatewayResponse {
+        status_code: 200,
+        headers: Some(Headers::from([("Content-Type", "application/json")])),
+        body: Some(body),
+    }
+}
@@ -0,0 +1,279 @@
+//! Offline city lookup and spelling fallbacks for location names.
+//!
+//! [`lookup`] resolves the names of major cities from an embedded table of
+//! coordinates and timezones, so `get_weather` can skip the geocoding API
+//! for most requests. A name may carry a country qualifier (`Paris, FR` or
+//! `Paris, France`); any other qualifier, e.g. a US state, is left to the
+//! API, as are cities not in the table.
+//!
+//! When the geocoding API finds nothing for a location name, `get_weather`
+//! retries with [`spelling_variants`] of it: a well-known abbreviation
+//! expanded (`NYC` to `New York City`), a trailing qualifier dropped
+//! (`Paris, France`), abbreviated words spelled out (`St.` to `Saint`) and
+//! diacritics stripped. If none of them match, [`suggestions`] offers the
+//! closest names from the embedded table, ranked by edit distance, for the
+//! caller to choose from.
+
+use std::sync::LazyLock;
+
+/// Major world cities as `name,country_code,country,latitude,longitude,timezone`
+const CITIES_CSV: &str = include_str!("cities.csv");
+
+/// Most spelling variants tried per location, since each costs a request
+const MAX_VARIANTS: usize = 3;
+
+/// Whole names commonly written as an abbreviation or a former name
+const ALIASES: &[(&str, &str)] = &[
+    ("nyc", "New York City"),
+    ("ny", "New York City"),
+    ("new york", "New York City"),
+    ("la", "Los Angeles"),
+    ("sf", "San Francisco"),
+    ("dc", "Washington"),
+    ("kl", "Kuala Lumpur"),
+    ("hk", "Hong Kong"),
+    ("cdmx", "Mexico City"),
+    ("bkk", "Bangkok"),
+    ("rio", "Rio de Janeiro"),
+    ("philly", "Philadelphia"),
+    ("vegas", "Las Vegas"),
+    ("joburg", "Johannesburg"),
+    ("saigon", "Ho Chi Minh City"),
+    ("bombay", "Mumbai"),
+    ("calcutta", "Kolkata"),
+    ("madras", "Chennai"),
+    ("peking", "Beijing"),
+    ("kiev", "Kyiv"),
+];
+
+/// Abbreviated words within names
+const WORD_EXPANSIONS: &[(&str, &str)] = &[("st", "Saint"), ("ft", "Fort"), ("mt", "Mount")];
+
+/// A city in the embedded table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct City {
+    pub name: &'static str,
+    /// ISO 3166-1 alpha-2 country code
+    pub country_code: &'static str,
+    pub country: &'static str,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// IANA timezone name
+    pub timezone: &'static str,
+    /// Folded name, for matching
+    folded: String,
+}
+
+static CITIES: LazyLock<Vec<City>> =
+    LazyLock::new(|| CITIES_CSV.lines().skip(1).filter_map(parse_city).collect());
+
+/// Every city in the embedded table.
+#[must_use]
+pub fn cities() -> &'static [City] {
+    &CITIES
+}
+
+/// The embedded city `location` names, if it is one and any country
+/// qualifier after a comma matches it.
+///
+/// Abbreviations and former names such as `NYC` or `Bombay` resolve to the
+/// city they stand for.
+#[must_use]
+pub fn lookup(location: &str) -> Option<&'static City> {
+    let (name, qualifier) = split_qualifier(location);
+    let folded = fold(&expand_words(name));
+    let folded = ALIASES
+        .iter()
+        .find(|(short, _)| folded == *short)
+        .map_or(folded, |(_, alias)| fold(alias));
+    let city = CITIES.iter().find(|city| city.folded == folded)?;
+    match qualifier.map(fold) {
+        Some(qualifier)
+            if !qualifier.is_empty()
+                && qualifier != fold(city.country_code)
+                && qualifier != fold(city.country) =>
+        {
+            None
+        }
+        _ => Some(city),
+    }
+}
+
+/// Names of embedded cities starting with `prefix`, ignoring case, accents
+/// and punctuation, in alphabetical order.
+#[must_use]
+pub fn names_starting_with(prefix: &str) -> Vec<&'static str> {
+    let prefix = fold(prefix);
+    CITIES
+        .iter()
+        .filter(|city| city.folded.starts_with(&prefix))
+        .map(|city| city.name)
+        .collect()
+}
+
+/// Alternative spellings of `location` worth geocoding when it matches
+/// nothing, most likely first, without duplicates or `location` itself.
+#[must_use]
+pub fn spelling_variants(location: &str) -> Vec<String> {
+    let location = location.trim();
+    let (name, _) = split_qualifier(location);
+    let expanded = expand_words(name);
+
+    let mut candidates = Vec::new();
+    if let Some((_, alias)) = ALIASES.iter().find(|(short, _)| fold(name) == *short) {
+        candidates.push((*alias).to_string());
+    }
+    candidates.push(name.to_string());
+    let unaccented = fold_diacritics(&expanded);
+    candidates.push(expanded);
+    candidates.push(unaccented);
+
+    let mut seen = vec![location.to_lowercase()];
+    candidates.retain(|candidate| {
+        let key = candidate.to_lowercase();
+        if candidate.is_empty() || seen.contains(&key) {
+            return false;
+        }
+        seen.push(key);
+        true
+    });
+    candidates.truncate(MAX_VARIANTS);
+    candidates
+}
+
+/// Up to `limit` city names closest to `location`, closest first.
+///
+/// Names further than a third of the query's length in edit distance (at
+/// least one edit) are left out, so unrelated cities are never suggested.
+#[must_use]
+pub fn suggestions(location: &str, limit: usize) -> Vec<String> {
+    let (name, _) = split_qualifier(location);
+    let query = fold(&expand_words(name));
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = (query.chars().count() / 3).max(1);
+
+    let mut ranked: Vec<(usize, &'static str)> = CITIES
+        .iter()
+        .map(|city| (levenshtein(&query, &city.folded), city.name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    ranked.sort_unstable();
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, city)| city.to_string())
+        .collect()
+}
+
+/// Lowercases `text`, strips diacritics and collapses punctuation and
+/// whitespace, so spellings of a name compare equal.
+#[must_use]
+pub fn fold(text: &str) -> String {
+    fold_diacritics(&text.to_lowercase())
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Number of single-character insertions, deletions and substitutions
+/// needed to turn `a` into `b`.
+#[must_use]
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Splits a location into its name and the qualifier after the first comma.
+fn split_qualifier(location: &str) -> (&str, Option<&str>) {
+    match location.split_once(',') {
+        Some((name, qualifier)) => (name.trim(), Some(qualifier.trim())),
+        None => (location.trim(), None),
+    }
+}
+
+/// Reads one row of the embedded table, skipping malformed rows.
+fn parse_city(row: &'static str) -> Option<City> {
+    let mut fields = row.split(',').map(str::trim);
+    let name = fields.next().filter(|name| !name.is_empty())?;
+    let city = City {
+        name,
+        country_code: fields.next()?,
+        country: fields.next()?,
+        latitude: fields.next()?.parse().ok()?,
+        longitude: fields.next()?.parse().ok()?,
+        timezone: fields.next()?,
+        folded: fold(name),
+    };
+    fields.next().is_none().then_some(city)
+}
+
+/// Spells out abbreviated words such as `St.`, keeping the rest as written.
+fn expand_words(name: &str) -> String {
+    name.split_whitespace()
+        .map(|word| {
+            let bare = word.trim_end_matches('.').to_lowercase();
+            WORD_EXPANSIONS
+                .iter()
+                .find(|(short, _)| bare == *short)
+                .map_or(word, |(_, long)| long)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Replaces accented Latin letters with their unaccented forms.
+fn fold_diacritics(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => folded.push('a'),
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => folded.push('A'),
+            'ç' | 'ć' | 'č' => folded.push('c'),
+            'Ç' | 'Ć' | 'Č' => folded.push('C'),
+            'ď' | 'đ' => folded.push('d'),
+            'Ď' | 'Đ' => folded.push('D'),
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => folded.push('e'),
+            'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => folded.push('E'),
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => folded.push('i'),
+            'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'İ' => folded.push('I'),
+            'ł' => folded.push('l'),
+            'Ł' => folded.push('L'),
+            'ñ' | 'ń' | 'ň' => folded.push('n'),
+            'Ñ' | 'Ń' | 'Ň' => folded.push('N'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => folded.push('o'),
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => folded.push('O'),
+            'ř' => folded.push('r'),
+            'Ř' => folded.push('R'),
+            'ś' | 'š' | 'ş' | 'ș' => folded.push('s'),
+            'Ś' | 'Š' | 'Ş' | 'Ș' => folded.push('S'),
+            'ť' | 'ţ' | 'ț' => folded.push('t'),
+            'Ť' | 'Ţ' | 'Ț' => folded.push('T'),
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => folded.push('u'),
+            'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' => folded.push('U'),
+            'ý' | 'ÿ' => folded.push('y'),
+            'Ý' => folded.push('Y'),
+            'ź' | 'ż' | 'ž' => folded.push('z'),
+            'Ź' | 'Ż' | 'Ž' => folded.push('Z'),
+            'æ' => folded.push_str("ae"),
+            'Æ' => folded.push_str("AE"),
+            'œ' => folded.push_str("oe"),
+            'Œ' => folded.push_str("OE"),
+            'ß' => folded.push_str("ss"),
+            c => folded.push(c),
+        }
+    }
+    folded
+}
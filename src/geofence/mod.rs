@@ -0,0 +1,154 @@
+//! Regions each tenant's location-based tools may serve.
+//!
+//! Some tenants are only licensed for specific markets. A tenant's overlay
+//! (see [`crate::tenants`]) may set a [`Geofence`] of countries and bounding
+//! boxes, for example:
+//!
+//! ```json
+//! "geofence": {"countries": ["AU", "NZ"],
+//!              "bounding_boxes": [{"min_latitude": 1.1, "min_longitude": 103.6,
+//!                                  "max_latitude": 1.5, "max_longitude": 104.1}]}
+//! ```
+//!
+//! A place is inside the fence if it is in one of the countries or within
+//! one of the boxes; a fence listing neither serves nowhere. Boxes whose
+//! `min_longitude` is greater than their `max_longitude` cross the
+//! antimeridian.
+//!
+//! The fence is enforced after geocoding, so it applies to every tool that
+//! resolves a location name (`get_weather`, `render_weather_chart`,
+//! `get_pollen_forecast` and the forecast of `daily_briefing`): a best match
+//! outside the fence is refused with `GeofenceDenied`, and alternates
+//! outside it are left out of the response. `get_public_holidays` takes a
+//! country rather than a place, so it is only restricted by `countries`,
+//! when the fence lists any. Callers without a tenant, or whose tenant has
+//! no fence, are served everywhere.
+
+use crate::config::config;
+use crate::context;
+use crate::models::ResolvedLocation;
+use crate::models::error::AppError;
+use lambda_runtime::tracing::warn;
+use serde::Deserialize;
+
+/// The regions a tenant may serve.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Geofence {
+    /// ISO 3166-1 alpha-2 codes of the countries served, e.g. `["AU", "NZ"]`
+    pub countries: Vec<String>,
+    /// Areas served regardless of country
+    pub bounding_boxes: Vec<BoundingBox>,
+}
+
+/// An area between two latitudes and two longitudes, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub min_longitude: f64,
+    pub max_latitude: f64,
+    pub max_longitude: f64,
+}
+
+impl BoundingBox {
+    /// Whether the point at `latitude` and `longitude` is in the box.
+    #[must_use]
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        let within_longitudes = if self.min_longitude <= self.max_longitude {
+            (self.min_longitude..=self.max_longitude).contains(&longitude)
+        } else {
+            longitude >= self.min_longitude || longitude <= self.max_longitude
+        };
+        (self.min_latitude..=self.max_latitude).contains(&latitude) && within_longitudes
+    }
+}
+
+impl Geofence {
+    /// Whether `place` is inside the fence.
+    #[must_use]
+    pub fn contains(&self, place: &ResolvedLocation) -> bool {
+        let in_country = place
+            .country_code
+            .as_deref()
+            .is_some_and(|code| self.lists_country(code));
+        in_country
+            || self
+                .bounding_boxes
+                .iter()
+                .any(|area| area.contains(place.latitude, place.longitude))
+    }
+
+    /// Whether the country with ISO code `code` may be served; every country
+    /// may if the fence lists none.
+    #[must_use]
+    pub fn allows_country(&self, code: &str) -> bool {
+        self.countries.is_empty() || self.lists_country(code)
+    }
+
+    fn lists_country(&self, code: &str) -> bool {
+        self.countries
+            .iter()
+            .any(|country| country.trim().eq_ignore_ascii_case(code.trim()))
+    }
+}
+
+/// The fence of the current caller's tenant, if it has one.
+#[must_use]
+pub fn current() -> Option<Geofence> {
+    let tenant = context::current().tenant?;
+    config()
+        .tenants
+        .overlays
+        .get(&tenant)
+        .and_then(|overlay| overlay.geofence.clone())
+}
+
+/// Refuses `place` if it is outside the current caller's tenant's fence,
+/// and drops the `alternates` that are.
+///
+/// # Errors
+///
+/// Returns `AppError::GeofenceDenied` if `place` is outside the fence.
+pub fn check_place(
+    place: &ResolvedLocation,
+    alternates: &mut Vec<ResolvedLocation>,
+) -> Result<(), AppError> {
+    let Some(fence) = current() else {
+        return Ok(());
+    };
+    alternates.retain(|alternate| fence.contains(alternate));
+    if fence.contains(place) {
+        return Ok(());
+    }
+    warn!(place = %place.name, country = ?place.country_code, "Location outside the tenant's geofence");
+    Err(AppError::GeofenceDenied(format!(
+        "{} is outside the regions this tenant may serve",
+        describe(place)
+    )))
+}
+
+/// Refuses the country with ISO code `code` if the current caller's
+/// tenant's fence lists countries and not this one.
+///
+/// # Errors
+///
+/// Returns `AppError::GeofenceDenied` if the country may not be served.
+pub fn check_country(code: &str) -> Result<(), AppError> {
+    match current() {
+        Some(fence) if !fence.allows_country(code) => {
+            warn!(country = code, "Country outside the tenant's geofence");
+            Err(AppError::GeofenceDenied(format!(
+                "{code} is outside the countries this tenant may serve"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn describe(place: &ResolvedLocation) -> String {
+    place.country.as_ref().map_or_else(
+        || place.name.clone(),
+        |country| format!("{}, {country}", place.name),
+    )
+}
@@ -0,0 +1,25 @@
+//! gRPC interface to the tool core, for consumers outside Lambda.
+//!
+//! The `grpc-server` binary (built with the `grpc` feature) serves every
+//! registered tool as a method of the `aws_lambda_mcp.tools.v1.Tools`
+//! service, for running in the dev server environment or as a container on
+//! ECS inside the VPC. The protobuf definitions are generated from the same
+//! registry and schemas as `tool_schema.json` (see [`proto`]), so each tool
+//! has a typed request message; `grpc-server --proto` prints them for
+//! generating client stubs.
+//!
+//! Calls go through [`crate::handler::route_tool`] like Lambda invocations,
+//! with the bearer token from the `authorization` metadata as the caller's
+//! token and `x-request-id` as the correlation ID. With no gateway in front,
+//! the token is verified first (see [`verify`]) and the arguments reserved
+//! for the interceptor are removed from the call, as the gateway would. Besides the per-tool
+//! methods, `CallTool` calls a tool named at run time, and `CallTools`
+//! streams calls and their replies over one connection.
+
+pub mod proto;
+pub mod service;
+pub mod verify;
+
+pub use proto::ProtoFile;
+pub use service::ToolService;
+pub use verify::TokenVerifier;
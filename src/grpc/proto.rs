@@ -0,0 +1,505 @@
+//! Protobuf definitions generated from the published tool schemas.
+//!
+//! Each tool's published input schema (see [`crate::registry::bedrock_tool_schema`])
+//! becomes a request message: strings, integers, numbers and booleans map
+//! to `string`, `int64`, `double` and `bool` fields with explicit presence,
+//! so an omitted argument is told apart from a zero one; objects with
+//! properties become nested messages, lists become `repeated` fields, and
+//! free-form objects and anything else map to `google.protobuf.Struct`,
+//! `ListValue` or `Value`. Enum arguments stay strings, since their values
+//! are not valid protobuf identifiers. Fields are numbered in the order of
+//! their names, so clients regenerate their stubs when a tool's arguments
+//! change.
+//!
+//! Every field's JSON name is the argument name, so a request message
+//! converts to the tool's JSON arguments unchanged.
+
+use crate::registry::{ToolDescriptor, bedrock_tool_schema};
+use prost_reflect::{DescriptorError, DescriptorPool};
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, MethodDescriptorProto,
+    OneofDescriptorProto, ServiceDescriptorProto,
+};
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Package of the generated definitions
+pub const PACKAGE: &str = "aws_lambda_mcp.tools.v1";
+
+/// Name of the generated service within [`PACKAGE`]
+pub const SERVICE: &str = "Tools";
+
+/// Path the generated file is registered under
+const FILE_NAME: &str = "aws_lambda_mcp/tools/v1/tools.proto";
+
+/// Well-known types file the definitions import
+const STRUCT_FILE: &str = "google/protobuf/struct.proto";
+
+/// Response of every unary method
+pub const TOOL_RESULT: &str = "ToolResult";
+/// Request of the generic and streaming methods
+pub const TOOL_CALL: &str = "ToolCall";
+/// Response of the streaming method
+pub const TOOL_REPLY: &str = "ToolReply";
+/// Error carried in a [`TOOL_REPLY`]
+const TOOL_ERROR: &str = "ToolError";
+
+/// Method calling any tool by name
+pub const CALL_TOOL: &str = "CallTool";
+/// Method calling tools as their requests arrive on a stream
+pub const CALL_TOOLS: &str = "CallTools";
+
+/// The type of a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Int64,
+    Double,
+    Bool,
+    /// A message, by its name relative to the enclosing message and its
+    /// fully qualified name
+    Message {
+        name: String,
+        full_name: String,
+    },
+}
+
+impl FieldType {
+    fn well_known(name: &str) -> Self {
+        Self::Message {
+            name: format!("google.protobuf.{name}"),
+            full_name: format!(".google.protobuf.{name}"),
+        }
+    }
+
+    const fn proto_type(&self) -> Type {
+        match self {
+            Self::String => Type::String,
+            Self::Int64 => Type::Int64,
+            Self::Double => Type::Double,
+            Self::Bool => Type::Bool,
+            Self::Message { .. } => Type::Message,
+        }
+    }
+
+    fn keyword(&self) -> &str {
+        match self {
+            Self::String => "string",
+            Self::Int64 => "int64",
+            Self::Double => "double",
+            Self::Bool => "bool",
+            Self::Message { name, .. } => name,
+        }
+    }
+}
+
+/// A message field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    /// Name in the JSON form, the tool argument's name
+    pub json_name: String,
+    pub number: i32,
+    pub field_type: FieldType,
+    pub repeated: bool,
+    /// Whether the field has explicit presence (`optional`)
+    pub optional: bool,
+    pub description: Option<String>,
+}
+
+/// A message, with the messages nested in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub name: String,
+    pub description: Option<String>,
+    pub fields: Vec<Field>,
+    pub nested: Vec<Self>,
+}
+
+/// A service method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Method {
+    pub name: String,
+    /// Tool the method calls; `None` for methods naming the tool in the request
+    pub tool: Option<&'static str>,
+    pub input: String,
+    pub output: String,
+    pub streaming: bool,
+    pub description: String,
+}
+
+/// The generated definitions: one typed method per tool, plus the generic
+/// and streaming methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtoFile {
+    pub messages: Vec<Message>,
+    pub methods: Vec<Method>,
+}
+
+impl ProtoFile {
+    /// Generates the definitions for `tools`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a tool's published schema cannot be generated.
+    pub fn from_tools(tools: &[ToolDescriptor]) -> serde_json::Result<Self> {
+        let mut messages = Vec::new();
+        let mut methods = Vec::new();
+        for tool in tools {
+            let schema = bedrock_tool_schema(tool)?;
+            let method = pascal_case(tool.name);
+            let request = format!("{method}Request");
+            let scope = format!(".{PACKAGE}.{request}");
+            let mut message = message(&request, &scope, &schema["inputSchema"]);
+            message.description = Some(format!("Arguments of `{}`", tool.name));
+            messages.push(message);
+            methods.push(Method {
+                name: method,
+                tool: Some(tool.name),
+                input: request,
+                output: TOOL_RESULT.to_string(),
+                streaming: false,
+                description: tool.description.to_string(),
+            });
+        }
+        messages.extend(envelope_messages());
+        methods.push(Method {
+            name: CALL_TOOL.to_string(),
+            tool: None,
+            input: TOOL_CALL.to_string(),
+            output: TOOL_RESULT.to_string(),
+            streaming: false,
+            description: "Calls the tool named in the request with untyped arguments".to_string(),
+        });
+        methods.push(Method {
+            name: CALL_TOOLS.to_string(),
+            tool: None,
+            input: TOOL_CALL.to_string(),
+            output: TOOL_REPLY.to_string(),
+            streaming: true,
+            description: "Calls tools as their requests arrive, replying to each in order; failed calls are replied to with an error instead of ending the stream".to_string(),
+        });
+        Ok(Self { messages, methods })
+    }
+
+    /// The definitions as a file descriptor.
+    #[must_use]
+    pub fn descriptor(&self) -> FileDescriptorProto {
+        FileDescriptorProto {
+            name: Some(FILE_NAME.to_string()),
+            package: Some(PACKAGE.to_string()),
+            dependency: vec![STRUCT_FILE.to_string()],
+            message_type: self.messages.iter().map(descriptor_proto).collect(),
+            service: vec![ServiceDescriptorProto {
+                name: Some(SERVICE.to_string()),
+                method: self
+                    .methods
+                    .iter()
+                    .map(|method| MethodDescriptorProto {
+                        name: Some(method.name.clone()),
+                        input_type: Some(format!(".{PACKAGE}.{}", method.input)),
+                        output_type: Some(format!(".{PACKAGE}.{}", method.output)),
+                        client_streaming: Some(method.streaming),
+                        server_streaming: Some(method.streaming),
+                        ..MethodDescriptorProto::default()
+                    })
+                    .collect(),
+                ..ServiceDescriptorProto::default()
+            }],
+            syntax: Some("proto3".to_string()),
+            ..FileDescriptorProto::default()
+        }
+    }
+
+    /// A descriptor pool holding the definitions and the well-known types
+    /// they use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the generated descriptor is invalid.
+    pub fn pool(&self) -> Result<DescriptorPool, DescriptorError> {
+        let mut pool = DescriptorPool::global();
+        pool.add_file_descriptor_proto(self.descriptor())?;
+        Ok(pool)
+    }
+
+    /// The definitions as `.proto` source, for generating client stubs.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("// Generated from the tool schemas by `grpc-server --proto`; do not edit.\n");
+        out.push_str("syntax = \"proto3\";\n\n");
+        let _ = writeln!(out, "package {PACKAGE};\n");
+        let _ = writeln!(out, "import \"{STRUCT_FILE}\";\n");
+        let _ = writeln!(out, "service {SERVICE} {{");
+        for method in &self.methods {
+            comment(&mut out, "  ", Some(&method.description));
+            let stream = if method.streaming { "stream " } else { "" };
+            let _ = writeln!(
+                out,
+                "  rpc {}({stream}{}) returns ({stream}{});",
+                method.name, method.input, method.output
+            );
+        }
+        out.push_str("}\n");
+        for message in &self.messages {
+            out.push('\n');
+            render_message(&mut out, message, "");
+        }
+        out
+    }
+}
+
+/// `snake_case` or other separated words as `PascalCase`.
+#[must_use]
+pub fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// A message for the object `schema`, nested types qualified by `scope`.
+fn message(name: &str, scope: &str, schema: &Value) -> Message {
+    let mut message = Message {
+        name: name.to_string(),
+        description: None,
+        fields: Vec::new(),
+        nested: Vec::new(),
+    };
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return message;
+    };
+    for (number, (json_name, property)) in (1..).zip(properties) {
+        let (field_type, repeated) = field_type(json_name, scope, property, &mut message.nested);
+        message.fields.push(Field {
+            name: field_name(json_name),
+            json_name: json_name.clone(),
+            number,
+            optional: !repeated && !matches!(field_type, FieldType::Message { .. }),
+            field_type,
+            repeated,
+            description: property
+                .get("description")
+                .and_then(Value::as_str)
+                .map(String::from),
+        });
+    }
+    message
+}
+
+/// The type of the property `name` described by `schema`, and whether it is
+/// repeated; object types are added to `nested`.
+fn field_type(
+    name: &str,
+    scope: &str,
+    schema: &Value,
+    nested: &mut Vec<Message>,
+) -> (FieldType, bool) {
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => (FieldType::String, false),
+        Some("integer") => (FieldType::Int64, false),
+        Some("number") => (FieldType::Double, false),
+        Some("boolean") => (FieldType::Bool, false),
+        Some("object")
+            if schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .is_some_and(|properties| !properties.is_empty()) =>
+        {
+            let type_name = pascal_case(name);
+            let full_name = format!("{scope}.{type_name}");
+            nested.push(message(&type_name, &full_name, schema));
+            (
+                FieldType::Message {
+                    name: type_name,
+                    full_name,
+                },
+                false,
+            )
+        }
+        Some("object") => (FieldType::well_known("Struct"), false),
+        Some("array") => {
+            let items = schema.get("items").unwrap_or(&Value::Null);
+            let is_list = items.get("type").and_then(Value::as_str) == Some("array");
+            match field_type(name, scope, items, nested) {
+                (item, false) if !is_list && item != FieldType::well_known("Value") => (item, true),
+                _ => (FieldType::well_known("ListValue"), false),
+            }
+        }
+        _ => (FieldType::well_known("Value"), false),
+    }
+}
+
+/// A valid field name for the argument `json_name`.
+fn field_name(json_name: &str) -> String {
+    let name: String = json_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("field_{name}")
+    }
+}
+
+/// The messages of the generic and streaming methods.
+fn envelope_messages() -> Vec<Message> {
+    let field = |name: &str, number, field_type, description: &str| Field {
+        name: name.to_string(),
+        json_name: name.to_string(),
+        number,
+        field_type,
+        repeated: false,
+        optional: false,
+        description: Some(description.to_string()),
+    };
+    let error = FieldType::Message {
+        name: TOOL_ERROR.to_string(),
+        full_name: format!(".{PACKAGE}.{TOOL_ERROR}"),
+    };
+    let message = |name: &str, description: &str, fields| Message {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        fields,
+        nested: Vec::new(),
+    };
+    vec![
+        message(
+            TOOL_CALL,
+            "A call to a tool named at run time",
+            vec![
+                field("tool", 1, FieldType::String, "Name of the tool to call"),
+                field(
+                    "arguments",
+                    2,
+                    FieldType::well_known("Struct"),
+                    "Arguments, as in the tool's JSON schema",
+                ),
+                field(
+                    "id",
+                    3,
+                    FieldType::String,
+                    "Echoed in the reply, to match replies to calls",
+                ),
+            ],
+        ),
+        message(
+            TOOL_RESULT,
+            "A tool's response",
+            vec![field(
+                "result",
+                1,
+                FieldType::well_known("Struct"),
+                "Response, as in the tool's JSON output schema",
+            )],
+        ),
+        message(
+            TOOL_REPLY,
+            "A reply to one call on a stream",
+            vec![
+                field("id", 1, FieldType::String, "ID of the call replied to"),
+                field("tool", 2, FieldType::String, "Tool that was called"),
+                field(
+                    "result",
+                    3,
+                    FieldType::well_known("Struct"),
+                    "Response, when the call succeeded",
+                ),
+                field("error", 4, error, "Failure, when the call failed"),
+            ],
+        ),
+        message(
+            TOOL_ERROR,
+            "Why a call failed",
+            vec![
+                field(
+                    "error_type",
+                    1,
+                    FieldType::String,
+                    "Error type, e.g. InvalidInput or Unauthorized",
+                ),
+                field("message", 2, FieldType::String, "Error message"),
+            ],
+        ),
+    ]
+}
+
+fn descriptor_proto(message: &Message) -> DescriptorProto {
+    let mut proto = DescriptorProto {
+        name: Some(message.name.clone()),
+        nested_type: message.nested.iter().map(descriptor_proto).collect(),
+        ..DescriptorProto::default()
+    };
+    for field in &message.fields {
+        // proto3 `optional` fields are each the only member of a synthetic oneof
+        let oneof_index = field.optional.then(|| {
+            proto.oneof_decl.push(OneofDescriptorProto {
+                name: Some(format!("_{}", field.name)),
+                ..OneofDescriptorProto::default()
+            });
+            i32::try_from(proto.oneof_decl.len() - 1).unwrap_or_default()
+        });
+        let label = if field.repeated {
+            Label::Repeated
+        } else {
+            Label::Optional
+        };
+        proto.field.push(FieldDescriptorProto {
+            name: Some(field.name.clone()),
+            number: Some(field.number),
+            label: Some(label as i32),
+            r#type: Some(field.field_type.proto_type() as i32),
+            type_name: match &field.field_type {
+                FieldType::Message { full_name, .. } => Some(full_name.clone()),
+                _ => None,
+            },
+            json_name: Some(field.json_name.clone()),
+            oneof_index,
+            proto3_optional: field.optional.then_some(true),
+            ..FieldDescriptorProto::default()
+        });
+    }
+    proto
+}
+
+fn render_message(out: &mut String, message: &Message, indent: &str) {
+    comment(out, indent, message.description.as_deref());
+    let _ = writeln!(out, "{indent}message {} {{", message.name);
+    let inner = format!("{indent}  ");
+    for field in &message.fields {
+        comment(out, &inner, field.description.as_deref());
+        let label = match (field.repeated, field.optional) {
+            (true, _) => "repeated ",
+            (false, true) => "optional ",
+            (false, false) => "",
+        };
+        let _ = writeln!(
+            out,
+            "{inner}{label}{} {} = {} [json_name = \"{}\"];",
+            field.field_type.keyword(),
+            field.name,
+            field.number,
+            field.json_name
+        );
+    }
+    for nested in &message.nested {
+        out.push('\n');
+        render_message(out, nested, &inner);
+    }
+    let _ = writeln!(out, "{indent}}}");
+}
+
+fn comment(out: &mut String, indent: &str, text: Option<&str>) {
+    for line in text.into_iter().flat_map(str::lines) {
+        let _ = writeln!(out, "{indent}// {line}");
+    }
+}
@@ -0,0 +1,416 @@
+//! The tonic service answering the generated methods.
+//!
+//! Messages are decoded as [`DynamicMessage`]s of the generated descriptors
+//! and converted to and from the tools' JSON, so the service needs no code
+//! generated at build time and always matches the registry it was built
+//! from.
+
+use super::proto::{PACKAGE, ProtoFile, SERVICE};
+use super::verify::TokenVerifier;
+use crate::auth::AUTH_TOKEN_ARG;
+use crate::handler::route_tool;
+use crate::interceptor::RESERVED_ARGS;
+use crate::logging;
+use crate::registry::tools;
+use anyhow::{Context as _, anyhow};
+use lambda_runtime::Diagnostic;
+use lambda_runtime::tracing::{Instrument, warn};
+use prost::Message;
+use prost_reflect::{DeserializeOptions, DynamicMessage, MessageDescriptor, SerializeOptions};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::body::BoxBody;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::{BoxFuture, Service, empty_body, http};
+use tonic::metadata::{GRPC_CONTENT_TYPE, MetadataMap};
+use tonic::server::{Grpc, NamedService, StreamingService, UnaryService};
+use tonic::{Code, Request, Response, Status, Streaming};
+
+/// Replies buffered on a stream before calls wait for the client to read
+const REPLY_BUFFER: usize = 16;
+
+/// A generated method and the messages it exchanges.
+#[derive(Clone)]
+struct Route {
+    tool: Option<&'static str>,
+    input: MessageDescriptor,
+    output: MessageDescriptor,
+    streaming: bool,
+}
+
+/// Serves the methods generated from the registry (see [`super::proto`]).
+#[derive(Clone)]
+pub struct ToolService {
+    routes: Arc<HashMap<String, Route>>,
+    verifier: Arc<TokenVerifier>,
+}
+
+impl ToolService {
+    /// Builds the service for every registered tool, accepting callers whose
+    /// tokens `verifier` verifies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the definitions cannot be generated or are
+    /// invalid.
+    pub fn new(verifier: TokenVerifier) -> anyhow::Result<Self> {
+        let file =
+            ProtoFile::from_tools(tools()).context("Failed to generate the tool definitions")?;
+        Self::from_file(&file, verifier)
+    }
+
+    /// Builds the service for the definitions in `file`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the definitions are invalid.
+    pub fn from_file(file: &ProtoFile, verifier: TokenVerifier) -> anyhow::Result<Self> {
+        let pool = file
+            .pool()
+            .context("Generated tool definitions are invalid")?;
+        let message = |name: &str| {
+            pool.get_message_by_name(&format!("{PACKAGE}.{name}"))
+                .ok_or_else(|| anyhow!("Message {name} is not defined"))
+        };
+        let routes = file
+            .methods
+            .iter()
+            .map(|method| {
+                Ok((
+                    method.name.clone(),
+                    Route {
+                        tool: method.tool,
+                        input: message(&method.input)?,
+                        output: message(&method.output)?,
+                        streaming: method.streaming,
+                    },
+                ))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            routes: Arc::new(routes),
+            verifier: Arc::new(verifier),
+        })
+    }
+}
+
+impl NamedService for ToolService {
+    // `PACKAGE` and `SERVICE`, which a constant cannot concatenate
+    const NAME: &'static str = "aws_lambda_mcp.tools.v1.Tools";
+}
+
+impl<B> Service<http::Request<B>> for ToolService
+where
+    B: tonic::codegen::Body + Send + 'static,
+    B::Error: Into<tonic::codegen::StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let Some(route) = self.routes.get(&method).cloned() else {
+            warn!(method = %method, "Unknown gRPC method called on {SERVICE}");
+            return Box::pin(async move { Ok(unimplemented()) });
+        };
+        let verifier = Arc::clone(&self.verifier);
+        Box::pin(async move {
+            let mut grpc = Grpc::new(DynamicCodec {
+                input: route.input.clone(),
+            });
+            Ok(if route.streaming {
+                grpc.streaming(StreamingCall { route, verifier }, request)
+                    .await
+            } else {
+                grpc.unary(UnaryCall { route, verifier }, request).await
+            })
+        })
+    }
+}
+
+struct UnaryCall {
+    route: Route,
+    verifier: Arc<TokenVerifier>,
+}
+
+impl UnaryService<DynamicMessage> for UnaryCall {
+    type Response = DynamicMessage;
+    type Future = BoxFuture<Response<DynamicMessage>, Status>;
+
+    fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
+        let route = self.route.clone();
+        let verifier = Arc::clone(&self.verifier);
+        Box::pin(async move {
+            let (metadata, _, message) = request.into_parts();
+            let token = authenticate(&verifier, &metadata).await?;
+            let (tool, arguments) = match route.tool {
+                Some(tool) => (
+                    tool.to_string(),
+                    to_json(&message).map_err(|e| invalid_request(&e))?,
+                ),
+                None => call_arguments(&to_json(&message).map_err(|e| invalid_request(&e))?)
+                    .ok_or_else(missing_tool)?,
+            };
+            let result = call(&tool, arguments, &token, &metadata)
+                .await
+                .map_err(|diagnostic| status(&diagnostic))?;
+            from_json(&route.output, &json!({ "result": result }))
+                .map(Response::new)
+                .map_err(|e| encode_failed(&e))
+        })
+    }
+}
+
+struct StreamingCall {
+    route: Route,
+    verifier: Arc<TokenVerifier>,
+}
+
+impl StreamingService<DynamicMessage> for StreamingCall {
+    type Response = DynamicMessage;
+    type ResponseStream = ReceiverStream<Result<DynamicMessage, Status>>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<Streaming<DynamicMessage>>) -> Self::Future {
+        let output = self.route.output.clone();
+        let verifier = Arc::clone(&self.verifier);
+        Box::pin(async move {
+            let (metadata, _, mut calls) = request.into_parts();
+            // Authenticated once, when the stream is opened
+            let token = authenticate(&verifier, &metadata).await?;
+            let (replies, stream) = mpsc::channel(REPLY_BUFFER);
+            tokio::spawn(async move {
+                while let Some(message) = calls.message().await.transpose() {
+                    let (reply, ended) = match message {
+                        Ok(message) => (reply(&output, &message, &token, &metadata).await, false),
+                        Err(status) => (Err(status), true),
+                    };
+                    // A closed channel means the client has gone away
+                    if replies.send(reply).await.is_err() || ended {
+                        break;
+                    }
+                }
+            });
+            Ok(Response::new(ReceiverStream::new(stream)))
+        })
+    }
+}
+
+/// The response to a method that is not defined, as generated servers
+/// answer it.
+fn unimplemented() -> http::Response<BoxBody> {
+    let mut response = http::Response::new(empty_body());
+    let headers = response.headers_mut();
+    headers.insert(Status::GRPC_STATUS, (Code::Unimplemented as i32).into());
+    headers.insert(http::header::CONTENT_TYPE, GRPC_CONTENT_TYPE);
+    response
+}
+
+/// Calls the tool `message` names and wraps the outcome in a reply.
+async fn reply(
+    output: &MessageDescriptor,
+    message: &DynamicMessage,
+    token: &str,
+    metadata: &MetadataMap,
+) -> Result<DynamicMessage, Status> {
+    let call_message = to_json(message).map_err(|e| invalid_request(&e))?;
+    let id = call_message.get("id").cloned().unwrap_or_default();
+    let (tool, arguments) = call_arguments(&call_message).ok_or_else(missing_tool)?;
+    let reply = match call(&tool, arguments, token, metadata).await {
+        Ok(result) => json!({ "id": id, "tool": tool, "result": result }),
+        Err(diagnostic) => json!({
+            "id": id,
+            "tool": tool,
+            "error": {
+                "error_type": diagnostic.error_type,
+                "message": diagnostic.error_message,
+            },
+        }),
+    };
+    from_json(output, &reply).map_err(|e| encode_failed(&e))
+}
+
+/// The verified bearer token from the `authorization` metadata.
+///
+/// # Errors
+///
+/// Returns `Unauthenticated` if there is no token or `verifier` rejects it.
+pub async fn authenticate(
+    verifier: &TokenVerifier,
+    metadata: &MetadataMap,
+) -> Result<String, Status> {
+    let token = metadata
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.strip_prefix("Bearer ").unwrap_or(value).trim())
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| Status::unauthenticated("A bearer token is required"))?;
+    verifier.verify(token).await?;
+    Ok(token.to_string())
+}
+
+/// `arguments` as the interceptor would forward them: without the reserved
+/// arguments the client sent and with the verified `token` as the caller's.
+#[must_use]
+pub fn caller_arguments(mut arguments: Value, token: &str) -> Value {
+    if let Some(fields) = arguments.as_object_mut() {
+        for name in RESERVED_ARGS {
+            fields.remove(*name);
+        }
+        fields.insert(AUTH_TOKEN_ARG.to_string(), Value::String(token.to_string()));
+    }
+    arguments
+}
+
+/// Routes a call to `tool` like the Lambda handler, with the verified bearer
+/// token as the caller's token.
+async fn call(
+    tool: &str,
+    arguments: Value,
+    token: &str,
+    metadata: &MetadataMap,
+) -> Result<Value, Diagnostic> {
+    let arguments = caller_arguments(arguments, token);
+    let correlation_id = metadata
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let payload = json!({ "params": { "name": tool, "arguments": arguments } });
+    route_tool(tool, payload)
+        .instrument(logging::invocation_span(tool, correlation_id))
+        .await
+}
+
+/// The tool name and arguments of a [`super::proto::TOOL_CALL`] in JSON
+/// form, or `None` if it names no tool.
+fn call_arguments(call: &Value) -> Option<(String, Value)> {
+    let tool = call
+        .get("tool")
+        .and_then(Value::as_str)
+        .filter(|tool| !tool.is_empty())?;
+    let arguments = call.get("arguments").cloned().unwrap_or_else(|| json!({}));
+    Some((tool.to_string(), arguments))
+}
+
+/// The status for a [`super::proto::TOOL_CALL`] that names no tool.
+fn missing_tool() -> Status {
+    Status::invalid_argument("The tool to call is required")
+}
+
+/// The status for a request message with no JSON form.
+fn invalid_request(error: &serde_json::Error) -> Status {
+    Status::invalid_argument(format!("Invalid request: {error}"))
+}
+
+/// The status for a response that does not fit its message type.
+fn encode_failed(error: &serde_json::Error) -> Status {
+    Status::internal(format!("Failed to encode response: {error}"))
+}
+
+/// The JSON form of `message`, with 64-bit integers as numbers.
+///
+/// # Errors
+///
+/// Returns an error if `message` has no JSON form, e.g. a non-finite
+/// `double`.
+pub fn to_json(message: &DynamicMessage) -> Result<Value, serde_json::Error> {
+    let options = SerializeOptions::new().stringify_64_bit_integers(false);
+    message.serialize_with_options(serde_json::value::Serializer, &options)
+}
+
+/// A message of type `descriptor` from its JSON form, ignoring fields the
+/// descriptor does not define.
+///
+/// # Errors
+///
+/// Returns an error if `value` does not fit the descriptor.
+pub fn from_json(
+    descriptor: &MessageDescriptor,
+    value: &Value,
+) -> Result<DynamicMessage, serde_json::Error> {
+    let options = DeserializeOptions::new().deny_unknown_fields(false);
+    DynamicMessage::deserialize_with_options(descriptor.clone(), value, &options)
+}
+
+/// The gRPC status for a tool's error diagnostic.
+#[must_use]
+pub fn status(diagnostic: &Diagnostic) -> Status {
+    let code = match diagnostic.error_type.as_str() {
+        "InvalidInput" => Code::InvalidArgument,
+        "Unauthorized" => Code::PermissionDenied,
+        "UnknownTool" => Code::NotFound,
+        "ToolDisabled" => Code::FailedPrecondition,
+        "QuotaExceeded" => Code::ResourceExhausted,
+        "TooBusy" | "Overloaded" => Code::Unavailable,
+        _ => Code::Internal,
+    };
+    if code == Code::Internal {
+        warn!(error_type = %diagnostic.error_type, "Tool failed over gRPC");
+    }
+    Status::new(code, diagnostic.error_message.clone())
+}
+
+/// Encodes and decodes [`DynamicMessage`]s of the method's types.
+struct DynamicCodec {
+    input: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder {
+            input: self.input.clone(),
+        }
+    }
+}
+
+struct DynamicEncoder;
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: DynamicMessage, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(format!("Failed to encode message: {e}")))
+    }
+}
+
+struct DynamicDecoder {
+    input: MessageDescriptor,
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<DynamicMessage>, Status> {
+        DynamicMessage::decode(self.input.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::invalid_argument(format!("Failed to decode message: {e}")))
+    }
+}
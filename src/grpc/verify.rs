@@ -0,0 +1,190 @@
+//! Bearer token verification for the gRPC server.
+//!
+//! Lambda invocations only arrive through the gateway, which validates the
+//! caller's JWT before the interceptor forwards it, so [`crate::auth`] only
+//! decodes the claims. Nothing stands in front of the gRPC server, so it
+//! verifies each token itself before routing a call: the signature against
+//! the issuer's JSON Web Key Set, then the issuer, audience and expiry.
+//! Only asymmetric keys are used, so a token signed with a shared secret,
+//! or not signed at all, is rejected.
+//!
+//! [`TokenVerifier::from_env`] reads the issuer and key set location from the
+//! `OpenID` discovery document at `GRPC_OIDC_DISCOVERY_URL`, the document the
+//! gateway's authorizer uses, and the accepted audiences from the
+//! comma-separated `GRPC_TOKEN_AUDIENCES`. A token signed with a key the set
+//! does not hold refetches it, at most every [`JWKS_REFRESH_INTERVAL`], so
+//! rotated keys are picked up without a restart.
+
+use anyhow::{Context as _, bail};
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
+use jsonwebtoken::{DecodingKey, Validation, decode, decode_header};
+use lambda_runtime::tracing::warn;
+use reqwest::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::env;
+use std::sync::{LazyLock, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+use tonic::Status;
+
+/// Shortest time between two fetches of the key set
+pub const JWKS_REFRESH_INTERVAL: Duration = Duration::from_mins(5);
+
+/// Claims every token must carry
+const REQUIRED_CLAIMS: &[&str] = &["exp", "iss", "aud"];
+
+/// Client for the discovery document and key set, which are deployment
+/// configuration rather than tool traffic, so outside the egress policy
+static CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
+
+/// The fields of an `OpenID` discovery document the verifier reads.
+#[derive(Deserialize)]
+struct Discovery {
+    issuer: String,
+    jwks_uri: String,
+}
+
+/// Verifies bearer tokens against an issuer's signing keys.
+pub struct TokenVerifier {
+    issuer: String,
+    audiences: Vec<String>,
+    jwks_uri: Option<String>,
+    keys: RwLock<JwkSet>,
+    fetched: Mutex<Instant>,
+}
+
+impl TokenVerifier {
+    /// A verifier for tokens from `issuer` for any of `audiences`, signed
+    /// with one of `keys`, which are never refetched.
+    #[must_use]
+    pub fn new(keys: JwkSet, issuer: impl Into<String>, audiences: Vec<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            audiences,
+            jwks_uri: None,
+            keys: RwLock::new(keys),
+            fetched: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// A verifier for the issuer `discovery_url` describes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no audience is given or the discovery document or
+    /// key set cannot be fetched.
+    pub async fn discover(discovery_url: &str, audiences: Vec<String>) -> anyhow::Result<Self> {
+        if audiences.is_empty() {
+            bail!("At least one token audience is required");
+        }
+        let discovery: Discovery = fetch(discovery_url)
+            .await
+            .context("Failed to fetch the OpenID discovery document")?;
+        let keys = fetch(&discovery.jwks_uri)
+            .await
+            .context("Failed to fetch the token signing keys")?;
+        Ok(Self {
+            jwks_uri: Some(discovery.jwks_uri),
+            ..Self::new(keys, discovery.issuer, audiences)
+        })
+    }
+
+    /// A verifier configured by `GRPC_OIDC_DISCOVERY_URL` and
+    /// `GRPC_TOKEN_AUDIENCES`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either is unset, so the server never starts
+    /// without verifying tokens, or if [`Self::discover`] fails.
+    pub async fn from_env() -> anyhow::Result<Self> {
+        let discovery_url = env::var("GRPC_OIDC_DISCOVERY_URL")
+            .context("GRPC_OIDC_DISCOVERY_URL is required to verify tokens")?;
+        let audiences = env::var("GRPC_TOKEN_AUDIENCES")
+            .context("GRPC_TOKEN_AUDIENCES is required to verify tokens")?
+            .split(',')
+            .map(str::trim)
+            .filter(|audience| !audience.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self::discover(&discovery_url, audiences).await
+    }
+
+    /// Verifies `token`'s signature, issuer, audience and expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unauthenticated` if the token is malformed, unsigned, signed
+    /// with a key the issuer does not publish or a shared secret, or its
+    /// claims do not match.
+    pub async fn verify(&self, token: &str) -> Result<(), Status> {
+        let header = decode_header(token).map_err(|e| invalid_token(&e))?;
+        let kid = header
+            .kid
+            .ok_or_else(|| Status::unauthenticated("The token names no signing key"))?;
+        let jwk = if let Some(jwk) = self.key(&kid) {
+            jwk
+        } else {
+            self.refresh().await;
+            self.key(&kid)
+                .ok_or_else(|| Status::unauthenticated("The token's signing key is unknown"))?
+        };
+        if matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)) {
+            return Err(Status::unauthenticated(
+                "Tokens signed with a shared secret are not accepted",
+            ));
+        }
+        let key = DecodingKey::from_jwk(&jwk).map_err(|e| invalid_token(&e))?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&self.audiences);
+        validation.set_required_spec_claims(REQUIRED_CLAIMS);
+        decode::<Value>(token, &key, &validation)
+            .map(|_| ())
+            .map_err(|e| invalid_token(&e))
+    }
+
+    /// The signing key named `kid`, if the set holds it.
+    fn key(&self, kid: &str) -> Option<Jwk> {
+        self.keys
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .find(kid)
+            .cloned()
+    }
+
+    /// Refetches the key set, unless it was fetched within
+    /// [`JWKS_REFRESH_INTERVAL`] or is fixed.
+    async fn refresh(&self) {
+        let Some(jwks_uri) = &self.jwks_uri else {
+            return;
+        };
+        {
+            let mut fetched = self.fetched.lock().unwrap_or_else(PoisonError::into_inner);
+            if fetched.elapsed() < JWKS_REFRESH_INTERVAL {
+                return;
+            }
+            *fetched = Instant::now();
+        }
+        match fetch(jwks_uri).await {
+            Ok(keys) => *self.keys.write().unwrap_or_else(PoisonError::into_inner) = keys,
+            Err(e) => warn!(error = %e, "Failed to refresh the token signing keys"),
+        }
+    }
+}
+
+/// Fetches and parses the JSON document at `url`.
+async fn fetch<T: DeserializeOwned>(url: &str) -> anyhow::Result<T> {
+    Ok(CLIENT
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// The status for a token that fails verification.
+fn invalid_token(error: &jsonwebtoken::errors::Error) -> Status {
+    Status::unauthenticated(format!("Invalid auth token: {error}"))
+}
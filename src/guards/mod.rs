@@ -0,0 +1,173 @@
+//! Size and nesting guards for untrusted request bodies.
+//!
+//! The interceptor sees every request the gateway receives, before anything
+//! has checked it. A body of many megabytes, or one nested thousands of
+//! levels deep, costs memory and stack to walk, serialize and pass on. Each
+//! body is checked against [`BodyLimits`] first, from
+//! `INTERCEPTOR_MAX_BODY_BYTES` (default 1 MiB) and
+//! `INTERCEPTOR_MAX_BODY_DEPTH` (default 32), and a body over either limit
+//! is refused with an `E1008` error instead of being forwarded.
+//!
+//! Bodies that arrive as JSON text are measured and scanned for nesting
+//! without being parsed; bodies that arrive as JSON values are measured by
+//! counting their serialized bytes, stopping at the limit, and walked
+//! without recursion.
+
+use serde_json::Value;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Bounds on a request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimits {
+    /// Most bytes of serialized JSON
+    pub max_bytes: usize,
+    /// Most levels of nested objects and arrays
+    pub max_depth: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+            max_depth: 32,
+        }
+    }
+}
+
+/// Why a body was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyRejection {
+    /// Larger than `limit` bytes
+    TooLarge { limit: usize },
+    /// Nested deeper than `limit` levels
+    TooDeep { limit: usize },
+}
+
+impl BodyRejection {
+    /// HTTP status of the refusal: 413 for a body too large, 400 otherwise.
+    #[must_use]
+    pub const fn status_code(&self) -> u16 {
+        match self {
+            Self::TooLarge { .. } => 413,
+            Self::TooDeep { .. } => 400,
+        }
+    }
+}
+
+impl fmt::Display for BodyRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { limit } => write!(f, "body is larger than {limit} bytes"),
+            Self::TooDeep { limit } => write!(f, "body is nested deeper than {limit} levels"),
+        }
+    }
+}
+
+/// Checks `body` against `limits`; JSON text is checked as text.
+///
+/// # Errors
+///
+/// Returns the limit `body` exceeds.
+pub fn check_body(body: &Value, limits: BodyLimits) -> Result<(), BodyRejection> {
+    match body {
+        Value::String(text) => check_text(text, limits),
+        value => check_value(value, limits),
+    }
+}
+
+/// Checks JSON `text` against `limits` without parsing it.
+///
+/// # Errors
+///
+/// Returns the limit `text` exceeds.
+pub fn check_text(text: &str, limits: BodyLimits) -> Result<(), BodyRejection> {
+    if text.len() > limits.max_bytes {
+        return Err(BodyRejection::TooLarge {
+            limit: limits.max_bytes,
+        });
+    }
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for byte in text.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > limits.max_depth {
+                    return Err(BodyRejection::TooDeep {
+                        limit: limits.max_depth,
+                    });
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Checks a parsed JSON `value` against `limits`.
+///
+/// # Errors
+///
+/// Returns the limit `value` exceeds.
+pub fn check_value(value: &Value, limits: BodyLimits) -> Result<(), BodyRejection> {
+    // Values with the number of objects and arrays enclosing them
+    let mut pending = vec![(value, 0usize)];
+    while let Some((value, enclosing)) = pending.pop() {
+        if !(value.is_object() || value.is_array()) {
+            continue;
+        }
+        if enclosing >= limits.max_depth {
+            return Err(BodyRejection::TooDeep {
+                limit: limits.max_depth,
+            });
+        }
+        match value {
+            Value::Object(fields) => {
+                pending.extend(fields.values().map(|field| (field, enclosing + 1)));
+            }
+            Value::Array(items) => pending.extend(items.iter().map(|item| (item, enclosing + 1))),
+            _ => {}
+        }
+    }
+
+    let mut counter = ByteCounter {
+        bytes: 0,
+        limit: limits.max_bytes,
+    };
+    serde_json::to_writer(&mut counter, value).map_err(|_| BodyRejection::TooLarge {
+        limit: limits.max_bytes,
+    })
+}
+
+/// Counts bytes written, failing once they pass `limit`.
+struct ByteCounter {
+    bytes: usize,
+    limit: usize,
+}
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes += buf.len();
+        if self.bytes > self.limit {
+            return Err(io::Error::other("body limit exceeded"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
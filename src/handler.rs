@@ -1,11 +1,82 @@
 use anyhow::Result;
-use lambda_runtime::tracing::{debug, error, info};
+use lambda_runtime::tracing::{Instrument, debug, error, info};
 use lambda_runtime::{Context, Diagnostic, LambdaEvent};
 use serde_json::Value;
+use std::time::Instant;
+use tokio::sync::OwnedSemaphorePermit;
 
-use crate::models::{PersonalizedGreetingRequest, WeatherRequest};
-use crate::tools::{get_personalized_greeting, get_weather};
+use crate::admin;
+use crate::arguments;
+use crate::async_calls;
+use crate::audit::{self, InvocationRecord};
+use crate::auth::{AUTH_TOKEN_ARG, authorize_tool_call, decode_claims};
+use crate::capture::{self, Recorder};
+use crate::catalog;
+use crate::codec;
+use crate::completion;
+use crate::compression;
+use crate::config::config;
+use crate::confirmation::{self, Confirmation};
+use crate::consent;
+use crate::context::{self, ToolContext};
+use crate::crypto::decrypt_args;
+use crate::deadline;
+use crate::defaults;
+use crate::dry_run;
+use crate::export::{self, OutputFormat};
+use crate::identity::{IdentityContext, apply_verified_identity};
+use crate::limits::{acquire_tool_permit, enter_invocation};
+use crate::logging;
+use crate::memo;
+use crate::memory::Interaction;
+use crate::metrics::{self, ContentMetrics};
+use crate::models::InvocationOutcome;
+use crate::models::completion::COMPLETE_METHOD;
+use crate::projection::{self, Projection};
+use crate::provenance::{self, SESSION_CACHE_SOURCE};
+use crate::quotas::{self, QuotaWarning};
+use crate::registry::{self, ToolDescriptor};
+use crate::secrets;
+use crate::shapes;
+use crate::slo;
+use crate::tenants;
+use crate::transform;
+use crate::usage;
 use crate::utils::strip_gateway_prefix;
+use crate::webhooks;
+
+/// Detail for an unknown tool: the name, the closest tool the caller may
+/// call if the name looks like a misspelling of one, and the tools they may
+/// call.
+///
+/// Tools switched off by an administrator, outside the caller's scopes or
+/// not enabled for their tenant are neither suggested nor listed.
+async fn unknown_tool_detail(tool_name: &str, tool_args: &Value) -> String {
+    let claims = tool_args
+        .get(AUTH_TOKEN_ARG)
+        .and_then(Value::as_str)
+        .and_then(|token| decode_claims(token).ok());
+    let tenant = tenants::settings(claims.as_ref().and_then(tenants::tenant_id).as_deref()).await;
+    let policy = &config().auth.policy;
+    let callable = |name: &str| {
+        !admin::is_disabled(name)
+            && policy.permits(name, claims.as_ref())
+            // Settings that fail to load leave no tool open to list
+            && tenant.as_ref().is_ok_and(|settings| {
+                settings.as_ref().is_none_or(|settings| settings.allows(name))
+            })
+    };
+    let available = registry::tools()
+        .iter()
+        .map(|tool| tool.name)
+        .filter(|name| callable(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    registry::closest(tool_name, callable).map_or_else(
+        || format!("{tool_name}. Available tools: {available}"),
+        |name| format!("{tool_name}. Did you mean {name}? Available tools: {available}"),
+    )
+}
 
 /// Extracts tool name from Lambda context or MCP event payload.
 ///
@@ -52,99 +123,415 @@ fn extract_tool_name(event_payload: &Value, context: &Context) -> String {
     "unknown".to_string()
 }
 
-/// Routes a tool request to the appropriate handler.
+/// Routes a tool request to its handler in the tool [`registry`].
 ///
-/// Supported tools:
-/// - `get_weather`: Fetches weather data for a location
-/// - `get_personalized_greeting`: Generates personalized greeting for user
+/// Invocations beyond the container's in-flight limit are shed first.
+/// Encrypted arguments are decrypted (see [`crate::crypto`]), authorization
+/// is checked before the tool runs (see [`crate::auth`]), tools switched off
+/// by an administrator are refused (see [`crate::admin`]), and
+/// identity arguments are only trusted from a signed context (see
+/// [`crate::identity`]); the resulting caller identity is available to the
+/// tool through its [`ToolContext`], and their name and user ID are removed
+/// from its arguments unless they consented (see [`crate::consent`]).
+/// Callers whose tenant has an overlay only reach the tools it enables,
+/// within its limits (see [`crate::tenants`]).
+/// Calls then wait for a slot under the tool's concurrency limit (see
+/// [`crate::limits`]), and arguments the caller left out are filled from
+/// their preferences or configured defaults (see [`crate::defaults`]).
+/// Calls to tools with side effects that ask for a dry run, or any such call
+/// with `DRY_RUN` enabled, plan their effects instead (see
+/// [`crate::dry_run`]). Calls to destructive tools without a confirmation
+/// token run as dry runs whose responses carry a token confirming the call,
+/// and only act when repeated with it (see [`crate::confirmation`]).
+/// Successful responses are scanned for leaked secrets
+/// (see [`crate::secrets`]), cut down to the fields the caller selected (see
+/// [`crate::projection`]) and their rows serialized in the requested
+/// format (see [`crate::export`]), then measured and logged, and every call is
+/// counted with its latency (see [`crate::metrics`]) and against the tool's
+/// SLO (see [`crate::slo`]). Calls to deprecated
+/// tools are logged, and carry a deprecation notice when enabled. With
+/// auditing enabled, calls to non-admin tools are recorded for replay and
+/// their responses carry the invocation ID (see [`crate::audit`]). A call
+/// repeating an earlier one in the same conversation is answered from its
+/// response without running the tool again (see [`crate::memo`]). With user
+/// memory enabled, a signed-in caller's successful call is remembered for
+/// their next greeting (see [`crate::memory`]). New
+/// response structures are recorded against the deployment that returned
+/// them (see [`crate::shapes`]), and calls and their upstream requests are
+/// counted per tenant for charge-back (see [`crate::usage`]). Responses list
+/// the upstreams and caches their data came from (see
+/// [`crate::provenance`]), and responses of tools returning third-party data
+/// credit its providers (see [`registry::Provider`]). Spikes of
+/// failed calls to a tool are announced to the configured webhooks (see
+/// [`crate::webhooks`]). Calls that ask for it, or come from a tenant that
+/// does, have the arguments they ran with, their upstream requests and the
+/// time each stage finished added to the invocation's trace (see
+/// [`crate::capture`]).
 ///
 /// # Errors
 ///
-/// Returns a `Diagnostic` error if:
+/// Returns a catalogued `Diagnostic` (see [`crate::catalog`]) if:
 /// - Tool name is not recognized (`UnknownTool`)
+/// - Tool was switched off by an administrator (`ToolDisabled`)
+/// - Container has too many invocations in flight (`Overloaded`)
+/// - Caller lacks a scope required by the tool policy (`Unauthorized`)
+/// - Tool is not enabled for the caller's tenant (`Unauthorized`)
+/// - Caller's tenant has used up its daily or monthly quota (`QuotaExceeded`,
+///   see [`crate::quotas`])
+/// - Settings of the caller's tenant cannot be loaded (`ToolError`)
+/// - Encrypted auth token cannot be decrypted (`Unauthorized`)
+/// - Signed identity context fails verification (`Unauthorized`)
+/// - Tool is saturated, overall or for the caller's tenant, for longer than
+///   the queue timeout (`TooBusy`)
 /// - Request payload cannot be parsed (`InvalidInput`)
+/// - Selected response fields are malformed or not in the tool's output
+///   schema (`InvalidInput`)
+/// - Output format is not `json`, `csv` or `ndjson` (`InvalidInput`)
+/// - Dry run flag is not a boolean (`InvalidInput`)
+/// - Confirmation token is not a string (`InvalidInput`)
+/// - Confirmation token does not confirm the call, has expired or was
+///   already used (`Unauthorized`, see [`crate::confirmation`])
+/// - Arguments the tool does not declare are passed while it is parsed
+///   strictly (`InvalidInput`, see [`crate::arguments`])
 /// - Tool execution fails (`ToolError`)
 /// - Response cannot be serialized (`SerializationError`)
 pub async fn route_tool(tool_name: &str, event_payload: Value) -> Result<Value, Diagnostic> {
-    debug!(tool_name = %tool_name, "Entering route_tool function");
-    debug!(
-        "Routing tool: {} with payload: {:?}",
-        tool_name, event_payload
-    );
+    let started = Instant::now();
+    let result = dispatch(tool_name, event_payload).await;
+    // Unrecognized names are counted together to bound label cardinality
+    let tool = registry::find(tool_name);
+    let label = tool.map_or("unknown", |tool| tool.name);
+    let elapsed = started.elapsed();
+    metrics::observe_call(label, result.is_ok(), elapsed);
+    if let Some(tool) = tool {
+        slo::observe(tool.name, &tool.slo, &result, elapsed);
+    }
+    if result.is_err() {
+        webhooks::record_failure(label);
+    }
+    webhooks::retry_due();
+    result
+}
 
+/// Extracts the tool arguments from an MCP request, decrypts them and checks
+/// the caller may make the call.
+async fn authorized_args(
+    tool_name: &str,
+    event_payload: &Value,
+) -> Result<(Value, Option<IdentityContext>), Diagnostic> {
     // Extract arguments from MCP request structure if present
-    let tool_args = event_payload
+    let mut tool_args = event_payload
         .get("params")
         .and_then(|params| params.get("arguments"))
-        .unwrap_or(&event_payload)
+        .unwrap_or(event_payload)
         .clone();
+    if capture::take_flag(&mut tool_args) {
+        capture::activate(None);
+    }
 
     debug!("Extracted tool arguments: {:?}", tool_args);
 
-    match tool_name {
-        "get_weather" => {
-            let request: WeatherRequest = serde_json::from_value(tool_args).map_err(|e| {
-                error!(error = %e, "Failed to parse weather request");
-                Diagnostic {
-                    error_type: "InvalidInput".to_string(),
-                    error_message: format!("Failed to parse weather request: {e}"),
-                }
-            })?;
+    decrypt_args(&mut tool_args, &[AUTH_TOKEN_ARG])
+        .await
+        .map_err(|e| {
+            error!(tool = %tool_name, error = %e, "Failed to decrypt tool arguments");
+            e.to_diagnostic("Unauthorized")
+        })?;
 
-            let response = get_weather(request).await.map_err(|e| {
-                error!(error = %format!("{e:#}"), "Weather tool execution failed");
-                Diagnostic {
-                    error_type: "ToolError".to_string(),
-                    error_message: format!("{e}"),
-                }
-            })?;
+    authorize_tool_call(tool_name, &tool_args).map_err(|e| {
+        error!(tool = %tool_name, error = %e, "Tool call rejected by authorization policy");
+        e.to_diagnostic("Unauthorized")
+    })?;
 
-            serde_json::to_value(response).map_err(|e| {
-                error!(error = %e, "Failed to serialize weather response");
-                Diagnostic {
-                    error_type: "SerializationError".to_string(),
-                    error_message: format!("Failed to serialize weather response: {e}"),
-                }
-            })
+    let identity = apply_verified_identity(&mut tool_args).await.map_err(|e| {
+        error!(tool = %tool_name, error = %e, "Identity context rejected");
+        e.to_diagnostic("Unauthorized")
+    })?;
+    capture::mark("authorized");
+    Ok((tool_args, identity))
+}
+
+/// The registered tool called `tool_name`, if an administrator has not
+/// switched it off.
+async fn enabled_tool(
+    tool_name: &str,
+    tool_args: &Value,
+) -> Result<&'static ToolDescriptor, Diagnostic> {
+    let Some(tool) = registry::find(tool_name) else {
+        error!(tool = %tool_name, "Unknown tool requested");
+        let detail = unknown_tool_detail(tool_name, tool_args).await;
+        return Err(catalog::UNKNOWN_TOOL.diagnostic("UnknownTool", &detail));
+    };
+    if admin::is_disabled(tool.name) {
+        error!(tool = %tool_name, "Disabled tool requested");
+        return Err(catalog::TOOL_DISABLED.diagnostic("ToolDisabled", tool_name));
+    }
+    Ok(tool)
+}
+
+/// Arguments controlling how a call is made rather than what the tool does.
+struct CallOptions {
+    projection: Option<Projection>,
+    output_format: Option<OutputFormat>,
+    dry_run: bool,
+    confirmation_token: Option<String>,
+}
+
+/// Takes the call options out of `tool_args`, then checks and coerces the
+/// arguments left for the tool.
+fn take_options(tool: &ToolDescriptor, tool_args: &mut Value) -> Result<CallOptions, Diagnostic> {
+    let projection = projection::take(tool, tool_args).map_err(|e| {
+        error!(tool = tool.name, error = %e, "Invalid response field selection");
+        e.to_diagnostic("InvalidInput")
+    })?;
+    let output_format = export::take(tool, tool_args).map_err(|e| {
+        error!(tool = tool.name, error = %e, "Invalid output format");
+        e.to_diagnostic("InvalidInput")
+    })?;
+    let dry_run = dry_run::take(tool, tool_args).map_err(|e| {
+        error!(tool = tool.name, error = %e, "Invalid dry run flag");
+        e.to_diagnostic("InvalidInput")
+    })?;
+    let confirmation_token = confirmation::take(tool, tool_args).map_err(|e| {
+        error!(tool = tool.name, error = %e, "Invalid confirmation token");
+        e.to_diagnostic("InvalidInput")
+    })?;
+    arguments::check(tool, tool_args).map_err(|e| {
+        error!(tool = tool.name, error = %e, "Tool call rejected by strict argument parsing");
+        e.to_diagnostic("InvalidInput")
+    })?;
+    arguments::coerce(tool, tool_args);
+    Ok(CallOptions {
+        projection,
+        output_format,
+        dry_run,
+        confirmation_token,
+    })
+}
+
+/// A call let through its tenant's settings, quotas and concurrency limits,
+/// holding its concurrency permits until dropped.
+struct Admission {
+    quota_warning: Option<QuotaWarning>,
+    _permit: Option<OwnedSemaphorePermit>,
+    _tenant_permit: Option<OwnedSemaphorePermit>,
+}
+
+/// Admits a call to `tool` for the caller's tenant.
+async fn admit(tool: &ToolDescriptor, context: &ToolContext) -> Result<Admission, Diagnostic> {
+    if let Some(tenant) = &context.tenant {
+        logging::record_tenant(tenant);
+    }
+    let tenant = tenants::settings(context.tenant.as_deref())
+        .await
+        .map_err(|e| {
+            error!(tool = tool.name, error = %e, "Failed to load tenant settings");
+            e.to_diagnostic("ToolError")
+        })?;
+    if capture::is_active() || tenant.as_ref().is_some_and(|tenant| tenant.trace_capture) {
+        capture::activate(context.tenant.as_deref());
+    }
+    if let Some(tenant) = &tenant {
+        tenant.check(tool.name).map_err(|e| {
+            error!(tool = tool.name, tenant = %tenant.tenant, "Tool not enabled for tenant");
+            e.to_diagnostic("Unauthorized")
+        })?;
+    }
+    let quota_warning = match &tenant {
+        Some(tenant) => quotas::check(tenant, tool.name)
+            .await
+            .map_err(|e| e.to_diagnostic("QuotaExceeded"))?,
+        None => None,
+    };
+
+    let permit = acquire_tool_permit(tool.name).await.map_err(|e| {
+        error!(tool = tool.name, error = %e, "Tool call rejected by concurrency limit");
+        e.to_diagnostic("TooBusy")
+    })?;
+    let tenant_permit = match &tenant {
+        Some(tenant) => tenants::acquire_permit(&tenant.tenant, tool.name)
+            .await
+            .map_err(|e| {
+                error!(tool = tool.name, error = %e, "Tool call rejected by tenant concurrency limit");
+                e.to_diagnostic("TooBusy")
+            })?,
+        None => None,
+    };
+    capture::mark("admitted");
+    Ok(Admission {
+        quota_warning,
+        _permit: permit,
+        _tenant_permit: tenant_permit,
+    })
+}
+
+/// Runs the tool and reshapes its response, remembering it for the session.
+async fn invoke(
+    tool: &ToolDescriptor,
+    tool_args: Value,
+    context: ToolContext,
+    memo_key: Option<String>,
+) -> (Result<Value, Diagnostic>, Vec<String>) {
+    let (result, sources) =
+        provenance::scope(context::scope(context, (tool.handler)(tool_args))).await;
+    let mut result = result.and_then(|response| {
+        transform::apply(tool.name, response).map_err(|e| {
+            error!(tool = tool.name, error = %e, "Response transform failed");
+            e.to_diagnostic("ToolError")
+        })
+    });
+    if config().secrets.scan_responses
+        && let Ok(response) = &mut result
+    {
+        secrets::scrub_response(tool.name, response);
+    }
+    if let (Some(key), Ok(response)) = (memo_key, &result) {
+        memo::insert(key, response);
+    }
+    (result, sources)
+}
+
+/// What a response's `_meta` reports besides its size.
+struct ResponseMeta {
+    invocation_id: Option<String>,
+    quota_warning: Option<QuotaWarning>,
+    confirmation: Option<Confirmation>,
+    sources: Vec<String>,
+    from_session_cache: bool,
+}
+
+impl ResponseMeta {
+    /// Adds the notices, credits and markers a response to `tool` carries.
+    fn attach(&self, tool: &ToolDescriptor, response: &mut Value) {
+        if let Some(deprecation) = &tool.deprecation
+            && config().deprecation.notices
+        {
+            registry::attach_notice(response, tool.name, deprecation);
         }
-        "get_personalized_greeting" => {
-            let request: PersonalizedGreetingRequest =
-                serde_json::from_value(tool_args).map_err(|e| {
-                    error!(error = %e, "Failed to parse personalized greeting request");
-                    Diagnostic {
-                        error_type: "InvalidInput".to_string(),
-                        error_message: format!(
-                            "Failed to parse personalized greeting request: {e}"
-                        ),
-                    }
-                })?;
-
-            let response = get_personalized_greeting(request).await.map_err(|e| {
-                error!(error = %format!("{e:#}"), "Personalized greeting tool execution failed");
-                Diagnostic {
-                    error_type: "ToolError".to_string(),
-                    error_message: format!("{e}"),
-                }
+        registry::attach_attribution(response, tool.providers);
+        if let Some(invocation_id) = &self.invocation_id {
+            audit::attach_id(response, invocation_id);
+        }
+        if let Some(warning) = &self.quota_warning {
+            quotas::attach_warning(response, warning);
+        }
+        if let Some(confirmation) = &self.confirmation {
+            confirmation::attach(response, confirmation);
+        }
+        if config().provenance.enabled {
+            provenance::attach(response, &self.sources);
+        }
+        if self.from_session_cache {
+            memo::attach_marker(response);
+        }
+    }
+}
+
+async fn dispatch(tool_name: &str, event_payload: Value) -> Result<Value, Diagnostic> {
+    debug!(tool = %tool_name, "Entering route_tool function");
+    let _in_flight = enter_invocation().map_err(|e| e.to_diagnostic("Overloaded"))?;
+    debug!(
+        "Routing tool: {} with payload: {:?}",
+        tool_name, event_payload
+    );
+
+    let (mut tool_args, identity) = authorized_args(tool_name, &event_payload).await?;
+    let tool = enabled_tool(tool_name, &tool_args).await?;
+    let CallOptions {
+        projection,
+        output_format,
+        dry_run,
+        confirmation_token,
+    } = take_options(tool, &mut tool_args)?;
+
+    let mut context = ToolContext::from_args(tool.name, &tool_args).with_identity(identity);
+    context.dry_run = dry_run;
+    if !consent::granted(&context).await {
+        consent::anonymize(&mut tool_args, &mut context);
+    }
+    let Admission {
+        quota_warning,
+        _permit,
+        _tenant_permit,
+    } = admit(tool, &context).await?;
+
+    if let Some(deprecation) = &tool.deprecation {
+        registry::warn_call(tool.name, deprecation);
+    }
+
+    info!(
+        tool = tool.name,
+        session_id = context.session_id.as_deref(),
+        user_id = context.user_id.as_deref(),
+        tenant = context.tenant.as_deref(),
+        "Routing tool call"
+    );
+    defaults::apply(tool, &mut tool_args, &context).await;
+    capture::record_arguments(&tool_args);
+    let pending_confirmation =
+        confirmation::check(tool, confirmation_token.as_deref(), &tool_args, &context)
+            .await
+            .map_err(|e| {
+                error!(tool = %tool_name, error = %e, "Confirmation token rejected");
+                e.to_diagnostic("Unauthorized")
             })?;
+    if pending_confirmation.is_some() {
+        context.dry_run = true;
+    }
 
-            serde_json::to_value(response).map_err(|e| {
-                error!(error = %e, "Failed to serialize personalized greeting response");
-                Diagnostic {
-                    error_type: "SerializationError".to_string(),
-                    error_message: format!(
-                        "Failed to serialize personalized greeting response: {e}"
-                    ),
-                }
-            })
-        }
-        _ => {
-            error!(tool_name = %tool_name, "Unknown tool requested");
-            Err(Diagnostic {
-                error_type: "UnknownTool".to_string(),
-                error_message: format!("Unknown tool: {tool_name}"),
-            })
+    let input = ContentMetrics::for_input(&tool_args);
+    let audited = (config().audit.enabled && !admin::is_admin_tool(tool.name))
+        .then(|| (tool_args.clone(), context.clone()));
+    usage::record_invocation(context.tenant.as_deref(), tool.name);
+    let remembered = context
+        .memory()
+        .zip(Interaction::from_call(tool.name, &tool_args));
+    let memo_key = memo::key(tool, &tool_args, &context);
+    let memoized = memo_key.as_deref().and_then(memo::get);
+    let from_session_cache = memoized.is_some();
+    let (result, sources) = match memoized {
+        Some(response) => (Ok(response), vec![SESSION_CACHE_SOURCE.to_string()]),
+        None => invoke(tool, tool_args, context, memo_key).await,
+    };
+    capture::mark("tool finished");
+    if let (Some((memory, interaction)), Ok(_)) = (remembered, &result) {
+        memory.remember(interaction).await;
+    }
+    let invocation_id = match audited {
+        Some((args, context)) => {
+            let outcome = InvocationOutcome::from(&result);
+            Some(audit::record(InvocationRecord::new(tool.name, args, &context, outcome)).await)
         }
+        None => None,
+    };
+    usage::flush_if_due().await;
+    let mut response = result?;
+    if config().shapes.enabled && !admin::is_admin_tool(tool.name) {
+        shapes::observe(tool.name, &response).await;
+    }
+    let confirmation = pending_confirmation.and_then(|pending| pending.issue(&response));
+    if let Some(projection) = &projection {
+        projection.apply(&mut response);
+    }
+    if let Some(format) = output_format {
+        export::apply(format, tool.rows, &mut response);
+    }
+
+    let sizes = input.with_output(&response);
+    metrics::record(tool.name, &sizes);
+    if config().metrics.include_in_response {
+        metrics::attach(&mut response, &sizes);
+    }
+    ResponseMeta {
+        invocation_id,
+        quota_warning,
+        confirmation,
+        sources,
+        from_session_cache,
     }
+    .attach(tool, &mut response);
+    capture::mark("response ready");
+    Ok(response)
 }
 
 /// Main Lambda event handler.
@@ -155,9 +542,22 @@ pub async fn route_tool(tool_name: &str, event_payload: Value) -> Result<Value,
 /// # Event Processing
 ///
 /// 1. Extracts tool name from context or payload
-/// 2. Parses request arguments
-/// 3. Routes to appropriate tool handler
-/// 4. Returns JSON response or diagnostic error
+/// 2. Answers `completion/complete` requests directly (see [`crate::completion`])
+/// 3. Parses request arguments
+/// 4. Routes to appropriate tool handler, within the invocation deadline and
+///    a logging span correlating its lines by request ID (see [`crate::logging`]),
+///    writing a trace of the invocation to S3 when one is asked for (see
+///    [`crate::capture`])
+/// 5. Returns JSON response or diagnostic error, compressed for HTTP-origin
+///    events that accept it when `RESPONSE_COMPRESSION` is enabled
+///
+/// With `BINARY_PAYLOADS` enabled, CBOR and `MessagePack` request bodies are
+/// decoded in step 3, and responses are encoded in whichever of them the
+/// caller accepts instead of being compressed (see [`crate::codec`]).
+///
+/// Batches of SQS messages and `EventBridge` events are asynchronous calls,
+/// which never fail: their failures are retried or recorded (see
+/// [`crate::async_calls`]).
 ///
 /// # Errors
 ///
@@ -166,17 +566,71 @@ pub async fn route_tool(tool_name: &str, event_payload: Value) -> Result<Value,
 /// - `ToolError`: The requested tool failed to execute
 /// - `SerializationError`: Failed to serialize the tool response back to JSON
 /// - `UnknownTool`: The requested tool name was not recognized
+/// - `ToolDisabled`: The requested tool was switched off by an administrator
+/// - `Unauthorized`: The caller lacks a scope required by the tool policy
+/// - `TooBusy`: The tool is at its concurrency limit; the call may be retried
+/// - `Overloaded`: The container has too many invocations in flight; the call may be retried
+/// - `QuotaExceeded`: The caller's tenant has used up a usage quota; the message says when it resets
 pub async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Diagnostic> {
     let (event_payload, context) = event.into_parts();
+    if async_calls::is_async_event(&event_payload) {
+        return Ok(Box::pin(async_calls::handle(&event_payload)).await);
+    }
     let tool_name = extract_tool_name(&event_payload, &context);
+    let recorder = capture::is_enabled()
+        .then(|| Recorder::new(&context.request_id, &tool_name, &event_payload));
+    let settings = &config().compression;
+    let encoding = settings
+        .enabled
+        .then(|| compression::negotiate(&event_payload))
+        .flatten();
+    let binary = config().codec.binary_payloads;
+    let format = binary.then(|| codec::negotiate(&event_payload)).flatten();
+    let decoded = if binary {
+        codec::decode_request(&event_payload).map_err(|e| {
+            error!(tool = %tool_name, error = %e, "Failed to decode binary request body");
+            e.to_diagnostic("InvalidInput")
+        })?
+    } else {
+        None
+    };
 
     // Extract the actual payload - if it's an API Gateway event, get from body
-    let payload_for_tool = event_payload
-        .get("body")
-        .and_then(|b| b.as_str())
-        .and_then(|body_str| serde_json::from_str(body_str).ok())
-        .unwrap_or(event_payload);
-
-    info!(message = format!("Invoking tool: {}", tool_name));
-    route_tool(&tool_name, payload_for_tool).await
+    let payload_for_tool = decoded.unwrap_or_else(|| {
+        event_payload
+            .get("body")
+            .and_then(|b| b.as_str())
+            .and_then(|body_str| serde_json::from_str(body_str).ok())
+            .unwrap_or(event_payload)
+    });
+
+    if payload_for_tool.get("method").and_then(Value::as_str) == Some(COMPLETE_METHOD) {
+        return completion::handle(&payload_for_tool);
+    }
+
+    let span = logging::invocation_span(&tool_name, &context.request_id);
+    span.in_scope(|| info!(message = format!("Invoking tool: {}", tool_name)));
+    let result = Box::pin(
+        capture::scope(
+            recorder.clone(),
+            deadline::scope(
+                deadline::from_epoch_millis(context.deadline),
+                route_tool(&tool_name, payload_for_tool),
+            ),
+        )
+        .instrument(span),
+    )
+    .await;
+    if let Some(recorder) = &recorder {
+        recorder.finish(&result).await;
+    }
+    let response = result?;
+
+    Ok(match (format, encoding) {
+        (Some(format), _) => codec::encode_response(response, format),
+        (None, Some(encoding)) => {
+            compression::compress_response(response, encoding, settings.min_bytes)
+        }
+        (None, None) => response,
+    })
 }
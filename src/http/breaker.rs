@@ -0,0 +1,123 @@
+//! Circuit breaker for upstream APIs.
+//!
+//! The breaker opens when an upstream rate limits us (for as long as its
+//! `Retry-After` asks) or after repeated failures (for a fixed cooldown).
+//! While open, calls fail fast instead of adding load to an upstream that
+//! is already struggling. Opening and closing are announced to the
+//! configured webhooks (see [`crate::webhooks`]).
+
+use crate::webhooks::{self, WebhookEvent};
+use lambda_runtime::tracing::{info, warn};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Tracks upstream health and short-circuits calls while it is unhealthy.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: &'static str,
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker that opens for `cooldown` after
+    /// `failure_threshold` consecutive failures.
+    #[must_use]
+    pub fn new(name: &'static str, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name,
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Name of the upstream this breaker protects.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Time until the breaker closes again, or `None` if calls may proceed.
+    #[must_use]
+    pub fn open_for(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .open_until
+            .map(|until| until.saturating_duration_since(Instant::now()))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Records a successful call, closing the breaker.
+    pub fn record_success(&self) {
+        let was_open = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            std::mem::take(&mut *state).open_until.is_some()
+        };
+        if was_open {
+            info!(upstream = self.name, "Closing circuit breaker");
+            self.announce(None);
+        }
+    }
+
+    /// Records a failed call, opening the breaker once the threshold is reached.
+    pub fn record_failure(&self) {
+        let opened = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.consecutive_failures += 1;
+            if state.consecutive_failures < self.failure_threshold {
+                return;
+            }
+            warn!(
+                upstream = self.name,
+                failures = state.consecutive_failures,
+                "Opening circuit breaker after repeated failures"
+            );
+            // Until a success closes it, a breaker whose cooldown has passed
+            // is still announced as open
+            let was_open = state.open_until.is_some();
+            state.open_until = Some(Instant::now() + self.cooldown);
+            !was_open
+        };
+        if opened {
+            self.announce(Some(self.cooldown));
+        }
+    }
+
+    /// Records a rate-limited call, opening the breaker for `retry_after`
+    /// (or the cooldown if the upstream gave no hint).
+    pub fn record_rate_limited(&self, retry_after: Option<Duration>) {
+        let open_for = retry_after.unwrap_or(self.cooldown);
+        warn!(
+            upstream = self.name,
+            open_secs = open_for.as_secs(),
+            "Opening circuit breaker after rate limiting"
+        );
+        let opened = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            let was_open = state.open_until.is_some();
+            state.open_until = Some(Instant::now() + open_for);
+            !was_open
+        };
+        if opened {
+            self.announce(Some(open_for));
+        }
+    }
+
+    /// Tells the configured webhooks the breaker opened for `open_for`, or
+    /// closed if `None`.
+    fn announce(&self, open_for: Option<Duration>) {
+        webhooks::emit(WebhookEvent::CircuitBreaker {
+            upstream: self.name.to_string(),
+            open: open_for.is_some(),
+            open_secs: open_for.map(|open_for| open_for.as_secs()),
+        });
+    }
+}
@@ -0,0 +1,132 @@
+//! Upstream connection reuse diagnostics and keepalive pings.
+//!
+//! A request sent over a pooled connection skips the TCP and TLS handshakes,
+//! which can take longer than the request itself. Every upstream response is
+//! checked against the connections seen before, by local and remote address,
+//! and counted as `reused` or `new` per host (see
+//! [`crate::metrics::observe_connection`]), so the cost of connection setup
+//! can be measured in production. Responses that carry no connection
+//! details, such as replayed fixtures, are not counted. A closed connection's
+//! local port may be handed to a later one, so a new connection is
+//! occasionally counted as reused.
+//!
+//! Upstreams close connections that sit idle, so the first call after a
+//! quiet spell pays for a new handshake. With `HTTP_KEEPALIVE_PING_SECS` set,
+//! [`spawn_keepalive`] sends a `HEAD` request to each host called in the last
+//! [`KEEPALIVE_WINDOW`] at that interval, keeping its pooled connection
+//! open. Lambda freezes the container between invocations, so there pings
+//! are only sent while it is thawed; in the dev server they run throughout.
+
+use super::{HTTP_CLIENT, VcrMode};
+use crate::config::config;
+use crate::metrics::{ConnectionUse, observe_connection};
+use hyper_util::client::legacy::connect::HttpInfo;
+use lambda_runtime::tracing::debug;
+use reqwest::{Response, Url};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::time::{MissedTickBehavior, interval};
+
+/// How long after its last call a host is still pinged
+pub const KEEPALIVE_WINDOW: Duration = Duration::from_mins(15);
+
+/// Longest wait for a keepalive ping
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connections remembered before all are forgotten and counted afresh
+const MAX_CONNECTIONS: usize = 1024;
+
+#[derive(Debug, Default)]
+struct Connections {
+    /// Local and remote address of every connection seen
+    seen: HashSet<(SocketAddr, SocketAddr)>,
+    /// When each origin was last called
+    origins: HashMap<String, Instant>,
+}
+
+static CONNECTIONS: LazyLock<Mutex<Connections>> =
+    LazyLock::new(|| Mutex::new(Connections::default()));
+
+/// Counts whether the connection `response` from `url` arrived on was reused.
+pub fn observe(url: &Url, response: &Response) {
+    let Some(info) = response.extensions().get::<HttpInfo>() else {
+        return;
+    };
+    let host = url.host_str().unwrap_or("unknown");
+    let reused = !remember(info);
+    CONNECTIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .origins
+        .insert(url.origin().ascii_serialization(), Instant::now());
+    debug!(host, reused, version = ?response.version(), "Upstream connection");
+    observe_connection(
+        host,
+        if reused {
+            ConnectionUse::Reused
+        } else {
+            ConnectionUse::New
+        },
+    );
+}
+
+/// Starts pinging recently called hosts every `HTTP_KEEPALIVE_PING_SECS`,
+/// unless it is `0` or upstream responses are recorded or replayed.
+///
+/// Must be called from within a Tokio runtime.
+pub fn spawn_keepalive() {
+    let settings = &config().http;
+    let every = settings.keepalive_ping;
+    if every.is_zero() || settings.vcr_mode != VcrMode::Off {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut ticks = interval(every);
+        // After a freeze, ping once rather than for every tick missed
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticks.tick().await;
+        loop {
+            ticks.tick().await;
+            for origin in recent_origins() {
+                ping(&origin).await;
+            }
+        }
+    });
+}
+
+/// Remembers the connection `info` describes, returning whether it is new.
+fn remember(info: &HttpInfo) -> bool {
+    let mut connections = CONNECTIONS.lock().unwrap_or_else(PoisonError::into_inner);
+    if connections.seen.len() >= MAX_CONNECTIONS {
+        connections.seen.clear();
+    }
+    connections
+        .seen
+        .insert((info.local_addr(), info.remote_addr()))
+}
+
+/// Origins called within [`KEEPALIVE_WINDOW`]; older ones are forgotten.
+fn recent_origins() -> Vec<String> {
+    let mut connections = CONNECTIONS.lock().unwrap_or_else(PoisonError::into_inner);
+    connections
+        .origins
+        .retain(|_, called| called.elapsed() < KEEPALIVE_WINDOW);
+    connections.origins.keys().cloned().collect()
+}
+
+async fn ping(origin: &str) {
+    let started = Instant::now();
+    let result = HTTP_CLIENT.head(origin).timeout(PING_TIMEOUT).send().await;
+    match result {
+        Ok(response) => {
+            // A connection the ping opened is not new to the next call
+            if let Some(info) = response.extensions().get::<HttpInfo>() {
+                remember(info);
+            }
+            debug!(origin, status = %response.status(), latency = ?started.elapsed(), "Keepalive ping");
+        }
+        Err(e) => debug!(origin, error = %e, "Keepalive ping failed"),
+    }
+}
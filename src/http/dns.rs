@@ -0,0 +1,114 @@
+//! DNS resolution with an in-process cache.
+//!
+//! By default reqwest resolves every new connection's host through the
+//! system resolver on a blocking thread, which adds avoidable latency on
+//! cold starts and when idle connections have been closed. [`HTTP_CLIENT`]
+//! resolves through [`CachingResolver`] instead: an asynchronous
+//! `hickory-resolver` configured from `/etc/resolv.conf`, whose answers are
+//! kept in the `dns` cache for `DNS_CACHE_TTL_SECS`, shared across warm
+//! invocations. Its hits and misses are counted with the other caches, and
+//! it can be inspected and flushed with the admin tools.
+//!
+//! Set `DNS_CACHE=false` to use the system resolver, through
+//! [`SystemResolver`].
+//!
+//! Either way, addresses the egress policy does not permit, such as
+//! loopback or link-local ones, are dropped from the answer, and a host
+//! resolving only to such addresses is refused (see [`super::egress`]).
+//!
+//! [`HTTP_CLIENT`]: super::HTTP_CLIENT
+
+use super::EGRESS;
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::models::error::AppError;
+use hickory_resolver::{ResolveError, TokioResolver};
+use lambda_runtime::tracing::{debug, warn};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, LazyLock};
+
+/// Addresses per host name
+pub(crate) static DNS_CACHE: LazyLock<CacheStore<Vec<IpAddr>>> = LazyLock::new(|| {
+    let settings = &config().http;
+    CacheStore::new("dns", settings.dns_cache_ttl, settings.dns_cache_size)
+});
+
+/// Resolves host names for reqwest, answering from [`DNS_CACHE`] when it
+/// can.
+#[derive(Clone)]
+pub struct CachingResolver {
+    resolver: Arc<TokioResolver>,
+}
+
+impl CachingResolver {
+    /// Creates a resolver using the name servers in the system configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system configuration cannot be read.
+    pub fn from_system_conf() -> Result<Self, ResolveError> {
+        let mut builder = TokioResolver::builder_tokio()?;
+        // Answers are cached in DNS_CACHE, so the resolver keeps none of its own
+        builder.options_mut().cache_size = 0;
+        Ok(Self {
+            resolver: Arc::new(builder.build()),
+        })
+    }
+}
+
+impl Resolve for CachingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = Arc::clone(&self.resolver);
+        Box::pin(async move {
+            let host = name.as_str();
+            let addresses = if let Some(addresses) = DNS_CACHE.get(host) {
+                addresses
+            } else {
+                let lookup = resolver.lookup_ip(host).await?;
+                let addresses: Vec<IpAddr> = lookup.iter().collect();
+                debug!(host, addresses = addresses.len(), "Resolved host");
+                DNS_CACHE.insert(host, addresses.clone());
+                addresses
+            };
+            Ok(permitted(host, addresses)?)
+        })
+    }
+}
+
+/// Resolves host names through the system resolver on a blocking thread,
+/// as reqwest does by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let lookup = host.clone();
+            let addresses = tokio::task::spawn_blocking(move || {
+                (lookup.as_str(), 0)
+                    .to_socket_addrs()
+                    .map(|addresses| addresses.map(|address| address.ip()).collect())
+            })
+            .await??;
+            Ok(permitted(&host, addresses)?)
+        })
+    }
+}
+
+/// The addresses of `host` that the egress policy permits connecting to.
+fn permitted(host: &str, addresses: Vec<IpAddr>) -> Result<Addrs, AppError> {
+    let permitted: Vec<SocketAddr> = addresses
+        .into_iter()
+        .filter(|address| EGRESS.permits_address(*address))
+        .map(|address| SocketAddr::new(address, 0))
+        .collect();
+    if permitted.is_empty() {
+        warn!(host, reason = "private", "Egress denied by policy");
+        return Err(AppError::EgressDenied(format!(
+            "Requests to {host} are not permitted by the egress policy"
+        )));
+    }
+    Ok(Box::new(permitted.into_iter()))
+}
@@ -0,0 +1,179 @@
+//! Domain allow/deny policy for outbound HTTP.
+//!
+//! Every request sent through [`super::send`] is checked against the policy
+//! before it leaves the container, whichever tool built it. A rule such as
+//! `example.com` matches that host and all of its subdomains. The denylist
+//! (`EGRESS_DENYLIST`) always wins; when the allowlist (`EGRESS_ALLOWLIST`)
+//! is non-empty, hosts not on it are denied too. Only `http` and `https`
+//! URLs are ever permitted. Denials are logged as audit entries.
+//!
+//! Loopback, private, link-local and other non-public addresses, such as
+//! the Lambda runtime API on 127.0.0.1:9001 or the metadata endpoints on
+//! 169.254.x.x, are denied too unless `EGRESS_ALLOW_PRIVATE` is set: as IP
+//! literals by [`EgressPolicy::check`], and as resolved addresses by the
+//! client's resolvers (see [`super::dns`]). Redirects are followed only
+//! after each hop passes the same check (see
+//! [`EgressPolicy::redirect_policy`]).
+
+use crate::context;
+use crate::models::error::AppError;
+use lambda_runtime::tracing::warn;
+use reqwest::Url;
+use reqwest::redirect::Policy;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Most redirects followed for one request, as reqwest's default policy
+const MAX_REDIRECTS: usize = 10;
+
+/// Allowed and denied destination domains.
+#[derive(Debug, Clone, Default)]
+pub struct EgressPolicy {
+    allow: Vec<String>,
+    deny: Vec<String>,
+    /// Whether non-public addresses may be reached
+    private: bool,
+}
+
+impl EgressPolicy {
+    /// Creates a policy from domain rules, ignoring case and leading dots.
+    #[must_use]
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        let normalize = |rules: &[String]| {
+            rules
+                .iter()
+                .map(|rule| rule.trim().trim_start_matches("*.").trim_start_matches('.'))
+                .filter(|rule| !rule.is_empty())
+                .map(str::to_ascii_lowercase)
+                .collect()
+        };
+        Self {
+            allow: normalize(allow),
+            deny: normalize(deny),
+            private: false,
+        }
+    }
+
+    /// Returns this policy permitting loopback, private and link-local
+    /// addresses, for upstreams inside the VPC.
+    #[must_use]
+    pub fn allowing_private_networks(self) -> Self {
+        Self {
+            private: true,
+            ..self
+        }
+    }
+
+    /// Whether a connection to `address` may be opened.
+    #[must_use]
+    pub fn permits_address(&self, address: IpAddr) -> bool {
+        self.private || is_public(address)
+    }
+
+    /// Checks whether a request to `url` may be sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::EgressDenied` if the scheme is not HTTP(S), the host
+    /// is a non-public address or `localhost` while those are denied, the host
+    /// is denylisted, or an allowlist is set and the host is not on it.
+    pub fn check(&self, url: &Url) -> Result<(), AppError> {
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        let reason = if !matches!(url.scheme(), "http" | "https") {
+            Some("scheme")
+        } else if host.is_empty() {
+            Some("host")
+        } else if !self.private && is_private_host(&host) {
+            Some("private")
+        } else if self.deny.iter().any(|rule| matches_rule(&host, rule)) {
+            Some("denylist")
+        } else if !self.allow.is_empty() && !self.allow.iter().any(|rule| matches_rule(&host, rule))
+        {
+            Some("allowlist")
+        } else {
+            None
+        };
+
+        let Some(reason) = reason else {
+            return Ok(());
+        };
+        warn!(
+            host = %host,
+            scheme = url.scheme(),
+            url = %url,
+            reason,
+            session_id = ?context::current().session_id,
+            "Egress denied by policy"
+        );
+        Err(AppError::EgressDenied(format!(
+            "Requests to {host} are not permitted by the egress policy"
+        )))
+    }
+
+    /// A redirect policy following up to ten redirects, each only if its
+    /// target passes [`Self::check`].
+    #[must_use]
+    pub fn redirect_policy(&'static self) -> Policy {
+        Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if let Err(e) = self.check(attempt.url()) {
+                attempt.error(e)
+            } else {
+                attempt.follow()
+            }
+        })
+    }
+}
+
+/// Whether `host`, as written in a URL, is a non-public address or the
+/// local machine.
+fn is_private_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.');
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .map_or_else(
+            |_| matches_rule(host, "localhost"),
+            |address| !is_public(address),
+        )
+}
+
+/// Whether `address` is reachable on the public internet, rather than a
+/// loopback, private, link-local, shared, multicast or unspecified one.
+fn is_public(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_v4(address),
+        IpAddr::V6(address) => address
+            .to_ipv4_mapped()
+            .map_or_else(|| is_public_v6(address), is_public_v4),
+    }
+}
+
+const fn is_public_v4(address: Ipv4Addr) -> bool {
+    let [first, second, ..] = address.octets();
+    !(address.is_loopback()
+        || address.is_private()
+        || address.is_link_local()
+        || address.is_unspecified()
+        || address.is_broadcast()
+        || address.is_multicast()
+        || address.is_documentation()
+        || first == 0
+        // Shared address space (100.64.0.0/10), used for carrier-grade NAT
+        || (first == 100 && second & 0xc0 == 64))
+}
+
+const fn is_public_v6(address: Ipv6Addr) -> bool {
+    !(address.is_loopback()
+        || address.is_unspecified()
+        || address.is_multicast()
+        || address.is_unique_local()
+        || address.is_unicast_link_local())
+}
+
+fn matches_rule(host: &str, rule: &str) -> bool {
+    host == rule
+        || host
+            .strip_suffix(rule)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
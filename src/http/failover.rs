@@ -0,0 +1,158 @@
+//! Failover between regional endpoints of an upstream provider.
+//!
+//! A provider may be reachable through several base URLs (for example
+//! regional mirrors). The pool tracks each endpoint's health and latency
+//! across warm invocations: requests go to the fastest healthy endpoint
+//! first, and an endpoint that fails repeatedly is marked unhealthy and only
+//! retried once its cooldown has passed. Health is checked passively, from
+//! the outcome of real requests, so idle containers make no extra calls.
+//! The pool also keeps the recent latencies its requests are hedged by (see
+//! [`super::hedge`]).
+
+use super::Hedge;
+use lambda_runtime::tracing::{info, warn};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Weight of the newest sample in the latency moving average.
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    /// Exponentially weighted moving average of response latency
+    latency: Option<Duration>,
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    base_url: String,
+    health: Mutex<EndpointHealth>,
+}
+
+/// Base URLs of one provider, ordered by health and latency.
+#[derive(Debug)]
+pub struct EndpointPool {
+    name: &'static str,
+    failure_threshold: u32,
+    cooldown: Duration,
+    endpoints: Vec<Endpoint>,
+    hedge: Hedge,
+}
+
+impl EndpointPool {
+    /// Creates a pool over `base_urls` (trailing slashes removed) that marks
+    /// an endpoint unhealthy for `cooldown` after `failure_threshold`
+    /// consecutive failures.
+    #[must_use]
+    pub fn new(
+        name: &'static str,
+        base_urls: &[String],
+        failure_threshold: u32,
+        cooldown: Duration,
+    ) -> Self {
+        Self {
+            name,
+            failure_threshold,
+            cooldown,
+            endpoints: base_urls
+                .iter()
+                .map(|url| Endpoint {
+                    base_url: url.trim().trim_end_matches('/').to_string(),
+                    health: Mutex::new(EndpointHealth::default()),
+                })
+                .filter(|endpoint| !endpoint.base_url.is_empty())
+                .collect(),
+            hedge: Hedge::new(name),
+        }
+    }
+
+    /// Name of the provider.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Recent latencies of the provider, for hedging requests to it.
+    #[must_use]
+    pub const fn hedge(&self) -> &Hedge {
+        &self.hedge
+    }
+
+    /// Base URLs in the order they should be tried.
+    ///
+    /// Healthy endpoints come first, fastest first, with endpoints that have
+    /// no latency sample yet tried in configured order ahead of measured
+    /// ones. Unhealthy endpoints follow as a last resort.
+    #[must_use]
+    pub fn ordered(&self) -> Vec<&str> {
+        let now = Instant::now();
+        let mut ranked: Vec<(bool, Option<Duration>, usize, &str)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                let health = endpoint
+                    .health
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let unhealthy = health.unhealthy_until.is_some_and(|until| until > now);
+                (unhealthy, health.latency, index, endpoint.base_url.as_str())
+            })
+            .collect();
+        ranked.sort_by_key(|&(unhealthy, latency, index, _)| (unhealthy, latency, index));
+        ranked.into_iter().map(|(.., url)| url).collect()
+    }
+
+    /// Records a response from `base_url` that took `latency`.
+    pub fn record_success(&self, base_url: &str, latency: Duration) {
+        let Some(endpoint) = self.find(base_url) else {
+            return;
+        };
+        let mut health = endpoint
+            .health
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if health.unhealthy_until.is_some() {
+            info!(
+                upstream = self.name,
+                endpoint = base_url,
+                "Endpoint recovered"
+            );
+        }
+        health.latency = Some(health.latency.map_or(latency, |average| {
+            average.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING)
+        }));
+        health.consecutive_failures = 0;
+        health.unhealthy_until = None;
+    }
+
+    /// Records a failed request to `base_url`, marking it unhealthy once the
+    /// threshold is reached.
+    pub fn record_failure(&self, base_url: &str) {
+        let Some(endpoint) = self.find(base_url) else {
+            return;
+        };
+        let mut health = endpoint
+            .health
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= self.failure_threshold {
+            warn!(
+                upstream = self.name,
+                endpoint = base_url,
+                failures = health.consecutive_failures,
+                "Marking endpoint unhealthy after repeated failures"
+            );
+            health.unhealthy_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    fn find(&self, base_url: &str) -> Option<&Endpoint> {
+        self.endpoints
+            .iter()
+            .find(|endpoint| endpoint.base_url == base_url)
+    }
+}
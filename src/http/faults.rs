@@ -0,0 +1,294 @@
+//! Injected upstream faults for soak and chaos testing.
+//!
+//! With the `chaos` feature, each upstream request may be failed on purpose
+//! before it is sent, with the probabilities in `HTTP_FAULTS`, e.g.
+//! `timeout=0.05;server_error=0.1;malformed=0.05;slow=0.2`:
+//!
+//! - `timeout`: the request fails as timed out, after `HTTP_FAULT_DELAY_MS`
+//!   or the request's own timeout, whichever is shorter
+//! - `server_error`: the upstream answers `503 Service Unavailable`
+//! - `malformed`: the upstream answers `200 OK` with a truncated JSON body
+//! - `slow`: the request is sent normally after `HTTP_FAULT_DELAY_MS`
+//!
+//! Faults are drawn from a seeded generator (`HTTP_FAULT_SEED`), so a run
+//! that failed can be repeated. Builds without the feature never inject
+//! faults, whatever the configuration says.
+
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "chaos")]
+use crate::config::config;
+#[cfg(feature = "chaos")]
+use lambda_runtime::tracing::debug;
+#[cfg(feature = "chaos")]
+use reqwest::{Request, Response};
+#[cfg(feature = "chaos")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "chaos")]
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+
+/// Slack for rounding in sums of probabilities, far below the resolution of
+/// a roll, so that `0.1 + 0.2 + 0.3` still ends its band at 0.6
+const ROUNDING: f64 = 1e-12;
+
+/// A way an upstream request can be made to fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fault {
+    /// The request times out
+    Timeout,
+    /// The upstream answers with a server error
+    ServerError,
+    /// The upstream answers successfully with a body that is not valid JSON
+    Malformed,
+    /// The upstream answers normally, but late
+    Slow,
+}
+
+impl Fault {
+    /// Every fault, in the order they are drawn.
+    pub const ALL: [Self; 4] = [
+        Self::Timeout,
+        Self::ServerError,
+        Self::Malformed,
+        Self::Slow,
+    ];
+
+    /// The name of the fault in `HTTP_FAULTS`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::ServerError => "server_error",
+            Self::Malformed => "malformed",
+            Self::Slow => "slow",
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How often each fault is injected, and how.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultProfile {
+    /// Probability of each fault per request, indexed like [`Fault::ALL`]
+    pub probabilities: [f64; 4],
+    /// How long slow responses are held back and timeouts take
+    pub delay: Duration,
+    /// Seed of the generator faults are drawn from
+    pub seed: u64,
+}
+
+impl Default for FaultProfile {
+    fn default() -> Self {
+        Self {
+            probabilities: [0.0; 4],
+            delay: Duration::from_secs(2),
+            seed: 0,
+        }
+    }
+}
+
+impl FaultProfile {
+    /// Parses `name=probability` pairs separated by `;` or `,` into the
+    /// default profile; faults not named are never injected.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first pair that names an unknown fault
+    /// or a probability outside 0 to 1, or if the probabilities add up to
+    /// more than 1.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut profile = Self::default();
+        for pair in spec
+            .split([';', ','])
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected name=probability, got {pair:?}"))?;
+            let fault = Fault::ALL
+                .into_iter()
+                .find(|fault| fault.name() == name.trim())
+                .ok_or_else(|| format!("unknown fault {:?}", name.trim()))?;
+            let probability = value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|p| (0.0..=1.0).contains(p))
+                .ok_or_else(|| format!("{fault} probability {value:?} is not between 0 and 1"))?;
+            profile.probabilities[fault.index()] = probability;
+        }
+        if profile.probabilities.iter().sum::<f64>() > 1.0 + ROUNDING {
+            return Err("fault probabilities add up to more than 1".to_string());
+        }
+        Ok(profile)
+    }
+
+    /// A profile injecting only `fault`, with probability `probability`.
+    #[must_use]
+    pub fn only(fault: Fault, probability: f64) -> Self {
+        let mut profile = Self::default();
+        profile.probabilities[fault.index()] = probability;
+        profile
+    }
+
+    /// Whether any fault can be injected.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.probabilities.iter().any(|p| *p > 0.0)
+    }
+
+    /// The fault a uniform `roll` in `[0, 1)` lands on, if any.
+    #[must_use]
+    pub fn pick(&self, roll: f64) -> Option<Fault> {
+        let mut threshold = 0.0;
+        Fault::ALL.into_iter().find(|fault| {
+            threshold += self.probabilities[fault.index()];
+            roll < threshold - ROUNDING
+        })
+    }
+}
+
+/// Draws faults for upstream requests from a [`FaultProfile`], counting
+/// what it injects.
+#[cfg(feature = "chaos")]
+#[derive(Debug)]
+pub struct FaultInjector {
+    profile: FaultProfile,
+    draws: AtomicU64,
+    injected: [AtomicU64; 4],
+}
+
+#[cfg(feature = "chaos")]
+impl FaultInjector {
+    #[must_use]
+    pub const fn new(profile: FaultProfile) -> Self {
+        Self {
+            profile,
+            draws: AtomicU64::new(0),
+            injected: [const { AtomicU64::new(0) }; 4],
+        }
+    }
+
+    #[must_use]
+    pub const fn profile(&self) -> &FaultProfile {
+        &self.profile
+    }
+
+    /// Requests faults have been drawn for.
+    #[must_use]
+    pub fn draws(&self) -> u64 {
+        self.draws.load(Ordering::Relaxed)
+    }
+
+    /// Times `fault` has been injected.
+    #[must_use]
+    pub fn injected(&self, fault: Fault) -> u64 {
+        self.injected[fault.index()].load(Ordering::Relaxed)
+    }
+
+    /// Draws the fault, if any, for the next request.
+    pub fn draw(&self) -> Option<Fault> {
+        let n = self.draws.fetch_add(1, Ordering::Relaxed);
+        let fault = self.profile.pick(roll(self.profile.seed, n))?;
+        self.injected[fault.index()].fetch_add(1, Ordering::Relaxed);
+        Some(fault)
+    }
+}
+
+/// The `n`th uniform draw in `[0, 1)` from `seed`, by `SplitMix64`.
+#[cfg(feature = "chaos")]
+fn roll(seed: u64, n: u64) -> f64 {
+    let mut z = seed.wrapping_add(n.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    let high = u32::try_from(z >> 32).unwrap_or(u32::MAX);
+    f64::from(high) / (f64::from(u32::MAX) + 1.0)
+}
+
+/// The injector in use, from `HTTP_FAULTS` unless [`install`] replaced it.
+#[cfg(feature = "chaos")]
+static INJECTOR: LazyLock<Mutex<Option<Arc<FaultInjector>>>> = LazyLock::new(|| {
+    let profile = config().http.faults;
+    Mutex::new(
+        profile
+            .is_active()
+            .then(|| Arc::new(FaultInjector::new(profile))),
+    )
+});
+
+/// Replaces the configured faults with `profile`, returning the injector
+/// that now draws them so its counts can be checked.
+#[cfg(feature = "chaos")]
+pub fn install(profile: FaultProfile) -> Arc<FaultInjector> {
+    let injector = Arc::new(FaultInjector::new(profile));
+    *INJECTOR.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::clone(&injector));
+    injector
+}
+
+/// Stops injecting faults.
+#[cfg(feature = "chaos")]
+pub fn clear() {
+    *INJECTOR.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// The injector in use, if faults are being injected.
+#[cfg(feature = "chaos")]
+#[must_use]
+pub fn injector() -> Option<Arc<FaultInjector>> {
+    INJECTOR
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Draws a fault for `request`, returning the failed outcome to use instead
+/// of sending it, or `None` if it should be sent (possibly after a delay).
+#[cfg(feature = "chaos")]
+pub async fn inject(request: &Request) -> Option<anyhow::Result<Response>> {
+    let injector = injector()?;
+    let fault = injector.draw()?;
+    let delay = injector.profile().delay;
+    debug!(%fault, url = %request.url(), "Injecting upstream fault");
+    match fault {
+        Fault::Timeout => {
+            let delay = request
+                .timeout()
+                .map_or(delay, |timeout| delay.min(*timeout));
+            tokio::time::sleep(delay).await;
+            Some(Err(anyhow::anyhow!(
+                "Injected fault: request to {} timed out after {delay:?}",
+                request.url()
+            )))
+        }
+        Fault::ServerError => Some(response(
+            503,
+            "{\"error\":true,\"reason\":\"Injected fault\"}",
+        )),
+        Fault::Malformed => Some(response(200, "{\"latitude\":-33.87,\"current\":{\"time\":")),
+        Fault::Slow => {
+            tokio::time::sleep(delay).await;
+            None
+        }
+    }
+}
+
+#[cfg(feature = "chaos")]
+fn response(status: u16, body: &str) -> anyhow::Result<Response> {
+    let response = http::Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(body.to_string())?;
+    Ok(Response::from(response))
+}
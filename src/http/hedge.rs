@@ -0,0 +1,84 @@
+//! Hedged requests against slow upstream responses.
+//!
+//! Most upstream calls answer quickly, but an occasional one takes several
+//! seconds, and the tool call waits for it. A [`Hedge`] keeps the latencies
+//! of an upstream's recent responses. Once it has enough of them, a request
+//! sent with [`crate::http::send_hedged`] that has not been answered within
+//! their 95th percentile is sent a second time, and whichever copy answers
+//! first is used. Only `GET` requests are hedged, as repeating them is safe.
+//!
+//! Hedging is off unless `HTTP_HEDGING` is set, and never waits less than
+//! `HTTP_HEDGE_MIN_DELAY_MS`, so an upstream that is uniformly fast is not
+//! sent a second request for every call. How often requests were hedged,
+//! and which copy answered, is counted per upstream (see
+//! [`crate::metrics::observe_hedge`]).
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+/// Recent response latencies kept per upstream
+const WINDOW: usize = 100;
+
+/// Responses seen before requests are hedged
+const MIN_SAMPLES: usize = 20;
+
+/// Percentile of recent latencies after which a request is hedged
+const PERCENTILE: usize = 95;
+
+/// Recent response latencies of one upstream.
+#[derive(Debug)]
+pub struct Hedge {
+    name: &'static str,
+    latencies: Mutex<VecDeque<Duration>>,
+}
+
+impl Hedge {
+    /// Creates a hedge for the upstream named `name`, with no latencies yet.
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            latencies: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Name of the upstream.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Records a response that took `latency`, forgetting the oldest once
+    /// the window is full.
+    pub fn record(&self, latency: Duration) {
+        let mut latencies = self
+            .latencies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if latencies.len() >= WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+
+    /// How long to wait for a response before hedging: the 95th percentile
+    /// of recent latencies, but at least `min_delay`, or `None` until enough
+    /// responses have been seen.
+    #[must_use]
+    pub fn budget(&self, min_delay: Duration) -> Option<Duration> {
+        let mut latencies: Vec<Duration> = self
+            .latencies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .copied()
+            .collect();
+        if latencies.len() < MIN_SAMPLES {
+            return None;
+        }
+        latencies.sort_unstable();
+        let rank = (latencies.len() * PERCENTILE).div_ceil(100);
+        Some(latencies[rank - 1].max(min_delay))
+    }
+}
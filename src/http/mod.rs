@@ -1,26 +1,288 @@
-use reqwest::Client;
-use std::sync::LazyLock;
-use std::time::Duration;
+//! Shared HTTP client and helpers for calling upstream APIs.
+
+pub mod breaker;
+pub mod connections;
+pub mod dns;
+pub mod egress;
+pub mod failover;
+pub mod faults;
+pub mod hedge;
+pub mod transport;
+pub mod vcr;
+
+pub use breaker::CircuitBreaker;
+pub use dns::{CachingResolver, SystemResolver};
+pub use egress::EgressPolicy;
+pub use failover::EndpointPool;
+pub use faults::{Fault, FaultProfile};
+pub use hedge::Hedge;
+pub use vcr::{Vcr, VcrMode};
+
+use crate::buffers::{BUFFERS, Buffer};
+use crate::capture;
+use crate::config::config;
+use crate::metrics::{HedgeOutcome, observe_hedge};
+use crate::models::error::AppError;
+use crate::usage;
+
+use chrono::{DateTime, Utc};
+use lambda_runtime::tracing::{debug, warn};
+use reqwest::header::{ETAG, HeaderMap, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::{Client, Method, Request, RequestBuilder, Response};
+use serde_json::value::RawValue;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
 
 /// Global HTTP client with optimized configuration for Lambda environment.
 ///
 /// This client is configured with:
 /// - Connection timeout of 10 seconds
-/// - Request timeout of 30 seconds
+/// - Request timeout of 30 seconds, as a backstop; each request also sets a
+///   deadline-aware timeout from [`crate::deadline::upstream_timeout`]
 /// - Connection pool with max of 10 idle connections per host
 /// - TCP keepalive enabled
 /// - Compression support (GZIP, Brotli, Deflate)
+/// - Cached DNS resolution (see [`dns`]), unless `DNS_CACHE` is off; either
+///   way, resolved addresses the egress policy denies are never connected to
+/// - Redirects followed only to targets the egress policy permits (see
+///   [`EgressPolicy::redirect_policy`])
+/// - The client certificate, extra CA certificates and proxy read by
+///   [`transport::load`], if any
 pub static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
     // In a Lambda environment, we can safely panic on startup if the client can't be created
     // as this indicates a fundamental configuration issue
-    Client::builder()
+    let builder = Client::builder()
         .connect_timeout(Duration::from_secs(10))
         .timeout(Duration::from_secs(30))
         .pool_max_idle_per_host(10)
-        .tcp_keepalive(Duration::from_secs(60))
+        .tcp_keepalive(Duration::from_mins(1))
         .gzip(true)
         .brotli(true)
         .deflate(true)
-        .build()
-        .unwrap_or_else(|_| Client::new())
+        .redirect(EGRESS.redirect_policy());
+    let builder = if config().http.dns_cache {
+        match CachingResolver::from_system_conf() {
+            Ok(resolver) => builder.dns_resolver(Arc::new(resolver)),
+            Err(e) => {
+                warn!(error = %e, "Failed to configure the DNS resolver, using the system resolver");
+                builder.dns_resolver(Arc::new(SystemResolver))
+            }
+        }
+    } else {
+        builder.dns_resolver(Arc::new(SystemResolver))
+    };
+    transport::configure(builder).build().unwrap_or_else(|_| {
+        Client::builder()
+            .redirect(EGRESS.redirect_policy())
+            .dns_resolver(Arc::new(SystemResolver))
+            .build()
+            .unwrap_or_default()
+    })
 });
+
+/// Record/replay behaviour for [`send`], from `HTTP_VCR_MODE` and `HTTP_VCR_DIR`.
+static VCR: LazyLock<Vcr> = LazyLock::new(|| {
+    let settings = &config().http;
+    Vcr::new(settings.vcr_mode, &settings.vcr_dir)
+});
+
+/// Domain policy for [`send`], from `EGRESS_ALLOWLIST`, `EGRESS_DENYLIST`
+/// and `EGRESS_ALLOW_PRIVATE`.
+static EGRESS: LazyLock<EgressPolicy> = LazyLock::new(|| {
+    let settings = &config().http;
+    let policy = EgressPolicy::new(&settings.egress_allowlist, &settings.egress_denylist);
+    if settings.egress_allow_private {
+        policy.allowing_private_networks()
+    } else {
+        policy
+    }
+});
+
+/// Sends an upstream request built from [`HTTP_CLIENT`].
+///
+/// All upstream calls go through here so they are checked against the
+/// egress policy (see [`egress`]), can be recorded or replayed (see
+/// [`vcr`]), are counted against the caller's tenant (see
+/// [`crate::usage`]), have their connection reuse counted (see
+/// [`connections`]) and are added to the invocation's trace while one is
+/// captured (see [`crate::capture`]).
+///
+/// # Errors
+///
+/// Returns an error if the request cannot be built or sent, if the egress
+/// policy denies it (an `AppError::EgressDenied`, see [`send_error`]), or,
+/// in replay mode, if no fixture was recorded for it.
+pub async fn send(request: RequestBuilder) -> anyhow::Result<Response> {
+    execute(request.build()?).await
+}
+
+/// Sends an upstream request like [`send`], sending a second copy if it is
+/// slow to answer by `hedge`'s recent latencies and returning whichever
+/// answers first (see [`hedge`]).
+///
+/// Requests other than `GET` are never hedged, nor are any while
+/// `HTTP_HEDGING` is off.
+///
+/// # Errors
+///
+/// As for [`send`]; a hedged request fails only if both copies do, with the
+/// error of the one that failed last.
+pub async fn send_hedged(request: RequestBuilder, hedge: &Hedge) -> anyhow::Result<Response> {
+    let request = request.build()?;
+    let settings = &config().http;
+    let budget = (settings.hedging && *request.method() == Method::GET)
+        .then(|| hedge.budget(settings.hedge_min_delay))
+        .flatten();
+    let started = Instant::now();
+    let (Some(budget), Some(copy)) = (budget, budget.and_then(|_| request.try_clone())) else {
+        let result = execute(request).await;
+        record_answer(hedge, result.is_ok(), started, HedgeOutcome::Unhedged);
+        return result;
+    };
+
+    let primary = execute(request);
+    tokio::pin!(primary);
+    tokio::select! {
+        result = &mut primary => {
+            record_answer(hedge, result.is_ok(), started, HedgeOutcome::Unhedged);
+            return result;
+        }
+        () = sleep(budget) => {}
+    }
+    debug!(
+        upstream = hedge.name(),
+        ?budget,
+        "Upstream slow to answer, hedging the request"
+    );
+    let hedged = Instant::now();
+    let copy = execute(copy);
+    tokio::pin!(copy);
+    tokio::select! {
+        result = &mut primary => if result.is_ok() {
+            record_answer(hedge, true, started, HedgeOutcome::Primary);
+            result
+        } else {
+            let result = copy.await;
+            record_answer(hedge, result.is_ok(), hedged, HedgeOutcome::Hedge);
+            result
+        },
+        result = &mut copy => if result.is_ok() {
+            record_answer(hedge, true, hedged, HedgeOutcome::Hedge);
+            result
+        } else {
+            let result = primary.await;
+            record_answer(hedge, result.is_ok(), started, HedgeOutcome::Primary);
+            result
+        },
+    }
+}
+
+/// Sends a built request, after checking it against the egress policy.
+///
+/// With the `chaos` feature, a fault may be injected in place of the
+/// upstream's answer (see [`faults`]).
+async fn execute(request: Request) -> anyhow::Result<Response> {
+    EGRESS.check(request.url())?;
+    usage::record_upstream_call();
+    let url = request.url().clone();
+    let captured = capture::request(&request);
+    #[cfg(feature = "chaos")]
+    let result = match faults::inject(&request).await {
+        Some(result) => result,
+        None => VCR.execute(&HTTP_CLIENT, request).await,
+    };
+    #[cfg(not(feature = "chaos"))]
+    let result = VCR.execute(&HTTP_CLIENT, request).await;
+    if let Ok(response) = &result {
+        connections::observe(&url, response);
+    }
+    match captured {
+        Some(pending) => capture::exchange(pending, result).await,
+        None => result,
+    }
+}
+
+/// Counts the copy of a hedgeable request that answered, recording its
+/// latency from `sent` if it succeeded.
+fn record_answer(hedge: &Hedge, succeeded: bool, sent: Instant, outcome: HedgeOutcome) {
+    if succeeded {
+        hedge.record(sent.elapsed());
+    }
+    observe_hedge(hedge.name(), outcome);
+}
+
+/// Converts a [`send`] error into an `AppError`.
+///
+/// Egress policy denials are returned as they are, including those of a
+/// redirect or a resolved address, which reqwest wraps; other errors are
+/// passed to `wrap`.
+pub fn send_error(error: anyhow::Error, wrap: impl FnOnce(anyhow::Error) -> AppError) -> AppError {
+    let denied = error.chain().find_map(|cause| match cause.downcast_ref() {
+        Some(AppError::EgressDenied(message)) => Some(message.clone()),
+        _ => None,
+    });
+    denied.map_or_else(
+        || error.downcast::<AppError>().unwrap_or_else(wrap),
+        AppError::EgressDenied,
+    )
+}
+
+/// Reads a response body into a pooled buffer (see [`crate::buffers`]), for
+/// bodies that are parsed and then discarded.
+///
+/// # Errors
+///
+/// Returns an error if the body cannot be read.
+pub async fn read_body(mut response: Response) -> reqwest::Result<Buffer<'static>> {
+    let mut body = BUFFERS.take();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Reads a JSON response body to pass through to the caller unchanged.
+///
+/// The body is checked to be valid JSON, but is not parsed into a tree or
+/// copied, so a large upstream document costs one scan rather than building
+/// and re-serializing every value in it.
+///
+/// # Errors
+///
+/// Returns an error if the body cannot be read or is not valid JSON.
+pub async fn passthrough(response: Response) -> anyhow::Result<Box<RawValue>> {
+    Ok(RawValue::from_string(response.text().await?)?)
+}
+
+/// Parses a `Retry-After` header, given either as delay seconds or an HTTP date.
+#[must_use]
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Reads the `ETag` header from a response.
+#[must_use]
+pub fn etag(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(ETAG)?
+        .to_str()
+        .ok()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+}
+
+/// Makes a request conditional on the cached representation's `ETag`.
+///
+/// The upstream answers `304 Not Modified` if `etag` is still current.
+pub fn if_none_match(request: RequestBuilder, etag: Option<&str>) -> RequestBuilder {
+    match etag {
+        Some(tag) => request.header(IF_NONE_MATCH, tag),
+        None => request,
+    }
+}
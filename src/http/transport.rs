@@ -0,0 +1,186 @@
+//! Client certificates, private CA bundles and proxies for outbound calls.
+//!
+//! Internal services behind private ALBs are usually reached from a
+//! VPC-attached Lambda, often through an egress proxy, and may require
+//! mutual TLS with certificates issued by a private CA. [`HTTP_CLIENT`] can
+//! be configured for all three:
+//!
+//! - `HTTP_CLIENT_CERT_SECRET_ARN` names a Secrets Manager secret holding
+//!   the client certificate chain and its private key, either as one PEM
+//!   document or as `{"certificate": "<PEM>", "private_key": "<PEM>"}`
+//! - `HTTP_CA_BUNDLE_SECRET_ARN` names a secret holding PEM CA certificates
+//!   trusted in addition to the system roots
+//! - `HTTP_PROXY_URL` sends every request through a proxy, except those to
+//!   the hosts, domains and CIDR ranges in `HTTP_NO_PROXY`
+//!
+//! Secrets are read once, by [`load`] when the container starts and before
+//! the client is first used, so a missing or malformed secret stops the
+//! container instead of surfacing as handshake failures on every call.
+//!
+//! [`HTTP_CLIENT`]: super::HTTP_CLIENT
+
+use crate::aws::sdk_config;
+use crate::config::config;
+use crate::models::error::AppError;
+use lambda_runtime::tracing::info;
+use reqwest::{Certificate, ClientBuilder, Identity, NoProxy, Proxy};
+use serde::Deserialize;
+use std::sync::OnceLock;
+use tokio::sync::OnceCell;
+
+/// Settings loaded by [`load`], applied when the client is built.
+static TRANSPORT: OnceLock<Transport> = OnceLock::new();
+
+static SECRETS_CLIENT: OnceCell<aws_sdk_secretsmanager::Client> = OnceCell::const_new();
+
+/// TLS material and proxy for outbound connections.
+#[derive(Clone, Default)]
+pub struct Transport {
+    identity: Option<Identity>,
+    roots: Vec<Certificate>,
+    proxy: Option<Proxy>,
+}
+
+#[derive(Deserialize)]
+struct ClientCertSecret {
+    certificate: String,
+    private_key: String,
+}
+
+impl Transport {
+    /// Builds the transport from the contents of the client certificate and
+    /// CA bundle secrets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client certificate or a CA certificate cannot
+    /// be parsed.
+    pub fn new(client_cert: Option<&str>, ca_bundle: Option<&str>) -> Result<Self, AppError> {
+        let identity = client_cert
+            .map(|secret| {
+                Identity::from_pem(client_cert_pem(secret).as_bytes())
+                    .map_err(|e| AppError::GenericError(format!("Invalid client certificate: {e}")))
+            })
+            .transpose()?;
+        let roots = ca_bundle
+            .map(|bundle| {
+                Certificate::from_pem_bundle(bundle.as_bytes())
+                    .map_err(|e| AppError::GenericError(format!("Invalid CA bundle: {e}")))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self {
+            identity,
+            roots,
+            proxy: None,
+        })
+    }
+
+    /// Sends requests through the proxy at `url`, except those to the hosts,
+    /// domains and CIDR ranges in `no_proxy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `url` is not a valid proxy URL.
+    pub fn with_proxy(mut self, url: &str, no_proxy: &[String]) -> Result<Self, AppError> {
+        let proxy = Proxy::all(url)
+            .map_err(|e| AppError::GenericError(format!("Invalid HTTP_PROXY_URL: {e}")))?;
+        self.proxy = Some(proxy.no_proxy(NoProxy::from_string(&no_proxy.join(","))));
+        Ok(self)
+    }
+
+    /// Whether a client certificate is presented.
+    #[must_use]
+    pub const fn has_identity(&self) -> bool {
+        self.identity.is_some()
+    }
+
+    /// Number of CA certificates trusted besides the system roots.
+    #[must_use]
+    pub const fn extra_roots(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Whether requests go through a proxy.
+    #[must_use]
+    pub const fn is_proxied(&self) -> bool {
+        self.proxy.is_some()
+    }
+
+    /// Applies the certificate, CA certificates and proxy to `builder`.
+    pub fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        if let Some(identity) = &self.identity {
+            builder = builder.identity(identity.clone());
+        }
+        for root in &self.roots {
+            builder = builder.add_root_certificate(root.clone());
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        builder
+    }
+}
+
+/// Reads the secrets named in the configuration and keeps the transport for
+/// [`configure`]; call once at startup, before the first request.
+///
+/// # Errors
+///
+/// Returns an error if a secret cannot be read or its contents, or the proxy
+/// URL, cannot be parsed.
+pub async fn load() -> Result<(), AppError> {
+    let settings = &config().http;
+    let client_cert = read_secret(settings.client_cert_secret_arn.as_deref()).await?;
+    let ca_bundle = read_secret(settings.ca_bundle_secret_arn.as_deref()).await?;
+    let mut transport = Transport::new(client_cert.as_deref(), ca_bundle.as_deref())?;
+    if let Some(url) = settings.proxy_url.as_deref() {
+        transport = transport.with_proxy(url, &settings.no_proxy)?;
+    }
+    if transport.has_identity() || transport.extra_roots() > 0 || transport.is_proxied() {
+        info!(
+            client_certificate = transport.has_identity(),
+            extra_roots = transport.extra_roots(),
+            proxied = transport.is_proxied(),
+            "Configured outbound transport"
+        );
+    }
+    // A second call keeps the first transport, which the client may already use
+    let _ = TRANSPORT.set(transport);
+    Ok(())
+}
+
+/// Applies the transport kept by [`load`] to `builder`, if it has run.
+pub fn configure(builder: ClientBuilder) -> ClientBuilder {
+    match TRANSPORT.get() {
+        Some(transport) => transport.apply(builder),
+        None => builder,
+    }
+}
+
+/// The client certificate secret as one PEM document.
+fn client_cert_pem(secret: &str) -> String {
+    serde_json::from_str::<ClientCertSecret>(secret).map_or_else(
+        |_| secret.to_string(),
+        |parts| format!("{}\n{}", parts.certificate.trim(), parts.private_key.trim()),
+    )
+}
+
+async fn read_secret(secret_arn: Option<&str>) -> Result<Option<String>, AppError> {
+    let Some(secret_arn) = secret_arn else {
+        return Ok(None);
+    };
+    let client = SECRETS_CLIENT
+        .get_or_init(|| async { aws_sdk_secretsmanager::Client::new(sdk_config().await) })
+        .await;
+    let output = client
+        .get_secret_value()
+        .secret_id(secret_arn)
+        .send()
+        .await
+        .map_err(|e| AppError::GenericError(format!("Secrets Manager read failed: {e}")))?;
+    output
+        .secret_string()
+        .map(|secret| Some(secret.to_string()))
+        .ok_or_else(|| AppError::GenericError(format!("Secret {secret_arn} has no string value")))
+}
@@ -0,0 +1,173 @@
+//! Record and replay of upstream HTTP exchanges.
+//!
+//! With `HTTP_VCR_MODE=record`, every upstream response is saved as a JSON
+//! fixture in `HTTP_VCR_DIR`. With `HTTP_VCR_MODE=replay`, responses are
+//! served from those fixtures and the network is never touched, giving tests
+//! and local development deterministic, realistic upstream behaviour.
+//! The default mode, `off`, sends requests normally.
+//!
+//! Fixtures are keyed by method and full URL.
+
+use anyhow::{Context, Result, bail};
+use aws_lc_rs::digest;
+use lambda_runtime::tracing::{debug, info};
+use reqwest::{Client, Request, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Response headers worth keeping in fixtures.
+const RECORDED_HEADERS: &[&str] = &["content-type", "etag", "last-modified", "retry-after"];
+
+/// How upstream requests are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VcrMode {
+    /// Send requests to the upstream (the default)
+    #[default]
+    Off,
+    /// Send requests and save each response as a fixture
+    Record,
+    /// Serve responses from fixtures without touching the network
+    Replay,
+}
+
+impl VcrMode {
+    /// Parses `off`, `record` or `replay`, case-insensitively.
+    #[must_use]
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "off" => Some(Self::Off),
+            "record" => Some(Self::Record),
+            "replay" => Some(Self::Replay),
+            _ => None,
+        }
+    }
+}
+
+/// A recorded upstream exchange.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl Fixture {
+    /// Path of the fixture for `method` and `url` within `dir`.
+    ///
+    /// File names start with the host, for readability, followed by a hash
+    /// of the method and URL.
+    #[must_use]
+    pub fn path(dir: &Path, method: &str, url: &reqwest::Url) -> PathBuf {
+        let hash = digest::digest(&digest::SHA256, format!("{method} {url}").as_bytes());
+        let mut name = url.host_str().unwrap_or("unknown").to_string();
+        name.push('-');
+        for byte in &hash.as_ref()[..8] {
+            let _ = write!(name, "{byte:02x}");
+        }
+        name.push_str(".json");
+        dir.join(name)
+    }
+
+    /// Builds a response that replays this fixture.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recorded status or headers are invalid.
+    pub fn to_response(&self) -> Result<Response> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(self.body.clone())
+            .context("Invalid recorded response")?;
+        Ok(Response::from(response))
+    }
+}
+
+/// Sends requests according to a [`VcrMode`].
+#[derive(Debug, Clone)]
+pub struct Vcr {
+    mode: VcrMode,
+    dir: PathBuf,
+}
+
+impl Vcr {
+    #[must_use]
+    pub fn new(mode: VcrMode, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode,
+            dir: dir.into(),
+        }
+    }
+
+    #[must_use]
+    pub const fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    /// Executes `request`, recording or replaying it as configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, a fixture cannot be written,
+    /// or no fixture exists for the request in replay mode.
+    pub async fn execute(&self, client: &Client, request: Request) -> Result<Response> {
+        match self.mode {
+            VcrMode::Off => Ok(client.execute(request).await?),
+            VcrMode::Replay => self.replay(&request),
+            VcrMode::Record => self.record(client, request).await,
+        }
+    }
+
+    fn replay(&self, request: &Request) -> Result<Response> {
+        let path = Fixture::path(&self.dir, request.method().as_str(), request.url());
+        let Ok(raw) = fs::read_to_string(&path) else {
+            bail!(
+                "No recorded fixture for {} {} (expected {})",
+                request.method(),
+                request.url(),
+                path.display()
+            );
+        };
+        debug!(path = %path.display(), "Replaying recorded response");
+        let fixture: Fixture = serde_json::from_str(&raw)
+            .with_context(|| format!("Invalid fixture {}", path.display()))?;
+        fixture.to_response()
+    }
+
+    async fn record(&self, client: &Client, request: Request) -> Result<Response> {
+        let method = request.method().to_string();
+        let url = request.url().clone();
+        let response = client.execute(request).await?;
+
+        let headers = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| RECORDED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let fixture = Fixture {
+            method,
+            status: response.status().as_u16(),
+            headers,
+            body: response.text().await?,
+            url: url.to_string(),
+        };
+
+        let path = Fixture::path(&self.dir, &fixture.method, &url);
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        fs::write(&path, serde_json::to_string_pretty(&fixture)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!(path = %path.display(), "Recorded upstream response");
+
+        fixture.to_response()
+    }
+}
@@ -0,0 +1,243 @@
+//! Signed caller identity passed from the interceptor to the tool Lambda.
+//!
+//! Plain `user_id`/`user_name` arguments can be set by any client, so the
+//! interceptor instead attaches an HMAC-signed `identity_context` blob built
+//! from the caller's token. Before a tool runs, the handler verifies the blob
+//! and replaces the identity arguments with its contents (see
+//! [`apply_verified_identity`]).
+//!
+//! Signing keys come from the Secrets Manager secret named by
+//! `IDENTITY_SECRET_ARN`, or from `IDENTITY_CONTEXT_SECRET`. The secret is
+//! either a single key or a key ring of the form
+//! `{"current": "2026-10", "keys": {"2026-10": "...", "2026-07": "..."}}`:
+//! blobs are signed with the current key and verified against any key in the
+//! ring, so keys can be rotated without rejecting blobs in flight. The ring is
+//! re-read every `IDENTITY_KEY_REFRESH_SECS`.
+
+use crate::aws::sdk_config;
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::models::error::AppError;
+use aws_lc_rs::hmac;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use lambda_runtime::tracing::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::OnceCell;
+
+/// Argument the interceptor uses to forward the signed identity
+pub const IDENTITY_CONTEXT_ARG: &str = "identity_context";
+/// Caller ID argument, only trusted when it comes from a verified context
+pub const USER_ID_ARG: &str = "user_id";
+/// Caller name argument, only trusted when it comes from a verified context
+pub const USER_NAME_ARG: &str = "user_name";
+
+/// Key ID used when the secret holds a single key
+const DEFAULT_KEY_ID: &str = "default";
+
+/// Cache key for the key ring
+const KEY_RING_KEY: &str = "key-ring";
+
+pub(crate) static KEY_RING_CACHE: LazyLock<CacheStore<Arc<KeyRing>>> =
+    LazyLock::new(|| CacheStore::new("identity-keys", config().identity.key_refresh, 1));
+
+static SECRETS_CLIENT: OnceCell<aws_sdk_secretsmanager::Client> = OnceCell::const_new();
+
+/// Caller identity vouched for by the interceptor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityContext {
+    #[serde(rename = "uid")]
+    pub user_id: String,
+    #[serde(rename = "name")]
+    pub user_name: String,
+    /// Expiry, in seconds since the Unix epoch
+    pub exp: u64,
+}
+
+impl IdentityContext {
+    /// Creates a context that expires after `IDENTITY_CONTEXT_TTL_SECS`.
+    #[must_use]
+    pub fn new(user_id: String, user_name: String) -> Self {
+        Self {
+            user_id,
+            user_name,
+            exp: now_secs().saturating_add(config().identity.ttl.as_secs()),
+        }
+    }
+}
+
+/// Signing keys by ID, and the ID of the key new blobs are signed with.
+pub struct KeyRing {
+    current: String,
+    keys: HashMap<String, hmac::Key>,
+}
+
+#[derive(Deserialize)]
+struct KeyRingSecret {
+    current: String,
+    keys: HashMap<String, String>,
+}
+
+impl KeyRing {
+    /// Parses a secret holding either a single key or a JSON key ring.
+    ///
+    /// Returns `None` if the secret is empty or the current key is missing.
+    #[must_use]
+    pub fn parse(secret: &str) -> Option<Self> {
+        let secret = secret.trim();
+        if secret.is_empty() {
+            return None;
+        }
+        let Ok(ring) = serde_json::from_str::<KeyRingSecret>(secret) else {
+            return Some(Self {
+                current: DEFAULT_KEY_ID.to_string(),
+                keys: HashMap::from([(DEFAULT_KEY_ID.to_string(), signing_key(secret))]),
+            });
+        };
+        if !ring.keys.contains_key(&ring.current) {
+            warn!(current = %ring.current, "Identity key ring has no current key");
+            return None;
+        }
+        Some(Self {
+            current: ring.current,
+            keys: ring
+                .keys
+                .iter()
+                .map(|(id, key)| (id.clone(), signing_key(key)))
+                .collect(),
+        })
+    }
+
+    /// Encodes and signs `context` with the current key.
+    #[must_use]
+    pub fn sign(&self, context: &IdentityContext) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(context).unwrap_or_default());
+        let input = format!("{}.{payload}", self.current);
+        let tag = self
+            .keys
+            .get(&self.current)
+            .map(|key| hmac::sign(key, input.as_bytes()))
+            .map(|tag| URL_SAFE_NO_PAD.encode(tag.as_ref()))
+            .unwrap_or_default();
+        format!("{input}.{tag}")
+    }
+
+    /// Verifies a signed blob against any key in the ring and decodes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Unauthorized` if the blob is malformed, signed with
+    /// an unknown key, modified, or expired.
+    pub fn verify(&self, token: &str) -> Result<IdentityContext, AppError> {
+        let invalid = || AppError::Unauthorized("Invalid identity context".to_string());
+
+        let (input, tag) = token.trim().rsplit_once('.').ok_or_else(invalid)?;
+        let (key_id, payload) = input.split_once('.').ok_or_else(invalid)?;
+        let key = self.keys.get(key_id).ok_or_else(invalid)?;
+        let tag = URL_SAFE_NO_PAD.decode(tag).map_err(|_| invalid())?;
+        hmac::verify(key, input.as_bytes(), &tag).map_err(|_| invalid())?;
+
+        let bytes = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+        let context: IdentityContext = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+        if context.exp < now_secs() {
+            return Err(AppError::Unauthorized(
+                "Identity context has expired".to_string(),
+            ));
+        }
+        Ok(context)
+    }
+}
+
+/// Returns the configured key ring, re-reading it once the cached copy expires.
+///
+/// If the secret cannot be read, the previous ring keeps being used.
+pub async fn key_ring() -> Option<Arc<KeyRing>> {
+    if let Some(ring) = KEY_RING_CACHE.get(KEY_RING_KEY) {
+        return Some(ring);
+    }
+    match load_secret().await {
+        Ok(Some(secret)) => {
+            let ring = Arc::new(KeyRing::parse(&secret)?);
+            info!(current = %ring.current, "Loaded identity signing keys");
+            KEY_RING_CACHE.insert(KEY_RING_KEY, Arc::clone(&ring));
+            Some(ring)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!(error = %e, "Failed to load identity signing keys");
+            KEY_RING_CACHE.get_stale(KEY_RING_KEY)
+        }
+    }
+}
+
+/// Replaces identity arguments with the verified contents of `identity_context`.
+///
+/// Without a signed context, unsigned identity arguments are removed when
+/// `IDENTITY_CONTEXT_REQUIRED` is enabled or a signing key is configured,
+/// since the interceptor then always signs the identity it vouches for, and
+/// passed through otherwise.
+///
+/// Returns the verified context, if there was one.
+///
+/// # Errors
+///
+/// Returns `AppError::Unauthorized` if a context is present but cannot be
+/// verified.
+pub async fn apply_verified_identity(
+    tool_args: &mut Value,
+) -> Result<Option<IdentityContext>, AppError> {
+    let Some(args) = tool_args.as_object_mut() else {
+        return Ok(None);
+    };
+
+    let Some(token) = args.remove(IDENTITY_CONTEXT_ARG) else {
+        if config().identity.required || key_ring().await.is_some() {
+            let dropped_id = args.remove(USER_ID_ARG).is_some();
+            let dropped_name = args.remove(USER_NAME_ARG).is_some();
+            if dropped_id || dropped_name {
+                warn!("Dropping unsigned identity arguments");
+            }
+        }
+        return Ok(None);
+    };
+
+    let ring = key_ring().await.ok_or_else(|| {
+        AppError::Unauthorized("No identity signing key is configured".to_string())
+    })?;
+    let context = ring.verify(token.as_str().unwrap_or_default())?;
+    args.insert(USER_ID_ARG.to_string(), json!(context.user_id));
+    args.insert(USER_NAME_ARG.to_string(), json!(context.user_name));
+    Ok(Some(context))
+}
+
+async fn load_secret() -> Result<Option<String>, AppError> {
+    let settings = &config().identity;
+    let Some(secret_arn) = settings.secret_arn.as_deref() else {
+        return Ok(settings.secret.clone());
+    };
+
+    let client = SECRETS_CLIENT
+        .get_or_init(|| async { aws_sdk_secretsmanager::Client::new(sdk_config().await) })
+        .await;
+    let output = client
+        .get_secret_value()
+        .secret_id(secret_arn)
+        .send()
+        .await
+        .map_err(|e| AppError::GenericError(format!("Secrets Manager read failed: {e}")))?;
+    Ok(output.secret_string().map(String::from))
+}
+
+fn signing_key(secret: &str) -> hmac::Key {
+    hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
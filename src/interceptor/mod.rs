@@ -0,0 +1,329 @@
+//! Bedrock `AgentCore` Gateway request interceptor.
+//!
+//! The interceptor Lambda (`src/bin/interceptor.rs`) sees every request
+//! before the gateway forwards it. For tool calls it injects the caller's
+//! token and identity, the conversation session ID, the requested response
+//! version and the trace capture flag into the tool arguments, and logs
+//! whether the tool scope policy would reject the call. Tools trust the
+//! token and identity arguments they receive, so any copies the client sent
+//! itself are removed first, even in passthrough mode. Oversized or deeply
+//! nested bodies, and calls whose token cannot be encrypted, are answered
+//! directly instead of forwarded.
+
+use crate::auth::AUTH_TOKEN_ARG;
+use crate::capture::{self, TRACE_CAPTURE_ARG, TRACE_CAPTURE_HEADER};
+use crate::catalog;
+use crate::config::config;
+use crate::context::{SESSION_ID_ARG, session_id_from_headers};
+use crate::crypto;
+use crate::guards::check_body;
+use crate::identity::{
+    IDENTITY_CONTEXT_ARG, IdentityContext, USER_ID_ARG, USER_NAME_ARG, key_ring,
+};
+use crate::models::Headers;
+use crate::models::error::AppError;
+use crate::models::interceptor::{
+    GatewayResponse, InterceptorDecision, InterceptorEvent, InterceptorResponse,
+};
+use crate::models::versioning::{RESPONSE_VERSION_ARG, RESPONSE_VERSION_HEADER, ResponseVersion};
+use crate::utils::strip_gateway_prefix;
+use jsonwebtoken::dangerous::insecure_decode;
+use lambda_runtime::Error;
+use lambda_runtime::tracing::{debug, info, warn};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Arguments only the interceptor may set; copies the client sent are removed
+pub const RESERVED_ARGS: &[&str] = &[
+    AUTH_TOKEN_ARG,
+    IDENTITY_CONTEXT_ARG,
+    USER_ID_ARG,
+    USER_NAME_ARG,
+];
+
+/// Minimal JWT claims for extracting user information.
+#[derive(Debug, Deserialize)]
+struct Claims {
+    exp: Option<u64>,
+    sub: Option<String>,
+    name: Option<String>,
+    email: Option<String>,
+    preferred_username: Option<String>,
+}
+
+/// Extract authorization token from headers.
+fn extract_auth_token(headers: &Headers) -> Option<&str> {
+    headers
+        .get("authorization")
+        .map(|v| v.strip_prefix("Bearer ").unwrap_or(v))
+}
+
+/// Extract the requested response version from headers
+fn extract_response_version(headers: &Headers) -> Option<ResponseVersion> {
+    headers
+        .get(RESPONSE_VERSION_HEADER)
+        .and_then(ResponseVersion::parse)
+}
+
+/// Whether the headers ask for a trace of the call to be captured
+fn extract_trace_capture(headers: &Headers) -> bool {
+    headers
+        .get(TRACE_CAPTURE_HEADER)
+        .is_some_and(capture::is_truthy)
+}
+
+/// Extract tool name from the request body
+fn extract_tool_name(body: &Value) -> Option<String> {
+    body.get("params")
+        .and_then(|params| params.get("name"))
+        .and_then(serde_json::Value::as_str)
+        .map(strip_gateway_prefix)
+}
+
+/// Insecurely decodes a JWT to extract user ID and name without validation.
+/// Checks for token expiry.
+///
+/// # Returns
+///
+/// Returns `None` if:
+/// - Token cannot be decoded
+/// - Token is expired
+/// - Required claims are missing
+fn extract_user_info_from_token(token: &str) -> Option<(String, String)> {
+    let claims = insecure_decode::<Claims>(token).map(|d| d.claims).ok()?;
+
+    // Check token expiry if present
+    if let Some(exp) = claims.exp {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        if exp < now {
+            warn!(message = "Token is expired");
+            return None;
+        }
+    }
+
+    let user_id = claims
+        .sub
+        .or_else(|| claims.preferred_username.clone())
+        .or(claims.email)?;
+
+    let user_name = claims.name.unwrap_or_else(|| {
+        claims
+            .preferred_username
+            .unwrap_or_else(|| user_id.split('@').next().unwrap_or(&user_id).to_string())
+    });
+
+    Some((user_id, user_name))
+}
+
+/// Handles interceptor events from the Bedrock `AgentCore` Gateway.
+///
+/// This function:
+/// 1. Refuses request bodies over the size or nesting limits (see
+///    [`crate::guards`]), then parses incoming interceptor events
+/// 2. Identifies tool calls
+/// 3. Decides which authentication tokens, user information, conversation
+///    session ID, requested response version and trace capture flag to
+///    inject, and whether the tool scope policy would reject the call
+/// 4. Logs the decision and, unless `PASSTHROUGH_MODE` is enabled, applies it
+/// 5. Forwards requests to the main Lambda
+///
+/// # Errors
+///
+/// Returns an error if `payload` is not an interceptor event.
+pub async fn handle(payload: Value) -> Result<InterceptorResponse, Error> {
+    if let Some(body) = payload.pointer("/mcp/gatewayRequest/body")
+        && let Err(rejection) = check_body(body, config().interceptor.body_limits)
+    {
+        warn!(error = %rejection, "Refusing request body");
+        let message = catalog::BODY_REJECTED.render(&rejection.to_string());
+        return Ok(refuse(body, rejection.status_code(), &message));
+    }
+    info!(payload = ?payload, "Interceptor handler invoked");
+    let interceptor_event: InterceptorEvent = serde_json::from_value(payload)?;
+    let mut gateway_request = interceptor_event.mcp.gateway_request;
+
+    let is_tool_call = gateway_request
+        .body
+        .as_ref()
+        .and_then(|b| b.get("method"))
+        .is_some_and(|m| m == "tools/call");
+
+    if !is_tool_call {
+        debug!(message = "Skipping non-tool request");
+        return Ok(InterceptorResponse::forward(gateway_request));
+    }
+
+    let tool_name = gateway_request.body.as_ref().and_then(extract_tool_name);
+    let token = gateway_request
+        .headers
+        .as_ref()
+        .and_then(extract_auth_token)
+        .map(str::to_string);
+    let arguments = gateway_request
+        .body
+        .as_ref()
+        .and_then(|b| b.get("params"))
+        .and_then(|p| p.get("arguments"))
+        .and_then(Value::as_object);
+    let has_arguments = arguments.is_some();
+    let stripped = arguments.map(client_credentials).unwrap_or_default();
+    // An explicit argument takes precedence over the header
+    let response_version = arguments
+        .filter(|args| !args.contains_key(RESPONSE_VERSION_ARG))
+        .and(gateway_request.headers.as_ref())
+        .and_then(extract_response_version);
+    // The gateway's session ID replaces any the caller passed
+    let session_id = gateway_request.headers.as_ref().and_then(|headers| {
+        session_id_from_headers(headers, &config().interceptor.session_headers)
+    });
+    let trace_capture = gateway_request
+        .headers
+        .as_ref()
+        .is_some_and(extract_trace_capture);
+
+    let injections = match token.as_deref() {
+        Some(token) if has_arguments => plan_injections(token, tool_name.as_deref()).await,
+        _ => Ok(Vec::new()),
+    };
+    let mut injections = match injections {
+        Ok(injections) => injections,
+        Err(e) => {
+            warn!(error = %e, "Refusing tool call whose token cannot be encrypted");
+            let body = gateway_request.body.as_ref().unwrap_or(&Value::Null);
+            return Ok(refuse(body, 500, &catalog::ENCRYPTION_FAILED.render("")));
+        }
+    };
+    if let Some(version) = response_version {
+        injections.push((RESPONSE_VERSION_ARG, json!(version)));
+    }
+    if has_arguments && let Some(session_id) = &session_id {
+        injections.push((SESSION_ID_ARG, json!(session_id)));
+    }
+    if has_arguments && trace_capture {
+        injections.push((TRACE_CAPTURE_ARG, json!(true)));
+    }
+
+    let passthrough = config().interceptor.passthrough;
+    let decision = InterceptorDecision {
+        blocked: tool_name.as_deref().and_then(|tool| {
+            config()
+                .auth
+                .policy
+                .authorize(tool, token.as_deref())
+                .err()
+                .map(|e| e.to_string())
+        }),
+        tool: tool_name,
+        session_id,
+        applied: !passthrough,
+        injected: injections.iter().map(|(name, _)| *name).collect(),
+        stripped,
+    };
+    info!(
+        decision = %serde_json::to_string(&decision).unwrap_or_default(),
+        "Interceptor decision"
+    );
+
+    // Client copies are stripped even in passthrough mode, since tools
+    // trust whatever token and identity arguments reach them
+    if let Some(arguments) = gateway_request
+        .body
+        .as_mut()
+        .and_then(|b| b.get_mut("params"))
+        .and_then(|p| p.get_mut("arguments"))
+        .and_then(|a| a.as_object_mut())
+    {
+        apply(arguments, if passthrough { Vec::new() } else { injections });
+    }
+
+    Ok(InterceptorResponse::forward(gateway_request))
+}
+
+/// The credential and identity arguments a client sent itself.
+fn client_credentials(arguments: &Map<String, Value>) -> Vec<&'static str> {
+    let sent: Vec<&'static str> = RESERVED_ARGS
+        .iter()
+        .copied()
+        .filter(|name| arguments.contains_key(*name))
+        .collect();
+    if !sent.is_empty() {
+        warn!(arguments = ?sent, "Client sent credential or identity arguments");
+    }
+    sent
+}
+
+/// Replaces any credential and identity arguments the client sent with the
+/// interceptor's `injections`.
+fn apply(arguments: &mut Map<String, Value>, injections: Vec<(&'static str, Value)>) {
+    for name in RESERVED_ARGS {
+        arguments.remove(*name);
+    }
+    for (name, value) in injections {
+        arguments.insert(name.to_string(), value);
+    }
+}
+
+/// Answers a refused request with a JSON-RPC error carrying the catalogued
+/// `message`, echoing the request ID when it is a number or short string.
+fn refuse(body: &Value, status_code: u16, message: &str) -> InterceptorResponse {
+    let id = body
+        .get("id")
+        .filter(|id| id.is_number() || id.as_str().is_some_and(|id| id.len() <= 128))
+        .cloned()
+        .unwrap_or(Value::Null);
+    InterceptorResponse::respond(GatewayResponse {
+        status_code,
+        headers: Some(Headers::from([("Content-Type", "application/json")])),
+        body: Some(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": -32600,
+                "message": message
+            }
+        })),
+    })
+}
+
+/// Arguments to inject for a caller presenting `token` to `tool`.
+///
+/// User information is sent as a signed `identity_context` when a signing key
+/// is configured, and as plain `user_id`/`user_name` arguments otherwise.
+/// The token itself is envelope-encrypted when `ARG_ENCRYPTION_KMS_KEY_ID`
+/// is set.
+///
+/// # Errors
+///
+/// Returns `AppError::EncryptionError` if the token cannot be encrypted; it
+/// is never sent in plaintext instead.
+async fn plan_injections(
+    token: &str,
+    tool: Option<&str>,
+) -> Result<Vec<(&'static str, Value)>, AppError> {
+    let mut injections = Vec::new();
+    if crypto::is_enabled() {
+        let sealed = crypto::encrypt_field(AUTH_TOKEN_ARG, token).await?;
+        injections.push((AUTH_TOKEN_ARG, json!(sealed)));
+    } else {
+        injections.push((AUTH_TOKEN_ARG, json!(token)));
+    }
+    // Every tool call may be remembered, personalized or keyed on the
+    // caller, so user information goes with each one
+    if tool.is_none() {
+        return Ok(injections);
+    }
+    let Some((user_id, user_name)) = extract_user_info_from_token(token) else {
+        warn!(message = "Could not extract user info from token");
+        return Ok(injections);
+    };
+    if let Some(ring) = key_ring().await {
+        let context = IdentityContext::new(user_id, user_name);
+        injections.push((IDENTITY_CONTEXT_ARG, json!(ring.sign(&context))));
+    } else {
+        warn!(message = "No identity signing key configured; injecting unsigned user info");
+        injections.push((USER_ID_ARG, json!(user_id)));
+        injections.push((USER_NAME_ARG, json!(user_name)));
+    }
+    Ok(injections)
+}
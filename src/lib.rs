@@ -1,5 +1,63 @@
+pub mod admin;
+pub mod arguments;
+pub mod async_calls;
+pub mod audit;
+pub mod auth;
+pub mod aws;
+pub mod buffers;
+pub mod cache;
+pub mod capture;
+pub mod catalog;
+pub mod codec;
+pub mod completion;
+pub mod compression;
+pub mod config;
+pub mod confirmation;
+pub mod consent;
+pub mod content;
+pub mod context;
+pub mod crypto;
+pub mod dates;
+pub mod deadline;
+pub mod declarative;
+pub mod defaults;
+pub mod derived;
+pub mod dry_run;
+pub mod effects;
+pub mod export;
+pub mod gateway;
+pub mod gazetteer;
+pub mod geofence;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod guards;
 pub mod handler;
-pub(crate) mod http;
+pub mod http;
+pub mod identity;
+pub mod interceptor;
+pub mod limits;
+pub mod logging;
+pub mod memo;
+pub mod memory;
+pub mod metrics;
 pub mod models;
+pub mod pagination;
+pub mod preferences;
+pub mod projection;
+pub mod provenance;
+pub mod quotas;
+pub mod registry;
+pub mod residency;
+pub mod roots;
+pub mod sampling;
+#[cfg(feature = "sandbox")]
+pub mod sandbox;
+pub mod secrets;
+pub mod shapes;
+pub mod slo;
+pub mod tenants;
 pub mod tools;
+pub mod transform;
+pub mod usage;
 pub mod utils;
+pub mod webhooks;
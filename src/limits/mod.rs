@@ -0,0 +1,211 @@
+//! Concurrency limits and load shedding.
+//!
+//! Each limited tool has a semaphore shared by every invocation in the warm
+//! container, so parallel calls cannot overwhelm a fragile upstream. A call
+//! that cannot get a permit within the queue timeout is rejected with
+//! `AppError::TooBusy` instead of waiting indefinitely.
+//!
+//! Independently of per-tool limits, the container tracks how many
+//! invocations are in flight and sheds new ones with `AppError::Overloaded`
+//! beyond a configured threshold, keeping latency bounded when the gateway
+//! fans out aggressively.
+
+use crate::config::config;
+use crate::models::error::AppError;
+use lambda_runtime::tracing::{debug, warn};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::timeout;
+
+static LIMITER: LazyLock<ConcurrencyLimiter> = LazyLock::new(|| {
+    let settings = &config().concurrency;
+    ConcurrencyLimiter::new(
+        settings.tool_limits.clone(),
+        settings.default_limit,
+        settings.queue_timeout,
+    )
+});
+
+static IN_FLIGHT: LazyLock<InFlightTracker> =
+    LazyLock::new(|| InFlightTracker::new(config().concurrency.max_in_flight));
+
+/// Semaphores bounding how many calls to each tool may run at once.
+#[derive(Debug)]
+pub struct ConcurrencyLimiter {
+    limits: HashMap<String, usize>,
+    default_limit: usize,
+    queue_timeout: Duration,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl ConcurrencyLimiter {
+    /// Creates a limiter with per-tool `limits`.
+    ///
+    /// Tools without an entry use `default_limit`; a limit of `0` means
+    /// unlimited.
+    #[must_use]
+    pub fn new(
+        limits: HashMap<String, usize>,
+        default_limit: usize,
+        queue_timeout: Duration,
+    ) -> Self {
+        Self {
+            limits,
+            default_limit,
+            queue_timeout,
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Concurrent calls allowed for `tool`, or `None` if unlimited.
+    #[must_use]
+    pub fn limit_for(&self, tool: &str) -> Option<usize> {
+        Some(self.limits.get(tool).copied().unwrap_or(self.default_limit)).filter(|&n| n > 0)
+    }
+
+    /// Waits for a permit to run `tool`.
+    ///
+    /// Returns `None` for unlimited tools. The permit is released when dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::TooBusy` if no permit frees up within the queue timeout.
+    pub async fn acquire(&self, tool: &str) -> Result<Option<OwnedSemaphorePermit>, AppError> {
+        let Some(limit) = self.limit_for(tool) else {
+            return Ok(None);
+        };
+
+        let semaphore = Arc::clone(
+            self.semaphores
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(tool.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(limit))),
+        );
+
+        // The semaphore is never closed, so an error here always means a timeout
+        if let Ok(Ok(permit)) = timeout(self.queue_timeout, semaphore.acquire_owned()).await {
+            return Ok(Some(permit));
+        }
+        warn!(tool, limit, "Tool concurrency limit reached");
+        Err(AppError::TooBusy(format!(
+            "{tool} already has {limit} calls in progress, retry shortly"
+        )))
+    }
+}
+
+/// Parses per-tool limits in the form `tool=4;other_tool=1`.
+///
+/// Malformed rules are skipped with a warning.
+#[must_use]
+pub fn parse_tool_limits(spec: &str) -> HashMap<String, usize> {
+    spec.split(';')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .filter_map(|rule| {
+            let parsed = rule.split_once('=').and_then(|(tool, limit)| {
+                Some((tool.trim().to_string(), limit.trim().parse().ok()?))
+            });
+            if parsed.is_none() {
+                warn!(rule, "Ignoring malformed tool concurrency rule");
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Waits for a permit to run `tool` under the configured limits.
+///
+/// # Errors
+///
+/// Returns `AppError::TooBusy` if the tool stays saturated for the queue timeout.
+pub async fn acquire_tool_permit(tool: &str) -> Result<Option<OwnedSemaphorePermit>, AppError> {
+    LIMITER.acquire(tool).await
+}
+
+/// Concurrent calls the configured limits allow for `tool`, or `None` if
+/// unlimited.
+#[must_use]
+pub fn tool_limit(tool: &str) -> Option<usize> {
+    LIMITER.limit_for(tool)
+}
+
+/// Counts in-flight invocations and sheds load beyond a threshold.
+#[derive(Debug)]
+pub struct InFlightTracker {
+    in_flight: Arc<AtomicUsize>,
+    max_in_flight: usize,
+}
+
+/// Marks an invocation as in flight until dropped.
+#[derive(Debug)]
+pub struct InFlightGuard {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl InFlightTracker {
+    /// Creates a tracker that sheds beyond `max_in_flight`; `0` disables shedding.
+    #[must_use]
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            max_in_flight,
+        }
+    }
+
+    /// Number of invocations currently in flight.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Admits an invocation, or sheds it if the container is saturated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Overloaded` if `max_in_flight` invocations are
+    /// already running.
+    pub fn try_enter(&self) -> Result<InFlightGuard, AppError> {
+        let current = self.in_flight.fetch_add(1, Ordering::AcqRel) + 1;
+        let guard = InFlightGuard {
+            in_flight: Arc::clone(&self.in_flight),
+        };
+
+        if self.max_in_flight > 0 && current > self.max_in_flight {
+            warn!(
+                in_flight = current - 1,
+                max_in_flight = self.max_in_flight,
+                "Shedding invocation, container saturated"
+            );
+            return Err(AppError::Overloaded(format!(
+                "{} invocations already in flight, retry shortly",
+                self.max_in_flight
+            )));
+        }
+
+        debug!(
+            in_flight = current,
+            max_in_flight = self.max_in_flight,
+            "Invocation admitted"
+        );
+        Ok(guard)
+    }
+}
+
+/// Admits an invocation under the container-wide in-flight limit.
+///
+/// # Errors
+///
+/// Returns `AppError::Overloaded` if the container is saturated.
+pub fn enter_invocation() -> Result<InFlightGuard, AppError> {
+    IN_FLIGHT.try_enter()
+}
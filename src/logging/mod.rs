@@ -0,0 +1,371 @@
+//! Log output in a selectable schema.
+//!
+//! `LOG_FORMAT` picks how log lines are written:
+//!
+//! - `plain`: human-readable text, for local development
+//! - `lambda`: AWS Lambda structured JSON, one object per line with
+//!   `timestamp`, `level` and `target` keys and the event's fields, including
+//!   its `message`, under `fields`; the `CloudWatch` metric filters in `iac`
+//!   read this layout
+//! - `otel`: the OpenTelemetry log data model, with `Timestamp`,
+//!   `SeverityText`, `SeverityNumber` and `Body`, the fields under
+//!   `Attributes` and the function name and version under `Resource`; the
+//!   metric filters in `iac` match nothing in this layout, so `iac` pins
+//!   `LOG_FORMAT=lambda` and `otel` is for other deployments and collectors
+//!
+//! Unset, the format follows Lambda's own `AWS_LAMBDA_LOG_FORMAT`: `lambda`
+//! when it is `JSON`, otherwise `plain`. The level is read from
+//! `AWS_LAMBDA_LOG_LEVEL` or `RUST_LOG` as before.
+//!
+//! Levels can be raised per module with `LOG_FILTER` directives, such as
+//! `info,aws_lambda_mcp::tools::weather=debug` for debug logs from the
+//! weather tool only. The filter is swapped in place, so a configuration
+//! reload applies a changed `LOG_FILTER` and [`set_filter`] applies new
+//! directives at runtime, without recycling the container.
+//!
+//! Each invocation runs in a span (see [`invocation_span`]) carrying the
+//! tool name, a correlation ID and, once known, the caller's tenant. Every
+//! line logged within it carries them as `tool`, `correlation_id` and
+//! `tenant`, whichever format is selected, so one query finds all lines of
+//! a call.
+
+use crate::config::config;
+use chrono::{DateTime, SecondsFormat, Utc};
+use lambda_runtime::tracing::field::{Empty, Field, Visit};
+use lambda_runtime::tracing::span::{Attributes, Id, Record};
+use lambda_runtime::tracing::{Event, Level, Span, Subscriber, info_span, warn};
+use serde_json::{Map, Value, json};
+use std::env;
+use std::fmt::{self, Write as _};
+use std::io::Write;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use tracing_subscriber::filter::ParseError;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+/// Fields identifying the call a line belongs to, named alike in every format
+pub const CORRELATION_FIELDS: [&str; 3] = ["tool", "correlation_id", "tenant"];
+
+/// Swaps the level filter of the subscriber [`init`] installed
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Directives of the level filter in effect
+static DIRECTIVES: Mutex<String> = Mutex::new(String::new());
+
+/// Log line schema, from `LOG_FORMAT`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable text
+    #[default]
+    Plain,
+    /// AWS Lambda structured JSON
+    Lambda,
+    /// OpenTelemetry log data model, as JSON
+    ///
+    /// Not read by the `CloudWatch` metric filters in `iac`, which expect
+    /// the fields under `fields` rather than `Attributes` and `Body`.
+    Otel,
+}
+
+impl LogFormat {
+    /// Parses a format name such as `otel` or `JSON`.
+    #[must_use]
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "plain" | "text" => Some(Self::Plain),
+            "lambda" | "json" => Some(Self::Lambda),
+            "otel" | "opentelemetry" => Some(Self::Otel),
+            _ => None,
+        }
+    }
+
+    /// The format Lambda's own `AWS_LAMBDA_LOG_FORMAT` asks for.
+    fn lambda_default() -> Self {
+        match env::var("AWS_LAMBDA_LOG_FORMAT") {
+            Ok(format) if format.eq_ignore_ascii_case("json") => Self::Lambda,
+            _ => Self::Plain,
+        }
+    }
+
+    /// Renders one log line, without the trailing newline.
+    #[must_use]
+    pub fn render(self, line: LogLine<'_>) -> String {
+        let LogLine {
+            timestamp,
+            level,
+            target,
+            service,
+            mut fields,
+        } = line;
+        let message = match fields.remove("message") {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        match self {
+            Self::Plain => {
+                let mut text = format!(
+                    "{} {level:>5} {message}",
+                    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+                );
+                let correlated = CORRELATION_FIELDS
+                    .iter()
+                    .filter_map(|name| fields.remove_entry(*name));
+                for (name, value) in correlated.collect::<Vec<_>>().into_iter().chain(fields) {
+                    let value = match value {
+                        Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    let _ = write!(text, " {name}={value}");
+                }
+                text
+            }
+            Self::Lambda => {
+                fields.insert("message".to_string(), json!(message));
+                json!({
+                    "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+                    "level": level.as_str(),
+                    "fields": fields,
+                    "target": target,
+                })
+                .to_string()
+            }
+            Self::Otel => {
+                let nanos = timestamp.timestamp_nanos_opt().unwrap_or_default();
+                json!({
+                    "Timestamp": nanos.to_string(),
+                    "SeverityText": level.as_str(),
+                    "SeverityNumber": severity_number(level),
+                    "Body": message,
+                    "Attributes": fields,
+                    "Resource": {
+                        "service.name": service,
+                        "service.version": env!("CARGO_PKG_VERSION"),
+                    },
+                    "InstrumentationScope": {"Name": target},
+                })
+                .to_string()
+            }
+        }
+    }
+}
+
+/// One event to render, with the fields of the spans it was logged in.
+#[derive(Debug, Clone)]
+pub struct LogLine<'a> {
+    pub timestamp: DateTime<Utc>,
+    pub level: Level,
+    /// Module that logged the event
+    pub target: &'a str,
+    /// Name of the service logging, for the OpenTelemetry resource
+    pub service: &'a str,
+    /// Event and span fields; the event's text is under `message`
+    pub fields: Map<String, Value>,
+}
+
+/// OpenTelemetry severity number of the first severity of each level.
+const fn severity_number(level: Level) -> u8 {
+    match level {
+        Level::TRACE => 1,
+        Level::DEBUG => 5,
+        Level::INFO => 9,
+        Level::WARN => 13,
+        // ERROR
+        _ => 17,
+    }
+}
+
+/// Installs the global subscriber, writing lines in the `LOG_FORMAT` schema
+/// to `writer`.
+///
+/// Does nothing if a subscriber is already installed.
+pub fn init<W>(writer: W)
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let requested = env::var("LOG_FORMAT")
+        .ok()
+        .filter(|raw| !raw.trim().is_empty());
+    let parsed = requested.as_deref().and_then(LogFormat::parse);
+    let format = parsed.unwrap_or_else(LogFormat::lambda_default);
+
+    let filter = EnvFilter::builder()
+        .with_default_directive(default_level().into())
+        .parse_lossy("");
+    let (filter, handle) = reload::Layer::new(filter);
+    let installed = Registry::default()
+        .with(filter)
+        .with(LogLayer::new(format, writer))
+        .try_init()
+        .is_ok();
+    if !installed {
+        return;
+    }
+
+    let _ = FILTER.set(handle);
+    if let Some(raw) = requested
+        && parsed.is_none()
+    {
+        warn!(value = %raw, "Ignoring unknown LOG_FORMAT");
+    }
+    if let Err(e) = set_filter(&config().logging.filter) {
+        warn!(error = %e, "Ignoring invalid LOG_FILTER");
+    }
+}
+
+/// Replaces the level filter with `directives`, returning the directives it
+/// replaced.
+///
+/// Modules no directive names log at `AWS_LAMBDA_LOG_LEVEL`, default `INFO`.
+/// Before [`init`] has installed a subscriber, the directives are only
+/// checked and remembered.
+///
+/// # Errors
+///
+/// Returns an error if `directives` cannot be parsed; the filter in effect
+/// is kept.
+pub fn set_filter(directives: &str) -> Result<String, ParseError> {
+    let directives = directives.trim();
+    let filter = EnvFilter::builder()
+        .with_default_directive(default_level().into())
+        .parse(directives)?;
+    if let Some(handle) = FILTER.get()
+        && let Err(e) = handle.reload(filter)
+    {
+        warn!(error = %e, "Failed to swap the log filter");
+    }
+    let mut current = DIRECTIVES.lock().unwrap_or_else(PoisonError::into_inner);
+    Ok(std::mem::replace(&mut *current, directives.to_string()))
+}
+
+/// Level of modules no filter directive names.
+fn default_level() -> Level {
+    env::var("AWS_LAMBDA_LOG_LEVEL")
+        .or_else(|_| env::var("RUST_LOG"))
+        .ok()
+        .and_then(|level| level.parse::<Level>().ok())
+        .unwrap_or(Level::INFO)
+}
+
+/// Span for one invocation of `tool`; lines logged within it carry the
+/// [`CORRELATION_FIELDS`].
+#[must_use]
+pub fn invocation_span(tool: &str, correlation_id: &str) -> Span {
+    info_span!("invocation", tool, correlation_id, tenant = Empty)
+}
+
+/// Adds the caller's tenant to the current invocation span.
+pub fn record_tenant(tenant: &str) {
+    Span::current().record("tenant", tenant);
+}
+
+/// Writes each event as one line in a [`LogFormat`], with the fields of the
+/// spans it was logged in.
+pub struct LogLayer<W> {
+    format: LogFormat,
+    writer: W,
+    service: String,
+}
+
+impl<W> LogLayer<W> {
+    /// Creates a layer writing `format` lines to `writer`, naming the
+    /// service after the Lambda function.
+    #[must_use]
+    pub fn new(format: LogFormat, writer: W) -> Self {
+        Self {
+            format,
+            writer,
+            service: env::var("AWS_LAMBDA_FUNCTION_NAME")
+                .unwrap_or_else(|_| env!("CARGO_PKG_NAME").to_string()),
+        }
+    }
+}
+
+impl<S, W> Layer<S> for LogLayer<W>
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+    W: for<'writer> MakeWriter<'writer> + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(fields);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+            values.record(fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(span_fields) = span.extensions().get::<Fields>() {
+                    fields.0.extend(span_fields.0.clone());
+                }
+            }
+        }
+        event.record(&mut fields);
+
+        let metadata = event.metadata();
+        let mut line = self.format.render(LogLine {
+            timestamp: Utc::now(),
+            level: *metadata.level(),
+            target: metadata.target(),
+            service: &self.service,
+            fields: fields.0,
+        });
+        line.push('\n');
+        let _ = self
+            .writer
+            .make_writer_for(metadata)
+            .write_all(line.as_bytes());
+    }
+}
+
+/// Recorded values of a span or event, by field name.
+#[derive(Debug, Default)]
+struct Fields(Map<String, Value>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.0
+            .insert(field.name().to_string(), json!(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{value:?}")));
+    }
+}
@@ -1,4 +1,10 @@
+use aws_lambda_mcp::config;
+use aws_lambda_mcp::declarative;
 use aws_lambda_mcp::handler::function_handler;
+use aws_lambda_mcp::http::connections::spawn_keepalive;
+use aws_lambda_mcp::http::transport;
+use aws_lambda_mcp::logging;
+use aws_lambda_mcp::transform;
 use lambda_runtime::{Error, service_fn};
 use std::io::stdout;
 use std::mem::drop;
@@ -9,12 +15,17 @@ async fn main() -> Result<(), Error> {
     let func = service_fn(function_handler);
 
     let (writer, log_guard) = non_blocking(stdout());
-    lambda_runtime::tracing::init_default_subscriber_with_writer(writer);
+    logging::init(writer);
 
     let shutdown_hook = || async move {
         drop(log_guard);
     };
     lambda_runtime::spawn_graceful_shutdown_handler(shutdown_hook).await;
+    config::load().await?;
+    transport::load().await?;
+    declarative::load()?;
+    transform::load()?;
+    spawn_keepalive();
 
     lambda_runtime::run(func).await
 }
@@ -0,0 +1,101 @@
+//! Memoization of tool calls within a conversation.
+//!
+//! Agents running long chains of tool calls often ask the same question
+//! again, such as the forecast for a city they already looked up a few
+//! steps earlier. Calls that share a session ID (see
+//! [`crate::context::SESSION_ID_ARG`]) are memoized: a successful response
+//! is kept for `SESSION_CACHE_TTL_SECS`, and an identical call later in the
+//! conversation is answered from it without running the tool, marked with
+//! `_meta.from_session_cache: true`.
+//!
+//! Calls are identical when they are made by the same caller, in the same
+//! tenant, to the same tool, with the same arguments once defaults are
+//! filled in; the selected response fields and output format are applied to
+//! the memoized response, so they need not match. Only tools marked
+//! [`ToolDescriptor::session_cached`] are memoized, which leaves out tools
+//! with side effects and those polling state that changes, and calls
+//! without a session ID or replayed by an administrator never are.
+//! Disable with `SESSION_CACHE=false`.
+
+use crate::auth::AUTH_TOKEN_ARG;
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::context::{SESSION_ID_ARG, ToolContext};
+use crate::metrics::META_KEY;
+use crate::registry::ToolDescriptor;
+use aws_lc_rs::digest;
+use lambda_runtime::tracing::debug;
+use serde_json::{Map, Value};
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+/// Metadata flag set on responses answered from the session cache
+pub const FROM_SESSION_CACHE: &str = "from_session_cache";
+
+/// Responses per session, tool and arguments, keyed
+/// `{session_id}/{tool}/{hash}` so a session's entries can be flushed together
+pub(crate) static SESSION_CACHE: LazyLock<CacheStore<Value>> = LazyLock::new(|| {
+    let settings = &config().memo;
+    CacheStore::new("session", settings.ttl, settings.max_entries)
+});
+
+/// Cache key of a call to `tool` with `args`, or `None` if the call is not
+/// memoized.
+///
+/// `args` should be the arguments the tool is invoked with, after defaults
+/// are applied; the session ID and bearer token they carry are not part of
+/// the key.
+#[must_use]
+pub fn key(tool: &ToolDescriptor, args: &Value, context: &ToolContext) -> Option<String> {
+    if !config().memo.enabled || !tool.session_cached || context.sandbox {
+        return None;
+    }
+    let session = context.session_id.as_deref()?;
+    let arguments: Map<String, Value> = args
+        .as_object()?
+        .iter()
+        .filter(|(name, _)| ![SESSION_ID_ARG, AUTH_TOKEN_ARG].contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    let caller = context.user_id.as_deref().unwrap_or_default();
+    let tenant = context.tenant.as_deref().unwrap_or_default();
+    let hash = digest::digest(
+        &digest::SHA256,
+        format!("{caller}\0{tenant}\0{}", Value::Object(arguments)).as_bytes(),
+    );
+    let mut key = format!("{session}/{}/", tool.name);
+    for byte in hash.as_ref() {
+        let _ = write!(key, "{byte:02x}");
+    }
+    Some(key)
+}
+
+/// The memoized response under `key`, if an identical call was answered
+/// within the TTL.
+#[must_use]
+pub fn get(key: &str) -> Option<Value> {
+    let response = SESSION_CACHE.get(key)?;
+    debug!(session_cache_key = %key, "Answering a repeated call from the session cache");
+    Some(response)
+}
+
+/// Memoizes the response of the call under `key`.
+pub fn insert(key: String, response: &Value) {
+    SESSION_CACHE.insert(key, response.clone());
+}
+
+/// Marks an object response as answered from the session cache under
+/// `_meta.from_session_cache`, keeping any other metadata.
+///
+/// Non-object responses are left unchanged.
+pub fn attach_marker(response: &mut Value) {
+    let Value::Object(fields) = response else {
+        return;
+    };
+    let meta = fields
+        .entry(META_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(meta) = meta {
+        meta.insert(FROM_SESSION_CACHE.to_string(), Value::Bool(true));
+    }
+}
@@ -0,0 +1,211 @@
+//! What signed-in users last asked about, recalled by greetings.
+//!
+//! With `USER_MEMORY` enabled the routing layer remembers each signed-in
+//! user's latest successful call to a tool listed in [`SUBJECTS`]: the tool
+//! and its topic, such as the location of a forecast. The
+//! `get_personalized_greeting` tool recalls it ("Welcome back, John — last
+//! time you asked about Sydney weather"). Memories are kept in the container
+//! and, when `USER_MEMORY_TABLE` names a `DynamoDB` table, there too, one
+//! item per user keyed by their token's subject (`sub`) in the region of
+//! their data residency (see [`crate::residency`]), so any container can
+//! recall them; either way they expire after `USER_MEMORY_TTL_DAYS`.
+//!
+//! Memories are only reachable through
+//! [`crate::context::ToolContext::memory`], which is bound to the subject of
+//! the token the interceptor forwarded, so one user's calls never surface in
+//! another's greeting; callers without a token, or who have not consented
+//! to their personal data being used (see [`crate::consent`]), are neither
+//! remembered nor recalled. Sandboxed replays (see [`crate::audit`]) recall
+//! but never remember. Failing to read or write the table is logged, and
+//! the greeting falls back to a plain one.
+
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::preferences::USER_ID_KEY;
+use crate::residency::{self, Placement, Store};
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{DateTime, Utc};
+use lambda_runtime::tracing::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// Tools whose calls are remembered: the tool, the argument naming its
+/// topic, if any, and what the call was about
+pub const SUBJECTS: &[(&str, Option<&str>, &str)] = &[
+    ("get_weather", Some("location"), "weather"),
+    ("render_weather_chart", Some("location"), "weather"),
+    ("get_pollen_forecast", Some("location"), "pollen"),
+    (
+        "get_public_holidays",
+        Some("country_code"),
+        "public holidays",
+    ),
+    ("read_feed", None, "a news feed"),
+    ("summarize_text", None, "a summary"),
+    ("daily_briefing", None, "your daily briefing"),
+];
+
+/// Longest topic remembered, in characters
+const MAX_TOPIC_CHARS: usize = 80;
+
+/// Memories per user ID, kept in this container
+static MEMORIES: LazyLock<CacheStore<Interaction>> = LazyLock::new(|| {
+    let settings = &config().memory;
+    CacheStore::new("user memory", settings.ttl, settings.max_entries)
+});
+
+/// A call a user made.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Registered name of the tool
+    pub tool: String,
+    /// What the call was about, e.g. the location of a forecast
+    pub topic: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl Interaction {
+    /// The interaction a call to `tool` with `args` is remembered as, or
+    /// `None` if calls to `tool` are not remembered.
+    #[must_use]
+    pub fn from_call(tool: &str, args: &Value) -> Option<Self> {
+        let (_, topic_arg, _) = SUBJECTS.iter().find(|(name, ..)| *name == tool)?;
+        let topic = topic_arg
+            .and_then(|name| args.get(name))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(|topic| topic.chars().take(MAX_TOPIC_CHARS).collect());
+        Some(Self {
+            tool: tool.to_string(),
+            topic,
+            at: Utc::now(),
+        })
+    }
+
+    /// What the interaction was about, e.g. "Sydney weather", or `None` for
+    /// a tool no longer remembered.
+    #[must_use]
+    pub fn describe(&self) -> Option<String> {
+        let (.., subject) = SUBJECTS.iter().find(|(name, ..)| *name == self.tool)?;
+        Some(self.topic.as_ref().map_or_else(
+            || (*subject).to_string(),
+            |topic| format!("{topic} {subject}"),
+        ))
+    }
+}
+
+/// One user's memory, obtained from
+/// [`crate::context::ToolContext::memory`].
+#[derive(Debug, Clone)]
+pub struct UserMemory {
+    user_id: String,
+    residency: Option<String>,
+    read_only: bool,
+}
+
+impl UserMemory {
+    /// The memory of `user_id`, which must come from the caller's token,
+    /// kept in the region of `residency`; with `read_only`, nothing is
+    /// remembered.
+    pub(crate) const fn new(user_id: String, residency: Option<String>, read_only: bool) -> Self {
+        Self {
+            user_id,
+            residency,
+            read_only,
+        }
+    }
+
+    /// The user's latest interaction, if one is remembered and has not
+    /// expired.
+    pub async fn recall(&self) -> Option<Interaction> {
+        if let Some(interaction) = MEMORIES.get(&self.user_id) {
+            return Some(interaction);
+        }
+        let placement = residency::place(Store::Memory, self.residency.as_deref())
+            .inspect_err(|e| warn!(error = %e, "Not reading user memory"))
+            .ok()
+            .flatten()?;
+        match get_item(&placement, &self.user_id).await {
+            Ok(interaction) => {
+                if let Some(interaction) = &interaction {
+                    MEMORIES.insert(self.user_id.clone(), interaction.clone());
+                }
+                interaction
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to read user memory");
+                None
+            }
+        }
+    }
+
+    /// Remembers `interaction` as the user's latest, unless the call is a
+    /// sandboxed replay.
+    pub async fn remember(&self, interaction: Interaction) {
+        if self.read_only {
+            return;
+        }
+        debug!(tool = %interaction.tool, "Remembering interaction");
+        let written = match residency::place_write(Store::Memory, self.residency.as_deref()) {
+            Ok(Some(placement)) => put_item(&placement, &self.user_id, &interaction).await,
+            Ok(None) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = written {
+            warn!(error = %e, "Failed to write user memory");
+        }
+        MEMORIES.insert(self.user_id.clone(), interaction);
+    }
+}
+
+async fn get_item(placement: &Placement, user_id: &str) -> Result<Option<Interaction>, String> {
+    let output = residency::dynamodb_client(placement.region.as_deref())
+        .await
+        .get_item()
+        .table_name(&placement.table)
+        .key(USER_ID_KEY, AttributeValue::S(user_id.to_string()))
+        .send()
+        .await
+        .map_err(|e| format!("DynamoDB GetItem failed: {e}"))?;
+
+    // Expired items linger until DynamoDB gets round to deleting them
+    let Some(item) = output.item().filter(|item| {
+        item.get("expires_at")
+            .and_then(|value| value.as_n().ok())
+            .and_then(|value| value.parse::<i64>().ok())
+            .is_none_or(|expires_at| expires_at > Utc::now().timestamp())
+    }) else {
+        return Ok(None);
+    };
+    let interaction = item
+        .get("interaction")
+        .and_then(|value| value.as_s().ok())
+        .ok_or("Memory item has no interaction")?;
+    serde_json::from_str(interaction)
+        .map(Some)
+        .map_err(|e| format!("Malformed memory item: {e}"))
+}
+
+async fn put_item(
+    placement: &Placement,
+    user_id: &str,
+    interaction: &Interaction,
+) -> Result<(), String> {
+    let serialized = serde_json::to_string(interaction)
+        .map_err(|e| format!("Failed to serialize interaction: {e}"))?;
+    let ttl = i64::try_from(config().memory.ttl.as_secs()).unwrap_or(i64::MAX);
+    let expires_at = interaction.at.timestamp().saturating_add(ttl);
+    residency::dynamodb_client(placement.region.as_deref())
+        .await
+        .put_item()
+        .table_name(&placement.table)
+        .item(USER_ID_KEY, AttributeValue::S(user_id.to_string()))
+        .item("interaction", AttributeValue::S(serialized))
+        .item("expires_at", AttributeValue::N(expires_at.to_string()))
+        .send()
+        .await
+        .map_err(|e| format!("DynamoDB PutItem failed: {e}"))?;
+    Ok(())
+}
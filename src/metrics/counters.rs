@@ -0,0 +1,387 @@
+//! In-process counters exposed in Prometheus text format.
+//!
+//! Tool calls (by outcome), their latency and output size, argument
+//! coercions, SLO events, cache lookups, hedged upstream requests and
+//! upstream connection reuse are counted per
+//! container alongside the logged metrics, and the size and
+//! removals of every cache are reported with them. In Lambda the counters
+//! simply accumulate; the local dev server (`dev-server` feature) serves them
+//! at `/metrics`, so load tests can be observed with the usual Prometheus
+//! tooling.
+
+use crate::admin;
+use crate::cache::CacheStats;
+use crate::registry;
+use crate::slo::{self, Sli};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+/// Upper bounds of the tool latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Content type of the Prometheus text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Default)]
+struct ToolStats {
+    ok: u64,
+    error: u64,
+    /// Cumulative counts per [`LATENCY_BUCKETS`] bound
+    latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+    output_bytes: u64,
+    output_tokens: u64,
+}
+
+#[derive(Debug)]
+struct Counters {
+    tools: BTreeMap<&'static str, ToolStats>,
+    coercions: BTreeMap<(&'static str, String, &'static str), u64>,
+    /// Good and bad events per tool and indicator
+    slo: BTreeMap<(&'static str, Sli), (u64, u64)>,
+    cache: BTreeMap<(&'static str, &'static str), u64>,
+    hedges: BTreeMap<(&'static str, &'static str), u64>,
+    connections: BTreeMap<(String, &'static str), u64>,
+}
+
+static COUNTERS: Mutex<Counters> = Mutex::new(Counters {
+    tools: BTreeMap::new(),
+    coercions: BTreeMap::new(),
+    slo: BTreeMap::new(),
+    cache: BTreeMap::new(),
+    hedges: BTreeMap::new(),
+    connections: BTreeMap::new(),
+});
+
+/// Outcome of a cache read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheResult {
+    Hit,
+    Stale,
+    Miss,
+}
+
+impl CacheResult {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Hit => "hit",
+            Self::Stale => "stale",
+            Self::Miss => "miss",
+        }
+    }
+}
+
+/// Which copy of a hedgeable upstream request answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HedgeOutcome {
+    /// The request answered before it was hedged
+    Unhedged,
+    /// The request was hedged, but answered first
+    Primary,
+    /// The request was hedged and the second copy answered first
+    Hedge,
+}
+
+impl HedgeOutcome {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Unhedged => "unhedged",
+            Self::Primary => "primary",
+            Self::Hedge => "hedge",
+        }
+    }
+}
+
+/// Whether an upstream response arrived on a pooled connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionUse {
+    /// An idle pooled connection was reused
+    Reused,
+    /// A new connection was opened, with its TCP and TLS handshakes
+    New,
+}
+
+impl ConnectionUse {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Reused => "reused",
+            Self::New => "new",
+        }
+    }
+}
+
+/// Counts a tool call that took `latency` and succeeded if `ok`.
+pub fn observe_call(tool: &'static str, ok: bool, latency: Duration) {
+    let seconds = latency.as_secs_f64();
+    update_tool(tool, |stats| {
+        if ok {
+            stats.ok += 1;
+        } else {
+            stats.error += 1;
+        }
+        stats.latency_sum += seconds;
+        for (count, bound) in stats.latency_buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *count += 1;
+            }
+        }
+    });
+}
+
+/// Adds the size of a tool response to the output counters.
+pub fn observe_output(tool: &'static str, bytes: usize, tokens: usize) {
+    update_tool(tool, |stats| {
+        stats.output_bytes += u64::try_from(bytes).unwrap_or(u64::MAX);
+        stats.output_tokens += u64::try_from(tokens).unwrap_or(u64::MAX);
+    });
+}
+
+/// Updates `tool`'s stats while holding the counters lock.
+fn update_tool(tool: &'static str, update: impl FnOnce(&mut ToolStats)) {
+    update(
+        COUNTERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .tools
+            .entry(tool)
+            .or_default(),
+    );
+}
+
+/// Counts a conversion of `tool`'s argument `field` (see
+/// [`crate::arguments::coercion`]).
+pub fn observe_coercion(tool: &'static str, field: &str, kind: &'static str) {
+    let mut counters = COUNTERS.lock().unwrap_or_else(PoisonError::into_inner);
+    *counters
+        .coercions
+        .entry((tool, field.to_string(), kind))
+        .or_default() += 1;
+}
+
+/// Counts a good or bad event against `tool`'s `sli` objective (see
+/// [`crate::slo`]).
+pub fn observe_slo(tool: &'static str, sli: Sli, good: bool) {
+    let events = (u64::from(good), u64::from(!good));
+    COUNTERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .slo
+        .entry((tool, sli))
+        .and_modify(|(good_events, bad_events)| {
+            *good_events += events.0;
+            *bad_events += events.1;
+        })
+        .or_insert(events);
+}
+
+/// Counts a read of the cache named `cache`.
+pub fn observe_cache(cache: &'static str, result: CacheResult) {
+    let mut counters = COUNTERS.lock().unwrap_or_else(PoisonError::into_inner);
+    *counters.cache.entry((cache, result.label())).or_default() += 1;
+}
+
+/// Counts a hedgeable request to the upstream named `upstream`.
+pub fn observe_hedge(upstream: &'static str, outcome: HedgeOutcome) {
+    let mut counters = COUNTERS.lock().unwrap_or_else(PoisonError::into_inner);
+    *counters
+        .hedges
+        .entry((upstream, outcome.label()))
+        .or_default() += 1;
+}
+
+/// Counts an upstream response from `host` by how its connection was used.
+pub fn observe_connection(host: &str, connection: ConnectionUse) {
+    let mut counters = COUNTERS.lock().unwrap_or_else(PoisonError::into_inner);
+    *counters
+        .connections
+        .entry((host.to_string(), connection.label()))
+        .or_default() += 1;
+}
+
+/// Renders all counters in the Prometheus text exposition format.
+#[must_use]
+pub fn render_prometheus() -> String {
+    let mut lines = Vec::new();
+    let counters = COUNTERS.lock().unwrap_or_else(PoisonError::into_inner);
+    render_tools(&counters, &mut lines);
+    render_slo(&counters, &mut lines);
+    render_upstreams(&counters, &mut lines);
+    drop(counters);
+    render_caches(&mut lines);
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Renders calls, latency, output size and argument coercions per tool.
+fn render_tools(counters: &Counters, lines: &mut Vec<String>) {
+    lines.push("# HELP mcp_tool_calls_total Tool invocations by outcome.".to_string());
+    lines.push("# TYPE mcp_tool_calls_total counter".to_string());
+    for (tool, stats) in &counters.tools {
+        for (outcome, count) in [("ok", stats.ok), ("error", stats.error)] {
+            lines.push(format!(
+                r#"mcp_tool_calls_total{{tool="{tool}",outcome="{outcome}"}} {count}"#
+            ));
+        }
+    }
+
+    lines.push("# HELP mcp_tool_duration_seconds Tool invocation latency.".to_string());
+    lines.push("# TYPE mcp_tool_duration_seconds histogram".to_string());
+    for (tool, stats) in &counters.tools {
+        for (count, bound) in stats.latency_buckets.iter().zip(LATENCY_BUCKETS) {
+            lines.push(format!(
+                r#"mcp_tool_duration_seconds_bucket{{tool="{tool}",le="{bound}"}} {count}"#
+            ));
+        }
+        let total = stats.ok + stats.error;
+        lines.push(format!(
+            r#"mcp_tool_duration_seconds_bucket{{tool="{tool}",le="+Inf"}} {total}"#
+        ));
+        lines.push(format!(
+            r#"mcp_tool_duration_seconds_sum{{tool="{tool}"}} {}"#,
+            stats.latency_sum
+        ));
+        lines.push(format!(
+            r#"mcp_tool_duration_seconds_count{{tool="{tool}"}} {total}"#
+        ));
+    }
+
+    lines.push("# HELP mcp_tool_output_bytes_total Serialized size of tool responses.".to_string());
+    lines.push("# TYPE mcp_tool_output_bytes_total counter".to_string());
+    for (tool, stats) in &counters.tools {
+        lines.push(format!(
+            r#"mcp_tool_output_bytes_total{{tool="{tool}"}} {}"#,
+            stats.output_bytes
+        ));
+    }
+
+    lines.push(
+        "# HELP mcp_tool_output_tokens_total Estimated tokens in tool responses.".to_string(),
+    );
+    lines.push("# TYPE mcp_tool_output_tokens_total counter".to_string());
+    for (tool, stats) in &counters.tools {
+        lines.push(format!(
+            r#"mcp_tool_output_tokens_total{{tool="{tool}"}} {}"#,
+            stats.output_tokens
+        ));
+    }
+
+    lines.push(
+        "# HELP mcp_argument_coercions_total Tool arguments converted to their declared type."
+            .to_string(),
+    );
+    lines.push("# TYPE mcp_argument_coercions_total counter".to_string());
+    for ((tool, field, kind), count) in &counters.coercions {
+        lines.push(format!(
+            r#"mcp_argument_coercions_total{{tool="{tool}",field="{field}",kind="{kind}"}} {count}"#
+        ));
+    }
+}
+
+/// Renders SLO events, objectives and burn rates per tool.
+fn render_slo(counters: &Counters, lines: &mut Vec<String>) {
+    lines.push(
+        "# HELP mcp_slo_events_total Tool calls counted against an SLO, by whether they met it."
+            .to_string(),
+    );
+    lines.push("# TYPE mcp_slo_events_total counter".to_string());
+    for ((tool, sli), (good, bad)) in &counters.slo {
+        let sli = sli.label();
+        for (outcome, count) in [("good", good), ("bad", bad)] {
+            lines.push(format!(
+                r#"mcp_slo_events_total{{tool="{tool}",sli="{sli}",outcome="{outcome}"}} {count}"#
+            ));
+        }
+    }
+
+    lines.push(
+        "# HELP mcp_slo_objective Share of events each tool's SLO requires to be good.".to_string(),
+    );
+    lines.push("# TYPE mcp_slo_objective gauge".to_string());
+    for tool in registry::tools() {
+        for sli in Sli::ALL {
+            lines.push(format!(
+                r#"mcp_slo_objective{{tool="{}",sli="{}"}} {}"#,
+                tool.name,
+                sli.label(),
+                tool.slo.objective(sli)
+            ));
+        }
+    }
+
+    lines.push(
+        "# HELP mcp_slo_burn_rate Rate each tool's error budget burns at, over the events counted here."
+            .to_string(),
+    );
+    lines.push("# TYPE mcp_slo_burn_rate gauge".to_string());
+    for ((tool, sli), (good, bad)) in &counters.slo {
+        if let Some(descriptor) = registry::find(tool) {
+            let burn = slo::burn_rate(descriptor.slo.objective(*sli), *bad, good + bad);
+            lines.push(format!(
+                r#"mcp_slo_burn_rate{{tool="{tool}",sli="{}"}} {burn}"#,
+                sli.label()
+            ));
+        }
+    }
+}
+
+/// Renders cache reads, hedged requests and upstream connection reuse.
+fn render_upstreams(counters: &Counters, lines: &mut Vec<String>) {
+    lines.push("# HELP mcp_cache_requests_total Cache reads by result.".to_string());
+    lines.push("# TYPE mcp_cache_requests_total counter".to_string());
+    for ((cache, result), count) in &counters.cache {
+        lines.push(format!(
+            r#"mcp_cache_requests_total{{cache="{cache}",result="{result}"}} {count}"#
+        ));
+    }
+
+    lines.push(
+        "# HELP mcp_upstream_hedged_requests_total Hedgeable upstream requests by the copy that answered."
+            .to_string(),
+    );
+    lines.push("# TYPE mcp_upstream_hedged_requests_total counter".to_string());
+    for ((upstream, outcome), count) in &counters.hedges {
+        lines.push(format!(
+            r#"mcp_upstream_hedged_requests_total{{upstream="{upstream}",outcome="{outcome}"}} {count}"#
+        ));
+    }
+
+    lines.push(
+        "# HELP mcp_upstream_connections_total Upstream responses by whether their connection was reused."
+            .to_string(),
+    );
+    lines.push("# TYPE mcp_upstream_connections_total counter".to_string());
+    for ((host, connection), count) in &counters.connections {
+        lines.push(format!(
+            r#"mcp_upstream_connections_total{{host="{host}",connection="{connection}"}} {count}"#
+        ));
+    }
+}
+
+/// Renders the entries held and removed per cache.
+fn render_caches(lines: &mut Vec<String>) {
+    let caches: Vec<CacheStats> = admin::caches().iter().map(|cache| cache.stats()).collect();
+    lines.push("# HELP mcp_cache_entries Entries stored per cache.".to_string());
+    lines.push("# TYPE mcp_cache_entries gauge".to_string());
+    for stats in &caches {
+        lines.push(format!(
+            r#"mcp_cache_entries{{cache="{}"}} {}"#,
+            stats.name, stats.entries
+        ));
+    }
+    lines.push("# HELP mcp_cache_removals_total Cache entries removed by reason.".to_string());
+    lines.push("# TYPE mcp_cache_removals_total counter".to_string());
+    for stats in &caches {
+        for (reason, count) in [
+            ("eviction", stats.evictions),
+            ("invalidation", stats.invalidations),
+        ] {
+            lines.push(format!(
+                r#"mcp_cache_removals_total{{cache="{}",reason="{reason}"}} {count}"#,
+                stats.name
+            ));
+        }
+    }
+}
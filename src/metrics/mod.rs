@@ -0,0 +1,127 @@
+//! Content size metrics for tool calls.
+//!
+//! Every tool call is measured in bytes and estimated tokens, both for the
+//! arguments the agent sent and the response it receives. The figures are
+//! logged per call so context budget consumption can be tracked per tool,
+//! and can optionally be returned to the caller in the response `_meta`.
+//! Calls, latency, argument coercions, SLO events (see [`crate::slo`]),
+//! cache reads, hedged upstream requests and connection reuse are also
+//! counted in-process (see [`render_prometheus`]).
+
+mod counters;
+
+pub use counters::{
+    CacheResult, ConnectionUse, HedgeOutcome, PROMETHEUS_CONTENT_TYPE, observe_cache, observe_call,
+    observe_coercion, observe_connection, observe_hedge, observe_slo, render_prometheus,
+};
+
+use crate::auth::AUTH_TOKEN_ARG;
+use crate::buffers::{self, Buffer};
+use crate::config::config;
+use lambda_runtime::tracing::{info, warn};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Key under which metrics are attached to tool responses
+pub const META_KEY: &str = "_meta";
+
+/// Size of a tool call's input and output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ContentMetrics {
+    pub input_bytes: usize,
+    pub input_tokens: usize,
+    pub output_bytes: usize,
+    pub output_tokens: usize,
+}
+
+impl ContentMetrics {
+    /// Measures serialized tool arguments.
+    ///
+    /// The forwarded auth token is excluded: it is added by the interceptor
+    /// and never occupies the agent's context.
+    #[must_use]
+    pub fn for_input(tool_args: &Value) -> Self {
+        let input = match tool_args {
+            Value::Object(args) if args.contains_key(AUTH_TOKEN_ARG) => {
+                let mut args = args.clone();
+                args.remove(AUTH_TOKEN_ARG);
+                buffers::to_json(&args)
+            }
+            other => buffers::to_json(other),
+        };
+        let input = input.as_ref().map(Buffer::as_str).unwrap_or_default();
+        Self {
+            input_bytes: input.len(),
+            input_tokens: estimate_tokens(input),
+            ..Self::default()
+        }
+    }
+
+    /// Adds the measurements of a serialized tool response.
+    #[must_use]
+    pub fn with_output(self, response: &Value) -> Self {
+        let output = buffers::to_json(response);
+        let output = output.as_ref().map(Buffer::as_str).unwrap_or_default();
+        Self {
+            output_bytes: output.len(),
+            output_tokens: estimate_tokens(output),
+            ..self
+        }
+    }
+}
+
+/// Approximates the number of BPE tokens in `text`.
+///
+/// Mirrors how common byte-pair encodings split text: runs of letters and
+/// digits cost roughly one token per four characters, while punctuation and
+/// non-ASCII characters usually cost a token each. Whitespace is folded into
+/// the following word. The result is an estimate, not an exact count.
+#[must_use]
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut run: usize = 0;
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            run += 1;
+            continue;
+        }
+        tokens += run.div_ceil(4);
+        run = 0;
+        if !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + run.div_ceil(4)
+}
+
+/// Logs `metrics` for `tool`, warning when the response is oversized.
+pub fn record(tool: &'static str, metrics: &ContentMetrics) {
+    counters::observe_output(tool, metrics.output_bytes, metrics.output_tokens);
+    info!(
+        tool,
+        input_bytes = metrics.input_bytes,
+        input_tokens = metrics.input_tokens,
+        output_bytes = metrics.output_bytes,
+        output_tokens = metrics.output_tokens,
+        "Tool content size"
+    );
+
+    let threshold = config().metrics.output_token_alert_threshold;
+    if metrics.output_tokens > threshold {
+        warn!(
+            tool,
+            output_tokens = metrics.output_tokens,
+            threshold,
+            "Oversized tool response"
+        );
+    }
+}
+
+/// Attaches `metrics` to an object response under [`META_KEY`].
+///
+/// Non-object responses are left unchanged.
+pub fn attach(response: &mut Value, metrics: &ContentMetrics) {
+    if let (Value::Object(fields), Ok(meta)) = (response, serde_json::to_value(metrics)) {
+        fields.insert(META_KEY.to_string(), serde_json::json!({ "content": meta }));
+    }
+}
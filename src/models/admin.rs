@@ -0,0 +1,309 @@
+use crate::cache::CacheStats;
+use lambda_runtime::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminFlushCacheRequest {
+    /// Name of the cache to flush, e.g. "geocode" (default: every cache)
+    #[serde(default)]
+    pub cache: Option<String>,
+    /// Only remove entries whose key starts with this, e.g. a lowercased location for the "geocode" cache; requires `cache`
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminFlushCacheResponse {
+    /// Caches flushed in this container
+    pub flushed: Vec<FlushedCache>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FlushedCache {
+    /// Cache name
+    pub name: String,
+    /// Number of entries removed
+    pub entries: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminCacheStatsRequest {
+    /// Name of the cache to report, e.g. "geocode" (default: every cache)
+    #[serde(default)]
+    pub cache: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminCacheStatsResponse {
+    /// Caches in this container
+    pub caches: Vec<CacheReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CacheReport {
+    /// Cache name
+    pub name: String,
+    /// Stored entries, including expired ones not yet purged
+    pub entries: usize,
+    /// Stored entries past their TTL
+    pub expired: usize,
+    pub max_entries: usize,
+    pub ttl_seconds: u64,
+    /// Reads served a fresh entry
+    pub hits: u64,
+    /// Reads served an entry past its TTL
+    pub stale_hits: u64,
+    pub misses: u64,
+    /// Share of reads served from the cache; absent before the first read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_rate: Option<f64>,
+    /// Entries removed to make room for new ones
+    pub evictions: u64,
+    /// Entries removed by admin flushes
+    pub invalidations: u64,
+}
+
+impl From<CacheStats> for CacheReport {
+    fn from(stats: CacheStats) -> Self {
+        Self {
+            name: stats.name.to_string(),
+            entries: stats.entries,
+            expired: stats.expired,
+            max_entries: stats.max_entries,
+            ttl_seconds: stats.ttl.as_secs(),
+            hits: stats.hits,
+            stale_hits: stats.stale_hits,
+            misses: stats.misses,
+            hit_rate: stats.hit_rate(),
+            evictions: stats.evictions,
+            invalidations: stats.invalidations,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReloadConfigRequest {
+    /// Also flush every cache, not only those holding keys (default false)
+    #[serde(default)]
+    pub flush_caches: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReloadConfigResponse {
+    /// When the configuration was reloaded (RFC 3339)
+    pub reloaded_at: String,
+    /// Caches flushed so their contents are rebuilt with the new configuration
+    pub flushed: Vec<FlushedCache>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminToggleToolRequest {
+    /// Name of the tool to switch on or off
+    pub tool: String,
+    /// Whether the tool should accept calls
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminToggleToolResponse {
+    pub tool: String,
+    /// Whether the tool now accepts calls in this container
+    pub enabled: bool,
+    /// Whether the tool accepted calls before this change
+    pub previously_enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminSetLogFilterRequest {
+    /// Level directives, e.g. `info,aws_lambda_mcp::tools::weather=debug` (default: the configured `LOG_FILTER`)
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminSetLogFilterResponse {
+    /// Directives now in effect in this container; empty for the default level everywhere
+    pub filter: String,
+    /// Directives in effect before this change
+    pub previous_filter: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReplayInvocationRequest {
+    /// ID of the recorded invocation, from `_meta.invocationId` of its response
+    pub invocation_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReplayInvocationResponse {
+    pub invocation_id: String,
+    /// Tool that was called
+    pub tool: String,
+    /// When the invocation was recorded (RFC 3339)
+    pub recorded_at: String,
+    /// Whether the replay returned exactly what was recorded
+    pub matches: bool,
+    /// Values that differ between the recorded and replayed outcomes, up to 50
+    pub differences: Vec<InvocationDifference>,
+    /// Outcome of the recorded call
+    pub recorded: InvocationOutcome,
+    /// Outcome of the replay, run in sandbox mode
+    pub replayed: InvocationOutcome,
+}
+
+/// What a tool call returned: its response, or the error it failed with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InvocationOutcome {
+    /// Tool response, when the call succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Map<String, Value>>")]
+    pub response: Option<Value>,
+    /// Error type, e.g. `ToolError`, when the call failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+    /// Error message, when the call failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+impl From<&Result<Value, Diagnostic>> for InvocationOutcome {
+    fn from(result: &Result<Value, Diagnostic>) -> Self {
+        match result {
+            Ok(response) => Self {
+                response: Some(response.clone()),
+                error_type: None,
+                error_message: None,
+            },
+            Err(diagnostic) => Self {
+                response: None,
+                error_type: Some(diagnostic.error_type.clone()),
+                error_message: Some(diagnostic.error_message.clone()),
+            },
+        }
+    }
+}
+
+/// A value that differs between a recorded call and its replay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InvocationDifference {
+    /// JSON pointer to the value that differs, e.g. "/response/days/0/temperatureMax"
+    pub path: String,
+    /// Recorded value; absent if only the replay has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded: Option<Value>,
+    /// Replayed value; absent if only the recorded call has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replayed: Option<Value>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReplayFailedRequest {
+    /// IDs of the failed calls to replay, from the failed calls topic; all
+    /// recorded calls, oldest first, if empty
+    #[serde(default)]
+    pub failure_ids: Vec<String>,
+    /// Most calls to replay when none are named (default 25)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReplayFailedResponse {
+    /// Calls replayed, in order
+    pub replayed: Vec<ReplayedCall>,
+    /// Named IDs with no recorded failed call, e.g. expired or already replayed
+    pub not_found: Vec<String>,
+}
+
+/// A failed asynchronous call run again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ReplayedCall {
+    pub failure_id: String,
+    /// Tool that was called
+    pub tool: String,
+    /// Attempts made at the call, including this one
+    pub attempts: u32,
+    /// Whether the call succeeded and was removed from the record
+    pub succeeded: bool,
+    /// Outcome of the replay
+    pub outcome: InvocationOutcome,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminToolVersionsRequest {
+    /// Name of the tool to report (default: every tool)
+    #[serde(default)]
+    pub tool: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminToolVersionsResponse {
+    /// Deployment serving this call
+    pub version: String,
+    /// Response shapes of each tool
+    pub tools: Vec<ToolShapeHistory>,
+}
+
+/// Response shapes a tool has returned, across deployments.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolShapeHistory {
+    pub tool: String,
+    /// Whether this deployment returned a shape no earlier deployment did
+    pub changed: bool,
+    /// Shapes in the order they were first seen
+    pub shapes: Vec<ResponseShape>,
+}
+
+/// The structure of a tool response, without its values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ResponseShape {
+    /// Hash of the structure
+    pub fingerprint: String,
+    /// Deployment that first returned it
+    pub version: String,
+    /// When it was first seen (RFC 3339)
+    pub first_seen: String,
+    /// Field names with the JSON type of their values; lists hold each distinct item shape
+    #[schemars(with = "Map<String, Value>")]
+    pub structure: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminUsageReportRequest {
+    /// Only report this tenant (default: every tenant)
+    #[serde(default)]
+    pub tenant: Option<String>,
+    /// Month (YYYY-MM) or day (YYYY-MM-DD) to report, in UTC (default: the current month)
+    #[serde(default)]
+    pub period: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminUsageReportResponse {
+    /// Month or day reported
+    pub period: String,
+    /// Usage per tenant, busiest first
+    pub tenants: Vec<TenantUsage>,
+}
+
+/// Calls a tenant made and the upstream requests they caused.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TenantUsage {
+    /// Tenant ID, or "none" for callers without one
+    pub tenant: String,
+    pub invocations: u64,
+    /// Upstream HTTP requests made while serving the tenant's calls
+    pub upstream_calls: u64,
+    /// Usage per tool
+    pub tools: Vec<ToolUsage>,
+}
+
+/// Calls a tenant made to one tool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolUsage {
+    pub tool: String,
+    pub invocations: u64,
+    pub upstream_calls: u64,
+}
@@ -0,0 +1,45 @@
+use super::holidays::Holiday;
+use super::weather::{CurrentConditions, DailyForecast};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DailyBriefingRequest {
+    /// Location for the forecast (default: the user's home city from their preferences)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// ISO 3166-1 alpha-2 country code for holidays (default: from the user's preferences)
+    #[serde(default)]
+    pub country_code: Option<String>,
+    /// How many days ahead to list public holidays (default 30)
+    #[serde(default)]
+    #[schemars(range(max = 366))]
+    pub holiday_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DailyBriefingResponse {
+    pub greeting: String,
+    /// Location the forecast is for
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub weather: Option<BriefingWeather>,
+    /// Public holidays from today until the end of the look-ahead window
+    pub holidays: Vec<Holiday>,
+    /// The briefing as text, rendered from the server's template
+    pub summary: String,
+    /// Sections left out of the briefing, and why
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unavailable: Vec<String>,
+}
+
+/// Today's weather at the briefing location.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BriefingWeather {
+    /// Unit of the temperatures, e.g. "°C"
+    pub temperature_unit: String,
+    pub today: DailyForecast,
+    #[serde(default)]
+    pub current: Option<CurrentConditions>,
+}
@@ -0,0 +1,57 @@
+//! MCP `completion/complete` request and result types.
+//!
+//! The MCP specification defines completion references for prompts
+//! (`ref/prompt`) and resource templates (`ref/resource`); this server
+//! completes tool arguments, referenced as `ref/tool` with the tool's name.
+
+use serde::{Deserialize, Serialize};
+
+/// JSON-RPC method a client calls for argument completions
+pub const COMPLETE_METHOD: &str = "completion/complete";
+
+/// Parameters of a `completion/complete` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompleteParams {
+    /// What is being completed
+    #[serde(rename = "ref")]
+    pub reference: CompletionRef,
+    pub argument: CompletionArgument,
+}
+
+/// The prompt, resource template or tool whose argument is being completed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CompletionRef {
+    #[serde(rename = "ref/tool")]
+    Tool { name: String },
+    #[serde(rename = "ref/prompt")]
+    Prompt { name: String },
+    #[serde(rename = "ref/resource")]
+    Resource { uri: String },
+}
+
+/// The argument being completed and what the user has typed so far.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionArgument {
+    pub name: String,
+    pub value: String,
+}
+
+/// The reply to a `completion/complete` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompleteResult {
+    pub completion: Completion,
+}
+
+/// Suggested values for an argument, best first.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    /// At most 100 values, as the specification requires
+    pub values: Vec<String>,
+    /// Number of matching values, including those not returned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    /// Whether more values match than were returned
+    pub has_more: bool,
+}
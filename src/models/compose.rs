@@ -0,0 +1,69 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunWorkflowRequest {
+    /// Steps to run; each runs after the steps it references or depends on
+    pub steps: Vec<WorkflowStep>,
+}
+
+/// One tool call in a workflow.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowStep {
+    /// Unique step ID, used to reference its output, e.g. `${forecast.timezone}`
+    pub id: String,
+    /// Name of the tool to call
+    pub tool: String,
+    /// Tool arguments; a string that is exactly one `${step.field}` reference takes the referenced value as is
+    #[serde(default)]
+    pub arguments: Map<String, Value>,
+    /// Steps that must finish first, in addition to those referenced in `arguments`
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Time limit for this step in milliseconds (default from server configuration)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunWorkflowResponse {
+    pub status: RunStatus,
+    /// Outcome of each step, in the order they were declared
+    pub steps: Vec<StepResult>,
+}
+
+/// Overall outcome: `partial` when some steps succeeded and others did not
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Succeeded,
+    Partial,
+    Failed,
+}
+
+/// Outcome of one workflow step.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StepResult {
+    pub id: String,
+    pub tool: String,
+    pub status: StepStatus,
+    /// Tool response, when the step succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Map<String, Value>>")]
+    pub output: Option<Value>,
+    /// Why the step failed, timed out or was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Step outcome; steps whose dependencies did not succeed are `skipped`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+    TimedOut,
+    Skipped,
+}
@@ -0,0 +1,21 @@
+use super::effects::DryRunPlan;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RecordConsentRequest {
+    /// Whether the caller consents to their name, user ID, preferences and past calls being used; false withdraws consent
+    pub granted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RecordConsentResponse {
+    /// Whether consent is now granted, or would be after a dry run
+    pub granted: bool,
+    /// When consent was granted, as RFC 3339; absent once withdrawn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub granted_at: Option<String>,
+    /// The consent that would have been recorded, for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<DryRunPlan>,
+}
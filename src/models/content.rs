@@ -0,0 +1,168 @@
+//! MCP content blocks for tool responses.
+//!
+//! Most tools return structured JSON, but tools that render images or fetch
+//! binary objects return a list of content blocks as defined by the MCP
+//! specification: text, base64-encoded images, and embedded binary resources,
+//! each labelled with its MIME type. Binary data is capped at
+//! `MAX_BINARY_CONTENT_BYTES` before encoding, and the declared type must be
+//! on an allowlist and, for images, match the data's file signature, so
+//! clients are never handed mislabelled bytes.
+
+use crate::config::config;
+use crate::models::error::AppError;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Image types tools may return.
+pub const IMAGE_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/svg+xml",
+];
+
+/// Other types tools may return as embedded resources.
+pub const RESOURCE_MIME_TYPES: &[&str] = &[
+    "application/json",
+    "application/octet-stream",
+    "application/pdf",
+    "application/zip",
+    "text/csv",
+    "text/plain",
+];
+
+/// A single item of tool output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        /// Base64-encoded image data
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    Resource {
+        resource: BlobResource,
+    },
+}
+
+/// Binary resource embedded in a response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobResource {
+    pub uri: String,
+    pub mime_type: String,
+    /// Base64-encoded resource data
+    pub blob: String,
+}
+
+/// Response of tools that return content blocks rather than structured JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ContentResponse {
+    pub content: Vec<ContentBlock>,
+}
+
+impl ContentBlock {
+    /// Creates a text block.
+    #[must_use]
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Creates an image block from raw image bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ContentError` if `mime_type` is not an allowed image
+    /// type, the data does not look like that type, or it exceeds the size cap.
+    pub fn image(bytes: &[u8], mime_type: &str) -> Result<Self, AppError> {
+        let mime_type = normalize_mime(mime_type);
+        if !IMAGE_MIME_TYPES.contains(&mime_type.as_str()) {
+            return Err(AppError::ContentError(format!(
+                "Unsupported image type {mime_type}"
+            )));
+        }
+        validate_binary(bytes, &mime_type)?;
+        Ok(Self::Image {
+            data: STANDARD.encode(bytes),
+            mime_type,
+        })
+    }
+
+    /// Creates an embedded resource block for `uri` from raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ContentError` if `mime_type` is not an allowed
+    /// type, image data does not look like its type, or the data exceeds the
+    /// size cap.
+    pub fn blob(uri: impl Into<String>, bytes: &[u8], mime_type: &str) -> Result<Self, AppError> {
+        let mime_type = normalize_mime(mime_type);
+        if !IMAGE_MIME_TYPES.contains(&mime_type.as_str())
+            && !RESOURCE_MIME_TYPES.contains(&mime_type.as_str())
+        {
+            return Err(AppError::ContentError(format!(
+                "Unsupported content type {mime_type}"
+            )));
+        }
+        validate_binary(bytes, &mime_type)?;
+        Ok(Self::Resource {
+            resource: BlobResource {
+                uri: uri.into(),
+                mime_type,
+                blob: STANDARD.encode(bytes),
+            },
+        })
+    }
+}
+
+/// Lowercases `mime_type` and drops parameters such as `; charset=utf-8`.
+fn normalize_mime(mime_type: &str) -> String {
+    mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn validate_binary(bytes: &[u8], mime_type: &str) -> Result<(), AppError> {
+    let max_bytes = config().content.max_binary_bytes;
+    if bytes.len() > max_bytes {
+        return Err(AppError::ContentError(format!(
+            "{mime_type} content is {} bytes, over the {max_bytes} byte limit",
+            bytes.len()
+        )));
+    }
+    if !matches_signature(bytes, mime_type) {
+        return Err(AppError::ContentError(format!(
+            "Data is not valid {mime_type}"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks image data against the file signature of `mime_type`.
+///
+/// Types without a signature check always match.
+fn matches_signature(bytes: &[u8], mime_type: &str) -> bool {
+    match mime_type {
+        "image/png" => bytes.starts_with(b"\x89PNG\r\n\x1a\n"),
+        "image/jpeg" => bytes.starts_with(&[0xFF, 0xD8, 0xFF]),
+        "image/gif" => bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a"),
+        "image/webp" => {
+            bytes.starts_with(b"RIFF") && bytes.get(8..12).is_some_and(|tag| tag == b"WEBP")
+        }
+        "image/svg+xml" => std::str::from_utf8(bytes).is_ok_and(|svg| {
+            let svg = svg.trim_start();
+            svg.starts_with("<svg") || (svg.starts_with("<?xml") && svg.contains("<svg"))
+        }),
+        _ => true,
+    }
+}
@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CostsRequest {
+    /// Only include these services, using Cost Explorer names (e.g. "Amazon Simple Storage Service")
+    #[serde(default)]
+    pub services: Vec<String>,
+    /// Only include usage in this region (e.g. "ap-southeast-2")
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Return only the most expensive services (default: all)
+    #[serde(default)]
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CostsResponse {
+    /// First day of the period (inclusive), YYYY-MM-DD
+    pub start: String,
+    /// Last day of the period (exclusive), YYYY-MM-DD
+    pub end: String,
+    pub currency: String,
+    /// Month-to-date unblended cost across the returned services
+    pub total: f64,
+    /// Whether AWS still considers the figures estimates
+    pub estimated: bool,
+    /// Cost per service, most expensive first
+    pub services: Vec<ServiceCost>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServiceCost {
+    pub service: String,
+    pub amount: f64,
+}
@@ -0,0 +1,21 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunDeclarativeToolRequest {
+    /// Configured tool name
+    pub tool: String,
+    /// Arguments for the tool; validated against its input schema
+    #[serde(default)]
+    #[schemars(with = "Map<String, Value>")]
+    pub arguments: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunDeclarativeToolResponse {
+    pub tool: String,
+    /// The upstream response, shaped by the tool's response mapping; results that are not objects are returned as `{"value": ...}`
+    #[schemars(with = "Map<String, Value>")]
+    pub result: Value,
+}
@@ -0,0 +1,90 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DescribeServerRequest {
+    /// Only describe this tool (default: every enabled tool)
+    #[serde(default)]
+    pub tool: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DescribeServerResponse {
+    /// Build serving the call
+    pub server: ServerBuild,
+    /// Tools enabled in this container
+    pub tools: Vec<ToolCapability>,
+    /// How callers are identified and authorized
+    pub auth: AuthCapabilities,
+    /// Limits applied to every caller
+    pub limits: LoadLimits,
+    /// Caches in this container and how long they keep entries
+    pub caches: Vec<CachePolicy>,
+}
+
+/// Build and deployment of the server answering the call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerBuild {
+    /// Crate name
+    pub name: String,
+    /// Crate version
+    pub version: String,
+    /// Deployment serving the call, e.g. the Lambda package hash
+    pub deployment: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolCapability {
+    /// Tool name
+    pub name: String,
+    pub description: String,
+    /// Values accepted for `response_version`; only "1" if the tool has a single shape
+    pub response_versions: Vec<String>,
+    /// Whether the tool accepts `cursor` and returns `next_cursor`
+    pub paginated: bool,
+    /// Scopes of which the caller needs one; empty if any caller may call the tool
+    pub required_scopes: Vec<String>,
+    /// Calls to the tool that may run at once in a container; absent if unlimited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency_limit: Option<usize>,
+    /// Date after which the tool may be removed, as `YYYY-MM-DD`, if it is deprecated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<String>,
+    /// Tool callers should move to, if it is deprecated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+}
+
+/// How callers are identified and what decides the tools they may call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuthCapabilities {
+    /// Accepted ways of identifying the caller: `bearer_token` (the gateway's JWT in `auth_token`) and, when a signing key is configured, `identity_context` (a signed identity added by the interceptor)
+    pub modes: Vec<String>,
+    /// Whether unsigned `user_id`/`user_name` arguments are dropped
+    pub identity_context_required: bool,
+    /// Scopes accepted for admin tools
+    pub admin_scopes: Vec<String>,
+    /// Token claim naming the caller's tenant
+    pub tenant_claim: String,
+}
+
+/// Limits applied to every caller, per container.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LoadLimits {
+    /// Invocations a container runs at once before shedding new ones; 0 if unlimited
+    pub max_in_flight: usize,
+    /// How long a call waits for a free slot when its tool is at its concurrency limit
+    pub queue_timeout_ms: u64,
+    /// Longest an upstream request may take
+    pub upstream_timeout_ms: u64,
+    /// Most steps a `run_workflow` call may run
+    pub max_workflow_steps: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CachePolicy {
+    /// Cache name
+    pub name: String,
+    pub ttl_seconds: u64,
+    pub max_entries: usize,
+}
@@ -0,0 +1,24 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Whether this call performed its side effect: `executed`, `deduplicated` when it repeated an earlier call and returned that call's result instead, or `planned` when it was a dry run and only describes the effect in `plan`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SideEffectStatus {
+    #[default]
+    Executed,
+    Deduplicated,
+    Planned,
+}
+
+/// What a dry run would have done had it not been one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DryRunPlan {
+    /// The side effect, e.g. "Send an email"
+    pub action: String,
+    /// What the effect would act on, e.g. a topic ARN or email address
+    pub target: String,
+    /// Validated values the effect would be performed with, e.g. the rendered message
+    pub details: Map<String, Value>,
+}
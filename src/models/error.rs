@@ -5,16 +5,89 @@
 //! more precise.
 
 use std::fmt;
+use std::time::Duration;
 
 /// Custom error type for the application.
 #[derive(Debug)]
 pub enum AppError {
     /// Error related to geocoding operations
     GeocodingError(String),
+    /// No place matches a location name, even after spelling fallbacks
+    LocationNotFound {
+        location: String,
+        suggestions: Vec<String>,
+    },
     /// Error related to weather API operations
     WeatherApiError(String),
     /// Error related to user information extraction
     UserExtractionError(String),
+    /// Error related to fetching or parsing RSS/Atom feeds
+    FeedError(String),
+    /// Error related to Bedrock model invocation
+    BedrockError(String),
+    /// Request rejected by input validation or guardrails
+    ValidationError(String),
+    /// Error related to SNS/SES notification delivery
+    NotificationError(String),
+    /// Error related to `CloudWatch` Logs Insights queries
+    LogsQueryError(String),
+    /// Error related to Cost Explorer requests
+    CostExplorerError(String),
+    /// Error related to Step Functions executions
+    WorkflowError(String),
+    /// Error related to the public holidays API
+    HolidayError(String),
+    /// Error reading user preferences
+    PreferencesError(String),
+    /// Error reading or writing the invocation audit log
+    AuditError(String),
+    /// Error reading or writing the history of tool response shapes
+    ShapesError(String),
+    /// Error loading the settings of the caller's tenant
+    TenantError(String),
+    /// Error reading or writing usage accounting records
+    UsageError(String),
+    /// Error reading or writing the journal of side effects
+    EffectJournalError(String),
+    /// Error reading or writing the record of failed asynchronous calls
+    FailedCallsError(String),
+    /// A sandboxed tool failed to load, ran out of a resource, or reported an error
+    SandboxError(String),
+    /// Error calling the upstream of a tool defined in configuration
+    DeclarativeToolError(String),
+    /// Caller is not permitted to invoke the requested tool
+    Unauthorized(String),
+    /// Tool is at its concurrency limit; the call may be retried
+    TooBusy(String),
+    /// Container has too many invocations in flight; the call may be retried
+    Overloaded(String),
+    /// Caller's tenant has used up a usage quota until it resets
+    QuotaExceeded {
+        period: &'static str,
+        limit: u64,
+        resets_at: String,
+    },
+    /// Upstream asked us to back off, optionally saying for how long
+    RateLimited {
+        upstream: String,
+        retry_after: Option<Duration>,
+    },
+    /// Not enough time left before the invocation deadline
+    DeadlineExceeded(String),
+    /// Error encrypting or decrypting a sensitive argument
+    EncryptionError(String),
+    /// Outbound request blocked by the egress domain policy
+    EgressDenied(String),
+    /// Write of a caller's data outside the region of their data residency
+    ResidencyDenied(String),
+    /// Confirmation token of a destructive call is invalid, expired or spent
+    ConfirmationInvalid(String),
+    /// Location outside the regions the caller's tenant may serve
+    GeofenceDenied(String),
+    /// A response transform failed or produced a response that breaks the output schema
+    TransformError(String),
+    /// Binary content rejected by the size cap or content-type checks
+    ContentError(String),
     /// Generic error for other cases
     GenericError(String),
 }
@@ -23,8 +96,56 @@ impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::GeocodingError(msg) => write!(f, "Geocoding error: {msg}"),
+            Self::LocationNotFound { .. } => write!(f, "Location not found: {}", self.detail()),
             Self::WeatherApiError(msg) => write!(f, "Weather API error: {msg}"),
             Self::UserExtractionError(msg) => write!(f, "User extraction error: {msg}"),
+            Self::FeedError(msg) => write!(f, "Feed error: {msg}"),
+            Self::BedrockError(msg) => write!(f, "Bedrock error: {msg}"),
+            Self::ValidationError(msg) => write!(f, "Validation error: {msg}"),
+            Self::NotificationError(msg) => write!(f, "Notification error: {msg}"),
+            Self::LogsQueryError(msg) => write!(f, "Logs query error: {msg}"),
+            Self::CostExplorerError(msg) => write!(f, "Cost Explorer error: {msg}"),
+            Self::WorkflowError(msg) => write!(f, "Workflow error: {msg}"),
+            Self::HolidayError(msg) => write!(f, "Holiday API error: {msg}"),
+            Self::PreferencesError(msg) => write!(f, "Preferences error: {msg}"),
+            Self::AuditError(msg) => write!(f, "Audit log error: {msg}"),
+            Self::ShapesError(msg) => write!(f, "Response shapes error: {msg}"),
+            Self::TenantError(msg) => write!(f, "Tenant error: {msg}"),
+            Self::UsageError(msg) => write!(f, "Usage accounting error: {msg}"),
+            Self::EffectJournalError(msg) => write!(f, "Effect journal error: {msg}"),
+            Self::FailedCallsError(msg) => write!(f, "Failed calls error: {msg}"),
+            Self::SandboxError(msg) => write!(f, "Sandboxed tool error: {msg}"),
+            Self::DeclarativeToolError(msg) => write!(f, "Declarative tool error: {msg}"),
+            Self::Unauthorized(msg) => write!(f, "Unauthorized: {msg}"),
+            Self::TooBusy(msg) => write!(f, "Too busy: {msg}"),
+            Self::Overloaded(msg) => write!(f, "Overloaded: {msg}"),
+            Self::QuotaExceeded {
+                period,
+                limit,
+                resets_at,
+            } => write!(
+                f,
+                "The {period} quota of {limit} calls is used up; it resets at {resets_at}"
+            ),
+            Self::RateLimited {
+                upstream,
+                retry_after: Some(delay),
+            } => write!(
+                f,
+                "Rate limited by {upstream}, retry after {} seconds",
+                delay.as_secs().max(1)
+            ),
+            Self::RateLimited { upstream, .. } => {
+                write!(f, "Rate limited by {upstream}, retry later")
+            }
+            Self::DeadlineExceeded(msg) => write!(f, "Deadline exceeded: {msg}"),
+            Self::EncryptionError(msg) => write!(f, "Encryption error: {msg}"),
+            Self::EgressDenied(msg) => write!(f, "Egress denied: {msg}"),
+            Self::ResidencyDenied(msg) => write!(f, "Residency denied: {msg}"),
+            Self::ConfirmationInvalid(msg) => write!(f, "Confirmation invalid: {msg}"),
+            Self::GeofenceDenied(msg) => write!(f, "Geofence denied: {msg}"),
+            Self::TransformError(msg) => write!(f, "Response transform error: {msg}"),
+            Self::ContentError(msg) => write!(f, "Content error: {msg}"),
             Self::GenericError(msg) => write!(f, "{msg}"),
         }
     }
@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FeedRequest {
+    /// URL of the RSS or Atom feed
+    pub url: String,
+    /// Maximum number of items to return per page, newest first (default 10)
+    #[serde(default)]
+    #[schemars(range(min = 1, max = 50))]
+    pub limit: Option<usize>,
+    /// Cursor from a previous response's `next_cursor`, to fetch the next page
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeedResponse {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub items: Vec<FeedItem>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeedItem {
+    pub title: Option<String>,
+    /// Publication (or last update) time in RFC 3339 format
+    pub published: Option<String>,
+    /// Plain-text summary with markup removed
+    pub summary: Option<String>,
+    pub link: Option<String>,
+}
@@ -0,0 +1,169 @@
+//! HTTP headers as carried in gateway events.
+//!
+//! Gateway events carry headers as a JSON object. Header names are
+//! case-insensitive, so the same header can arrive as both `Authorization`
+//! and `authorization`, and a repeated header can arrive as a list of
+//! values. [`Headers`] keeps every value: names are canonicalized to lower
+//! case, each name's values are kept in the order they arrived, and lookups
+//! ignore case.
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A header map with case-insensitive names and ordered values per name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Headers {
+    /// Canonical names with their values, in the order names were first seen
+    entries: Vec<(String, Vec<String>)>,
+}
+
+impl Headers {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Canonical form of a header name: trimmed and lower case.
+    #[must_use]
+    pub fn canonical_name(name: &str) -> String {
+        name.trim().to_ascii_lowercase()
+    }
+
+    /// Adds `value` after any values `name` already has.
+    pub fn append(&mut self, name: &str, value: impl Into<String>) {
+        let name = Self::canonical_name(name);
+        match self.entries.iter_mut().find(|(known, _)| *known == name) {
+            Some((_, values)) => values.push(value.into()),
+            None => self.entries.push((name, vec![value.into()])),
+        }
+    }
+
+    /// Sets `value` as the only value of `name`.
+    pub fn insert(&mut self, name: &str, value: impl Into<String>) {
+        self.remove(name);
+        self.append(name, value);
+    }
+
+    /// Removes `name`, returning its values.
+    pub fn remove(&mut self, name: &str) -> Vec<String> {
+        let name = Self::canonical_name(name);
+        self.entries
+            .iter()
+            .position(|(known, _)| *known == name)
+            .map(|index| self.entries.remove(index).1)
+            .unwrap_or_default()
+    }
+
+    /// First value of `name`, ignoring case.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.get_all(name).first().map(String::as_str)
+    }
+
+    /// Every value of `name` in the order they arrived, ignoring case.
+    #[must_use]
+    pub fn get_all(&self, name: &str) -> &[String] {
+        let name = name.trim();
+        self.entries
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            .map_or(&[], |(_, values)| values.as_slice())
+    }
+
+    /// Whether `name` has a value, ignoring case.
+    #[must_use]
+    pub fn contains_key(&self, name: &str) -> bool {
+        !self.get_all(name).is_empty()
+    }
+
+    /// Every name and value, one pair per value.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().flat_map(|(name, values)| {
+            values
+                .iter()
+                .map(move |value| (name.as_str(), value.as_str()))
+        })
+    }
+
+    /// Number of distinct names.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K: AsRef<str>, V: Into<String>> FromIterator<(K, V)> for Headers {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(pairs: I) -> Self {
+        let mut headers = Self::new();
+        for (name, value) in pairs {
+            headers.append(name.as_ref(), value);
+        }
+        headers
+    }
+}
+
+impl<K: AsRef<str>, V: Into<String>, const N: usize> From<[(K, V); N]> for Headers {
+    fn from(pairs: [(K, V); N]) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+/// Serialized as an object with one string per name; repeated values are
+/// combined with `, `, as HTTP allows for request headers.
+impl Serialize for Headers {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.entries
+                .iter()
+                .map(|(name, values)| (name, values.join(", "))),
+        )
+    }
+}
+
+/// Deserialized from an object whose values are strings or lists of
+/// strings; names differing only in case are merged.
+impl<'de> Deserialize<'de> for Headers {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(HeadersVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HeaderValues {
+    One(String),
+    Many(Vec<String>),
+}
+
+struct HeadersVisitor;
+
+impl<'de> Visitor<'de> for HeadersVisitor {
+    type Value = Headers;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an object of header names to a string or list of strings")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Headers, A::Error> {
+        let mut headers = Headers::new();
+        while let Some((name, values)) = map.next_entry::<String, HeaderValues>()? {
+            match values {
+                HeaderValues::One(value) => headers.append(&name, value),
+                HeaderValues::Many(values) => {
+                    for value in values {
+                        headers.append(&name, value);
+                    }
+                }
+            }
+        }
+        Ok(headers)
+    }
+}
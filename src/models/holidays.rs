@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HolidaysRequest {
+    /// ISO 3166-1 alpha-2 country code, e.g. "AU"
+    #[schemars(length(min = 2, max = 2))]
+    pub country_code: String,
+    /// Calendar year (default: the current year)
+    #[serde(default)]
+    #[schemars(range(min = 1900, max = 2200))]
+    pub year: Option<i32>,
+    /// Maximum number of holidays to return per page, in date order (default 25)
+    #[serde(default)]
+    #[schemars(range(min = 1, max = 100))]
+    pub limit: Option<usize>,
+    /// Cursor from a previous response's `next_cursor`, to fetch the next page
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HolidaysResponse {
+    pub country_code: String,
+    pub year: i32,
+    pub holidays: Vec<Holiday>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Holiday {
+    /// Date of the holiday, YYYY-MM-DD
+    pub date: String,
+    /// English name
+    pub name: String,
+    /// Name in the country's language
+    pub local_name: String,
+    /// Whether the holiday is observed nationwide rather than in some regions only
+    pub nationwide: bool,
+}
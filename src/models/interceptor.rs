@@ -3,9 +3,9 @@
 //! These types define the structure of events received and responses sent
 //! by the gateway interceptor Lambda function.
 
+use super::Headers;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
 
 /// AWS Bedrock `AgentCore` Gateway interceptor event structure
 #[derive(Deserialize, Debug)]
@@ -27,7 +27,18 @@ pub struct McpData {
 #[serde(rename_all = "camelCase")]
 pub struct GatewayRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<HashMap<String, String>>,
+    pub headers: Option<Headers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// Response the gateway returns to the caller instead of forwarding the request
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayResponse {
+    pub status_code: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Headers>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body: Option<Value>,
 }
@@ -40,8 +51,65 @@ pub struct InterceptorResponse {
     pub mcp: McpResponse,
 }
 
+impl InterceptorResponse {
+    /// Forwards `request` to the target.
+    #[must_use]
+    pub fn forward(request: GatewayRequest) -> Self {
+        Self {
+            interceptor_output_version: "1.0".to_string(),
+            mcp: McpResponse {
+                transformed_gateway_request: Some(request),
+                transformed_gateway_response: None,
+            },
+        }
+    }
+
+    /// Answers the caller with `response`, without forwarding the request.
+    #[must_use]
+    pub fn respond(response: GatewayResponse) -> Self {
+        Self {
+            interceptor_output_version: "1.0".to_string(),
+            mcp: McpResponse {
+                transformed_gateway_request: None,
+                transformed_gateway_response: Some(response),
+            },
+        }
+    }
+}
+
+/// Either the request to forward or the response to return in its place
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct McpResponse {
-    pub transformed_gateway_request: GatewayRequest,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transformed_gateway_request: Option<GatewayRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transformed_gateway_response: Option<GatewayResponse>,
+}
+
+/// What the interceptor decided for a request, logged as a structured record.
+///
+/// In passthrough mode the decision is only logged, so policy changes can be
+/// checked against production traffic before they take effect. Credential
+/// and identity arguments the client sent are still stripped.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InterceptorDecision {
+    /// Tool being called, with any gateway prefix stripped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// Agent conversation the call belongs to, from the request headers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Whether the decision was applied to the request
+    pub applied: bool,
+    /// Arguments injected (or that would have been), by name only
+    pub injected: Vec<&'static str>,
+    /// Credential and identity arguments the client sent itself, which are
+    /// always removed, by name only
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stripped: Vec<&'static str>,
+    /// Why the tool scope policy would reject the call, if it would
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked: Option<String>,
 }
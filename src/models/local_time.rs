@@ -0,0 +1,80 @@
+//! Local times as Open-Meteo reports them.
+//!
+//! Open-Meteo gives times in the forecast's timezone without an offset and
+//! to the minute, e.g. `2026-10-14T09:15`, which `chrono`'s own
+//! deserializer rejects. [`LocalTime`] parses that format (seconds are
+//! accepted too) and writes it back unchanged, so cached forecasts keep
+//! their shape. Published responses attach the forecast's UTC offset with
+//! [`LocalTime::with_offset`].
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Offset, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Format Open-Meteo uses for times
+const FORMAT: &str = "%Y-%m-%dT%H:%M";
+/// The same with seconds
+const FORMAT_WITH_SECONDS: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// A time in the forecast location's timezone, without an offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LocalTime(NaiveDateTime);
+
+impl LocalTime {
+    /// The time as a `chrono` value.
+    #[must_use]
+    pub const fn get(self) -> NaiveDateTime {
+        self.0
+    }
+
+    /// The calendar date of the time.
+    #[must_use]
+    pub const fn date(self) -> NaiveDate {
+        self.0.date()
+    }
+
+    /// The time with the location's offset from UTC of `utc_offset_seconds`,
+    /// or as UTC if the offset is out of range.
+    #[must_use]
+    pub fn with_offset(self, utc_offset_seconds: i32) -> DateTime<FixedOffset> {
+        let offset = FixedOffset::east_opt(utc_offset_seconds).unwrap_or_else(|| Utc.fix());
+        let utc = self.0 - TimeDelta::seconds(i64::from(offset.local_minus_utc()));
+        DateTime::from_naive_utc_and_offset(utc, offset)
+    }
+}
+
+impl From<NaiveDateTime> for LocalTime {
+    fn from(time: NaiveDateTime) -> Self {
+        Self(time)
+    }
+}
+
+impl std::str::FromStr for LocalTime {
+    type Err = chrono::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveDateTime::parse_from_str(s, FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(s, FORMAT_WITH_SECONDS))
+            .map(Self)
+    }
+}
+
+impl fmt::Display for LocalTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0.format(FORMAT), f)
+    }
+}
+
+impl Serialize for LocalTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse()
+            .map_err(|e| serde::de::Error::custom(format!("invalid time {text:?}: {e}")))
+    }
+}
@@ -0,0 +1,37 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct QueryLogsRequest {
+    /// Log group names to query; each must be on the server's allowlist
+    pub log_groups: Vec<String>,
+    /// `CloudWatch` Logs Insights query, e.g. `fields @timestamp, @message | filter @message like /ERROR/`
+    pub query: String,
+    /// Start of the time range in RFC 3339 format (default: one hour before `end_time`)
+    #[serde(default)]
+    pub start_time: Option<String>,
+    /// End of the time range in RFC 3339 format (default: now)
+    #[serde(default)]
+    pub end_time: Option<String>,
+    /// Maximum number of rows to return per page (default 100, capped by server configuration)
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Cursor from a previous response's `next_cursor`; the original time range is reused
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueryLogsResponse {
+    /// Query status reported by `CloudWatch`, e.g. `Complete`
+    pub status: String,
+    /// One map of field name to value per result row
+    pub rows: Vec<BTreeMap<String, String>>,
+    pub records_matched: f64,
+    pub records_scanned: f64,
+    pub bytes_scanned: f64,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
@@ -1,10 +1,81 @@
+pub mod admin;
+pub mod briefing;
+pub mod completion;
+pub mod compose;
+pub mod consent;
+pub mod content;
+pub mod costs;
+pub mod declarative;
+pub mod describe;
+pub mod effects;
 pub mod error;
+pub mod feed;
+pub mod headers;
+pub mod holidays;
 pub mod interceptor;
+pub mod local_time;
+pub mod logs;
+pub mod notification;
 pub mod open_meteo;
 pub mod personalized;
+pub mod pollen;
+pub mod roots;
+pub mod sampling;
+pub mod sandbox;
+pub mod summarize;
+pub mod units;
+pub mod versioning;
 pub mod weather;
+pub mod workflow;
 
+pub use admin::{
+    AdminCacheStatsRequest, AdminCacheStatsResponse, AdminFlushCacheRequest,
+    AdminFlushCacheResponse, AdminReloadConfigRequest, AdminReloadConfigResponse,
+    AdminReplayFailedRequest, AdminReplayFailedResponse, AdminReplayInvocationRequest,
+    AdminReplayInvocationResponse, AdminSetLogFilterRequest, AdminSetLogFilterResponse,
+    AdminToggleToolRequest, AdminToggleToolResponse, AdminToolVersionsRequest,
+    AdminToolVersionsResponse, AdminUsageReportRequest, AdminUsageReportResponse, CacheReport,
+    FlushedCache, InvocationDifference, InvocationOutcome, ReplayedCall, ResponseShape,
+    TenantUsage, ToolShapeHistory, ToolUsage,
+};
+pub use briefing::{BriefingWeather, DailyBriefingRequest, DailyBriefingResponse};
+pub use completion::{
+    CompleteParams, CompleteResult, Completion, CompletionArgument, CompletionRef,
+};
+pub use compose::{
+    RunStatus, RunWorkflowRequest, RunWorkflowResponse, StepResult, StepStatus, WorkflowStep,
+};
+pub use consent::{RecordConsentRequest, RecordConsentResponse};
+pub use content::{BlobResource, ContentBlock, ContentResponse};
+pub use costs::{CostsRequest, CostsResponse, ServiceCost};
+pub use declarative::{RunDeclarativeToolRequest, RunDeclarativeToolResponse};
+pub use describe::{
+    AuthCapabilities, CachePolicy, DescribeServerRequest, DescribeServerResponse, LoadLimits,
+    ServerBuild, ToolCapability,
+};
+pub use effects::{DryRunPlan, SideEffectStatus};
 pub use error::AppError;
+pub use feed::{FeedItem, FeedRequest, FeedResponse};
+pub use headers::Headers;
+pub use holidays::{Holiday, HolidaysRequest, HolidaysResponse};
 pub use interceptor::*;
+pub use local_time::LocalTime;
+pub use logs::{QueryLogsRequest, QueryLogsResponse};
+pub use notification::{NotificationChannel, NotificationRequest, NotificationResponse};
 pub use personalized::*;
-pub use weather::{Daily, DailyUnits, WeatherRequest, WeatherResponse};
+pub use pollen::{PollenDay, PollenLevel, PollenRequest, PollenResponse};
+pub use roots::{ListRootsResult, Root};
+pub use sampling::{CreateMessageParams, CreateMessageResult, SamplingContent, SamplingMessage};
+pub use sandbox::{RunSandboxedToolRequest, RunSandboxedToolResponse};
+pub use summarize::{SummarizeRequest, SummarizeResponse};
+pub use units::{Celsius, Centimeters, Fahrenheit, Millimeters, Temperature, WmoCode};
+pub use versioning::{ResponseVersion, Versioned};
+pub use weather::{
+    ConvectionRisk, CurrentConditions, Daily, DailyForecast, DailyUnits, ForecastLocation,
+    LocatedForecast, ResolvedLocation, RiskLevel, RoadConditions, UnitSystem,
+    VersionedWeatherResponse, WeatherChartRequest, WeatherOutput, WeatherRequest, WeatherResponse,
+    WeatherResponseV2,
+};
+pub use workflow::{
+    StartWorkflowRequest, StartWorkflowResponse, WorkflowStatusRequest, WorkflowStatusResponse,
+};
@@ -0,0 +1,42 @@
+use super::effects::{DryRunPlan, SideEffectStatus};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Delivery channel: `sns` publishes to an SNS topic, `email` sends through SES
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    Sns,
+    Email,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationRequest {
+    pub channel: NotificationChannel,
+    /// SNS topic ARN or email address; must be on the server's allowlist
+    pub destination: String,
+    /// Subject line (email subject, or SNS subject for email subscribers)
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// Message body; `{{name}}` placeholders are filled from `variables`
+    pub message: String,
+    /// Values for `{{name}}` placeholders in the subject and message
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Key identifying this notification, so a retried call does not send it twice (default: derived from the arguments and conversation)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationResponse {
+    pub channel: NotificationChannel,
+    pub destination: String,
+    /// Message ID assigned by SNS or SES
+    pub message_id: Option<String>,
+    pub side_effect_status: SideEffectStatus,
+    /// The notification that would have been sent, for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<DryRunPlan>,
+}
@@ -1,6 +1,20 @@
+//! Open-Meteo forecast API response.
+//!
+//! This is the single upstream weather model: it carries both the current
+//! conditions block and the daily forecast, in Open-Meteo's own field names.
+//! Forecasts are cached in this shape, and each published response version
+//! is derived from it by the conversions in [`crate::models::weather`].
+//! Temperatures and amounts are always requested in °C, mm and cm. They are
+//! validated along with weather codes as they are parsed, but serialize bare
+//! as Open-Meteo sends them (see [`crate::models::units`]); dates and local
+//! times are parsed into `chrono` types (see [`crate::models::local_time`]).
+
+use super::local_time::LocalTime;
+use super::units::{Celsius, Centimeters, Millimeters, WmoCode, bare};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenMeteoResponse {
     pub latitude: f64,
     pub longitude: f64,
@@ -9,22 +23,112 @@ pub struct OpenMeteoResponse {
     pub timezone: String,
     pub timezone_abbreviation: String,
     pub elevation: f64,
+    /// Present when `current` variables were requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_units: Option<CurrentUnits>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<Current>,
     pub daily_units: DailyUnits,
     pub daily: Daily,
+    /// Present when `hourly` variables were requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly_units: Option<HourlyUnits>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly: Option<Hourly>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentUnits {
+    pub time: String,
+    pub interval: String,
+    pub temperature_2m: String,
+    pub relative_humidity_2m: String,
+    pub apparent_temperature: String,
+    pub weather_code: String,
+    pub wind_speed_10m: String,
+    pub wind_direction_10m: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Current {
+    pub time: LocalTime,
+    /// Length of the averaging window, in seconds
+    pub interval: u32,
+    #[serde(serialize_with = "bare")]
+    pub temperature_2m: Celsius,
+    pub relative_humidity_2m: f64,
+    #[serde(serialize_with = "bare")]
+    pub apparent_temperature: Celsius,
+    pub weather_code: WmoCode,
+    pub wind_speed_10m: f64,
+    pub wind_direction_10m: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyUnits {
     pub time: String,
     pub weather_code: String,
     pub temperature_2m_max: String,
     pub temperature_2m_min: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snowfall_sum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precipitation_sum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_humidity_2m_max: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Daily forecast as parallel lists; the snow, precipitation and humidity
+/// lists are empty in forecasts cached before they were requested.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Daily {
-    pub time: Vec<String>,
-    pub weather_code: Vec<i32>,
-    pub temperature_2m_max: Vec<f64>,
-    pub temperature_2m_min: Vec<f64>,
+    pub time: Vec<NaiveDate>,
+    pub weather_code: Vec<WmoCode>,
+    #[serde(serialize_with = "bare")]
+    pub temperature_2m_max: Vec<Celsius>,
+    #[serde(serialize_with = "bare")]
+    pub temperature_2m_min: Vec<Celsius>,
+    #[serde(default, serialize_with = "bare")]
+    pub snowfall_sum: Vec<Centimeters>,
+    #[serde(default, serialize_with = "bare")]
+    pub precipitation_sum: Vec<Millimeters>,
+    #[serde(default)]
+    pub relative_humidity_2m_max: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyUnits {
+    pub time: String,
+    pub cape: String,
+    pub lifted_index: String,
+}
+
+/// Hourly convection variables as parallel lists; values are `null` for
+/// hours a model does not cover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hourly {
+    pub time: Vec<LocalTime>,
+    /// Convective available potential energy, in J/kg
+    pub cape: Vec<Option<f64>>,
+    pub lifted_index: Vec<Option<f64>>,
+}
+
+impl Daily {
+    /// Keeps only the days whose date `keep` accepts, in every list.
+    pub fn retain(&mut self, keep: impl Fn(NaiveDate) -> bool) {
+        let kept: Vec<bool> = self.time.iter().map(|date| keep(*date)).collect();
+        retain_days(&mut self.time, &kept);
+        retain_days(&mut self.weather_code, &kept);
+        retain_days(&mut self.temperature_2m_max, &kept);
+        retain_days(&mut self.temperature_2m_min, &kept);
+        retain_days(&mut self.snowfall_sum, &kept);
+        retain_days(&mut self.precipitation_sum, &kept);
+        retain_days(&mut self.relative_humidity_2m_max, &kept);
+    }
+}
+
+/// Keeps the entries of `list` whose flag in `kept` is set
+fn retain_days<T>(list: &mut Vec<T>, kept: &[bool]) {
+    let mut kept = kept.iter();
+    list.retain(|_| kept.next().copied().unwrap_or(false));
 }
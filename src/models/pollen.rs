@@ -0,0 +1,46 @@
+use super::weather::{ResolvedLocation, RiskLevel};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PollenRequest {
+    /// City or place name, optionally followed by a country, e.g. "Vienna, AT"
+    pub location: String,
+    /// Number of days to forecast, starting today (default 3)
+    #[serde(default)]
+    #[schemars(range(min = 1, max = 4))]
+    pub days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PollenResponse {
+    pub resolved_location: ResolvedLocation,
+    /// Whether pollen forecasts cover the location; if not, `days` is empty and `reason` says why
+    pub available: bool,
+    /// Why the location has no pollen forecast
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Unit of the pollen concentrations, e.g. "grains/m³"
+    pub unit: String,
+    pub days: Vec<PollenDay>,
+}
+
+/// Pollen forecast for one day.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PollenDay {
+    /// ISO 8601 date
+    pub date: String,
+    /// Pollen types forecast for the location; types without data there are left out
+    pub pollen: Vec<PollenLevel>,
+}
+
+/// Forecast concentration of one pollen type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PollenLevel {
+    /// Pollen type, e.g. "birch" or "grass"
+    pub kind: String,
+    /// Highest hourly concentration of the day
+    pub peak: f64,
+    /// Allergy risk of the peak concentration for this pollen type
+    pub risk: RiskLevel,
+}
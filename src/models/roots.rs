@@ -0,0 +1,43 @@
+//! MCP `roots/list` result types and notification names.
+//!
+//! Roots are the workspace locations a client exposes to the server, as
+//! URIs such as `file:///home/ada/project`. Field names follow the MCP
+//! specification.
+
+use serde::{Deserialize, Serialize};
+
+/// JSON-RPC method a server calls to list the client's roots
+pub const ROOTS_LIST_METHOD: &str = "roots/list";
+
+/// Notification a client sends when its roots change
+pub const ROOTS_LIST_CHANGED: &str = "notifications/roots/list_changed";
+
+/// A workspace location exposed by the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Root {
+    /// Location of the root, e.g. `file:///home/ada/project`
+    pub uri: String,
+    /// Display name for the root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Root {
+    /// Returns `true` if `uri` is this root or lies beneath it.
+    ///
+    /// Matching is by path segment, so `file:///work/app` does not contain
+    /// `file:///work/application`, and URIs with `..` segments never match.
+    #[must_use]
+    pub fn contains(&self, uri: &str) -> bool {
+        let root = self.uri.trim_end_matches('/');
+        uri.strip_prefix(root).is_some_and(|rest| {
+            (rest.is_empty() || rest.starts_with('/')) && !rest.split('/').any(|s| s == "..")
+        })
+    }
+}
+
+/// The client's reply to a `roots/list` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListRootsResult {
+    pub roots: Vec<Root>,
+}
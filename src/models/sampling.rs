@@ -0,0 +1,78 @@
+//! MCP `sampling/createMessage` request and result types.
+//!
+//! Field names follow the MCP specification, so these serialize directly
+//! into the JSON-RPC params a client expects and parse its reply.
+
+use serde::{Deserialize, Serialize};
+
+/// JSON-RPC method a server calls to ask the client's model for a message
+pub const SAMPLING_METHOD: &str = "sampling/createMessage";
+
+/// Parameters of a `sampling/createMessage` request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageParams {
+    pub messages: Vec<SamplingMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+}
+
+/// One message of the conversation sent for sampling.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SamplingMessage {
+    /// `user` or `assistant`
+    pub role: String,
+    pub content: SamplingContent,
+}
+
+impl SamplingMessage {
+    /// A text message from the user.
+    #[must_use]
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: SamplingContent::Text { text: text.into() },
+        }
+    }
+}
+
+/// Content of a sampled message; only text is requested by this server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SamplingContent {
+    Text {
+        text: String,
+    },
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+}
+
+/// The client's reply to a `sampling/createMessage` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    pub role: String,
+    pub content: SamplingContent,
+    /// Model the client chose
+    pub model: String,
+    /// Why generation stopped, e.g. `endTurn` or `maxTokens`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+impl CreateMessageResult {
+    /// The generated text, or `None` for non-text content.
+    #[must_use]
+    pub fn text(&self) -> Option<&str> {
+        match &self.content {
+            SamplingContent::Text { text } => Some(text),
+            SamplingContent::Image { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunSandboxedToolRequest {
+    /// Sandboxed tool name from the server's allowlist
+    pub tool: String,
+    /// Arguments for the tool; validated against its input schema
+    #[serde(default)]
+    #[schemars(with = "Map<String, Value>")]
+    pub arguments: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunSandboxedToolResponse {
+    pub tool: String,
+    /// The object the tool returned
+    #[schemars(with = "Map<String, Value>")]
+    pub result: Value,
+    /// Fuel the call burned, roughly the number of instructions executed
+    pub fuel_used: u64,
+}
@@ -0,0 +1,24 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SummarizeRequest {
+    /// Text to summarize
+    pub text: String,
+    /// Optional focus for the summary, e.g. "key risks" or "three bullet points"
+    #[serde(default)]
+    pub instructions: Option<String>,
+    /// Maximum summary length in tokens (capped by server configuration)
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SummarizeResponse {
+    pub summary: String,
+    pub model_id: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    /// Why generation stopped, e.g. `end_turn` or `max_tokens`
+    pub stop_reason: String,
+}
@@ -0,0 +1,333 @@
+//! Typed weather values, validated when they are deserialized.
+//!
+//! Open-Meteo occasionally returns values no real forecast can contain, and
+//! a bare `f64` or `i32` passes them straight to agents. These newtypes
+//! reject them when the upstream response (or a cached forecast) is parsed,
+//! so the call fails instead of reporting a weather code of 9999.
+//!
+//! Each measurement has its unit in its type: forecasts are fetched in °C,
+//! mm and cm, and converted to °F only when a response is built for an
+//! imperial caller (see [`Temperature`]). Measurements serialize tagged with
+//! their unit, e.g. `{"value": 21.5, "unit": "°C"}`, so a number is never
+//! read in the wrong unit; they deserialize from the tagged form or from a
+//! bare number in their own unit, as Open-Meteo sends them. Models kept in
+//! Open-Meteo's shape, including version 1 of the `get_weather` response,
+//! serialize them bare instead (see [`bare`]).
+
+use super::weather::UnitSystem;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+
+/// Lowest plausible temperature, in °C
+const MIN_CELSIUS: f64 = -90.0;
+/// Highest plausible temperature, in °C
+const MAX_CELSIUS: f64 = 60.0;
+/// Lowest plausible temperature, in °F: -90 °C
+const MIN_FAHRENHEIT: f64 = -130.0;
+/// Highest plausible temperature, in °F: 60 °C
+const MAX_FAHRENHEIT: f64 = 140.0;
+/// Highest plausible daily precipitation, in mm
+const MAX_MILLIMETERS: f64 = 2000.0;
+/// Highest plausible daily snowfall, in cm
+const MAX_CENTIMETERS: f64 = 500.0;
+
+/// WMO weather interpretation codes Open-Meteo reports.
+const WMO_CODES: &[i32] = &[
+    0, 1, 2, 3, 45, 48, 51, 53, 55, 56, 57, 61, 63, 65, 66, 67, 71, 73, 75, 77, 80, 81, 82, 85, 86,
+    95, 96, 99,
+];
+
+/// A measurement as it is written: tagged with its unit, or as Open-Meteo
+/// sends it, a bare number in the unit requested.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Reading<'a> {
+    Bare(f64),
+    Tagged { value: f64, unit: Cow<'a, str> },
+}
+
+/// A value that serializes as bare numbers in models kept in Open-Meteo's
+/// shape.
+pub trait Bare {
+    /// The value as Open-Meteo sends it
+    type Form: Serialize;
+
+    /// The value without its unit.
+    fn bare(&self) -> Self::Form;
+}
+
+impl<T: Bare> Bare for Vec<T> {
+    type Form = Vec<T::Form>;
+
+    fn bare(&self) -> Self::Form {
+        self.iter().map(Bare::bare).collect()
+    }
+}
+
+/// Serializes `value` without its unit, for `#[serde(serialize_with)]`.
+///
+/// # Errors
+///
+/// Returns the serializer's error.
+pub fn bare<T: Bare, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.bare().serialize(serializer)
+}
+
+/// A temperature, in °C.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Celsius(f64);
+
+/// A temperature, in °F; only built from [`Celsius`] for imperial callers.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Fahrenheit(f64);
+
+/// A precipitation amount, in mm.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Millimeters(f64);
+
+/// A snowfall amount, in cm.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Centimeters(f64);
+
+impl From<Celsius> for Fahrenheit {
+    fn from(celsius: Celsius) -> Self {
+        Self(celsius.0 * 9.0 / 5.0 + 32.0)
+    }
+}
+
+impl Centimeters {
+    /// The amount in inches.
+    #[must_use]
+    pub fn inches(self) -> f64 {
+        self.0 / 2.54
+    }
+}
+
+/// A temperature in the unit system a caller asked for, serialized tagged
+/// with its unit like the [`Celsius`] or [`Fahrenheit`] it holds.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Temperature {
+    Celsius(Celsius),
+    Fahrenheit(Fahrenheit),
+}
+
+impl Temperature {
+    /// `celsius` in the temperature unit of `units`.
+    #[must_use]
+    pub fn new(celsius: Celsius, units: UnitSystem) -> Self {
+        match units {
+            UnitSystem::Metric => Self::Celsius(celsius),
+            UnitSystem::Imperial => Self::Fahrenheit(celsius.into()),
+        }
+    }
+
+    /// The temperature as a number, in its unit.
+    #[must_use]
+    pub const fn get(self) -> f64 {
+        match self {
+            Self::Celsius(Celsius(value)) | Self::Fahrenheit(Fahrenheit(value)) => value,
+        }
+    }
+
+    /// The temperature's unit, e.g. "°C".
+    #[must_use]
+    pub const fn unit(self) -> &'static str {
+        match self {
+            Self::Celsius(_) => Celsius::UNIT,
+            Self::Fahrenheit(_) => Fahrenheit::UNIT,
+        }
+    }
+}
+
+impl From<Celsius> for Temperature {
+    fn from(celsius: Celsius) -> Self {
+        Self::Celsius(celsius)
+    }
+}
+
+impl Bare for Temperature {
+    type Form = f64;
+
+    fn bare(&self) -> f64 {
+        self.get()
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.get(), f)
+    }
+}
+
+impl JsonSchema for Temperature {
+    fn schema_name() -> Cow<'static, str> {
+        "Temperature".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A temperature in °C, or °F for imperial units, tagged with its unit",
+            "type": "object",
+            "properties": {
+                "value": {"type": "number"},
+                "unit": {"enum": [Celsius::UNIT, Fahrenheit::UNIT]}
+            },
+            "required": ["value", "unit"]
+        })
+    }
+}
+
+/// A WMO weather interpretation code, e.g. 3 for overcast or 95 for a
+/// thunderstorm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "i32", into = "i32")]
+pub struct WmoCode(i32);
+
+impl WmoCode {
+    /// The code as a number.
+    #[must_use]
+    pub const fn get(self) -> i32 {
+        self.0
+    }
+}
+
+impl TryFrom<i32> for WmoCode {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        if WMO_CODES.contains(&value) {
+            Ok(Self(value))
+        } else {
+            Err(format!("{value} is not a WMO weather code"))
+        }
+    }
+}
+
+impl From<WmoCode> for i32 {
+    fn from(code: WmoCode) -> Self {
+        code.0
+    }
+}
+
+impl fmt::Display for WmoCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl JsonSchema for WmoCode {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        i32::schema_name()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        i32::json_schema(generator)
+    }
+}
+
+macro_rules! measurement {
+    ($name:ident, $unit:literal, $min:expr, $max:expr) => {
+        impl $name {
+            /// Unit the value is in, as it is tagged when serialized
+            pub const UNIT: &'static str = $unit;
+
+            /// The value as a number, in [`Self::UNIT`].
+            #[must_use]
+            pub const fn get(self) -> f64 {
+                self.0
+            }
+        }
+
+        impl TryFrom<f64> for $name {
+            type Error = String;
+
+            fn try_from(value: f64) -> Result<Self, Self::Error> {
+                if ($min..=$max).contains(&value) {
+                    Ok(Self(value))
+                } else {
+                    Err(format!(
+                        "{value} {} is outside {} to {}",
+                        $unit, $min, $max
+                    ))
+                }
+            }
+        }
+
+        impl From<$name> for f64 {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Bare for $name {
+            type Form = f64;
+
+            fn bare(&self) -> f64 {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                Reading::Tagged {
+                    value: self.0,
+                    unit: Cow::Borrowed($unit),
+                }
+                .serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = match Reading::deserialize(deserializer)? {
+                    Reading::Bare(value) => value,
+                    Reading::Tagged { value, unit } if unit == $unit => value,
+                    Reading::Tagged { unit, .. } => {
+                        return Err(de::Error::custom(format!(
+                            "expected a value in {}, not {unit}",
+                            $unit
+                        )));
+                    }
+                };
+                Self::try_from(value).map_err(de::Error::custom)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "description": concat!("A value in ", $unit, ", tagged with its unit"),
+                    "type": "object",
+                    "properties": {
+                        "value": {"type": "number"},
+                        "unit": {"const": $unit}
+                    },
+                    "required": ["value", "unit"]
+                })
+            }
+        }
+    };
+}
+
+measurement!(Celsius, "°C", MIN_CELSIUS, MAX_CELSIUS);
+measurement!(Fahrenheit, "°F", MIN_FAHRENHEIT, MAX_FAHRENHEIT);
+measurement!(Millimeters, "mm", 0.0, MAX_MILLIMETERS);
+measurement!(Centimeters, "cm", 0.0, MAX_CENTIMETERS);
@@ -0,0 +1,75 @@
+//! Response version negotiation.
+//!
+//! Tool responses evolve, but Bedrock agent configurations are written
+//! against the shape they were built with. A caller asks for a newer shape
+//! with the `response_version` argument, which the interceptor also fills
+//! from the `X-Response-Version` header; callers that ask for nothing keep
+//! getting version 1. Every version converts from the same underlying data
+//! with a `From` impl, so tools fetch once and [`Versioned`] builds the
+//! representation to serialize.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool argument selecting the response version
+pub const RESPONSE_VERSION_ARG: &str = "response_version";
+
+/// Gateway request header the interceptor copies into [`RESPONSE_VERSION_ARG`]
+pub const RESPONSE_VERSION_HEADER: &str = "x-response-version";
+
+/// Response shape requested by the caller; defaults to "1".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ResponseVersion {
+    #[default]
+    #[serde(rename = "1", alias = "v1")]
+    V1,
+    #[serde(rename = "2", alias = "v2")]
+    V2,
+}
+
+impl ResponseVersion {
+    /// Every version, oldest first
+    pub const ALL: [Self; 2] = [Self::V1, Self::V2];
+
+    /// The version as sent in `response_version`, e.g. `2`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::V1 => "1",
+            Self::V2 => "2",
+        }
+    }
+
+    /// Parses a header value such as `2` or `v2`.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "1" | "v1" => Some(Self::V1),
+            "2" | "v2" => Some(Self::V2),
+            _ => None,
+        }
+    }
+}
+
+/// A response serialized in the version the caller asked for.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Versioned<V1, V2> {
+    V1(V1),
+    V2(V2),
+}
+
+impl<V1, V2> Versioned<V1, V2> {
+    /// Builds the `version` representation of `data`.
+    #[must_use]
+    pub fn negotiate<T>(data: T, version: ResponseVersion) -> Self
+    where
+        V1: From<T>,
+        V2: From<T>,
+    {
+        match version {
+            ResponseVersion::V1 => Self::V1(data.into()),
+            ResponseVersion::V2 => Self::V2(data.into()),
+        }
+    }
+}
@@ -1,13 +1,83 @@
+use super::open_meteo::{self, OpenMeteoResponse};
+use super::units::{Celsius, Fahrenheit, Temperature, WmoCode, bare};
+use super::versioning::{ResponseVersion, Versioned};
+use crate::derived;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WeatherRequest {
     pub location: String,
+    #[serde(default, rename = "response_version")]
+    pub response_version: ResponseVersion,
+    #[serde(default)]
+    pub units: UnitSystem,
+    /// IANA timezone for dates and times, e.g. "Europe/Berlin" (default: the caller's profile, else the location's)
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Only return the days this phrase names in the location's timezone, e.g. "tomorrow", "this weekend", "next Tuesday", "in 3 days" or "2026-10-17" (default: every forecast day)
+    #[serde(default)]
+    pub when: Option<String>,
+    /// Locale whose language `when` is in, e.g. "de-DE"; English, German, French and Spanish are understood (default: the caller's profile, else English)
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Return Open-Meteo's forecast JSON as is, without the matched location or derived fields; `response_version` and `when` are ignored (default false)
+    #[serde(default)]
+    pub raw: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+/// Units for temperatures and wind speeds: "metric" (°C, km/h, the default) or "imperial" (°F, mph).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    /// Parses a unit preference such as `imperial` or `US`.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "metric" | "si" => Some(Self::Metric),
+            "imperial" | "us" => Some(Self::Imperial),
+            _ => None,
+        }
+    }
+
+    /// Unit temperatures are reported in, e.g. "°C".
+    #[must_use]
+    pub const fn temperature_unit(self) -> &'static str {
+        match self {
+            Self::Metric => Celsius::UNIT,
+            Self::Imperial => Fahrenheit::UNIT,
+        }
+    }
+}
+
+/// `get_weather` response in the version the caller asked for.
+pub type VersionedWeatherResponse = Versioned<WeatherResponse, WeatherResponseV2>;
+
+/// `get_weather` response: a published version, or in raw mode the upstream
+/// forecast passed through without being deserialized.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum WeatherOutput {
+    Versioned(Box<VersionedWeatherResponse>),
+    Raw(Box<RawValue>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherChartRequest {
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WeatherResponse {
     pub latitude: f64,
@@ -19,9 +89,20 @@ pub struct WeatherResponse {
     pub elevation: f64,
     pub daily_units: DailyUnits,
     pub daily: Daily,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_location: Option<ResolvedLocation>,
+    /// Other places the location name matched, most likely first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<ResolvedLocation>,
+    /// Road conditions for each day in `daily.time`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub road_conditions: Vec<RoadConditions>,
+    /// Severe convection risk for each day in `daily.time`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub convection: Vec<ConvectionRisk>,
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyUnits {
     pub time: String,
@@ -30,11 +111,296 @@ pub struct DailyUnits {
     pub temperature_2m_min: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Daily {
-    pub time: Vec<String>,
-    pub weather_code: Vec<i32>,
-    pub temperature_2m_max: Vec<f64>,
-    pub temperature_2m_min: Vec<f64>,
+    pub time: Vec<NaiveDate>,
+    pub weather_code: Vec<WmoCode>,
+    // Version 1 keeps bare numbers, in the units of `daily_units`
+    #[serde(serialize_with = "bare")]
+    #[schemars(with = "Vec<f64>")]
+    pub temperature_2m_max: Vec<Temperature>,
+    #[serde(serialize_with = "bare")]
+    #[schemars(with = "Vec<f64>")]
+    pub temperature_2m_min: Vec<Temperature>,
+}
+
+/// Version 2 of the `get_weather` response: location details grouped,
+/// current conditions, and one record per day instead of parallel arrays.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherResponseV2 {
+    pub location: ForecastLocation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_location: Option<ResolvedLocation>,
+    /// Other places the location name matched, most likely first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<ResolvedLocation>,
+    /// Unit of the temperatures, e.g. "°C"
+    pub temperature_unit: String,
+    /// Unit of the wind speed, e.g. "km/h"; absent without current conditions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind_speed_unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<CurrentConditions>,
+    pub days: Vec<DailyForecast>,
+}
+
+/// Conditions at the location at the time of the request.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentConditions {
+    /// ISO 8601 local time of the observation, with the location's UTC offset
+    pub time: DateTime<FixedOffset>,
+    pub temperature: Temperature,
+    pub apparent_temperature: Temperature,
+    /// Relative humidity, in percent
+    pub relative_humidity: f64,
+    /// WMO weather interpretation code
+    pub weather_code: WmoCode,
+    pub wind_speed: f64,
+    /// Wind direction, in degrees
+    pub wind_direction: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation: f64,
+    pub timezone: String,
+    pub timezone_abbreviation: String,
+    pub utc_offset_seconds: i32,
+}
+
+/// A place a location name was geocoded to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedLocation {
+    pub name: String,
+    pub country: Option<String>,
+    /// ISO 3166-1 alpha-2 country code
+    pub country_code: Option<String>,
+    /// First-level administrative region, such as a state or province
+    pub admin_region: Option<String>,
+    pub population: Option<u64>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// How likely this is the place meant, from 0 to 1: its share of the combined population of all matches. Confirm with the user when it is low.
+    pub confidence: f64,
+}
+
+/// A forecast together with the places its location name was geocoded to.
+#[derive(Debug, Clone)]
+pub struct LocatedForecast {
+    pub forecast: OpenMeteoResponse,
+    /// Unit system the response is built in; the forecast itself is metric
+    pub units: UnitSystem,
+    pub resolved_location: ResolvedLocation,
+    pub alternates: Vec<ResolvedLocation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyForecast {
+    /// ISO 8601 date
+    pub date: NaiveDate,
+    /// WMO weather interpretation code
+    pub weather_code: WmoCode,
+    pub temperature_max: Temperature,
+    pub temperature_min: Temperature,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub road_conditions: Option<RoadConditions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub convection: Option<ConvectionRisk>,
+}
+
+/// Snow and ice on roads expected during a day, derived from its forecast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RoadConditions {
+    /// Snow expected to settle, in cm (inches with imperial units)
+    pub snow_accumulation: f64,
+    /// Risk of the temperature falling below freezing
+    pub freezing_risk: RiskLevel,
+    /// Risk of black ice, from a near-freezing low with precipitation or high humidity
+    pub black_ice_risk: RiskLevel,
+}
+
+/// Risk of thunderstorms, lightning and other severe convection during a day, derived from its hourly forecast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvectionRisk {
+    pub risk: RiskLevel,
+    /// Highest hourly convective available potential energy, in J/kg
+    pub max_cape: f64,
+    /// Lowest hourly lifted index; the more negative, the more unstable the air
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lifted_index: Option<f64>,
+}
+
+/// How likely a hazard is.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    None,
+    Low,
+    Moderate,
+    High,
+}
+
+impl WeatherResponse {
+    /// The response for `response`, with temperatures and snow in `units`.
+    #[must_use]
+    pub fn new(response: OpenMeteoResponse, units: UnitSystem) -> Self {
+        Self {
+            latitude: response.latitude,
+            longitude: response.longitude,
+            generationtime_ms: response.generationtime_ms,
+            utc_offset_seconds: response.utc_offset_seconds,
+            timezone: response.timezone,
+            timezone_abbreviation: response.timezone_abbreviation,
+            elevation: response.elevation,
+            road_conditions: derived::road_conditions(&response.daily, units),
+            convection: derived::convection_risk(&response.daily, response.hourly.as_ref()),
+            daily_units: DailyUnits::new(response.daily_units, units),
+            daily: Daily::new(response.daily, units),
+            resolved_location: None,
+            alternates: Vec::new(),
+        }
+    }
+}
+
+impl From<OpenMeteoResponse> for WeatherResponse {
+    fn from(response: OpenMeteoResponse) -> Self {
+        Self::new(response, UnitSystem::Metric)
+    }
+}
+
+impl From<LocatedForecast> for WeatherResponse {
+    fn from(located: LocatedForecast) -> Self {
+        Self {
+            resolved_location: Some(located.resolved_location),
+            alternates: located.alternates,
+            ..Self::new(located.forecast, located.units)
+        }
+    }
+}
+
+impl DailyUnits {
+    /// Units of `units`, with the temperature unit of `system`.
+    #[must_use]
+    pub fn new(units: open_meteo::DailyUnits, system: UnitSystem) -> Self {
+        Self {
+            time: units.time,
+            weather_code: units.weather_code,
+            temperature_2m_max: system.temperature_unit().to_string(),
+            temperature_2m_min: system.temperature_unit().to_string(),
+        }
+    }
+}
+
+impl Daily {
+    /// The days of `daily`, with temperatures in `units`.
+    #[must_use]
+    pub fn new(daily: open_meteo::Daily, units: UnitSystem) -> Self {
+        let temperatures = |temperatures: Vec<Celsius>| {
+            temperatures
+                .into_iter()
+                .map(|temperature| Temperature::new(temperature, units))
+                .collect()
+        };
+        Self {
+            time: daily.time,
+            weather_code: daily.weather_code,
+            temperature_2m_max: temperatures(daily.temperature_2m_max),
+            temperature_2m_min: temperatures(daily.temperature_2m_min),
+        }
+    }
+}
+
+impl CurrentConditions {
+    /// Conditions from `current`, at a location `utc_offset_seconds` ahead
+    /// of UTC, with temperatures in `units`.
+    #[must_use]
+    pub fn new(current: &open_meteo::Current, utc_offset_seconds: i32, units: UnitSystem) -> Self {
+        Self {
+            time: current.time.with_offset(utc_offset_seconds),
+            temperature: Temperature::new(current.temperature_2m, units),
+            apparent_temperature: Temperature::new(current.apparent_temperature, units),
+            relative_humidity: current.relative_humidity_2m,
+            weather_code: current.weather_code,
+            wind_speed: current.wind_speed_10m,
+            wind_direction: current.wind_direction_10m,
+        }
+    }
+}
+
+impl WeatherResponseV2 {
+    /// The response for `response`, with temperatures and snow in `units`.
+    #[must_use]
+    pub fn new(response: OpenMeteoResponse, units: UnitSystem) -> Self {
+        let mut road_conditions = derived::road_conditions(&response.daily, units).into_iter();
+        let mut convection =
+            derived::convection_risk(&response.daily, response.hourly.as_ref()).into_iter();
+        let daily = response.daily;
+        let days = daily
+            .time
+            .into_iter()
+            .zip(daily.weather_code)
+            .zip(
+                daily
+                    .temperature_2m_max
+                    .into_iter()
+                    .zip(daily.temperature_2m_min),
+            )
+            .map(
+                |((date, weather_code), (temperature_max, temperature_min))| DailyForecast {
+                    date,
+                    weather_code,
+                    temperature_max: Temperature::new(temperature_max, units),
+                    temperature_min: Temperature::new(temperature_min, units),
+                    road_conditions: road_conditions.next(),
+                    convection: convection.next(),
+                },
+            )
+            .collect();
+        Self {
+            location: ForecastLocation {
+                latitude: response.latitude,
+                longitude: response.longitude,
+                elevation: response.elevation,
+                timezone: response.timezone,
+                timezone_abbreviation: response.timezone_abbreviation,
+                utc_offset_seconds: response.utc_offset_seconds,
+            },
+            resolved_location: None,
+            alternates: Vec::new(),
+            temperature_unit: units.temperature_unit().to_string(),
+            wind_speed_unit: response.current_units.map(|units| units.wind_speed_10m),
+            current: response.current.map(|current| {
+                CurrentConditions::new(&current, response.utc_offset_seconds, units)
+            }),
+            days,
+        }
+    }
+}
+
+impl From<OpenMeteoResponse> for WeatherResponseV2 {
+    fn from(response: OpenMeteoResponse) -> Self {
+        Self::new(response, UnitSystem::Metric)
+    }
+}
+
+impl From<LocatedForecast> for WeatherResponseV2 {
+    fn from(located: LocatedForecast) -> Self {
+        Self {
+            resolved_location: Some(located.resolved_location),
+            alternates: located.alternates,
+            ..Self::new(located.forecast, located.units)
+        }
+    }
 }
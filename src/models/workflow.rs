@@ -0,0 +1,53 @@
+use super::effects::{DryRunPlan, SideEffectStatus};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StartWorkflowRequest {
+    /// Workflow name (or state machine ARN) from the server's allowlist
+    pub workflow: String,
+    /// Execution input; validated against the workflow's input schema
+    #[serde(default)]
+    #[schemars(with = "Map<String, Value>")]
+    pub input: Value,
+    /// Optional execution name, unique per state machine for 90 days
+    #[serde(default)]
+    pub execution_name: Option<String>,
+    /// Key identifying this start, so a retried call does not start a second execution (default: derived from the arguments and conversation)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StartWorkflowResponse {
+    pub workflow: String,
+    /// Pass to `get_workflow_status` to track progress; empty for a dry run
+    pub execution_arn: String,
+    /// Start time in RFC 3339 format; empty for a dry run
+    pub start_date: String,
+    pub side_effect_status: SideEffectStatus,
+    /// The execution that would have been started, for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<DryRunPlan>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowStatusRequest {
+    /// Execution ARN returned by `start_workflow`
+    pub execution_arn: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowStatusResponse {
+    pub execution_arn: String,
+    /// One of `RUNNING`, `SUCCEEDED`, `FAILED`, `TIMED_OUT`, `ABORTED` or `PENDING_REDRIVE`
+    pub status: String,
+    pub start_date: String,
+    pub stop_date: Option<String>,
+    /// Execution output, once the workflow has succeeded
+    #[schemars(with = "Option<Map<String, Value>>")]
+    pub output: Option<Value>,
+    pub error: Option<String>,
+    pub cause: Option<String>,
+}
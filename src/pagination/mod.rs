@@ -0,0 +1,172 @@
+//! Cursor-based pagination shared by list-style tools.
+//!
+//! Paginated tools accept an optional `cursor` argument and return
+//! `next_cursor` when more results are available. A cursor is an opaque,
+//! HMAC-signed token carrying the offset of the next page and any state the
+//! tool needs to reproduce the same result set (e.g. a resolved time range).
+//! Each cursor is bound to a scope string describing the original query, so
+//! it cannot be tampered with or replayed against a different query.
+//!
+//! The signing key comes from `PAGINATION_SECRET`. Without it a random key is
+//! generated per container, so cursors only survive while the container is warm.
+//! If no key can be generated either, cursors are neither issued nor accepted,
+//! and a result that needs a second page fails rather than risk cursors signed
+//! with a guessable key.
+
+use crate::config::config;
+use crate::models::error::AppError;
+use aws_lc_rs::{digest, hmac, rand};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use lambda_runtime::tracing::{error, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::LazyLock;
+
+static SIGNING_KEY: LazyLock<Option<hmac::Key>> = LazyLock::new(|| {
+    if let Some(secret) = config().pagination.secret.as_deref() {
+        return Some(hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()));
+    }
+    warn!("PAGINATION_SECRET is not set; cursors will not survive cold starts");
+    let mut secret = [0u8; 32];
+    if rand::fill(&mut secret).is_err() {
+        error!("Failed to generate random pagination key; cursors are disabled");
+        return None;
+    }
+    Some(hmac::Key::new(hmac::HMAC_SHA256, &secret))
+});
+
+/// Decoded contents of a pagination cursor.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageCursor {
+    /// Index of the first item on the next page
+    #[serde(rename = "o")]
+    pub offset: usize,
+    /// Tool-specific state needed to reproduce the result set
+    #[serde(rename = "s", default, skip_serializing_if = "Value::is_null")]
+    pub state: Value,
+}
+
+/// One page of results plus the cursor for the following page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+/// Encodes and signs a cursor for `scope`.
+///
+/// # Errors
+///
+/// Returns `AppError::GenericError` if there is no signing key.
+pub fn sign_cursor(scope: &str, cursor: &PageCursor) -> Result<String, AppError> {
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(cursor).unwrap_or_default());
+    let tag = hmac::sign(signing_key()?, signing_input(scope, &payload).as_bytes());
+    Ok(format!(
+        "{payload}.{}",
+        URL_SAFE_NO_PAD.encode(tag.as_ref())
+    ))
+}
+
+/// Verifies a cursor's signature against `scope` and decodes it.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the cursor is malformed, was
+/// modified, or was issued for a different query, and
+/// `AppError::GenericError` if there is no signing key.
+pub fn verify_cursor(scope: &str, token: &str) -> Result<PageCursor, AppError> {
+    let invalid = || AppError::ValidationError("Invalid or expired cursor".to_string());
+
+    let (payload, tag) = token.trim().split_once('.').ok_or_else(invalid)?;
+    let tag = URL_SAFE_NO_PAD.decode(tag).map_err(|_| invalid())?;
+    hmac::verify(
+        signing_key()?,
+        signing_input(scope, payload).as_bytes(),
+        &tag,
+    )
+    .map_err(|_| invalid())?;
+
+    let bytes = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+    serde_json::from_slice(&bytes).map_err(|_| invalid())
+}
+
+/// Resolves an optional cursor argument, treating `None` as the first page.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the cursor fails verification.
+pub fn resolve_cursor(scope: &str, cursor: Option<&str>) -> Result<PageCursor, AppError> {
+    cursor.map(str::trim).filter(|c| !c.is_empty()).map_or_else(
+        || Ok(PageCursor::default()),
+        |token| verify_cursor(scope, token),
+    )
+}
+
+/// Slices `items` into the page starting at `cursor.offset`.
+///
+/// `next_cursor` is issued, carrying the same state, only if items remain
+/// after this page.
+///
+/// # Errors
+///
+/// Returns `AppError::GenericError` if items remain but there is no signing
+/// key to issue `next_cursor` with.
+pub fn paginate<T>(
+    items: Vec<T>,
+    scope: &str,
+    cursor: &PageCursor,
+    page_size: usize,
+) -> Result<Page<T>, AppError> {
+    let total = items.len();
+    let page_size = page_size.max(1);
+    let end = cursor.offset.saturating_add(page_size);
+
+    let next_cursor = (end < total)
+        .then(|| {
+            sign_cursor(
+                scope,
+                &PageCursor {
+                    offset: end,
+                    state: cursor.state.clone(),
+                },
+            )
+        })
+        .transpose()?;
+
+    Ok(Page {
+        items: items
+            .into_iter()
+            .skip(cursor.offset)
+            .take(page_size)
+            .collect(),
+        next_cursor,
+    })
+}
+
+/// Builds a compact scope string from the parts identifying a query.
+///
+/// The parts are hashed, so long inputs (such as log queries) keep
+/// cursors short.
+#[must_use]
+pub fn scope_for(tool: &str, parts: &[&str]) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    for part in parts {
+        context.update(part.as_bytes());
+        context.update(&[0]);
+    }
+    format!(
+        "{tool}:{}",
+        URL_SAFE_NO_PAD.encode(context.finish().as_ref())
+    )
+}
+
+fn signing_key() -> Result<&'static hmac::Key, AppError> {
+    SIGNING_KEY
+        .as_ref()
+        .ok_or_else(|| AppError::GenericError("Cursors are disabled: no signing key".to_string()))
+}
+
+fn signing_input(scope: &str, payload: &str) -> String {
+    format!("{scope}|{payload}")
+}
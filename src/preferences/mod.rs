@@ -0,0 +1,115 @@
+//! Stored preferences of signed-in users.
+//!
+//! Preferences live in the `DynamoDB` table named by `USER_PREFERENCES_TABLE`,
+//! one item per user keyed by `user_id`, with optional string attributes
+//! `home_city` and `country_code`. The routing layer uses them as defaults
+//! for arguments the caller left out (see [`crate::defaults`]). The item
+//! also records when the user consented to their personal data being used,
+//! as an RFC 3339 `consent_granted_at` (see [`crate::consent`]). Users with
+//! a data residency have their item in the table of its region (see
+//! [`crate::residency`]). Items are cached for five minutes per container.
+//! Without a table every user has empty preferences.
+
+use crate::cache::CacheStore;
+use crate::models::error::AppError;
+use crate::residency::{self, Store};
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{DateTime, Utc};
+use lambda_runtime::tracing::debug;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Partition key of the preferences table
+pub const USER_ID_KEY: &str = "user_id";
+
+/// Tool arguments that preferences can supply
+pub const PREFERENCE_ARGS: &[&str] = &["location", "country_code"];
+
+/// Preferences per user ID, shared across warm invocations
+pub(crate) static PREFERENCES_CACHE: LazyLock<CacheStore<UserPreferences>> =
+    LazyLock::new(|| CacheStore::new("preferences", Duration::from_mins(5), 256));
+
+/// A user's stored preferences; every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserPreferences {
+    /// City used when a tool needs a location and none was given
+    pub home_city: Option<String>,
+    /// ISO 3166-1 alpha-2 country code, e.g. "AU"
+    pub country_code: Option<String>,
+    /// When the user consented to their personal data being used, unless
+    /// they have not or withdrew it
+    pub consent_granted_at: Option<DateTime<Utc>>,
+}
+
+impl UserPreferences {
+    /// Reads preferences from a `DynamoDB` item, ignoring blank or non-string
+    /// attributes.
+    #[must_use]
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Self {
+        let text = |name: &str| {
+            item.get(name)
+                .and_then(|value| value.as_s().ok())
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+        Self {
+            home_city: text("home_city"),
+            country_code: text("country_code").map(|code| code.to_ascii_uppercase()),
+            consent_granted_at: text("consent_granted_at")
+                .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                .map(|at| at.with_timezone(&Utc)),
+        }
+    }
+
+    /// The tool arguments these preferences supply, keyed as in
+    /// [`PREFERENCE_ARGS`].
+    #[must_use]
+    pub fn arguments(&self) -> Map<String, Value> {
+        [
+            ("location", &self.home_city),
+            ("country_code", &self.country_code),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.to_string(), Value::from(value.clone()?))))
+        .collect()
+    }
+}
+
+/// Loads the preferences of `user_id`, from the region of their data
+/// `residency` (see [`crate::residency`]).
+///
+/// Returns empty preferences if no table is configured or the user has no
+/// item.
+///
+/// # Errors
+///
+/// Returns `AppError::PreferencesError` if the table cannot be read, or
+/// `AppError::ResidencyDenied` if no region is configured for `residency`.
+pub async fn load(user_id: &str, residency: Option<&str>) -> Result<UserPreferences, AppError> {
+    let Some(placement) = residency::place(Store::Preferences, residency)? else {
+        return Ok(UserPreferences::default());
+    };
+    if let Some(preferences) = PREFERENCES_CACHE.get(user_id) {
+        return Ok(preferences);
+    }
+
+    debug!(table = %placement.table, region = ?placement.region, "Reading user preferences");
+    let output = residency::dynamodb_client(placement.region.as_deref())
+        .await
+        .get_item()
+        .table_name(&placement.table)
+        .key(USER_ID_KEY, AttributeValue::S(user_id.to_string()))
+        .send()
+        .await
+        .map_err(|e| AppError::PreferencesError(format!("DynamoDB GetItem failed: {e}")))?;
+
+    let preferences = output
+        .item()
+        .map(UserPreferences::from_item)
+        .unwrap_or_default();
+    PREFERENCES_CACHE.insert(user_id, preferences.clone());
+    Ok(preferences)
+}
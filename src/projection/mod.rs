@@ -0,0 +1,294 @@
+//! Projection of tool responses onto the fields a caller asks for.
+//!
+//! Every tool accepts a `fields` argument listing the response fields to
+//! return, as dot paths such as `daily.temperature2mMax`, either as a list
+//! or comma-separated. Lists are stepped through transparently, so
+//! `days.date` keeps the date of every day; a JSONPath-style `$.` prefix and
+//! `[*]` are accepted and ignored. Selecting an object keeps it whole, and
+//! `_meta` is always kept. Callers that need a few values from a large
+//! response can so spend fewer tokens on it.
+//!
+//! The routing layer takes the argument before the tool runs and checks each
+//! path against the tool's output schema, so a misspelt field is reported
+//! as `InvalidInput` instead of silently returning nothing. Calls asking for
+//! a non-default `response_version` are not checked, since the published
+//! schema describes version 1; their unknown paths select nothing. Tools
+//! that declare their own `fields` argument receive it unchanged.
+
+use crate::metrics::META_KEY;
+use crate::models::error::AppError;
+use crate::models::versioning::{RESPONSE_VERSION_ARG, ResponseVersion};
+use crate::registry::ToolDescriptor;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Tool argument listing the response fields to return
+pub const FIELDS_ARG: &str = "fields";
+
+/// Most paths accepted in one call
+pub const MAX_FIELDS: usize = 50;
+
+/// Response fields selected by a caller, as a tree of field names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Projection(BTreeMap<String, Selection>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selection {
+    /// The whole field
+    All,
+    /// Only some fields of it
+    Fields(Projection),
+}
+
+impl Projection {
+    /// Parses `fields` given as a list of dot paths or a comma-separated
+    /// string; returns `None` if it selects nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ValidationError` if `fields` is neither, holds an
+    /// empty path segment, or lists more than [`MAX_FIELDS`] paths.
+    pub fn parse(fields: &Value) -> Result<Option<Self>, AppError> {
+        let paths: Vec<&str> = match fields {
+            Value::Null => Vec::new(),
+            Value::String(list) => list.split(',').collect(),
+            Value::Array(items) => items
+                .iter()
+                .map(|item| item.as_str().ok_or_else(invalid_fields))
+                .collect::<Result<_, _>>()?,
+            _ => return Err(invalid_fields()),
+        };
+        let paths: Vec<&str> = paths
+            .into_iter()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .collect();
+        if paths.len() > MAX_FIELDS {
+            return Err(AppError::ValidationError(format!(
+                "At most {MAX_FIELDS} fields may be selected"
+            )));
+        }
+
+        let mut projection = Self::default();
+        for path in paths {
+            let normalized = path
+                .strip_prefix("$.")
+                .unwrap_or(path)
+                .replace("[*]", "")
+                .replace("[]", "");
+            let segments: Vec<&str> = normalized.split('.').map(str::trim).collect();
+            if segments.iter().any(|segment| segment.is_empty()) {
+                return Err(AppError::ValidationError(format!(
+                    "Invalid field path: {path}"
+                )));
+            }
+            projection.select(&segments);
+        }
+        Ok((!projection.0.is_empty()).then_some(projection))
+    }
+
+    fn select(&mut self, segments: &[&str]) {
+        let Some((first, rest)) = segments.split_first() else {
+            return;
+        };
+        let selection = self
+            .0
+            .entry((*first).to_string())
+            .or_insert_with(|| Selection::Fields(Self::default()));
+        match selection {
+            Selection::All => {}
+            Selection::Fields(_) if rest.is_empty() => *selection = Selection::All,
+            Selection::Fields(nested) => nested.select(rest),
+        }
+    }
+
+    /// Checks every selected path against `schema`, a tool's output schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ValidationError` naming the first path the schema
+    /// does not declare, and the fields declared where it goes wrong.
+    pub fn validate(&self, schema: &Value) -> Result<(), AppError> {
+        self.check(&[schema], schema, "")
+    }
+
+    fn check(&self, schemas: &[&Value], root: &Value, prefix: &str) -> Result<(), AppError> {
+        let candidates: Vec<&Value> = schemas
+            .iter()
+            .flat_map(|schema| expand(schema, root))
+            .collect();
+        if candidates.iter().any(|schema| is_open(schema)) {
+            return Ok(());
+        }
+        for (name, selection) in &self.0 {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}.{name}")
+            };
+            let matches: Vec<&Value> = candidates
+                .iter()
+                .filter_map(|schema| field_schema(schema, name))
+                .collect();
+            if matches.is_empty() {
+                let mut known: Vec<&str> = candidates
+                    .iter()
+                    .filter_map(|schema| schema.get("properties").and_then(Value::as_object))
+                    .flat_map(Map::keys)
+                    .map(String::as_str)
+                    .collect();
+                known.sort_unstable();
+                known.dedup();
+                let known = if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                };
+                return Err(AppError::ValidationError(format!(
+                    "Unknown response field `{path}`; available fields: {known}"
+                )));
+            }
+            if let Selection::Fields(nested) = selection {
+                nested.check(&matches, root, &path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the fields of `response` that are not selected.
+    pub fn apply(&self, response: &mut Value) {
+        match response {
+            Value::Object(fields) => fields.retain(|name, field| {
+                if name == META_KEY {
+                    return true;
+                }
+                match self.0.get(name) {
+                    None => false,
+                    Some(Selection::All) => true,
+                    Some(Selection::Fields(nested)) => {
+                        nested.apply(field);
+                        true
+                    }
+                }
+            }),
+            Value::Array(items) => {
+                for item in items {
+                    self.apply(item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Takes the `fields` argument out of `args` for a call to `tool`.
+///
+/// Returns `None` if the call selects no fields, or the tool declares its
+/// own `fields` argument.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the argument is malformed or
+/// selects a field the tool's output schema does not declare.
+pub fn take(tool: &ToolDescriptor, args: &mut Value) -> Result<Option<Projection>, AppError> {
+    let declared = (tool.input_schema)()
+        .get("properties")
+        .and_then(|properties| properties.get(FIELDS_ARG))
+        .is_some();
+    if declared {
+        return Ok(None);
+    }
+    let Some(fields) = args
+        .as_object_mut()
+        .and_then(|args| args.remove(FIELDS_ARG))
+    else {
+        return Ok(None);
+    };
+    let Some(projection) = Projection::parse(&fields)? else {
+        return Ok(None);
+    };
+
+    let versioned = args
+        .get(RESPONSE_VERSION_ARG)
+        .and_then(Value::as_str)
+        .and_then(ResponseVersion::parse)
+        .is_some_and(|version| version != ResponseVersion::default());
+    if !versioned {
+        let schema = (tool.output_schema)();
+        projection.validate(schema.as_value())?;
+    }
+    Ok(Some(projection))
+}
+
+fn invalid_fields() -> AppError {
+    AppError::ValidationError(
+        "`fields` must be a list of dot paths, e.g. [\"daily.temperature2mMax\"]".to_string(),
+    )
+}
+
+/// The object schemas `schema` stands for: references are resolved,
+/// alternatives expanded and lists stepped through.
+fn expand<'a>(schema: &'a Value, root: &'a Value) -> Vec<&'a Value> {
+    if let Some(target) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/$defs/"))
+        .and_then(|name| root.get("$defs")?.get(name))
+    {
+        return expand(target, root);
+    }
+    let mut expanded = Vec::new();
+    for keyword in ["anyOf", "oneOf", "allOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(Value::as_array) {
+            expanded.extend(branches.iter().flat_map(|branch| expand(branch, root)));
+        }
+    }
+    if let Some(items) = schema.get("items") {
+        expanded.extend(expand(items, root));
+    }
+    let describes_object = schema.get("properties").is_some()
+        || schema.get("additionalProperties").is_some()
+        || is_open(schema);
+    if expanded.is_empty() || describes_object {
+        expanded.push(schema);
+    }
+    expanded
+}
+
+/// Whether `schema` accepts any field, so paths below it cannot be checked.
+fn is_open(schema: &Value) -> bool {
+    match schema {
+        Value::Bool(open) => *open,
+        Value::Object(keywords) => {
+            keywords.get("additionalProperties") == Some(&Value::Bool(true))
+                || !keywords.keys().any(|keyword| {
+                    matches!(
+                        keyword.as_str(),
+                        "type"
+                            | "properties"
+                            | "additionalProperties"
+                            | "items"
+                            | "enum"
+                            | "const"
+                            | "$ref"
+                            | "anyOf"
+                            | "oneOf"
+                            | "allOf"
+                    )
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Schema of the field `name` in the object schema `schema`, if declared.
+fn field_schema<'a>(schema: &'a Value, name: &str) -> Option<&'a Value> {
+    schema
+        .get("properties")
+        .and_then(|properties| properties.get(name))
+        .or_else(|| {
+            schema
+                .get("additionalProperties")
+                .filter(|extra| extra.is_object())
+        })
+}
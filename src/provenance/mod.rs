@@ -0,0 +1,82 @@
+//! Where the data in each response came from.
+//!
+//! While a tool runs, the upstreams it reads from and the caches that answer
+//! in their place record themselves as sources, named `<provider>:<api>` for
+//! upstreams (e.g. `open-meteo:forecast`, `aws:cost-explorer`) and
+//! `cache:<name>` for caches (e.g. `cache:geocode`). Steps of composed tools
+//! record into the call that runs them. With `RESPONSE_SOURCES` enabled (the
+//! default), the response lists them under `_meta.sources` in the order they
+//! were first used, so agents can attribute the data and end users can see
+//! where an answer came from, for example:
+//!
+//! ```json
+//! {"_meta": {"sources": ["cache:geocode", "open-meteo:forecast"]}}
+//! ```
+//!
+//! Only successful reads are recorded: an upstream that failed while a
+//! cache answered instead is not listed. Responses answered from the session
+//! cache (see [`crate::memo`]) list `cache:session`, and responses without
+//! any source carry no list. Background refreshes of stale cache entries run
+//! outside the call and are not recorded.
+
+use crate::cache::CacheStore;
+use crate::metrics::META_KEY;
+use serde_json::{Map, Value, json};
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Key of the source list in a response's `_meta`
+pub const SOURCES_KEY: &str = "sources";
+
+/// Source of responses answered from the session cache
+pub const SESSION_CACHE_SOURCE: &str = "cache:session";
+
+tokio::task_local! {
+    static SOURCES: Arc<Mutex<Vec<String>>>;
+}
+
+/// Runs `future`, returning its output with the sources it recorded.
+pub async fn scope<F: Future>(future: F) -> (F::Output, Vec<String>) {
+    let sources = Arc::new(Mutex::new(Vec::new()));
+    let output = SOURCES.scope(Arc::clone(&sources), future).await;
+    let recorded = sources
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    (output, recorded)
+}
+
+/// Records that the running call used `source`.
+///
+/// Does nothing outside a [`scope`].
+pub fn record(source: &str) {
+    let _ = SOURCES.try_with(|sources| {
+        let mut sources = sources.lock().unwrap_or_else(PoisonError::into_inner);
+        if !sources.iter().any(|recorded| recorded == source) {
+            sources.push(source.to_string());
+        }
+    });
+}
+
+/// Records that `cache` answered for the running call.
+pub fn record_cache<V: Clone>(cache: &CacheStore<V>) {
+    record(&format!("cache:{}", cache.name()));
+}
+
+/// Attaches `sources` to an object response under `_meta.sources`.
+///
+/// Non-object responses, and empty source lists, are left out.
+pub fn attach(response: &mut Value, sources: &[String]) {
+    if sources.is_empty() {
+        return;
+    }
+    let Value::Object(fields) = response else {
+        return;
+    };
+    let meta = fields
+        .entry(META_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(meta) = meta {
+        meta.insert(SOURCES_KEY.to_string(), json!(sources));
+    }
+}
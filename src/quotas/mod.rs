@@ -0,0 +1,196 @@
+//! Daily and monthly call quotas per tenant.
+//!
+//! A tenant's overlay (see [`crate::tenants`]) may set a [`Quota`] on the
+//! calls its callers make to non-admin tools, per UTC day and calendar
+//! month, for example `"quota": {"daily": 500, "monthly": 10000}`. Before a
+//! call runs the routing layer compares the calls already counted for the
+//! tenant (see [`crate::usage`]) with each limit:
+//! - at or past `warn_percent` of a limit (80 by default) the call goes ahead,
+//!   is logged, and its response carries a `_meta.quota` warning
+//! - at the limit the call is refused with `QuotaExceeded`, which says when
+//!   the quota resets
+//!
+//! Each crossing is also announced once to the configured webhooks (see
+//! [`crate::webhooks`]).
+//!
+//! Counts trail other containers by up to one flush interval, so a busy
+//! tenant can overshoot a limit slightly before every container refuses it.
+//! A period whose count cannot be read is skipped, and the other periods
+//! are still checked, so an outage of the usage table does not take the
+//! tools down with it.
+
+use crate::admin::is_admin_tool;
+use crate::metrics::META_KEY;
+use crate::models::error::AppError;
+use crate::tenants::TenantSettings;
+use crate::usage;
+use crate::webhooks::{self, WebhookEvent};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveTime, Utc};
+use lambda_runtime::tracing::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// Percentage of a limit at which calls start carrying a warning, unless a
+/// quota sets its own
+pub const DEFAULT_WARN_PERCENT: u64 = 80;
+
+/// Most quota crossings remembered so each is announced once
+const MAX_ANNOUNCED: usize = 1024;
+
+/// Quota crossings already announced to the webhooks
+static ANNOUNCED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Limits on the calls a tenant's callers may make.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Quota {
+    /// Calls per UTC day
+    pub daily: Option<u64>,
+    /// Calls per calendar month, in UTC
+    pub monthly: Option<u64>,
+    /// Percentage of a limit from which calls carry a warning
+    pub warn_percent: u64,
+}
+
+impl Default for Quota {
+    fn default() -> Self {
+        Self {
+            daily: None,
+            monthly: None,
+            warn_percent: DEFAULT_WARN_PERCENT,
+        }
+    }
+}
+
+/// A quota the tenant is close to using up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaWarning {
+    /// "daily" or "monthly"
+    pub period: &'static str,
+    pub used: u64,
+    pub limit: u64,
+    /// When the count starts again (RFC 3339)
+    pub resets_at: String,
+}
+
+/// Checks `tenant`'s quota before a call to `tool`.
+///
+/// Returns the warning to attach to the response, if a limit is close.
+///
+/// # Errors
+///
+/// Returns `AppError::QuotaExceeded` if the tenant has used up its daily or
+/// monthly quota.
+pub async fn check(tenant: &TenantSettings, tool: &str) -> Result<Option<QuotaWarning>, AppError> {
+    let Some(quota) = tenant.quota else {
+        return Ok(None);
+    };
+    if is_admin_tool(tool) {
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    let limits = [
+        (
+            "daily",
+            quota.daily,
+            now.format("%Y-%m-%d").to_string(),
+            next_day(now),
+        ),
+        (
+            "monthly",
+            quota.monthly,
+            now.format("%Y-%m").to_string(),
+            next_month(now),
+        ),
+    ];
+    let mut warning = None;
+    for (period, limit, key, resets_at) in limits {
+        let Some(limit) = limit else {
+            continue;
+        };
+        let used = match usage::invocations(&tenant.tenant, &key).await {
+            Ok(used) => used,
+            Err(e) => {
+                warn!(tenant = %tenant.tenant, period, error = %e, "Skipping quota check");
+                continue;
+            }
+        };
+        let resets_at = resets_at.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        if used >= limit {
+            warn!(tenant = %tenant.tenant, tool, period, used, limit, "Quota exceeded");
+            announce(&tenant.tenant, period, used, limit, true, &resets_at);
+            return Err(AppError::QuotaExceeded {
+                period,
+                limit,
+                resets_at,
+            });
+        }
+        let close = used.saturating_mul(100) >= limit.saturating_mul(quota.warn_percent);
+        if close && warning.is_none() {
+            warn!(tenant = %tenant.tenant, tool, period, used, limit, "Quota nearly used up");
+            announce(&tenant.tenant, period, used, limit, false, &resets_at);
+            warning = Some(QuotaWarning {
+                period,
+                used,
+                limit,
+                resets_at,
+            });
+        }
+    }
+    Ok(warning)
+}
+
+/// Attaches `warning` to an object response under `_meta.quota`.
+///
+/// Non-object responses are left unchanged.
+pub fn attach_warning(response: &mut Value, warning: &QuotaWarning) {
+    let Value::Object(fields) = response else {
+        return;
+    };
+    let meta = fields
+        .entry(META_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(meta) = meta {
+        meta.insert("quota".to_string(), json!(warning));
+    }
+}
+
+/// Tells the configured webhooks `tenant` crossed a threshold of its
+/// `period` quota, once per threshold until the quota resets.
+fn announce(tenant: &str, period: &str, used: u64, limit: u64, exceeded: bool, resets_at: &str) {
+    let key = format!("{tenant}#{period}#{resets_at}#{exceeded}");
+    {
+        let mut announced = ANNOUNCED.lock().unwrap_or_else(PoisonError::into_inner);
+        if announced.len() >= MAX_ANNOUNCED {
+            announced.clear();
+        }
+        if !announced.insert(key) {
+            return;
+        }
+    }
+    webhooks::emit(WebhookEvent::QuotaThreshold {
+        tenant: tenant.to_string(),
+        period: period.to_string(),
+        used,
+        limit,
+        exceeded,
+        resets_at: resets_at.to_string(),
+    });
+}
+
+/// Start of the UTC day after `now`.
+fn next_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    let midnight = now.date_naive() + Days::new(1);
+    midnight.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Start of the UTC month after `now`.
+fn next_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .map_or(now, |first| first.and_time(NaiveTime::MIN).and_utc())
+}
@@ -0,0 +1,169 @@
+//! Registered tools as `AgentCore` Gateway API tool definitions.
+//!
+//! The `register-tools` binary pushes these to the gateway's Lambda target.
+//! They are built from the same published schemas as `tool_schema.json`
+//! (see [`bedrock_tool_schemas`]), but keep the nesting Terraform's copy
+//! flattens away. The API's schemas have a single type and no unions, so a
+//! nullable type becomes its primary type, a union of objects becomes one
+//! object with the properties of each (none of them required), any other
+//! union becomes its first variant, and a value of any type is described as
+//! an object.
+
+use super::bedrock_tool_schemas;
+use anyhow::{Context, Result};
+use aws_sdk_bedrockagentcorecontrol::types::{
+    McpLambdaTargetConfiguration, McpTargetConfiguration, SchemaDefinition, SchemaType,
+    TargetConfiguration, ToolDefinition, ToolSchema,
+};
+use serde_json::{Map, Value, json};
+
+/// Keywords holding the variants of a union
+const UNION_KEYWORDS: [&str; 2] = ["anyOf", "oneOf"];
+
+/// The definition of every registered tool.
+///
+/// # Errors
+///
+/// Returns an error if a tool's schema cannot be generated or converted.
+pub fn tool_definitions() -> Result<Vec<ToolDefinition>> {
+    bedrock_tool_schemas()?
+        .iter()
+        .map(tool_definition)
+        .collect()
+}
+
+/// The definition of the tool with the published `schema`.
+///
+/// # Errors
+///
+/// Returns an error if the schema has no name, description or input schema.
+pub fn tool_definition(schema: &Value) -> Result<ToolDefinition> {
+    let name = schema
+        .get("name")
+        .and_then(Value::as_str)
+        .context("Tool schema has no name")?;
+    let description = schema
+        .get("description")
+        .and_then(Value::as_str)
+        .with_context(|| format!("{name} has no description"))?;
+    let input = schema
+        .get("inputSchema")
+        .with_context(|| format!("{name} has no input schema"))?;
+    Ok(ToolDefinition::builder()
+        .name(name)
+        .description(description)
+        .input_schema(schema_definition(input)?)
+        .set_output_schema(
+            schema
+                .get("outputSchema")
+                .map(schema_definition)
+                .transpose()?,
+        )
+        .build()?)
+}
+
+/// `schema` as the API describes a value.
+///
+/// # Errors
+///
+/// Returns an error if a definition is incomplete.
+pub fn schema_definition(schema: &Value) -> Result<SchemaDefinition> {
+    let variants: Vec<&Value> = UNION_KEYWORDS
+        .iter()
+        .filter_map(|keyword| schema.get(*keyword).and_then(Value::as_array))
+        .flatten()
+        .filter(|variant| schema_type(variant) != Some("null"))
+        .collect();
+    if variants.is_empty() {
+        return definition(schema, schema.get("description"));
+    }
+    if variants.len() > 1 && variants.iter().all(|v| schema_type(v) == Some("object")) {
+        let mut properties = Map::new();
+        for variant in &variants {
+            if let Some(Value::Object(variant)) = variant.get("properties") {
+                for (name, property) in variant {
+                    properties
+                        .entry(name.clone())
+                        .or_insert_with(|| property.clone());
+                }
+            }
+        }
+        let merged = json!({"type": "object", "properties": properties});
+        return definition(&merged, schema.get("description"));
+    }
+    let first = variants[0];
+    let description = schema
+        .get("description")
+        .or_else(|| first.get("description"));
+    definition(first, description)
+}
+
+/// A target configuration invoking `lambda_arn` for `tools`.
+///
+/// # Errors
+///
+/// Returns an error if the configuration is incomplete.
+pub fn target_configuration(
+    lambda_arn: &str,
+    tools: Vec<ToolDefinition>,
+) -> Result<TargetConfiguration> {
+    let lambda = McpLambdaTargetConfiguration::builder()
+        .lambda_arn(lambda_arn)
+        .tool_schema(ToolSchema::InlinePayload(tools))
+        .build()?;
+    Ok(TargetConfiguration::Mcp(McpTargetConfiguration::Lambda(
+        lambda,
+    )))
+}
+
+/// The Lambda a target configuration invokes, if it is a Lambda target.
+#[must_use]
+pub fn target_lambda_arn(configuration: &TargetConfiguration) -> Option<&str> {
+    match configuration {
+        TargetConfiguration::Mcp(McpTargetConfiguration::Lambda(lambda)) => {
+            Some(lambda.lambda_arn())
+        }
+        _ => None,
+    }
+}
+
+fn definition(schema: &Value, description: Option<&Value>) -> Result<SchemaDefinition> {
+    let kind = match schema_type(schema) {
+        Some("string") => SchemaType::String,
+        Some("number") => SchemaType::Number,
+        Some("integer") => SchemaType::Integer,
+        Some("boolean") => SchemaType::Boolean,
+        Some("array") => SchemaType::Array,
+        _ => SchemaType::Object,
+    };
+    let mut definition = SchemaDefinition::builder()
+        .r#type(kind)
+        .set_description(description.and_then(Value::as_str).map(String::from));
+    if let Some(Value::Object(properties)) = schema.get("properties") {
+        for (name, property) in properties {
+            definition = definition.properties(name, schema_definition(property)?);
+        }
+    }
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            definition = definition.required(name);
+        }
+    }
+    if let Some(items) = schema.get("items") {
+        definition = definition.items(schema_definition(items)?);
+    }
+    Ok(definition.build()?)
+}
+
+/// The primary type of `schema`: its type, or the first that is not null.
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type")? {
+        Value::String(kind) => Some(kind),
+        Value::Array(kinds) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|kind| *kind != "null")
+            .or(Some("null")),
+        _ => None,
+    }
+}
@@ -0,0 +1,79 @@
+//! Deprecation lifecycle of registered tools.
+//!
+//! A tool being retired keeps working until its sunset date, but is marked
+//! as deprecated wherever agents discover it: its published description
+//! gains a notice and its schema carries `annotations`. Each call to it logs
+//! a warning so remaining callers can be found, and with
+//! `DEPRECATION_NOTICES` enabled the notice is also returned in the
+//! response's `_meta`.
+
+use crate::metrics::META_KEY;
+use lambda_runtime::tracing::warn;
+use serde_json::{Map, Value, json};
+
+/// Retirement plan for a deprecated tool.
+#[derive(Debug, Clone, Copy)]
+pub struct Deprecation {
+    /// Date after which the tool may be removed, as `YYYY-MM-DD`
+    pub sunset: &'static str,
+    /// Tool callers should move to, if any
+    pub replacement: Option<&'static str>,
+}
+
+impl Deprecation {
+    /// Human-readable notice for callers of `tool`.
+    #[must_use]
+    pub fn notice(&self, tool: &str) -> String {
+        let replacement = self
+            .replacement
+            .map(|replacement| format!(" Use {replacement} instead."))
+            .unwrap_or_default();
+        format!(
+            "{tool} is deprecated and will be removed after {}.{replacement}",
+            self.sunset
+        )
+    }
+
+    /// Annotations published with the tool's schema.
+    #[must_use]
+    pub fn annotations(&self) -> Value {
+        let mut annotations = json!({
+            "deprecated": true,
+            "sunsetDate": self.sunset,
+        });
+        if let (Some(replacement), Some(fields)) = (self.replacement, annotations.as_object_mut()) {
+            fields.insert("replacement".to_string(), json!(replacement));
+        }
+        annotations
+    }
+}
+
+/// Logs a call to a deprecated tool.
+pub fn warn_call(tool: &str, deprecation: &Deprecation) {
+    warn!(
+        tool,
+        sunset = deprecation.sunset,
+        replacement = deprecation.replacement.unwrap_or_default(),
+        "Deprecated tool called"
+    );
+}
+
+/// Adds the deprecation notice to an object response under
+/// `_meta.deprecation`, keeping any other metadata.
+///
+/// Non-object responses are left unchanged.
+pub fn attach_notice(response: &mut Value, tool: &str, deprecation: &Deprecation) {
+    let Value::Object(fields) = response else {
+        return;
+    };
+    let meta = fields
+        .entry(META_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(meta) = meta {
+        let mut notice = deprecation.annotations();
+        if let Some(notice) = notice.as_object_mut() {
+            notice.insert("message".to_string(), json!(deprecation.notice(tool)));
+        }
+        meta.insert("deprecation".to_string(), notice);
+    }
+}
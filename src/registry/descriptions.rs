@@ -0,0 +1,84 @@
+//! Hand-written descriptions of tool input properties.
+//!
+//! Request structs document their fields with doc comments, which schemars
+//! captures as descriptions. Where a field needs more guidance for the
+//! model than belongs in the code, `descriptions.toml` overrides or extends
+//! it; the file is embedded at build time and merged when schemas are
+//! published, so `tool_schema.json` always reflects it.
+
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Description overrides per tool and property path
+const DESCRIPTIONS_TOML: &str = include_str!("descriptions.toml");
+
+/// Overrides per tool, keyed by property path.
+pub type DescriptionOverrides = HashMap<String, HashMap<String, DescriptionOverride>>;
+
+/// A hand-written description of one property.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum DescriptionOverride {
+    /// Replaces the doc comment
+    Replace(String),
+    /// Follows the doc comment
+    Append { append: String },
+}
+
+static OVERRIDES: LazyLock<Result<DescriptionOverrides, String>> = LazyLock::new(|| {
+    toml::from_str(DESCRIPTIONS_TOML).map_err(|e| format!("Invalid descriptions.toml: {e}"))
+});
+
+/// The embedded description overrides.
+///
+/// # Errors
+///
+/// Returns an error if `descriptions.toml` cannot be parsed.
+pub fn description_overrides() -> Result<&'static DescriptionOverrides, String> {
+    OVERRIDES.as_ref().map_err(Clone::clone)
+}
+
+/// Merges the overrides for `tool` into its input schema.
+///
+/// # Errors
+///
+/// Returns an error if the overrides cannot be parsed or name a property
+/// the schema does not have.
+pub fn apply(tool: &str, schema: &mut Value) -> Result<(), String> {
+    let Some(overrides) = description_overrides()?.get(tool) else {
+        return Ok(());
+    };
+    for (path, description) in overrides {
+        let property = find_property(schema, path)
+            .ok_or_else(|| format!("descriptions.toml: {tool} has no input property {path}"))?;
+        let merged = match description {
+            DescriptionOverride::Replace(text) => text.clone(),
+            DescriptionOverride::Append { append } => {
+                match property.get("description").and_then(Value::as_str) {
+                    Some(doc) if !doc.trim().is_empty() => {
+                        let doc = doc.trim_end();
+                        let separator = if doc.ends_with('.') { " " } else { ". " };
+                        format!("{doc}{separator}{append}")
+                    }
+                    _ => append.clone(),
+                }
+            }
+        };
+        property.insert("description".to_string(), json!(merged));
+    }
+    Ok(())
+}
+
+// Follows a dotted path through `properties`, and `items` of array properties
+fn find_property<'a>(schema: &'a mut Value, path: &str) -> Option<&'a mut Map<String, Value>> {
+    let mut current = schema;
+    for segment in path.split('.') {
+        if current.get("properties").is_none() {
+            current = current.get_mut("items")?;
+        }
+        current = current.get_mut("properties")?.get_mut(segment)?;
+    }
+    current.as_object_mut()
+}
@@ -0,0 +1,96 @@
+//! Example invocations of registered tools.
+//!
+//! Examples show agents what a call looks like: the arguments and what the
+//! tool returns for them. They are published in the tool's schema as
+//! `examples` and appended to its description, for clients that only read
+//! descriptions. An example's output may leave out fields and trailing list
+//! items of the real response, so it stays short; [`ToolExample::matches`]
+//! checks that the rest agrees with what the tool actually returns.
+
+use serde_json::{Value, json};
+
+/// A call to a tool and what it returns.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolExample {
+    /// What the call does, e.g. "Weather in Sydney"
+    pub description: &'static str,
+    /// Tool arguments, as JSON
+    pub input: &'static str,
+    /// Response, as JSON, possibly abridged
+    pub output: &'static str,
+}
+
+impl ToolExample {
+    /// Parsed tool arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `input` is not valid JSON.
+    pub fn input(&self) -> serde_json::Result<Value> {
+        serde_json::from_str(self.input)
+    }
+
+    /// Parsed response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `output` is not valid JSON.
+    pub fn output(&self) -> serde_json::Result<Value> {
+        serde_json::from_str(self.output)
+    }
+
+    /// The example as published in a tool's `examples`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input or output is not valid JSON.
+    pub fn published(&self) -> serde_json::Result<Value> {
+        Ok(json!({
+            "description": self.description,
+            "input": self.input()?,
+            "output": self.output()?,
+        }))
+    }
+
+    /// The example as a sentence for a tool's description.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input or output is not valid JSON.
+    pub fn sentence(&self) -> serde_json::Result<String> {
+        Ok(format!(
+            "Example ({}): {} returns {}.",
+            self.description,
+            serde_json::to_string(&self.input()?)?,
+            serde_json::to_string(&self.output()?)?
+        ))
+    }
+
+    /// Whether `actual`, a response of the tool to this example's input,
+    /// agrees with the example's output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `output` is not valid JSON.
+    pub fn matches(&self, actual: &Value) -> serde_json::Result<bool> {
+        Ok(contains(actual, &self.output()?))
+    }
+}
+
+/// Whether `actual` holds every field of `expected` and starts with every
+/// list item of it, recursively.
+fn contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|found| contains(found, value))),
+        (Value::Array(actual), Value::Array(expected)) => {
+            expected.len() <= actual.len()
+                && actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(found, value)| contains(found, value))
+        }
+        _ => actual == expected,
+    }
+}
@@ -0,0 +1,90 @@
+//! Conversion of registered tools into Amazon Bedrock `AgentCore` schemas.
+//!
+//! The gateway accepts a subset of JSON Schema, so generated schemas are
+//! flattened: `$defs` references are inlined, `format` and `title` are
+//! dropped, nullable types are reduced to their primary type, and arguments
+//! injected by the interceptor are hidden from callers. The gateway ignores
+//! constraint keywords such as `minimum`, `maxLength` and `enum`, so those
+//! on input properties are also spelled out in the property's description,
+//! letting the model see valid ranges and values. Deprecated tools carry an
+//! `annotations` object, example calls are listed in `examples`, and tools
+//! returning third-party data credit its providers in `attribution` (see
+//! [`crate::registry::Provider`]).
+
+use super::passes::{self, Pass};
+use super::{SchemaExporter, description, input_schema, output_schema};
+use crate::registry::{ToolDescriptor, ToolExample, attributions, tools};
+use serde_json::{Value, json};
+
+/// Cleaning rules for the gateway's subset of JSON Schema
+const PASSES: &[Pass] = &[
+    passes::strip_meta_keywords,
+    passes::inline_definitions,
+    passes::hide_injected_arguments,
+    passes::drop_formats,
+    passes::collapse_nullable_types,
+];
+
+/// Exports tools as Amazon Bedrock `AgentCore` gateway target schemas.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BedrockExporter;
+
+impl SchemaExporter for BedrockExporter {
+    fn name(&self) -> &'static str {
+        "bedrock"
+    }
+
+    fn file_name(&self) -> &'static str {
+        "tool_schema.json"
+    }
+
+    fn tool(&self, descriptor: &ToolDescriptor) -> serde_json::Result<Value> {
+        let mut input_schema = input_schema(descriptor, PASSES)?;
+        passes::describe_constraints(&mut input_schema);
+
+        let mut tool = json!({
+            "name": descriptor.name,
+            "description": description(descriptor)?,
+            "inputSchema": input_schema,
+            "outputSchema": output_schema(descriptor, PASSES)?,
+        });
+        if let Some(fields) = tool.as_object_mut() {
+            if !descriptor.examples.is_empty() {
+                let examples = descriptor
+                    .examples
+                    .iter()
+                    .map(ToolExample::published)
+                    .collect::<serde_json::Result<Vec<_>>>()?;
+                fields.insert("examples".to_string(), Value::Array(examples));
+            }
+            if let Some(deprecation) = &descriptor.deprecation {
+                fields.insert("annotations".to_string(), deprecation.annotations());
+            }
+            if !descriptor.providers.is_empty() {
+                fields.insert(
+                    "attribution".to_string(),
+                    attributions(descriptor.providers),
+                );
+            }
+        }
+        Ok(tool)
+    }
+}
+
+/// Published schema of every registered tool, as written to `tool_schema.json`.
+///
+/// # Errors
+///
+/// Returns an error if a generated schema cannot be serialized.
+pub fn bedrock_tool_schemas() -> serde_json::Result<Vec<Value>> {
+    tools().iter().map(bedrock_tool_schema).collect()
+}
+
+/// Published schema of a single tool.
+///
+/// # Errors
+///
+/// Returns an error if a generated schema cannot be serialized.
+pub fn bedrock_tool_schema(descriptor: &ToolDescriptor) -> serde_json::Result<Value> {
+    BedrockExporter.tool(descriptor)
+}
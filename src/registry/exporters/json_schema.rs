@@ -0,0 +1,38 @@
+//! Conversion of registered tools into standalone JSON Schema draft 2020-12.
+//!
+//! For validators and code generators rather than agents: schemas keep the
+//! `$schema` and `title` keywords schemars generates, formats, nullable
+//! types and constraint keywords. `$defs` are inlined like for the other
+//! targets, so that hand-written descriptions can reach nested properties
+//! and each schema stands alone.
+
+use super::passes::{self, Pass};
+use super::{SchemaExporter, description, input_schema, output_schema};
+use crate::registry::ToolDescriptor;
+use serde_json::{Value, json};
+
+/// Cleaning rules for standalone schemas
+const PASSES: &[Pass] = &[passes::inline_definitions, passes::hide_injected_arguments];
+
+/// Exports each tool's input and output as JSON Schema draft 2020-12.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSchemaExporter;
+
+impl SchemaExporter for JsonSchemaExporter {
+    fn name(&self) -> &'static str {
+        "json-schema"
+    }
+
+    fn file_name(&self) -> &'static str {
+        "tool_schema.2020-12.json"
+    }
+
+    fn tool(&self, descriptor: &ToolDescriptor) -> serde_json::Result<Value> {
+        Ok(json!({
+            "name": descriptor.name,
+            "description": description(descriptor)?,
+            "input": input_schema(descriptor, PASSES)?,
+            "output": output_schema(descriptor, PASSES)?,
+        }))
+    }
+}
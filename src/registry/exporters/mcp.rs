@@ -0,0 +1,82 @@
+//! Conversion of registered tools into MCP `tools/list` entries.
+//!
+//! MCP clients read full JSON Schema, so formats, nullable types and
+//! constraint keywords are kept; `$defs` are still inlined, since not every
+//! client resolves references. Behavior hints go in `annotations`
+//! (`readOnlyHint` for tools without side effects, `destructiveHint` for
+//! destructive ones), and what MCP has no field for, such as example calls,
+//! the deprecation notice and provider attribution, in `_meta`.
+
+use super::passes::{self, Pass};
+use super::{SchemaExporter, description, input_schema, output_schema};
+use crate::metrics::META_KEY;
+use crate::registry::{ToolDescriptor, ToolExample, attributions};
+use serde_json::{Map, Value, json};
+
+/// Cleaning rules for MCP clients
+const PASSES: &[Pass] = &[
+    passes::strip_meta_keywords,
+    passes::inline_definitions,
+    passes::hide_injected_arguments,
+];
+
+/// Exports tools as the entries of an MCP `tools/list` result.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct McpExporter;
+
+impl SchemaExporter for McpExporter {
+    fn name(&self) -> &'static str {
+        "mcp"
+    }
+
+    fn file_name(&self) -> &'static str {
+        "mcp_tools.json"
+    }
+
+    fn tool(&self, descriptor: &ToolDescriptor) -> serde_json::Result<Value> {
+        let mut meta = Map::new();
+        if !descriptor.examples.is_empty() {
+            let examples = descriptor
+                .examples
+                .iter()
+                .map(ToolExample::published)
+                .collect::<serde_json::Result<Vec<_>>>()?;
+            meta.insert("examples".to_string(), Value::Array(examples));
+        }
+        if let Some(deprecation) = &descriptor.deprecation {
+            meta.insert("deprecation".to_string(), deprecation.annotations());
+        }
+        if !descriptor.providers.is_empty() {
+            meta.insert(
+                "attribution".to_string(),
+                attributions(descriptor.providers),
+            );
+        }
+
+        let mut tool = json!({
+            "name": descriptor.name,
+            "description": description(descriptor)?,
+            "inputSchema": input_schema(descriptor, PASSES)?,
+            "outputSchema": output_schema(descriptor, PASSES)?,
+            "annotations": {
+                "readOnlyHint": !descriptor.side_effects(),
+                "destructiveHint": descriptor.destructive(),
+            },
+        });
+        if !meta.is_empty()
+            && let Some(fields) = tool.as_object_mut()
+        {
+            fields.insert(META_KEY.to_string(), Value::Object(meta));
+        }
+        Ok(tool)
+    }
+
+    /// The result of `tools/list`, listing every tool on one page.
+    fn document(&self, tools: &[ToolDescriptor]) -> serde_json::Result<Value> {
+        let tools = tools
+            .iter()
+            .map(|descriptor| self.tool(descriptor))
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        Ok(json!({"tools": tools}))
+    }
+}
@@ -0,0 +1,230 @@
+//! Export of registered tools into the schema formats of each target.
+//!
+//! Tools are described once, in the registry. Each target format has a
+//! [`SchemaExporter`], which turns a tool's generated schemas into the shape
+//! the target accepts: Amazon Bedrock `AgentCore` (`tool_schema.json`, see
+//! [`BedrockExporter`]), MCP `tools/list` entries ([`McpExporter`]), an
+//! `OpenAPI` 3.1 document ([`OpenApiExporter`]) and standalone JSON Schema
+//! draft 2020-12 ([`JsonSchemaExporter`]). The cleaning rules targets share,
+//! such as inlining `$defs` or hiding the arguments the interceptor injects,
+//! are [`passes`] that each exporter composes, so a new target picks the
+//! passes it needs instead of forking the cleaning code. Every schema is
+//! normalized last (see [`passes::normalize`]), and documents are rendered
+//! by [`render`], so regenerating them from unchanged types gives the same
+//! bytes and diffs between runs only show real changes.
+//!
+//! Whatever the target, hand-written property descriptions from
+//! `descriptions.toml` are merged over the doc comments, and every tool
+//! advertises the `fields` argument that selects response fields (see
+//! [`crate::projection`]), tools that return a table the `output_format`
+//! argument (see [`crate::export`]), tools with side effects the `dry_run`
+//! argument (see [`crate::dry_run`]), and destructive tools the
+//! `confirmation_token` argument. Descriptions note pagination and how to
+//! confirm calls (see [`crate::confirmation`]), summarize example calls, and
+//! lead with the notice of deprecated tools.
+
+mod bedrock;
+mod json_schema;
+mod mcp;
+mod openapi;
+pub mod passes;
+
+pub use bedrock::{BedrockExporter, bedrock_tool_schema, bedrock_tool_schemas};
+pub use json_schema::JsonSchemaExporter;
+pub use mcp::McpExporter;
+pub use openapi::OpenApiExporter;
+
+use super::{ToolDescriptor, descriptions};
+use crate::confirmation::CONFIRMATION_TOKEN_ARG;
+use crate::dry_run::DRY_RUN_ARG;
+use crate::export::{OUTPUT_FORMAT_ARG, OutputFormat};
+use crate::projection::FIELDS_ARG;
+use passes::Pass;
+use serde::de::Error;
+use serde_json::{Value, json, to_string_pretty, to_value};
+
+/// Appended to the description of tools that support cursor pagination
+const PAGINATION_NOTE: &str =
+    " Results are paginated: pass the returned next_cursor as cursor to fetch the next page.";
+
+/// Appended to the description of tools that only act once confirmed
+const CONFIRMATION_NOTE: &str = " Calls must be confirmed: a call without confirmation_token does nothing and returns _meta.confirmation; show its summary to the user and, once they agree, repeat the call with the same arguments and its token as confirmation_token.";
+
+/// Description of the `confirmation_token` argument added to destructive tools
+const CONFIRMATION_TOKEN_DESCRIPTION: &str = "Token from _meta.confirmation of an unconfirmed call with the same arguments, confirming that the user agreed to it";
+
+/// Description of the `output_format` argument added to tools that return a table
+const OUTPUT_FORMAT_DESCRIPTION: &str = "How to return the response rows: json (default), or csv or ndjson to return them as a single string for analytics";
+
+/// Description of the `dry_run` argument added to tools with side effects
+const DRY_RUN_DESCRIPTION: &str =
+    "Check the call and return a plan of what it would do without doing it (default: false)";
+
+/// Description of the `fields` argument added to every tool
+const FIELDS_DESCRIPTION: &str = "Response fields to return, as dot paths into the output schema such as \"location.name\"; lists are stepped through (default: every field)";
+
+/// Every exporter, in the order the schema generator lists them
+pub static EXPORTERS: [&dyn SchemaExporter; 4] = [
+    &BedrockExporter,
+    &McpExporter,
+    &OpenApiExporter,
+    &JsonSchemaExporter,
+];
+
+/// Publishes registered tools in one target's schema format.
+pub trait SchemaExporter: Sync {
+    /// Name the exporter is selected by, e.g. "openapi"
+    fn name(&self) -> &'static str;
+
+    /// File the schema generator writes the document to.
+    fn file_name(&self) -> &'static str;
+
+    /// Published form of a single tool.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a generated schema cannot be serialized.
+    fn tool(&self, descriptor: &ToolDescriptor) -> serde_json::Result<Value>;
+
+    /// Document publishing `tools`; by default, the list of their published
+    /// forms.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a generated schema cannot be serialized.
+    fn document(&self, tools: &[ToolDescriptor]) -> serde_json::Result<Value> {
+        tools
+            .iter()
+            .map(|descriptor| self.tool(descriptor))
+            .collect::<serde_json::Result<Vec<_>>>()
+            .map(Value::Array)
+    }
+}
+
+/// The exporter named `name`, if there is one.
+#[must_use]
+pub fn exporter(name: &str) -> Option<&'static dyn SchemaExporter> {
+    EXPORTERS
+        .iter()
+        .copied()
+        .find(|exporter| exporter.name() == name)
+}
+
+/// Renders a document as written to its file: pretty-printed and ending
+/// with a newline.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be serialized.
+pub fn render(document: &Value) -> serde_json::Result<String> {
+    let mut json = to_string_pretty(document)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// The description of a tool as published, with its notes and examples.
+fn description(descriptor: &ToolDescriptor) -> serde_json::Result<String> {
+    let mut description = descriptor.description.to_string();
+    if descriptor.paginated {
+        description.push_str(PAGINATION_NOTE);
+    }
+    if descriptor.destructive() {
+        description.push_str(CONFIRMATION_NOTE);
+    }
+    for example in descriptor.examples {
+        description.push(' ');
+        description.push_str(&example.sentence()?);
+    }
+    if let Some(deprecation) = &descriptor.deprecation {
+        description = format!(
+            "DEPRECATED: {} {description}",
+            deprecation.notice(descriptor.name)
+        );
+    }
+    Ok(description)
+}
+
+/// The input schema of a tool after `passes`, with hand-written descriptions
+/// merged and the server's own arguments declared, normalized.
+///
+/// `passes` must inline `$defs`, so the descriptions can find nested
+/// properties.
+fn input_schema(descriptor: &ToolDescriptor, passes: &[Pass]) -> serde_json::Result<Value> {
+    let mut schema = to_value((descriptor.input_schema)())?;
+    passes::apply(&mut schema, passes);
+    descriptions::apply(descriptor.name, &mut schema).map_err(Error::custom)?;
+    add_fields_argument(&mut schema);
+    if !descriptor.rows.is_empty() {
+        add_output_format_argument(&mut schema);
+    }
+    if descriptor.side_effects() {
+        add_dry_run_argument(&mut schema);
+    }
+    if descriptor.destructive() {
+        add_confirmation_token_argument(&mut schema);
+    }
+    passes::normalize(&mut schema);
+    Ok(schema)
+}
+
+/// The output schema of a tool after `passes`, normalized.
+fn output_schema(descriptor: &ToolDescriptor, passes: &[Pass]) -> serde_json::Result<Value> {
+    let mut schema = to_value((descriptor.output_schema)())?;
+    passes::apply(&mut schema, passes);
+    passes::normalize(&mut schema);
+    Ok(schema)
+}
+
+// Declares the projection argument on tools that do not use the name themselves
+fn add_fields_argument(schema: &mut Value) {
+    if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.entry(FIELDS_ARG).or_insert_with(|| {
+            json!({
+                "description": FIELDS_DESCRIPTION,
+                "items": {"type": "string"},
+                "type": "array",
+            })
+        });
+    }
+}
+
+// Declares the export argument on tools that return a table
+fn add_output_format_argument(schema: &mut Value) {
+    if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        let formats: Vec<&str> = OutputFormat::ALL
+            .into_iter()
+            .map(OutputFormat::as_str)
+            .collect();
+        properties.entry(OUTPUT_FORMAT_ARG).or_insert_with(|| {
+            json!({
+                "description": OUTPUT_FORMAT_DESCRIPTION,
+                "enum": formats,
+                "type": "string",
+            })
+        });
+    }
+}
+
+// Declares the dry run argument on tools with side effects
+fn add_dry_run_argument(schema: &mut Value) {
+    if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.entry(DRY_RUN_ARG).or_insert_with(|| {
+            json!({
+                "description": DRY_RUN_DESCRIPTION,
+                "type": "boolean",
+            })
+        });
+    }
+}
+
+// Declares the confirmation token argument on destructive tools
+fn add_confirmation_token_argument(schema: &mut Value) {
+    if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.entry(CONFIRMATION_TOKEN_ARG).or_insert_with(|| {
+            json!({
+                "description": CONFIRMATION_TOKEN_DESCRIPTION,
+                "type": "string",
+            })
+        });
+    }
+}
@@ -0,0 +1,93 @@
+//! Conversion of registered tools into an `OpenAPI` 3.1 document.
+//!
+//! Each tool is a `POST /<tool name>` operation whose `operationId` is the
+//! tool name, taking its arguments as the JSON request body and returning its
+//! response, the layout `OpenAPI`-based agent frameworks such as Amazon
+//! Bedrock Agents action groups expect. `OpenAPI` 3.1 schemas are JSON Schema
+//! draft 2020-12, so formats, nullable types and constraint keywords are
+//! kept; `$defs` are inlined, since they are only valid under `components`.
+//! Deprecated tools are marked `deprecated`, and example calls and provider
+//! attribution go in the `x-examples` and `x-attribution` extensions.
+
+use super::passes::{self, Pass};
+use super::{SchemaExporter, description, input_schema, output_schema};
+use crate::registry::{ToolDescriptor, ToolExample, attributions};
+use serde_json::{Map, Value, json};
+
+/// `OpenAPI` version of the document
+const OPENAPI_VERSION: &str = "3.1.0";
+
+/// Cleaning rules for `OpenAPI` schema objects
+const PASSES: &[Pass] = &[
+    passes::strip_meta_keywords,
+    passes::inline_definitions,
+    passes::hide_injected_arguments,
+];
+
+/// Exports tools as the operations of an `OpenAPI` 3.1 document.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenApiExporter;
+
+impl SchemaExporter for OpenApiExporter {
+    fn name(&self) -> &'static str {
+        "openapi"
+    }
+
+    fn file_name(&self) -> &'static str {
+        "openapi.json"
+    }
+
+    /// The path item of a tool's operation.
+    fn tool(&self, descriptor: &ToolDescriptor) -> serde_json::Result<Value> {
+        let mut operation = json!({
+            "operationId": descriptor.name,
+            "description": description(descriptor)?,
+            "requestBody": {
+                "required": true,
+                "content": {"application/json": {"schema": input_schema(descriptor, PASSES)?}},
+            },
+            "responses": {
+                "200": {
+                    "description": "The tool's response",
+                    "content": {"application/json": {"schema": output_schema(descriptor, PASSES)?}},
+                },
+            },
+        });
+        if let Some(fields) = operation.as_object_mut() {
+            if descriptor.deprecation.is_some() {
+                fields.insert("deprecated".to_string(), json!(true));
+            }
+            if !descriptor.examples.is_empty() {
+                let examples = descriptor
+                    .examples
+                    .iter()
+                    .map(ToolExample::published)
+                    .collect::<serde_json::Result<Vec<_>>>()?;
+                fields.insert("x-examples".to_string(), Value::Array(examples));
+            }
+            if !descriptor.providers.is_empty() {
+                fields.insert(
+                    "x-attribution".to_string(),
+                    attributions(descriptor.providers),
+                );
+            }
+        }
+        Ok(json!({"post": operation}))
+    }
+
+    /// The document, with one path per tool.
+    fn document(&self, tools: &[ToolDescriptor]) -> serde_json::Result<Value> {
+        let mut paths = Map::new();
+        for descriptor in tools {
+            paths.insert(format!("/{}", descriptor.name), self.tool(descriptor)?);
+        }
+        Ok(json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {
+                "title": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": paths,
+        }))
+    }
+}
@@ -0,0 +1,283 @@
+//! Cleaning rules shared by the schema exporters.
+//!
+//! Each pass rewrites a schema generated by schemars in place, and an
+//! exporter lists the passes its target needs (see [`apply`]). Passes only
+//! look at the schema they are given, so they compose in any order, except
+//! that [`inline_definitions`] must come before any pass that follows
+//! properties into their definitions.
+
+use crate::identity::{USER_ID_ARG, USER_NAME_ARG};
+use serde_json::{Map, Value, json};
+
+/// A rewrite of a generated schema.
+pub type Pass = fn(&mut Value);
+
+/// Arguments the interceptor injects, which callers never pass
+const INJECTED_ARGS: [&str; 2] = [USER_ID_ARG, USER_NAME_ARG];
+
+/// Runs `passes` over `schema` in order.
+pub fn apply(schema: &mut Value, passes: &[Pass]) {
+    for pass in passes {
+        pass(schema);
+    }
+}
+
+/// Removes the top-level `$schema` and `title` keywords.
+pub fn strip_meta_keywords(schema: &mut Value) {
+    if let Some(obj) = schema.as_object_mut() {
+        obj.remove("$schema");
+        obj.remove("title");
+    }
+}
+
+/// Replaces `$ref` references into `$defs` with the definitions themselves,
+/// and removes `$defs`.
+///
+/// Properties referring to an enum definition are typed as strings.
+pub fn inline_definitions(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    if let Some(defs) = obj.remove("$defs")
+        && let Some(properties) = obj.get_mut("properties").and_then(|p| p.as_object_mut())
+    {
+        for (_prop_name, prop_value) in properties.iter_mut() {
+            if let Some(prop_obj) = prop_value.as_object_mut()
+                && let Some(Value::String(ref_path)) = prop_obj.get("$ref")
+                && let Some(def_name) = ref_path.strip_prefix("#/$defs/")
+                && let Some(def_value) = defs.get(def_name)
+            {
+                // Inline the definition instead of keeping the reference
+                if let Some(def_obj) = def_value.as_object() {
+                    prop_obj.clear();
+                    prop_obj.extend(def_obj.clone());
+                }
+
+                // Convert enums to string type for Amazon Bedrock compatibility
+                if def_value.get("enum").is_some() {
+                    prop_obj.insert("type".to_string(), json!("string"));
+                }
+            }
+
+            // Inline references nested deeper, e.g. array items
+            inline_nested_refs(prop_value, &defs);
+        }
+    }
+}
+
+/// Removes the arguments injected by the interceptor from `properties` and
+/// `required`, since callers do not pass them.
+pub fn hide_injected_arguments(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    if let Some(properties) = obj.get_mut("properties").and_then(Value::as_object_mut) {
+        for name in INJECTED_ARGS {
+            properties.remove(name);
+        }
+    }
+    if let Some(required) = obj.get_mut("required").and_then(Value::as_array_mut) {
+        required.retain(|item| !INJECTED_ARGS.iter().any(|name| item == name));
+    }
+}
+
+/// Removes the `format` keyword of each top-level property.
+pub fn drop_formats(schema: &mut Value) {
+    for property in top_level_properties(schema) {
+        property.remove("format");
+    }
+}
+
+/// Reduces nullable property types such as `["string", "null"]` to their
+/// primary type.
+pub fn collapse_nullable_types(schema: &mut Value) {
+    for property in top_level_properties(schema) {
+        if let Some(type_value) = property.get("type")
+            && let Some(type_array) = type_value.as_array()
+            && type_array.len() == 2
+            && type_array.contains(&json!("null"))
+        {
+            for t in type_array {
+                if t != &json!("null") {
+                    property.insert("type".to_string(), t.clone());
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Orders the schema canonically, at any depth: object keys are sorted and
+/// `required` lists sorted and deduplicated.
+///
+/// Keys are sorted explicitly rather than left to `serde_json`'s map, which
+/// keeps insertion order if any dependency enables its `preserve_order`
+/// feature, and `required` lists otherwise follow field declaration order,
+/// so reordering a struct's fields does not change its published schema.
+pub fn normalize(schema: &mut Value) {
+    match schema {
+        Value::Object(obj) => {
+            let mut entries: Vec<(String, Value)> = std::mem::take(obj).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut value) in entries {
+                if key == "required"
+                    && let Value::Array(names) = &mut value
+                {
+                    names.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+                    names.dedup();
+                }
+                normalize(&mut value);
+                obj.insert(key, value);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                normalize(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Appends the constraints of each property, at any depth, to its
+/// description, for targets that ignore constraint keywords.
+pub fn describe_constraints(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    if let Some(properties) = obj.get_mut("properties").and_then(Value::as_object_mut) {
+        for property in properties.values_mut() {
+            if let Some(property) = property.as_object_mut() {
+                let notes = constraint_notes(property);
+                if !notes.is_empty() {
+                    let mut description = property
+                        .get("description")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .trim_end()
+                        .to_string();
+                    if !description.is_empty() && !description.ends_with('.') {
+                        description.push('.');
+                    }
+                    for note in notes {
+                        if !description.is_empty() {
+                            description.push(' ');
+                        }
+                        description.push_str(&note);
+                    }
+                    property.insert("description".to_string(), json!(description));
+                }
+            }
+            describe_constraints(property);
+        }
+    }
+    if let Some(items) = obj.get_mut("items") {
+        describe_constraints(items);
+    }
+}
+
+// The top-level properties of a schema that are objects
+fn top_level_properties(schema: &mut Value) -> impl Iterator<Item = &mut Map<String, Value>> {
+    schema
+        .get_mut("properties")
+        .and_then(Value::as_object_mut)
+        .into_iter()
+        .flat_map(|properties| properties.values_mut())
+        .filter_map(Value::as_object_mut)
+}
+
+// Sentences describing the constraint keywords of a property schema
+fn constraint_notes(property: &Map<String, Value>) -> Vec<String> {
+    let is_integer = match property.get("type") {
+        Some(Value::String(kind)) => kind == "integer",
+        Some(Value::Array(kinds)) => kinds.contains(&json!("integer")),
+        _ => false,
+    };
+    // A minimum of zero only restates that the integer is unsigned
+    let minimum = property
+        .get("minimum")
+        .filter(|minimum| !(is_integer && minimum.as_u64() == Some(0)));
+
+    let mut notes: Vec<String> = [
+        bounds(minimum, property.get("maximum"), ""),
+        property
+            .get("exclusiveMinimum")
+            .map(|minimum| format!("Must be greater than {minimum}.")),
+        property
+            .get("exclusiveMaximum")
+            .map(|maximum| format!("Must be less than {maximum}.")),
+        bounds(
+            property.get("minLength"),
+            property.get("maxLength"),
+            " characters long",
+        ),
+        bounds(property.get("minItems"), property.get("maxItems"), " items"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    // Values the description already names are not repeated
+    if let Some(values) = property.get("enum").and_then(Value::as_array) {
+        let description = property
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), String::from)
+            })
+            .collect();
+        if !values
+            .iter()
+            .all(|value| description.contains(value.as_str()))
+        {
+            notes.push(format!("One of: {}.", values.join(", ")));
+        }
+    }
+    notes
+}
+
+// A sentence stating inclusive lower and upper bounds, if there are any
+fn bounds(minimum: Option<&Value>, maximum: Option<&Value>, unit: &str) -> Option<String> {
+    match (minimum, maximum) {
+        (Some(minimum), Some(maximum)) if minimum == maximum => {
+            Some(format!("Must be exactly {minimum}{unit}."))
+        }
+        (Some(minimum), Some(maximum)) => {
+            Some(format!("Must be between {minimum} and {maximum}{unit}."))
+        }
+        (Some(minimum), None) => Some(format!("Must be at least {minimum}{unit}.")),
+        (None, Some(maximum)) => Some(format!("Must be at most {maximum}{unit}.")),
+        (None, None) => None,
+    }
+}
+
+// Recursively replaces `$ref` objects with their definitions, since `$defs` is removed
+fn inline_nested_refs(value: &mut Value, defs: &Value) {
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::String(ref_path)) = obj.get("$ref")
+                && let Some(def_obj) = ref_path
+                    .strip_prefix("#/$defs/")
+                    .and_then(|def_name| defs.get(def_name))
+                    .and_then(Value::as_object)
+            {
+                obj.clear();
+                obj.extend(def_obj.clone());
+            }
+            for nested in obj.values_mut() {
+                inline_nested_refs(nested, defs);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                inline_nested_refs(item, defs);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,662 @@
+//! Registry of the tools this server exposes.
+//!
+//! Each tool is described once here: its name, description, request and
+//! response schemas, and the handler that executes it. The Lambda handler
+//! dispatches through the registry, and the schema generator reads it to
+//! build `tool_schema.json` and the other published formats (see
+//! [`SchemaExporter`]), so the two can never drift apart.
+
+#[cfg(feature = "register-tools")]
+mod agentcore;
+mod deprecation;
+mod descriptions;
+mod examples;
+mod exporters;
+mod providers;
+
+#[cfg(feature = "register-tools")]
+pub use agentcore::{
+    schema_definition, target_configuration, target_lambda_arn, tool_definition, tool_definitions,
+};
+pub use deprecation::{Deprecation, attach_notice, warn_call};
+pub use descriptions::{DescriptionOverride, DescriptionOverrides, description_overrides};
+pub use examples::ToolExample;
+pub use exporters::{
+    BedrockExporter, EXPORTERS, JsonSchemaExporter, McpExporter, OpenApiExporter, SchemaExporter,
+    bedrock_tool_schema, bedrock_tool_schemas, exporter, passes, render,
+};
+pub use providers::{NAGER_DATE, OPEN_METEO, Provider, attach_attribution, attributions};
+
+use crate::catalog;
+use crate::gazetteer;
+use crate::models::error::AppError;
+use crate::models::{
+    AdminCacheStatsRequest, AdminCacheStatsResponse, AdminFlushCacheRequest,
+    AdminFlushCacheResponse, AdminReloadConfigRequest, AdminReloadConfigResponse,
+    AdminReplayFailedRequest, AdminReplayFailedResponse, AdminReplayInvocationRequest,
+    AdminReplayInvocationResponse, AdminSetLogFilterRequest, AdminSetLogFilterResponse,
+    AdminToggleToolRequest, AdminToggleToolResponse, AdminToolVersionsRequest,
+    AdminToolVersionsResponse, AdminUsageReportRequest, AdminUsageReportResponse, ContentResponse,
+    DailyBriefingRequest, DailyBriefingResponse, DescribeServerRequest, DescribeServerResponse,
+    FeedRequest, FeedResponse, HolidaysRequest, HolidaysResponse, NotificationRequest,
+    NotificationResponse, PersonalizedGreetingRequest, PersonalizedGreetingResponse, PollenRequest,
+    PollenResponse, RecordConsentRequest, RecordConsentResponse, RunDeclarativeToolRequest,
+    RunDeclarativeToolResponse, RunWorkflowRequest, RunWorkflowResponse, WeatherChartRequest,
+    WeatherRequest, WeatherResponse,
+};
+#[cfg(feature = "aws-costs")]
+use crate::models::{CostsRequest, CostsResponse};
+#[cfg(feature = "query-logs")]
+use crate::models::{QueryLogsRequest, QueryLogsResponse};
+#[cfg(feature = "sandbox")]
+use crate::models::{RunSandboxedToolRequest, RunSandboxedToolResponse};
+#[cfg(feature = "workflows")]
+use crate::models::{
+    StartWorkflowRequest, StartWorkflowResponse, WorkflowStatusRequest, WorkflowStatusResponse,
+};
+#[cfg(feature = "summarize")]
+use crate::models::{SummarizeRequest, SummarizeResponse};
+use crate::slo::Slo;
+#[cfg(feature = "aws-costs")]
+use crate::tools::get_aws_costs;
+#[cfg(feature = "query-logs")]
+use crate::tools::query_logs;
+#[cfg(feature = "sandbox")]
+use crate::tools::run_sandboxed_tool;
+#[cfg(feature = "summarize")]
+use crate::tools::summarize_text;
+use crate::tools::{
+    admin_cache_stats, admin_flush_cache, admin_reload_config, admin_replay_failed,
+    admin_replay_invocation, admin_set_log_filter, admin_toggle_tool, admin_tool_versions,
+    admin_usage_report, daily_briefing, describe_server, get_personalized_greeting,
+    get_pollen_forecast, get_public_holidays, get_weather, read_feed, record_consent,
+    render_weather_chart, run_declarative_tool, run_workflow, send_notification,
+};
+#[cfg(feature = "workflows")]
+use crate::tools::{get_workflow_status, start_workflow};
+use lambda_runtime::Diagnostic;
+use lambda_runtime::tracing::error;
+use schemars::{JsonSchema, Schema, schema_for};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::pin::Pin;
+
+/// Future returned by a tool handler.
+pub type ToolFuture = Pin<Box<dyn Future<Output = Result<Value, Diagnostic>> + Send>>;
+
+/// Static description of a tool and how to invoke it.
+pub struct ToolDescriptor {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Whether the tool accepts `cursor` and returns `next_cursor`
+    pub paginated: bool,
+    /// Response fields holding the rows `output_format` exports, tried in
+    /// order; empty if the tool returns no table
+    pub rows: &'static [&'static str],
+    /// Whether identical calls within a conversation may be answered from
+    /// the first call's response (see [`crate::memo`])
+    pub session_cached: bool,
+    /// Whether the tool acts on the outside world, and how hard that is to
+    /// undo
+    pub effects: Effects,
+    /// Set while the tool is being retired
+    pub deprecation: Option<Deprecation>,
+    /// Availability and latency objectives (see [`crate::slo`])
+    pub slo: Slo,
+    /// Example calls, published with the schema
+    pub examples: &'static [ToolExample],
+    /// Upstreams whose data the tool returns, credited in schema and responses
+    pub providers: &'static [Provider],
+    pub input_schema: fn() -> Schema,
+    pub output_schema: fn() -> Schema,
+    /// Parses the tool arguments, runs the tool and serializes its response
+    pub handler: fn(Value) -> ToolFuture,
+}
+
+impl ToolDescriptor {
+    /// Whether the tool acts on the outside world, and so accepts `dry_run`
+    /// (see [`crate::dry_run`]).
+    #[must_use]
+    pub const fn side_effects(&self) -> bool {
+        !matches!(self.effects, Effects::None)
+    }
+
+    /// Whether calls only act once confirmed with a token from an earlier
+    /// dry run of the same call (see [`crate::confirmation`]).
+    #[must_use]
+    pub const fn destructive(&self) -> bool {
+        matches!(self.effects, Effects::Destructive)
+    }
+}
+
+/// What calling a tool does besides answering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effects {
+    /// Only reads
+    None,
+    /// Acts on the outside world, e.g. sends a notification
+    SideEffects,
+    /// Acts on the outside world in a way that cannot be taken back, e.g.
+    /// starts a workflow
+    Destructive,
+}
+
+static TOOLS: &[ToolDescriptor] = &[
+    ToolDescriptor {
+        name: "get_weather",
+        description: "Fetches weather data from the Open-Meteo API. Set response_version to \"2\" for one record per day.",
+        paginated: false,
+        rows: &["days", "daily"],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[ToolExample {
+            description: "Forecast for Sydney, one record per day",
+            input: r#"{"location": "Sydney", "response_version": "2"}"#,
+            output: r#"{"resolvedLocation": {"name": "Sydney", "countryCode": "AU", "latitude": -33.87, "longitude": 151.21}, "temperatureUnit": "°C", "days": [{"date": "2026-10-14", "temperatureMax": {"value": 22.4, "unit": "°C"}, "temperatureMin": {"value": 14.1, "unit": "°C"}, "weatherCode": 3}]}"#,
+        }],
+        providers: &[OPEN_METEO],
+        input_schema: schema::<WeatherRequest>,
+        output_schema: schema::<WeatherResponse>,
+        handler: |args| Box::pin(invoke_tool("weather", args, get_weather)),
+    },
+    ToolDescriptor {
+        name: "render_weather_chart",
+        description: "Renders the daily forecast for a location as an SVG chart of high and low temperatures.",
+        paginated: false,
+        rows: &[],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[OPEN_METEO],
+        input_schema: schema::<WeatherChartRequest>,
+        output_schema: schema::<ContentResponse>,
+        handler: |args| Box::pin(invoke_tool("weather chart", args, render_weather_chart)),
+    },
+    ToolDescriptor {
+        name: "get_pollen_forecast",
+        description: "Forecasts daily peak pollen concentrations (alder, birch, olive, grass, mugwort, ragweed) and their allergy risk for a location in Europe; elsewhere it reports that no forecast is available.",
+        paginated: false,
+        rows: &[],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[OPEN_METEO],
+        input_schema: schema::<PollenRequest>,
+        output_schema: schema::<PollenResponse>,
+        handler: |args| Box::pin(invoke_tool("pollen forecast", args, get_pollen_forecast)),
+    },
+    ToolDescriptor {
+        name: "get_personalized_greeting",
+        description: "Generates a personalized greeting for a user.",
+        paginated: false,
+        rows: &[],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<PersonalizedGreetingRequest>,
+        output_schema: schema::<PersonalizedGreetingResponse>,
+        handler: |args| {
+            Box::pin(invoke_tool(
+                "personalized greeting",
+                args,
+                get_personalized_greeting,
+            ))
+        },
+    },
+    ToolDescriptor {
+        name: "read_feed",
+        description: "Reads the latest items (title, date, summary, link) from an RSS or Atom feed.",
+        paginated: true,
+        rows: &[],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<FeedRequest>,
+        output_schema: schema::<FeedResponse>,
+        handler: |args| Box::pin(invoke_tool("feed", args, read_feed)),
+    },
+    #[cfg(feature = "summarize")]
+    ToolDescriptor {
+        name: "summarize_text",
+        description: "Summarizes long text with an Amazon Bedrock model, optionally focused by instructions.",
+        paginated: false,
+        rows: &[],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::LONG_RUNNING,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<SummarizeRequest>,
+        output_schema: schema::<SummarizeResponse>,
+        handler: |args| Box::pin(invoke_tool("summarize", args, summarize_text)),
+    },
+    ToolDescriptor {
+        name: "send_notification",
+        description: "Sends a templated notification to an allowlisted SNS topic or email address. Requires the Notifications.Send scope.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::SideEffects,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<NotificationRequest>,
+        output_schema: schema::<NotificationResponse>,
+        handler: |args| Box::pin(invoke_tool("notification", args, send_notification)),
+    },
+    #[cfg(feature = "query-logs")]
+    ToolDescriptor {
+        name: "query_logs",
+        description: "Runs a CloudWatch Logs Insights query against allowlisted log groups and returns structured rows.",
+        paginated: true,
+        rows: &["rows"],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::LONG_RUNNING,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<QueryLogsRequest>,
+        output_schema: schema::<QueryLogsResponse>,
+        handler: |args| Box::pin(invoke_tool("query logs", args, query_logs)),
+    },
+    #[cfg(feature = "aws-costs")]
+    ToolDescriptor {
+        name: "get_aws_costs",
+        description: "Returns month-to-date AWS spend grouped by service, optionally filtered by service or region.",
+        paginated: false,
+        rows: &["services"],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<CostsRequest>,
+        output_schema: schema::<CostsResponse>,
+        handler: |args| Box::pin(invoke_tool("costs", args, get_aws_costs)),
+    },
+    #[cfg(feature = "workflows")]
+    ToolDescriptor {
+        name: "start_workflow",
+        description: "Starts an allowlisted Step Functions workflow with validated JSON input. Requires the Workflows.Start scope.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::Destructive,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<StartWorkflowRequest>,
+        output_schema: schema::<StartWorkflowResponse>,
+        handler: |args| Box::pin(invoke_tool("start workflow", args, start_workflow)),
+    },
+    #[cfg(feature = "workflows")]
+    ToolDescriptor {
+        name: "get_workflow_status",
+        description: "Reports the status, output or error of a workflow execution started by start_workflow.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<WorkflowStatusRequest>,
+        output_schema: schema::<WorkflowStatusResponse>,
+        handler: |args| Box::pin(invoke_tool("workflow status", args, get_workflow_status)),
+    },
+    ToolDescriptor {
+        name: "run_workflow",
+        description: "Runs several of this server's tools in one call as a sequence or DAG, passing outputs between steps with ${step.field} references, and reports the outcome of each step.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::SideEffects,
+        deprecation: None,
+        slo: Slo::LONG_RUNNING,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<RunWorkflowRequest>,
+        output_schema: schema::<RunWorkflowResponse>,
+        handler: |args| Box::pin(invoke_tool("run workflow", args, run_workflow)),
+    },
+    #[cfg(feature = "sandbox")]
+    ToolDescriptor {
+        name: "run_sandboxed_tool",
+        description: "Runs an allowlisted community tool in an isolated WASM sandbox with no network or AWS access, passing it JSON arguments and returning the JSON object it produces.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<RunSandboxedToolRequest>,
+        output_schema: schema::<RunSandboxedToolResponse>,
+        handler: |args| Box::pin(invoke_tool("run sandboxed tool", args, run_sandboxed_tool)),
+    },
+    ToolDescriptor {
+        name: "run_declarative_tool",
+        description: "Calls a REST-wrapping tool defined in the server's configuration by name, filling its request template from the arguments and returning the mapped JSON response.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<RunDeclarativeToolRequest>,
+        output_schema: schema::<RunDeclarativeToolResponse>,
+        handler: |args| {
+            Box::pin(invoke_tool(
+                "run declarative tool",
+                args,
+                run_declarative_tool,
+            ))
+        },
+    },
+    ToolDescriptor {
+        name: "get_public_holidays",
+        description: "Lists the public holidays of a country for a year from the Nager.Date API.",
+        paginated: true,
+        rows: &["holidays"],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[ToolExample {
+            description: "First holidays of 2026 in Australia",
+            input: r#"{"country_code": "AU", "year": 2026, "limit": 2}"#,
+            output: r#"{"country_code": "AU", "year": 2026, "holidays": [{"date": "2026-01-01", "name": "New Year's Day", "local_name": "New Year's Day", "nationwide": true}, {"date": "2026-01-26", "name": "Australia Day", "local_name": "Australia Day", "nationwide": true}]}"#,
+        }],
+        providers: &[NAGER_DATE],
+        input_schema: schema::<HolidaysRequest>,
+        output_schema: schema::<HolidaysResponse>,
+        handler: |args| Box::pin(invoke_tool("holidays", args, get_public_holidays)),
+    },
+    ToolDescriptor {
+        name: "daily_briefing",
+        description: "Builds a daily briefing for the signed-in user: a greeting, today's weather for their home city and upcoming public holidays.",
+        paginated: false,
+        rows: &[],
+        session_cached: true,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::LONG_RUNNING,
+        examples: &[],
+        providers: &[OPEN_METEO, NAGER_DATE],
+        input_schema: schema::<DailyBriefingRequest>,
+        output_schema: schema::<DailyBriefingResponse>,
+        handler: |args| Box::pin(invoke_tool("daily briefing", args, daily_briefing)),
+    },
+    ToolDescriptor {
+        name: "describe_server",
+        description: "Describes what this deployment supports: enabled tools with their response versions, required scopes and concurrency limits, authentication modes, load limits, cache policies and build information.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<DescribeServerRequest>,
+        output_schema: schema::<DescribeServerResponse>,
+        handler: |args| Box::pin(invoke_tool("describe server", args, describe_server)),
+    },
+    ToolDescriptor {
+        name: "record_consent",
+        description: "Records whether the signed-in caller consents to their name, user ID, preferences and past calls being used to personalize responses, or withdraws that consent.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::SideEffects,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<RecordConsentRequest>,
+        output_schema: schema::<RecordConsentResponse>,
+        handler: |args| Box::pin(invoke_tool("record consent", args, record_consent)),
+    },
+    ToolDescriptor {
+        name: "admin_flush_cache",
+        description: "Admin only: flushes a named cache, or every cache, in the serving container.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminFlushCacheRequest>,
+        output_schema: schema::<AdminFlushCacheResponse>,
+        handler: |args| Box::pin(invoke_tool("flush cache", args, admin_flush_cache)),
+    },
+    ToolDescriptor {
+        name: "admin_cache_stats",
+        description: "Admin only: reports the size, hit rate and removals of each cache in the serving container.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminCacheStatsRequest>,
+        output_schema: schema::<AdminCacheStatsResponse>,
+        handler: |args| Box::pin(invoke_tool("cache stats", args, admin_cache_stats)),
+    },
+    ToolDescriptor {
+        name: "admin_reload_config",
+        description: "Admin only: re-reads the server configuration from the environment in the serving container.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminReloadConfigRequest>,
+        output_schema: schema::<AdminReloadConfigResponse>,
+        handler: |args| Box::pin(invoke_tool("reload config", args, admin_reload_config)),
+    },
+    ToolDescriptor {
+        name: "admin_toggle_tool",
+        description: "Admin only: switches a tool off or back on in the serving container.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminToggleToolRequest>,
+        output_schema: schema::<AdminToggleToolResponse>,
+        handler: |args| Box::pin(invoke_tool("toggle tool", args, admin_toggle_tool)),
+    },
+    ToolDescriptor {
+        name: "admin_set_log_filter",
+        description: "Admin only: changes log levels per module in the serving container, e.g. debug logging for one tool.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminSetLogFilterRequest>,
+        output_schema: schema::<AdminSetLogFilterResponse>,
+        handler: |args| Box::pin(invoke_tool("set log filter", args, admin_set_log_filter)),
+    },
+    ToolDescriptor {
+        name: "admin_replay_invocation",
+        description: "Admin only: replays a recorded tool invocation in sandbox mode and reports how the result differs from the recorded one.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::LONG_RUNNING,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminReplayInvocationRequest>,
+        output_schema: schema::<AdminReplayInvocationResponse>,
+        handler: |args| {
+            Box::pin(invoke_tool(
+                "replay invocation",
+                args,
+                admin_replay_invocation,
+            ))
+        },
+    },
+    ToolDescriptor {
+        name: "admin_replay_failed",
+        description: "Admin only: runs failed asynchronous tool calls again, by failure ID or oldest first, and removes those that succeed from the record.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::SideEffects,
+        deprecation: None,
+        slo: Slo::LONG_RUNNING,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminReplayFailedRequest>,
+        output_schema: schema::<AdminReplayFailedResponse>,
+        handler: |args| {
+            Box::pin(invoke_tool(
+                "replay failed calls",
+                args,
+                admin_replay_failed,
+            ))
+        },
+    },
+    ToolDescriptor {
+        name: "admin_tool_versions",
+        description: "Admin only: lists the response shapes each tool has returned and the deployment that introduced them, to detect output changes between deployments.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::UPSTREAM,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminToolVersionsRequest>,
+        output_schema: schema::<AdminToolVersionsResponse>,
+        handler: |args| Box::pin(invoke_tool("tool versions", args, admin_tool_versions)),
+    },
+    ToolDescriptor {
+        name: "admin_usage_report",
+        description: "Admin only: reports tool calls and the upstream API requests they made per tenant and tool for a month or day, for charging back API consumption.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::LONG_RUNNING,
+        examples: &[],
+        providers: &[],
+        input_schema: schema::<AdminUsageReportRequest>,
+        output_schema: schema::<AdminUsageReportResponse>,
+        handler: |args| Box::pin(invoke_tool("usage report", args, admin_usage_report)),
+    },
+];
+
+/// All registered tools, in the order they are published.
+#[must_use]
+pub fn tools() -> &'static [ToolDescriptor] {
+    TOOLS
+}
+
+/// Looks up a tool by name.
+#[must_use]
+pub fn find(name: &str) -> Option<&'static ToolDescriptor> {
+    TOOLS.iter().find(|tool| tool.name == name)
+}
+
+/// The registered tool name closest to `name` in edit distance, among
+/// those `candidate` accepts, if it is close enough to be a misspelling.
+///
+/// Names further than a third of `name`'s length (at least two edits) are
+/// not suggested, so unrelated tools are never offered.
+#[must_use]
+pub fn closest(name: &str, candidate: impl Fn(&str) -> bool) -> Option<&'static str> {
+    let query = name.trim().to_lowercase();
+    let max_distance = (query.chars().count() / 3).max(2);
+    TOOLS
+        .iter()
+        .filter(|tool| candidate(tool.name))
+        .map(|tool| (gazetteer::levenshtein(&query, tool.name), tool.name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, name)| name)
+}
+
+fn schema<T: JsonSchema>() -> Schema {
+    schema_for!(T)
+}
+
+/// Parses tool arguments, runs the tool and serializes its response.
+///
+/// `label` names the tool in log messages, e.g. "Failed to parse weather request".
+/// Errors are returned as catalogued diagnostics (see [`crate::catalog`]).
+async fn invoke_tool<Req, Resp, F, Fut>(
+    label: &str,
+    tool_args: Value,
+    tool: F,
+) -> Result<Value, Diagnostic>
+where
+    Req: DeserializeOwned,
+    Resp: Serialize,
+    F: FnOnce(Req) -> Fut,
+    Fut: Future<Output = Result<Resp, AppError>>,
+{
+    let request: Req = serde_json::from_value(tool_args).map_err(|e| {
+        error!(error = %e, code = catalog::INVALID_INPUT.code, "Failed to parse {} request", label);
+        catalog::INVALID_INPUT.diagnostic("InvalidInput", &e.to_string())
+    })?;
+
+    let response = tool(request).await.map_err(|e| {
+        error!(
+            error = %format!("{e:#}"),
+            code = e.catalog_entry().code,
+            "Tool execution failed: {}", label
+        );
+        e.to_diagnostic("ToolError")
+    })?;
+
+    serde_json::to_value(response).map_err(|e| {
+        error!(
+            error = %e,
+            code = catalog::SERIALIZATION_FAILED.code,
+            "Failed to serialize {} response", label
+        );
+        catalog::SERIALIZATION_FAILED.diagnostic("SerializationError", &e.to_string())
+    })
+}
@@ -0,0 +1,78 @@
+//! Upstream data providers and the attribution their terms ask for.
+//!
+//! A tool lists the providers whose data it returns in its descriptor, so
+//! attribution is declared once in the registry rather than by each tool.
+//! Their attributions are published in the tool's schema as `attribution`,
+//! and every successful response of the tool carries them in
+//! `_meta.attribution`, for agents to pass on to users wherever the data is
+//! shown.
+
+use crate::metrics::META_KEY;
+use serde_json::{Map, Value, json};
+
+/// An upstream whose data the tools return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Provider {
+    /// Provider name, e.g. "Open-Meteo"
+    pub name: &'static str,
+    /// Credit to show with the data
+    pub attribution: &'static str,
+    /// License of the data, e.g. "CC BY 4.0"
+    pub license: &'static str,
+    /// Provider's site, linked from the credit
+    pub url: &'static str,
+}
+
+/// Weather, geocoding and air quality APIs, whose data is licensed CC BY 4.0
+pub const OPEN_METEO: Provider = Provider {
+    name: "Open-Meteo",
+    attribution: "Weather data by Open-Meteo.com",
+    license: "CC BY 4.0",
+    url: "https://open-meteo.com/",
+};
+
+/// Public holidays API
+pub const NAGER_DATE: Provider = Provider {
+    name: "Nager.Date",
+    attribution: "Public holidays by Nager.Date",
+    license: "MIT",
+    url: "https://date.nager.at/",
+};
+
+impl Provider {
+    /// The attribution as published in a tool's schema and responses.
+    #[must_use]
+    pub fn published(&self) -> Value {
+        json!({
+            "provider": self.name,
+            "text": self.attribution,
+            "license": self.license,
+            "url": self.url,
+        })
+    }
+}
+
+/// The attributions of `providers`, as published.
+#[must_use]
+pub fn attributions(providers: &[Provider]) -> Value {
+    Value::Array(providers.iter().map(Provider::published).collect())
+}
+
+/// Adds the attributions of `providers` to an object response under
+/// `_meta.attribution`, keeping any other metadata.
+///
+/// Non-object responses, and tools without providers, are left unchanged.
+pub fn attach_attribution(response: &mut Value, providers: &[Provider]) {
+    if providers.is_empty() {
+        return;
+    }
+    let Value::Object(fields) = response else {
+        return;
+    };
+    let meta = fields
+        .entry(META_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(meta) = meta {
+        meta.insert("attribution".to_string(), attributions(providers));
+    }
+}
@@ -0,0 +1,228 @@
+//! Regions callers' personal data is stored in.
+//!
+//! Some callers' data must stay in a region, for example EU users' data in
+//! the EU. A caller's data residency is read from the token claim named by
+//! `RESIDENCY_CLAIM` (`residency` by default), or pinned for every caller of
+//! a tenant by the `residency` of its overlay (see [`crate::tenants`]),
+//! which wins over the claim. `RESIDENCY_REGIONS` holds a [`ResidencyRegion`]
+//! per residency, for example:
+//!
+//! ```json
+//! {"eu": {"region": "eu-central-1",
+//!         "tables": {"audit": "mcp-eu-invocation-audit"}}}
+//! ```
+//!
+//! Preferences and consent (see [`crate::preferences`]), memories (see
+//! [`crate::memory`]) and invocation records (see [`crate::audit`]) of a
+//! caller with a residency are read from and written to tables in its
+//! region: the table named for the [`Store`] in `tables`, or else the table
+//! of the same name as in the Lambda's own region, as with `DynamoDB` global
+//! tables or a copy of the stack deployed per region. Callers without a
+//! residency use the Lambda's own region.
+//!
+//! Every write passes [`check_write`], which refuses to write a resident's
+//! data outside their region. A caller whose residency has no region
+//! configured is not written anywhere rather than falling back to the
+//! Lambda's own region; the refusal is logged like any other failed write,
+//! and reads of their data find nothing.
+
+use crate::auth::Claims;
+use crate::aws::sdk_config;
+use crate::config::config;
+use crate::models::error::AppError;
+use aws_sdk_dynamodb::config::Region;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// `DynamoDB` clients by region; the Lambda's own region is keyed by ""
+static DYNAMODB_CLIENTS: LazyLock<Mutex<HashMap<String, aws_sdk_dynamodb::Client>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Where one residency's data is stored.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResidencyRegion {
+    /// AWS region, e.g. "eu-central-1"
+    pub region: String,
+    /// Table per store in the region, e.g. `{"audit": "mcp-eu-invocation-audit"}`; unlisted stores use the table of the same name as in the Lambda's region
+    #[serde(default)]
+    pub tables: HashMap<String, String>,
+}
+
+/// A store of callers' personal data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Store {
+    /// User preferences and consent (`USER_PREFERENCES_TABLE`)
+    Preferences,
+    /// User memories (`USER_MEMORY_TABLE`)
+    Memory,
+    /// Recorded invocations (`INVOCATION_AUDIT_TABLE`)
+    Audit,
+}
+
+impl Store {
+    /// Every store.
+    pub const ALL: [Self; 3] = [Self::Preferences, Self::Memory, Self::Audit];
+
+    /// The store's key in [`ResidencyRegion::tables`].
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Preferences => "preferences",
+            Self::Memory => "memory",
+            Self::Audit => "audit",
+        }
+    }
+
+    /// The store's table in the Lambda's own region, if it has one.
+    fn home_table(self) -> Option<String> {
+        let config = config();
+        match self {
+            Self::Preferences => config.preferences.table.clone(),
+            Self::Memory => config.memory.table.clone(),
+            Self::Audit => config.audit.table.clone(),
+        }
+    }
+}
+
+/// A table holding some callers' data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Region of the table; the Lambda's own region if `None`
+    pub region: Option<String>,
+    pub table: String,
+}
+
+/// The data residency of a caller of `tenant` with `claims`, if any.
+#[must_use]
+pub fn resolve(claims: Option<&Claims>, tenant: Option<&str>) -> Option<String> {
+    let config = config();
+    let pinned = tenant
+        .and_then(|tenant| config.tenants.overlays.get(tenant))
+        .and_then(|overlay| overlay.residency.as_deref());
+    let claimed = || {
+        claims?
+            .other
+            .get(&config.residency.claim)
+            .and_then(Value::as_str)
+    };
+    pinned
+        .or_else(claimed)
+        .map(str::trim)
+        .filter(|residency| !residency.is_empty())
+        .map(str::to_ascii_lowercase)
+}
+
+/// The table in `store` holding the data of callers with `residency`, or
+/// `None` if the store has no table.
+///
+/// # Errors
+///
+/// Returns `AppError::ResidencyDenied` if no region is configured for
+/// `residency`.
+pub fn place(store: Store, residency: Option<&str>) -> Result<Option<Placement>, AppError> {
+    let Some(residency) = residency else {
+        return Ok(store.home_table().map(|table| Placement {
+            region: None,
+            table,
+        }));
+    };
+    let settings = region_of(residency)?;
+    let table = settings
+        .tables
+        .get(store.name())
+        .cloned()
+        .or_else(|| store.home_table());
+    Ok(table.map(|table| Placement {
+        region: Some(settings.region),
+        table,
+    }))
+}
+
+/// Where in `store` to write the data of callers with `residency`, once the
+/// write has passed [`check_write`].
+///
+/// # Errors
+///
+/// Returns `AppError::ResidencyDenied` if no region is configured for
+/// `residency`.
+pub fn place_write(store: Store, residency: Option<&str>) -> Result<Option<Placement>, AppError> {
+    let placement = place(store, residency)?;
+    if let Some(placement) = &placement {
+        check_write(residency, placement)?;
+    }
+    Ok(placement)
+}
+
+/// Refuses writes of the data of callers with `residency` outside their
+/// region.
+///
+/// # Errors
+///
+/// Returns `AppError::ResidencyDenied` if `placement` is not in the region
+/// of `residency`, or no region is configured for it.
+pub fn check_write(residency: Option<&str>, placement: &Placement) -> Result<(), AppError> {
+    let Some(residency) = residency else {
+        return Ok(());
+    };
+    let expected = region_of(residency)?.region;
+    if placement.region.as_ref() == Some(&expected) {
+        return Ok(());
+    }
+    Err(AppError::ResidencyDenied(format!(
+        "Data of {residency} residents may only be written to {expected}, not {}",
+        placement.region.as_deref().unwrap_or("the default region")
+    )))
+}
+
+/// Every table in `store`: the Lambda's own, then each residency's.
+#[must_use]
+pub fn placements(store: Store) -> Vec<Placement> {
+    let config = config();
+    let mut residencies: Vec<_> = config.residency.regions.keys().collect();
+    residencies.sort();
+    let mut placements = Vec::new();
+    for residency in std::iter::once(None).chain(residencies.into_iter().map(|r| Some(r.as_str())))
+    {
+        if let Ok(Some(placement)) = place(store, residency)
+            && !placements.contains(&placement)
+        {
+            placements.push(placement);
+        }
+    }
+    placements
+}
+
+/// A `DynamoDB` client for `region`, or the Lambda's own region if `None`.
+pub async fn dynamodb_client(region: Option<&str>) -> aws_sdk_dynamodb::Client {
+    let sdk_config = sdk_config().await;
+    let key = region.unwrap_or_default();
+    let mut clients = DYNAMODB_CLIENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    clients
+        .entry(key.to_string())
+        .or_insert_with(|| {
+            region.map_or_else(
+                || aws_sdk_dynamodb::Client::new(sdk_config),
+                |region| {
+                    aws_sdk_dynamodb::Client::from_conf(
+                        aws_sdk_dynamodb::config::Builder::from(sdk_config)
+                            .region(Region::new(region.to_string()))
+                            .build(),
+                    )
+                },
+            )
+        })
+        .clone()
+}
+
+fn region_of(residency: &str) -> Result<ResidencyRegion, AppError> {
+    config().residency.regions.get(residency).cloned().ok_or_else(|| {
+        AppError::ResidencyDenied(format!(
+            "No region is configured for data residency {residency}"
+        ))
+    })
+}
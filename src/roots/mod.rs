@@ -0,0 +1,98 @@
+//! Workspace roots declared by the MCP client.
+//!
+//! Clients with the `roots` capability answer `roots/list` with the
+//! workspace URIs the server may work in, and send
+//! `notifications/roots/list_changed` when those change. A transport that
+//! can reach its client installs a [`RootsProvider`] on the tool context
+//! (see [`ToolContext::with_roots_provider`]), and [`current`] returns the
+//! roots for file-aware tools to scope their behavior to (see
+//! [`Root::contains`]).
+//!
+//! Root sets are cached per conversation session (see
+//! [`crate::context::SESSION_ID_ARG`]) until the client reports a change,
+//! which the transport passes to [`handle_notification`], or
+//! `MCP_ROOTS_CACHE_TTL_SECS` passes. Calls routed through the `AgentCore`
+//! gateway carry no provider and see no roots.
+//!
+//! [`ToolContext::with_roots_provider`]: crate::context::ToolContext::with_roots_provider
+
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::context;
+use crate::deadline;
+use crate::models::error::AppError;
+use crate::models::roots::ROOTS_LIST_CHANGED;
+use crate::models::{ListRootsResult, Root};
+use lambda_runtime::tracing::{debug, warn};
+use std::pin::Pin;
+use std::sync::LazyLock;
+use tokio::time::timeout;
+
+/// Root sets per session, shared across warm invocations
+pub(crate) static ROOTS_CACHE: LazyLock<CacheStore<Vec<Root>>> =
+    LazyLock::new(|| CacheStore::new("roots", config().roots.cache_ttl, 256));
+
+/// Future returned by a [`RootsProvider`].
+pub type RootsFuture = Pin<Box<dyn Future<Output = Result<ListRootsResult, AppError>> + Send>>;
+
+/// Sends `roots/list` requests to the MCP client.
+pub trait RootsProvider: Send + Sync {
+    /// Asks the client for its current roots.
+    fn list_roots(&self) -> RootsFuture;
+}
+
+/// The client's roots for the current call, or none if the transport cannot
+/// ask for them or the client does not answer in time.
+pub async fn current() -> Vec<Root> {
+    let context = context::current();
+    let Some(provider) = context.roots_provider() else {
+        return Vec::new();
+    };
+    let key = context.session_id.as_deref().map(cache_key);
+    if let Some(roots) = key.as_deref().and_then(|key| ROOTS_CACHE.get(key)) {
+        return roots;
+    }
+
+    let limit = config().roots.timeout;
+    let wait = deadline::remaining().map_or(limit, |left| left.min(limit));
+    match timeout(wait, provider.list_roots()).await {
+        Ok(Ok(result)) => {
+            debug!(roots = result.roots.len(), "Listed client roots");
+            if let Some(key) = key {
+                ROOTS_CACHE.insert(key, result.roots.clone());
+            }
+            result.roots
+        }
+        Ok(Err(e)) => {
+            warn!(error = %e, "Listing client roots failed");
+            Vec::new()
+        }
+        Err(_) => {
+            warn!(timeout = ?wait, "Listing client roots timed out");
+            Vec::new()
+        }
+    }
+}
+
+/// Handles a notification from the client, returning `true` if it reported
+/// changed roots.
+///
+/// The cached roots of `session_id` are dropped so the next call lists them
+/// again; without a session every cached root set is dropped.
+pub fn handle_notification(method: &str, session_id: Option<&str>) -> bool {
+    if method != ROOTS_LIST_CHANGED {
+        return false;
+    }
+    let dropped = session_id.map_or_else(
+        || ROOTS_CACHE.clear(),
+        |session_id| ROOTS_CACHE.invalidate(&cache_key(session_id)),
+    );
+    debug!(session_id, dropped, "Client roots changed");
+    true
+}
+
+// Session IDs cannot contain '/', so the separator keeps prefix
+// invalidation from reaching other sessions
+fn cache_key(session_id: &str) -> String {
+    format!("{session_id}/")
+}
@@ -0,0 +1,76 @@
+//! Text generation by the MCP client's own model.
+//!
+//! MCP clients that declare the `sampling` capability accept
+//! `sampling/createMessage` requests from the server, so a tool can have the
+//! client's model phrase its result, e.g. a natural-language weather
+//! summary, instead of bundling a model call of its own. A transport that
+//! can send such requests to its client installs a [`Sampler`] on the tool
+//! context (see [`ToolContext::with_sampler`]), and [`sample_text`] uses it.
+//!
+//! Calls routed through the `AgentCore` gateway have no channel back to the
+//! client, so they carry no sampler and tools return their deterministic
+//! output. The same fallback applies when sampling is switched off
+//! (`MCP_SAMPLING`), the client fails, or it does not reply within
+//! `MCP_SAMPLING_TIMEOUT_MS` or the invocation deadline.
+//!
+//! [`ToolContext::with_sampler`]: crate::context::ToolContext::with_sampler
+
+use crate::config::config;
+use crate::context;
+use crate::deadline;
+use crate::models::error::AppError;
+use crate::models::{CreateMessageParams, CreateMessageResult, SamplingMessage};
+use lambda_runtime::tracing::{info, warn};
+use std::pin::Pin;
+use tokio::time::timeout;
+
+/// Future returned by a [`Sampler`].
+pub type SamplingFuture =
+    Pin<Box<dyn Future<Output = Result<CreateMessageResult, AppError>> + Send>>;
+
+/// Sends `sampling/createMessage` requests to the MCP client.
+pub trait Sampler: Send + Sync {
+    /// Sends `params` to the client and waits for its reply.
+    fn create_message(&self, params: CreateMessageParams) -> SamplingFuture;
+}
+
+/// Asks the client's model to respond to `prompt`, returning `None` when the
+/// caller should fall back to its deterministic text.
+pub async fn sample_text(system_prompt: &str, prompt: String) -> Option<String> {
+    let settings = &config().sampling;
+    if !settings.enabled {
+        return None;
+    }
+    let sampler = context::current().sampler()?;
+    let wait = deadline::remaining().map_or(settings.timeout, |left| left.min(settings.timeout));
+    let params = CreateMessageParams {
+        messages: vec![SamplingMessage::user(prompt)],
+        system_prompt: Some(system_prompt.to_string()),
+        max_tokens: settings.max_tokens,
+        temperature: None,
+    };
+
+    match timeout(wait, sampler.create_message(params)).await {
+        Ok(Ok(result)) => {
+            let text = result
+                .text()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(String::from);
+            if text.is_some() {
+                info!(model = %result.model, "Sampled text from the client's model");
+            } else {
+                warn!(model = %result.model, "Client returned no sampled text; using fallback");
+            }
+            text
+        }
+        Ok(Err(e)) => {
+            warn!(error = %e, "Sampling request failed; using fallback");
+            None
+        }
+        Err(_) => {
+            warn!(timeout = ?wait, "Sampling request timed out; using fallback");
+            None
+        }
+    }
+}
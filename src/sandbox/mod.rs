@@ -0,0 +1,261 @@
+//! WASM sandbox for community-contributed tools.
+//!
+//! Tools listed in `SANDBOX_TOOLS` are WebAssembly modules run inside
+//! wasmtime by `run_sandboxed_tool`. A module is instantiated with no
+//! imports at all, so it has no WASI, no clock, no filesystem and no
+//! network: it cannot use the Lambda's IAM role or reach any endpoint, and
+//! can only compute a result from its arguments. Each call gets a fresh
+//! instance, a memory limit and a fuel budget, so a runaway module traps
+//! instead of holding the container.
+//!
+//! Modules implement a small ABI over their linear memory:
+//!
+//! - export `memory`
+//! - export `alloc(len: i32) -> i32`, returning a buffer of `len` bytes
+//! - export `run(ptr: i32, len: i32) -> i64`, reading the arguments as UTF-8
+//!   JSON from the buffer and returning where its result is, as the pointer
+//!   in the high 32 bits and the length in the low 32 bits
+//!
+//! The result must be a JSON object. A module reports a failure by
+//! returning `{"error": "<message>"}`.
+//!
+//! Modules are read from `SANDBOX_TOOLS_DIR`, usually a Lambda layer, and
+//! compiled once per container.
+
+use crate::config::{SandboxConfig, SandboxToolDefinition, config};
+use crate::models::error::AppError;
+use lambda_runtime::tracing::{debug, info};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimitsBuilder, Trap};
+
+/// Shared by every module; fuel metering must be enabled when it is built.
+static ENGINE: LazyLock<Result<Engine, String>> = LazyLock::new(|| {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).map_err(|e| e.to_string())
+});
+
+/// Compiled modules keyed by tool name.
+static MODULES: LazyLock<Mutex<HashMap<String, Module>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Resources one call may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Largest size the module's memory may grow to
+    pub max_memory_bytes: usize,
+    /// Fuel the call may burn before it traps
+    pub fuel: u64,
+    /// Largest result the module may return
+    pub max_output_bytes: usize,
+}
+
+impl Limits {
+    /// The limits for `definition`, falling back to the configured defaults.
+    #[must_use]
+    pub fn for_tool(settings: &SandboxConfig, definition: &SandboxToolDefinition) -> Self {
+        Self {
+            max_memory_bytes: definition
+                .max_memory_bytes
+                .unwrap_or(settings.max_memory_bytes),
+            fuel: definition.fuel.unwrap_or(settings.fuel),
+            max_output_bytes: settings.max_output_bytes,
+        }
+    }
+}
+
+/// The outcome of a sandboxed call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    pub output: Value,
+    pub fuel_used: u64,
+}
+
+/// Compiles a module, refusing any that imports something.
+///
+/// # Errors
+///
+/// Returns an error if `wasm` is not a valid module or declares imports.
+pub fn compile(wasm: &[u8]) -> Result<Module, AppError> {
+    let engine = ENGINE
+        .as_ref()
+        .map_err(|e| AppError::SandboxError(format!("WASM engine is unavailable: {e}")))?;
+    let module = Module::new(engine, wasm)
+        .map_err(|e| AppError::SandboxError(format!("Invalid module: {e}")))?;
+    let imports = module
+        .imports()
+        .map(|import| format!("{}.{}", import.module(), import.name()))
+        .collect::<Vec<_>>();
+    if !imports.is_empty() {
+        return Err(AppError::SandboxError(format!(
+            "Module imports {}, but sandboxed tools get no host functions",
+            imports.join(", ")
+        )));
+    }
+    Ok(module)
+}
+
+/// Runs `module` on `input` in a fresh instance limited by `limits`.
+///
+/// This blocks until the module returns or runs out of fuel; call it off the
+/// async runtime.
+///
+/// # Errors
+///
+/// Returns an error if the module does not implement the ABI, exceeds a
+/// limit, traps, or returns something other than a JSON object, or an
+/// object with an `error`.
+pub fn execute(module: &Module, input: &Value, limits: &Limits) -> Result<Execution, AppError> {
+    let mut store = Store::new(
+        module.engine(),
+        StoreLimitsBuilder::new()
+            .memory_size(limits.max_memory_bytes)
+            .instances(1)
+            .memories(1)
+            .tables(1)
+            .build(),
+    );
+    store.limiter(|limits| limits);
+    store
+        .set_fuel(limits.fuel)
+        .map_err(|e| AppError::SandboxError(format!("Failed to set fuel: {e}")))?;
+
+    let instance = Instance::new(&mut store, module, &[])
+        .map_err(|e| trap_error(&e, "Failed to instantiate module"))?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| AppError::SandboxError("Module exports no memory".to_string()))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&mut store, "alloc")
+        .map_err(|e| AppError::SandboxError(format!("Module has no usable alloc: {e}")))?;
+    let run = instance
+        .get_typed_func::<(i32, i32), i64>(&mut store, "run")
+        .map_err(|e| AppError::SandboxError(format!("Module has no usable run: {e}")))?;
+
+    let arguments = input.to_string();
+    let len = i32::try_from(arguments.len())
+        .map_err(|_| AppError::ValidationError("Arguments are too large".to_string()))?;
+    let ptr = alloc
+        .call(&mut store, len)
+        .map_err(|e| trap_error(&e, "alloc failed"))?;
+    memory
+        .write(&mut store, offset(ptr)?, arguments.as_bytes())
+        .map_err(|e| AppError::SandboxError(format!("alloc returned an unusable buffer: {e}")))?;
+    let packed = run
+        .call(&mut store, (ptr, len))
+        .map_err(|e| trap_error(&e, "run failed"))?;
+
+    let (out_ptr, out_len) = unpack(packed);
+    if out_len > limits.max_output_bytes {
+        return Err(AppError::SandboxError(format!(
+            "Result of {out_len} bytes exceeds the {} byte limit",
+            limits.max_output_bytes
+        )));
+    }
+    let mut output = vec![0; out_len];
+    memory
+        .read(&store, out_ptr, &mut output)
+        .map_err(|e| AppError::SandboxError(format!("run returned an unusable result: {e}")))?;
+    let fuel_used = limits
+        .fuel
+        .saturating_sub(store.get_fuel().unwrap_or_default());
+    parse_output(&output).map(|output| Execution { output, fuel_used })
+}
+
+/// Runs the allowlisted tool `name`, compiling its module on first use.
+///
+/// # Errors
+///
+/// Returns an error if the module cannot be read or compiled, or the call
+/// fails (see [`execute`]).
+pub async fn run(
+    name: &str,
+    definition: &SandboxToolDefinition,
+    input: Value,
+) -> Result<Execution, AppError> {
+    let settings = &config().sandbox;
+    let module = module(name, definition, &settings.modules_dir).await?;
+    let limits = Limits::for_tool(settings, definition);
+    debug!(tool = name, fuel = limits.fuel, "Running sandboxed tool");
+    tokio::task::spawn_blocking(move || execute(&module, &input, &limits))
+        .await
+        .map_err(|e| AppError::SandboxError(format!("Sandboxed call was aborted: {e}")))?
+}
+
+async fn module(
+    name: &str,
+    definition: &SandboxToolDefinition,
+    modules_dir: &str,
+) -> Result<Module, AppError> {
+    let cached = MODULES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .cloned();
+    if let Some(module) = cached {
+        return Ok(module);
+    }
+    let path = Path::new(modules_dir).join(&definition.module);
+    let (tool, file) = (name.to_string(), path.clone());
+    let module = tokio::task::spawn_blocking(move || {
+        let wasm = std::fs::read(&file).map_err(|e| {
+            AppError::SandboxError(format!("Failed to read module for {tool}: {e}"))
+        })?;
+        compile(&wasm)
+    })
+    .await
+    .map_err(|e| AppError::SandboxError(format!("Compilation was aborted: {e}")))??;
+    info!(tool = name, module = %path.display(), "Compiled sandboxed tool");
+    MODULES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.to_string(), module.clone());
+    Ok(module)
+}
+
+/// The error for a failed call into the module, naming the limit it hit.
+fn trap_error(error: &wasmtime::Error, context: &str) -> AppError {
+    match error.downcast_ref::<Trap>() {
+        Some(Trap::OutOfFuel) => {
+            AppError::SandboxError("Tool ran out of fuel before finishing".to_string())
+        }
+        _ => AppError::SandboxError(format!("{context}: {error}")),
+    }
+}
+
+/// A pointer into linear memory as a byte offset.
+fn offset(ptr: i32) -> Result<usize, AppError> {
+    usize::try_from(ptr)
+        .map_err(|_| AppError::SandboxError(format!("alloc returned an invalid pointer {ptr}")))
+}
+
+/// Splits the result of `run` into its pointer and length.
+fn unpack(packed: i64) -> (usize, usize) {
+    let bytes = packed.to_be_bytes();
+    let ptr = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let len = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    (
+        usize::try_from(ptr).unwrap_or(usize::MAX),
+        usize::try_from(len).unwrap_or(usize::MAX),
+    )
+}
+
+fn parse_output(output: &[u8]) -> Result<Value, AppError> {
+    let value = serde_json::from_slice::<Value>(output)
+        .map_err(|e| AppError::SandboxError(format!("Result is not valid JSON: {e}")))?;
+    let Some(object) = value.as_object() else {
+        return Err(AppError::SandboxError(
+            "Result is not a JSON object".to_string(),
+        ));
+    };
+    if let Some(error) = object.get("error") {
+        let message = error
+            .as_str()
+            .map_or_else(|| error.to_string(), String::from);
+        return Err(AppError::SandboxError(message));
+    }
+    Ok(value)
+}
@@ -0,0 +1,209 @@
+//! Secret scanning for outbound tool responses.
+//!
+//! Tools that fetch documents or query logs can echo credentials that happen
+//! to be in the data they read. As a last line of defence every response is
+//! scanned for obvious secret patterns before it is returned: AWS access key
+//! IDs, bearer tokens, JSON Web Tokens and PEM private keys. Matches are
+//! replaced with a placeholder such as `[REDACTED:aws_access_key]`, and each
+//! redaction is logged so the `SecretsRedacted` metric can alarm on it.
+
+use lambda_runtime::tracing::warn;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Shortest bearer token treated as a secret.
+const MIN_BEARER_LEN: usize = 16;
+
+/// Category of a detected secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SecretKind {
+    AwsAccessKey,
+    BearerToken,
+    Jwt,
+    PrivateKey,
+}
+
+impl SecretKind {
+    /// Stable label used in placeholders and logs.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::AwsAccessKey => "aws_access_key",
+            Self::BearerToken => "bearer_token",
+            Self::Jwt => "jwt",
+            Self::PrivateKey => "private_key",
+        }
+    }
+
+    fn placeholder(self) -> String {
+        format!("[REDACTED:{}]", self.label())
+    }
+}
+
+/// Redacts secrets in every string of `response`, logging what was found.
+///
+/// Returns the number of secrets redacted.
+pub fn scrub_response(tool: &str, response: &mut Value) -> usize {
+    let mut found = BTreeMap::new();
+    scrub_value(response, &mut found);
+
+    for (kind, count) in &found {
+        warn!(
+            tool,
+            kind = kind.label(),
+            count,
+            "Secrets redacted from tool response"
+        );
+    }
+    found.values().sum()
+}
+
+fn scrub_value(value: &mut Value, found: &mut BTreeMap<SecretKind, usize>) {
+    match value {
+        Value::String(text) => {
+            if let Some((scrubbed, kinds)) = redact(text) {
+                *text = scrubbed;
+                for kind in kinds {
+                    *found.entry(kind).or_default() += 1;
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| scrub_value(item, found)),
+        Value::Object(fields) => {
+            // Base64 payloads of binary content blocks are not text, and
+            // rewriting them would corrupt the data; every other field of a
+            // content block, such as the `text` of a text resource, is
+            // scrubbed
+            let content_block = fields.contains_key("mimeType");
+            for (name, field) in fields.iter_mut() {
+                let binary = content_block && matches!(name.as_str(), "data" | "blob");
+                if !binary {
+                    scrub_value(field, found);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replaces secrets in `text` with placeholders.
+///
+/// Returns `None` if `text` contains no secrets.
+#[must_use]
+pub fn redact(text: &str) -> Option<(String, Vec<SecretKind>)> {
+    let mut out = String::new();
+    let mut kinds = Vec::new();
+    let mut prev = None;
+    let mut pos = 0;
+
+    while let Some(rest) = text.get(pos..).filter(|rest| !rest.is_empty()) {
+        if let Some((kind, len)) = match_secret(rest, prev) {
+            if kinds.is_empty() {
+                out.push_str(text.get(..pos).unwrap_or_default());
+            }
+            out.push_str(&kind.placeholder());
+            kinds.push(kind);
+            pos += len;
+            prev = None;
+            continue;
+        }
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        if !kinds.is_empty() {
+            out.push(c);
+        }
+        prev = Some(c);
+        pos += c.len_utf8();
+    }
+
+    (!kinds.is_empty()).then_some((out, kinds))
+}
+
+/// Matches a secret at the start of `rest`, returning its kind and byte length.
+fn match_secret(rest: &str, prev: Option<char>) -> Option<(SecretKind, usize)> {
+    let at_boundary = !prev.is_some_and(|c| c.is_ascii_alphanumeric());
+    if rest.starts_with("-----BEGIN ") {
+        return private_key_len(rest).map(|len| (SecretKind::PrivateKey, len));
+    }
+    if !at_boundary {
+        return None;
+    }
+    if let Some(len) = bearer_len(rest) {
+        return Some((SecretKind::BearerToken, len));
+    }
+    if let Some(len) = jwt_len(rest) {
+        return Some((SecretKind::Jwt, len));
+    }
+    access_key_len(rest).map(|len| (SecretKind::AwsAccessKey, len))
+}
+
+/// PEM private key block, through its `END` line (or the end of the text).
+fn private_key_len(rest: &str) -> Option<usize> {
+    const BEGIN: &str = "-----BEGIN ";
+    const END: &str = "-----END ";
+    const DASHES: &str = "-----";
+
+    let header_end = BEGIN.len() + rest.get(BEGIN.len()..)?.find(DASHES)?;
+    if !rest.get(..header_end)?.ends_with("PRIVATE KEY") {
+        return None;
+    }
+    let Some(footer_start) = rest.find(END).map(|end| end + END.len()) else {
+        return Some(rest.len());
+    };
+    Some(
+        rest.get(footer_start..)?
+            .find(DASHES)
+            .map_or(rest.len(), |close| footer_start + close + DASHES.len()),
+    )
+}
+
+/// `Bearer <token>`, case-insensitive.
+fn bearer_len(rest: &str) -> Option<usize> {
+    const PREFIX: &str = "bearer ";
+    if !rest.get(..PREFIX.len())?.eq_ignore_ascii_case(PREFIX) {
+        return None;
+    }
+    let token = run_len(rest.get(PREFIX.len()..)?, |b| {
+        b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b'+' | b'/' | b'=')
+    });
+    (token >= MIN_BEARER_LEN).then_some(PREFIX.len() + token)
+}
+
+/// `eyJ...` header, payload and signature segments.
+fn jwt_len(rest: &str) -> Option<usize> {
+    if !rest.starts_with("eyJ") {
+        return None;
+    }
+    let len = run_len(rest, |b| {
+        b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.')
+    });
+    let token = rest.get(..len)?.trim_end_matches('.');
+    let segments: Vec<&str> = token.split('.').collect();
+    (segments.len() == 3
+        && segments.iter().all(|segment| !segment.is_empty())
+        && segments
+            .get(1)
+            .is_some_and(|payload| payload.starts_with("eyJ")))
+    .then_some(token.len())
+}
+
+/// `AKIA`/`ASIA` followed by 16 uppercase letters or digits.
+fn access_key_len(rest: &str) -> Option<usize> {
+    const LEN: usize = 20;
+    if !(rest.starts_with("AKIA") || rest.starts_with("ASIA")) {
+        return None;
+    }
+    let len = run_len(rest, |b| b.is_ascii_uppercase() || b.is_ascii_digit());
+    (len == LEN
+        && !rest
+            .as_bytes()
+            .get(LEN)
+            .is_some_and(u8::is_ascii_alphanumeric))
+    .then_some(LEN)
+}
+
+/// Length of the leading run of bytes matching `pred`.
+fn run_len(text: &str, pred: impl Fn(u8) -> bool) -> usize {
+    text.bytes().take_while(|&b| pred(b)).count()
+}
@@ -0,0 +1,239 @@
+//! Changelog of the shapes of tool responses.
+//!
+//! With `RESPONSE_SHAPE_TRACKING` enabled the routing layer reduces every
+//! response of a non-admin tool to its [`structure`]: field names and the
+//! JSON types of their values, without the values themselves. The first
+//! time a container sees a structure it is recorded under its
+//! [`fingerprint`], the deployment that returned it (`DEPLOYMENT_VERSION`)
+//! and when. With `RESPONSE_SHAPES_TABLE` set the record goes to `DynamoDB`,
+//! where the first deployment to return a structure keeps it, so
+//! `admin_tool_versions` can show integrators whether a tool's output
+//! changed between deployments; otherwise only this container's shapes are
+//! known.
+//!
+//! Optional fields make distinct structures, as a response with the field
+//! and one without it differ; a new structure in a deployment is a prompt
+//! to look, not proof of a breaking change. Null values and `_meta` are
+//! left out, since they come and go with the data.
+
+use crate::aws::sdk_config;
+use crate::config::config;
+use crate::metrics::META_KEY;
+use crate::models::error::AppError;
+use crate::models::{ResponseShape, ToolShapeHistory};
+use aws_lc_rs::digest;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::Utc;
+use lambda_runtime::tracing::{debug, info, warn};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use tokio::sync::OnceCell;
+
+/// Partition key of the shapes table
+pub const TOOL_KEY: &str = "tool";
+
+/// Sort key of the shapes table
+pub const FINGERPRINT_KEY: &str = "fingerprint";
+
+/// Most structures tracked per tool in a container, so a tool whose output
+/// varies widely cannot grow the changelog without bound
+const MAX_SHAPES_PER_TOOL: usize = 32;
+
+static DYNAMODB_CLIENT: OnceCell<aws_sdk_dynamodb::Client> = OnceCell::const_new();
+
+/// Structures seen in this container, per tool
+static SEEN: LazyLock<Mutex<HashMap<&'static str, Vec<ResponseShape>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The structure of `value`, without its values.
+///
+/// Objects keep their field names, lists hold each distinct item structure
+/// once, sorted so that item order does not change the structure, and other
+/// values become the name of their JSON type.
+#[must_use]
+pub fn structure(value: &Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .filter(|(key, value)| *key != META_KEY && !value.is_null())
+                .map(|(key, value)| (key.clone(), structure(value)))
+                .collect(),
+        ),
+        Value::Array(items) => {
+            let mut items: Vec<Value> = items.iter().map(structure).collect();
+            items.sort_by_cached_key(Value::to_string);
+            items.dedup();
+            Value::Array(items)
+        }
+        Value::String(_) => json!("string"),
+        Value::Number(_) => json!("number"),
+        Value::Bool(_) => json!("boolean"),
+        Value::Null => json!("null"),
+    }
+}
+
+/// Hash of a structure, as 16 hex digits.
+#[must_use]
+pub fn fingerprint(structure: &Value) -> String {
+    let hash = digest::digest(&digest::SHA256, structure.to_string().as_bytes());
+    hash.as_ref()[..8]
+        .iter()
+        .fold(String::with_capacity(16), |mut fingerprint, byte| {
+            let _ = write!(fingerprint, "{byte:02x}");
+            fingerprint
+        })
+}
+
+/// Records the structure of `response` from `tool`, if this container has
+/// not seen it before.
+///
+/// Failures to write the shapes table are logged rather than failing the
+/// call.
+pub async fn observe(tool: &'static str, response: &Value) {
+    let structure = structure(response);
+    let fingerprint = fingerprint(&structure);
+    let Some(shape) = remember(
+        SEEN.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(tool)
+            .or_default(),
+        structure,
+        fingerprint,
+    ) else {
+        return;
+    };
+
+    let Some(table) = config().shapes.table.clone() else {
+        debug!(tool, fingerprint = %shape.fingerprint, "New response shape in this container");
+        return;
+    };
+    match put_shape(&table, tool, &shape).await {
+        Ok(true) => info!(
+            tool,
+            fingerprint = %shape.fingerprint,
+            version = %shape.version,
+            "New tool response shape"
+        ),
+        // An earlier deployment or another container recorded it first
+        Ok(false) => {}
+        Err(e) => warn!(tool, error = %e, "Failed to record tool response shape"),
+    }
+}
+
+/// Adds a new structure to the `shapes` seen for a tool, returning it unless
+/// it was seen already or the tool has too many.
+fn remember(
+    shapes: &mut Vec<ResponseShape>,
+    structure: Value,
+    fingerprint: String,
+) -> Option<ResponseShape> {
+    if shapes.len() >= MAX_SHAPES_PER_TOOL
+        || shapes.iter().any(|shape| shape.fingerprint == fingerprint)
+    {
+        return None;
+    }
+    let shape = ResponseShape {
+        fingerprint,
+        version: config().shapes.version.clone(),
+        first_seen: Utc::now().to_rfc3339(),
+        structure,
+    };
+    shapes.push(shape.clone());
+    Some(shape)
+}
+
+/// The structures `tool` has returned, in the order they were first seen.
+///
+/// # Errors
+///
+/// Returns `AppError::ShapesError` if the shapes table cannot be read.
+pub async fn history(tool: &str) -> Result<ToolShapeHistory, AppError> {
+    let mut shapes = match config().shapes.table.as_deref() {
+        Some(table) => query_shapes(table, tool).await?,
+        None => SEEN
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(tool)
+            .cloned()
+            .unwrap_or_default(),
+    };
+    shapes.sort_by(|a, b| a.first_seen.cmp(&b.first_seen));
+
+    let version = &config().shapes.version;
+    let changed = shapes.iter().any(|shape| &shape.version == version)
+        && shapes.iter().any(|shape| &shape.version != version);
+    Ok(ToolShapeHistory {
+        tool: tool.to_string(),
+        changed,
+        shapes,
+    })
+}
+
+/// Writes `shape` unless the table already has it; returns whether it was
+/// new.
+async fn put_shape(table: &str, tool: &str, shape: &ResponseShape) -> Result<bool, AppError> {
+    let result = dynamodb_client()
+        .await
+        .put_item()
+        .table_name(table)
+        .item(TOOL_KEY, AttributeValue::S(tool.to_string()))
+        .item(
+            FINGERPRINT_KEY,
+            AttributeValue::S(shape.fingerprint.clone()),
+        )
+        .item("version", AttributeValue::S(shape.version.clone()))
+        .item("first_seen", AttributeValue::S(shape.first_seen.clone()))
+        .item("structure", AttributeValue::S(shape.structure.to_string()))
+        .condition_expression("attribute_not_exists(#fingerprint)")
+        .expression_attribute_names("#fingerprint", FINGERPRINT_KEY)
+        .send()
+        .await;
+    match result {
+        Ok(_) => Ok(true),
+        Err(e)
+            if e.as_service_error()
+                .is_some_and(PutItemError::is_conditional_check_failed_exception) =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(AppError::ShapesError(format!(
+            "DynamoDB PutItem failed: {e}"
+        ))),
+    }
+}
+
+async fn query_shapes(table: &str, tool: &str) -> Result<Vec<ResponseShape>, AppError> {
+    debug!(table, tool, "Reading tool response shapes");
+    let output = dynamodb_client()
+        .await
+        .query()
+        .table_name(table)
+        .key_condition_expression("#tool = :tool")
+        .expression_attribute_names("#tool", TOOL_KEY)
+        .expression_attribute_values(":tool", AttributeValue::S(tool.to_string()))
+        .send()
+        .await
+        .map_err(|e| AppError::ShapesError(format!("DynamoDB Query failed: {e}")))?;
+    Ok(output.items().iter().filter_map(shape_from_item).collect())
+}
+
+/// Reads a shape from a `DynamoDB` item, skipping malformed items.
+fn shape_from_item(item: &HashMap<String, AttributeValue>) -> Option<ResponseShape> {
+    let text = |name: &str| item.get(name)?.as_s().ok().cloned();
+    Some(ResponseShape {
+        fingerprint: text(FINGERPRINT_KEY)?,
+        version: text("version")?,
+        first_seen: text("first_seen")?,
+        structure: serde_json::from_str(&text("structure")?).ok()?,
+    })
+}
+
+async fn dynamodb_client() -> &'static aws_sdk_dynamodb::Client {
+    DYNAMODB_CLIENT
+        .get_or_init(|| async { aws_sdk_dynamodb::Client::new(sdk_config().await) })
+        .await
+}
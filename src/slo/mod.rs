@@ -0,0 +1,136 @@
+//! Service level objectives per tool.
+//!
+//! Each tool in the [`crate::registry`] declares an [`Slo`]: the share of
+//! calls that must succeed, and the share of successful calls that must
+//! answer within a latency threshold. Every call is a good or bad event
+//! against each objective; calls rejected with a request error (`E1xxx`, see
+//! [`crate::catalog`]) are the caller's to fix and count against neither.
+//!
+//! Events are counted in-process with the other metrics (see
+//! [`crate::metrics`]) and logged as an `SLO event` line, which carries the
+//! share of the error budget the event burns: `1 / (1 - objective)` for a
+//! bad event and `0` for a good one. Averaged over a window, that is the
+//! rate the budget burns at (1 means it runs out exactly at the end of the
+//! SLO period), which the `CloudWatch` alarms in `iac/lambda.tf` page on per
+//! tool.
+
+use crate::metrics;
+use lambda_runtime::Diagnostic;
+use lambda_runtime::tracing::info;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Prefix of the codes of request errors, which do not count against SLOs
+const REQUEST_ERROR_PREFIX: &str = "[E1";
+
+/// A tool's availability and latency objectives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slo {
+    /// Share of calls that must succeed, e.g. `0.995`
+    pub availability: f64,
+    /// Latency successful calls should answer within
+    pub latency: Duration,
+    /// Share of successful calls that must answer within `latency`
+    pub latency_target: f64,
+}
+
+impl Slo {
+    /// Tools answered in process, without upstream requests
+    pub const IN_PROCESS: Self = Self {
+        availability: 0.999,
+        latency: Duration::from_millis(250),
+        latency_target: 0.99,
+    };
+    /// Tools that make a few upstream requests
+    pub const UPSTREAM: Self = Self {
+        availability: 0.995,
+        latency: Duration::from_secs(2),
+        latency_target: 0.95,
+    };
+    /// Tools that wait on model inference, queries or many upstream requests
+    pub const LONG_RUNNING: Self = Self {
+        availability: 0.99,
+        latency: Duration::from_secs(15),
+        latency_target: 0.9,
+    };
+
+    /// The objective `sli` is measured against.
+    #[must_use]
+    pub const fn objective(&self, sli: Sli) -> f64 {
+        match sli {
+            Sli::Availability => self.availability,
+            Sli::Latency => self.latency_target,
+        }
+    }
+}
+
+/// What an objective measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Sli {
+    /// Whether calls succeed
+    Availability,
+    /// Whether successful calls answer in time
+    Latency,
+}
+
+impl Sli {
+    /// Every indicator.
+    pub const ALL: [Self; 2] = [Self::Availability, Self::Latency];
+
+    /// The indicator's name in metrics and logs.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Availability => "availability",
+            Self::Latency => "latency",
+        }
+    }
+}
+
+/// The share of the error budget for `objective` that `bad` of `total`
+/// events burn, relative to burning it evenly over the SLO period.
+#[must_use]
+pub fn burn_rate(objective: f64, bad: u64, total: u64) -> f64 {
+    if total == 0 || objective >= 1.0 {
+        return 0.0;
+    }
+    let as_f64 = |count: u64| f64::from(u32::try_from(count).unwrap_or(u32::MAX));
+    as_f64(bad) / as_f64(total) / (1.0 - objective)
+}
+
+/// The events a call that returned `result` after `latency` counts against
+/// `slo`, as whether each was good; empty for request errors.
+#[must_use]
+pub fn events(
+    slo: &Slo,
+    result: &Result<Value, Diagnostic>,
+    latency: Duration,
+) -> Vec<(Sli, bool)> {
+    match result {
+        Ok(_) => vec![
+            (Sli::Availability, true),
+            (Sli::Latency, latency <= slo.latency),
+        ],
+        Err(diagnostic) if diagnostic.error_message.starts_with(REQUEST_ERROR_PREFIX) => Vec::new(),
+        Err(_) => vec![(Sli::Availability, false)],
+    }
+}
+
+/// Counts and logs the events a call to `tool` counts against `slo`.
+pub fn observe(
+    tool: &'static str,
+    slo: &Slo,
+    result: &Result<Value, Diagnostic>,
+    latency: Duration,
+) {
+    for (sli, good) in events(slo, result, latency) {
+        metrics::observe_slo(tool, sli, good);
+        info!(
+            tool,
+            sli = sli.label(),
+            good,
+            burn = burn_rate(slo.objective(sli), u64::from(!good), 1),
+            "SLO event"
+        );
+    }
+}
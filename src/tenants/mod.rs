@@ -0,0 +1,275 @@
+//! Per-tenant overlays on the base configuration.
+//!
+//! Several teams can share one gateway, each signing in through its own
+//! tenant. The caller's tenant is read from the token claim named by
+//! `TENANT_CLAIM` (the Entra ID `tid` by default), and `TENANT_OVERLAYS`
+//! holds a [`TenantOverlay`] per tenant ID, for example:
+//!
+//! ```json
+//! {"7f3c...": {"enabled_tools": ["get_weather", "get_public_holidays"],
+//!              "tool_concurrency": {"get_weather": 4},
+//!              "api_keys": {"open_meteo": "arn:aws:secretsmanager:...:secret:team-a/open-meteo"},
+//!              "units": "imperial"}}
+//! ```
+//!
+//! An overlay is merged over the base configuration per call:
+//! - `enabled_tools` narrows the tools the tenant may call; tools switched
+//!   off by an administrator stay off
+//! - `tool_concurrency` bounds the tenant's concurrent calls to a tool, on
+//!   top of the tool's own limit (see [`crate::limits`])
+//! - `api_keys` names the Secrets Manager secrets holding the tenant's keys
+//!   for upstream APIs, sent instead of calling anonymously
+//! - `units` is the default for callers whose request and profile name none
+//!   (see [`crate::defaults`])
+//! - `quota` limits the calls the tenant makes per day and month (see
+//!   [`crate::quotas`])
+//! - `trace_capture` captures a trace of every call the tenant makes (see
+//!   [`crate::capture`])
+//! - `residency` keeps the personal data of every caller of the tenant in
+//!   the region of that data residency (see [`crate::residency`])
+//! - `geofence` limits the places location-based tools serve the tenant's
+//!   callers to the markets it is licensed for (see [`crate::geofence`])
+//!
+//! Resolved settings, including the API keys read from Secrets Manager, are
+//! cached per tenant for `TENANT_CACHE_TTL_SECS`. Callers without a tenant,
+//! or whose tenant has no overlay, get the base configuration.
+
+use crate::auth::Claims;
+use crate::aws::sdk_config;
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::context;
+use crate::geofence::Geofence;
+use crate::limits::ConcurrencyLimiter;
+use crate::models::UnitSystem;
+use crate::models::error::AppError;
+use crate::quotas::Quota;
+use lambda_runtime::tracing::{debug, warn};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+use tokio::sync::{OnceCell, OwnedSemaphorePermit};
+
+/// Upstream name of the Open-Meteo APIs in `api_keys`
+pub const OPEN_METEO: &str = "open_meteo";
+
+static SECRETS_CLIENT: OnceCell<aws_sdk_secretsmanager::Client> = OnceCell::const_new();
+
+/// Resolved settings per tenant ID, shared across warm invocations
+pub(crate) static TENANT_CACHE: LazyLock<CacheStore<TenantSettings>> =
+    LazyLock::new(|| CacheStore::new("tenants", config().tenants.cache_ttl, 64));
+
+/// Concurrency limits keyed by `tenant:tool`; calls without an entry are
+/// only bounded by the tool's own limit
+static TENANT_LIMITER: LazyLock<ConcurrencyLimiter> = LazyLock::new(|| {
+    let limits = config()
+        .tenants
+        .overlays
+        .iter()
+        .flat_map(|(tenant, overlay)| {
+            overlay
+                .tool_concurrency
+                .iter()
+                .map(move |(tool, limit)| (limiter_key(tenant, tool), *limit))
+        })
+        .collect();
+    ConcurrencyLimiter::new(limits, 0, config().concurrency.queue_timeout)
+});
+
+/// A tenant's changes to the base configuration; every field is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TenantOverlay {
+    /// Tools the tenant may call; every tool if absent
+    pub enabled_tools: Option<Vec<String>>,
+    /// Concurrent calls the tenant may make to each tool, e.g. `{"get_weather": 4}`
+    pub tool_concurrency: HashMap<String, usize>,
+    /// Secrets Manager secret IDs of the tenant's upstream API keys, by upstream, e.g. `{"open_meteo": "..."}`
+    pub api_keys: HashMap<String, String>,
+    /// Units for callers whose request and profile name none
+    pub units: Option<UnitSystem>,
+    /// Daily and monthly call limits (see [`crate::quotas`])
+    pub quota: Option<Quota>,
+    /// Capture a trace of every call (see [`crate::capture`])
+    pub trace_capture: bool,
+    /// Data residency of every caller, e.g. "eu" (see [`crate::residency`])
+    pub residency: Option<String>,
+    /// Countries and areas location-based tools may serve (see [`crate::geofence`])
+    pub geofence: Option<Geofence>,
+}
+
+/// A tenant's overlay merged over the base configuration.
+#[derive(Clone, Default)]
+pub struct TenantSettings {
+    pub tenant: String,
+    /// Tools the tenant may call; every tool if `None`
+    pub enabled_tools: Option<Vec<String>>,
+    /// Default units for the tenant's callers
+    pub units: Option<UnitSystem>,
+    /// Call limits; unlimited if `None`
+    pub quota: Option<Quota>,
+    /// Whether every call is captured
+    pub trace_capture: bool,
+    /// Upstream API keys by upstream name
+    api_keys: HashMap<String, String>,
+}
+
+impl TenantSettings {
+    /// Merges `overlay` for `tenant` over the base configuration, with the
+    /// API keys it names already read.
+    #[must_use]
+    pub fn new(tenant: &str, overlay: &TenantOverlay, api_keys: HashMap<String, String>) -> Self {
+        Self {
+            tenant: tenant.to_string(),
+            enabled_tools: overlay.enabled_tools.clone(),
+            units: overlay.units,
+            quota: overlay.quota,
+            trace_capture: overlay.trace_capture,
+            api_keys,
+        }
+    }
+
+    /// Whether the tenant may call `tool`.
+    #[must_use]
+    pub fn allows(&self, tool: &str) -> bool {
+        self.enabled_tools
+            .as_ref()
+            .is_none_or(|tools| tools.iter().any(|enabled| enabled == tool))
+    }
+
+    /// Refuses calls to tools the tenant may not call.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Unauthorized` if `tool` is not enabled for the
+    /// tenant.
+    pub fn check(&self, tool: &str) -> Result<(), AppError> {
+        if self.allows(tool) {
+            return Ok(());
+        }
+        Err(AppError::Unauthorized(format!(
+            "{tool} is not enabled for this tenant"
+        )))
+    }
+
+    /// The tool arguments the tenant's defaults supply: `units`.
+    #[must_use]
+    pub fn arguments(&self) -> Map<String, Value> {
+        self.units
+            .map(|units| ("units".to_string(), json!(units)))
+            .into_iter()
+            .collect()
+    }
+
+    /// The tenant's API key for `upstream`, if it has one.
+    #[must_use]
+    pub fn api_key(&self, upstream: &str) -> Option<&str> {
+        self.api_keys.get(upstream).map(String::as_str)
+    }
+}
+
+// API keys are credentials, so they are never logged
+impl fmt::Debug for TenantSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TenantSettings")
+            .field("tenant", &self.tenant)
+            .field("enabled_tools", &self.enabled_tools)
+            .field("units", &self.units)
+            .field("quota", &self.quota)
+            .field("trace_capture", &self.trace_capture)
+            .field("api_keys", &self.api_keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The tenant named in `claims`, if any.
+#[must_use]
+pub fn tenant_id(claims: &Claims) -> Option<String> {
+    claims
+        .other
+        .get(&config().tenants.claim)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|tenant| !tenant.is_empty())
+        .map(String::from)
+}
+
+/// Settings of `tenant`, or `None` if there is no tenant or it has no
+/// overlay.
+///
+/// # Errors
+///
+/// Returns `AppError::TenantError` if an API key the overlay names cannot
+/// be read.
+pub async fn settings(tenant: Option<&str>) -> Result<Option<TenantSettings>, AppError> {
+    let config = config();
+    let Some((tenant, overlay)) =
+        tenant.and_then(|tenant| config.tenants.overlays.get_key_value(tenant))
+    else {
+        return Ok(None);
+    };
+    if let Some(settings) = TENANT_CACHE.get(tenant) {
+        return Ok(Some(settings));
+    }
+
+    debug!(tenant = %tenant, "Resolving tenant settings");
+    let mut api_keys = HashMap::new();
+    for (upstream, secret_id) in &overlay.api_keys {
+        api_keys.insert(upstream.clone(), read_secret(secret_id).await?);
+    }
+    let settings = TenantSettings::new(tenant, overlay, api_keys);
+    TENANT_CACHE.insert(tenant.as_str(), settings.clone());
+    Ok(Some(settings))
+}
+
+/// Waits for a permit for `tenant` to run `tool`.
+///
+/// Returns `None` if the tenant has no limit for the tool. The permit is
+/// released when dropped.
+///
+/// # Errors
+///
+/// Returns `AppError::TooBusy` if no permit frees up within the queue timeout.
+pub async fn acquire_permit(
+    tenant: &str,
+    tool: &str,
+) -> Result<Option<OwnedSemaphorePermit>, AppError> {
+    TENANT_LIMITER.acquire(&limiter_key(tenant, tool)).await
+}
+
+/// The current caller's tenant's API key for `upstream`, if it has one.
+///
+/// Settings are resolved before the tool runs, so a failure here only
+/// means the key has expired from the cache and cannot be read again; the
+/// call then goes ahead without it.
+pub async fn api_key(upstream: &str) -> Option<String> {
+    let tenant = context::current().tenant?;
+    match settings(Some(&tenant)).await {
+        Ok(settings) => settings?.api_key(upstream).map(String::from),
+        Err(e) => {
+            warn!(tenant = %tenant, upstream, error = %e, "Calling upstream without the tenant's API key");
+            None
+        }
+    }
+}
+
+fn limiter_key(tenant: &str, tool: &str) -> String {
+    format!("{tenant}:{tool}")
+}
+
+async fn read_secret(secret_id: &str) -> Result<String, AppError> {
+    let output = SECRETS_CLIENT
+        .get_or_init(|| async { aws_sdk_secretsmanager::Client::new(sdk_config().await) })
+        .await
+        .get_secret_value()
+        .secret_id(secret_id)
+        .send()
+        .await
+        .map_err(|e| AppError::TenantError(format!("Secrets Manager read failed: {e}")))?;
+    output
+        .secret_string()
+        .map(|secret| secret.trim().to_string())
+        .ok_or_else(|| AppError::TenantError("API key secret has no string value".to_string()))
+}
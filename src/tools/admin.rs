@@ -0,0 +1,384 @@
+use crate::admin::{self, is_admin_tool};
+use crate::async_calls::{self, DEFAULT_REPLAY_LIMIT};
+use crate::audit;
+use crate::auth::decode_claims;
+use crate::cache::ManagedCache;
+use crate::config::{config, reload_config};
+use crate::context;
+use crate::logging;
+use crate::models::error::AppError;
+use crate::models::{
+    AdminCacheStatsRequest, AdminCacheStatsResponse, AdminFlushCacheRequest,
+    AdminFlushCacheResponse, AdminReloadConfigRequest, AdminReloadConfigResponse,
+    AdminReplayFailedRequest, AdminReplayFailedResponse, AdminReplayInvocationRequest,
+    AdminReplayInvocationResponse, AdminSetLogFilterRequest, AdminSetLogFilterResponse,
+    AdminToggleToolRequest, AdminToggleToolResponse, AdminToolVersionsRequest,
+    AdminToolVersionsResponse, AdminUsageReportRequest, AdminUsageReportResponse, CacheReport,
+    FlushedCache,
+};
+use crate::registry;
+use crate::shapes;
+use crate::usage;
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use lambda_runtime::tracing::warn;
+
+/// Flushes one cache, or every cache, in this container.
+///
+/// With `prefix`, only the entries of the named cache whose keys start with
+/// it are removed, e.g. a location that was geocoded to the wrong place.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if no cache has the requested name, or
+/// a prefix is given without a cache.
+pub async fn admin_flush_cache(
+    request: AdminFlushCacheRequest,
+) -> Result<AdminFlushCacheResponse, AppError> {
+    let prefix = request
+        .prefix
+        .as_deref()
+        .filter(|prefix| !prefix.is_empty());
+    let caches = select_caches(request.cache.as_deref())?;
+    let flushed = match prefix {
+        Some(_) if caches.len() != 1 => {
+            return Err(AppError::ValidationError(
+                "A prefix can only be flushed from a named cache".to_string(),
+            ));
+        }
+        Some(prefix) => caches
+            .into_iter()
+            .map(|cache| FlushedCache {
+                name: cache.name().to_string(),
+                entries: cache.invalidate(prefix),
+            })
+            .collect(),
+        None => flush(caches),
+    };
+    warn!(caller = ?caller(), session_id = ?session_id(), prefix, caches = ?flushed, "Admin flushed caches");
+    Ok(AdminFlushCacheResponse { flushed })
+}
+
+/// Reports the size, hit rate and removals of one cache, or every cache, in
+/// this container.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if no cache has the requested name.
+pub async fn admin_cache_stats(
+    request: AdminCacheStatsRequest,
+) -> Result<AdminCacheStatsResponse, AppError> {
+    let caches = select_caches(request.cache.as_deref())?
+        .into_iter()
+        .map(|cache| CacheReport::from(cache.stats()))
+        .collect();
+    Ok(AdminCacheStatsResponse { caches })
+}
+
+/// Re-reads the configuration secret in this container.
+///
+/// Caches of keys and secrets are flushed so they are fetched again with the
+/// new settings; `flush_caches` flushes every cache. The log filter is reset
+/// to `LOG_FILTER`, replacing one set with [`admin_set_log_filter`]. See
+/// [`reload_config`] for settings that only change on a new container.
+///
+/// # Errors
+///
+/// Returns `AppError::GenericError` if the configuration secret cannot be
+/// read or is invalid, keeping the current configuration.
+pub async fn admin_reload_config(
+    request: AdminReloadConfigRequest,
+) -> Result<AdminReloadConfigResponse, AppError> {
+    let reloaded = reload_config().await?;
+    if let Err(e) = logging::set_filter(&reloaded.logging.filter) {
+        warn!(error = %e, "Ignoring invalid LOG_FILTER");
+    }
+    let flushed = if request.flush_caches {
+        flush(admin::caches())
+    } else {
+        flush(admin::secret_caches())
+    };
+    warn!(caller = ?caller(), session_id = ?session_id(), caches = ?flushed, "Admin reloaded configuration");
+    Ok(AdminReloadConfigResponse {
+        reloaded_at: Utc::now().to_rfc3339(),
+        flushed,
+    })
+}
+
+/// Switches a tool on or off in this container.
+///
+/// A disabled tool is refused with `E1006` until it is switched back on or
+/// the container is recycled; tools listed in `DISABLED_TOOLS` start off.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the tool is unknown or is an admin
+/// tool, which cannot be switched off.
+pub async fn admin_toggle_tool(
+    request: AdminToggleToolRequest,
+) -> Result<AdminToggleToolResponse, AppError> {
+    let Some(tool) = registry::find(request.tool.trim()) else {
+        return Err(AppError::ValidationError(format!(
+            "Unknown tool: {}",
+            request.tool
+        )));
+    };
+    if is_admin_tool(tool.name) {
+        return Err(AppError::ValidationError(format!(
+            "Admin tools cannot be switched off: {}",
+            tool.name
+        )));
+    }
+
+    let previously_enabled = admin::set_enabled(tool.name, request.enabled);
+    warn!(
+        caller = ?caller(),
+        session_id = ?session_id(),
+        tool = tool.name,
+        enabled = request.enabled,
+        previously_enabled,
+        "Admin toggled tool"
+    );
+    Ok(AdminToggleToolResponse {
+        tool: tool.name.to_string(),
+        enabled: request.enabled,
+        previously_enabled,
+    })
+}
+
+/// Replaces the log level filter in this container.
+///
+/// Directives such as `aws_lambda_mcp::tools::weather=debug` raise the level
+/// of one module in production without a redeploy. They last until the
+/// configuration is reloaded or the container is recycled; without a filter,
+/// `LOG_FILTER` is restored.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the directives cannot be parsed.
+pub async fn admin_set_log_filter(
+    request: AdminSetLogFilterRequest,
+) -> Result<AdminSetLogFilterResponse, AppError> {
+    let filter = request
+        .filter
+        .unwrap_or_else(|| config().logging.filter.clone())
+        .trim()
+        .to_string();
+    let previous_filter = logging::set_filter(&filter)
+        .map_err(|e| AppError::ValidationError(format!("Invalid log filter: {e}")))?;
+    warn!(
+        caller = ?caller(),
+        session_id = ?session_id(),
+        filter = %filter,
+        previous_filter = %previous_filter,
+        "Admin replaced the log filter"
+    );
+    Ok(AdminSetLogFilterResponse {
+        filter,
+        previous_filter,
+    })
+}
+
+/// Runs a recorded invocation again and compares the outcome with the
+/// recorded one.
+///
+/// The tool runs with the recorded arguments in sandbox mode, so tools that
+/// act on the outside world refuse instead (see [`crate::audit`]).
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if no unexpired invocation has the
+/// ID, or its tool is no longer registered, and `AppError::AuditError` if
+/// the audit table cannot be read.
+pub async fn admin_replay_invocation(
+    request: AdminReplayInvocationRequest,
+) -> Result<AdminReplayInvocationResponse, AppError> {
+    let invocation_id = request.invocation_id.trim();
+    let Some(record) = audit::find(invocation_id).await? else {
+        return Err(AppError::ValidationError(format!(
+            "No recorded invocation: {invocation_id}"
+        )));
+    };
+    let Some(tool) = registry::find(&record.tool).filter(|tool| !is_admin_tool(tool.name)) else {
+        return Err(AppError::ValidationError(format!(
+            "Recorded tool cannot be replayed: {}",
+            record.tool
+        )));
+    };
+
+    let replayed = audit::replay(tool, &record).await;
+    let differences = audit::diff(&record.outcome, &replayed);
+    warn!(
+        caller = ?caller(),
+        session_id = ?session_id(),
+        invocation_id,
+        tool = tool.name,
+        differences = differences.len(),
+        "Admin replayed invocation"
+    );
+    Ok(AdminReplayInvocationResponse {
+        invocation_id: record.invocation_id,
+        tool: record.tool,
+        recorded_at: record.recorded_at,
+        matches: differences.is_empty(),
+        differences,
+        recorded: record.outcome,
+        replayed,
+    })
+}
+
+/// Runs failed asynchronous calls again: those named, or the oldest
+/// recorded ones.
+///
+/// Calls that succeed are removed from the record; the others are kept with
+/// the new error and attempt count (see [`crate::async_calls`]).
+///
+/// # Errors
+///
+/// Returns `AppError::FailedCallsError` if the failed calls table cannot be
+/// read.
+pub async fn admin_replay_failed(
+    request: AdminReplayFailedRequest,
+) -> Result<AdminReplayFailedResponse, AppError> {
+    let mut calls = Vec::new();
+    let mut not_found = Vec::new();
+    if request.failure_ids.is_empty() {
+        calls = async_calls::list(request.limit.unwrap_or(DEFAULT_REPLAY_LIMIT)).await?;
+    } else {
+        for failure_id in &request.failure_ids {
+            let failure_id = failure_id.trim();
+            match async_calls::find(failure_id).await? {
+                Some(call) => calls.push(call),
+                None => not_found.push(failure_id.to_string()),
+            }
+        }
+    }
+
+    let mut replayed = Vec::with_capacity(calls.len());
+    for call in calls {
+        replayed.push(async_calls::replay(call).await);
+    }
+    warn!(
+        caller = ?caller(),
+        session_id = ?session_id(),
+        replayed = replayed.len(),
+        succeeded = replayed.iter().filter(|call| call.succeeded).count(),
+        not_found = not_found.len(),
+        "Admin replayed failed calls"
+    );
+    Ok(AdminReplayFailedResponse {
+        replayed,
+        not_found,
+    })
+}
+
+/// Reports the response shapes one tool, or every non-admin tool, has
+/// returned across deployments (see [`crate::shapes`]).
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the tool is unknown, and
+/// `AppError::ShapesError` if the shapes table cannot be read.
+pub async fn admin_tool_versions(
+    request: AdminToolVersionsRequest,
+) -> Result<AdminToolVersionsResponse, AppError> {
+    let names: Vec<&str> = match request.tool.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            let tool = registry::find(name)
+                .ok_or_else(|| AppError::ValidationError(format!("Unknown tool: {name}")))?;
+            vec![tool.name]
+        }
+        _ => registry::tools()
+            .iter()
+            .map(|tool| tool.name)
+            .filter(|name| !is_admin_tool(name))
+            .collect(),
+    };
+    let mut tools = Vec::with_capacity(names.len());
+    for name in names {
+        tools.push(shapes::history(name).await?);
+    }
+    Ok(AdminToolVersionsResponse {
+        version: config().shapes.version.clone(),
+        tools,
+    })
+}
+
+/// Reports tool calls and upstream requests per tenant and tool for a
+/// month or day (see [`crate::usage`]).
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the period is not a `YYYY-MM`
+/// month or `YYYY-MM-DD` day, and `AppError::UsageError` if the usage table
+/// cannot be read.
+pub async fn admin_usage_report(
+    request: AdminUsageReportRequest,
+) -> Result<AdminUsageReportResponse, AppError> {
+    let period = match request.period.as_deref().map(str::trim) {
+        Some(period) if !period.is_empty() => {
+            // Periods match stored days by prefix, so they must be zero-padded
+            let valid = match period.len() {
+                7 => NaiveDate::parse_from_str(&format!("{period}-01"), "%Y-%m-%d").is_ok(),
+                10 => NaiveDate::parse_from_str(period, "%Y-%m-%d").is_ok(),
+                _ => false,
+            };
+            if !valid {
+                return Err(AppError::ValidationError(format!(
+                    "Invalid period {period}; expected YYYY-MM or YYYY-MM-DD"
+                )));
+            }
+            period.to_string()
+        }
+        _ => Utc::now().format("%Y-%m").to_string(),
+    };
+    let tenant = request
+        .tenant
+        .as_deref()
+        .map(str::trim)
+        .filter(|tenant| !tenant.is_empty());
+    let tenants = usage::report(tenant, &period).await?;
+    Ok(AdminUsageReportResponse { period, tenants })
+}
+
+/// The cache called `name`, or every cache if no name is given.
+fn select_caches(name: Option<&str>) -> Result<Vec<&'static dyn ManagedCache>, AppError> {
+    let name = name.map(str::trim).filter(|name| !name.is_empty());
+    let caches: Vec<&'static dyn ManagedCache> = admin::caches()
+        .into_iter()
+        .filter(|cache| name.is_none_or(|name| cache.name() == name))
+        .collect();
+    match name {
+        Some(name) if caches.is_empty() => {
+            let known: Vec<&str> = admin::caches().iter().map(|cache| cache.name()).collect();
+            Err(AppError::ValidationError(format!(
+                "Unknown cache {name}; expected one of: {}",
+                known.join(", ")
+            )))
+        }
+        _ => Ok(caches),
+    }
+}
+
+fn flush<'a>(caches: impl IntoIterator<Item = &'a dyn ManagedCache>) -> Vec<FlushedCache> {
+    caches
+        .into_iter()
+        .map(|cache| FlushedCache {
+            name: cache.name().to_string(),
+            entries: cache.clear(),
+        })
+        .collect()
+}
+
+/// Subject of the caller's token, for the audit log.
+fn caller() -> Option<String> {
+    context::current()
+        .auth_token()
+        .and_then(|token| decode_claims(token).ok())
+        .and_then(|claims| claims.sub)
+}
+
+/// Conversation the admin call was made in, for the audit log.
+fn session_id() -> Option<String> {
+    context::current().session_id
+}
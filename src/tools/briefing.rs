@@ -0,0 +1,288 @@
+use crate::config::{DEFAULT_BRIEFING_TEMPLATE, config};
+use crate::models::error::AppError;
+use crate::models::{
+    BriefingWeather, DailyBriefingRequest, DailyBriefingResponse, Holiday, RunWorkflowResponse,
+    StepStatus, WeatherResponseV2, WorkflowStep,
+};
+use crate::sampling::sample_text;
+use crate::tools::compose::run_steps;
+use crate::tools::notification::render_template;
+use anyhow::Result;
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use lambda_runtime::tracing::{info, warn};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// Holiday look-ahead when the request does not specify one
+const DEFAULT_HOLIDAY_DAYS: u32 = 30;
+
+/// Longest holiday look-ahead, so at most two calendar years are queried
+const MAX_HOLIDAY_DAYS: u32 = 366;
+
+/// Most holidays a single year can contribute to the briefing
+const HOLIDAYS_PER_YEAR: usize = 100;
+
+/// Instructions for the client's model when it rewrites the summary
+const SUMMARY_SYSTEM_PROMPT: &str = "You rewrite daily briefings for a user. Reply with two or \
+    three friendly sentences of plain text that keep every fact, name, date and number unchanged \
+    and add nothing new.";
+
+/// Builds a daily briefing for the current user.
+///
+/// The location and holiday country default to the stored preferences of
+/// the user the interceptor identifies, which the routing layer fills in
+/// (see [`crate::defaults`]).
+/// The greeting, today's forecast and upcoming public holidays are then
+/// fetched as one composition of the `get_personalized_greeting`,
+/// `get_weather` and `get_public_holidays` tools (see [`run_steps`]), so each
+/// is authorized, limited and cached as if called directly. Sections that
+/// cannot be produced are listed in `unavailable` instead of failing the
+/// briefing, and the text summary is rendered from `BRIEFING_TEMPLATE`. When
+/// the MCP client supports sampling, its model rewrites that text in natural
+/// language (see [`crate::sampling`]); otherwise the rendered text is
+/// returned as is.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The country code is set but is not two letters
+/// - The greeting cannot be produced
+pub async fn daily_briefing(
+    request: DailyBriefingRequest,
+) -> Result<DailyBriefingResponse, AppError> {
+    let mut unavailable = Vec::new();
+    let location = non_blank(request.location);
+    let country_code = non_blank(request.country_code);
+    if let Some(code) = &country_code
+        && (code.len() != 2 || !code.bytes().all(|b| b.is_ascii_alphabetic()))
+    {
+        return Err(AppError::ValidationError(format!(
+            "Country code must be two letters (ISO 3166-1 alpha-2): {code}"
+        )));
+    }
+
+    let today = Utc::now().date_naive();
+    let holiday_days = request
+        .holiday_days
+        .unwrap_or(DEFAULT_HOLIDAY_DAYS)
+        .min(MAX_HOLIDAY_DAYS);
+    let until = today
+        .checked_add_days(Days::new(u64::from(holiday_days)))
+        .unwrap_or(today);
+
+    info!(
+        has_location = location.is_some(),
+        has_country = country_code.is_some(),
+        "Building daily briefing"
+    );
+    let steps = briefing_steps(
+        location.as_deref(),
+        country_code.as_deref(),
+        today.year(),
+        until.year(),
+    );
+    let run = run_steps(&steps).await?;
+
+    let greeting = step_output::<Value>(&run, "greeting")
+        .and_then(|output| output.get("greeting")?.as_str().map(String::from))
+        .ok_or_else(|| {
+            AppError::UserExtractionError(
+                step_error(&run, "greeting")
+                    .unwrap_or("no greeting was produced")
+                    .to_string(),
+            )
+        })?;
+
+    let weather = if location.is_some() {
+        briefing_weather(&run, &mut unavailable)
+    } else {
+        unavailable.push("weather: no location given or saved as home city".to_string());
+        None
+    };
+    let holidays = if country_code.is_some() {
+        upcoming_holidays(&run, today, until, &mut unavailable)
+    } else {
+        unavailable.push("holidays: no country code given or saved".to_string());
+        Vec::new()
+    };
+
+    let rendered = render_summary(
+        &greeting,
+        location.as_deref(),
+        weather.as_ref(),
+        &holidays,
+        today,
+    );
+    let summary = sample_text(SUMMARY_SYSTEM_PROMPT, rendered.clone())
+        .await
+        .unwrap_or(rendered);
+    Ok(DailyBriefingResponse {
+        greeting,
+        location,
+        weather,
+        holidays,
+        summary,
+        unavailable,
+    })
+}
+
+/// Declares the tool calls behind a briefing.
+fn briefing_steps(
+    location: Option<&str>,
+    country_code: Option<&str>,
+    year: i32,
+    until_year: i32,
+) -> Vec<WorkflowStep> {
+    let step = |id: &str, tool: &str, arguments: Value| WorkflowStep {
+        id: id.to_string(),
+        tool: tool.to_string(),
+        arguments: match arguments {
+            Value::Object(arguments) => arguments,
+            _ => Map::new(),
+        },
+        depends_on: Vec::new(),
+        timeout_ms: None,
+    };
+
+    let mut steps = vec![step("greeting", "get_personalized_greeting", json!({}))];
+    if let Some(location) = location {
+        steps.push(step(
+            "weather",
+            "get_weather",
+            json!({"location": location, "response_version": "2"}),
+        ));
+    }
+    if let Some(country_code) = country_code {
+        let holidays = |id: &str, year: i32| {
+            step(
+                id,
+                "get_public_holidays",
+                json!({"country_code": country_code, "year": year, "limit": HOLIDAYS_PER_YEAR}),
+            )
+        };
+        steps.push(holidays("holidays", year));
+        if until_year != year {
+            steps.push(holidays("holidays_next_year", until_year));
+        }
+    }
+    steps
+}
+
+fn step_output<T: DeserializeOwned>(run: &RunWorkflowResponse, id: &str) -> Option<T> {
+    run.steps
+        .iter()
+        .find(|step| step.id == id && step.status == StepStatus::Succeeded)
+        .and_then(|step| serde_json::from_value(step.output.clone()?).ok())
+}
+
+fn step_error<'a>(run: &'a RunWorkflowResponse, id: &str) -> Option<&'a str> {
+    run.steps
+        .iter()
+        .find(|step| step.id == id)
+        .and_then(|step| step.error.as_deref())
+}
+
+/// Today's weather from the `weather` step, noting why if there is none.
+fn briefing_weather(
+    run: &RunWorkflowResponse,
+    unavailable: &mut Vec<String>,
+) -> Option<BriefingWeather> {
+    let weather = step_output::<WeatherResponseV2>(run, "weather").and_then(|forecast| {
+        Some(BriefingWeather {
+            temperature_unit: forecast.temperature_unit,
+            today: forecast.days.into_iter().next()?,
+            current: forecast.current,
+        })
+    });
+    if weather.is_none() {
+        unavailable.push(format!(
+            "weather: {}",
+            step_error(run, "weather").unwrap_or("no forecast for today")
+        ));
+    }
+    weather
+}
+
+/// Holidays from `today` through `until` found by the holiday steps, noting
+/// any step that failed.
+fn upcoming_holidays(
+    run: &RunWorkflowResponse,
+    today: NaiveDate,
+    until: NaiveDate,
+    unavailable: &mut Vec<String>,
+) -> Vec<Holiday> {
+    let mut holidays = Vec::new();
+    for id in ["holidays", "holidays_next_year"] {
+        if !run.steps.iter().any(|step| step.id == id) {
+            continue;
+        }
+        let found = step_output::<Value>(run, id).and_then(|output| {
+            serde_json::from_value::<Vec<Holiday>>(output.get("holidays")?.clone()).ok()
+        });
+        match found {
+            Some(found) => holidays.extend(found),
+            None => unavailable.push(format!(
+                "holidays: {}",
+                step_error(run, id).unwrap_or("unexpected response")
+            )),
+        }
+    }
+    holidays.retain(|holiday| {
+        NaiveDate::parse_from_str(&holiday.date, "%Y-%m-%d")
+            .is_ok_and(|date| date >= today && date <= until)
+    });
+    holidays
+}
+
+/// Renders the briefing text from the configured template, falling back to
+/// the default template if the configured one is invalid.
+fn render_summary(
+    greeting: &str,
+    location: Option<&str>,
+    weather: Option<&BriefingWeather>,
+    holidays: &[Holiday],
+    today: NaiveDate,
+) -> String {
+    let weather_text = match (location, weather) {
+        (Some(location), Some(weather)) => format!(
+            "Today in {location}: high {}{unit}, low {}{unit}.",
+            weather.today.temperature_max,
+            weather.today.temperature_min,
+            unit = weather.temperature_unit
+        ),
+        _ => String::new(),
+    };
+    let holidays_text = if holidays.is_empty() {
+        String::new()
+    } else {
+        let names: Vec<String> = holidays
+            .iter()
+            .map(|holiday| format!("{} ({})", holiday.name, holiday.date))
+            .collect();
+        format!("Upcoming holidays: {}.", names.join(", "))
+    };
+    let variables = HashMap::from([
+        ("greeting".to_string(), greeting.to_string()),
+        ("weather".to_string(), weather_text),
+        ("holidays".to_string(), holidays_text),
+        (
+            "location".to_string(),
+            location.unwrap_or_default().to_string(),
+        ),
+        ("date".to_string(), today.to_string()),
+    ]);
+
+    let rendered = render_template(&config().briefing.template, &variables).unwrap_or_else(|e| {
+        warn!(error = %e, "Invalid BRIEFING_TEMPLATE, using the default");
+        render_template(DEFAULT_BRIEFING_TEMPLATE, &variables).unwrap_or_default()
+    });
+    // Empty sections leave gaps behind
+    rendered.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
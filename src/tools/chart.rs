@@ -0,0 +1,176 @@
+use crate::models::error::AppError;
+use crate::models::{
+    ContentBlock, ContentResponse, Daily, Temperature, WeatherChartRequest, WeatherResponse,
+};
+use crate::tools::weather::{ForecastOptions, fetch_forecast_for};
+use anyhow::Result;
+use lambda_runtime::tracing::info;
+
+const WIDTH: f64 = 480.0;
+const HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 48.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 32.0;
+const MARGIN_BOTTOM: f64 = 36.0;
+
+/// Most date labels drawn along the x axis
+const MAX_DATE_LABELS: usize = 8;
+
+const HIGH_COLOR: &str = "#d9480f";
+const LOW_COLOR: &str = "#1c7ed6";
+
+/// Renders the daily forecast for a location as an SVG temperature chart.
+///
+/// The forecast comes from the same cached lookup as `get_weather` (see
+/// [`fetch_forecast_for`]). The response holds a one-line text summary followed by an
+/// `image/svg+xml` content block plotting daily highs and lows.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The forecast cannot be fetched (see [`fetch_forecast_for`])
+/// - The forecast has no temperature data
+/// - The rendered chart is rejected by the content limits
+pub async fn render_weather_chart(
+    request: WeatherChartRequest,
+) -> Result<ContentResponse, AppError> {
+    info!("Rendering weather chart for location: {}", request.location);
+
+    let forecast = WeatherResponse::from(
+        fetch_forecast_for(&request.location, &ForecastOptions::default()).await?,
+    );
+    let unit = &forecast.daily_units.temperature_2m_max;
+    let title = format!("{} daily temperature", request.location.trim());
+    let svg = render_temperature_svg(&title, &forecast.daily, unit)?;
+
+    Ok(ContentResponse {
+        content: vec![
+            ContentBlock::text(summarize(&request.location, &forecast.daily, unit)),
+            ContentBlock::image(svg.as_bytes(), "image/svg+xml")?,
+        ],
+    })
+}
+
+/// Draws daily highs and lows as two lines on a labelled SVG chart.
+///
+/// # Errors
+///
+/// Returns `AppError::WeatherApiError` if `daily` has no temperatures.
+pub fn render_temperature_svg(title: &str, daily: &Daily, unit: &str) -> Result<String, AppError> {
+    let days = day_count(daily);
+    let Some((low, high)) = temperature_range(daily, days) else {
+        return Err(AppError::WeatherApiError(
+            "Forecast has no temperature data".to_string(),
+        ));
+    };
+    // Keep flat forecasts off the chart edges
+    let (low, high) = if high - low < 1.0 {
+        (low - 1.0, high + 1.0)
+    } else {
+        (low, high)
+    };
+
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let intervals = f64::from(u32::try_from(days.saturating_sub(1)).unwrap_or(u32::MAX));
+    let x = |day: u32| {
+        if days > 1 {
+            MARGIN_LEFT + plot_width * f64::from(day) / intervals
+        } else {
+            MARGIN_LEFT + plot_width / 2.0
+        }
+    };
+    let y = |temperature: f64| MARGIN_TOP + plot_height * (high - temperature) / (high - low);
+
+    let line = |temperatures: &[Temperature], color: &str| {
+        let points: Vec<String> = (0..)
+            .zip(temperatures.iter().take(days))
+            .map(|(day, temperature)| format!("{:.1},{:.1}", x(day), y(temperature.get())))
+            .collect();
+        format!(
+            r#"<polyline fill="none" stroke="{color}" stroke-width="2" points="{}"/>"#,
+            points.join(" ")
+        )
+    };
+
+    let label_every = days.div_ceil(MAX_DATE_LABELS).max(1);
+    let date_labels: Vec<String> = (0..)
+        .zip(daily.time.iter().take(days))
+        .step_by(label_every)
+        .map(|(day, date)| {
+            format!(
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                x(day),
+                HEIGHT - MARGIN_BOTTOM + 16.0,
+                date.format("%m-%d")
+            )
+        })
+        .collect();
+
+    let (w, h) = (WIDTH, HEIGHT);
+    let (left, right) = (MARGIN_LEFT, WIDTH - MARGIN_RIGHT);
+    let (top, bottom) = (MARGIN_TOP, HEIGHT - MARGIN_BOTTOM);
+    let (center, label_x) = (WIDTH / 2.0, MARGIN_LEFT - 6.0);
+    let title = escape_xml(title);
+    let unit = escape_xml(unit);
+    let high_line = line(&daily.temperature_2m_max, HIGH_COLOR);
+    let low_line = line(&daily.temperature_2m_min, LOW_COLOR);
+    let dates = date_labels.concat();
+
+    Ok(format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="11">
+<rect width="{w}" height="{h}" fill="white"/>
+<text x="{center}" y="20" text-anchor="middle" font-size="14">{title}</text>
+<text x="{left}" y="20" fill="{LOW_COLOR}">Low</text>
+<text x="{right}" y="20" text-anchor="end" fill="{HIGH_COLOR}">High</text>
+<line x1="{left}" y1="{top}" x2="{left}" y2="{bottom}" stroke="#868e96"/>
+<line x1="{left}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="#868e96"/>
+<text x="{label_x}" y="{top}" text-anchor="end" dominant-baseline="middle">{high:.0}{unit}</text>
+<text x="{label_x}" y="{bottom}" text-anchor="end" dominant-baseline="middle">{low:.0}{unit}</text>
+{high_line}
+{low_line}
+{dates}
+</svg>"##
+    ))
+}
+
+/// Lowest low and highest high over the first `days` days.
+fn temperature_range(daily: &Daily, days: usize) -> Option<(f64, f64)> {
+    daily
+        .temperature_2m_min
+        .iter()
+        .take(days)
+        .chain(daily.temperature_2m_max.iter().take(days))
+        .map(|temperature| temperature.get())
+        .fold(None, |range, temperature| match range {
+            None => Some((temperature, temperature)),
+            Some((low, high)) => Some((low.min(temperature), high.max(temperature))),
+        })
+}
+
+/// Number of days with a date, high and low.
+fn day_count(daily: &Daily) -> usize {
+    daily
+        .time
+        .len()
+        .min(daily.temperature_2m_max.len())
+        .min(daily.temperature_2m_min.len())
+}
+
+fn summarize(location: &str, daily: &Daily, unit: &str) -> String {
+    let days = day_count(daily);
+    match temperature_range(daily, days) {
+        Some((low, high)) => format!(
+            "Daily temperatures for {} over {days} days range from {low:.0}{unit} to {high:.0}{unit}.",
+            location.trim()
+        ),
+        None => format!("No temperature data for {}.", location.trim()),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
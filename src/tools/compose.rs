@@ -0,0 +1,422 @@
+use crate::admin;
+use crate::auth::{AUTH_TOKEN_ARG, authorize_tool_call};
+use crate::catalog;
+use crate::config::config;
+use crate::confirmation;
+use crate::context::{self, ToolContext};
+use crate::deadline;
+use crate::defaults;
+use crate::dry_run;
+use crate::identity::{IDENTITY_CONTEXT_ARG, USER_ID_ARG, USER_NAME_ARG};
+use crate::limits::acquire_tool_permit;
+use crate::metrics;
+use crate::models::error::AppError;
+use crate::models::{
+    RunStatus, RunWorkflowRequest, RunWorkflowResponse, StepResult, StepStatus, WorkflowStep,
+};
+use crate::quotas;
+use crate::registry;
+use crate::tenants;
+use crate::usage;
+use anyhow::Result;
+use lambda_runtime::tracing::{info, warn};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Registered name of this tool, which workflows may not call
+const RUN_WORKFLOW_TOOL: &str = "run_workflow";
+
+/// A step failure: its status and the reason reported to the caller.
+type StepError = (StepStatus, String);
+
+/// Part of a string argument: literal text or a `${step.field}` reference.
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Reference(&'a str),
+}
+
+/// Runs a caller-declared composition of this server's tools.
+///
+/// See [`run_steps`] for how the steps are executed.
+///
+/// # Errors
+///
+/// Returns the same errors as [`run_steps`].
+pub async fn run_workflow(request: RunWorkflowRequest) -> Result<RunWorkflowResponse, AppError> {
+    run_steps(&request.steps).await
+}
+
+/// Runs `steps` on behalf of the current caller and reports each outcome.
+///
+/// Steps form a DAG: a step runs after every step listed in its
+/// `depends_on` or referenced from its arguments, and steps run one at a time
+/// in dependency order (ties in declaration order). String arguments may
+/// reference earlier outputs as `${step.field.0.name}`; a string that is a
+/// single reference takes the referenced value with its JSON type, otherwise
+/// references are interpolated as text.
+///
+/// Each step is authorized, checked against the disabled tools and
+/// concurrency-limited like a direct call, and runs under its own timeout
+/// (`timeout_ms`, or `RUN_WORKFLOW_STEP_TIMEOUT_MS`) capped by the invocation
+/// deadline. The caller's identity and auth token
+/// come from the current [`ToolContext`], are passed to every step, and
+/// cannot be overridden by step arguments. Missing arguments get the same
+/// defaults as in a direct call (see [`crate::defaults`]). Steps of a dry run
+/// plan their side effects instead of performing them, as do steps passing
+/// `dry_run` (see [`crate::dry_run`]). Steps calling destructive tools need
+/// their own `confirmation_token`, and without one report the token
+/// confirming them in their output (see [`crate::confirmation`]). Failed
+/// steps do not abort the run: steps depending on them are skipped,
+/// independent steps still run, and every outcome is reported.
+///
+/// Tools can call this to build higher-level tools from existing ones.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the workflow is invalid: no steps or
+/// more than `RUN_WORKFLOW_MAX_STEPS`, a blank or duplicate step ID, an unknown
+/// or nested `run_workflow` tool, a dependency on an undeclared step, or a
+/// dependency cycle. Step failures are reported in the response instead.
+pub async fn run_steps(steps: &[WorkflowStep]) -> Result<RunWorkflowResponse, AppError> {
+    let order = plan(steps)?;
+    info!(steps = steps.len(), "Running tool workflow");
+
+    let context = context::current();
+    let mut results: Vec<Option<StepResult>> = vec![None; steps.len()];
+    let mut outputs: HashMap<&str, Value> = HashMap::new();
+    let mut statuses: HashMap<&str, StepStatus> = HashMap::new();
+
+    for index in order {
+        let step = &steps[index];
+        let started = Instant::now();
+        let blocker = dependencies(step)
+            .into_iter()
+            .find(|dep| statuses.get(dep) != Some(&StepStatus::Succeeded));
+        let outcome = match blocker {
+            Some(blocker) => Err((
+                StepStatus::Skipped,
+                format!("Dependency {blocker} did not succeed"),
+            )),
+            None => run_step(step, &outputs, &context).await,
+        };
+
+        let (status, output, error) = match outcome {
+            Ok(output) => (StepStatus::Succeeded, Some(output), None),
+            Err((status, error)) => {
+                warn!(
+                    step = %step.id,
+                    tool = %step.tool,
+                    status = ?status,
+                    error = %error,
+                    "Workflow step did not succeed"
+                );
+                (status, None, Some(error))
+            }
+        };
+        if let Some(output) = &output {
+            outputs.insert(&step.id, output.clone());
+        }
+        statuses.insert(&step.id, status);
+        results[index] = Some(StepResult {
+            id: step.id.clone(),
+            tool: step.tool.clone(),
+            status,
+            output,
+            error,
+            duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        });
+    }
+
+    let steps: Vec<StepResult> = results.into_iter().flatten().collect();
+    let succeeded = steps
+        .iter()
+        .filter(|step| step.status == StepStatus::Succeeded)
+        .count();
+    let status = if succeeded == steps.len() {
+        RunStatus::Succeeded
+    } else if succeeded == 0 {
+        RunStatus::Failed
+    } else {
+        RunStatus::Partial
+    };
+    info!(status = ?status, succeeded, steps = steps.len(), "Tool workflow finished");
+
+    Ok(RunWorkflowResponse { status, steps })
+}
+
+/// Validates `steps` and returns their indices in execution order.
+fn plan(steps: &[WorkflowStep]) -> Result<Vec<usize>, AppError> {
+    let max_steps = config().compose.max_steps;
+    if steps.is_empty() {
+        return Err(AppError::ValidationError(
+            "A workflow needs at least one step".to_string(),
+        ));
+    }
+    if steps.len() > max_steps {
+        return Err(AppError::ValidationError(format!(
+            "A workflow may have at most {max_steps} steps"
+        )));
+    }
+
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    for (index, step) in steps.iter().enumerate() {
+        if step.id.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Every step needs a non-blank id".to_string(),
+            ));
+        }
+        if ids.insert(step.id.as_str(), index).is_some() {
+            return Err(AppError::ValidationError(format!(
+                "Duplicate step id: {}",
+                step.id
+            )));
+        }
+        if step.tool == RUN_WORKFLOW_TOOL {
+            return Err(AppError::ValidationError(format!(
+                "Step {} cannot call {RUN_WORKFLOW_TOOL}",
+                step.id
+            )));
+        }
+        if registry::find(&step.tool).is_none() {
+            return Err(AppError::ValidationError(format!(
+                "Step {} calls an unknown tool: {}",
+                step.id, step.tool
+            )));
+        }
+    }
+
+    let mut depends_on = Vec::with_capacity(steps.len());
+    for step in steps {
+        let indices = dependencies(step)
+            .into_iter()
+            .map(|dep| {
+                ids.get(dep).copied().ok_or_else(|| {
+                    AppError::ValidationError(format!(
+                        "Step {} depends on an undeclared step: {dep}",
+                        step.id
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        depends_on.push(indices);
+    }
+
+    // Repeatedly take the first declared step whose dependencies are done
+    let mut done = vec![false; steps.len()];
+    let mut order = Vec::with_capacity(steps.len());
+    while order.len() < steps.len() {
+        let Some(next) = (0..steps.len())
+            .find(|&index| !done[index] && depends_on[index].iter().all(|&dep| done[dep]))
+        else {
+            return Err(AppError::ValidationError(
+                "Workflow steps have a dependency cycle".to_string(),
+            ));
+        };
+        done[next] = true;
+        order.push(next);
+    }
+    Ok(order)
+}
+
+/// IDs of the steps `step` waits for: its `depends_on` and every step
+/// referenced from its arguments, without duplicates.
+fn dependencies(step: &WorkflowStep) -> Vec<&str> {
+    let mut deps: Vec<&str> = step.depends_on.iter().map(String::as_str).collect();
+    for value in step.arguments.values() {
+        collect_references(value, &mut deps);
+    }
+    let mut seen = Vec::with_capacity(deps.len());
+    deps.retain(|dep| {
+        let first = !seen.contains(dep);
+        seen.push(*dep);
+        first
+    });
+    deps
+}
+
+fn collect_references<'a>(value: &'a Value, deps: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => {
+            for segment in segments(text) {
+                if let Segment::Reference(reference) = segment {
+                    deps.push(reference.split('.').next().unwrap_or_default());
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_references(item, deps)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| collect_references(field, deps)),
+        _ => {}
+    }
+}
+
+/// Splits `text` into literal text and `${...}` references.
+fn segments(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some((before, after)) = rest.split_once("${") {
+        let Some((reference, tail)) = after.split_once('}') else {
+            break;
+        };
+        if !before.is_empty() {
+            segments.push(Segment::Text(before));
+        }
+        segments.push(Segment::Reference(reference.trim()));
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+async fn run_step(
+    step: &WorkflowStep,
+    outputs: &HashMap<&str, Value>,
+    context: &ToolContext,
+) -> Result<Value, StepError> {
+    let failed = |message: String| (StepStatus::Failed, message);
+    let tool =
+        registry::find(&step.tool).ok_or_else(|| failed(format!("Unknown tool: {}", step.tool)))?;
+
+    let mut args = step
+        .arguments
+        .iter()
+        .filter(|(name, _)| {
+            ![
+                USER_ID_ARG,
+                USER_NAME_ARG,
+                AUTH_TOKEN_ARG,
+                IDENTITY_CONTEXT_ARG,
+            ]
+            .contains(&name.as_str())
+        })
+        .map(|(name, value)| Ok((name.clone(), resolve(value, outputs)?)))
+        .collect::<Result<Map<String, Value>, String>>()
+        .map_err(failed)?;
+    args.extend(context.identity_args());
+    let mut args = Value::Object(args);
+    let mut step_context = context.for_tool(tool.name);
+    step_context.dry_run |= dry_run::take(tool, &mut args)
+        .map_err(|e| failed(e.to_diagnostic("InvalidInput").error_message))?;
+    let confirmation_token = confirmation::take(tool, &mut args)
+        .map_err(|e| failed(e.to_diagnostic("InvalidInput").error_message))?;
+    defaults::apply(tool, &mut args, context).await;
+
+    authorize_tool_call(tool.name, &args)
+        .map_err(|e| failed(e.to_diagnostic("Unauthorized").error_message))?;
+    if admin::is_disabled(tool.name) {
+        return Err(failed(catalog::TOOL_DISABLED.render(tool.name)));
+    }
+    let tenant = tenants::settings(context.tenant.as_deref())
+        .await
+        .map_err(|e| failed(e.to_diagnostic("ToolError").error_message))?;
+    if let Some(tenant) = &tenant {
+        tenant
+            .check(tool.name)
+            .map_err(|e| failed(e.to_diagnostic("Unauthorized").error_message))?;
+        quotas::check(tenant, tool.name)
+            .await
+            .map_err(|e| failed(e.to_diagnostic("QuotaExceeded").error_message))?;
+    }
+    let _permit = acquire_tool_permit(tool.name)
+        .await
+        .map_err(|e| failed(e.to_diagnostic("TooBusy").error_message))?;
+    let _tenant_permit = match &tenant {
+        Some(tenant) => tenants::acquire_permit(&tenant.tenant, tool.name)
+            .await
+            .map_err(|e| failed(e.to_diagnostic("TooBusy").error_message))?,
+        None => None,
+    };
+    if let Some(deprecation) = &tool.deprecation {
+        registry::warn_call(tool.name, deprecation);
+    }
+
+    let limit = step
+        .timeout_ms
+        .map_or_else(|| config().compose.step_timeout, Duration::from_millis);
+    let limit = deadline::remaining().map_or(limit, |remaining| remaining.min(limit));
+
+    let pending_confirmation =
+        confirmation::check(tool, confirmation_token.as_deref(), &args, &step_context)
+            .await
+            .map_err(|e| failed(e.to_diagnostic("Unauthorized").error_message))?;
+    if pending_confirmation.is_some() {
+        step_context.dry_run = true;
+    }
+    usage::record_invocation(context.tenant.as_deref(), tool.name);
+    let started = Instant::now();
+    let result =
+        tokio::time::timeout(limit, context::scope(step_context, (tool.handler)(args))).await;
+    metrics::observe_call(tool.name, matches!(result, Ok(Ok(_))), started.elapsed());
+    match result {
+        Ok(Ok(mut output)) => {
+            if let Some(confirmation) =
+                pending_confirmation.and_then(|pending| pending.issue(&output))
+            {
+                confirmation::attach(&mut output, &confirmation);
+            }
+            Ok(output)
+        }
+        Ok(Err(diagnostic)) => Err(failed(diagnostic.error_message)),
+        Err(_) => Err((
+            StepStatus::TimedOut,
+            format!("Step timed out after {} ms", limit.as_millis()),
+        )),
+    }
+}
+
+/// Replaces `${...}` references in the strings of `value` with earlier outputs.
+fn resolve(value: &Value, outputs: &HashMap<&str, Value>) -> Result<Value, String> {
+    match value {
+        Value::String(text) => resolve_text(text, outputs),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| resolve(item, outputs))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(name, field)| Ok((name.clone(), resolve(field, outputs)?)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+fn resolve_text(text: &str, outputs: &HashMap<&str, Value>) -> Result<Value, String> {
+    let segments = segments(text);
+    if let [Segment::Reference(reference)] = segments.as_slice() {
+        return lookup(reference, outputs).cloned();
+    }
+
+    let mut resolved = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => resolved.push_str(text),
+            Segment::Reference(reference) => match lookup(reference, outputs)? {
+                Value::String(value) => resolved.push_str(value),
+                value => resolved.push_str(&value.to_string()),
+            },
+        }
+    }
+    Ok(Value::String(resolved))
+}
+
+/// Looks up `step.field.0.name` in the outputs of earlier steps.
+fn lookup<'a>(reference: &str, outputs: &'a HashMap<&str, Value>) -> Result<&'a Value, String> {
+    let mut path = reference.split('.');
+    let id = path.next().unwrap_or_default();
+    let pointer = path.fold(String::new(), |mut pointer, field| {
+        pointer.push('/');
+        pointer.push_str(field);
+        pointer
+    });
+    outputs
+        .get(id)
+        .and_then(|output| output.pointer(&pointer))
+        .ok_or_else(|| format!("Reference ${{{reference}}} did not resolve"))
+}
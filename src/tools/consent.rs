@@ -0,0 +1,72 @@
+use crate::audit;
+use crate::consent;
+use crate::context;
+use crate::dry_run;
+use crate::models::error::AppError;
+use crate::models::{DryRunPlan, RecordConsentRequest, RecordConsentResponse};
+use crate::residency::{self, Store};
+use anyhow::Result;
+use serde_json::{Map, json};
+
+/// Records whether the caller consents to their personal data being used
+/// (see [`crate::consent`]).
+///
+/// Consent is recorded against the user ID of a signed identity context
+/// (see [`crate::identity`]), so neither anonymous callers nor a `user_id`
+/// argument alone can record it. A dry run returns where consent would be
+/// recorded without recording it (see [`crate::dry_run`]).
+///
+/// # Errors
+///
+/// Returns `AppError::Unauthorized` if the caller's identity is not signed,
+/// `AppError::ValidationError` when replayed in the sandbox,
+/// `AppError::PreferencesError` if it cannot be stored, and
+/// `AppError::ResidencyDenied` if it cannot be stored in the region of the
+/// caller's data residency.
+pub async fn record_consent(
+    request: RecordConsentRequest,
+) -> Result<RecordConsentResponse, AppError> {
+    let context = context::current();
+    let Some(user_id) = context.verified_user_id.as_deref() else {
+        return Err(AppError::Unauthorized(
+            "Consent can only be recorded for a caller with a signed identity".to_string(),
+        ));
+    };
+
+    if dry_run::is_active() {
+        let mut details = Map::new();
+        details.insert("granted".to_string(), json!(request.granted));
+        if let Some(placement) =
+            residency::place_write(Store::Preferences, context.residency.as_deref())?
+        {
+            details.insert("table".to_string(), json!(placement.table));
+            if let Some(region) = placement.region {
+                details.insert("region".to_string(), json!(region));
+            }
+        }
+        let action = if request.granted {
+            "Record consent"
+        } else {
+            "Withdraw consent"
+        };
+        return Ok(RecordConsentResponse {
+            granted: request.granted,
+            granted_at: None,
+            plan: Some(DryRunPlan {
+                action: action.to_string(),
+                target: user_id.to_string(),
+                details,
+            }),
+        });
+    }
+
+    audit::refuse_in_sandbox("Recording consent")?;
+
+    let granted_at =
+        consent::record(user_id, context.residency.as_deref(), request.granted).await?;
+    Ok(RecordConsentResponse {
+        granted: granted_at.is_some(),
+        granted_at: granted_at.map(|at| at.to_rfc3339()),
+        plan: None,
+    })
+}
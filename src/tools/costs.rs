@@ -0,0 +1,199 @@
+use crate::aws::sdk_config;
+use crate::cache::CacheStore;
+use crate::models::costs::{CostsRequest, CostsResponse, ServiceCost};
+use crate::models::error::AppError;
+use crate::provenance;
+use anyhow::Result;
+use aws_sdk_costexplorer::Client;
+use aws_sdk_costexplorer::config::Region;
+use aws_sdk_costexplorer::types::{
+    DateInterval, Dimension, DimensionValues, Expression, Granularity, GroupDefinition,
+    GroupDefinitionType,
+};
+use chrono::{Datelike, NaiveDate, TimeDelta, Utc};
+use lambda_runtime::tracing::info;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+/// Cost Explorer is a global service served only from `us-east-1`
+const COST_EXPLORER_REGION: &str = "us-east-1";
+
+const COST_METRIC: &str = "UnblendedCost";
+
+/// Month-to-date costs keyed by filters. Cost Explorer data refreshes a few
+/// times a day and each API request is billed, so an hour is plenty fresh.
+pub(crate) static COSTS_CACHE: LazyLock<CacheStore<CostsResponse>> =
+    LazyLock::new(|| CacheStore::new("costs", Duration::from_hours(1), 32));
+
+static COST_EXPLORER_CLIENT: OnceCell<Client> = OnceCell::const_new();
+
+/// Returns month-to-date AWS spend grouped by service.
+///
+/// Results are cached per filter combination for an hour. This tool is
+/// read-only: it only calls `ce:GetCostAndUsage`.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The Cost Explorer request fails (e.g. Cost Explorer not enabled)
+/// - The response contains an unparsable amount
+pub async fn get_aws_costs(request: CostsRequest) -> Result<CostsResponse, AppError> {
+    let today = Utc::now().date_naive();
+    let start = today.with_day(1).unwrap_or(today);
+    // The end date is exclusive, so include today's partial usage
+    let end = today + TimeDelta::days(1);
+
+    let mut services: Vec<String> = request
+        .services
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    services.sort();
+    services.dedup();
+    let region = request
+        .region
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    let cache_key = format!("{start}|{}|{}", region.unwrap_or("*"), services.join(","));
+    let costs = if let Some(cached) = COSTS_CACHE.get(&cache_key) {
+        info!("Serving costs from cache");
+        provenance::record_cache(&COSTS_CACHE);
+        cached
+    } else {
+        let costs = fetch_costs(start, end, &services, region).await?;
+        provenance::record("aws:cost-explorer");
+        COSTS_CACHE.insert(cache_key, costs.clone());
+        costs
+    };
+
+    Ok(match request.top {
+        Some(top) => apply_top(costs, top),
+        None => costs,
+    })
+}
+
+/// Keeps the `top` most expensive services and recomputes the total.
+fn apply_top(costs: CostsResponse, top: usize) -> CostsResponse {
+    let services: Vec<ServiceCost> = costs.services.into_iter().take(top).collect();
+    CostsResponse {
+        total: services.iter().map(|s| s.amount).sum(),
+        services,
+        ..costs
+    }
+}
+
+async fn fetch_costs(
+    start: NaiveDate,
+    end: NaiveDate,
+    services: &[String],
+    region: Option<&str>,
+) -> Result<CostsResponse, AppError> {
+    info!(%start, %end, ?services, ?region, "Fetching costs from Cost Explorer");
+
+    let client = COST_EXPLORER_CLIENT
+        .get_or_init(|| async {
+            let config = aws_sdk_costexplorer::config::Builder::from(sdk_config().await)
+                .region(Region::new(COST_EXPLORER_REGION))
+                .build();
+            Client::from_conf(config)
+        })
+        .await;
+
+    let period = DateInterval::builder()
+        .start(start.to_string())
+        .end(end.to_string())
+        .build()
+        .map_err(|e| AppError::CostExplorerError(format!("Invalid time period: {e}")))?;
+
+    let mut filters: Vec<Expression> = Vec::new();
+    if !services.is_empty() {
+        filters.push(dimension_filter(Dimension::Service, services.to_vec()));
+    }
+    if let Some(region) = region {
+        filters.push(dimension_filter(
+            Dimension::Region,
+            vec![region.to_string()],
+        ));
+    }
+    let filter = match filters.len() {
+        0 => None,
+        1 => filters.pop(),
+        _ => Some(Expression::builder().set_and(Some(filters)).build()),
+    };
+
+    let mut by_service: Vec<ServiceCost> = Vec::new();
+    let mut currency = None;
+    let mut estimated = false;
+    let mut next_page_token: Option<String> = None;
+
+    loop {
+        let output = client
+            .get_cost_and_usage()
+            .time_period(period.clone())
+            .granularity(Granularity::Monthly)
+            .metrics(COST_METRIC)
+            .group_by(
+                GroupDefinition::builder()
+                    .r#type(GroupDefinitionType::Dimension)
+                    .key("SERVICE")
+                    .build(),
+            )
+            .set_filter(filter.clone())
+            .set_next_page_token(next_page_token.take())
+            .send()
+            .await
+            .map_err(|e| AppError::CostExplorerError(format!("GetCostAndUsage failed: {e}")))?;
+
+        for result in output.results_by_time() {
+            estimated |= result.estimated();
+            for group in result.groups() {
+                let Some(metric) = group.metrics().and_then(|m| m.get(COST_METRIC)) else {
+                    continue;
+                };
+                let amount: f64 = metric.amount().unwrap_or("0").parse().map_err(|e| {
+                    AppError::CostExplorerError(format!("Invalid cost amount: {e}"))
+                })?;
+                if currency.is_none() {
+                    currency = metric.unit().map(String::from);
+                }
+                let service = group.keys().join(" / ");
+                if let Some(existing) = by_service.iter_mut().find(|s| s.service == service) {
+                    existing.amount += amount;
+                } else {
+                    by_service.push(ServiceCost { service, amount });
+                }
+            }
+        }
+
+        match output.next_page_token() {
+            Some(token) if !token.is_empty() => next_page_token = Some(token.to_string()),
+            _ => break,
+        }
+    }
+
+    by_service.sort_by(|a, b| b.amount.total_cmp(&a.amount));
+
+    Ok(CostsResponse {
+        start: start.to_string(),
+        end: end.to_string(),
+        currency: currency.unwrap_or_else(|| "USD".to_string()),
+        total: by_service.iter().map(|s| s.amount).sum(),
+        estimated,
+        services: by_service,
+    })
+}
+
+fn dimension_filter(key: Dimension, values: Vec<String>) -> Expression {
+    Expression::builder()
+        .dimensions(
+            DimensionValues::builder()
+                .key(key)
+                .set_values(Some(values))
+                .build(),
+        )
+        .build()
+}
@@ -0,0 +1,55 @@
+use crate::declarative;
+use crate::models::declarative::{RunDeclarativeToolRequest, RunDeclarativeToolResponse};
+use crate::models::error::AppError;
+use crate::utils::schema;
+use serde_json::{Value, json};
+
+/// Calls a REST-wrapping tool defined in configuration.
+///
+/// Tools are configured via the `DECLARATIVE_TOOLS` environment variable
+/// (see [`crate::declarative`]). The arguments are validated against the
+/// tool's `input_schema` (if any) before they are substituted into its
+/// request template.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - No tool of that name is configured
+/// - The arguments are not a JSON object, fail schema validation, or cannot
+///   be substituted safely
+/// - The upstream request fails or its response cannot be mapped
+pub async fn run_declarative_tool(
+    request: RunDeclarativeToolRequest,
+) -> Result<RunDeclarativeToolResponse, AppError> {
+    let name = request.tool.trim();
+    let tool = declarative::find(name).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "No declarative tool is configured as {}",
+            request.tool
+        ))
+    })?;
+
+    let arguments = if request.arguments.is_null() {
+        Value::Object(serde_json::Map::new())
+    } else {
+        request.arguments
+    };
+    if !arguments.is_object() {
+        return Err(AppError::ValidationError(
+            "Declarative tool arguments must be a JSON object".to_string(),
+        ));
+    }
+    if let Some(schema) = tool.input_schema() {
+        schema::validate(schema, &arguments)
+            .map_err(|e| AppError::ValidationError(format!("Invalid tool arguments: {e}")))?;
+    }
+
+    let result = match tool.call(&arguments).await? {
+        result @ Value::Object(_) => result,
+        other => json!({ "value": other }),
+    };
+    Ok(RunDeclarativeToolResponse {
+        tool: name.to_string(),
+        result,
+    })
+}
@@ -0,0 +1,121 @@
+use crate::admin;
+use crate::config::config;
+use crate::limits::tool_limit;
+use crate::models::error::AppError;
+use crate::models::versioning::RESPONSE_VERSION_ARG;
+use crate::models::{
+    AuthCapabilities, CachePolicy, DescribeServerRequest, DescribeServerResponse, LoadLimits,
+    ResponseVersion, ServerBuild, ToolCapability,
+};
+use crate::registry::{self, ToolDescriptor};
+use anyhow::Result;
+use std::time::Duration;
+
+/// Describes what this deployment supports, so client platforms can
+/// configure themselves against it.
+///
+/// The description covers the enabled tools with their response versions,
+/// scopes and limits, how callers authenticate, the load limits and the
+/// cache policies, and the build serving the call.
+///
+/// Tools switched off in this container are left out.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the requested tool is unknown or
+/// switched off.
+pub async fn describe_server(
+    request: DescribeServerRequest,
+) -> Result<DescribeServerResponse, AppError> {
+    let tools = match request.tool.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            let tool = registry::find(name)
+                .filter(|tool| !admin::is_disabled(tool.name))
+                .ok_or_else(|| AppError::ValidationError(format!("Unknown tool: {name}")))?;
+            vec![capability(tool)]
+        }
+        _ => registry::tools()
+            .iter()
+            .filter(|tool| !admin::is_disabled(tool.name))
+            .map(capability)
+            .collect(),
+    };
+
+    let settings = config();
+    let mut modes = vec!["bearer_token".to_string()];
+    if settings.identity.secret_arn.is_some() || settings.identity.secret.is_some() {
+        modes.push("identity_context".to_string());
+    }
+
+    Ok(DescribeServerResponse {
+        server: ServerBuild {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            deployment: settings.shapes.version.clone(),
+        },
+        tools,
+        auth: AuthCapabilities {
+            modes,
+            identity_context_required: settings.identity.required,
+            admin_scopes: settings.auth.policy.admin_scopes().to_vec(),
+            tenant_claim: settings.tenants.claim.clone(),
+        },
+        limits: LoadLimits {
+            max_in_flight: settings.concurrency.max_in_flight,
+            queue_timeout_ms: millis(settings.concurrency.queue_timeout),
+            upstream_timeout_ms: millis(settings.timeouts.upstream),
+            max_workflow_steps: settings.compose.max_steps,
+        },
+        caches: admin::caches()
+            .into_iter()
+            .map(|cache| {
+                let stats = cache.stats();
+                CachePolicy {
+                    name: stats.name.to_string(),
+                    ttl_seconds: stats.ttl.as_secs(),
+                    max_entries: stats.max_entries,
+                }
+            })
+            .collect(),
+    })
+}
+
+fn capability(tool: &ToolDescriptor) -> ToolCapability {
+    let versioned = (tool.input_schema)()
+        .get("properties")
+        .and_then(|properties| properties.get(RESPONSE_VERSION_ARG))
+        .is_some();
+    let response_versions = if versioned {
+        ResponseVersion::ALL.to_vec()
+    } else {
+        vec![ResponseVersion::default()]
+    };
+    ToolCapability {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        response_versions: response_versions
+            .into_iter()
+            .map(|version| version.as_str().to_string())
+            .collect(),
+        paginated: tool.paginated,
+        required_scopes: config()
+            .auth
+            .policy
+            .required_scopes(tool.name)
+            .map(<[String]>::to_vec)
+            .unwrap_or_default(),
+        concurrency_limit: tool_limit(tool.name),
+        sunset: tool
+            .deprecation
+            .as_ref()
+            .map(|deprecation| deprecation.sunset.to_string()),
+        replacement: tool
+            .deprecation
+            .as_ref()
+            .and_then(|deprecation| deprecation.replacement.map(str::to_string)),
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,201 @@
+use crate::cache::{CacheStore, Fetched};
+use crate::content::{ContentFormat, ExtractOptions, extract_body};
+use crate::deadline::upstream_timeout;
+use crate::http::{HTTP_CLIENT, etag, if_none_match, read_body, send, send_error};
+use crate::models::error::AppError;
+use crate::models::feed::{FeedItem, FeedRequest, FeedResponse};
+use crate::pagination::{paginate, resolve_cursor, scope_for};
+use crate::provenance;
+use anyhow::Result;
+use feed_rs::model::{Entry, Feed, Link};
+use lambda_runtime::tracing::info;
+use reqwest::{StatusCode, Url};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Number of items returned when the request does not specify a limit
+const DEFAULT_ITEM_LIMIT: usize = 10;
+
+/// Upper bound on items returned (and cached) per feed
+const MAX_ITEM_LIMIT: usize = 50;
+
+/// Approximate token budget for each item summary
+const SUMMARY_MAX_TOKENS: usize = 120;
+
+/// Parsed feeds keyed by URL, shared across warm invocations
+pub(crate) static FEED_CACHE: LazyLock<CacheStore<FeedResponse>> =
+    LazyLock::new(|| CacheStore::new("feed", Duration::from_mins(10), 64));
+
+/// Reads an RSS or Atom feed and returns its latest items.
+///
+/// Parsed feeds are cached per URL for ten minutes, so repeated "what's new"
+/// questions within a warm container do not refetch the feed. Expired feeds
+/// are revalidated with `If-None-Match` when the server sent an `ETag`, so an
+/// unchanged feed is not downloaded again. Up to 50 items
+/// are available, paged with `cursor`/`next_cursor`.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The URL is not a valid `http`/`https` URL
+/// - The cursor is invalid or was issued for a different feed
+/// - The HTTP request fails or returns a non-success status
+/// - The response body is not a recognizable RSS/Atom/JSON feed
+pub async fn read_feed(request: FeedRequest) -> Result<FeedResponse, AppError> {
+    let url = parse_feed_url(&request.url)?;
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_ITEM_LIMIT)
+        .clamp(1, MAX_ITEM_LIMIT);
+    let scope = scope_for("read_feed", &[url.as_str()]);
+    let cursor = resolve_cursor(&scope, request.cursor.as_deref())?;
+
+    let feed = if let Some(cached) = FEED_CACHE.get(url.as_str()) {
+        info!("Serving feed from cache: {}", url);
+        provenance::record_cache(&FEED_CACHE);
+        cached
+    } else {
+        let fetched = fetch_feed(&url, FEED_CACHE.etag(url.as_str()).as_deref()).await?;
+        provenance::record(&format!("feed:{}", url.host_str().unwrap_or_default()));
+        if matches!(fetched, Fetched::NotModified) {
+            provenance::record_cache(&FEED_CACHE);
+        }
+        FEED_CACHE
+            .store_fetched(url.as_str(), fetched)
+            .ok_or_else(|| AppError::FeedError("Cached feed was evicted".to_string()))?
+    };
+
+    let page = paginate(feed.items, &scope, &cursor, limit)?;
+    Ok(FeedResponse {
+        title: feed.title,
+        link: feed.link,
+        items: page.items,
+        next_cursor: page.next_cursor,
+    })
+}
+
+/// Parses raw feed bytes into a response with at most `limit` items, newest first.
+///
+/// # Errors
+///
+/// Returns `AppError::FeedError` if the bytes are not a valid feed document.
+pub fn parse_feed(bytes: &[u8], limit: usize) -> Result<FeedResponse, AppError> {
+    let feed = feed_rs::parser::parse(bytes)
+        .map_err(|e| AppError::FeedError(format!("Failed to parse feed: {e}")))?;
+    Ok(convert_feed(feed, limit))
+}
+
+fn parse_feed_url(raw: &str) -> Result<Url, AppError> {
+    let url = Url::parse(raw.trim())
+        .map_err(|e| AppError::ValidationError(format!("Invalid feed URL: {e}")))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::ValidationError(format!(
+            "Unsupported feed URL scheme: {}",
+            url.scheme()
+        )));
+    }
+    Ok(url)
+}
+
+async fn fetch_feed(
+    url: &Url,
+    cached_etag: Option<&str>,
+) -> Result<Fetched<FeedResponse>, AppError> {
+    info!("Fetching feed: {}", url);
+
+    let request =
+        if_none_match(HTTP_CLIENT.get(url.clone()), cached_etag).timeout(upstream_timeout()?);
+    let response = send(request).await.map_err(|e| {
+        send_error(e, |e| {
+            AppError::FeedError(format!("Failed to send feed request: {e}"))
+        })
+    })?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        info!("Feed not modified: {}", url);
+        return Ok(Fetched::NotModified);
+    }
+    if !response.status().is_success() {
+        return Err(AppError::FeedError(format!(
+            "Feed returned non-success status: {}",
+            response.status()
+        )));
+    }
+
+    let etag = etag(response.headers());
+    let bytes = read_body(response)
+        .await
+        .map_err(|e| AppError::FeedError(format!("Failed to read feed body: {e}")))?;
+
+    Ok(Fetched::Modified {
+        value: parse_feed(&bytes, MAX_ITEM_LIMIT)?,
+        etag,
+    })
+}
+
+fn convert_feed(feed: Feed, limit: usize) -> FeedResponse {
+    let mut entries = feed.entries;
+    // Undated entries sort last
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.published.or(entry.updated)));
+
+    FeedResponse {
+        title: feed.title.map(|t| t.content.trim().to_string()),
+        link: preferred_link(&feed.links),
+        items: entries.into_iter().take(limit).map(convert_entry).collect(),
+        next_cursor: None,
+    }
+}
+
+fn convert_entry(entry: Entry) -> FeedItem {
+    let summary = entry
+        .summary
+        .map(|s| (s.content_type.to_string(), s.content))
+        .or_else(|| {
+            entry
+                .content
+                .and_then(|c| c.body.map(|body| (c.content_type.to_string(), body)))
+        })
+        .map(|(content_type, body)| summarize(&content_type, &body))
+        .filter(|s| !s.is_empty());
+
+    FeedItem {
+        title: entry.title.map(|t| t.content.trim().to_string()),
+        published: entry
+            .published
+            .or(entry.updated)
+            .map(|date| date.to_rfc3339()),
+        summary,
+        link: preferred_link(&entry.links),
+    }
+}
+
+/// Strips markup from an item summary and trims it to a small token budget.
+fn summarize(content_type: &str, body: &str) -> String {
+    // RSS descriptions are frequently HTML even when declared as plain text
+    let content_type = if body.contains('<') {
+        "text/html"
+    } else {
+        content_type
+    };
+
+    extract_body(
+        Some(content_type),
+        body,
+        &ExtractOptions {
+            format: ContentFormat::Text,
+            preserve_links: false,
+            max_tokens: Some(SUMMARY_MAX_TOKENS),
+        },
+    )
+    .content
+}
+
+/// Picks the `alternate` link if present, otherwise the first link.
+fn preferred_link(links: &[Link]) -> Option<String> {
+    links
+        .iter()
+        .find(|link| link.rel.as_deref() == Some("alternate"))
+        .or_else(|| links.first())
+        .map(|link| link.href.clone())
+}
@@ -0,0 +1,156 @@
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::deadline::upstream_timeout;
+use crate::geofence;
+use crate::http::{HTTP_CLIENT, read_body, send, send_error};
+use crate::models::error::AppError;
+use crate::models::holidays::{Holiday, HolidaysRequest, HolidaysResponse};
+use crate::pagination::{paginate, resolve_cursor, scope_for};
+use crate::provenance;
+use anyhow::Result;
+use chrono::{Datelike, Utc};
+use lambda_runtime::tracing::info;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Number of holidays returned when the request does not specify a limit
+const DEFAULT_HOLIDAY_LIMIT: usize = 25;
+
+/// Upper bound on holidays returned per page
+const MAX_HOLIDAY_LIMIT: usize = 100;
+
+/// Years the holidays API is queried for
+const SUPPORTED_YEARS: std::ops::RangeInclusive<i32> = 1900..=2200;
+
+/// Holidays per country and year, which do not change once published
+pub(crate) static HOLIDAY_CACHE: LazyLock<CacheStore<Vec<Holiday>>> =
+    LazyLock::new(|| CacheStore::new("holidays", Duration::from_hours(24), 64));
+
+/// A holiday as returned by the Nager.Date API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NagerHoliday {
+    date: String,
+    local_name: String,
+    name: String,
+    #[serde(default)]
+    global: bool,
+}
+
+/// Lists the public holidays of a country for a year, in date order.
+///
+/// Holidays come from the Nager.Date API (`HOLIDAYS_API_URL`) and are cached
+/// per country and year for a day. Results are paged with
+/// `cursor`/`next_cursor`.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The country code is not two letters, or the year is out of range
+/// - The cursor is invalid or was issued for a different query
+/// - The API does not know the country (`ValidationError`)
+/// - The country is outside the geofence of the caller's tenant
+///   (`GeofenceDenied`, see [`crate::geofence`])
+/// - The HTTP request fails or the response cannot be parsed
+pub async fn get_public_holidays(request: HolidaysRequest) -> Result<HolidaysResponse, AppError> {
+    let country_code = request.country_code.trim().to_ascii_uppercase();
+    if country_code.len() != 2 || !country_code.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return Err(AppError::ValidationError(format!(
+            "Country code must be two letters (ISO 3166-1 alpha-2): {}",
+            request.country_code
+        )));
+    }
+    geofence::check_country(&country_code)?;
+    let year = request.year.unwrap_or_else(|| Utc::now().year());
+    if !SUPPORTED_YEARS.contains(&year) {
+        return Err(AppError::ValidationError(format!(
+            "Year must be between {} and {}",
+            SUPPORTED_YEARS.start(),
+            SUPPORTED_YEARS.end()
+        )));
+    }
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_HOLIDAY_LIMIT)
+        .clamp(1, MAX_HOLIDAY_LIMIT);
+    let scope = scope_for("get_public_holidays", &[&country_code, &year.to_string()]);
+    let cursor = resolve_cursor(&scope, request.cursor.as_deref())?;
+
+    let key = format!("{country_code}/{year}");
+    let holidays = if let Some(cached) = HOLIDAY_CACHE.get(&key) {
+        info!("Serving holidays from cache: {}", key);
+        provenance::record_cache(&HOLIDAY_CACHE);
+        cached
+    } else {
+        let holidays = fetch_holidays(&country_code, year).await?;
+        provenance::record("nager-date:holidays");
+        HOLIDAY_CACHE.insert(key, holidays.clone());
+        holidays
+    };
+
+    let page = paginate(holidays, &scope, &cursor, limit)?;
+    Ok(HolidaysResponse {
+        country_code,
+        year,
+        holidays: page.items,
+        next_cursor: page.next_cursor,
+    })
+}
+
+/// Parses a Nager.Date `PublicHolidays` response body, sorted by date.
+///
+/// # Errors
+///
+/// Returns `AppError::HolidayError` if the body is not a list of holidays.
+pub fn parse_holidays(bytes: &[u8]) -> Result<Vec<Holiday>, AppError> {
+    let mut holidays: Vec<NagerHoliday> = serde_json::from_slice(bytes)
+        .map_err(|e| AppError::HolidayError(format!("Failed to parse holidays response: {e}")))?;
+    holidays.sort_by(|a, b| a.date.cmp(&b.date));
+
+    Ok(holidays
+        .into_iter()
+        .map(|holiday| Holiday {
+            date: holiday.date,
+            name: holiday.name,
+            local_name: holiday.local_name,
+            nationwide: holiday.global,
+        })
+        .collect())
+}
+
+async fn fetch_holidays(country_code: &str, year: i32) -> Result<Vec<Holiday>, AppError> {
+    let config = config();
+    let base_url = config.holidays.api_url.trim_end_matches('/');
+    let url = format!("{base_url}/api/v3/PublicHolidays/{year}/{country_code}");
+    info!("Fetching public holidays: {}", url);
+
+    let response = send(HTTP_CLIENT.get(&url).timeout(upstream_timeout()?))
+        .await
+        .map_err(|e| {
+            send_error(e, |e| {
+                AppError::HolidayError(format!("Failed to send holidays request: {e}"))
+            })
+        })?;
+
+    match response.status() {
+        StatusCode::NOT_FOUND => {
+            return Err(AppError::ValidationError(format!(
+                "No public holiday data for country code {country_code}"
+            )));
+        }
+        StatusCode::NO_CONTENT => return Ok(Vec::new()),
+        status if !status.is_success() => {
+            return Err(AppError::HolidayError(format!(
+                "Holidays API returned non-success status: {status}"
+            )));
+        }
+        _ => {}
+    }
+
+    let bytes = read_body(response)
+        .await
+        .map_err(|e| AppError::HolidayError(format!("Failed to read holidays body: {e}")))?;
+    parse_holidays(&bytes)
+}
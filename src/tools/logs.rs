@@ -0,0 +1,320 @@
+use crate::aws::sdk_config;
+use crate::cache::CacheStore;
+use crate::config::{LogsQueryConfig, config};
+use crate::deadline::remaining;
+use crate::models::error::AppError;
+use crate::models::logs::{QueryLogsRequest, QueryLogsResponse};
+use crate::pagination::{paginate, resolve_cursor, scope_for};
+use crate::provenance;
+use anyhow::Result;
+use aws_sdk_cloudwatchlogs::Client;
+use aws_sdk_cloudwatchlogs::types::QueryStatus;
+use chrono::{DateTime, TimeDelta, Utc};
+use lambda_runtime::tracing::{info, warn};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Rows returned when the request does not specify a limit
+const DEFAULT_ROW_LIMIT: u32 = 100;
+
+/// Default time range when no start time is given
+const DEFAULT_RANGE: TimeDelta = TimeDelta::hours(1);
+
+/// Logs Insights rejects queries longer than this
+const MAX_QUERY_CHARS: usize = 10_000;
+
+/// Logs Insights accepts at most 50 log groups per query
+const MAX_LOG_GROUPS: usize = 50;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Completed query results, so following pages skip rerunning the query
+pub(crate) static RESULTS_CACHE: LazyLock<CacheStore<QueryLogsResponse>> =
+    LazyLock::new(|| CacheStore::new("logs", Duration::from_mins(5), 16));
+
+static LOGS_CLIENT: OnceCell<Client> = OnceCell::const_new();
+
+/// Runs a `CloudWatch` Logs Insights query and returns the result rows.
+///
+/// Guardrails are applied before the query starts:
+/// - Every log group must match `QUERY_LOGS_GROUP_ALLOWLIST`
+/// - The time range may not exceed `QUERY_LOGS_MAX_RANGE_HOURS`
+/// - Row count is capped at `QUERY_LOGS_MAX_ROWS`
+/// - The query is sanitized (see [`sanitize_query`])
+///
+/// The query is polled until it completes, and stopped if it runs past
+/// `QUERY_LOGS_TIMEOUT_SECS` or the invocation deadline.
+///
+/// Rows are returned in pages of `limit`. Completed results are cached
+/// briefly so following pages do not rerun the query, and the cursor pins
+/// the resolved time range so a rerun sees the same window.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - A guardrail rejects the request
+/// - The cursor is invalid or was issued for a different query
+/// - The query fails, is cancelled, or times out
+/// - A `CloudWatch` Logs API call fails
+pub async fn query_logs(request: QueryLogsRequest) -> Result<QueryLogsResponse, AppError> {
+    let settings = &config().logs;
+    check_log_groups(settings, &request.log_groups)?;
+    let query = sanitize_query(&request.query)?;
+    let page_size = request
+        .limit
+        .unwrap_or(DEFAULT_ROW_LIMIT)
+        .clamp(1, settings.max_rows);
+
+    let scope = scope_for("query_logs", &[&request.log_groups.join("\n"), &query]);
+    let mut cursor = resolve_cursor(&scope, request.cursor.as_deref())?;
+    let (start, end) = if let Some(range) = pinned_range(&cursor.state) {
+        range
+    } else {
+        resolve_time_range(
+            settings,
+            request.start_time.as_deref(),
+            request.end_time.as_deref(),
+        )?
+    };
+    cursor.state = json!({"start": start.timestamp(), "end": end.timestamp()});
+
+    let cache_key = format!("{scope}|{}|{}", start.timestamp(), end.timestamp());
+    let results = if let Some(cached) = RESULTS_CACHE.get(&cache_key) {
+        info!("Serving Logs Insights results from cache");
+        provenance::record_cache(&RESULTS_CACHE);
+        cached
+    } else {
+        let results = run_query(settings, &request.log_groups, query, start, end).await?;
+        provenance::record("aws:cloudwatch-logs");
+        RESULTS_CACHE.insert(cache_key, results.clone());
+        results
+    };
+
+    let page = paginate(
+        results.rows,
+        &scope,
+        &cursor,
+        usize::try_from(page_size).unwrap_or(usize::MAX),
+    )?;
+    Ok(QueryLogsResponse {
+        rows: page.items,
+        next_cursor: page.next_cursor,
+        ..results
+    })
+}
+
+/// Starts a query and polls until it completes, returning up to
+/// `QUERY_LOGS_MAX_ROWS` rows.
+async fn run_query(
+    settings: &LogsQueryConfig,
+    log_groups: &[String],
+    query: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<QueryLogsResponse, AppError> {
+    info!(
+        ?log_groups,
+        %start,
+        %end,
+        "Starting Logs Insights query"
+    );
+
+    let client = LOGS_CLIENT
+        .get_or_init(|| async { Client::new(sdk_config().await) })
+        .await;
+
+    let started = client
+        .start_query()
+        .set_log_group_names(Some(log_groups.to_vec()))
+        .query_string(query)
+        .start_time(start.timestamp())
+        .end_time(end.timestamp())
+        .limit(i32::try_from(settings.max_rows).unwrap_or(i32::MAX))
+        .send()
+        .await
+        .map_err(|e| AppError::LogsQueryError(format!("Failed to start query: {e}")))?;
+
+    let query_id = started
+        .query_id()
+        .ok_or_else(|| AppError::LogsQueryError("Query ID missing from response".to_string()))?;
+
+    // Give up before the invocation deadline, not just the configured timeout
+    let timeout = Duration::from_secs(settings.timeout_secs);
+    let deadline = Instant::now() + remaining().map_or(timeout, |left| left.min(timeout));
+    loop {
+        let results = client
+            .get_query_results()
+            .query_id(query_id)
+            .send()
+            .await
+            .map_err(|e| AppError::LogsQueryError(format!("Failed to get query results: {e}")))?;
+
+        match results.status() {
+            Some(QueryStatus::Complete) => {
+                let rows: Vec<BTreeMap<String, String>> = results
+                    .results()
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            // `@ptr` is an opaque record pointer, not useful to callers
+                            .filter(|field| field.field() != Some("@ptr"))
+                            .filter_map(|field| {
+                                Some((field.field()?.to_string(), field.value()?.to_string()))
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let (records_matched, records_scanned, bytes_scanned) =
+                    results.statistics().map_or((0.0, 0.0, 0.0), |stats| {
+                        (
+                            stats.records_matched(),
+                            stats.records_scanned(),
+                            stats.bytes_scanned(),
+                        )
+                    });
+
+                info!(rows = rows.len(), "Logs Insights query completed");
+                return Ok(QueryLogsResponse {
+                    status: QueryStatus::Complete.as_str().to_string(),
+                    rows,
+                    records_matched,
+                    records_scanned,
+                    bytes_scanned,
+                    next_cursor: None,
+                });
+            }
+            Some(QueryStatus::Running | QueryStatus::Scheduled) | None => {}
+            Some(status) => {
+                return Err(AppError::LogsQueryError(format!(
+                    "Query ended with status {}",
+                    status.as_str()
+                )));
+            }
+        }
+
+        if Instant::now() >= deadline {
+            warn!(query_id, "Logs Insights query timed out, stopping it");
+            // Best effort: the query is abandoned either way
+            if let Err(e) = client.stop_query().query_id(query_id).send().await {
+                warn!(error = %e, "Failed to stop timed-out query");
+            }
+            return Err(AppError::LogsQueryError(format!(
+                "Query did not complete within {} seconds or before the invocation deadline",
+                settings.timeout_secs
+            )));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Reads the time range pinned in a cursor's state by an earlier page.
+fn pinned_range(state: &Value) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = DateTime::from_timestamp(state.get("start")?.as_i64()?, 0)?;
+    let end = DateTime::from_timestamp(state.get("end")?.as_i64()?, 0)?;
+    Some((start, end))
+}
+
+/// Validates a Logs Insights query and returns it trimmed.
+///
+/// Rejects empty or oversized queries, control characters other than
+/// newlines and tabs, and the `SOURCE` command, which would let the query
+/// select log groups outside the allowlist.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` describing the first rule violated.
+pub fn sanitize_query(query: &str) -> Result<String, AppError> {
+    let query = query.trim();
+
+    if query.is_empty() {
+        return Err(AppError::ValidationError(
+            "Query must not be empty".to_string(),
+        ));
+    }
+    if query.chars().count() > MAX_QUERY_CHARS {
+        return Err(AppError::ValidationError(format!(
+            "Query exceeds the {MAX_QUERY_CHARS} character limit"
+        )));
+    }
+    if query
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return Err(AppError::ValidationError(
+            "Query contains control characters".to_string(),
+        ));
+    }
+    let selects_source = query.split('|').any(|stage| {
+        stage
+            .split_whitespace()
+            .next()
+            .is_some_and(|command| command.eq_ignore_ascii_case("source"))
+    });
+    if selects_source {
+        return Err(AppError::ValidationError(
+            "The SOURCE command is not allowed; pass log groups via `log_groups`".to_string(),
+        ));
+    }
+
+    Ok(query.to_string())
+}
+
+fn check_log_groups(settings: &LogsQueryConfig, log_groups: &[String]) -> Result<(), AppError> {
+    if log_groups.is_empty() {
+        return Err(AppError::ValidationError(
+            "At least one log group is required".to_string(),
+        ));
+    }
+    if log_groups.len() > MAX_LOG_GROUPS {
+        return Err(AppError::ValidationError(format!(
+            "At most {MAX_LOG_GROUPS} log groups may be queried at once"
+        )));
+    }
+
+    let is_allowed = |group: &str| {
+        settings.log_group_allowlist.iter().any(|entry| {
+            entry
+                .strip_suffix('*')
+                .map_or_else(|| group == entry, |prefix| group.starts_with(prefix))
+        })
+    };
+
+    if let Some(group) = log_groups.iter().find(|group| !is_allowed(group)) {
+        return Err(AppError::ValidationError(format!(
+            "Log group is not on the query allowlist: {group}"
+        )));
+    }
+    Ok(())
+}
+
+fn resolve_time_range(
+    settings: &LogsQueryConfig,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+    let parse = |label: &str, raw: &str| {
+        DateTime::parse_from_rfc3339(raw.trim())
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| AppError::ValidationError(format!("Invalid {label}: {e}")))
+    };
+
+    let end = end.map_or_else(|| Ok(Utc::now()), |raw| parse("end_time", raw))?;
+    let start = start.map_or_else(|| Ok(end - DEFAULT_RANGE), |raw| parse("start_time", raw))?;
+
+    if start >= end {
+        return Err(AppError::ValidationError(
+            "start_time must be before end_time".to_string(),
+        ));
+    }
+    if end - start > TimeDelta::hours(i64::from(settings.max_range_hours)) {
+        return Err(AppError::ValidationError(format!(
+            "Time range exceeds the {} hour limit",
+            settings.max_range_hours
+        )));
+    }
+
+    Ok((start, end))
+}
@@ -1,5 +1,51 @@
+pub mod admin;
+pub mod briefing;
+pub mod chart;
+pub mod compose;
+pub mod consent;
+#[cfg(feature = "aws-costs")]
+pub mod costs;
+pub mod declarative;
+pub mod describe;
+pub mod feed;
+pub mod holidays;
+#[cfg(feature = "query-logs")]
+pub mod logs;
+pub mod notification;
 pub mod personalized;
+pub mod pollen;
+#[cfg(feature = "sandbox")]
+pub mod sandbox;
+#[cfg(feature = "summarize")]
+pub mod summarize;
 pub mod weather;
+#[cfg(feature = "workflows")]
+pub mod workflow;
 
+pub use admin::{
+    admin_cache_stats, admin_flush_cache, admin_reload_config, admin_replay_failed,
+    admin_replay_invocation, admin_set_log_filter, admin_toggle_tool, admin_tool_versions,
+    admin_usage_report,
+};
+pub use briefing::daily_briefing;
+pub use chart::render_weather_chart;
+pub use compose::{run_steps, run_workflow};
+pub use consent::record_consent;
+#[cfg(feature = "aws-costs")]
+pub use costs::get_aws_costs;
+pub use declarative::run_declarative_tool;
+pub use describe::describe_server;
+pub use feed::read_feed;
+pub use holidays::get_public_holidays;
+#[cfg(feature = "query-logs")]
+pub use logs::query_logs;
+pub use notification::send_notification;
 pub use personalized::get_personalized_greeting;
+pub use pollen::get_pollen_forecast;
+#[cfg(feature = "sandbox")]
+pub use sandbox::run_sandboxed_tool;
+#[cfg(feature = "summarize")]
+pub use summarize::summarize_text;
 pub use weather::get_weather;
+#[cfg(feature = "workflows")]
+pub use workflow::{get_workflow_status, start_workflow};
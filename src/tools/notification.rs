@@ -0,0 +1,273 @@
+use crate::audit;
+use crate::aws::sdk_config;
+use crate::config::{NotificationConfig, config};
+use crate::dry_run;
+use crate::effects;
+use crate::models::error::AppError;
+use crate::models::notification::{NotificationChannel, NotificationRequest, NotificationResponse};
+use crate::models::{DryRunPlan, SideEffectStatus};
+use crate::provenance;
+use anyhow::Result;
+#[cfg(feature = "email")]
+use aws_sdk_sesv2::types::{Body, Content, Destination, EmailContent, Message};
+use lambda_runtime::tracing::info;
+use serde_json::{Map, json};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use tokio::sync::OnceCell;
+
+/// Largest rendered message accepted, well under the SNS 256 KB limit
+const MAX_MESSAGE_CHARS: usize = 10_000;
+
+/// SNS rejects subjects longer than 100 characters
+const MAX_SUBJECT_CHARS: usize = 100;
+
+const DEFAULT_SUBJECT: &str = "Notification";
+
+static SNS_CLIENT: OnceCell<aws_sdk_sns::Client> = OnceCell::const_new();
+#[cfg(feature = "email")]
+static SES_CLIENT: OnceCell<aws_sdk_sesv2::Client> = OnceCell::const_new();
+
+/// Sends a notification through SNS or SES.
+///
+/// Destinations must appear on the configured allowlist, so callers can only
+/// reach topics and mailboxes the operator has approved. Invocation is also
+/// restricted by the tool scope policy (see [`crate::auth`]). A retried
+/// call returns the first call's response instead of sending the
+/// notification again (see [`crate::effects`]), and a dry run returns the
+/// notification it would send without sending it (see [`crate::dry_run`]).
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The destination is not allowlisted, or the channel is not configured
+/// - The template references a variable that was not supplied
+/// - The rendered message exceeds the size limit
+/// - A recorded call is being replayed (see [`crate::audit`])
+/// - An identical call is still sending the notification
+/// - The SNS/SES request fails
+pub async fn send_notification(
+    request: NotificationRequest,
+) -> Result<NotificationResponse, AppError> {
+    let settings = &config().notifications;
+    let destination = request.destination.trim().to_string();
+    check_destination(settings, request.channel, &destination)?;
+
+    let message = render_template(&request.message, &request.variables)?;
+    if message.trim().is_empty() {
+        return Err(AppError::ValidationError(
+            "Notification message must not be empty".to_string(),
+        ));
+    }
+    if message.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(AppError::ValidationError(format!(
+            "Rendered message exceeds the {MAX_MESSAGE_CHARS} character limit"
+        )));
+    }
+
+    let subject = request
+        .subject
+        .as_deref()
+        .map(|s| render_template(s, &request.variables))
+        .transpose()?
+        .map_or_else(
+            || DEFAULT_SUBJECT.to_string(),
+            |s| s.chars().take(MAX_SUBJECT_CHARS).collect(),
+        );
+
+    let from = match request.channel {
+        NotificationChannel::Sns => None,
+        NotificationChannel::Email => {
+            Some(settings.ses_from_address.as_deref().ok_or_else(|| {
+                AppError::NotificationError("Email sender address is not configured".to_string())
+            })?)
+        }
+    };
+
+    if dry_run::is_active() {
+        let mut details = Map::new();
+        if let Some(from) = from {
+            details.insert("from".to_string(), json!(from));
+        }
+        details.insert("subject".to_string(), json!(subject));
+        details.insert("message".to_string(), json!(message));
+        let action = match request.channel {
+            NotificationChannel::Sns => "Publish a message to an SNS topic",
+            NotificationChannel::Email => "Send an email",
+        };
+        return Ok(NotificationResponse {
+            channel: request.channel,
+            destination: destination.clone(),
+            message_id: None,
+            side_effect_status: SideEffectStatus::Planned,
+            plan: Some(DryRunPlan {
+                action: action.to_string(),
+                target: destination,
+                details,
+            }),
+        });
+    }
+
+    audit::refuse_in_sandbox("Sending a notification")?;
+
+    let channel = request.channel;
+    let (destination, subject, message) = (&destination, &subject, &message);
+    let (response, side_effect_status) = effects::run_once(
+        "send_notification",
+        request.idempotency_key.as_deref(),
+        &request,
+        || async move {
+            info!(channel = ?channel, destination = %destination, "Sending notification");
+            let message_id = match from {
+                #[cfg(feature = "email")]
+                Some(from) => send_email(from, destination, subject, message).await?,
+                #[cfg(not(feature = "email"))]
+                Some(_) => {
+                    return Err(AppError::NotificationError(
+                        "Email is not built into this binary".to_string(),
+                    ));
+                }
+                None => publish_sns(destination, subject, message).await?,
+            };
+            Ok::<_, AppError>(NotificationResponse {
+                channel,
+                destination: destination.clone(),
+                message_id,
+                side_effect_status: SideEffectStatus::Executed,
+                plan: None,
+            })
+        },
+    )
+    .await?;
+
+    Ok(NotificationResponse {
+        side_effect_status,
+        ..response
+    })
+}
+
+/// Replaces `{{name}}` placeholders with values from `variables`.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if a placeholder has no matching
+/// variable or is not closed.
+pub fn render_template<S: BuildHasher>(
+    template: &str,
+    variables: &HashMap<String, String, S>,
+) -> Result<String, AppError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some((before, after)) = rest.split_once("{{") {
+        rendered.push_str(before);
+        let (name, remainder) = after.split_once("}}").ok_or_else(|| {
+            AppError::ValidationError("Unclosed `{{` placeholder in template".to_string())
+        })?;
+        let name = name.trim();
+        let value = variables.get(name).ok_or_else(|| {
+            AppError::ValidationError(format!("Missing value for template variable `{name}`"))
+        })?;
+        rendered.push_str(value);
+        rest = remainder;
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+/// Rejects destinations that are not on the allowlist for their channel.
+fn check_destination(
+    settings: &NotificationConfig,
+    channel: NotificationChannel,
+    destination: &str,
+) -> Result<(), AppError> {
+    let allowed = match channel {
+        NotificationChannel::Sns => settings
+            .sns_topic_allowlist
+            .iter()
+            .any(|arn| arn == destination),
+        NotificationChannel::Email => {
+            let address = destination.to_ascii_lowercase();
+            settings.email_allowlist.iter().any(|entry| {
+                let entry = entry.to_ascii_lowercase();
+                if entry.starts_with('@') {
+                    address.ends_with(&entry)
+                } else {
+                    address == entry
+                }
+            })
+        }
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(AppError::ValidationError(format!(
+            "Destination is not on the notification allowlist: {destination}"
+        )))
+    }
+}
+
+async fn publish_sns(
+    topic_arn: &str,
+    subject: &str,
+    message: &str,
+) -> Result<Option<String>, AppError> {
+    let client = SNS_CLIENT
+        .get_or_init(|| async { aws_sdk_sns::Client::new(sdk_config().await) })
+        .await;
+
+    let output = client
+        .publish()
+        .topic_arn(topic_arn)
+        .subject(subject)
+        .message(message)
+        .send()
+        .await
+        .map_err(|e| AppError::NotificationError(format!("SNS publish failed: {e}")))?;
+    provenance::record("aws:sns");
+
+    Ok(output.message_id().map(String::from))
+}
+
+#[cfg(feature = "email")]
+async fn send_email(
+    from: &str,
+    to: &str,
+    subject: &str,
+    message: &str,
+) -> Result<Option<String>, AppError> {
+    let client = SES_CLIENT
+        .get_or_init(|| async { aws_sdk_sesv2::Client::new(sdk_config().await) })
+        .await;
+
+    let content = |data: &str| {
+        Content::builder()
+            .data(data)
+            .charset("UTF-8")
+            .build()
+            .map_err(|e| AppError::NotificationError(format!("Failed to build email: {e}")))
+    };
+
+    let email = EmailContent::builder()
+        .simple(
+            Message::builder()
+                .subject(content(subject)?)
+                .body(Body::builder().text(content(message)?).build())
+                .build(),
+        )
+        .build();
+
+    let output = client
+        .send_email()
+        .from_email_address(from)
+        .destination(Destination::builder().to_addresses(to).build())
+        .content(email)
+        .send()
+        .await
+        .map_err(|e| AppError::NotificationError(format!("SES send failed: {e}")))?;
+    provenance::record("aws:ses");
+
+    Ok(output.message_id().map(String::from))
+}
@@ -1,3 +1,5 @@
+use crate::context;
+use crate::memory::Interaction;
 use crate::models::error::AppError;
 use crate::models::personalized::{PersonalizedGreetingRequest, PersonalizedGreetingResponse};
 use anyhow::Result;
@@ -12,11 +14,15 @@ const DEFAULT_USER_NAME: &str = "there";
 /// - Extracts name from `user_id` (email) if available
 /// - Defaults to "there" if no user information is available
 ///
+/// With user memory enabled, a caller whose identity was verified is
+/// reminded of their latest call instead (see [`crate::memory`]).
+///
 /// # Examples
 ///
 /// With `user_name`: "Hello, John!"
 /// With `user_id`: "Hello, jane.doe!"
 /// Without user info: "Hello, there!"
+/// With a memory: "Welcome back, John — last time you asked about Sydney weather."
 ///
 /// # Errors
 ///
@@ -26,8 +32,22 @@ pub async fn get_personalized_greeting(
     request: PersonalizedGreetingRequest,
 ) -> Result<PersonalizedGreetingResponse, AppError> {
     let user_name = extract_user_name(&request);
-    let greeting = format!("Hello, {user_name}!");
-    Ok(PersonalizedGreetingResponse { greeting })
+    let last = match context::current().memory() {
+        Some(memory) => memory.recall().await,
+        None => None,
+    };
+    Ok(PersonalizedGreetingResponse {
+        greeting: greeting(&user_name, last.as_ref()),
+    })
+}
+
+/// Greets `user_name`, mentioning their `last` interaction if there is one.
+#[must_use]
+pub fn greeting(user_name: &str, last: Option<&Interaction>) -> String {
+    last.and_then(Interaction::describe).map_or_else(
+        || format!("Hello, {user_name}!"),
+        |topic| format!("Welcome back, {user_name} — last time you asked about {topic}."),
+    )
 }
 
 /// Extracts a user name from the request
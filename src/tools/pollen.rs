@@ -0,0 +1,227 @@
+use crate::cache::{CacheStore, Fetched};
+use crate::config::config;
+use crate::http::{EndpointPool, etag};
+use crate::models::error::AppError;
+use crate::models::{PollenDay, PollenLevel, PollenRequest, PollenResponse, RiskLevel};
+use crate::provenance;
+use crate::tools::weather::{cached_or_fetch, endpoint_pool, geocode_location, open_meteo_get};
+use anyhow::Result;
+use lambda_runtime::tracing::info;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Pollen types requested from the air quality API, with the concentrations
+/// (grains/m³) from which their allergy risk is moderate and high
+const POLLEN_TYPES: [(&str, f64, f64); 6] = [
+    ("alder", 10.0, 100.0),
+    ("birch", 10.0, 100.0),
+    ("olive", 10.0, 100.0),
+    ("grass", 5.0, 50.0),
+    ("mugwort", 5.0, 30.0),
+    ("ragweed", 3.0, 20.0),
+];
+
+/// Source of pollen forecasts from the air quality API (see [`crate::provenance`])
+const AIR_QUALITY_SOURCE: &str = "open-meteo:air-quality";
+
+/// Days forecast when the request does not say
+const DEFAULT_POLLEN_DAYS: u32 = 3;
+
+/// Most days the pollen forecast reaches ahead
+const MAX_POLLEN_DAYS: u32 = 4;
+
+/// Unit reported when the API leaves it out
+const DEFAULT_POLLEN_UNIT: &str = "grains/m³";
+
+/// Reported for locations without pollen data
+const UNAVAILABLE_REASON: &str = "Pollen forecasts are only available for locations in Europe";
+
+/// Air quality API endpoints (`OPEN_METEO_AIR_QUALITY_URLS`)
+static AIR_QUALITY_ENDPOINTS: LazyLock<EndpointPool> =
+    LazyLock::new(|| endpoint_pool("Open-Meteo air quality", &config().weather.air_quality_urls));
+
+/// Pollen forecasts per coordinate and length, refreshed every hour
+pub(crate) static POLLEN_CACHE: LazyLock<CacheStore<PollenForecast>> =
+    LazyLock::new(|| CacheStore::new("pollen", Duration::from_hours(1), 128));
+
+/// Hourly pollen concentrations as returned by the Open-Meteo air quality API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PollenForecast {
+    #[serde(default)]
+    pub hourly_units: BTreeMap<String, String>,
+    #[serde(default)]
+    pub hourly: HourlyPollen,
+}
+
+/// Parallel hourly lists; concentrations are `null` where the pollen model
+/// does not cover the location.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HourlyPollen {
+    #[serde(default)]
+    pub time: Vec<String>,
+    /// Concentrations per variable, e.g. `birch_pollen`
+    #[serde(flatten)]
+    pub series: BTreeMap<String, Vec<Option<f64>>>,
+}
+
+/// Forecasts pollen concentrations for a location, per day and pollen type.
+///
+/// The location is geocoded as for `get_weather`, and hourly concentrations
+/// of alder, birch, olive, grass, mugwort and ragweed pollen come from the
+/// Open-Meteo air quality API, cached per coordinate for an hour. Each day
+/// reports the peak concentration of every type and the allergy risk it
+/// poses. The pollen model only covers Europe: elsewhere the response says
+/// the forecast is not available rather than failing, and types a region
+/// lacks, such as olive in the north, are left out.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The location cannot be geocoded (`LocationNotFound`)
+/// - Open-Meteo is rate limiting (`RateLimited`)
+/// - The HTTP request fails or the response cannot be parsed
+pub async fn get_pollen_forecast(request: PollenRequest) -> Result<PollenResponse, AppError> {
+    let days = request
+        .days
+        .unwrap_or(DEFAULT_POLLEN_DAYS)
+        .clamp(1, MAX_POLLEN_DAYS);
+    let geocoded = geocode_location(&request.location).await?;
+    let (latitude, longitude) = (geocoded.place.latitude, geocoded.place.longitude);
+    let timezone = geocoded.timezone;
+
+    let key = format!("{latitude},{longitude},{timezone},{days}");
+    let forecast = cached_or_fetch(&POLLEN_CACHE, &key, move |etag| async move {
+        fetch_pollen(latitude, longitude, &timezone, days, etag.as_deref()).await
+    })
+    .await?;
+
+    let days = pollen_days(&forecast);
+    let available = days.iter().any(|day| !day.pollen.is_empty());
+    if !available {
+        info!(location = %request.location, "No pollen forecast for location");
+    }
+    Ok(PollenResponse {
+        resolved_location: geocoded.place,
+        available,
+        reason: (!available).then(|| UNAVAILABLE_REASON.to_string()),
+        unit: POLLEN_TYPES
+            .iter()
+            .find_map(|(kind, ..)| forecast.hourly_units.get(&variable(kind)))
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_POLLEN_UNIT.to_string()),
+        days: if available { days } else { Vec::new() },
+    })
+}
+
+/// Peak concentration and risk of each pollen type per day of `forecast`,
+/// in date order; types without data are left out.
+#[must_use]
+pub fn pollen_days(forecast: &PollenForecast) -> Vec<PollenDay> {
+    let hourly = &forecast.hourly;
+    let mut dates: Vec<&str> = Vec::new();
+    for time in &hourly.time {
+        let date = date_of(time);
+        if dates.last() != Some(&date) {
+            dates.push(date);
+        }
+    }
+
+    dates
+        .into_iter()
+        .map(|date| PollenDay {
+            date: date.to_string(),
+            pollen: POLLEN_TYPES
+                .iter()
+                .filter_map(|&(kind, moderate, high)| {
+                    let peak = hourly
+                        .time
+                        .iter()
+                        .zip(hourly.series.get(&variable(kind))?)
+                        .filter(|(time, _)| date_of(time) == date)
+                        .filter_map(|(_, concentration)| *concentration)
+                        .reduce(f64::max)?;
+                    Some(PollenLevel {
+                        kind: kind.to_string(),
+                        peak,
+                        risk: risk(peak, moderate, high),
+                    })
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+async fn fetch_pollen(
+    latitude: f64,
+    longitude: f64,
+    timezone: &str,
+    days: u32,
+    cached_etag: Option<&str>,
+) -> Result<Fetched<PollenForecast>, AppError> {
+    let variables: Vec<String> = POLLEN_TYPES
+        .iter()
+        .map(|(kind, ..)| variable(kind))
+        .collect();
+    let path = format!(
+        "/v1/air-quality?latitude={latitude}&longitude={longitude}&hourly={}&timezone={timezone}&forecast_days={days}",
+        variables.join(",")
+    );
+    info!("Making pollen forecast request for: {}", path);
+
+    let response = open_meteo_get(
+        &AIR_QUALITY_ENDPOINTS,
+        &path,
+        cached_etag,
+        "pollen forecast",
+        AppError::WeatherApiError,
+    )
+    .await?;
+    match response.status() {
+        StatusCode::NOT_MODIFIED => {
+            info!("Pollen forecast not modified");
+            provenance::record(AIR_QUALITY_SOURCE);
+            return Ok(Fetched::NotModified);
+        }
+        status if !status.is_success() => {
+            return Err(AppError::WeatherApiError(format!(
+                "Air quality API returned non-success status: {status}"
+            )));
+        }
+        _ => {}
+    }
+    provenance::record(AIR_QUALITY_SOURCE);
+
+    let etag = etag(response.headers());
+    let forecast: PollenForecast = response.json().await.map_err(|e| {
+        AppError::WeatherApiError(format!("Failed to parse pollen forecast response: {e}"))
+    })?;
+    Ok(Fetched::Modified {
+        value: forecast,
+        etag,
+    })
+}
+
+/// API variable holding the concentrations of a pollen type
+fn variable(kind: &str) -> String {
+    format!("{kind}_pollen")
+}
+
+/// Date part of an ISO 8601 local time such as `2026-04-02T09:00`
+fn date_of(time: &str) -> &str {
+    time.split_once('T').map_or(time, |(date, _)| date)
+}
+
+fn risk(peak: f64, moderate: f64, high: f64) -> RiskLevel {
+    if peak >= high {
+        RiskLevel::High
+    } else if peak >= moderate {
+        RiskLevel::Moderate
+    } else if peak > 0.0 {
+        RiskLevel::Low
+    } else {
+        RiskLevel::None
+    }
+}
@@ -0,0 +1,62 @@
+use crate::config::config;
+use crate::models::error::AppError;
+use crate::models::sandbox::{RunSandboxedToolRequest, RunSandboxedToolResponse};
+use crate::sandbox;
+use crate::utils::schema;
+use lambda_runtime::tracing::info;
+use serde_json::Value;
+
+/// Runs an allowlisted community tool inside the WASM sandbox.
+///
+/// Tools are configured via the `SANDBOX_TOOLS` environment variable. The
+/// arguments are validated against the tool's `input_schema` (if any)
+/// before the module runs. Modules get no host functions, so they cannot
+/// reach the network or use the Lambda's credentials (see
+/// [`crate::sandbox`]).
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The tool is not on the allowlist
+/// - The arguments are not a JSON object or fail schema validation
+/// - The module cannot be loaded, exceeds its memory or fuel, or reports an
+///   error
+pub async fn run_sandboxed_tool(
+    request: RunSandboxedToolRequest,
+) -> Result<RunSandboxedToolResponse, AppError> {
+    let name = request.tool.trim();
+    let config = config();
+    let definition = config.sandbox.tools.get(name).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "Sandboxed tool is not on the allowlist: {}",
+            request.tool
+        ))
+    })?;
+
+    let arguments = if request.arguments.is_null() {
+        Value::Object(serde_json::Map::new())
+    } else {
+        request.arguments
+    };
+    if !arguments.is_object() {
+        return Err(AppError::ValidationError(
+            "Sandboxed tool arguments must be a JSON object".to_string(),
+        ));
+    }
+    if let Some(schema) = &definition.input_schema {
+        schema::validate(schema, &arguments)
+            .map_err(|e| AppError::ValidationError(format!("Invalid tool arguments: {e}")))?;
+    }
+
+    let execution = sandbox::run(name, definition, arguments).await?;
+    info!(
+        tool = name,
+        fuel_used = execution.fuel_used,
+        "Sandboxed tool finished"
+    );
+    Ok(RunSandboxedToolResponse {
+        tool: name.to_string(),
+        result: execution.output,
+        fuel_used: execution.fuel_used,
+    })
+}
@@ -0,0 +1,137 @@
+use crate::aws::sdk_config;
+use crate::config::config;
+use crate::models::error::AppError;
+use crate::models::summarize::{SummarizeRequest, SummarizeResponse};
+use crate::provenance;
+use anyhow::Result;
+use aws_sdk_bedrockruntime::Client;
+use aws_sdk_bedrockruntime::types::{
+    ContentBlock, ConversationRole, ConverseOutput, InferenceConfiguration, Message,
+    SystemContentBlock,
+};
+use lambda_runtime::tracing::info;
+use tokio::sync::OnceCell;
+
+/// Default summary length when the request does not specify one
+const DEFAULT_MAX_TOKENS: u32 = 512;
+
+/// Low temperature keeps summaries faithful to the source text
+const TEMPERATURE: f32 = 0.2;
+
+const SYSTEM_PROMPT: &str = "You summarize text for other AI agents. Be accurate and concise, \
+    keep key facts, names and numbers, and never add information that is not in the text. \
+    Respond with the summary only.";
+
+static BEDROCK_CLIENT: OnceCell<Client> = OnceCell::const_new();
+
+/// Summarizes text using an Amazon Bedrock model.
+///
+/// The model is configured via `BEDROCK_MODEL_ID`. Guardrails are enforced
+/// before any model call is made:
+/// - Input larger than `SUMMARIZE_MAX_INPUT_CHARS` is rejected outright
+/// - Requested output length is capped at `SUMMARIZE_MAX_OUTPUT_TOKENS`
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The text is empty or exceeds the configured input size limit
+/// - The Bedrock request fails (e.g. missing model access or throttling)
+/// - The model returns no text content
+pub async fn summarize_text(request: SummarizeRequest) -> Result<SummarizeResponse, AppError> {
+    let settings = &config().summarize;
+    let text = request.text.trim();
+
+    if text.is_empty() {
+        return Err(AppError::ValidationError(
+            "Text to summarize must not be empty".to_string(),
+        ));
+    }
+
+    let input_chars = text.chars().count();
+    if input_chars > settings.max_input_chars {
+        return Err(AppError::ValidationError(format!(
+            "Input of {input_chars} characters exceeds the {} character limit",
+            settings.max_input_chars
+        )));
+    }
+
+    let max_tokens = request
+        .max_tokens
+        .unwrap_or(DEFAULT_MAX_TOKENS)
+        .clamp(1, settings.max_output_tokens);
+
+    info!(
+        model_id = %settings.model_id,
+        input_chars,
+        max_tokens,
+        "Invoking Bedrock model for summarization"
+    );
+
+    let prompt = request.instructions.as_deref().map_or_else(
+        || format!("Summarize the following text.\n\n{text}"),
+        |instructions| format!("Summarize the following text. Focus: {instructions}\n\n{text}"),
+    );
+
+    let message = Message::builder()
+        .role(ConversationRole::User)
+        .content(ContentBlock::Text(prompt))
+        .build()
+        .map_err(|e| AppError::BedrockError(format!("Failed to build request message: {e}")))?;
+
+    let output = bedrock_client()
+        .await
+        .converse()
+        .model_id(&settings.model_id)
+        .system(SystemContentBlock::Text(SYSTEM_PROMPT.to_string()))
+        .messages(message)
+        .inference_config(
+            InferenceConfiguration::builder()
+                .max_tokens(i32::try_from(max_tokens).unwrap_or(i32::MAX))
+                .temperature(TEMPERATURE)
+                .build(),
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::BedrockError(format!("Model invocation failed: {e}")))?;
+    provenance::record("aws:bedrock");
+
+    let summary = match output.output() {
+        Some(ConverseOutput::Message(message)) => message
+            .content()
+            .iter()
+            .filter_map(|block| block.as_text().ok())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+
+    if summary.trim().is_empty() {
+        return Err(AppError::BedrockError(
+            "Model returned no text content".to_string(),
+        ));
+    }
+
+    let (input_tokens, output_tokens) = output.usage().map_or((0, 0), |usage| {
+        (
+            u32::try_from(usage.input_tokens()).unwrap_or_default(),
+            u32::try_from(usage.output_tokens()).unwrap_or_default(),
+        )
+    });
+
+    info!(input_tokens, output_tokens, "Summarization completed");
+
+    Ok(SummarizeResponse {
+        summary: summary.trim().to_string(),
+        model_id: settings.model_id.clone(),
+        input_tokens,
+        output_tokens,
+        stop_reason: output.stop_reason().as_str().to_string(),
+    })
+}
+
+async fn bedrock_client() -> &'static Client {
+    BEDROCK_CLIENT
+        .get_or_init(|| async { Client::new(sdk_config().await) })
+        .await
+}
@@ -1,13 +1,101 @@
-use crate::http::HTTP_CLIENT;
+use crate::cache::{CacheStore, Fetched, Lookup};
+use crate::config::config;
+use crate::dates::{self, RelativeDate};
+use crate::deadline::upstream_timeout;
+use crate::gazetteer::{self, City};
+use crate::geofence;
+use crate::http::{
+    CircuitBreaker, EndpointPool, HTTP_CLIENT, etag, if_none_match, passthrough, retry_after,
+    send_error, send_hedged,
+};
 use crate::models::error::AppError;
 use crate::models::open_meteo::OpenMeteoResponse;
-use crate::models::{WeatherRequest, WeatherResponse};
+use crate::models::{
+    LocatedForecast, ResolvedLocation, UnitSystem, Versioned, WeatherOutput, WeatherRequest,
+};
+use crate::provenance;
+use crate::tenants;
+use crate::utils::is_timezone_name;
 use anyhow::Result;
-use lambda_runtime::tracing::info;
+use lambda_runtime::tracing::{info, warn};
+use reqwest::{Response, StatusCode};
+use serde_json::Value;
+use serde_json::value::RawValue;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
-/// Default daily weather parameters for Open-Meteo API requests
-const DEFAULT_DAILY_PARAMS: [&str; 3] =
-    ["weather_code", "temperature_2m_max", "temperature_2m_min"];
+/// Default daily weather parameters for Open-Meteo API requests; snow,
+/// precipitation and humidity feed the derived road conditions
+const DEFAULT_DAILY_PARAMS: [&str; 6] = [
+    "weather_code",
+    "temperature_2m_max",
+    "temperature_2m_min",
+    "snowfall_sum",
+    "precipitation_sum",
+    "relative_humidity_2m_max",
+];
+
+/// Default hourly parameters for Open-Meteo API requests, which feed the
+/// derived convection risk
+const DEFAULT_HOURLY_PARAMS: [&str; 2] = ["cape", "lifted_index"];
+
+/// Default current-conditions parameters for Open-Meteo API requests
+const DEFAULT_CURRENT_PARAMS: [&str; 6] = [
+    "temperature_2m",
+    "relative_humidity_2m",
+    "apparent_temperature",
+    "weather_code",
+    "wind_speed_10m",
+    "wind_direction_10m",
+];
+
+/// Source of places found by the geocoding API (see [`crate::provenance`])
+const GEOCODING_SOURCE: &str = "open-meteo:geocoding";
+
+/// Source of forecasts from the forecast API
+const FORECAST_SOURCE: &str = "open-meteo:forecast";
+
+/// Source of places resolved offline from the embedded gazetteer
+const GAZETTEER_SOURCE: &str = "gazetteer";
+
+/// Most alternate geocoding matches returned with a forecast
+const MAX_GEOCODE_ALTERNATES: usize = 10;
+
+/// Most "did you mean" names offered when a location matches nothing
+const MAX_LOCATION_SUGGESTIONS: usize = 3;
+
+/// Query parameters selecting imperial units from the forecast API, for raw
+/// forecasts passed through as is
+const IMPERIAL_UNIT_PARAMS: &str =
+    "&temperature_unit=fahrenheit&wind_speed_unit=mph&precipitation_unit=inch";
+
+/// Query parameters selecting imperial wind speeds from the forecast API;
+/// parsed forecasts keep °C, mm and cm and are converted when responding
+const IMPERIAL_WIND_PARAMS: &str = "&wind_speed_unit=mph";
+
+/// Geocoding API endpoints (`OPEN_METEO_GEOCODING_URLS`)
+static GEOCODING_ENDPOINTS: LazyLock<EndpointPool> =
+    LazyLock::new(|| endpoint_pool("Open-Meteo geocoding", &config().weather.geocoding_urls));
+
+/// Forecast API endpoints (`OPEN_METEO_FORECAST_URLS`)
+static FORECAST_ENDPOINTS: LazyLock<EndpointPool> =
+    LazyLock::new(|| endpoint_pool("Open-Meteo forecast", &config().weather.forecast_urls));
+
+/// Shared by the geocoding and forecast APIs, which are rate limited together
+static OPEN_METEO_BREAKER: LazyLock<CircuitBreaker> =
+    LazyLock::new(|| CircuitBreaker::new("Open-Meteo", 5, Duration::from_secs(30)));
+
+/// Geocoded places per location, which rarely change
+pub(crate) static GEOCODE_CACHE: LazyLock<CacheStore<Geocoded>> =
+    LazyLock::new(|| CacheStore::new("geocode", Duration::from_hours(24), 256));
+
+/// Forecasts per coordinate, in the upstream shape, refreshed every half hour
+pub(crate) static FORECAST_CACHE: LazyLock<CacheStore<OpenMeteoResponse>> =
+    LazyLock::new(|| CacheStore::new("forecast", Duration::from_mins(30), 256));
+
+/// Forecasts per coordinate as the upstream sent them, for raw mode
+pub(crate) static RAW_FORECAST_CACHE: LazyLock<CacheStore<Box<RawValue>>> =
+    LazyLock::new(|| CacheStore::new("raw_forecast", Duration::from_mins(30), 256));
 
 /// Fetches weather data from the Open-Meteo API.
 ///
@@ -16,55 +104,332 @@ const DEFAULT_DAILY_PARAMS: [&str; 3] =
 /// 2. Using sensible defaults for weather parameters
 /// 3. Automatically handling timezone detection
 ///
+/// Geocoding results and forecasts are cached. If Open-Meteo rate limits us
+/// (HTTP 429), its `Retry-After` opens a circuit breaker so further calls fail
+/// fast, and expired cache entries are served instead when `WEATHER_SERVE_STALE`
+/// is enabled. Each API may have several regional endpoints, which are failed
+/// over between by health and latency.
+///
 /// # Errors
 ///
 /// This function will return an error if:
 /// - The HTTP request to geocode the location fails
-/// - No locations are found for the provided query
+/// - No place matches the location or its spelling variants (`LocationNotFound`,
+///   with close city names to suggest)
 /// - Failed to extract coordinates from geocoding response
 /// - The HTTP request to the Open-Meteo API fails
+/// - Open-Meteo is rate limiting and no cached entry is available (`RateLimited`)
 /// - The response from either API cannot be parsed
-pub async fn get_weather(request: WeatherRequest) -> Result<WeatherResponse, AppError> {
-    info!(
-        "Starting weather request for location: {}",
-        request.location
-    );
+/// - The timezone is not a valid IANA timezone name
+/// - The place is outside the geofence of the caller's tenant (`GeofenceDenied`)
+///
+/// The response is returned in the shape selected by `response_version`
+/// (see [`crate::models::versioning`]), with the place the location resolved
+/// to and up to `WEATHER_GEOCODE_ALTERNATES` other matches, so agents can
+/// confirm with the user when the match looks wrong. Each day carries road
+/// conditions and a severe convection risk derived from its forecast (see
+/// [`crate::derived`]). Units and timezone default to the caller's profile
+/// when their token carries one (see [`crate::context::ProfileHints`]).
+/// With `when`, only the days a phrase such as "this weekend" names are
+/// returned, counted from today in the forecast's timezone (see
+/// [`crate::dates`]); a phrase that is not understood, or names no forecast
+/// day, is refused as a `ValidationError`.
+///
+/// With `raw` set, Open-Meteo's forecast JSON is returned as is instead,
+/// only checked to be valid rather than deserialized into the forecast model
+/// (see [`crate::http::passthrough`]), which saves converting and
+/// re-serializing the whole forecast.
+pub async fn get_weather(request: WeatherRequest) -> Result<WeatherOutput, AppError> {
+    if let Some(timezone) = &request.timezone
+        && !is_timezone_name(timezone)
+    {
+        return Err(AppError::ValidationError(format!(
+            "Timezone must be an IANA timezone name such as Europe/Berlin: {timezone}"
+        )));
+    }
+    let options = ForecastOptions {
+        units: request.units,
+        timezone: request.timezone,
+    };
+    if request.raw {
+        let forecast = fetch_raw_forecast_for(&request.location, &options).await?;
+        return Ok(WeatherOutput::Raw(forecast));
+    }
+    let when = request
+        .when
+        .as_deref()
+        .map(|phrase| {
+            RelativeDate::parse(phrase, request.locale.as_deref()).map(|when| (when, phrase))
+        })
+        .transpose()?;
+    let mut forecast = fetch_located_forecast(&request.location, &options).await?;
+    if let Some((when, phrase)) = when {
+        select_days(&mut forecast.forecast, when, phrase)?;
+    }
+    Ok(WeatherOutput::Versioned(Box::new(Versioned::negotiate(
+        forecast,
+        request.response_version,
+    ))))
+}
+
+/// Narrows `forecast` to the days `when` names, counted from today in the
+/// forecast's timezone.
+fn select_days(
+    forecast: &mut OpenMeteoResponse,
+    when: RelativeDate,
+    phrase: &str,
+) -> Result<(), AppError> {
+    let days = when.resolve(dates::today_at(forecast.utc_offset_seconds));
+    let covered = match (forecast.daily.time.first(), forecast.daily.time.last()) {
+        (Some(first), Some(last)) => format!("{first} to {last}"),
+        _ => "no days".to_string(),
+    };
+    forecast.daily.retain(|date| days.contains(date));
+    if forecast.daily.time.is_empty() {
+        return Err(AppError::ValidationError(format!(
+            "\"{}\" is {days}, outside the forecast, which covers {covered}",
+            phrase.trim()
+        )));
+    }
+    info!(when = phrase, %days, "Selected forecast days");
+    Ok(())
+}
+
+/// Where a location name was geocoded to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geocoded {
+    /// The best match, whose forecast is returned
+    pub place: ResolvedLocation,
+    /// IANA timezone of the best match, or `auto` if unknown
+    pub timezone: String,
+    /// Other matches, most likely first
+    pub alternates: Vec<ResolvedLocation>,
+}
+
+impl From<&City> for Geocoded {
+    fn from(city: &City) -> Self {
+        Self {
+            place: ResolvedLocation {
+                name: city.name.to_string(),
+                country: Some(city.country.to_string()),
+                country_code: Some(city.country_code.to_string()),
+                admin_region: None,
+                population: None,
+                latitude: city.latitude,
+                longitude: city.longitude,
+                confidence: 1.0,
+            },
+            timezone: city.timezone.to_string(),
+            alternates: Vec::new(),
+        }
+    }
+}
+
+/// How a forecast is requested; by default in metric units and the
+/// location's own timezone.
+#[derive(Debug, Clone, Default)]
+pub struct ForecastOptions {
+    pub units: UnitSystem,
+    /// IANA timezone for dates and times, instead of the location's
+    pub timezone: Option<String>,
+}
+
+/// Fetches current conditions and the daily forecast for a location name.
+///
+/// The upstream response is returned as is; convert it into the response
+/// version you need.
+///
+/// # Errors
+///
+/// Returns the same errors as [`get_weather`].
+pub async fn fetch_forecast_for(
+    location: &str,
+    options: &ForecastOptions,
+) -> Result<OpenMeteoResponse, AppError> {
+    Ok(fetch_located_forecast(location, options).await?.forecast)
+}
+
+/// Fetches the forecast for a location name along with the places the name
+/// was geocoded to.
+///
+/// # Errors
+///
+/// Returns the same errors as [`get_weather`].
+pub async fn fetch_located_forecast(
+    location: &str,
+    options: &ForecastOptions,
+) -> Result<LocatedForecast, AppError> {
+    info!("Starting weather request for location: {}", location);
 
     // Get coordinates for the location
-    let (latitude, longitude, timezone) = geocode_location(&request.location).await?;
+    let geocoded = geocode_location(location).await?;
+    let timezone = options.timezone.as_deref().unwrap_or(&geocoded.timezone);
 
     // Fetch weather data
-    let weather_data = fetch_weather_data(latitude, longitude, &timezone).await?;
+    let forecast = fetch_weather_data(
+        geocoded.place.latitude,
+        geocoded.place.longitude,
+        timezone,
+        options.units,
+    )
+    .await?;
 
     info!("Successfully fetched weather data");
-    Ok(weather_data)
+    Ok(LocatedForecast {
+        forecast,
+        units: options.units,
+        resolved_location: geocoded.place,
+        alternates: geocoded.alternates,
+    })
 }
 
-/// Geocodes a location name to coordinates
-async fn geocode_location(location: &str) -> Result<(f64, f64, String), AppError> {
-    let encoded_location = urlencoding::encode(location);
-    let geocode_url = format!(
-        "https://geocoding-api.open-meteo.com/v1/search?name={encoded_location}&count=1&language=en&format=json"
-    );
+/// Fetches the forecast for a location name as the upstream sent it.
+async fn fetch_raw_forecast_for(
+    location: &str,
+    options: &ForecastOptions,
+) -> Result<Box<RawValue>, AppError> {
+    info!("Starting raw weather request for location: {}", location);
+    let geocoded = geocode_location(location).await?;
+    let timezone = options.timezone.clone().unwrap_or(geocoded.timezone);
+    let (latitude, longitude) = (geocoded.place.latitude, geocoded.place.longitude);
+    let units = options.units;
+    let key = format!("{latitude},{longitude},{timezone},{units:?}");
+    cached_or_fetch(&RAW_FORECAST_CACHE, &key, move |cached_etag| async move {
+        let Some(response) = request_forecast(
+            latitude,
+            longitude,
+            &timezone,
+            unit_params(units, IMPERIAL_UNIT_PARAMS),
+            cached_etag.as_deref(),
+        )
+        .await?
+        else {
+            return Ok(Fetched::NotModified);
+        };
+        let etag = etag(response.headers());
+        let value = passthrough(response).await.map_err(|e| {
+            AppError::WeatherApiError(format!("Failed to read weather forecast response: {e}"))
+        })?;
+        Ok(Fetched::Modified { value, etag })
+    })
+    .await
+}
 
+/// Geocodes a location name to the best matching place and its alternates
+///
+/// Cities in the embedded gazetteer are resolved without a request, and
+/// without alternates. Places outside the geofence of the caller's tenant
+/// are refused, or dropped from the alternates (see [`crate::geofence`]).
+pub(crate) async fn geocode_location(location: &str) -> Result<Geocoded, AppError> {
+    let mut geocoded = lookup_location(location).await?;
+    geofence::check_place(&geocoded.place, &mut geocoded.alternates)?;
+    Ok(geocoded)
+}
+
+/// Geocodes a location name for every caller, from the cache if possible.
+async fn lookup_location(location: &str) -> Result<Geocoded, AppError> {
+    if config().weather.offline_geocoding
+        && let Some(city) = gazetteer::lookup(location)
+    {
+        info!(location, city = city.name, "Resolved location offline");
+        provenance::record(GAZETTEER_SOURCE);
+        return Ok(Geocoded::from(city));
+    }
+
+    let key = location.trim().to_lowercase();
+    let location = location.to_string();
+    cached_or_fetch(&GEOCODE_CACHE, &key, move |etag| async move {
+        fetch_coordinates(&location, etag.as_deref()).await
+    })
+    .await
+}
+
+/// Geocodes `location`, retrying spelling variants of it if the API finds
+/// nothing and suggesting close city names if none of them match either.
+async fn fetch_coordinates(
+    location: &str,
+    cached_etag: Option<&str>,
+) -> Result<Fetched<Geocoded>, AppError> {
     info!("Geocoding location: {}", location);
-    info!("Making geocoding request to: {}", geocode_url);
+    let (response, etag) = match search_places(location, cached_etag).await? {
+        Fetched::NotModified => return Ok(Fetched::NotModified),
+        Fetched::Modified { value, etag } => (value, etag),
+    };
+    if has_results(&response) {
+        return Ok(Fetched::Modified {
+            value: parse_geocoding(&response)?,
+            etag,
+        });
+    }
 
-    let client = &HTTP_CLIENT;
-    let response: serde_json::Value = client
-        .get(&geocode_url)
-        .send()
-        .await
-        .map_err(|e| AppError::GeocodingError(format!("Failed to send geocoding request: {e}")))?
-        .json()
-        .await
-        .map_err(|e| {
-            AppError::GeocodingError(format!("Failed to parse geocoding response: {e}"))
-        })?;
+    for variant in gazetteer::spelling_variants(location) {
+        if let Fetched::Modified { value, .. } = search_places(&variant, None).await?
+            && has_results(&value)
+        {
+            info!(
+                location,
+                variant, "Geocoded location by an alternative spelling"
+            );
+            // The ETag belongs to the variant's query, not the cached key
+            return Ok(Fetched::Modified {
+                value: parse_geocoding(&value)?,
+                etag: None,
+            });
+        }
+    }
+
+    Err(AppError::LocationNotFound {
+        location: location.trim().to_string(),
+        suggestions: gazetteer::suggestions(location, MAX_LOCATION_SUGGESTIONS),
+    })
+}
+
+/// Runs one geocoding search, returning the raw response
+async fn search_places(query: &str, cached_etag: Option<&str>) -> Result<Fetched<Value>, AppError> {
+    let encoded_query = urlencoding::encode(query);
+    let alternates = config()
+        .weather
+        .geocode_alternates
+        .min(MAX_GEOCODE_ALTERNATES);
+    let geocode_path = format!(
+        "/v1/search?name={encoded_query}&count={}&language=en&format=json",
+        alternates + 1
+    );
+
+    info!("Making geocoding request for: {}", geocode_path);
+
+    let response = open_meteo_get(
+        &GEOCODING_ENDPOINTS,
+        &geocode_path,
+        cached_etag,
+        "geocoding",
+        AppError::GeocodingError,
+    )
+    .await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        info!("Geocoding result not modified");
+        provenance::record(GEOCODING_SOURCE);
+        return Ok(Fetched::NotModified);
+    }
+
+    let etag = etag(response.headers());
+    let value: Value = response.json().await.map_err(|e| {
+        AppError::GeocodingError(format!("Failed to parse geocoding response: {e}"))
+    })?;
 
     info!("Received geocoding response");
+    provenance::record(GEOCODING_SOURCE);
 
-    extract_coordinates_from_geocode(&response)
+    Ok(Fetched::Modified { value, etag })
+}
+
+/// Whether a geocoding response matched any place; the API leaves out
+/// `results` entirely when nothing matches.
+fn has_results(geocode_response: &Value) -> bool {
+    geocode_response
+        .get("results")
+        .and_then(Value::as_array)
+        .is_some_and(|results| !results.is_empty())
 }
 
 /// Fetches weather data for the given coordinates
@@ -72,22 +437,87 @@ async fn fetch_weather_data(
     latitude: f64,
     longitude: f64,
     timezone: &str,
-) -> Result<WeatherResponse, AppError> {
+    units: UnitSystem,
+) -> Result<OpenMeteoResponse, AppError> {
+    let key = format!("{latitude},{longitude},{timezone},{units:?}");
+    let timezone = timezone.to_string();
+    cached_or_fetch(&FORECAST_CACHE, &key, move |etag| async move {
+        fetch_forecast(latitude, longitude, &timezone, units, etag.as_deref()).await
+    })
+    .await
+}
+
+async fn fetch_forecast(
+    latitude: f64,
+    longitude: f64,
+    timezone: &str,
+    units: UnitSystem,
+    cached_etag: Option<&str>,
+) -> Result<Fetched<OpenMeteoResponse>, AppError> {
+    let unit_params = unit_params(units, IMPERIAL_WIND_PARAMS);
+    let Some(response) =
+        request_forecast(latitude, longitude, timezone, unit_params, cached_etag).await?
+    else {
+        return Ok(Fetched::NotModified);
+    };
+
+    let etag = etag(response.headers());
+    let open_meteo_response: OpenMeteoResponse = response.json().await.map_err(|e| {
+        AppError::WeatherApiError(format!("Failed to parse weather forecast response: {e}"))
+    })?;
+
+    info!("Parsed weather forecast response successfully");
+
+    Ok(Fetched::Modified {
+        value: open_meteo_response,
+        etag,
+    })
+}
+
+/// Query parameters selecting `units`, which are `imperial` for imperial units
+/// and Open-Meteo's metric defaults otherwise
+const fn unit_params(units: UnitSystem, imperial: &'static str) -> &'static str {
+    match units {
+        UnitSystem::Metric => "",
+        UnitSystem::Imperial => imperial,
+    }
+}
+
+/// Requests the forecast for the given coordinates, with `unit_params`
+/// appended, returning `None` if the cached representation is still current.
+async fn request_forecast(
+    latitude: f64,
+    longitude: f64,
+    timezone: &str,
+    unit_params: &str,
+    cached_etag: Option<&str>,
+) -> Result<Option<Response>, AppError> {
     let daily_params_str = DEFAULT_DAILY_PARAMS.join(",");
-    let weather_url = format!(
-        "https://api.open-meteo.com/v1/forecast?latitude={latitude}&longitude={longitude}&daily={daily_params_str}&timezone={timezone}"
+    let current_params_str = DEFAULT_CURRENT_PARAMS.join(",");
+    let hourly_params_str = DEFAULT_HOURLY_PARAMS.join(",");
+    let weather_path = format!(
+        "/v1/forecast?latitude={latitude}&longitude={longitude}&current={current_params_str}&daily={daily_params_str}&hourly={hourly_params_str}&timezone={timezone}{unit_params}"
     );
 
     info!(
         "Fetching weather data for coordinates: {}, {}",
         latitude, longitude
     );
-    info!("Making weather forecast request to: {}", weather_url);
+    info!("Making weather forecast request for: {}", weather_path);
 
-    let client = &HTTP_CLIENT;
-    let response = client.get(&weather_url).send().await.map_err(|e| {
-        AppError::WeatherApiError(format!("Failed to send weather forecast request: {e}"))
-    })?;
+    let response = open_meteo_get(
+        &FORECAST_ENDPOINTS,
+        &weather_path,
+        cached_etag,
+        "weather forecast",
+        AppError::WeatherApiError,
+    )
+    .await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        info!("Weather forecast not modified");
+        provenance::record(FORECAST_SOURCE);
+        return Ok(None);
+    }
 
     info!(
         "Received weather forecast response with status: {}",
@@ -102,80 +532,243 @@ async fn fetch_weather_data(
         )));
     }
 
-    let open_meteo_response: OpenMeteoResponse = response.json().await.map_err(|e| {
-        AppError::WeatherApiError(format!("Failed to parse weather forecast response: {e}"))
-    })?;
+    provenance::record(FORECAST_SOURCE);
+    Ok(Some(response))
+}
 
-    info!("Parsed weather forecast response successfully");
+/// Returns a cached entry for `key`, or fetches and caches a new one.
+///
+/// Entries up to `WEATHER_CACHE_MAX_STALE_SECS` past their TTL are served
+/// immediately while a background task refreshes them. Background refreshes
+/// started just before the response is returned may only complete when the
+/// container next thaws. If the upstream is rate limiting, an expired entry
+/// is served instead when `WEATHER_SERVE_STALE` is enabled.
+///
+/// `fetch` receives the cached entry's `ETag`, if any, so it can make a
+/// conditional request; `Fetched::NotModified` renews the cached entry.
+/// Whenever a cached entry is returned the cache is recorded as a source of
+/// the response (see [`crate::provenance`]); `fetch` records its upstream.
+pub(crate) async fn cached_or_fetch<V, F, Fut>(
+    cache: &'static CacheStore<V>,
+    key: &str,
+    fetch: F,
+) -> Result<V, AppError>
+where
+    V: Clone + Send + 'static,
+    F: FnOnce(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Fetched<V>, AppError>> + Send + 'static,
+{
+    let settings = &config().weather;
+    match cache.lookup(key, settings.max_stale) {
+        Lookup::Fresh(cached) => {
+            info!(cache = cache.name(), "Serving from cache: {}", key);
+            provenance::record_cache(cache);
+            return Ok(cached);
+        }
+        Lookup::Stale(cached) => {
+            if cache.try_begin_refresh(key) {
+                info!(
+                    cache = cache.name(),
+                    "Refreshing stale entry in background: {}", key
+                );
+                let key = key.to_string();
+                let refresh = fetch(cache.etag(&key));
+                tokio::spawn(async move {
+                    match refresh.await {
+                        Ok(fetched) => {
+                            cache.store_fetched(&key, fetched);
+                        }
+                        Err(e) => {
+                            warn!(cache = cache.name(), error = %e, "Background refresh failed");
+                        }
+                    }
+                    cache.end_refresh(&key);
+                });
+            }
+            provenance::record_cache(cache);
+            return Ok(cached);
+        }
+        Lookup::Miss => {}
+    }
 
-    Ok(WeatherResponse {
-        latitude: open_meteo_response.latitude,
-        longitude: open_meteo_response.longitude,
-        generationtime_ms: open_meteo_response.generationtime_ms,
-        utc_offset_seconds: open_meteo_response.utc_offset_seconds,
-        timezone: open_meteo_response.timezone,
-        timezone_abbreviation: open_meteo_response.timezone_abbreviation,
-        elevation: open_meteo_response.elevation,
-        daily_units: open_meteo_response.daily_units.into(),
-        daily: open_meteo_response.daily.into(),
-    })
+    match fetch(cache.etag(key)).await {
+        Ok(fetched) => {
+            if matches!(fetched, Fetched::NotModified) {
+                provenance::record_cache(cache);
+            }
+            cache.store_fetched(key, fetched).ok_or_else(|| {
+                AppError::GenericError(format!(
+                    "Upstream reported {key} not modified, but the entry is no longer cached"
+                ))
+            })
+        }
+        Err(e @ AppError::RateLimited { .. }) if settings.serve_stale => {
+            cache.get_stale(key).map_or(Err(e), |stale| {
+                warn!(
+                    cache = cache.name(),
+                    "Rate limited, serving stale entry: {}", key
+                );
+                provenance::record_cache(cache);
+                Ok(stale)
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Sends a GET request to Open-Meteo through the circuit breaker.
+///
+/// `path` is tried against each of the pool's endpoints in turn (see
+/// [`EndpointPool::ordered`]) until one answers without a transport error or
+/// 5xx status. HTTP 429 opens the breaker for the `Retry-After` period and
+/// returns `AppError::RateLimited`; the breaker counts a failure only when
+/// every endpoint fails. A cached `ETag` is sent as `If-None-Match`, so the
+/// response may be `304 Not Modified`, and a slow request may be hedged (see
+/// [`crate::http::hedge`]). `label` and `error` describe transport errors.
+/// The caller's tenant's Open-Meteo API key, if it has one, is added
+/// to the URL sent but never to what is logged (see [`crate::tenants`]).
+pub(crate) async fn open_meteo_get(
+    endpoints: &EndpointPool,
+    path: &str,
+    cached_etag: Option<&str>,
+    label: &str,
+    error: fn(String) -> AppError,
+) -> Result<Response, AppError> {
+    let breaker = &OPEN_METEO_BREAKER;
+    if let Some(open_for) = breaker.open_for() {
+        return Err(AppError::RateLimited {
+            upstream: breaker.name().to_string(),
+            retry_after: Some(open_for),
+        });
+    }
+
+    let api_key = tenants::api_key(tenants::OPEN_METEO)
+        .await
+        .map(|key| format!("&apikey={}", urlencoding::encode(&key)))
+        .unwrap_or_default();
+    let mut last_error = error(format!("No {} endpoints are configured", endpoints.name()));
+    for base_url in endpoints.ordered() {
+        let url = format!("{base_url}{path}{api_key}");
+        let request =
+            if_none_match(HTTP_CLIENT.get(&url), cached_etag).timeout(upstream_timeout()?);
+        let started = Instant::now();
+        let response = match send_hedged(request, endpoints.hedge()).await {
+            Ok(response) => response,
+            Err(e) => {
+                let e = send_error(e, |e| {
+                    let message = format!("Failed to send {label} request: {e}");
+                    // Transport errors quote the URL, which holds the key
+                    if api_key.is_empty() {
+                        error(message)
+                    } else {
+                        error(message.replace(&api_key, ""))
+                    }
+                });
+                if matches!(e, AppError::EgressDenied(_)) {
+                    return Err(e);
+                }
+                warn!(endpoint = base_url, error = %e, "Endpoint failed, trying next");
+                endpoints.record_failure(base_url);
+                last_error = e;
+                continue;
+            }
+        };
+
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = retry_after(response.headers());
+            breaker.record_rate_limited(retry_after);
+            return Err(AppError::RateLimited {
+                upstream: breaker.name().to_string(),
+                retry_after,
+            });
+        }
+        if status.is_server_error() {
+            warn!(endpoint = base_url, %status, "Endpoint returned server error, trying next");
+            endpoints.record_failure(base_url);
+            last_error = error(format!("{label} request returned {status}"));
+            continue;
+        }
+        endpoints.record_success(base_url, started.elapsed());
+        breaker.record_success();
+        return Ok(response);
+    }
+
+    breaker.record_failure();
+    Err(last_error)
+}
+
+pub(crate) fn endpoint_pool(name: &'static str, base_urls: &[String]) -> EndpointPool {
+    EndpointPool::new(name, base_urls, 3, Duration::from_mins(1))
 }
 
-/// Extracts coordinates and timezone from geocoding API response
-fn extract_coordinates_from_geocode(
-    geocode_response: &serde_json::Value,
-) -> Result<(f64, f64, String), AppError> {
+/// Parses an Open-Meteo geocoding response into its best match and
+/// alternates, in the order returned.
+///
+/// Each match's confidence is its share of the combined population of all
+/// matches, or an equal share when no populations are known.
+///
+/// # Errors
+///
+/// Returns `AppError::GeocodingError` if there are no results or the first
+/// result has no coordinates.
+pub fn parse_geocoding(geocode_response: &Value) -> Result<Geocoded, AppError> {
     let results = geocode_response
         .get("results")
-        .and_then(serde_json::Value::as_array)
+        .and_then(Value::as_array)
         .ok_or_else(|| {
             AppError::GeocodingError("No results found in geocoding response".to_string())
         })?;
 
-    if results.is_empty() {
+    let Some(first_result) = results.first() else {
         return Err(AppError::GeocodingError(
             "No locations found for the provided query".to_string(),
         ));
-    }
-
-    let first_result = &results[0];
-    let latitude = first_result
-        .get("latitude")
-        .and_then(serde_json::Value::as_f64)
-        .ok_or_else(|| AppError::GeocodingError("Failed to extract latitude".to_string()))?;
-
-    let longitude = first_result
-        .get("longitude")
-        .and_then(serde_json::Value::as_f64)
-        .ok_or_else(|| AppError::GeocodingError("Failed to extract longitude".to_string()))?;
-
+    };
     let timezone = first_result
         .get("timezone")
-        .and_then(serde_json::Value::as_str)
+        .and_then(Value::as_str)
         .unwrap_or("auto")
         .to_string();
 
-    Ok((latitude, longitude, timezone))
-}
+    let as_f64 = |count: u64| f64::from(u32::try_from(count).unwrap_or(u32::MAX));
+    let population = |result: &Value| result.get("population").and_then(Value::as_u64);
+    let total: u64 = results.iter().filter_map(population).sum();
+    let confidence = |result: &Value| {
+        let share = if total == 0 {
+            1.0 / as_f64(u64::try_from(results.len()).unwrap_or(u64::MAX))
+        } else {
+            as_f64(population(result).unwrap_or(0)) / as_f64(total)
+        };
+        (share * 100.0).round() / 100.0
+    };
 
-impl From<crate::models::open_meteo::DailyUnits> for crate::models::weather::DailyUnits {
-    fn from(units: crate::models::open_meteo::DailyUnits) -> Self {
-        Self {
-            time: units.time,
-            weather_code: units.weather_code,
-            temperature_2m_max: units.temperature_2m_max,
-            temperature_2m_min: units.temperature_2m_min,
-        }
-    }
+    let place = resolved_location(first_result, confidence(first_result))
+        .ok_or_else(|| AppError::GeocodingError("Failed to extract coordinates".to_string()))?;
+    let alternates = results
+        .iter()
+        .skip(1)
+        .filter_map(|result| resolved_location(result, confidence(result)))
+        .collect();
+
+    Ok(Geocoded {
+        place,
+        timezone,
+        alternates,
+    })
 }
 
-impl From<crate::models::open_meteo::Daily> for crate::models::weather::Daily {
-    fn from(daily: crate::models::open_meteo::Daily) -> Self {
-        Self {
-            time: daily.time,
-            weather_code: daily.weather_code,
-            temperature_2m_max: daily.temperature_2m_max,
-            temperature_2m_min: daily.temperature_2m_min,
-        }
-    }
+/// Reads a geocoding result, or `None` if it has no coordinates.
+fn resolved_location(result: &Value, confidence: f64) -> Option<ResolvedLocation> {
+    let text = |field: &str| result.get(field).and_then(Value::as_str).map(String::from);
+    Some(ResolvedLocation {
+        name: text("name").unwrap_or_default(),
+        country: text("country"),
+        country_code: text("country_code"),
+        admin_region: text("admin1"),
+        population: result.get("population").and_then(Value::as_u64),
+        latitude: result.get("latitude").and_then(Value::as_f64)?,
+        longitude: result.get("longitude").and_then(Value::as_f64)?,
+        confidence,
+    })
 }
@@ -0,0 +1,234 @@
+use crate::audit;
+use crate::aws::sdk_config;
+use crate::config::{WorkflowConfig, config};
+use crate::dry_run;
+use crate::effects;
+use crate::models::error::AppError;
+use crate::models::workflow::{
+    StartWorkflowRequest, StartWorkflowResponse, WorkflowStatusRequest, WorkflowStatusResponse,
+};
+use crate::models::{DryRunPlan, SideEffectStatus};
+use crate::provenance;
+use crate::utils::schema;
+use anyhow::Result;
+use aws_sdk_sfn::Client;
+use aws_sdk_sfn::primitives::{DateTime, DateTimeFormat};
+use lambda_runtime::tracing::info;
+use serde_json::{Map, Value, json};
+use tokio::sync::OnceCell;
+
+/// Step Functions rejects inputs larger than 256 KiB
+const MAX_INPUT_BYTES: usize = 256 * 1024;
+
+/// Source of executions started or described (see [`crate::provenance`])
+const STEP_FUNCTIONS_SOURCE: &str = "aws:step-functions";
+
+static SFN_CLIENT: OnceCell<Client> = OnceCell::const_new();
+
+/// Starts a Step Functions execution for an allowlisted workflow.
+///
+/// Workflows are configured via the `WORKFLOWS` environment variable. The
+/// input is validated against the workflow's `input_schema` (if any) before
+/// the execution starts. Invocation is restricted by the tool scope policy
+/// (see [`crate::auth`]). A retried call returns the first call's response
+/// instead of starting another execution (see [`crate::effects`]), and a dry
+/// run returns the execution it would start without starting it (see
+/// [`crate::dry_run`]).
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The workflow is not on the allowlist
+/// - The input is not a JSON object, is too large, or fails schema validation
+/// - A recorded call is being replayed (see [`crate::audit`])
+/// - An identical call is still starting the execution
+/// - The `StartExecution` request fails
+pub async fn start_workflow(
+    request: StartWorkflowRequest,
+) -> Result<StartWorkflowResponse, AppError> {
+    let settings = &config().workflows;
+    let (name, definition) = settings.find(request.workflow.trim()).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "Workflow is not on the allowlist: {}",
+            request.workflow
+        ))
+    })?;
+
+    let input = validate_input(definition.input_schema.as_ref(), request.input)?;
+
+    let execution_name = request.execution_name.filter(|n| !n.trim().is_empty());
+
+    if dry_run::is_active() {
+        let mut details = Map::new();
+        let parsed = serde_json::from_str(&input).unwrap_or_else(|_| Value::String(input.clone()));
+        details.insert("input".to_string(), parsed);
+        if let Some(execution_name) = &execution_name {
+            details.insert("execution_name".to_string(), json!(execution_name));
+        }
+        return Ok(StartWorkflowResponse {
+            workflow: name.to_string(),
+            execution_arn: String::new(),
+            start_date: String::new(),
+            side_effect_status: SideEffectStatus::Planned,
+            plan: Some(DryRunPlan {
+                action: "Start a Step Functions execution".to_string(),
+                target: definition.state_machine_arn.clone(),
+                details,
+            }),
+        });
+    }
+
+    audit::refuse_in_sandbox("Starting a workflow")?;
+
+    let (input, execution_name) = (&input, &execution_name);
+    let (response, side_effect_status) = effects::run_once(
+        "start_workflow",
+        request.idempotency_key.as_deref(),
+        &(name, input, execution_name),
+        || async move {
+            info!(workflow = name, "Starting workflow execution");
+            let output = sfn_client()
+                .await
+                .start_execution()
+                .state_machine_arn(&definition.state_machine_arn)
+                .input(input)
+                .set_name(execution_name.clone())
+                .send()
+                .await
+                .map_err(|e| AppError::WorkflowError(format!("Failed to start execution: {e}")))?;
+            provenance::record(STEP_FUNCTIONS_SOURCE);
+            Ok::<_, AppError>(StartWorkflowResponse {
+                workflow: name.to_string(),
+                execution_arn: output.execution_arn().to_string(),
+                start_date: format_date(output.start_date()),
+                side_effect_status: SideEffectStatus::Executed,
+                plan: None,
+            })
+        },
+    )
+    .await?;
+
+    Ok(StartWorkflowResponse {
+        side_effect_status,
+        ..response
+    })
+}
+
+/// Reports the status of an execution started by `start_workflow`.
+///
+/// Only executions of allowlisted state machines can be inspected.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The execution does not belong to an allowlisted state machine
+/// - The `DescribeExecution` request fails
+pub async fn get_workflow_status(
+    request: WorkflowStatusRequest,
+) -> Result<WorkflowStatusResponse, AppError> {
+    let settings = &config().workflows;
+    let execution_arn = request.execution_arn.trim();
+    let state_machine_arn = state_machine_arn_for_execution(execution_arn).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "Not a Step Functions execution ARN: {execution_arn}"
+        ))
+    })?;
+    ensure_allowlisted(settings, &state_machine_arn)?;
+
+    let output = sfn_client()
+        .await
+        .describe_execution()
+        .execution_arn(execution_arn)
+        .send()
+        .await
+        .map_err(|e| AppError::WorkflowError(format!("Failed to describe execution: {e}")))?;
+    provenance::record(STEP_FUNCTIONS_SOURCE);
+
+    // Guard against ARNs that do not follow the usual naming scheme
+    ensure_allowlisted(settings, output.state_machine_arn())?;
+
+    Ok(WorkflowStatusResponse {
+        execution_arn: output.execution_arn().to_string(),
+        status: output.status().as_str().to_string(),
+        start_date: format_date(output.start_date()),
+        stop_date: output.stop_date().map(format_date),
+        output: output.output().map(|raw| {
+            serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+        }),
+        error: output.error().map(String::from),
+        cause: output.cause().map(String::from),
+    })
+}
+
+/// Checks execution input against an optional schema and serializes it.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the input is not an object, is
+/// larger than Step Functions accepts, or does not match `schema`.
+pub fn validate_input(schema: Option<&Value>, input: Value) -> Result<String, AppError> {
+    let input = if input.is_null() {
+        Value::Object(serde_json::Map::new())
+    } else {
+        input
+    };
+    if !input.is_object() {
+        return Err(AppError::ValidationError(
+            "Workflow input must be a JSON object".to_string(),
+        ));
+    }
+    if let Some(schema) = schema {
+        schema::validate(schema, &input)
+            .map_err(|e| AppError::ValidationError(format!("Invalid workflow input: {e}")))?;
+    }
+
+    let serialized = input.to_string();
+    if serialized.len() > MAX_INPUT_BYTES {
+        return Err(AppError::ValidationError(format!(
+            "Workflow input exceeds the {MAX_INPUT_BYTES} byte limit"
+        )));
+    }
+    Ok(serialized)
+}
+
+fn ensure_allowlisted(settings: &WorkflowConfig, state_machine_arn: &str) -> Result<(), AppError> {
+    if settings.find(state_machine_arn).is_some() {
+        Ok(())
+    } else {
+        Err(AppError::ValidationError(
+            "Execution does not belong to an allowlisted workflow".to_string(),
+        ))
+    }
+}
+
+/// Derives the state machine ARN from a standard execution ARN:
+/// `arn:aws:states:<region>:<account>:execution:<machine>:<execution>`
+fn state_machine_arn_for_execution(execution_arn: &str) -> Option<String> {
+    let parts: Vec<&str> = execution_arn.split(':').collect();
+    match parts.as_slice() {
+        [
+            "arn",
+            partition,
+            "states",
+            region,
+            account,
+            "execution",
+            machine,
+            _execution,
+        ] => Some(format!(
+            "arn:{partition}:states:{region}:{account}:stateMachine:{machine}"
+        )),
+        _ => None,
+    }
+}
+
+fn format_date(date: &DateTime) -> String {
+    date.fmt(DateTimeFormat::DateTime)
+        .unwrap_or_else(|_| date.secs().to_string())
+}
+
+async fn sfn_client() -> &'static Client {
+    SFN_CLIENT
+        .get_or_init(|| async { Client::new(sdk_config().await) })
+        .await
+}
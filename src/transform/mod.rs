@@ -0,0 +1,147 @@
+//! `JMESPath` reshaping of tool responses.
+//!
+//! A [`Transform`] is a compiled `JMESPath` expression applied to a JSON
+//! document. Declarative tools use one to shape their upstream response
+//! (see [`crate::declarative`]), and `RESPONSE_TRANSFORMS` can attach one to
+//! any registered tool, keyed by tool name, to reshape its response before
+//! projection, export and caching see it. A configured transform's result
+//! must still satisfy the tool's declared output schema; one that does not
+//! is reported as an error rather than returned.
+//!
+//! Configured transforms are compiled by [`load`] when the container
+//! starts, so an invalid expression or an unknown tool name stops the
+//! container instead of failing every call.
+
+use crate::config::config;
+use crate::models::error::AppError;
+use crate::registry;
+use crate::utils::schema;
+use jmespath::Expression;
+use lambda_runtime::tracing::{debug, info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Configured transforms keyed by tool name.
+static TRANSFORMS: OnceLock<HashMap<String, ToolTransform>> = OnceLock::new();
+
+/// A compiled `JMESPath` expression.
+pub struct Transform {
+    source: String,
+    expression: Expression<'static>,
+}
+
+impl fmt::Debug for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Transform").field(&self.source).finish()
+    }
+}
+
+impl Transform {
+    /// Compiles `expression`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message describing the syntax error if `expression` is not
+    /// valid `JMESPath`.
+    pub fn compile(expression: &str) -> Result<Self, String> {
+        jmespath::compile(expression)
+            .map(|compiled| Self {
+                source: expression.to_string(),
+                expression: compiled,
+            })
+            .map_err(|e| format!("invalid JMESPath expression: {e}"))
+    }
+
+    /// Applies the expression to `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression fails to evaluate, e.g. a function
+    /// is given an argument of the wrong type.
+    pub fn apply(&self, value: Value) -> Result<Value, AppError> {
+        let result = self
+            .expression
+            .search(value)
+            .map_err(|e| AppError::TransformError(format!("Expression failed: {e}")))?;
+        serde_json::to_value(&*result)
+            .map_err(|e| AppError::TransformError(format!("Expression result is invalid: {e}")))
+    }
+}
+
+/// A configured transform and the schema its results must satisfy.
+struct ToolTransform {
+    transform: Transform,
+    output_schema: Value,
+}
+
+/// Compiles the transforms in `RESPONSE_TRANSFORMS` and keeps them for
+/// [`apply`]; call once at startup.
+///
+/// # Errors
+///
+/// Returns an error naming the first transform whose tool is not registered
+/// or whose expression does not compile.
+pub fn load() -> Result<(), AppError> {
+    let transforms = config()
+        .transforms
+        .transforms
+        .iter()
+        .map(|(tool, expression)| Ok((tool.clone(), compile(tool, expression)?)))
+        .collect::<Result<HashMap<_, _>, AppError>>()?;
+    if !transforms.is_empty() {
+        info!(tools = transforms.len(), "Loaded response transforms");
+    }
+    // A second call keeps the first set, which calls may already be using
+    let _ = TRANSFORMS.set(transforms);
+    Ok(())
+}
+
+/// Reshapes `response` with the transform configured for `tool`, if any.
+///
+/// If [`load`] has not run, transforms are compiled on first use and those
+/// that fail are skipped with a warning.
+///
+/// # Errors
+///
+/// Returns an error if the transform fails or its result does not satisfy
+/// the tool's output schema.
+pub fn apply(tool: &str, response: Value) -> Result<Value, AppError> {
+    let transforms = TRANSFORMS.get_or_init(|| {
+        config()
+            .transforms
+            .transforms
+            .iter()
+            .filter_map(|(tool, expression)| {
+                compile(tool, expression)
+                    .inspect_err(|e| warn!(error = %e, "Skipping response transform"))
+                    .ok()
+                    .map(|transform| (tool.clone(), transform))
+            })
+            .collect()
+    });
+    let Some(configured) = transforms.get(tool) else {
+        return Ok(response);
+    };
+    debug!(tool, "Applying response transform");
+    let reshaped = configured.transform.apply(response)?;
+    schema::validate(&configured.output_schema, &reshaped).map_err(|e| {
+        AppError::TransformError(format!(
+            "Transformed response does not match the output schema of {tool}: {e}"
+        ))
+    })?;
+    Ok(reshaped)
+}
+
+fn compile(tool: &str, expression: &str) -> Result<ToolTransform, AppError> {
+    let descriptor = registry::find(tool).ok_or_else(|| {
+        AppError::GenericError(format!("Response transform names unknown tool {tool}"))
+    })?;
+    let transform = Transform::compile(expression)
+        .map_err(|e| AppError::GenericError(format!("Response transform for {tool}: {e}")))?;
+    Ok(ToolTransform {
+        transform,
+        output_schema: (descriptor.output_schema)().to_value(),
+    })
+}
@@ -0,0 +1,418 @@
+//! Usage accounting per tenant and tool, for charging back API consumption.
+//!
+//! With `USAGE_ACCOUNTING` enabled every call to a non-admin tool is counted
+//! against the caller's tenant (see [`crate::tenants`]), or [`NO_TENANT`]
+//! for callers without one, together with the upstream HTTP requests the
+//! tool makes while serving it (see [`crate::http::send`]). Counts are kept
+//! per UTC day.
+//!
+//! Counts accumulate in the container and are flushed at most every
+//! `USAGE_FLUSH_INTERVAL_SECS`, at the end of a call: each tenant and tool
+//! is logged as a "Tool usage" line, which a `CloudWatch` metric filter turns
+//! into metrics, and with `USAGE_TABLE` set added to its day's item in
+//! `DynamoDB`, which `admin_usage_report` sums across containers. Without a
+//! table only this container's usage is known. Counts not yet flushed when
+//! a container is retired are lost, so a report can trail the logs by up to
+//! one flush interval per container.
+
+use crate::admin::is_admin_tool;
+use crate::aws::sdk_config;
+use crate::cache::CacheStore;
+use crate::config::config;
+use crate::context;
+use crate::models::error::AppError;
+use crate::models::{TenantUsage, ToolUsage};
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::Utc;
+use lambda_runtime::tracing::{debug, info, warn};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::Instant;
+use tokio::sync::OnceCell;
+
+/// Partition key of the usage table
+pub const TENANT_KEY: &str = "tenant";
+
+/// Sort key of the usage table: the day and tool, as `2025-01-31#get_weather`
+pub const USAGE_KEY: &str = "usage_key";
+
+/// Tenant that calls without one are counted against
+pub const NO_TENANT: &str = "none";
+
+static DYNAMODB_CLIENT: OnceCell<aws_sdk_dynamodb::Client> = OnceCell::const_new();
+
+/// Calls per tenant and period read from the usage table, for quota checks
+pub(crate) static USAGE_CACHE: LazyLock<CacheStore<u64>> =
+    LazyLock::new(|| CacheStore::new("usage", config().usage.flush_interval, 256));
+
+static LEDGER: LazyLock<Mutex<Ledger>> = LazyLock::new(|| {
+    Mutex::new(Ledger {
+        pending: BTreeMap::new(),
+        flushed: BTreeMap::new(),
+        last_flush: Instant::now(),
+    })
+});
+
+/// Calls and upstream requests counted for one tenant, tool and day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageCounts {
+    pub invocations: u64,
+    pub upstream_calls: u64,
+}
+
+impl UsageCounts {
+    const fn add(&mut self, other: Self) {
+        self.invocations += other.invocations;
+        self.upstream_calls += other.upstream_calls;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct UsageKey {
+    day: String,
+    tenant: String,
+    tool: String,
+}
+
+impl UsageKey {
+    fn today(tenant: Option<&str>, tool: &str) -> Self {
+        Self {
+            day: Utc::now().format("%Y-%m-%d").to_string(),
+            tenant: tenant.unwrap_or(NO_TENANT).to_string(),
+            tool: tool.to_string(),
+        }
+    }
+
+    fn sort_key(&self) -> String {
+        format!("{}#{}", self.day, self.tool)
+    }
+}
+
+struct Ledger {
+    /// Counts not yet flushed
+    pending: BTreeMap<UsageKey, UsageCounts>,
+    /// Counts flushed in this container; only kept without a usage table
+    flushed: BTreeMap<UsageKey, UsageCounts>,
+    last_flush: Instant,
+}
+
+/// Counts a call by `tenant` to `tool`.
+pub fn record_invocation(tenant: Option<&str>, tool: &str) {
+    add(
+        tenant,
+        tool,
+        UsageCounts {
+            invocations: 1,
+            ..UsageCounts::default()
+        },
+    );
+}
+
+/// Counts an upstream request made by the tool call in progress.
+///
+/// Requests made outside a tool call, such as background cache refreshes,
+/// are not counted.
+pub fn record_upstream_call() {
+    let context = context::current();
+    if context.tool.is_empty() {
+        return;
+    }
+    add(
+        context.tenant.as_deref(),
+        context.tool,
+        UsageCounts {
+            upstream_calls: 1,
+            ..UsageCounts::default()
+        },
+    );
+}
+
+fn add(tenant: Option<&str>, tool: &str, counts: UsageCounts) {
+    if !config().usage.enabled || is_admin_tool(tool) {
+        return;
+    }
+    LEDGER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .pending
+        .entry(UsageKey::today(tenant, tool))
+        .or_default()
+        .add(counts);
+}
+
+/// Flushes pending counts if `USAGE_FLUSH_INTERVAL_SECS` has passed since
+/// the last flush.
+pub async fn flush_if_due() {
+    let due = {
+        let ledger = LEDGER.lock().unwrap_or_else(PoisonError::into_inner);
+        !ledger.pending.is_empty() && ledger.last_flush.elapsed() >= config().usage.flush_interval
+    };
+    if due {
+        flush().await;
+    }
+}
+
+/// Logs pending counts and adds them to the usage table.
+///
+/// Counts that cannot be written are kept for the next flush.
+pub async fn flush() {
+    let pending = {
+        let mut ledger = LEDGER.lock().unwrap_or_else(PoisonError::into_inner);
+        ledger.last_flush = Instant::now();
+        std::mem::take(&mut ledger.pending)
+    };
+    let config = config();
+    let table = config.usage.table.as_deref();
+    // Without a table, flushed counts stay in the container; with one, only
+    // those that could not be written do, to be retried
+    let mut kept = Vec::new();
+    for (key, counts) in pending {
+        info!(
+            tenant = %key.tenant,
+            tool = %key.tool,
+            invocations = counts.invocations,
+            upstream_calls = counts.upstream_calls,
+            "Tool usage"
+        );
+        match table {
+            Some(table) => {
+                if let Err(e) = add_to_table(table, &key, counts).await {
+                    warn!(tenant = %key.tenant, tool = %key.tool, error = %e, "Failed to record tool usage");
+                    kept.push((key, counts));
+                } else {
+                    // The cached totals no longer include these counts
+                    USAGE_CACHE.invalidate(&format!("{}#", key.tenant));
+                }
+            }
+            None => kept.push((key, counts)),
+        }
+    }
+
+    let mut guard = LEDGER.lock().unwrap_or_else(PoisonError::into_inner);
+    let ledger = &mut *guard;
+    let into = if table.is_some() {
+        &mut ledger.pending
+    } else {
+        &mut ledger.flushed
+    };
+    for (key, counts) in kept {
+        into.entry(key).or_default().add(counts);
+    }
+    drop(guard);
+}
+
+/// Usage in `period` (a `YYYY-MM` month or `YYYY-MM-DD` day), for one
+/// tenant or all of them, with the busiest tenants first.
+///
+/// Pending counts in this container are flushed first.
+///
+/// # Errors
+///
+/// Returns `AppError::UsageError` if the usage table cannot be read.
+pub async fn report(tenant: Option<&str>, period: &str) -> Result<Vec<TenantUsage>, AppError> {
+    flush().await;
+    let rows = if let Some(table) = config().usage.table.as_deref() {
+        read_table(table, tenant, period).await?
+    } else {
+        let ledger = LEDGER.lock().unwrap_or_else(PoisonError::into_inner);
+        ledger
+            .flushed
+            .iter()
+            .chain(&ledger.pending)
+            .filter(|(key, _)| key.day.starts_with(period))
+            .filter(|(key, _)| tenant.is_none_or(|tenant| key.tenant == tenant))
+            .map(|(key, counts)| (key.clone(), *counts))
+            .collect()
+    };
+    Ok(summarize(rows))
+}
+
+/// Calls `tenant` has made in `period` (a `YYYY-MM` month or `YYYY-MM-DD`
+/// day), across containers when there is a usage table.
+///
+/// Table totals are reused until this container next flushes, for at most
+/// one flush interval, and its unflushed calls are added to them; calls
+/// other containers made since the last read are not yet seen.
+///
+/// # Errors
+///
+/// Returns `AppError::UsageError` if the usage table cannot be read.
+pub async fn invocations(tenant: &str, period: &str) -> Result<u64, AppError> {
+    let (pending, flushed) = {
+        let ledger = LEDGER.lock().unwrap_or_else(PoisonError::into_inner);
+        (
+            sum_invocations(&ledger.pending, tenant, period),
+            sum_invocations(&ledger.flushed, tenant, period),
+        )
+    };
+    let stored = match config().usage.table.as_deref() {
+        Some(table) => {
+            let key = format!("{tenant}#{period}");
+            if let Some(stored) = USAGE_CACHE.get(&key) {
+                stored
+            } else {
+                let stored = read_table(table, Some(tenant), period)
+                    .await?
+                    .iter()
+                    .map(|(_, counts)| counts.invocations)
+                    .sum();
+                USAGE_CACHE.insert(key, stored);
+                stored
+            }
+        }
+        None => flushed,
+    };
+    Ok(stored + pending)
+}
+
+fn sum_invocations(counts: &BTreeMap<UsageKey, UsageCounts>, tenant: &str, period: &str) -> u64 {
+    counts
+        .iter()
+        .filter(|(key, _)| key.tenant == tenant && key.day.starts_with(period))
+        .map(|(_, counts)| counts.invocations)
+        .sum()
+}
+
+/// Sums `rows` per tenant and tool.
+fn summarize(rows: Vec<(UsageKey, UsageCounts)>) -> Vec<TenantUsage> {
+    let mut totals: BTreeMap<String, BTreeMap<String, UsageCounts>> = BTreeMap::new();
+    for (key, counts) in rows {
+        totals
+            .entry(key.tenant)
+            .or_default()
+            .entry(key.tool)
+            .or_default()
+            .add(counts);
+    }
+
+    let mut tenants: Vec<TenantUsage> = totals
+        .into_iter()
+        .map(|(tenant, tools)| {
+            let mut total = UsageCounts::default();
+            let tools = tools
+                .into_iter()
+                .map(|(tool, counts)| {
+                    total.add(counts);
+                    ToolUsage {
+                        tool,
+                        invocations: counts.invocations,
+                        upstream_calls: counts.upstream_calls,
+                    }
+                })
+                .collect();
+            TenantUsage {
+                tenant,
+                invocations: total.invocations,
+                upstream_calls: total.upstream_calls,
+                tools,
+            }
+        })
+        .collect();
+    tenants.sort_by_key(|usage| Reverse(usage.invocations));
+    tenants
+}
+
+async fn add_to_table(table: &str, key: &UsageKey, counts: UsageCounts) -> Result<(), AppError> {
+    dynamodb_client()
+        .await
+        .update_item()
+        .table_name(table)
+        .key(TENANT_KEY, AttributeValue::S(key.tenant.clone()))
+        .key(USAGE_KEY, AttributeValue::S(key.sort_key()))
+        .update_expression(
+            "SET #day = :day, #tool = :tool ADD invocations :invocations, upstream_calls :upstream_calls",
+        )
+        .expression_attribute_names("#day", "day")
+        .expression_attribute_names("#tool", "tool")
+        .expression_attribute_values(":day", AttributeValue::S(key.day.clone()))
+        .expression_attribute_values(":tool", AttributeValue::S(key.tool.clone()))
+        .expression_attribute_values(
+            ":invocations",
+            AttributeValue::N(counts.invocations.to_string()),
+        )
+        .expression_attribute_values(
+            ":upstream_calls",
+            AttributeValue::N(counts.upstream_calls.to_string()),
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::UsageError(format!("DynamoDB UpdateItem failed: {e}")))?;
+    Ok(())
+}
+
+/// Reads the usage items of `period`: a query for one tenant, otherwise a
+/// scan of the table.
+async fn read_table(
+    table: &str,
+    tenant: Option<&str>,
+    period: &str,
+) -> Result<Vec<(UsageKey, UsageCounts)>, AppError> {
+    debug!(table, tenant, period, "Reading tool usage");
+    let client = dynamodb_client().await;
+    let period = AttributeValue::S(period.to_string());
+    let mut rows = Vec::new();
+    let mut start_key = None;
+    loop {
+        let (items, next) = if let Some(tenant) = tenant {
+            let output = client
+                .query()
+                .table_name(table)
+                .key_condition_expression("#tenant = :tenant AND begins_with(#usage_key, :period)")
+                .expression_attribute_names("#tenant", TENANT_KEY)
+                .expression_attribute_names("#usage_key", USAGE_KEY)
+                .expression_attribute_values(":tenant", AttributeValue::S(tenant.to_string()))
+                .expression_attribute_values(":period", period.clone())
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| AppError::UsageError(format!("DynamoDB Query failed: {e}")))?;
+            (output.items, output.last_evaluated_key)
+        } else {
+            let output = client
+                .scan()
+                .table_name(table)
+                .filter_expression("begins_with(#usage_key, :period)")
+                .expression_attribute_names("#usage_key", USAGE_KEY)
+                .expression_attribute_values(":period", period.clone())
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| AppError::UsageError(format!("DynamoDB Scan failed: {e}")))?;
+            (output.items, output.last_evaluated_key)
+        };
+        rows.extend(items.iter().flatten().filter_map(usage_from_item));
+        start_key = next;
+        if start_key.is_none() {
+            return Ok(rows);
+        }
+    }
+}
+
+/// Reads usage from a `DynamoDB` item, skipping malformed items.
+fn usage_from_item(item: &HashMap<String, AttributeValue>) -> Option<(UsageKey, UsageCounts)> {
+    let text = |name: &str| item.get(name)?.as_s().ok().cloned();
+    let number = |name: &str| {
+        item.get(name)
+            .and_then(|value| value.as_n().ok())
+            .and_then(|n| n.parse().ok())
+            .unwrap_or_default()
+    };
+    Some((
+        UsageKey {
+            day: text("day")?,
+            tenant: text(TENANT_KEY)?,
+            tool: text("tool")?,
+        },
+        UsageCounts {
+            invocations: number("invocations"),
+            upstream_calls: number("upstream_calls"),
+        },
+    ))
+}
+
+async fn dynamodb_client() -> &'static aws_sdk_dynamodb::Client {
+    DYNAMODB_CLIENT
+        .get_or_init(|| async { aws_sdk_dynamodb::Client::new(sdk_config().await) })
+        .await
+}
@@ -3,6 +3,8 @@
 //! This module contains common utility functions that are used in multiple
 //! parts of the application to avoid code duplication.
 
+pub mod schema;
+
 /// Strips Bedrock Gateway prefix from tool name.
 ///
 /// Format: `gateway-target-id___tool_name` → `tool_name`
@@ -21,3 +23,16 @@ pub fn strip_gateway_prefix(name: &str) -> String {
         |(_, actual_name)| actual_name.to_string(),
     )
 }
+
+/// Checks that `name` looks like an IANA timezone name, e.g. `Europe/Berlin`
+/// or `Etc/GMT+10`.
+///
+/// Only the character set and length are checked, so the name is safe to
+/// pass to an upstream API; whether the zone exists is up to that API.
+#[must_use]
+pub fn is_timezone_name(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'/' | b'_' | b'-' | b'+'))
+}
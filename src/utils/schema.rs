@@ -0,0 +1,147 @@
+//! Minimal JSON Schema validation for operator-supplied schemas.
+//!
+//! Supports the keywords commonly used to describe tool and workflow inputs:
+//! `type`, `properties`, `required`, `additionalProperties` (boolean),
+//! `items`, `enum`, `minLength`/`maxLength` and `minimum`/`maximum`.
+//! Unknown keywords are ignored, so schemas written for full validators
+//! still work, just less strictly.
+
+use serde_json::{Map, Value};
+
+/// Validates `value` against `schema`.
+///
+/// # Errors
+///
+/// Returns a message naming the JSON path of the first violation, e.g.
+/// `$.customer.id: expected string`.
+pub fn validate(schema: &Value, value: &Value) -> Result<(), String> {
+    validate_at(schema, value, "$")
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // `true`, `{}` and non-object schemas accept anything
+        return Ok(());
+    };
+
+    if let Some(expected) = schema.get("type")
+        && !type_matches(expected, value)
+    {
+        return Err(format!("{path}: expected {}", describe_type(expected)));
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        return Err(format!("{path}: value is not one of the allowed options"));
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{path}[{index}]"))?;
+                }
+            }
+            Ok(())
+        }
+        Value::String(text) => {
+            let length = text.chars().count();
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64)
+                && (length as u64) < min
+            {
+                return Err(format!("{path}: shorter than {min} characters"));
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64)
+                && (length as u64) > max
+            {
+                return Err(format!("{path}: longer than {max} characters"));
+            }
+            Ok(())
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+                && number < min
+            {
+                return Err(format!("{path}: less than minimum {min}"));
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+                && number > max
+            {
+                return Err(format!("{path}: greater than maximum {max}"));
+            }
+            Ok(())
+        }
+        Value::Bool(_) | Value::Null => Ok(()),
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+) -> Result<(), String> {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                return Err(format!("{path}: missing required property `{name}`"));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let additional_allowed = schema
+        .get("additionalProperties")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+
+    for (name, property) in object {
+        match properties.and_then(|p| p.get(name)) {
+            Some(property_schema) => {
+                validate_at(property_schema, property, &format!("{path}.{name}"))?;
+            }
+            None if !additional_allowed => {
+                return Err(format!("{path}: unexpected property `{name}`"));
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn type_matches(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => single_type_matches(name, value),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| single_type_matches(name, value)),
+        _ => true,
+    }
+}
+
+fn single_type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.as_str().unwrap_or("a valid type").to_string(),
+    }
+}
@@ -0,0 +1,349 @@
+//! Outbound webhooks for tool lifecycle events.
+//!
+//! Platform teams can be alerted without scraping logs: each
+//! [`WebhookEvent`] is posted as JSON to every HTTPS endpoint in
+//! `WEBHOOK_URLS`. Events are raised when
+//! - calls to one tool fail `WEBHOOK_FAILURE_SPIKE_THRESHOLD` times within
+//!   `WEBHOOK_FAILURE_SPIKE_WINDOW_SECS` (see [`record_failure`])
+//! - a tenant crosses a quota's warning threshold or uses it up (see
+//!   [`crate::quotas`])
+//! - an upstream's circuit breaker opens or closes (see
+//!   [`crate::http::CircuitBreaker`])
+//!
+//! With `WEBHOOK_SECRET` set, the body is signed with HMAC-SHA256 and the
+//! hex digest sent as `X-Webhook-Signature: sha256=...`, so receivers can
+//! check it came from this server. Deliveries go out in the background
+//! through the shared HTTP client, so endpoints must pass the egress policy
+//! (see [`crate::http::egress`]). A failed delivery stays queued in the
+//! container and is retried with exponential backoff at the end of later
+//! calls, up to `WEBHOOK_MAX_ATTEMPTS` times; events still queued when a
+//! container is retired are lost.
+
+use crate::config::{WebhookConfig, config};
+use crate::http::{HTTP_CLIENT, send};
+use aws_lc_rs::{hmac, rand};
+use chrono::Utc;
+use lambda_runtime::tracing::{debug, info, warn};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Header carrying the payload signature
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Header naming the event, so receivers can route without parsing the body
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+
+/// Most deliveries queued in a container; the oldest are dropped beyond it
+const MAX_QUEUED: usize = 100;
+
+/// Time allowed for an endpoint to answer
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configured endpoints that use HTTPS
+static ENDPOINTS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    config()
+        .webhooks
+        .urls
+        .iter()
+        .filter(|url| {
+            let https = url.starts_with("https://");
+            if !https {
+                warn!(url = %url, "Ignoring webhook endpoint that does not use HTTPS");
+            }
+            https
+        })
+        .cloned()
+        .collect()
+});
+
+static QUEUE: Mutex<VecDeque<Delivery>> = Mutex::new(VecDeque::new());
+
+/// Set while a background task is delivering the queue
+static DELIVERING: AtomicBool = AtomicBool::new(false);
+
+/// Recent failures per tool
+static FAILURES: LazyLock<Mutex<HashMap<String, FailureWindow>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Something platform teams should hear about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// Calls to a tool failed repeatedly in a short window
+    ToolFailureSpike {
+        tool: String,
+        failures: usize,
+        window_secs: u64,
+    },
+    /// A tenant crossed a quota's warning threshold, or used it up
+    QuotaThreshold {
+        tenant: String,
+        period: String,
+        used: u64,
+        limit: u64,
+        exceeded: bool,
+        resets_at: String,
+    },
+    /// An upstream's circuit breaker opened or closed
+    CircuitBreaker {
+        upstream: String,
+        open: bool,
+        /// How long the breaker stays open
+        #[serde(skip_serializing_if = "Option::is_none")]
+        open_secs: Option<u64>,
+    },
+}
+
+impl WebhookEvent {
+    /// Name of the event, e.g. `tool_failure_spike`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ToolFailureSpike { .. } => "tool_failure_spike",
+            Self::QuotaThreshold { .. } => "quota_threshold",
+            Self::CircuitBreaker { .. } => "circuit_breaker",
+        }
+    }
+}
+
+/// The JSON body sent for an event.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    /// Unique per event, so receivers can drop duplicate deliveries
+    pub id: String,
+    /// When the event was raised (RFC 3339)
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: WebhookEvent,
+}
+
+impl WebhookPayload {
+    #[must_use]
+    pub fn new(event: WebhookEvent) -> Self {
+        Self {
+            id: new_event_id(),
+            timestamp: Utc::now().to_rfc3339(),
+            event,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Delivery {
+    url: String,
+    event: &'static str,
+    body: String,
+    attempts: u32,
+    due: Instant,
+}
+
+#[derive(Debug, Default)]
+struct FailureWindow {
+    failures: VecDeque<Instant>,
+    /// No further spike is raised for the tool until then
+    quiet_until: Option<Instant>,
+}
+
+impl FailureWindow {
+    /// Counts a failure at `now`, returning the failures within the window
+    /// if they make a spike.
+    fn record(&mut self, now: Instant, settings: &WebhookConfig) -> Option<usize> {
+        self.failures.push_back(now);
+        while self
+            .failures
+            .front()
+            .is_some_and(|failed| now.duration_since(*failed) > settings.failure_spike_window)
+        {
+            self.failures.pop_front();
+        }
+        let quiet = self.quiet_until.is_some_and(|until| now < until);
+        if quiet || self.failures.len() < settings.failure_spike_threshold {
+            return None;
+        }
+        self.quiet_until = Some(now + settings.failure_spike_window);
+        Some(self.failures.len())
+    }
+}
+
+/// Queues `event` for every endpoint and starts delivering it.
+pub fn emit(event: WebhookEvent) {
+    if ENDPOINTS.is_empty() {
+        return;
+    }
+    let name = event.name();
+    let body = match serde_json::to_string(&WebhookPayload::new(event)) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!(event = name, error = %e, "Failed to serialize webhook event");
+            return;
+        }
+    };
+    info!(
+        event = name,
+        endpoints = ENDPOINTS.len(),
+        "Raising webhook event"
+    );
+    {
+        let mut queue = QUEUE.lock().unwrap_or_else(PoisonError::into_inner);
+        for url in ENDPOINTS.iter() {
+            if queue.len() >= MAX_QUEUED
+                && let Some(dropped) = queue.pop_front()
+            {
+                warn!(event = dropped.event, url = %dropped.url, "Webhook queue full, dropping delivery");
+            }
+            queue.push_back(Delivery {
+                url: url.clone(),
+                event: name,
+                body: body.clone(),
+                attempts: 0,
+                due: Instant::now(),
+            });
+        }
+    }
+    spawn_delivery();
+}
+
+/// Starts delivering queued events whose retry is due.
+pub fn retry_due() {
+    let due = QUEUE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .any(|delivery| delivery.due <= Instant::now());
+    if due {
+        spawn_delivery();
+    }
+}
+
+/// Counts a failed call to `tool`, raising a [`WebhookEvent::ToolFailureSpike`]
+/// when the failures within the window reach the threshold.
+///
+/// A tool raises at most one spike per window.
+pub fn record_failure(tool: &str) {
+    let settings = &config().webhooks;
+    if settings.failure_spike_threshold == 0 {
+        return;
+    }
+    let Some(failures) = FAILURES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(tool.to_string())
+        .or_default()
+        .record(Instant::now(), settings)
+    else {
+        return;
+    };
+    warn!(tool, failures, "Tool failure spike");
+    emit(WebhookEvent::ToolFailureSpike {
+        tool: tool.to_string(),
+        failures,
+        window_secs: settings.failure_spike_window.as_secs(),
+    });
+}
+
+/// Signature of `body` under `secret`, as sent in the [`SIGNATURE_HEADER`]:
+/// `sha256=` and the hex HMAC-SHA256 digest.
+#[must_use]
+pub fn sign(secret: &str, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, body.as_bytes());
+    format!("sha256={}", hex(tag.as_ref()))
+}
+
+fn spawn_delivery() {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    if DELIVERING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    runtime.spawn(async {
+        deliver_due().await;
+        DELIVERING.store(false, Ordering::Release);
+    });
+}
+
+/// Sends each queued delivery that is due, requeueing failures with backoff.
+async fn deliver_due() {
+    loop {
+        let next = {
+            let mut queue = QUEUE.lock().unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            queue
+                .iter()
+                .position(|delivery| delivery.due <= now)
+                .and_then(|index| queue.remove(index))
+        };
+        let Some(mut delivery) = next else {
+            return;
+        };
+
+        delivery.attempts += 1;
+        match deliver(&delivery).await {
+            Ok(()) => debug!(event = delivery.event, url = %delivery.url, "Webhook delivered"),
+            Err(e) if delivery.attempts < config().webhooks.max_attempts => {
+                let backoff = Duration::from_secs(1 << delivery.attempts.min(10));
+                warn!(
+                    event = delivery.event,
+                    url = %delivery.url,
+                    attempt = delivery.attempts,
+                    retry_secs = backoff.as_secs(),
+                    error = %e,
+                    "Webhook delivery failed, will retry"
+                );
+                delivery.due = Instant::now() + backoff;
+                QUEUE
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push_back(delivery);
+            }
+            Err(e) => warn!(
+                event = delivery.event,
+                url = %delivery.url,
+                attempts = delivery.attempts,
+                error = %e,
+                "Dropping webhook delivery after repeated failures"
+            ),
+        }
+    }
+}
+
+async fn deliver(delivery: &Delivery) -> anyhow::Result<()> {
+    let mut request = HTTP_CLIENT
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header(EVENT_HEADER, delivery.event)
+        .body(delivery.body.clone())
+        .timeout(DELIVERY_TIMEOUT);
+    if let Some(secret) = config().webhooks.secret.as_deref() {
+        request = request.header(SIGNATURE_HEADER, sign(secret, &delivery.body));
+    }
+    let response = send(request).await?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("endpoint answered {status}");
+    }
+    Ok(())
+}
+
+/// 128 random bits, hex encoded.
+fn new_event_id() -> String {
+    let mut bytes = [0u8; 16];
+    if rand::fill(&mut bytes).is_err() {
+        warn!("Failed to generate random webhook event ID");
+    }
+    hex(&bytes)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
@@ -0,0 +1,189 @@
+// Admin tool tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::admin::{is_admin_tool, is_disabled};
+use aws_lambda_mcp::auth::ToolPolicy;
+use aws_lambda_mcp::config::{parse_overrides, reload_config};
+use aws_lambda_mcp::handler::route_tool;
+use common::{admin_token, token};
+use serde_json::json;
+
+#[test]
+fn test_policy_cannot_open_admin_tools() {
+    let policy = ToolPolicy::parse("admin_flush_cache=");
+
+    assert!(is_admin_tool("admin_flush_cache"));
+    assert_eq!(
+        policy.required_scopes("admin_flush_cache").unwrap(),
+        ["Admin".to_string()]
+    );
+    assert!(policy.authorize("admin_flush_cache", None).is_err());
+}
+
+#[test]
+fn test_admin_scopes_override() {
+    let policy = ToolPolicy::default().with_admin_scopes(vec!["Ops".to_string()]);
+    assert_eq!(
+        policy.required_scopes("admin_toggle_tool").unwrap(),
+        ["Ops".to_string()]
+    );
+
+    let policy = policy.with_admin_scopes(Vec::new());
+    assert_eq!(
+        policy.required_scopes("admin_toggle_tool").unwrap(),
+        ["Ops".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn test_admin_tool_requires_admin_scope() {
+    let err = route_tool("admin_flush_cache", json!({}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.error_type, "Unauthorized");
+
+    let payload = json!({
+        "auth_token": token(&json!({"sub": "user-1", "scp": "User.Read"}))
+    });
+    let err = route_tool("admin_flush_cache", payload).await.unwrap_err();
+    assert_eq!(err.error_type, "Unauthorized");
+    assert!(err.error_message.contains("Admin"));
+}
+
+#[tokio::test]
+async fn test_unknown_tool_lists_admin_tools_only_for_admins() {
+    let err = route_tool("unknown_tool", json!({})).await.unwrap_err();
+    assert!(!err.error_message.contains("admin_"));
+
+    let err = route_tool("unknown_tool", json!({"auth_token": admin_token()}))
+        .await
+        .unwrap_err();
+    assert!(err.error_message.contains("admin_flush_cache"));
+}
+
+#[tokio::test]
+async fn test_flush_named_cache() {
+    let payload = json!({"cache": "geocode", "auth_token": admin_token()});
+    let response = route_tool("admin_flush_cache", payload).await.unwrap();
+
+    let flushed = response["flushed"].as_array().unwrap();
+    assert_eq!(flushed.len(), 1);
+    assert_eq!(flushed[0]["name"], "geocode");
+}
+
+#[tokio::test]
+async fn test_flush_unknown_cache() {
+    let payload = json!({"cache": "nope", "auth_token": admin_token()});
+    let err = route_tool("admin_flush_cache", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("Unknown cache nope"));
+}
+
+#[tokio::test]
+async fn test_toggle_tool_off_and_on() {
+    let toggle = |enabled: bool| json!({"tool": "read_feed", "enabled": enabled, "auth_token": admin_token()});
+
+    let response = route_tool("admin_toggle_tool", toggle(false))
+        .await
+        .unwrap();
+    assert_eq!(response["enabled"], false);
+    assert_eq!(response["previously_enabled"], true);
+    assert!(is_disabled("read_feed"));
+
+    let err = route_tool("read_feed", json!({"url": "https://example.com/feed"}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.error_type, "ToolDisabled");
+    assert!(err.error_message.starts_with("[E1006] "));
+
+    let response = route_tool("admin_toggle_tool", toggle(true)).await.unwrap();
+    assert_eq!(response["previously_enabled"], false);
+    assert!(!is_disabled("read_feed"));
+}
+
+#[tokio::test]
+async fn test_admin_tools_cannot_be_disabled() {
+    let payload = json!({
+        "tool": "admin_toggle_tool",
+        "enabled": false,
+        "auth_token": admin_token()
+    });
+    let err = route_tool("admin_toggle_tool", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(!is_disabled("admin_toggle_tool"));
+}
+
+#[tokio::test]
+async fn test_cache_stats_and_prefix_flush() {
+    let payload = json!({"cache": "holidays", "auth_token": admin_token()});
+    let response = route_tool("admin_cache_stats", payload).await.unwrap();
+    let caches = response["caches"].as_array().unwrap();
+    assert_eq!(caches.len(), 1);
+    assert_eq!(caches[0]["name"], "holidays");
+    assert_eq!(caches[0]["max_entries"], 64);
+
+    let payload = json!({"prefix": "paris", "auth_token": admin_token()});
+    let err = route_tool("admin_flush_cache", payload).await.unwrap_err();
+    assert!(err.error_message.contains("named cache"));
+
+    let payload = json!({"cache": "geocode", "prefix": "paris", "auth_token": admin_token()});
+    let response = route_tool("admin_flush_cache", payload).await.unwrap();
+    assert_eq!(response["flushed"][0]["entries"], 0);
+}
+
+#[tokio::test]
+async fn test_dns_cache_is_managed() {
+    let payload = json!({"cache": "dns", "auth_token": admin_token()});
+    let response = route_tool("admin_cache_stats", payload).await.unwrap();
+    assert_eq!(response["caches"][0]["name"], "dns");
+    assert_eq!(response["caches"][0]["max_entries"], 64);
+}
+
+#[tokio::test]
+async fn test_set_log_filter() {
+    let payload = json!({
+        "filter": "info,aws_lambda_mcp::tools::weather=debug",
+        "auth_token": admin_token()
+    });
+    let response = route_tool("admin_set_log_filter", payload).await.unwrap();
+    assert_eq!(
+        response["filter"],
+        "info,aws_lambda_mcp::tools::weather=debug"
+    );
+
+    let payload = json!({"filter": "weather=loud", "auth_token": admin_token()});
+    let err = route_tool("admin_set_log_filter", payload)
+        .await
+        .unwrap_err();
+    assert!(err.error_message.contains("Invalid log filter"));
+
+    let payload = json!({"auth_token": admin_token()});
+    let response = route_tool("admin_set_log_filter", payload).await.unwrap();
+    assert_eq!(
+        response["previous_filter"],
+        "info,aws_lambda_mcp::tools::weather=debug"
+    );
+}
+
+#[tokio::test]
+async fn test_reload_config_without_a_secret() {
+    // Not through the tool, which would also reset the log filter
+    assert!(reload_config().await.is_ok());
+}
+
+#[test]
+fn test_config_secret_variables() {
+    let overrides = parse_overrides(
+        r#"{"DISABLED_TOOLS": "get_weather", "CACHE_TTL_SECS": 60, "WORKFLOWS": {}}"#,
+    )
+    .unwrap();
+    assert_eq!(overrides["DISABLED_TOOLS"], "get_weather");
+    assert_eq!(overrides["CACHE_TTL_SECS"], "60");
+    assert_eq!(overrides["WORKFLOWS"], "{}");
+
+    assert!(parse_overrides("[]").is_err());
+}
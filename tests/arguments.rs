@@ -0,0 +1,79 @@
+// Argument parsing mode tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::arguments::{self, ParsingMode, parse_tool_modes, unknown_arguments};
+use aws_lambda_mcp::config::ArgumentsConfig;
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::registry;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_parsing_mode_parsing() {
+    assert_eq!(ParsingMode::parse(" Strict "), Some(ParsingMode::Strict));
+    assert_eq!(ParsingMode::parse("lenient"), Some(ParsingMode::Lenient));
+    assert_eq!(ParsingMode::parse(""), Some(ParsingMode::Lenient));
+    assert_eq!(ParsingMode::parse("pedantic"), None);
+}
+
+#[test]
+fn test_parse_tool_modes() {
+    let modes = parse_tool_modes("get_weather=strict; read_feed = lenient;bad;query_logs=loose");
+    assert_eq!(
+        modes,
+        HashMap::from([
+            ("get_weather".to_string(), ParsingMode::Strict),
+            ("read_feed".to_string(), ParsingMode::Lenient),
+        ])
+    );
+}
+
+#[test]
+fn test_tool_mode_overrides_environment_mode() {
+    let settings = ArgumentsConfig {
+        mode: ParsingMode::Strict,
+        tool_modes: HashMap::from([("read_feed".to_string(), ParsingMode::Lenient)]),
+        coerce: true,
+    };
+    assert_eq!(settings.mode_for("read_feed"), ParsingMode::Lenient);
+    assert_eq!(settings.mode_for("get_weather"), ParsingMode::Strict);
+}
+
+#[test]
+fn test_unknown_arguments_are_listed() {
+    let tool = registry::find("get_weather").unwrap();
+    let args = json!({"location": "Sydney", "unit": "imperial", "days": 3});
+    assert_eq!(unknown_arguments(tool, &args), ["days", "unit"]);
+}
+
+#[test]
+fn test_injected_arguments_are_not_unknown() {
+    let tool = registry::find("get_public_holidays").unwrap();
+    let args = json!({
+        "country_code": "AU",
+        "user_id": "ada@example.com",
+        "user_name": "Ada",
+        "session_id": "session-1",
+        "auth_token": "token",
+        "response_version": "2"
+    });
+    assert!(unknown_arguments(tool, &args).is_empty());
+}
+
+#[test]
+fn test_lenient_mode_accepts_unknown_arguments() {
+    // ARGUMENT_PARSING defaults to lenient
+    let tool = registry::find("get_weather").unwrap();
+    assert!(arguments::check(tool, &json!({"location": "Sydney", "unit": "imperial"})).is_ok());
+}
+
+#[tokio::test]
+async fn test_lenient_call_ignores_unknown_arguments() {
+    let response = route_tool(
+        "get_personalized_greeting",
+        json!({"user_name": "Ada", "greeting_style": "formal"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response["greeting"], "Hello, Ada!");
+}
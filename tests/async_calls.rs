@@ -0,0 +1,177 @@
+// Asynchronous call tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::async_calls::{
+    FailedCall, Source, find, handle, is_async_event, is_retryable, list, record,
+};
+use aws_lambda_mcp::catalog;
+use aws_lambda_mcp::handler::route_tool;
+use common::admin_token;
+use lambda_runtime::Diagnostic;
+use serde_json::{Value, json};
+
+fn sqs_message(id: &str, body: &str, receive_count: u32) -> Value {
+    json!({
+        "messageId": id,
+        "eventSource": "aws:sqs",
+        "body": body,
+        "attributes": {"ApproximateReceiveCount": receive_count.to_string()},
+    })
+}
+
+fn eventbridge_event(detail: &Value) -> Value {
+    json!({
+        "id": "event-1",
+        "source": "scheduler",
+        "detail-type": "Tool call",
+        "detail": detail,
+    })
+}
+
+#[test]
+fn test_recognizes_async_events() {
+    assert!(is_async_event(&json!({
+        "Records": [sqs_message("m-1", "{}", 1)]
+    })));
+    assert!(is_async_event(&eventbridge_event(&json!({}))));
+
+    assert!(!is_async_event(&json!({"location": "Paris"})));
+    assert!(!is_async_event(&json!({"Records": []})));
+    assert!(!is_async_event(&json!({
+        "Records": [{"eventSource": "aws:s3"}]
+    })));
+}
+
+#[test]
+fn test_only_upstream_and_capacity_errors_are_retried() {
+    assert!(is_retryable(
+        &catalog::WEATHER_UNAVAILABLE.diagnostic("ToolError", "timed out")
+    ));
+    assert!(is_retryable(
+        &catalog::TOO_BUSY.diagnostic("TooBusy", "busy")
+    ));
+
+    assert!(!is_retryable(
+        &catalog::INVALID_INPUT.diagnostic("InvalidInput", "bad")
+    ));
+    assert!(!is_retryable(
+        &catalog::INTERNAL.diagnostic("ToolError", "bug")
+    ));
+    assert!(!is_retryable(&Diagnostic {
+        error_type: "ToolError".to_string(),
+        error_message: "no code".to_string(),
+    }));
+}
+
+#[tokio::test]
+async fn test_sqs_call_failing_for_good_is_recorded() {
+    let body = json!({"tool": "no_such_async_tool", "arguments": {}}).to_string();
+    let event = json!({"Records": [sqs_message("m-unknown", &body, 1)]});
+
+    let response = handle(&event).await;
+
+    assert_eq!(response, json!({"batchItemFailures": []}));
+    let calls = list(256).await.unwrap();
+    let call = calls
+        .iter()
+        .find(|call| call.tool == "no_such_async_tool")
+        .unwrap();
+    assert_eq!(call.source, Source::Sqs);
+    assert_eq!(call.error_type, "UnknownTool");
+    assert!(call.error_code.as_deref().unwrap().starts_with("E1"));
+    assert_eq!(call.attempts, 1);
+    assert_eq!(call.payload["tool"], "no_such_async_tool");
+}
+
+#[tokio::test]
+async fn test_malformed_sqs_message_is_recorded() {
+    let event = json!({"Records": [sqs_message("m-malformed", "not json", 2)]});
+
+    let response = handle(&event).await;
+
+    assert_eq!(response, json!({"batchItemFailures": []}));
+    let calls = list(256).await.unwrap();
+    let call = calls
+        .iter()
+        .find(|call| call.payload == json!("not json"))
+        .unwrap();
+    assert_eq!(call.tool, "");
+    assert_eq!(call.error_type, "InvalidInput");
+    assert_eq!(call.attempts, 2);
+}
+
+#[tokio::test]
+async fn test_async_calls_carry_no_caller() {
+    let event = eventbridge_event(&json!({
+        "tool": "get_personalized_greeting",
+        "arguments": {
+            "user_name": "Mallory",
+            "auth_token": admin_token(),
+        },
+    }));
+
+    let response = handle(&event).await;
+
+    let greeting = response["greeting"].as_str().unwrap();
+    assert!(!greeting.contains("Mallory"));
+
+    let event = eventbridge_event(&json!({
+        "tool": "admin_flush_cache",
+        "arguments": {"auth_token": admin_token()},
+    }));
+    let response = handle(&event).await;
+    let failure_id = response["failure_id"].as_str().unwrap();
+    let call = find(failure_id).await.unwrap().unwrap();
+    assert_eq!(call.error_type, "Unauthorized");
+    assert_eq!(call.source, Source::EventBridge);
+}
+
+#[tokio::test]
+async fn test_replay_failed_calls() {
+    let diagnostic = catalog::WEATHER_UNAVAILABLE.diagnostic("ToolError", "timed out");
+    let succeeding = FailedCall::new(
+        json!({"tool": "get_personalized_greeting", "arguments": {}}),
+        Source::Sqs,
+        &diagnostic,
+        3,
+    );
+    let failing = FailedCall::new(
+        json!({"tool": "no_such_replayed_tool", "arguments": {}}),
+        Source::Sqs,
+        &diagnostic,
+        3,
+    );
+    let (succeeding_id, failing_id) = (succeeding.failure_id.clone(), failing.failure_id.clone());
+    record(succeeding).await;
+    record(failing).await;
+
+    let payload = json!({
+        "failure_ids": [succeeding_id, failing_id, "missing"],
+        "auth_token": admin_token(),
+    });
+    let response = route_tool("admin_replay_failed", payload).await.unwrap();
+
+    assert_eq!(response["not_found"], json!(["missing"]));
+    let replayed = response["replayed"].as_array().unwrap();
+    assert_eq!(replayed.len(), 2);
+    assert_eq!(replayed[0]["succeeded"], true);
+    assert_eq!(replayed[0]["attempts"], 4);
+    assert!(replayed[0]["outcome"]["response"]["greeting"].is_string());
+    assert_eq!(replayed[1]["succeeded"], false);
+    assert_eq!(replayed[1]["outcome"]["error_type"], "UnknownTool");
+
+    assert!(find(&succeeding_id).await.unwrap().is_none());
+    let failing = find(&failing_id).await.unwrap().unwrap();
+    assert_eq!(failing.attempts, 4);
+    assert_eq!(failing.error_type, "UnknownTool");
+}
+
+#[tokio::test]
+async fn test_replay_failed_requires_admin_scope() {
+    let err = route_tool("admin_replay_failed", json!({}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.error_type, "Unauthorized");
+}
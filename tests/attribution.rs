@@ -0,0 +1,69 @@
+// Provider attribution tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::registry::{
+    self, NAGER_DATE, OPEN_METEO, attach_attribution, bedrock_tool_schema,
+};
+use serde_json::json;
+
+#[test]
+fn test_open_meteo_tools_credit_open_meteo() {
+    for name in [
+        "get_weather",
+        "render_weather_chart",
+        "get_pollen_forecast",
+        "daily_briefing",
+    ] {
+        let tool = registry::find(name).unwrap();
+        assert!(tool.providers.contains(&OPEN_METEO), "{name}");
+    }
+    let holidays = registry::find("get_public_holidays").unwrap();
+    assert_eq!(holidays.providers, &[NAGER_DATE]);
+}
+
+#[test]
+fn test_schema_publishes_attribution_only_for_credited_tools() {
+    for tool in registry::tools() {
+        let schema = bedrock_tool_schema(tool).unwrap();
+        assert_eq!(
+            schema.get("attribution").is_some(),
+            !tool.providers.is_empty(),
+            "{}",
+            tool.name
+        );
+    }
+    let schema = bedrock_tool_schema(registry::find("get_weather").unwrap()).unwrap();
+    assert_eq!(
+        schema["attribution"],
+        json!([{
+            "provider": "Open-Meteo",
+            "text": "Weather data by Open-Meteo.com",
+            "license": "CC BY 4.0",
+            "url": "https://open-meteo.com/"
+        }])
+    );
+}
+
+#[test]
+fn test_attach_keeps_other_metadata() {
+    let mut response = json!({"holidays": [], "_meta": {"invocationId": "abc"}});
+    attach_attribution(&mut response, &[NAGER_DATE]);
+    assert_eq!(response["_meta"]["invocationId"], "abc");
+    assert_eq!(
+        response["_meta"]["attribution"][0]["provider"],
+        "Nager.Date"
+    );
+
+    let mut response = json!({"greeting": "Hello!"});
+    attach_attribution(&mut response, &[]);
+    assert_eq!(response, json!({"greeting": "Hello!"}));
+}
+
+#[tokio::test]
+async fn test_responses_without_providers_carry_no_attribution() {
+    let response = route_tool("get_personalized_greeting", json!({"user_name": "Ada"}))
+        .await
+        .unwrap();
+    assert!(response["_meta"]["attribution"].is_null());
+}
@@ -0,0 +1,173 @@
+// Invocation audit and replay tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::audit::{self, InvocationRecord, MAX_DIFFERENCES};
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::InvocationOutcome;
+use aws_lambda_mcp::registry;
+use common::admin_token;
+use lambda_runtime::Diagnostic;
+use serde_json::{Value, json};
+
+fn response(value: Value) -> InvocationOutcome {
+    InvocationOutcome::from(&Ok(value))
+}
+
+fn failure(error_type: &str, message: &str) -> InvocationOutcome {
+    InvocationOutcome::from(&Err(Diagnostic {
+        error_type: error_type.to_string(),
+        error_message: message.to_string(),
+    }))
+}
+
+#[test]
+fn test_identical_outcomes_have_no_differences() {
+    let outcome = response(json!({"days": [{"date": "2026-10-15", "max": 21.5}]}));
+    assert!(audit::diff(&outcome, &outcome.clone()).is_empty());
+}
+
+#[test]
+fn test_diff_reports_changed_added_and_removed_values() {
+    let recorded = response(json!({"days": [{"max": 21.5}, {"max": 19.0}], "unit": "°C"}));
+    let replayed = response(json!({"days": [{"max": 22.0}], "source": "cache"}));
+    let differences = audit::diff(&recorded, &replayed);
+
+    let paths: Vec<&str> = differences.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/response/days/0/max",
+            "/response/days/1",
+            "/response/source",
+            "/response/unit"
+        ]
+    );
+    assert_eq!(differences[0].recorded, Some(json!(21.5)));
+    assert_eq!(differences[0].replayed, Some(json!(22.0)));
+    assert_eq!(differences[1].replayed, None);
+    assert_eq!(differences[2].recorded, None);
+}
+
+#[test]
+fn test_diff_between_response_and_error() {
+    let differences = audit::diff(
+        &response(json!({"ok": true})),
+        &failure("ToolError", "[E2001] Upstream failed"),
+    );
+
+    let paths: Vec<&str> = differences.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(paths, ["/error_message", "/error_type", "/response"]);
+}
+
+#[test]
+fn test_diff_escapes_pointer_segments() {
+    let differences = audit::diff(
+        &response(json!({"a/b~c": 1})),
+        &response(json!({"a/b~c": 2})),
+    );
+    assert_eq!(differences[0].path, "/response/a~1b~0c");
+}
+
+#[test]
+fn test_diff_is_capped() {
+    let recorded = response(json!({"items": (0..100).collect::<Vec<_>>()}));
+    let replayed = response(json!({"items": []}));
+    assert_eq!(audit::diff(&recorded, &replayed).len(), MAX_DIFFERENCES);
+}
+
+#[test]
+fn test_record_drops_bearer_token() {
+    let context = ToolContext::from_args(
+        "get_personalized_greeting",
+        &json!({"user_id": "ada@example.com", "session_id": "conv-1"}),
+    );
+    let record = InvocationRecord::new(
+        "get_personalized_greeting",
+        json!({"user_id": "ada@example.com", "auth_token": "secret"}),
+        &context,
+        response(json!({})),
+    );
+
+    assert_eq!(record.arguments, json!({"user_id": "ada@example.com"}));
+    assert_eq!(record.user_id.as_deref(), Some("ada@example.com"));
+    assert_eq!(record.session_id.as_deref(), Some("conv-1"));
+    assert_eq!(record.invocation_id.len(), 32);
+}
+
+#[test]
+fn test_attach_id_keeps_other_metadata() {
+    let mut response = json!({"_meta": {"deprecation": {}}});
+    audit::attach_id(&mut response, "abc");
+    assert_eq!(
+        response,
+        json!({"_meta": {"deprecation": {}, "invocationId": "abc"}})
+    );
+}
+
+#[tokio::test]
+async fn test_recorded_invocation_is_found() {
+    let record = InvocationRecord::new(
+        "get_personalized_greeting",
+        json!({"user_id": "grace@example.com"}),
+        &ToolContext::default(),
+        response(json!({"message": "Hello"})),
+    );
+    let invocation_id = audit::record(record.clone()).await;
+
+    assert_eq!(audit::find(&invocation_id).await.unwrap(), Some(record));
+    assert_eq!(audit::find("unknown").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_side_effects_are_refused_in_sandbox() {
+    assert!(audit::refuse_in_sandbox("Sending a notification").is_ok());
+
+    let mut sandbox = ToolContext::default();
+    sandbox.sandbox = true;
+    let err = context::scope(sandbox, async {
+        audit::refuse_in_sandbox("Sending a notification")
+    })
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("replaying a recorded invocation"));
+}
+
+#[tokio::test]
+async fn test_replay_matches_recorded_response() {
+    let tool = registry::find("get_personalized_greeting").unwrap();
+    let arguments = json!({"user_id": "ada@example.com"});
+    let result = (tool.handler)(arguments.clone()).await;
+    let record = InvocationRecord::new(
+        tool.name,
+        arguments,
+        &ToolContext::default(),
+        InvocationOutcome::from(&result),
+    );
+    let invocation_id = audit::record(record).await;
+
+    let payload = json!({"invocation_id": invocation_id, "auth_token": admin_token()});
+    let response = route_tool("admin_replay_invocation", payload)
+        .await
+        .unwrap();
+    assert_eq!(response["tool"], "get_personalized_greeting");
+    assert_eq!(response["matches"], true);
+    assert_eq!(response["differences"], json!([]));
+    assert_eq!(response["replayed"], response["recorded"]);
+}
+
+#[tokio::test]
+async fn test_replay_unknown_invocation() {
+    let payload = json!({"invocation_id": "missing", "auth_token": admin_token()});
+    let err = route_tool("admin_replay_invocation", payload)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(
+        err.error_message
+            .contains("No recorded invocation: missing")
+    );
+}
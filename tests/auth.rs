@@ -0,0 +1,97 @@
+// Authorization policy tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::auth::{ToolPolicy, decode_claims};
+use aws_lambda_mcp::handler::route_tool;
+use common::token;
+use serde_json::json;
+
+#[test]
+fn test_claims_merge_scopes_and_roles() {
+    let claims = decode_claims(&token(&json!({
+        "sub": "user-1",
+        "scp": "User.Read Notifications.Send",
+        "roles": ["Admin"]
+    })))
+    .unwrap();
+
+    assert!(claims.has_scope("Notifications.Send"));
+    assert!(claims.has_scope("Admin"));
+    assert!(!claims.has_scope("Other"));
+}
+
+#[test]
+fn test_expired_token_rejected() {
+    let result = decode_claims(&token(&json!({"sub": "user-1", "exp": 1})));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_policy_overrides() {
+    let policy = ToolPolicy::parse("get_weather=Weather.Read; send_notification=");
+
+    assert_eq!(
+        policy.required_scopes("get_weather").unwrap(),
+        ["Weather.Read".to_string()]
+    );
+    assert!(policy.required_scopes("send_notification").is_none());
+    assert!(policy.authorize("read_feed", None).is_ok());
+    assert!(policy.authorize("get_weather", None).is_err());
+}
+
+#[test]
+fn test_policy_permits_by_claims() {
+    let policy = ToolPolicy::parse("get_weather=Weather.Read");
+    let reader = decode_claims(&token(&json!({"sub": "user-1", "scp": "Weather.Read"}))).unwrap();
+    let other = decode_claims(&token(&json!({"sub": "user-2"}))).unwrap();
+
+    assert!(policy.permits("read_feed", None));
+    assert!(!policy.permits("get_weather", None));
+    assert!(!policy.permits("get_weather", Some(&other)));
+    assert!(policy.permits("get_weather", Some(&reader)));
+}
+
+#[tokio::test]
+async fn test_unknown_tool_suggests_only_permitted_tools() {
+    let err = route_tool("send_notificaton", json!({})).await.unwrap_err();
+    assert_eq!(err.error_type, "UnknownTool");
+    assert!(!err.error_message.contains("Did you mean"));
+    assert!(!err.error_message.contains("send_notification"));
+
+    let sender = token(&json!({"sub": "user-1", "scp": "Notifications.Send"}));
+    let err = route_tool("send_notificaton", json!({"auth_token": sender}))
+        .await
+        .unwrap_err();
+    assert!(
+        err.error_message
+            .contains("Did you mean send_notification?")
+    );
+}
+
+#[tokio::test]
+async fn test_restricted_tool_requires_token() {
+    let payload = json!({
+        "channel": "sns",
+        "destination": "arn:aws:sns:ap-southeast-2:123456789012:alerts",
+        "message": "hello"
+    });
+    let err = route_tool("send_notification", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "Unauthorized");
+}
+
+#[tokio::test]
+async fn test_restricted_tool_rejects_missing_scope() {
+    let payload = json!({
+        "channel": "sns",
+        "destination": "arn:aws:sns:ap-southeast-2:123456789012:alerts",
+        "message": "hello",
+        "auth_token": token(&json!({"sub": "user-1", "scp": "User.Read"}))
+    });
+    let err = route_tool("send_notification", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "Unauthorized");
+    assert!(err.error_message.contains("Notifications.Send"));
+}
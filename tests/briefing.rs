@@ -0,0 +1,91 @@
+// Daily briefing and tool context tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::preferences::UserPreferences;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_context_from_args() {
+    let context = ToolContext::from_args(
+        "daily_briefing",
+        &json!({
+            "user_id": "ada@example.com",
+            "user_name": " ",
+            "session_id": "conv-42",
+            "auth_token": "secret-token"
+        }),
+    );
+
+    assert_eq!(context.tool, "daily_briefing");
+    assert_eq!(context.user_id.as_deref(), Some("ada@example.com"));
+    assert_eq!(context.user_name, None);
+    assert_eq!(context.session_id.as_deref(), Some("conv-42"));
+    assert_eq!(context.auth_token(), Some("secret-token"));
+
+    let args = context.identity_args();
+    assert_eq!(args["user_id"], "ada@example.com");
+    assert_eq!(args["session_id"], "conv-42");
+    assert!(!args.contains_key("user_name"));
+    assert!(!format!("{context:?}").contains("secret-token"));
+}
+
+#[tokio::test]
+async fn test_context_is_scoped() {
+    let context = ToolContext::from_args("get_weather", &json!({"user_id": "ada"}));
+    let inner = context::scope(context, async { context::current() }).await;
+
+    assert_eq!(inner.user_id.as_deref(), Some("ada"));
+    assert_eq!(context::current().user_id, None);
+}
+
+#[test]
+fn test_preferences_from_item() {
+    let item = HashMap::from([
+        ("user_id".to_string(), AttributeValue::S("ada".to_string())),
+        (
+            "home_city".to_string(),
+            AttributeValue::S("Sydney".to_string()),
+        ),
+        (
+            "country_code".to_string(),
+            AttributeValue::S("au".to_string()),
+        ),
+    ]);
+    let preferences = UserPreferences::from_item(&item);
+
+    assert_eq!(preferences.home_city.as_deref(), Some("Sydney"));
+    assert_eq!(preferences.country_code.as_deref(), Some("AU"));
+    assert_eq!(
+        UserPreferences::from_item(&HashMap::new()),
+        UserPreferences::default()
+    );
+}
+
+#[tokio::test]
+async fn test_briefing_without_location_or_country() {
+    let response = route_tool("daily_briefing", json!({"user_name": "Ada"}))
+        .await
+        .unwrap();
+
+    assert_eq!(response["greeting"], "Hello, Ada!");
+    assert_eq!(response["summary"], "Hello, Ada!");
+    assert!(response["weather"].is_null());
+    assert_eq!(response["holidays"], json!([]));
+    assert_eq!(response["unavailable"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_briefing_rejects_invalid_country_code() {
+    let err = route_tool(
+        "daily_briefing",
+        json!({"user_name": "Ada", "country_code": "Australia"}),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("two letters"));
+}
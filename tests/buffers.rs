@@ -0,0 +1,41 @@
+// Buffer pool tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::buffers::{self, BufferPool};
+use serde_json::json;
+
+#[test]
+fn test_dropped_buffer_is_reused_empty() {
+    let pool = BufferPool::new(2, 1024);
+    let mut buffer = pool.take();
+    buffer.extend_from_slice(b"forecast");
+    let capacity = buffer.capacity();
+    drop(buffer);
+    assert_eq!(pool.len(), 1);
+
+    let reused = pool.take();
+    assert!(reused.is_empty());
+    assert_eq!(reused.capacity(), capacity);
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn test_pool_keeps_neither_oversized_nor_surplus_buffers() {
+    let pool = BufferPool::new(1, 8 * 1024);
+    let mut oversized = pool.take();
+    oversized.resize(64 * 1024, b' ');
+    drop(oversized);
+    assert!(pool.is_empty());
+
+    let (first, second) = (pool.take(), pool.take());
+    drop(first);
+    drop(second);
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn test_to_json_matches_value_serialization() {
+    let value = json!({"location": "Sydney", "days": [{"max": 22.4}], "note": "héllo"});
+    let buffer = buffers::to_json(&value).unwrap();
+    assert_eq!(buffer.as_str(), value.to_string());
+}
@@ -0,0 +1,118 @@
+// Cache store tests
+
+use aws_lambda_mcp::cache::{CacheStore, Fetched, Lookup};
+use std::thread::sleep;
+use std::time::Duration;
+
+#[test]
+fn test_cache_get_and_expiry() {
+    let cache: CacheStore<String> = CacheStore::new("test", Duration::from_millis(50), 10);
+    assert!(cache.is_empty());
+
+    cache.insert("sydney", "sunny".to_string());
+    assert_eq!(cache.get("sydney").as_deref(), Some("sunny"));
+    assert_eq!(cache.get("kolkata"), None);
+
+    sleep(Duration::from_millis(80));
+    assert_eq!(cache.get("sydney"), None, "Entry should expire after TTL");
+}
+
+#[test]
+fn test_cache_evicts_oldest_when_full() {
+    let cache: CacheStore<u32> = CacheStore::new("test", Duration::from_mins(1), 2);
+
+    cache.insert("a", 1);
+    sleep(Duration::from_millis(5));
+    cache.insert("b", 2);
+    sleep(Duration::from_millis(5));
+    cache.insert("c", 3);
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("a"), None, "Oldest entry should be evicted");
+    assert_eq!(cache.get("b"), Some(2));
+    assert_eq!(cache.get("c"), Some(3));
+
+    // Overwriting an existing key must not evict anything
+    cache.insert("c", 4);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("c"), Some(4));
+}
+
+#[test]
+fn test_cache_serves_stale_entries() {
+    let cache: CacheStore<String> = CacheStore::new("test", Duration::from_millis(20), 10);
+    cache.insert("sydney", "sunny".to_string());
+
+    sleep(Duration::from_millis(40));
+    assert_eq!(cache.get("sydney"), None);
+    assert_eq!(cache.get_stale("sydney").as_deref(), Some("sunny"));
+    assert_eq!(cache.get_stale("kolkata"), None);
+}
+
+#[test]
+fn test_cache_lookup_stale_while_revalidate() {
+    let cache: CacheStore<u32> = CacheStore::new("test", Duration::from_millis(30), 10);
+    let max_stale = Duration::from_millis(60);
+    cache.insert("a", 1);
+
+    assert_eq!(cache.lookup("a", max_stale), Lookup::Fresh(1));
+    sleep(Duration::from_millis(50));
+    assert_eq!(cache.lookup("a", max_stale), Lookup::Stale(1));
+    assert_eq!(cache.lookup("a", Duration::ZERO), Lookup::Miss);
+    sleep(Duration::from_millis(60));
+    assert_eq!(cache.lookup("a", max_stale), Lookup::Miss);
+
+    // Only one refresh at a time per key
+    assert!(cache.try_begin_refresh("a"));
+    assert!(!cache.try_begin_refresh("a"));
+    cache.end_refresh("a");
+    assert!(cache.try_begin_refresh("a"));
+}
+
+#[test]
+fn test_cache_revalidation_with_etag() {
+    let cache: CacheStore<u32> = CacheStore::new("test", Duration::from_millis(20), 10);
+    let modified = Fetched::Modified {
+        value: 1,
+        etag: Some("\"v1\"".to_string()),
+    };
+    assert_eq!(cache.store_fetched("a", modified), Some(1));
+    assert_eq!(cache.etag("a").as_deref(), Some("\"v1\""));
+
+    sleep(Duration::from_millis(40));
+    assert_eq!(cache.get("a"), None);
+
+    // 304 renews the entry without replacing it
+    assert_eq!(cache.store_fetched("a", Fetched::NotModified), Some(1));
+    assert_eq!(cache.get("a"), Some(1));
+    assert_eq!(cache.etag("a").as_deref(), Some("\"v1\""));
+
+    assert_eq!(cache.store_fetched("missing", Fetched::NotModified), None);
+}
+
+#[test]
+fn test_cache_invalidate_and_stats() {
+    let cache: CacheStore<u32> = CacheStore::new("test", Duration::from_mins(1), 2);
+    cache.insert("paris", 1);
+    sleep(Duration::from_millis(5));
+    cache.insert("paris, tx", 2);
+    let _ = cache.get("paris");
+    let _ = cache.get("berlin");
+    sleep(Duration::from_millis(5));
+    cache.insert("berlin", 3);
+
+    let stats = cache.stats();
+    assert_eq!(stats.entries, 2);
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 1, 1));
+    assert!(
+        stats
+            .hit_rate()
+            .is_some_and(|rate| (rate - 0.5).abs() < f64::EPSILON)
+    );
+
+    assert_eq!(cache.invalidate("par"), 1);
+    assert_eq!(cache.invalidate("par"), 0);
+    assert_eq!(cache.get("berlin"), Some(3));
+    assert_eq!(cache.stats().invalidations, 1);
+    assert_eq!(cache.stats().entries, 1);
+}
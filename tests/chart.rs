@@ -0,0 +1,50 @@
+// Weather chart rendering tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::{Celsius, ContentBlock, Daily, Temperature, WmoCode};
+use aws_lambda_mcp::tools::chart::render_temperature_svg;
+
+fn celsius(temperature: f64) -> Temperature {
+    Celsius::try_from(temperature).unwrap().into()
+}
+
+fn daily(max: Vec<f64>, min: Vec<f64>) -> Daily {
+    Daily {
+        time: (1..=max.len())
+            .map(|day| format!("2024-01-{day:02}").parse().unwrap())
+            .collect(),
+        weather_code: vec![WmoCode::try_from(0).unwrap(); max.len()],
+        temperature_2m_max: max.into_iter().map(celsius).collect(),
+        temperature_2m_min: min.into_iter().map(celsius).collect(),
+    }
+}
+
+#[test]
+fn test_render_temperature_svg() {
+    let forecast = daily(vec![25.0, 27.5, 24.0], vec![15.0, 16.0, 14.5]);
+    let svg = render_temperature_svg("Sydney <NSW> & surrounds", &forecast, "°C").unwrap();
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>"));
+    assert_eq!(svg.matches("<polyline").count(), 2);
+    assert!(svg.contains("Sydney &lt;NSW&gt; &amp; surrounds"));
+    assert!(svg.contains(">28°C</text>"));
+    assert!(svg.contains(">01-03</text>"));
+
+    assert!(ContentBlock::image(svg.as_bytes(), "image/svg+xml").is_ok());
+}
+
+#[test]
+fn test_render_temperature_svg_edge_cases() {
+    let single = render_temperature_svg("Flat", &daily(vec![20.0], vec![20.0]), "°C").unwrap();
+    assert!(!single.contains("NaN"));
+
+    let gaps = daily(vec![20.0], vec![10.0, 11.0]);
+    assert!(
+        !render_temperature_svg("Gaps", &gaps, "°C")
+            .unwrap()
+            .contains("NaN")
+    );
+
+    assert!(render_temperature_svg("Empty", &daily(vec![], vec![]), "°C").is_err());
+}
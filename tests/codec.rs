@@ -0,0 +1,90 @@
+// Binary payload serialization tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::codec::{Format, decode_request, encode_response, negotiate};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::json;
+
+#[test]
+fn test_formats_roundtrip() {
+    let value = json!({
+        "location": "Paris",
+        "daily": {"temperature2mMax": [21.5, 23.0], "time": ["2026-10-15", "2026-10-16"]},
+        "count": 2,
+        "raw": false,
+    });
+    for format in [Format::Json, Format::Cbor, Format::MessagePack] {
+        let bytes = format.encode(&value).unwrap();
+        assert_eq!(format.decode(&bytes).unwrap(), value);
+    }
+    let json = Format::Json.encode(&value).unwrap();
+    assert!(Format::Cbor.encode(&value).unwrap().len() < json.len());
+    assert!(Format::MessagePack.encode(&value).unwrap().len() < json.len());
+}
+
+#[test]
+fn test_media_types() {
+    assert_eq!(
+        Format::from_media_type("application/cbor"),
+        Some(Format::Cbor)
+    );
+    assert_eq!(
+        Format::from_media_type("Application/X-MsgPack; charset=binary"),
+        Some(Format::MessagePack)
+    );
+    assert_eq!(
+        Format::from_media_type("application/json; charset=utf-8"),
+        Some(Format::Json)
+    );
+    assert_eq!(Format::from_media_type("text/plain"), None);
+}
+
+#[test]
+fn test_negotiate_accept() {
+    let event = |accept: &str| json!({"headers": {"Accept": accept}, "body": "{}"});
+
+    assert_eq!(negotiate(&event("application/cbor")), Some(Format::Cbor));
+    assert_eq!(
+        negotiate(&event("application/cbor;q=0, application/msgpack")),
+        Some(Format::MessagePack)
+    );
+    assert_eq!(
+        negotiate(&event("application/json, application/cbor")),
+        None
+    );
+    assert_eq!(negotiate(&event("*/*")), None);
+    assert_eq!(negotiate(&json!({"location": "Paris"})), None);
+}
+
+#[test]
+fn test_decode_binary_request() {
+    let payload = json!({"params": {"arguments": {"location": "Paris"}}});
+    let event = json!({
+        "headers": {"Content-Type": "application/msgpack"},
+        "body": STANDARD.encode(Format::MessagePack.encode(&payload).unwrap()),
+        "isBase64Encoded": true,
+    });
+    assert_eq!(decode_request(&event).unwrap(), Some(payload));
+
+    let json_event = json!({"headers": {"Content-Type": "application/json"}, "body": "{}"});
+    assert_eq!(decode_request(&json_event).unwrap(), None);
+
+    let corrupt = json!({
+        "headers": {"content-type": "application/cbor"},
+        "body": STANDARD.encode([0xff, 0x00]),
+        "isBase64Encoded": true,
+    });
+    assert!(decode_request(&corrupt).is_err());
+}
+
+#[test]
+fn test_encode_binary_response() {
+    let response = json!({"greeting": "Hello, there!"});
+    let wrapped = encode_response(response.clone(), Format::Cbor);
+
+    assert_eq!(wrapped["isBase64Encoded"], true);
+    assert_eq!(wrapped["headers"]["content-type"], "application/cbor");
+    let body = STANDARD.decode(wrapped["body"].as_str().unwrap()).unwrap();
+    assert_eq!(Format::Cbor.decode(&body).unwrap(), response);
+}
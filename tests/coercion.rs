@@ -0,0 +1,113 @@
+// Argument coercion tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::arguments::coercion::coerce;
+use aws_lambda_mcp::registry;
+use serde_json::{Value, json};
+
+fn kinds(schema: &Value, args: &mut Value) -> Vec<(String, &'static str)> {
+    coerce(schema, args)
+        .into_iter()
+        .map(|c| (c.field, c.kind))
+        .collect()
+}
+
+#[test]
+fn test_scalars_are_coerced_to_declared_types() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "days": {"type": "integer"},
+            "ratio": {"type": "number"},
+            "metric": {"type": "boolean"},
+            "label": {"type": "string"}
+        }
+    });
+    let mut args = json!({"days": " 3 ", "ratio": "0.5", "metric": "Yes", "label": 42});
+    let coercions = kinds(&schema, &mut args);
+    assert_eq!(
+        args,
+        json!({"days": 3, "ratio": 0.5, "metric": true, "label": "42"})
+    );
+    assert_eq!(coercions.len(), 4);
+    assert!(coercions.contains(&("days".to_string(), "string_to_integer")));
+
+    let mut args = json!({"days": 2.0, "metric": 0});
+    kinds(&schema, &mut args);
+    assert_eq!(args, json!({"days": 2, "metric": false}));
+}
+
+#[test]
+fn test_ambiguous_values_are_left_alone() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "days": {"type": "integer"},
+            "metric": {"type": "boolean"}
+        }
+    });
+    let mut args = json!({"days": "three", "metric": "maybe", "extra": "1"});
+    let original = args.clone();
+    assert!(kinds(&schema, &mut args).is_empty());
+    assert_eq!(args, original);
+
+    for days in [2.5, 1e30] {
+        let mut args = json!({"days": days});
+        assert!(kinds(&schema, &mut args).is_empty());
+    }
+}
+
+#[test]
+fn test_lists_and_objects_are_parsed() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "ids": {"type": "array", "items": {"type": "integer"}},
+            "filter": {"type": "object"}
+        }
+    });
+    let mut args = json!({"tags": "a, b,,c", "ids": "[1, \"2\"]", "filter": "{\"x\": 1}"});
+    let coercions = kinds(&schema, &mut args);
+    assert_eq!(
+        args,
+        json!({"tags": ["a", "b", "c"], "ids": [1, 2], "filter": {"x": 1}})
+    );
+    assert!(coercions.contains(&("tags".to_string(), "list_to_array")));
+    assert!(coercions.contains(&("ids".to_string(), "json_to_array")));
+    assert!(coercions.contains(&("ids[]".to_string(), "string_to_integer")));
+
+    let mut args = json!({"ids": 7});
+    kinds(&schema, &mut args);
+    assert_eq!(args, json!({"ids": [7]}));
+}
+
+#[test]
+fn test_nested_and_optional_fields_are_coerced() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "steps": {"type": "array", "items": {"$ref": "#/$defs/Step"}},
+            "limit": {"anyOf": [{"type": "null"}, {"type": "integer"}]}
+        },
+        "$defs": {
+            "Step": {"type": "object", "properties": {"retries": {"type": "integer"}}}
+        }
+    });
+    let mut args = json!({"steps": [{"retries": "2"}], "limit": "10"});
+    let coercions = kinds(&schema, &mut args);
+    assert_eq!(args, json!({"steps": [{"retries": 2}], "limit": 10}));
+    assert!(coercions.contains(&("steps[].retries".to_string(), "string_to_integer")));
+
+    let mut args = json!({"limit": null});
+    assert!(kinds(&schema, &mut args).is_empty());
+}
+
+#[test]
+fn test_registered_tool_schema_is_followed() {
+    let tool = registry::find("get_pollen_forecast").unwrap();
+    let mut args = json!({"location": "Sydney", "days": "3"});
+    let coercions = coerce((tool.input_schema)().as_value(), &mut args);
+    assert_eq!(args["days"], json!(3));
+    assert_eq!(coercions.len(), 1);
+}
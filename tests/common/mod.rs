@@ -0,0 +1,20 @@
+// Helpers shared by the integration tests; each test crate uses only some
+#![allow(dead_code, clippy::unwrap_used)]
+
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde_json::{Value, json};
+
+/// A bearer token carrying `claims`, signed with the secret the tests use.
+pub fn token(claims: &Value) -> String {
+    encode(
+        &Header::default(),
+        claims,
+        &EncodingKey::from_secret(b"test"),
+    )
+    .unwrap()
+}
+
+/// A bearer token for a caller holding the `Admin` role.
+pub fn admin_token() -> String {
+    token(&json!({"sub": "admin-1", "roles": ["Admin"]}))
+}
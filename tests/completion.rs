@@ -0,0 +1,78 @@
+// Argument completion tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::completion::{complete, handle};
+use aws_lambda_mcp::gazetteer::names_starting_with;
+use aws_lambda_mcp::models::{CompleteParams, CompletionArgument, CompletionRef};
+use serde_json::json;
+
+fn params(tool: &str, argument: &str, value: &str) -> CompleteParams {
+    CompleteParams {
+        reference: CompletionRef::Tool {
+            name: tool.to_string(),
+        },
+        argument: CompletionArgument {
+            name: argument.to_string(),
+            value: value.to_string(),
+        },
+    }
+}
+
+#[test]
+fn test_complete_location_from_gazetteer() {
+    let result = complete(&params("get_weather", "location", "san"));
+    assert_eq!(
+        result.completion.values,
+        ["San Diego", "San Francisco", "Santiago"]
+    );
+    assert_eq!(result.completion.total, Some(3));
+    assert!(!result.completion.has_more);
+
+    assert_eq!(names_starting_with("zu"), ["Zürich"]);
+    assert_eq!(names_starting_with("Sao P"), ["São Paulo"]);
+}
+
+#[test]
+fn test_complete_enum_arguments() {
+    let units = complete(&params("get_weather", "units", "IM"));
+    assert_eq!(units.completion.values, ["imperial"]);
+
+    let channels = complete(&params("send_notification", "channel", ""));
+    assert_eq!(channels.completion.values, ["sns", "email"]);
+}
+
+#[test]
+fn test_complete_unknown_targets_is_empty() {
+    for (tool, argument) in [("no_such_tool", "location"), ("get_weather", "days")] {
+        let result = complete(&params(tool, argument, "a"));
+        assert!(result.completion.values.is_empty(), "{tool}.{argument}");
+    }
+}
+
+#[test]
+fn test_handle_completion_request() {
+    let response = handle(&json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "completion/complete",
+        "params": {
+            "ref": {"type": "ref/tool", "name": "get_weather"},
+            "argument": {"name": "units", "value": "m"}
+        }
+    }))
+    .unwrap();
+    assert_eq!(
+        response,
+        json!({"completion": {"values": ["metric"], "total": 1, "hasMore": false}})
+    );
+
+    let prompt = handle(&json!({"params": {
+        "ref": {"type": "ref/prompt", "name": "greeting"},
+        "argument": {"name": "name", "value": ""}
+    }}))
+    .unwrap();
+    assert_eq!(prompt["completion"]["values"], json!([]));
+
+    let err = handle(&json!({"params": {"argument": {}}})).unwrap_err();
+    assert_eq!(err.error_type, "InvalidInput");
+}
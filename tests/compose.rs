@@ -0,0 +1,112 @@
+// Tool composition (run_workflow) tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use serde_json::{Value, json};
+
+async fn run(payload: Value) -> Value {
+    route_tool("run_workflow", payload).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_steps_share_outputs_and_identity() {
+    let response = run(json!({
+        "user_name": "Ada",
+        "steps": [
+            {"id": "greet", "tool": "get_personalized_greeting"},
+            {
+                "id": "again",
+                "tool": "get_personalized_greeting",
+                "arguments": {"user_name": "Mallory", "note": "${greet.greeting}"}
+            }
+        ]
+    }))
+    .await;
+
+    assert_eq!(response["status"], "succeeded");
+    let steps = response["steps"].as_array().unwrap();
+    assert_eq!(steps[0]["output"]["greeting"], "Hello, Ada!");
+    // Identity comes from the workflow call, never from step arguments
+    assert_eq!(steps[1]["output"]["greeting"], "Hello, Ada!");
+}
+
+#[tokio::test]
+async fn test_failed_reference_skips_dependents() {
+    let response = run(json!({
+        "steps": [
+            {"id": "greet", "tool": "get_personalized_greeting"},
+            {
+                "id": "broken",
+                "tool": "get_personalized_greeting",
+                "arguments": {"note": "${greet.missing}"}
+            },
+            {"id": "after", "tool": "get_personalized_greeting", "depends_on": ["broken"]}
+        ]
+    }))
+    .await;
+
+    assert_eq!(response["status"], "partial");
+    let steps = response["steps"].as_array().unwrap();
+    assert_eq!(steps[0]["status"], "succeeded");
+    assert_eq!(steps[1]["status"], "failed");
+    assert!(
+        steps[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("${greet.missing}")
+    );
+    assert_eq!(steps[2]["status"], "skipped");
+    assert!(steps[2].get("output").is_none());
+}
+
+#[tokio::test]
+async fn test_steps_run_in_dependency_order() {
+    // Declared out of order: `first` must run before the step that references it
+    let response = run(json!({
+        "steps": [
+            {
+                "id": "second",
+                "tool": "get_personalized_greeting",
+                "arguments": {"note": "${first.greeting}"}
+            },
+            {"id": "first", "tool": "get_personalized_greeting"}
+        ]
+    }))
+    .await;
+
+    assert_eq!(response["status"], "succeeded");
+    assert_eq!(response["steps"][0]["id"], "second");
+}
+
+#[tokio::test]
+async fn test_invalid_workflows_are_rejected() {
+    for (steps, expected) in [
+        (json!([]), "at least one step"),
+        (
+            json!([{"id": "a", "tool": "get_personalized_greeting"}, {"id": "a", "tool": "get_personalized_greeting"}]),
+            "Duplicate step id",
+        ),
+        (json!([{"id": "a", "tool": "no_such_tool"}]), "unknown tool"),
+        (json!([{"id": "a", "tool": "run_workflow"}]), "cannot call"),
+        (
+            json!([{"id": "a", "tool": "get_personalized_greeting", "depends_on": ["b"]}]),
+            "undeclared step",
+        ),
+        (
+            json!([
+                {"id": "a", "tool": "get_personalized_greeting", "depends_on": ["b"]},
+                {"id": "b", "tool": "get_personalized_greeting", "arguments": {"x": "${a.greeting}"}}
+            ]),
+            "dependency cycle",
+        ),
+    ] {
+        let err = route_tool("run_workflow", json!({"steps": steps}))
+            .await
+            .unwrap_err();
+        assert!(
+            err.error_message.contains(expected),
+            "expected {expected:?} in {}",
+            err.error_message
+        );
+    }
+}
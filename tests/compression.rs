@@ -0,0 +1,52 @@
+// Response compression tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::compression::{Encoding, compress_response, negotiate};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::read::GzDecoder;
+use serde_json::json;
+use std::io::Read;
+
+#[test]
+fn test_negotiate_accept_encoding() {
+    let event = |accept: &str| json!({"headers": {"Accept-Encoding": accept}, "body": "{}"});
+
+    assert_eq!(negotiate(&event("gzip, deflate, br")), Some(Encoding::Gzip));
+    assert_eq!(negotiate(&event("br, deflate")), Some(Encoding::Deflate));
+    assert_eq!(
+        negotiate(&event("gzip;q=0, deflate;q=0.5")),
+        Some(Encoding::Deflate)
+    );
+    assert_eq!(negotiate(&event("*")), Some(Encoding::Gzip));
+    assert_eq!(negotiate(&event("br")), None);
+    assert_eq!(negotiate(&json!({"location": "Paris"})), None);
+}
+
+#[test]
+fn test_compress_response_roundtrip() {
+    let response = json!({"items": vec!["a long repeated forecast line"; 200]});
+    let wrapped = compress_response(response.clone(), Encoding::Gzip, 1_024);
+
+    assert_eq!(wrapped["isBase64Encoded"], true);
+    assert_eq!(wrapped["headers"]["content-encoding"], "gzip");
+
+    let compressed = STANDARD.decode(wrapped["body"].as_str().unwrap()).unwrap();
+    let mut body = String::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut body)
+        .unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        response
+    );
+}
+
+#[test]
+fn test_small_responses_left_uncompressed() {
+    let response = json!({"greeting": "Hello, there!"});
+    assert_eq!(
+        compress_response(response.clone(), Encoding::Gzip, 1_024),
+        response
+    );
+}
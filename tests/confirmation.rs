@@ -0,0 +1,157 @@
+// Confirmation token tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::confirmation;
+use aws_lambda_mcp::context::ToolContext;
+use aws_lambda_mcp::models::error::AppError;
+use aws_lambda_mcp::registry::{self, bedrock_tool_schema};
+use serde_json::{Value, json};
+
+fn caller(user_id: &str) -> ToolContext {
+    let mut context = ToolContext::default();
+    context.user_id = Some(user_id.to_string());
+    context
+}
+
+fn start_args() -> Value {
+    json!({"workflow": "nightly-report", "input": {"day": "2026-10-15"}})
+}
+
+async fn issue_token(args: &Value, context: &ToolContext) -> String {
+    let tool = registry::find("start_workflow").unwrap();
+    let pending = confirmation::check(tool, None, args, context)
+        .await
+        .unwrap()
+        .unwrap();
+    pending.issue(&json!({})).unwrap().token
+}
+
+#[test]
+fn test_destructive_tools_have_side_effects() {
+    for tool in registry::tools() {
+        assert!(!tool.destructive() || tool.side_effects(), "{}", tool.name);
+    }
+    assert!(registry::find("start_workflow").unwrap().destructive());
+    assert!(!registry::find("send_notification").unwrap().destructive());
+}
+
+#[test]
+fn test_only_destructive_tools_advertise_confirmation_token() {
+    for tool in registry::tools() {
+        let schema = bedrock_tool_schema(tool).unwrap();
+        let advertised = schema["inputSchema"]["properties"]
+            .get("confirmation_token")
+            .is_some();
+        assert_eq!(advertised, tool.destructive(), "{}", tool.name);
+    }
+}
+
+#[test]
+fn test_takes_token_from_destructive_tools() {
+    let tool = registry::find("start_workflow").unwrap();
+    let mut args = json!({"workflow": "nightly-report", "confirmation_token": " abc "});
+    assert_eq!(
+        confirmation::take(tool, &mut args).unwrap().as_deref(),
+        Some("abc")
+    );
+    assert_eq!(args, json!({"workflow": "nightly-report"}));
+    assert_eq!(confirmation::take(tool, &mut json!({})).unwrap(), None);
+    assert!(matches!(
+        confirmation::take(tool, &mut json!({"confirmation_token": 1})),
+        Err(AppError::ValidationError(_))
+    ));
+
+    let tool = registry::find("get_weather").unwrap();
+    let mut args = json!({"confirmation_token": "abc"});
+    assert_eq!(confirmation::take(tool, &mut args).unwrap(), None);
+    assert_eq!(args["confirmation_token"], "abc");
+}
+
+#[tokio::test]
+async fn test_unconfirmed_call_returns_summary_and_token() {
+    let tool = registry::find("start_workflow").unwrap();
+    let pending = confirmation::check(tool, None, &start_args(), &caller("ada"))
+        .await
+        .unwrap()
+        .unwrap();
+    let mut response = json!({
+        "plan": {
+            "action": "Start a Step Functions execution",
+            "target": "arn:aws:states:us-east-1:123456789012:stateMachine:nightly-report"
+        }
+    });
+    let issued = pending.issue(&response).unwrap();
+    assert_eq!(
+        issued.summary,
+        "Start a Step Functions execution: arn:aws:states:us-east-1:123456789012:stateMachine:nightly-report"
+    );
+    confirmation::attach(&mut response, &issued);
+    assert_eq!(response["_meta"]["confirmation"]["token"], issued.token);
+    assert!(response["_meta"]["confirmation"]["expiresAt"].is_string());
+
+    let tool = registry::find("get_weather").unwrap();
+    assert!(
+        confirmation::check(tool, None, &json!({}), &caller("ada"))
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_token_confirms_the_call_once() {
+    let tool = registry::find("start_workflow").unwrap();
+    let context = caller("ada");
+    let token = issue_token(&start_args(), &context).await;
+    assert!(
+        confirmation::check(tool, Some(&token), &start_args(), &context)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let error = confirmation::check(tool, Some(&token), &start_args(), &context)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, AppError::ConfirmationInvalid(_)));
+    assert!(
+        error
+            .to_diagnostic("Unauthorized")
+            .error_message
+            .starts_with("[E1010]")
+    );
+}
+
+#[tokio::test]
+async fn test_token_only_confirms_the_same_call_by_the_same_caller() {
+    let tool = registry::find("start_workflow").unwrap();
+    let token = issue_token(&start_args(), &caller("ada")).await;
+
+    let other_args = json!({"workflow": "nightly-report", "input": {"day": "2026-10-16"}});
+    assert!(matches!(
+        confirmation::check(tool, Some(&token), &other_args, &caller("ada")).await,
+        Err(AppError::ConfirmationInvalid(_))
+    ));
+    assert!(matches!(
+        confirmation::check(tool, Some(&token), &start_args(), &caller("grace")).await,
+        Err(AppError::ConfirmationInvalid(_))
+    ));
+    assert!(matches!(
+        confirmation::check(tool, Some("forged.token"), &start_args(), &caller("ada")).await,
+        Err(AppError::ConfirmationInvalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_dry_run_with_token_stays_pending() {
+    let tool = registry::find("start_workflow").unwrap();
+    let mut context = caller("ada");
+    let token = issue_token(&start_args(), &context).await;
+    context.dry_run = true;
+    assert!(
+        confirmation::check(tool, Some(&token), &start_args(), &context)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
@@ -0,0 +1,125 @@
+// Consent tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::consent::{anonymize, granted};
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::RecordConsentRequest;
+use aws_lambda_mcp::preferences::UserPreferences;
+use aws_lambda_mcp::tools::consent::record_consent;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_anonymize_strips_personal_data() {
+    let mut args = json!({
+        "user_id": "ada@example.com",
+        "user_name": "Ada",
+        "session_id": "conv-42",
+        "location": "Sydney"
+    });
+    let mut context = ToolContext::from_args("get_weather", &args);
+    assert!(context.consent);
+
+    anonymize(&mut args, &mut context);
+    assert_eq!(args, json!({"session_id": "conv-42", "location": "Sydney"}));
+    assert_eq!(context.user_name, None);
+    assert!(!context.consent);
+
+    // The caller is still identified for authorization and audit, but nested
+    // calls and memories never see who they are
+    assert_eq!(context.user_id.as_deref(), Some("ada@example.com"));
+    let nested = context.identity_args();
+    assert!(!nested.contains_key("user_id"));
+    assert!(!nested.contains_key("user_name"));
+    assert_eq!(nested["session_id"], "conv-42");
+    assert!(context.memory().is_none());
+}
+
+#[test]
+fn test_preferences_carry_consent() {
+    let item = HashMap::from([
+        ("user_id".to_string(), AttributeValue::S("ada".to_string())),
+        (
+            "consent_granted_at".to_string(),
+            AttributeValue::S("2026-10-01T09:30:00+10:00".to_string()),
+        ),
+    ]);
+    let preferences = UserPreferences::from_item(&item);
+    assert_eq!(
+        preferences.consent_granted_at.unwrap().to_rfc3339(),
+        "2026-09-30T23:30:00+00:00"
+    );
+
+    let malformed = HashMap::from([(
+        "consent_granted_at".to_string(),
+        AttributeValue::S("yesterday".to_string()),
+    )]);
+    assert_eq!(
+        UserPreferences::from_item(&malformed).consent_granted_at,
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_consent_is_implied_unless_required() {
+    // CONSENT_REQUIRED is off by default
+    assert!(
+        granted(&ToolContext::from_args(
+            "get_weather",
+            &json!({"user_id": "ada@example.com"})
+        ))
+        .await
+    );
+    assert!(granted(&ToolContext::default()).await);
+
+    let greeting = route_tool(
+        "get_personalized_greeting",
+        json!({"user_id": "ada@example.com", "user_name": "Ada"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(greeting["greeting"], "Hello, Ada!");
+}
+
+#[tokio::test]
+async fn test_record_consent() {
+    let mut caller = ToolContext::from_args("record_consent", &json!({}));
+    caller.verified_user_id = Some("grace@example.com".to_string());
+    let response = Box::pin(context::scope(
+        caller.clone(),
+        record_consent(RecordConsentRequest { granted: true }),
+    ))
+    .await
+    .unwrap();
+    assert!(response.granted);
+    assert!(response.granted_at.unwrap().starts_with("20"));
+
+    let response = Box::pin(context::scope(
+        caller,
+        record_consent(RecordConsentRequest { granted: false }),
+    ))
+    .await
+    .unwrap();
+    assert!(!response.granted);
+    assert!(response.granted_at.is_none());
+}
+
+#[tokio::test]
+async fn test_record_consent_needs_a_signed_identity() {
+    // Anonymous callers cannot record consent
+    let diagnostic = route_tool("record_consent", json!({"granted": true}))
+        .await
+        .unwrap_err();
+    assert!(diagnostic.error_message.starts_with("[E1004]"));
+
+    // Nor can a caller who only names a user
+    let diagnostic = route_tool(
+        "record_consent",
+        json!({"user_id": "grace@example.com", "granted": true}),
+    )
+    .await
+    .unwrap_err();
+    assert!(diagnostic.error_message.starts_with("[E1004]"));
+}
@@ -0,0 +1,131 @@
+// Schema constraint description tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::registry::{Effects, ToolDescriptor, bedrock_tool_schema};
+use aws_lambda_mcp::slo::Slo;
+use schemars::{JsonSchema, schema_for};
+use serde_json::{Value, json};
+
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+struct SearchRequest {
+    /// Words to search for
+    #[schemars(length(min = 3, max = 200))]
+    query: String,
+    /// Results per page
+    #[schemars(range(min = 1, max = 20))]
+    limit: Option<usize>,
+    /// Results to skip
+    offset: u32,
+    order: Order,
+    language: Language,
+    #[schemars(length(max = 5))]
+    filters: Vec<Filter>,
+}
+
+/// Sort order: "newest" or "oldest"
+#[derive(JsonSchema)]
+#[schemars(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum Order {
+    Newest,
+    Oldest,
+}
+
+/// Search language
+#[derive(JsonSchema)]
+#[schemars(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum Language {
+    En,
+    De,
+}
+
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+struct Filter {
+    /// Share of the score the filter contributes
+    #[schemars(range(min = 0.1))]
+    weight: f64,
+}
+
+fn search_tool() -> ToolDescriptor {
+    ToolDescriptor {
+        name: "search",
+        description: "Searches documents.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: None,
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: || schema_for!(SearchRequest),
+        output_schema: || schema_for!(Value),
+        handler: |args| Box::pin(async move { Ok(args) }),
+    }
+}
+
+fn property(name: &str) -> Value {
+    bedrock_tool_schema(&search_tool()).unwrap()["inputSchema"]["properties"][name].clone()
+}
+
+#[test]
+fn test_length_is_described() {
+    let query = property("query");
+    assert_eq!(
+        query["description"],
+        "Words to search for. Must be between 3 and 200 characters long."
+    );
+    assert_eq!(query["maxLength"], json!(200));
+}
+
+#[test]
+fn test_range_is_described() {
+    assert_eq!(
+        property("limit")["description"],
+        "Results per page. Must be between 1 and 20."
+    );
+}
+
+#[test]
+fn test_unsigned_minimum_is_not_described() {
+    assert_eq!(property("offset")["description"], "Results to skip");
+}
+
+#[test]
+fn test_enum_values_are_described_unless_already_named() {
+    assert_eq!(
+        property("order")["description"],
+        "Sort order: \"newest\" or \"oldest\""
+    );
+    assert_eq!(
+        property("language")["description"],
+        "Search language. One of: en, de."
+    );
+}
+
+#[test]
+fn test_nested_constraints_are_described() {
+    let filters = property("filters");
+    assert_eq!(filters["description"], "Must be at most 5 items.");
+    assert_eq!(
+        filters["items"]["properties"]["weight"]["description"],
+        "Share of the score the filter contributes. Must be at least 0.1."
+    );
+}
+
+#[test]
+fn test_published_schemas_describe_constraints() {
+    let tool = aws_lambda_mcp::registry::find("get_public_holidays").unwrap();
+    let schema = bedrock_tool_schema(tool).unwrap();
+    let limit = &schema["inputSchema"]["properties"]["limit"];
+    assert!(
+        limit["description"]
+            .as_str()
+            .unwrap()
+            .ends_with("Must be between 1 and 100.")
+    );
+    assert_eq!(limit["maximum"], json!(100));
+}
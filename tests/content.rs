@@ -0,0 +1,222 @@
+// Content extraction tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::content::{
+    ContentFormat, ExtractOptions, decode_entities, extract_body, extract_html, truncate_to_tokens,
+};
+use aws_lambda_mcp::models::ContentBlock;
+use serde_json::json;
+
+const SAMPLE_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Sydney Weather &amp; Climate</title>
+    <style>body { color: red; }</style>
+    <script>if (a < b) { document.write("<p>injected</p>"); }</script>
+</head>
+<body>
+    <header><nav><a href="/">Home</a> <a href="/about">About</a></nav></header>
+    <main>
+        <h1>Sydney Forecast</h1>
+        <p>Expect <strong>sunny</strong> skies with a
+           high of 25&deg;C. See the <a href="https://example.com/radar">radar</a>.</p>
+        <ul>
+            <li>Monday: sunny</li>
+            <li>Tuesday: <em>showers</em></li>
+        </ul>
+        <div role="navigation"><a href="/prev">Previous</a></div>
+    </main>
+    <footer>Copyright 2025</footer>
+</body>
+</html>"#;
+
+#[test]
+fn test_html_to_markdown_removes_boilerplate() {
+    let result = extract_html(SAMPLE_PAGE, &ExtractOptions::default());
+
+    assert_eq!(result.title.as_deref(), Some("Sydney Weather & Climate"));
+    assert!(result.content.starts_with("# Sydney Forecast"));
+    assert!(
+        result
+            .content
+            .contains("Expect **sunny** skies with a high of 25°C.")
+    );
+    assert!(result.content.contains("- Monday: sunny"));
+    assert!(result.content.contains("- Tuesday: _showers_"));
+
+    for boilerplate in [
+        "Home",
+        "About",
+        "Previous",
+        "Copyright",
+        "injected",
+        "color",
+    ] {
+        assert!(
+            !result.content.contains(boilerplate),
+            "Boilerplate '{boilerplate}' leaked into: {}",
+            result.content
+        );
+    }
+    assert!(!result.truncated);
+}
+
+#[test]
+fn test_links_are_preserved() {
+    let result = extract_html(SAMPLE_PAGE, &ExtractOptions::default());
+    assert!(
+        result
+            .content
+            .contains("[radar](https://example.com/radar)")
+    );
+
+    let text = extract_html(
+        SAMPLE_PAGE,
+        &ExtractOptions {
+            format: ContentFormat::Text,
+            ..ExtractOptions::default()
+        },
+    );
+    assert!(text.content.contains("radar (https://example.com/radar)"));
+    assert!(!text.content.contains("**"));
+    assert!(!text.content.contains('#'));
+
+    let no_links = extract_html(
+        SAMPLE_PAGE,
+        &ExtractOptions {
+            preserve_links: false,
+            ..ExtractOptions::default()
+        },
+    );
+    assert!(no_links.content.contains("See the radar."));
+}
+
+#[test]
+fn test_structural_elements() {
+    let html = "<body><ol><li>one</li><li>two<ul><li>nested</li></ul></li></ol>\
+                <pre>let x = 1;\n  let y = 2;</pre>\
+                <table><tr><th>Day</th><th>Max</th></tr><tr><td>Mon</td><td>25</td></tr></table>\
+                <blockquote>quoted text</blockquote><p><a href=\"javascript:void(0)\">noop</a></p></body>";
+    let result = extract_html(html, &ExtractOptions::default());
+
+    assert!(result.content.contains("1. one\n2. two\n  - nested"));
+    assert!(
+        result
+            .content
+            .contains("```\nlet x = 1;\n  let y = 2;\n```")
+    );
+    assert!(
+        result
+            .content
+            .contains("| Day | Max |\n| --- | --- |\n| Mon | 25 |")
+    );
+    assert!(result.content.contains("> quoted text"));
+    assert!(result.content.contains("noop"));
+    assert!(!result.content.contains("javascript:"));
+}
+
+#[test]
+fn test_truncation_respects_token_budget() {
+    let paragraphs: Vec<String> = (0..50)
+        .map(|i| format!("<p>Paragraph number {i} with some filler words.</p>"))
+        .collect();
+    let paragraphs = paragraphs.concat();
+    let result = extract_html(
+        &paragraphs,
+        &ExtractOptions {
+            max_tokens: Some(50),
+            ..ExtractOptions::default()
+        },
+    );
+
+    assert!(result.truncated);
+    assert!(result.content.ends_with("[Content truncated]"));
+    assert!(result.content.starts_with("Paragraph number 0"));
+    assert!(result.estimated_tokens <= 60);
+
+    let (short, truncated) = truncate_to_tokens("tiny", 10);
+    assert_eq!(short, "tiny");
+    assert!(!truncated);
+
+    let (cut, truncated) = truncate_to_tokens(&"word ".repeat(100), 5);
+    assert!(truncated);
+    assert!(cut.starts_with("word word word word"));
+}
+
+#[test]
+fn test_extract_body_by_content_type() {
+    let json = r#"{"temperature": 21}"#;
+    let passthrough = extract_body(Some("application/json"), json, &ExtractOptions::default());
+    assert_eq!(passthrough.content, json);
+
+    let html = extract_body(
+        Some("text/html; charset=utf-8"),
+        "<p>Hello <b>world</b></p>",
+        &ExtractOptions::default(),
+    );
+    assert_eq!(html.content, "Hello **world**");
+}
+
+#[test]
+fn test_unclosed_tag_is_kept_as_text() {
+    let html = "<p>Rain &amp; wind</p><a href=\"x";
+    let result = extract_html(html, &ExtractOptions::default());
+    assert!(result.content.contains("Rain & wind"));
+
+    // Each '<' would otherwise be rescanned to the end of the input
+    let garbage = format!("<p>start</p>{}", "<a x=\"".repeat(20_000));
+    let result = extract_html(&garbage, &ExtractOptions::default());
+    assert!(result.content.contains("start"));
+}
+
+#[test]
+fn test_entity_decoding() {
+    assert_eq!(decode_entities("a &amp; b &lt;c&gt;"), "a & b <c>");
+    assert_eq!(
+        decode_entities("&#169; &#x2014; &unknown; &"),
+        "© — &unknown; &"
+    );
+}
+
+#[test]
+fn test_image_block_serializes_per_mcp() {
+    let png = b"\x89PNG\r\n\x1a\nrest-of-image";
+    let block = ContentBlock::image(png, "IMAGE/PNG").unwrap();
+    assert_eq!(
+        serde_json::to_value(&block).unwrap(),
+        json!({"type": "image", "data": "iVBORw0KGgpyZXN0LW9mLWltYWdl", "mimeType": "image/png"})
+    );
+}
+
+#[test]
+fn test_binary_content_validation() {
+    assert!(ContentBlock::image(b"GIF89a...", "image/png").is_err());
+    assert!(ContentBlock::image(b"<svg></svg>", "image/svg+xml").is_ok());
+    assert!(ContentBlock::image(b"%PDF-1.7", "application/pdf").is_err());
+    assert!(ContentBlock::image(b"MZ", "application/x-msdownload").is_err());
+
+    let blob = ContentBlock::blob(
+        "s3://bucket/report.pdf",
+        b"%PDF-1.7",
+        "application/pdf; v=1",
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(&blob).unwrap()["resource"]["mimeType"],
+        "application/pdf"
+    );
+    assert!(ContentBlock::blob("file:///a.exe", b"MZ", "application/x-msdownload").is_err());
+}
+
+#[test]
+fn test_binary_content_size_cap() {
+    let oversized = vec![0u8; 2 * 1_048_576];
+    let err = ContentBlock::blob(
+        "s3://bucket/big.bin",
+        &oversized,
+        "application/octet-stream",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("byte limit"), "{err}");
+}
@@ -0,0 +1,165 @@
+// Contract tests against canonical Open-Meteo responses
+//
+// Each upstream response the server depends on has a canonical copy in
+// tests/fixtures/contracts. The tests check that the canonical copy still
+// parses into our models, and compare its shape to a fresh response when
+// live mode is enabled:
+//
+//     CONTRACTS_LIVE=1 cargo test --test contracts
+//
+// A renamed or retyped upstream field then fails with a diff of the JSON
+// paths involved instead of a generic parse error in production.
+
+use aws_lambda_mcp::models::open_meteo::OpenMeteoResponse;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+
+const GEOCODE_URL: &str =
+    "https://geocoding-api.open-meteo.com/v1/search?name=Sydney&count=1&language=en&format=json";
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast?latitude=-33.86785&longitude=151.20732&current=temperature_2m,relative_humidity_2m,apparent_temperature,weather_code,wind_speed_10m,wind_direction_10m&daily=weather_code,temperature_2m_max,temperature_2m_min,snowfall_sum,precipitation_sum,relative_humidity_2m_max&hourly=cape,lifted_index&timezone=Australia/Sydney";
+
+/// Paths the geocoding lookup reads from each result
+const GEOCODE_REQUIRED: &[&str] = &[
+    "$.results",
+    "$.results[].name",
+    "$.results[].latitude",
+    "$.results[].longitude",
+    "$.results[].timezone",
+];
+
+fn canonical(name: &str) -> Value {
+    let path = format!("tests/fixtures/contracts/{name}");
+    let raw = fs::read_to_string(&path).unwrap_or_default();
+    serde_json::from_str(&raw).unwrap_or(Value::Null)
+}
+
+/// Flattens a JSON document into `path -> type`, merging array elements
+/// under `path[]`.
+fn shape(value: &Value) -> BTreeMap<String, &'static str> {
+    fn walk(value: &Value, path: String, out: &mut BTreeMap<String, &'static str>) {
+        let kind = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(items) => {
+                for item in items {
+                    walk(item, format!("{path}[]"), out);
+                }
+                "array"
+            }
+            Value::Object(fields) => {
+                for (name, field) in fields {
+                    walk(field, format!("{path}.{name}"), out);
+                }
+                "object"
+            }
+        };
+        // Nulls say nothing about the type of a field seen elsewhere
+        if kind != "null" || !out.contains_key(&path) {
+            out.insert(path, kind);
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    walk(value, "$".to_string(), &mut out);
+    out
+}
+
+/// Lists how `actual` differs from `expected`: missing paths, changed
+/// types, and new paths (which, next to a missing one, usually mean a rename).
+fn contract_diff(expected: &Value, actual: &Value) -> Vec<String> {
+    let expected = shape(expected);
+    let actual = shape(actual);
+    let mut diff = Vec::new();
+
+    for (path, kind) in &expected {
+        match actual.get(path) {
+            None => diff.push(format!("- {path} ({kind}) no longer present")),
+            Some(&found) if found != *kind && found != "null" && *kind != "null" => {
+                diff.push(format!("~ {path} changed from {kind} to {found}"));
+            }
+            Some(_) => {}
+        }
+    }
+    for (path, kind) in &actual {
+        if !expected.contains_key(path) {
+            diff.push(format!("+ {path} ({kind}) is new"));
+        }
+    }
+    diff
+}
+
+fn assert_compatible(name: &str, expected: &Value, actual: &Value) {
+    let diff = contract_diff(expected, actual);
+    assert!(
+        !diff.iter().any(|line| !line.starts_with('+')),
+        "{name} contract broken:\n{}",
+        diff.join("\n")
+    );
+}
+
+#[test]
+fn test_canonical_forecast_matches_model() {
+    let forecast = canonical("open_meteo_forecast.json");
+    let parsed: Result<OpenMeteoResponse, _> = serde_json::from_value(forecast.clone());
+    assert!(parsed.is_ok(), "Forecast no longer parses: {parsed:?}");
+
+    // The model must not rely on fields the canonical response lacks
+    let modelled = parsed
+        .ok()
+        .and_then(|p| serde_json::to_value(p).ok())
+        .unwrap_or(Value::Null);
+    let missing: Vec<String> = contract_diff(&forecast, &modelled)
+        .into_iter()
+        .filter(|line| line.starts_with('+'))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "Model has fields upstream lacks:\n{}",
+        missing.join("\n")
+    );
+}
+
+#[test]
+fn test_canonical_geocoding_has_required_fields() {
+    let geocoding = shape(&canonical("open_meteo_geocoding.json"));
+    for path in GEOCODE_REQUIRED {
+        assert!(geocoding.contains_key(*path), "Missing {path}");
+    }
+}
+
+#[test]
+fn test_contract_diff_reports_renames() {
+    let expected = serde_json::json!({"daily": {"temperature_2m_max": [21.5]}});
+    let renamed = serde_json::json!({"daily": {"temperature_max": [21.5]}});
+
+    assert_eq!(
+        contract_diff(&expected, &renamed),
+        vec![
+            "- $.daily.temperature_2m_max (array) no longer present",
+            "- $.daily.temperature_2m_max[] (number) no longer present",
+            "+ $.daily.temperature_max (array) is new",
+            "+ $.daily.temperature_max[] (number) is new",
+        ]
+    );
+    assert!(contract_diff(&expected, &expected).is_empty());
+}
+
+#[tokio::test]
+async fn test_live_open_meteo_contracts() -> Result<(), reqwest::Error> {
+    if std::env::var("CONTRACTS_LIVE").is_err() {
+        return Ok(());
+    }
+
+    let client = reqwest::Client::new();
+    for (name, url, fixture) in [
+        ("Geocoding", GEOCODE_URL, "open_meteo_geocoding.json"),
+        ("Forecast", FORECAST_URL, "open_meteo_forecast.json"),
+    ] {
+        let live: Value = client.get(url).send().await?.json().await?;
+        assert_compatible(name, &canonical(fixture), &live);
+    }
+    Ok(())
+}
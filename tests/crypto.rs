@@ -0,0 +1,39 @@
+// Argument envelope encryption tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::crypto::{decrypt_args, decrypt_field, encrypt_field, is_encrypted};
+use aws_lambda_mcp::models::error::AppError;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_plain_arguments_pass_through() {
+    let mut args = json!({"auth_token": "header.payload.signature", "location": "Paris"});
+    decrypt_args(&mut args, &["auth_token", "missing"])
+        .await
+        .unwrap();
+    assert_eq!(args["auth_token"], "header.payload.signature");
+    assert!(!is_encrypted("header.payload.signature"));
+    assert!(is_encrypted("enc:v1:a.b.c"));
+}
+
+#[tokio::test]
+async fn test_malformed_envelope_rejected() {
+    for value in [
+        "enc:v1:",
+        "enc:v1:a.b",
+        "enc:v1:a.b.c.d",
+        "enc:v1:key.!!.data",
+    ] {
+        let result = decrypt_field("auth_token", value).await;
+        assert!(
+            matches!(result, Err(AppError::EncryptionError(_))),
+            "{value} should be rejected"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_encrypt_requires_kms_key() {
+    let result = encrypt_field("auth_token", "secret").await;
+    assert!(matches!(result, Err(AppError::EncryptionError(_))));
+}
@@ -0,0 +1,126 @@
+// Relative date tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::dates::{DateRange, RelativeDate};
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::open_meteo::Daily;
+use chrono::{NaiveDate, Weekday};
+use serde_json::json;
+
+const fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+const fn range(first: u32, last: u32) -> DateRange {
+    DateRange {
+        first: date(first),
+        last: date(last),
+    }
+}
+
+fn resolve(phrase: &str, locale: Option<&str>, today: NaiveDate) -> DateRange {
+    RelativeDate::parse(phrase, locale).unwrap().resolve(today)
+}
+
+#[test]
+fn test_english_phrases() {
+    // 2026-10-15 is a Thursday
+    let today = date(15);
+    assert_eq!(resolve("Today", None, today), range(15, 15));
+    assert_eq!(resolve("tomorrow.", None, today), range(16, 16));
+    assert_eq!(
+        resolve("the day after  tomorrow", None, today),
+        range(17, 17)
+    );
+    assert_eq!(resolve("in 3 days", None, today), range(18, 18));
+    assert_eq!(resolve("this weekend", None, today), range(17, 18));
+    assert_eq!(resolve("next weekend", None, today), range(24, 25));
+    assert_eq!(resolve("this week", None, today), range(15, 18));
+    assert_eq!(resolve("next week", None, today), range(19, 25));
+    assert_eq!(resolve("2026-10-20", None, today), range(20, 20));
+}
+
+#[test]
+fn test_weekdays_count_from_today() {
+    let today = date(15);
+    assert_eq!(resolve("Tuesday", None, today), range(20, 20));
+    assert_eq!(resolve("this thursday", None, today), range(15, 15));
+    assert_eq!(resolve("next Thursday", None, today), range(22, 22));
+    assert_eq!(resolve("next friday", None, today), range(16, 16));
+    assert_eq!(
+        RelativeDate::parse("sat", None).unwrap(),
+        RelativeDate::Weekday {
+            weekday: Weekday::Sat,
+            next: false
+        }
+    );
+}
+
+#[test]
+fn test_weekend_on_a_weekend() {
+    // Saturday, then Sunday
+    assert_eq!(resolve("this weekend", None, date(17)), range(17, 18));
+    assert_eq!(resolve("this weekend", None, date(18)), range(18, 18));
+    assert_eq!(resolve("next weekend", None, date(18)), range(24, 25));
+}
+
+#[test]
+fn test_phrases_in_the_callers_language() {
+    let today = date(15);
+    assert_eq!(resolve("Übermorgen", Some("de-AT"), today), range(17, 17));
+    assert_eq!(
+        resolve("nächsten Dienstag", Some("de"), today),
+        range(20, 20)
+    );
+    assert_eq!(resolve("ce week-end", Some("fr-FR"), today), range(17, 18));
+    assert_eq!(
+        resolve("mardi prochain", Some("fr_CA"), today),
+        range(20, 20)
+    );
+    assert_eq!(resolve("dans 2 jours", Some("fr"), today), range(17, 17));
+    assert_eq!(
+        resolve("pasado mañana", Some("es-ES"), today),
+        range(17, 17)
+    );
+    assert_eq!(
+        resolve("el martes que viene", Some("es"), today),
+        range(20, 20)
+    );
+    // English is always understood
+    assert_eq!(resolve("tomorrow", Some("de-DE"), today), range(16, 16));
+}
+
+#[test]
+fn test_unknown_phrases_are_refused() {
+    assert!(RelativeDate::parse("someday", None).is_err());
+    assert!(RelativeDate::parse("morgen", None).is_err());
+    assert!(RelativeDate::parse("in 1000 days", None).is_err());
+    let err = RelativeDate::parse("whenever", Some("en-GB")).unwrap_err();
+    assert!(err.to_string().contains("whenever"));
+}
+
+#[test]
+fn test_daily_retain_keeps_lists_aligned() {
+    let mut daily: Daily = serde_json::from_value(json!({
+        "time": ["2026-10-15", "2026-10-16", "2026-10-17"],
+        "weather_code": [0, 1, 2],
+        "temperature_2m_max": [20.0, 21.0, 22.0],
+        "temperature_2m_min": [10.0, 11.0, 12.0]
+    }))
+    .unwrap();
+    daily.retain(|day| range(16, 17).contains(day));
+    assert_eq!(daily.time, vec![date(16), date(17)]);
+    assert_eq!(daily.temperature_2m_max.len(), 2);
+    assert!(daily.snowfall_sum.is_empty());
+}
+
+#[tokio::test]
+async fn test_weather_refuses_unknown_when_before_fetching() {
+    let err = route_tool(
+        "get_weather",
+        json!({"location": "Sydney", "when": "someday"}),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.error_message.contains("someday"));
+}
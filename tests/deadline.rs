@@ -0,0 +1,53 @@
+// Invocation deadline tests
+
+use aws_lambda_mcp::deadline::{from_epoch_millis, remaining, scope, upstream_timeout};
+use aws_lambda_mcp::models::error::AppError;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[tokio::test]
+async fn test_upstream_timeout_capped_by_deadline() {
+    // Outside a deadline scope the configured timeout applies
+    assert!(remaining().is_none());
+    assert_eq!(upstream_timeout().ok(), Some(Duration::from_secs(30)));
+
+    let timeout = scope(Some(Instant::now() + Duration::from_secs(5)), async {
+        upstream_timeout()
+    })
+    .await;
+    let timeout = timeout.ok().unwrap_or_default();
+    // Capped below the five seconds left, minus the safety margin
+    assert!(timeout <= Duration::from_millis(4_500), "got {timeout:?}");
+    assert!(timeout > Duration::from_secs(4), "got {timeout:?}");
+}
+
+#[tokio::test]
+async fn test_upstream_timeout_fails_past_deadline() {
+    let result = scope(Some(Instant::now() + Duration::from_millis(100)), async {
+        upstream_timeout()
+    })
+    .await;
+    assert!(matches!(result, Err(AppError::DeadlineExceeded(_))));
+}
+
+#[test]
+fn test_from_epoch_millis() {
+    let now_ms = u64::try_from(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis(),
+    )
+    .unwrap_or_default();
+
+    let left = from_epoch_millis(now_ms + 10_000)
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+        .unwrap_or_default();
+    assert!(left > Duration::from_secs(9) && left <= Duration::from_secs(10));
+
+    // A deadline in the past resolves to now
+    let past = from_epoch_millis(now_ms.saturating_sub(10_000));
+    assert!(past.is_some_and(|deadline| deadline <= Instant::now()));
+
+    // An unset deadline means no deadline at all
+    assert!(from_epoch_millis(0).is_none());
+}
@@ -0,0 +1,153 @@
+// Declarative tool tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::config::DeclarativeToolDefinition;
+use aws_lambda_mcp::declarative::{DeclarativeTool, Template};
+use aws_lambda_mcp::handler::route_tool;
+use serde_json::{Value, json};
+
+fn tool(definition: Value) -> DeclarativeTool {
+    DeclarativeTool::new("test", &serde_json::from_value(definition).unwrap()).unwrap()
+}
+
+fn invalid(definition: Value) -> String {
+    let definition: DeclarativeToolDefinition = serde_json::from_value(definition).unwrap();
+    DeclarativeTool::new("test", &definition)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_template_parses_placeholders() {
+    let template = Template::parse("/users/{{ id }}/posts?page={{page}}").unwrap();
+    assert_eq!(template.arguments().collect::<Vec<_>>(), ["id", "page"]);
+    assert_eq!(template.literal_prefix(), "/users/");
+    assert_eq!(
+        Template::parse("{{id}}").unwrap().whole_argument(),
+        Some("id")
+    );
+
+    assert!(Template::parse("/users/{{id").is_err());
+    assert!(Template::parse("/users/{{id.name}}").is_err());
+}
+
+#[test]
+fn test_url_arguments_are_encoded() {
+    let tool = tool(json!({
+        "request": {"url": "https://api.example.com/users/{{id}}?q={{query}}"}
+    }));
+    let request = tool
+        .render(&json!({"id": "a/../b", "query": "x&admin=1#top"}))
+        .unwrap();
+    assert_eq!(
+        request.url.as_str(),
+        "https://api.example.com/users/a%2F..%2Fb?q=x%26admin%3D1%23top"
+    );
+    assert_eq!(request.method, "GET");
+    assert!(request.body.is_none());
+}
+
+#[test]
+fn test_url_arguments_cannot_be_dot_segments_or_structured() {
+    let tool = tool(json!({"request": {"url": "https://api.example.com/items/{{id}}"}}));
+    assert!(tool.render(&json!({"id": ".."})).is_err());
+    assert!(tool.render(&json!({"id": {"nested": true}})).is_err());
+    assert!(tool.render(&json!({"id": 42})).is_ok());
+}
+
+#[test]
+fn test_placeholders_outside_path_and_query_are_refused() {
+    for url in [
+        "https://{{tenant}}.example.com/items",
+        "https://api.example.com{{path}}",
+        "{{scheme}}://api.example.com/items",
+    ] {
+        let err = invalid(json!({"request": {"url": url}}));
+        assert!(err.contains("path or query"), "{url}: {err}");
+    }
+    let err = invalid(json!({"request": {"url": "file:///etc/{{name}}"}}));
+    assert!(err.contains("scheme") || err.contains("host"), "{err}");
+}
+
+#[test]
+fn test_header_values_cannot_inject_headers() {
+    let tool = tool(json!({
+        "request": {
+            "url": "https://api.example.com/items",
+            "headers": {"X-Customer": "{{customer}}"}
+        }
+    }));
+    let request = tool.render(&json!({"customer": "acme"})).unwrap();
+    assert_eq!(request.headers[0].0, "x-customer");
+    assert_eq!(request.headers[0].1, "acme");
+
+    let err = tool
+        .render(&json!({"customer": "acme\r\nX-Admin: true"}))
+        .unwrap_err();
+    assert!(err.to_string().contains("control characters"));
+
+    let err = invalid(json!({
+        "request": {"url": "https://api.example.com/items", "headers": {"Host": "internal"}}
+    }));
+    assert!(err.contains("cannot be set"), "{err}");
+}
+
+#[test]
+fn test_body_substitution_is_structural() {
+    let tool = tool(json!({
+        "request": {
+            "method": "post",
+            "url": "https://api.example.com/items",
+            "body": {"name": "{{name}}", "note": "Created by {{name}}", "tags": "{{tags}}"}
+        }
+    }));
+    let name = r#"x", "admin": true, "y": ""#;
+    let request = tool
+        .render(&json!({"name": name, "tags": ["a", "b"]}))
+        .unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(
+        request.body.unwrap(),
+        json!({"name": name, "note": format!("Created by {name}"), "tags": ["a", "b"]})
+    );
+}
+
+#[test]
+fn test_templates_may_only_use_declared_arguments() {
+    let err = invalid(json!({
+        "input_schema": {"type": "object", "properties": {"city": {"type": "string"}}},
+        "request": {"url": "https://api.example.com/weather?city={{city}}&key={{api_key}}"}
+    }));
+    assert!(err.contains("api_key"), "{err}");
+}
+
+#[test]
+fn test_response_is_mapped_with_jmespath() {
+    let tool = tool(json!({
+        "request": {"url": "https://api.example.com/weather"},
+        "response": "{city: name, temperature: main.temp}"
+    }));
+    let mapped = tool
+        .map_response(json!({"name": "Sydney", "main": {"temp": 21.5, "humidity": 60}}))
+        .unwrap();
+    assert_eq!(mapped, json!({"city": "Sydney", "temperature": 21.5}));
+
+    assert!(
+        invalid(json!({
+            "request": {"url": "https://api.example.com/weather"},
+            "response": "main.["
+        }))
+        .contains("response expression")
+    );
+}
+
+#[tokio::test]
+async fn test_unconfigured_tool_is_refused() {
+    let err = route_tool(
+        "run_declarative_tool",
+        json!({"tool": "not_configured", "arguments": {}}),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.error_message.contains("No declarative tool"));
+}
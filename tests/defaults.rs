@@ -0,0 +1,55 @@
+// Default argument tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::defaults::merge_defaults;
+use aws_lambda_mcp::preferences::UserPreferences;
+use serde_json::{Map, Value, json};
+
+fn object(value: Value) -> Map<String, Value> {
+    serde_json::from_value(value).unwrap()
+}
+
+fn declared(names: &[&str]) -> Vec<String> {
+    names.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_explicit_arguments_take_precedence() {
+    let mut args = object(json!({"location": "Paris", "holiday_days": null}));
+    let preferences = object(json!({"location": "Sydney", "country_code": "AU"}));
+    let configured = object(json!({"country_code": "NZ", "holiday_days": 7}));
+
+    let filled = merge_defaults(
+        &mut args,
+        &declared(&["location", "country_code", "holiday_days"]),
+        &[&preferences, &configured],
+    );
+
+    assert_eq!(filled, ["country_code", "holiday_days"]);
+    assert_eq!(args["location"], "Paris");
+    assert_eq!(args["country_code"], "AU");
+    assert_eq!(args["holiday_days"], 7);
+}
+
+#[test]
+fn test_undeclared_arguments_are_not_filled() {
+    let mut args = Map::new();
+    let configured = object(json!({"forecast_days": 3, "location": "Oslo"}));
+
+    let filled = merge_defaults(&mut args, &declared(&["location"]), &[&configured]);
+
+    assert_eq!(filled, ["location"]);
+    assert!(!args.contains_key("forecast_days"));
+}
+
+#[test]
+fn test_preference_arguments() {
+    let preferences = UserPreferences {
+        home_city: Some("Sydney".to_string()),
+        ..UserPreferences::default()
+    };
+    assert_eq!(
+        Value::Object(preferences.arguments()),
+        json!({"location": "Sydney"})
+    );
+}
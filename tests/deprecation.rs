@@ -0,0 +1,84 @@
+// Tool deprecation lifecycle tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::registry::{
+    self, Deprecation, Effects, ToolDescriptor, attach_notice, bedrock_tool_schema,
+};
+use aws_lambda_mcp::slo::Slo;
+use schemars::schema_for;
+use serde_json::{Value, json};
+
+const DEPRECATION: Deprecation = Deprecation {
+    sunset: "2027-01-31",
+    replacement: Some("get_weather"),
+};
+
+fn deprecated_tool() -> ToolDescriptor {
+    ToolDescriptor {
+        name: "get_forecast",
+        description: "Fetches a forecast.",
+        paginated: false,
+        rows: &[],
+        session_cached: false,
+        effects: Effects::None,
+        deprecation: Some(DEPRECATION),
+        slo: Slo::IN_PROCESS,
+        examples: &[],
+        providers: &[],
+        input_schema: || schema_for!(Value),
+        output_schema: || schema_for!(Value),
+        handler: |args| Box::pin(async move { Ok(args) }),
+    }
+}
+
+#[test]
+fn test_deprecated_tool_schema_is_annotated() {
+    let schema = bedrock_tool_schema(&deprecated_tool()).unwrap();
+    assert_eq!(
+        schema["description"],
+        "DEPRECATED: get_forecast is deprecated and will be removed after 2027-01-31. \
+         Use get_weather instead. Fetches a forecast."
+    );
+    assert_eq!(
+        schema["annotations"],
+        json!({"deprecated": true, "sunsetDate": "2027-01-31", "replacement": "get_weather"})
+    );
+}
+
+#[test]
+fn test_active_tools_have_no_annotations() {
+    for tool in registry::tools().iter().filter(|t| t.deprecation.is_none()) {
+        let schema = bedrock_tool_schema(tool).unwrap();
+        assert!(schema.get("annotations").is_none(), "{}", tool.name);
+    }
+}
+
+#[test]
+fn test_notice_merges_into_meta() {
+    let mut response = json!({"ok": true, "_meta": {"content": {"output_bytes": 11}}});
+    attach_notice(&mut response, "get_forecast", &DEPRECATION);
+
+    assert_eq!(response["_meta"]["content"]["output_bytes"], 11);
+    assert_eq!(response["_meta"]["deprecation"]["sunsetDate"], "2027-01-31");
+    assert_eq!(
+        response["_meta"]["deprecation"]["replacement"],
+        "get_weather"
+    );
+
+    let mut array = json!([1, 2]);
+    attach_notice(&mut array, "get_forecast", &DEPRECATION);
+    assert_eq!(array, json!([1, 2]));
+}
+
+#[test]
+fn test_notice_without_replacement() {
+    let deprecation = Deprecation {
+        sunset: "2027-01-31",
+        replacement: None,
+    };
+    assert_eq!(
+        deprecation.notice("get_forecast"),
+        "get_forecast is deprecated and will be removed after 2027-01-31."
+    );
+    assert!(deprecation.annotations().get("replacement").is_none());
+}
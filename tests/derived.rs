@@ -0,0 +1,168 @@
+// Derived road condition and convection tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::derived::{convection_risk, road_conditions};
+use aws_lambda_mcp::models::open_meteo::{Daily, Hourly};
+use aws_lambda_mcp::models::{ConvectionRisk, RiskLevel, RoadConditions, UnitSystem, WmoCode};
+
+/// One day per `(max, min, snowfall, precipitation, humidity)`.
+fn daily(days: &[(f64, f64, f64, f64, f64)]) -> Daily {
+    Daily {
+        time: (1..=days.len())
+            .map(|day| format!("2026-01-{day:02}").parse().unwrap())
+            .collect(),
+        weather_code: vec![WmoCode::try_from(3).unwrap(); days.len()],
+        temperature_2m_max: days.iter().map(|day| day.0.try_into().unwrap()).collect(),
+        temperature_2m_min: days.iter().map(|day| day.1.try_into().unwrap()).collect(),
+        snowfall_sum: days.iter().map(|day| day.2.try_into().unwrap()).collect(),
+        precipitation_sum: days.iter().map(|day| day.3.try_into().unwrap()).collect(),
+        relative_humidity_2m_max: days.iter().map(|day| day.4).collect(),
+    }
+}
+
+#[test]
+fn test_mild_day_has_no_road_hazards() {
+    let conditions = road_conditions(&daily(&[(18.0, 9.0, 0.0, 5.0, 95.0)]), UnitSystem::Metric);
+    assert_eq!(
+        conditions,
+        vec![RoadConditions {
+            snow_accumulation: 0.0,
+            freezing_risk: RiskLevel::None,
+            black_ice_risk: RiskLevel::None,
+        }]
+    );
+}
+
+#[test]
+fn test_thaw_and_refreeze_with_moisture_is_high_black_ice_risk() {
+    let conditions = road_conditions(
+        &daily(&[
+            (4.0, -1.0, 0.0, 2.5, 70.0),
+            (-1.0, -6.0, 3.2, 3.2, 92.0),
+            (3.0, 0.5, 0.0, 0.0, 60.0),
+        ]),
+        UnitSystem::Metric,
+    );
+
+    assert_eq!(conditions[0].freezing_risk, RiskLevel::Moderate);
+    assert_eq!(conditions[0].black_ice_risk, RiskLevel::High);
+    assert_eq!(conditions[1].freezing_risk, RiskLevel::High);
+    assert_eq!(conditions[1].black_ice_risk, RiskLevel::Moderate);
+    assert!((conditions[1].snow_accumulation - 3.2).abs() < f64::EPSILON);
+    assert_eq!(conditions[2].freezing_risk, RiskLevel::Low);
+    assert_eq!(conditions[2].black_ice_risk, RiskLevel::Low);
+}
+
+#[test]
+fn test_snow_does_not_settle_on_warm_days() {
+    let conditions = road_conditions(&daily(&[(8.0, 4.0, 1.5, 1.5, 99.0)]), UnitSystem::Metric);
+    assert!(conditions[0].snow_accumulation.abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_imperial_snow_is_in_inches() {
+    let conditions = road_conditions(
+        &daily(&[(1.0, -1.0, 5.08, 5.0, 80.0)]),
+        UnitSystem::Imperial,
+    );
+    assert!((conditions[0].snow_accumulation - 2.0).abs() < 1e-9);
+    assert_eq!(conditions[0].freezing_risk, RiskLevel::Moderate);
+}
+
+#[test]
+fn test_forecast_without_snow_or_humidity_has_no_conditions() {
+    let mut forecast = daily(&[(1.0, -3.0, 0.0, 0.0, 90.0)]);
+    forecast.relative_humidity_2m_max.clear();
+    assert!(road_conditions(&forecast, UnitSystem::Metric).is_empty());
+}
+
+/// Two hours per day of `daily`, one per `(cape, lifted_index)`.
+fn hourly(hours: &[(Option<f64>, Option<f64>)]) -> Hourly {
+    Hourly {
+        time: (0..hours.len())
+            .map(|hour| {
+                format!("2026-01-{:02}T{:02}:00", hour / 2 + 1, hour % 2 * 12)
+                    .parse()
+                    .unwrap()
+            })
+            .collect(),
+        cape: hours.iter().map(|hour| hour.0).collect(),
+        lifted_index: hours.iter().map(|hour| hour.1).collect(),
+    }
+}
+
+#[test]
+fn test_convection_risk_reports_daily_extremes() {
+    let days = daily(&[(25.0, 15.0, 0.0, 0.0, 60.0); 2]);
+    let risks = convection_risk(
+        &days,
+        Some(&hourly(&[
+            (Some(120.0), Some(2.5)),
+            (Some(80.0), Some(1.0)),
+            (Some(400.0), Some(-1.5)),
+            (Some(2800.0), Some(-4.0)),
+        ])),
+    );
+
+    assert_eq!(
+        risks,
+        vec![
+            ConvectionRisk {
+                risk: RiskLevel::None,
+                max_cape: 120.0,
+                min_lifted_index: Some(1.0),
+            },
+            ConvectionRisk {
+                risk: RiskLevel::High,
+                max_cape: 2800.0,
+                min_lifted_index: Some(-4.0),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_convection_risk_is_the_higher_of_cape_and_lifted_index() {
+    let days = daily(&[(25.0, 15.0, 0.0, 0.0, 60.0); 2]);
+    let risks = convection_risk(
+        &days,
+        Some(&hourly(&[
+            (Some(350.0), Some(-6.5)),
+            (None, None),
+            (Some(1200.0), None),
+            (Some(900.0), None),
+        ])),
+    );
+
+    assert_eq!(risks[0].risk, RiskLevel::High);
+    assert_eq!(risks[1].risk, RiskLevel::Moderate);
+    assert_eq!(risks[1].min_lifted_index, None);
+}
+
+#[test]
+fn test_lifted_index_levels_include_their_boundary() {
+    let days = daily(&[(25.0, 15.0, 0.0, 0.0, 60.0); 3]);
+    let risks = convection_risk(
+        &days,
+        Some(&hourly(&[
+            (None, Some(0.0)),
+            (None, Some(2.0)),
+            (None, Some(-3.0)),
+            (None, Some(1.0)),
+            (None, Some(-6.0)),
+            (None, Some(1.0)),
+        ])),
+    );
+
+    let levels: Vec<_> = risks.iter().map(|risk| risk.risk).collect();
+    assert_eq!(
+        levels,
+        [RiskLevel::None, RiskLevel::Moderate, RiskLevel::High]
+    );
+}
+
+#[test]
+fn test_forecast_without_hourly_data_has_no_convection_risk() {
+    let days = daily(&[(25.0, 15.0, 0.0, 0.0, 60.0)]);
+    assert!(convection_risk(&days, None).is_empty());
+}
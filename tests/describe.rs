@@ -0,0 +1,74 @@
+// Capability document tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::admin;
+use aws_lambda_mcp::handler::route_tool;
+use serde_json::{Value, json};
+
+fn tool<'a>(document: &'a Value, name: &str) -> Option<&'a Value> {
+    document["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|tool| tool["name"] == name)
+}
+
+#[tokio::test]
+async fn test_describes_tools_auth_and_build() {
+    let document = route_tool("describe_server", json!({})).await.unwrap();
+
+    assert_eq!(document["server"]["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        tool(&document, "get_weather").unwrap()["response_versions"],
+        json!(["1", "2"])
+    );
+    assert_eq!(
+        tool(&document, "read_feed").unwrap()["response_versions"],
+        json!(["1"])
+    );
+    assert_eq!(
+        tool(&document, "send_notification").unwrap()["required_scopes"],
+        json!(["Notifications.Send"])
+    );
+    assert_eq!(
+        tool(&document, "admin_cache_stats").unwrap()["required_scopes"],
+        json!(["Admin"])
+    );
+    assert!(
+        document["auth"]["modes"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("bearer_token"))
+    );
+    assert!(
+        document["caches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|cache| cache["name"] == "geocode" && cache["ttl_seconds"].as_u64() > Some(0))
+    );
+}
+
+#[tokio::test]
+async fn test_disabled_tools_are_left_out() {
+    admin::set_enabled("get_public_holidays", false);
+    let document = route_tool("describe_server", json!({})).await.unwrap();
+    admin::set_enabled("get_public_holidays", true);
+
+    assert!(tool(&document, "get_public_holidays").is_none());
+    assert!(tool(&document, "describe_server").is_some());
+}
+
+#[tokio::test]
+async fn test_describes_a_single_tool() {
+    let document = route_tool("describe_server", json!({"tool": "get_weather"}))
+        .await
+        .unwrap();
+    assert_eq!(document["tools"].as_array().unwrap().len(), 1);
+
+    let err = route_tool("describe_server", json!({"tool": "no_such_tool"}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("Unknown tool"));
+}
@@ -0,0 +1,46 @@
+// Description override tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::registry::{
+    self, DescriptionOverride, bedrock_tool_schema, description_overrides,
+};
+
+#[test]
+fn test_overrides_name_registered_tools_and_properties() {
+    for (tool, overrides) in description_overrides().unwrap() {
+        assert!(registry::find(tool).is_some(), "unknown tool {tool}");
+        let descriptor = registry::find(tool).unwrap();
+        assert!(!overrides.is_empty(), "{tool}");
+        // Publishing fails if an override names a missing property
+        bedrock_tool_schema(descriptor).unwrap();
+    }
+}
+
+#[test]
+fn test_override_replaces_doc_comment() {
+    let tool = registry::find("get_weather").unwrap();
+    let text = match &description_overrides().unwrap()["get_weather"]["location"] {
+        DescriptionOverride::Replace(text) => text.as_str(),
+        DescriptionOverride::Append { .. } => "",
+    };
+    assert!(text.starts_with("City or place name"));
+    let schema = bedrock_tool_schema(tool).unwrap();
+    assert_eq!(
+        schema["inputSchema"]["properties"]["location"]["description"],
+        text
+    );
+}
+
+#[test]
+fn test_append_follows_doc_comment() {
+    let tool = registry::find("run_workflow").unwrap();
+    let schema = bedrock_tool_schema(tool).unwrap();
+    let description =
+        schema["inputSchema"]["properties"]["steps"]["items"]["properties"]["tool"]["description"]
+            .as_str()
+            .unwrap();
+    assert_eq!(
+        description,
+        "Name of the tool to call. Any of this server's tools except run_workflow itself."
+    );
+}
@@ -0,0 +1,91 @@
+// Dry run tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::dry_run;
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::{NotificationRequest, RecordConsentRequest};
+use aws_lambda_mcp::registry::{self, bedrock_tool_schema};
+use aws_lambda_mcp::tools::consent::record_consent;
+use aws_lambda_mcp::tools::send_notification;
+use serde_json::json;
+
+#[test]
+fn test_takes_dry_run_from_tools_with_side_effects() {
+    let tool = registry::find("send_notification").unwrap();
+    let mut args = json!({"channel": "sns", "dry_run": true});
+    assert!(dry_run::take(tool, &mut args).unwrap());
+    assert_eq!(args, json!({"channel": "sns"}));
+
+    let mut args = json!({"dry_run": "false"});
+    assert!(!dry_run::take(tool, &mut args).unwrap());
+    assert!(!dry_run::take(tool, &mut json!({})).unwrap());
+    assert!(dry_run::take(tool, &mut json!({"dry_run": "maybe"})).is_err());
+    assert!(dry_run::take(tool, &mut json!({"dry_run": 1})).is_err());
+}
+
+#[test]
+fn test_tools_without_side_effects_leave_the_argument() {
+    let tool = registry::find("get_weather").unwrap();
+    let mut args = json!({"location": "Sydney", "dry_run": true});
+    assert!(!dry_run::take(tool, &mut args).unwrap());
+    assert_eq!(args["dry_run"], true);
+}
+
+#[test]
+fn test_only_tools_with_side_effects_advertise_dry_run() {
+    for tool in registry::tools() {
+        let schema = bedrock_tool_schema(tool).unwrap();
+        let advertised = schema["inputSchema"]["properties"].get("dry_run").is_some();
+        assert_eq!(advertised, tool.side_effects(), "{}", tool.name);
+    }
+    for name in ["send_notification", "start_workflow", "record_consent"] {
+        assert!(registry::find(name).unwrap().side_effects(), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_still_validates_arguments() {
+    let request: NotificationRequest = serde_json::from_value(json!({
+        "channel": "email",
+        "destination": "someone@example.com",
+        "message": "Hello"
+    }))
+    .unwrap();
+    let mut context = ToolContext::default();
+    context.dry_run = true;
+    // No destination is allowlisted by default, so the plan is refused too
+    assert!(
+        Box::pin(context::scope(context, send_notification(request)))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_plans_consent_without_recording_it() {
+    let mut caller = ToolContext::from_args("record_consent", &json!({}));
+    caller.verified_user_id = Some("ada@example.com".to_string());
+    caller.dry_run = true;
+    let response = Box::pin(context::scope(
+        caller,
+        record_consent(RecordConsentRequest { granted: true }),
+    ))
+    .await
+    .unwrap();
+
+    assert!(response.granted);
+    assert!(response.granted_at.is_none());
+    let plan = response.plan.unwrap();
+    assert_eq!(plan.action, "Record consent");
+    assert_eq!(plan.target, "ada@example.com");
+    assert_eq!(plan.details["granted"], true);
+
+    let error = route_tool(
+        "record_consent",
+        json!({"user_id": "ada@example.com", "granted": true, "dry_run": "yes"}),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error.error_type, "InvalidInput");
+}
@@ -0,0 +1,130 @@
+// Side effect journal tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::effects::run_once;
+use aws_lambda_mcp::models::SideEffectStatus;
+use aws_lambda_mcp::models::error::AppError;
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Runs a counted effect for `arguments` in the context built from `args`.
+async fn send(
+    runs: &AtomicUsize,
+    args: &Value,
+    key: Option<&str>,
+    arguments: &Value,
+) -> Result<(String, SideEffectStatus), AppError> {
+    let call = run_once("send_notification", key, arguments, || async move {
+        let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok::<_, AppError>(format!("message-{run}"))
+    });
+    Box::pin(context::scope(
+        ToolContext::from_args("send_notification", args),
+        call,
+    ))
+    .await
+}
+
+#[tokio::test]
+async fn test_repeated_key_returns_first_response() {
+    let runs = AtomicUsize::new(0);
+    let args = json!({"user_id": "ada"});
+    let arguments = json!({"message": "hello"});
+
+    let first = send(&runs, &args, Some("key-repeat"), &arguments).await;
+    let second = send(&runs, &args, Some("key-repeat"), &arguments).await;
+
+    assert_eq!(
+        first.unwrap(),
+        ("message-1".to_string(), SideEffectStatus::Executed)
+    );
+    assert_eq!(
+        second.unwrap(),
+        ("message-1".to_string(), SideEffectStatus::Deduplicated)
+    );
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_keys_are_scoped_to_the_caller() {
+    let runs = AtomicUsize::new(0);
+    let arguments = json!({"message": "hello"});
+
+    send(
+        &runs,
+        &json!({"user_id": "ada"}),
+        Some("key-shared"),
+        &arguments,
+    )
+    .await
+    .unwrap();
+    let (_, status) = send(
+        &runs,
+        &json!({"user_id": "grace"}),
+        Some("key-shared"),
+        &arguments,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(status, SideEffectStatus::Executed);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_session_retry_is_recognised_without_a_key() {
+    let runs = AtomicUsize::new(0);
+    let args = json!({"user_id": "ada", "session_id": "session-effects"});
+
+    send(&runs, &args, None, &json!({"message": "hello"}))
+        .await
+        .unwrap();
+    let (_, retried) = send(&runs, &args, None, &json!({"message": "hello"}))
+        .await
+        .unwrap();
+    let (_, changed) = send(&runs, &args, None, &json!({"message": "goodbye"}))
+        .await
+        .unwrap();
+
+    assert_eq!(retried, SideEffectStatus::Deduplicated);
+    assert_eq!(changed, SideEffectStatus::Executed);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_calls_without_key_or_session_are_not_journaled() {
+    let runs = AtomicUsize::new(0);
+    let args = json!({"user_id": "ada"});
+    let arguments = json!({"message": "hello"});
+
+    for _ in 0..2 {
+        let (_, status) = send(&runs, &args, None, &arguments).await.unwrap();
+        assert_eq!(status, SideEffectStatus::Executed);
+    }
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_failed_effect_can_be_retried() {
+    let args = json!({"user_id": "ada"});
+    let failed = Box::pin(context::scope(
+        ToolContext::from_args("send_notification", &args),
+        run_once(
+            "send_notification",
+            Some("key-failed"),
+            &json!({}),
+            || async {
+                Err::<String, _>(AppError::NotificationError("SNS unavailable".to_string()))
+            },
+        ),
+    ))
+    .await;
+    assert!(failed.is_err());
+
+    let runs = AtomicUsize::new(0);
+    let (_, status) = send(&runs, &args, Some("key-failed"), &json!({}))
+        .await
+        .unwrap();
+    assert_eq!(status, SideEffectStatus::Executed);
+}
@@ -0,0 +1,109 @@
+// Tool example tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::config::config;
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::http::VcrMode;
+use aws_lambda_mcp::registry::{self, ToolExample, bedrock_tool_schema};
+use serde_json::{Value, json};
+
+const EXAMPLE: ToolExample = ToolExample {
+    description: "New Year's Day in Australia",
+    input: r#"{"country_code": "AU"}"#,
+    output: r#"{"holidays": [{"date": "2026-01-01"}]}"#,
+};
+
+#[test]
+fn test_examples_are_published_in_schema_and_description() {
+    for tool in registry::tools() {
+        let schema = bedrock_tool_schema(tool).unwrap();
+        let description = schema["description"].as_str().unwrap();
+        if tool.examples.is_empty() {
+            assert!(schema.get("examples").is_none(), "{}", tool.name);
+        }
+        for (example, published) in tool
+            .examples
+            .iter()
+            .zip(schema["examples"].as_array().unwrap_or(&Vec::new()))
+        {
+            assert_eq!(published, &example.published().unwrap(), "{}", tool.name);
+            assert!(
+                description.contains(&example.sentence().unwrap()),
+                "{}",
+                tool.name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_example_inputs_follow_input_schema() {
+    for tool in registry::tools() {
+        let schema = bedrock_tool_schema(tool).unwrap();
+        let properties = schema["inputSchema"]["properties"].as_object().unwrap();
+        let required = schema["inputSchema"]["required"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for example in tool.examples {
+            let input = example.input().unwrap();
+            let input = input.as_object().unwrap();
+            for key in input.keys() {
+                assert!(
+                    properties.contains_key(key),
+                    "{}: unknown argument {key}",
+                    tool.name
+                );
+            }
+            for key in &required {
+                assert!(
+                    input.contains_key(key.as_str().unwrap()),
+                    "{}: missing {key}",
+                    tool.name
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_example_output_may_be_abridged() {
+    let actual = json!({
+        "country_code": "AU",
+        "holidays": [{"date": "2026-01-01", "name": "New Year's Day"}, {"date": "2026-01-26"}],
+        "next_cursor": "abc"
+    });
+    assert!(EXAMPLE.matches(&actual).unwrap());
+}
+
+#[test]
+fn test_example_output_must_agree_with_response() {
+    let wrong_date = json!({"holidays": [{"date": "2026-01-26"}]});
+    assert!(!EXAMPLE.matches(&wrong_date).unwrap());
+    let missing_field = json!({"country_code": "AU"});
+    assert!(!EXAMPLE.matches(&missing_field).unwrap());
+    let too_few_items = json!({"holidays": []});
+    assert!(!EXAMPLE.matches(&too_few_items).unwrap());
+}
+
+// Examples call upstream APIs, so they run against the recorded fixtures in
+// tests/fixtures/http under `make test-replay` and are skipped otherwise
+#[tokio::test]
+async fn test_examples_agree_with_tools() {
+    if config().http.vcr_mode != VcrMode::Replay {
+        return;
+    }
+    for tool in registry::tools() {
+        for example in tool.examples {
+            let response: Value = route_tool(tool.name, example.input().unwrap())
+                .await
+                .unwrap();
+            assert!(
+                example.matches(&response).unwrap(),
+                "{} example \"{}\" disagrees with {response}",
+                tool.name,
+                example.description
+            );
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Tabular export tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::export::{self, OutputFormat};
+use aws_lambda_mcp::registry;
+use serde_json::json;
+
+#[test]
+fn test_exports_row_lists_as_csv() {
+    let mut response = json!({
+        "status": "Complete",
+        "rows": [
+            {"@timestamp": "2026-10-15 09:00", "@message": "ERROR \"disk\" full, retrying"},
+            {"@timestamp": "2026-10-15 09:01", "level": "warn"}
+        ],
+        "next_cursor": "abc"
+    });
+    export::apply(OutputFormat::Csv, &["rows"], &mut response);
+
+    assert_eq!(
+        response["rows"],
+        "@message,@timestamp,level\n\"ERROR \"\"disk\"\" full, retrying\",2026-10-15 09:00,\n,2026-10-15 09:01,warn\n"
+    );
+    assert_eq!(response["next_cursor"], "abc");
+}
+
+#[test]
+fn test_exports_parallel_columns_as_ndjson() {
+    let mut response = json!({
+        "daily": {"time": ["2026-10-14", "2026-10-15"], "temperature2mMax": [21.5, 19.0]}
+    });
+    export::apply(OutputFormat::Ndjson, &["days", "daily"], &mut response);
+
+    assert_eq!(
+        response["daily"],
+        "{\"temperature2mMax\":21.5,\"time\":\"2026-10-14\"}\n{\"temperature2mMax\":19.0,\"time\":\"2026-10-15\"}\n"
+    );
+}
+
+#[test]
+fn test_takes_output_format_from_tabular_tools() {
+    let logs = registry::find("query_logs").unwrap();
+    let mut args = json!({"query": "fields @message", "output_format": "CSV"});
+    assert_eq!(
+        export::take(logs, &mut args).unwrap(),
+        Some(OutputFormat::Csv)
+    );
+    assert!(args.get("output_format").is_none());
+
+    let mut args = json!({"output_format": "json"});
+    assert_eq!(export::take(logs, &mut args).unwrap(), None);
+
+    let err = export::take(logs, &mut json!({"output_format": "xlsx"})).unwrap_err();
+    assert!(err.to_string().contains("json, csv, ndjson"));
+}
+
+#[test]
+fn test_tools_without_rows_leave_the_argument() {
+    let greeting = registry::find("get_personalized_greeting").unwrap();
+    let mut args = json!({"user_name": "Ada", "output_format": "csv"});
+    assert_eq!(export::take(greeting, &mut args).unwrap(), None);
+    assert_eq!(args["output_format"], "csv");
+}
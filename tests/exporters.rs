@@ -0,0 +1,110 @@
+// Schema exporter tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::registry::{
+    self, BedrockExporter, EXPORTERS, JsonSchemaExporter, McpExporter, OpenApiExporter,
+    SchemaExporter, bedrock_tool_schemas, exporter, passes,
+};
+use serde_json::json;
+
+#[test]
+fn test_every_exporter_publishes_every_tool() {
+    for exporter in EXPORTERS {
+        assert!(
+            exporter.document(registry::tools()).is_ok(),
+            "{}",
+            exporter.name()
+        );
+        assert_eq!(
+            registry::exporter(exporter.name()).unwrap().file_name(),
+            exporter.file_name()
+        );
+    }
+    assert!(exporter("swagger").is_none());
+}
+
+#[test]
+fn test_bedrock_document_is_tool_schema_json() {
+    let document = BedrockExporter.document(registry::tools()).unwrap();
+    assert_eq!(document, json!(bedrock_tool_schemas().unwrap()));
+}
+
+#[test]
+fn test_passes_compose() {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Request",
+        "properties": {
+            "user_id": {"type": "string"},
+            "when": {"type": ["string", "null"], "format": "date"},
+            "units": {"$ref": "#/$defs/Units"}
+        },
+        "required": ["user_id", "when"],
+        "$defs": {"Units": {"enum": ["metric", "imperial"]}}
+    });
+    passes::apply(
+        &mut schema,
+        &[
+            passes::strip_meta_keywords,
+            passes::inline_definitions,
+            passes::hide_injected_arguments,
+            passes::drop_formats,
+            passes::collapse_nullable_types,
+        ],
+    );
+    assert_eq!(
+        schema,
+        json!({
+            "properties": {
+                "when": {"type": "string"},
+                "units": {"enum": ["metric", "imperial"], "type": "string"}
+            },
+            "required": ["when"]
+        })
+    );
+}
+
+#[test]
+fn test_mcp_hints_and_metadata() {
+    let start = McpExporter
+        .tool(registry::find("start_workflow").unwrap())
+        .unwrap();
+    assert_eq!(
+        start["annotations"],
+        json!({"readOnlyHint": false, "destructiveHint": true})
+    );
+    assert!(start["inputSchema"]["properties"]["confirmation_token"].is_object());
+
+    let weather = McpExporter
+        .tool(registry::find("get_weather").unwrap())
+        .unwrap();
+    assert_eq!(weather["annotations"]["readOnlyHint"], true);
+    assert_eq!(weather["_meta"]["attribution"][0]["provider"], "Open-Meteo");
+    assert!(weather["inputSchema"].get("$defs").is_none());
+}
+
+#[test]
+fn test_openapi_operation_per_tool() {
+    let document = OpenApiExporter.document(registry::tools()).unwrap();
+    assert_eq!(document["openapi"], "3.1.0");
+    let paths = document["paths"].as_object().unwrap();
+    assert_eq!(paths.len(), registry::tools().len());
+    let operation = &paths["/get_weather"]["post"];
+    assert_eq!(operation["operationId"], "get_weather");
+    let body = &operation["requestBody"]["content"]["application/json"]["schema"];
+    assert!(body["properties"]["location"].is_object());
+    assert!(body["properties"].get("user_id").is_none());
+}
+
+#[test]
+fn test_json_schema_keeps_draft_2020_12_keywords() {
+    let tool = JsonSchemaExporter
+        .tool(registry::find("get_weather").unwrap())
+        .unwrap();
+    assert_eq!(
+        tool["input"]["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert!(tool["input"].get("$defs").is_none());
+    assert!(tool["output"]["properties"]["latitude"].is_object());
+}
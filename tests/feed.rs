@@ -0,0 +1,108 @@
+// Feed reader tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::tools::feed::parse_feed;
+use serde_json::json;
+
+const RSS_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Example News</title>
+    <link>https://news.example.com/</link>
+    <description>Latest stories</description>
+    <item>
+      <title>Older story</title>
+      <link>https://news.example.com/older</link>
+      <description>An &lt;b&gt;older&lt;/b&gt; story.</description>
+      <pubDate>Mon, 01 Jan 2024 09:00:00 GMT</pubDate>
+    </item>
+    <item>
+      <title>Newest story</title>
+      <link>https://news.example.com/newest</link>
+      <description><![CDATA[<p>Breaking <a href="https://x.example">news</a> today.</p>]]></description>
+      <pubDate>Wed, 03 Jan 2024 09:00:00 GMT</pubDate>
+    </item>
+    <item>
+      <title>Middle story</title>
+      <link>https://news.example.com/middle</link>
+      <pubDate>Tue, 02 Jan 2024 09:00:00 GMT</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+
+const ATOM_FEED: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example Blog</title>
+  <link rel="alternate" href="https://blog.example.com/"/>
+  <link rel="self" href="https://blog.example.com/atom.xml"/>
+  <id>urn:uuid:60a76c80-d399-11d9-b93C-0003939e0af6</id>
+  <updated>2024-01-05T18:30:02Z</updated>
+  <entry>
+    <title>Release notes</title>
+    <link href="https://blog.example.com/release"/>
+    <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+    <updated>2024-01-05T18:30:02Z</updated>
+    <summary>New version is out.</summary>
+  </entry>
+</feed>"#;
+
+#[test]
+fn test_parse_rss_feed_newest_first() {
+    let feed = parse_feed(RSS_FEED.as_bytes(), 2).unwrap();
+
+    assert_eq!(feed.title.as_deref(), Some("Example News"));
+    assert_eq!(feed.items.len(), 2, "Limit should cap returned items");
+
+    let newest = &feed.items[0];
+    assert_eq!(newest.title.as_deref(), Some("Newest story"));
+    assert_eq!(
+        newest.link.as_deref(),
+        Some("https://news.example.com/newest")
+    );
+    assert_eq!(newest.summary.as_deref(), Some("Breaking news today."));
+    assert!(
+        newest
+            .published
+            .as_deref()
+            .unwrap()
+            .starts_with("2024-01-03")
+    );
+
+    assert_eq!(feed.items[1].title.as_deref(), Some("Middle story"));
+    assert_eq!(feed.items[1].summary, None);
+}
+
+#[test]
+fn test_parse_atom_feed_prefers_alternate_link() {
+    let feed = parse_feed(ATOM_FEED.as_bytes(), 10).unwrap();
+
+    assert_eq!(feed.title.as_deref(), Some("Example Blog"));
+    assert_eq!(feed.link.as_deref(), Some("https://blog.example.com/"));
+    assert_eq!(feed.items.len(), 1);
+    assert_eq!(
+        feed.items[0].summary.as_deref(),
+        Some("New version is out.")
+    );
+}
+
+#[test]
+fn test_parse_invalid_feed() {
+    assert!(parse_feed(b"not a feed", 10).is_err());
+}
+
+#[tokio::test]
+async fn test_read_feed_rejects_invalid_url() {
+    let mcp_payload = json!({
+        "method": "tools/call",
+        "params": {
+            "arguments": {
+                "url": "ftp://example.com/feed.xml"
+            }
+        }
+    });
+
+    let err = route_tool("read_feed", mcp_payload).await.unwrap_err();
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("Unsupported feed URL scheme"));
+}
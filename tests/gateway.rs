@@ -0,0 +1,128 @@
+// Gateway flow emulation tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::gateway::call;
+use aws_lambda_mcp::models::Headers;
+use common::token;
+use serde_json::{Value, json};
+
+fn tools_call(name: &str, arguments: Value) -> Value {
+    let mut body = json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "tools/call",
+        "params": {"name": name}
+    });
+    body["params"]["arguments"] = arguments;
+    body
+}
+
+#[tokio::test]
+async fn test_interceptor_injections_reach_the_tool() {
+    let token = token(&json!({"sub": "jane@example.com", "name": "Jane"}));
+    let headers = Headers::from([("Authorization", format!("Bearer {token}"))]);
+    let exchange = call(
+        headers,
+        tools_call("weather-target___get_personalized_greeting", json!({})),
+    )
+    .await
+    .unwrap();
+
+    let forwarded = exchange.forwarded.as_ref().unwrap().body.as_ref().unwrap();
+    assert_eq!(forwarded["params"]["arguments"]["user_name"], "Jane");
+    assert!(forwarded["params"]["arguments"]["auth_token"].is_string());
+
+    assert_eq!(exchange.response.status_code, 200);
+    assert_eq!(exchange.response.body.as_ref().unwrap()["id"], 7);
+    assert!(!exchange.is_error());
+    assert_eq!(exchange.output().unwrap()["greeting"], "Hello, Jane!");
+}
+
+#[tokio::test]
+async fn test_identity_is_injected_for_every_tool() {
+    let token = token(&json!({"sub": "jane@example.com", "name": "Jane"}));
+    let headers = Headers::from([("Authorization", format!("Bearer {token}"))]);
+    let exchange = call(headers, tools_call("describe_server", json!({})))
+        .await
+        .unwrap();
+
+    let forwarded = &exchange.forwarded.as_ref().unwrap().body.as_ref().unwrap()["params"];
+    assert_eq!(forwarded["arguments"]["user_id"], "jane@example.com");
+    assert_eq!(forwarded["arguments"]["user_name"], "Jane");
+}
+
+#[tokio::test]
+async fn test_daily_briefing_knows_the_caller() {
+    let token = token(&json!({"sub": "jane@example.com", "name": "Jane"}));
+    let headers = Headers::from([("Authorization", format!("Bearer {token}"))]);
+    let exchange = call(headers, tools_call("daily_briefing", json!({})))
+        .await
+        .unwrap();
+
+    let forwarded = &exchange.forwarded.as_ref().unwrap().body.as_ref().unwrap()["params"];
+    assert_eq!(forwarded["arguments"]["user_id"], "jane@example.com");
+    assert!(!exchange.is_error());
+    assert_eq!(exchange.output().unwrap()["greeting"], "Hello, Jane!");
+}
+
+#[tokio::test]
+async fn test_client_credentials_are_replaced() {
+    let forged = token(&json!({"sub": "admin-1", "roles": ["Admin"]}));
+    let arguments = json!({"auth_token": forged, "user_id": "admin-1", "user_name": "Admin"});
+    let exchange = call(
+        Headers::new(),
+        tools_call("admin_cache_stats", arguments.clone()),
+    )
+    .await
+    .unwrap();
+
+    let forwarded = &exchange.forwarded.as_ref().unwrap().body.as_ref().unwrap()["params"];
+    assert_eq!(forwarded["arguments"], json!({}));
+    assert!(exchange.is_error());
+
+    let caller = token(&json!({"sub": "jane@example.com", "name": "Jane"}));
+    let headers = Headers::from([("Authorization", format!("Bearer {caller}"))]);
+    let exchange = call(headers, tools_call("get_personalized_greeting", arguments))
+        .await
+        .unwrap();
+    let forwarded = &exchange.forwarded.as_ref().unwrap().body.as_ref().unwrap()["params"];
+    assert_eq!(forwarded["arguments"]["auth_token"], json!(caller));
+    assert_eq!(exchange.output().unwrap()["greeting"], "Hello, Jane!");
+}
+
+#[tokio::test]
+async fn test_tool_errors_are_wrapped_like_the_gateway() {
+    let exchange = call(Headers::new(), tools_call("get_wether", json!({})))
+        .await
+        .unwrap();
+
+    assert!(exchange.is_error());
+    let text = exchange.result().unwrap()["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(text.contains("Did you mean get_weather?"), "{text}");
+}
+
+#[tokio::test]
+async fn test_interceptor_can_answer_without_forwarding() {
+    let body = tools_call("read_feed", json!({"url": "x".repeat(2_000_000)}));
+    let exchange = call(Headers::new(), body).await.unwrap();
+
+    assert!(exchange.forwarded.is_none());
+    assert_eq!(exchange.response.status_code, 413);
+    assert!(exchange.result().is_none());
+}
+
+#[tokio::test]
+async fn test_other_methods_are_not_forwarded_to_the_tool() {
+    let body = json!({"jsonrpc": "2.0", "id": "list", "method": "tools/list"});
+    let exchange = call(Headers::new(), body).await.unwrap();
+
+    assert!(exchange.forwarded.is_some());
+    assert_eq!(
+        exchange.response.body.unwrap()["error"]["code"],
+        json!(-32601)
+    );
+}
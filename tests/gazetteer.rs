@@ -0,0 +1,88 @@
+// Offline gazetteer and location spelling fallback tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::gazetteer::{
+    cities, fold, levenshtein, lookup, spelling_variants, suggestions,
+};
+use aws_lambda_mcp::tools::weather::Geocoded;
+use aws_lambda_mcp::utils::is_timezone_name;
+
+#[test]
+fn test_fold_strips_case_accents_and_punctuation() {
+    assert_eq!(fold("Zürich"), "zurich");
+    assert_eq!(fold("  São   Paulo "), "sao paulo");
+    assert_eq!(fold("St. Louis"), "st louis");
+    assert_eq!(fold("Kraków"), fold("krakow"));
+}
+
+#[test]
+fn test_spelling_variants() {
+    assert_eq!(spelling_variants("NYC"), ["New York City"]);
+    assert_eq!(spelling_variants("sf, CA"), ["San Francisco", "sf"]);
+    assert_eq!(
+        spelling_variants("St. Louis, Missouri"),
+        ["St. Louis", "Saint Louis"]
+    );
+    assert_eq!(spelling_variants("Zùrich"), ["Zurich"]);
+    assert!(spelling_variants("Paris").is_empty());
+}
+
+#[test]
+fn test_suggestions_rank_close_city_names() {
+    assert_eq!(suggestions("Londn", 3), ["London"]);
+    assert_eq!(suggestions("Sydny, Australia", 3), ["Sydney"]);
+    assert_eq!(suggestions("sao paolo", 3), ["São Paulo"]);
+    assert_eq!(suggestions("Pari", 3), ["Paris"]);
+    assert!(suggestions("Xyzzyville", 3).is_empty());
+    assert!(suggestions("", 3).is_empty());
+}
+
+#[test]
+fn test_levenshtein() {
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("", "oslo"), 4);
+    assert_eq!(levenshtein("zürich", "zurich"), 1);
+    assert_eq!(levenshtein("rome", "rome"), 0);
+}
+
+#[test]
+fn test_embedded_cities_parse() {
+    let rows = include_str!("../src/gazetteer/cities.csv").lines().count() - 1;
+    assert_eq!(cities().len(), rows);
+    for city in cities() {
+        assert!(is_timezone_name(city.timezone), "{}", city.name);
+        assert_eq!(city.country_code.len(), 2, "{}", city.name);
+        assert!((-90.0..=90.0).contains(&city.latitude), "{}", city.name);
+        assert!((-180.0..=180.0).contains(&city.longitude), "{}", city.name);
+    }
+}
+
+#[test]
+fn test_lookup_resolves_major_cities() {
+    let sydney = lookup("sydney").unwrap();
+    assert_eq!(sydney.country_code, "AU");
+    assert_eq!(sydney.timezone, "Australia/Sydney");
+
+    assert_eq!(lookup("Zurich").unwrap().name, "Zürich");
+    assert_eq!(lookup("NYC").unwrap().name, "New York City");
+    assert_eq!(lookup("Bombay").unwrap().name, "Mumbai");
+    assert_eq!(lookup("St. Petersburg").unwrap().name, "Saint Petersburg");
+    assert!(lookup("Springfield").is_none());
+}
+
+#[test]
+fn test_lookup_checks_country_qualifier() {
+    assert_eq!(lookup("Paris, FR").unwrap().name, "Paris");
+    assert_eq!(lookup("Paris, France").unwrap().name, "Paris");
+    assert!(lookup("Paris, TX").is_none());
+    assert!(lookup("London, Ontario").is_none());
+}
+
+#[test]
+fn test_offline_match_geocodes_without_alternates() {
+    let geocoded = Geocoded::from(lookup("Tokyo").unwrap());
+    assert_eq!(geocoded.timezone, "Asia/Tokyo");
+    assert_eq!(geocoded.place.country_code.as_deref(), Some("JP"));
+    assert!((geocoded.place.confidence - 1.0).abs() < f64::EPSILON);
+    assert!(geocoded.alternates.is_empty());
+}
@@ -0,0 +1,76 @@
+// Geocoding result tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::{LocatedForecast, UnitSystem, WeatherResponseV2};
+use aws_lambda_mcp::tools::weather::parse_geocoding;
+use serde_json::json;
+use std::fs;
+
+#[test]
+fn test_parse_canonical_geocoding() {
+    let raw = fs::read_to_string("tests/fixtures/contracts/open_meteo_geocoding.json").unwrap();
+    let geocoded = parse_geocoding(&serde_json::from_str(&raw).unwrap()).unwrap();
+
+    assert_eq!(geocoded.timezone, "Australia/Sydney");
+    assert_eq!(geocoded.place.name, "Sydney");
+    assert_eq!(geocoded.place.country_code.as_deref(), Some("AU"));
+    assert_eq!(
+        geocoded.place.admin_region.as_deref(),
+        Some("New South Wales")
+    );
+    assert_eq!(geocoded.place.population, Some(4_627_345));
+    assert!((geocoded.place.confidence - 1.0).abs() < f64::EPSILON);
+    assert!(geocoded.alternates.is_empty());
+}
+
+#[test]
+fn test_parse_ambiguous_geocoding() {
+    let response = json!({"results": [
+        {"name": "Paris", "latitude": 48.85, "longitude": 2.35, "country": "France",
+         "country_code": "FR", "admin1": "Île-de-France", "timezone": "Europe/Paris",
+         "population": 2_138_551},
+        {"name": "Paris", "latitude": 33.66, "longitude": -95.56, "country": "United States",
+         "country_code": "US", "admin1": "Texas", "timezone": "America/Chicago",
+         "population": 24_171},
+        {"name": "Paris", "country": "Nowhere"}
+    ]});
+    let geocoded = parse_geocoding(&response).unwrap();
+
+    assert_eq!(geocoded.timezone, "Europe/Paris");
+    assert!((geocoded.place.confidence - 0.99).abs() < f64::EPSILON);
+    assert_eq!(
+        geocoded.alternates.len(),
+        1,
+        "Results without coordinates are skipped"
+    );
+    assert_eq!(
+        geocoded.alternates[0].admin_region.as_deref(),
+        Some("Texas")
+    );
+    assert!((geocoded.alternates[0].confidence - 0.01).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_parse_geocoding_without_results() {
+    assert!(parse_geocoding(&json!({"generationtime_ms": 0.5})).is_err());
+    assert!(parse_geocoding(&json!({"results": []})).is_err());
+    assert!(parse_geocoding(&json!({"results": [{"name": "Atlantis"}]})).is_err());
+}
+
+#[test]
+fn test_response_includes_resolved_location() {
+    let raw = fs::read_to_string("tests/fixtures/contracts/open_meteo_forecast.json").unwrap();
+    let geocoding =
+        fs::read_to_string("tests/fixtures/contracts/open_meteo_geocoding.json").unwrap();
+    let geocoded = parse_geocoding(&serde_json::from_str(&geocoding).unwrap()).unwrap();
+    let located = LocatedForecast {
+        forecast: serde_json::from_str(&raw).unwrap(),
+        units: UnitSystem::Metric,
+        resolved_location: geocoded.place,
+        alternates: geocoded.alternates,
+    };
+
+    let response = serde_json::to_value(WeatherResponseV2::from(located)).unwrap();
+    assert_eq!(response["resolvedLocation"]["country"], "Australia");
+    assert!(response.get("alternates").is_none());
+}
@@ -0,0 +1,92 @@
+// Geofence tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::geofence::{self, BoundingBox, Geofence};
+use aws_lambda_mcp::models::ResolvedLocation;
+use aws_lambda_mcp::models::error::AppError;
+use aws_lambda_mcp::tenants::TenantOverlay;
+use serde_json::json;
+
+fn place(name: &str, country_code: &str, latitude: f64, longitude: f64) -> ResolvedLocation {
+    ResolvedLocation {
+        name: name.to_string(),
+        country: None,
+        country_code: Some(country_code.to_string()),
+        admin_region: None,
+        population: None,
+        latitude,
+        longitude,
+        confidence: 1.0,
+    }
+}
+
+fn fence() -> Geofence {
+    serde_json::from_value(json!({
+        "countries": ["au", "NZ"],
+        "bounding_boxes": [
+            {"min_latitude": 1.1, "min_longitude": 103.6, "max_latitude": 1.5, "max_longitude": 104.1}
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_fence_contains_listed_countries_and_areas() {
+    let fence = fence();
+    assert!(fence.contains(&place("Sydney", "AU", -33.87, 151.21)));
+    assert!(fence.contains(&place("Auckland", "NZ", -36.85, 174.76)));
+    assert!(fence.contains(&place("Singapore", "SG", 1.29, 103.85)));
+    assert!(!fence.contains(&place("London", "GB", 51.51, -0.13)));
+    assert!(!Geofence::default().contains(&place("Sydney", "AU", -33.87, 151.21)));
+}
+
+#[test]
+fn test_bounding_box_may_cross_the_antimeridian() {
+    let pacific = BoundingBox {
+        min_latitude: -25.0,
+        min_longitude: 170.0,
+        max_latitude: -10.0,
+        max_longitude: -170.0,
+    };
+    assert!(pacific.contains(-18.0, 178.4));
+    assert!(pacific.contains(-18.0, -175.0));
+    assert!(!pacific.contains(-18.0, 160.0));
+    assert!(!pacific.contains(-30.0, 178.4));
+}
+
+#[test]
+fn test_countries_restrict_holidays_only_when_listed() {
+    assert!(fence().allows_country("AU"));
+    assert!(!fence().allows_country("GB"));
+    let areas_only = Geofence {
+        countries: Vec::new(),
+        ..fence()
+    };
+    assert!(areas_only.allows_country("GB"));
+}
+
+#[test]
+fn test_overlay_takes_a_geofence() {
+    let overlay: TenantOverlay =
+        serde_json::from_value(json!({"geofence": {"countries": ["AU"]}})).unwrap();
+    assert_eq!(overlay.geofence.unwrap().countries, vec!["AU".to_string()]);
+    assert!(
+        serde_json::from_value::<TenantOverlay>(json!({"geofence": {"country": ["AU"]}})).is_err()
+    );
+}
+
+#[test]
+fn test_callers_without_a_fence_are_served_everywhere() {
+    let mut alternates = vec![place("London", "GB", 51.51, -0.13)];
+    assert!(geofence::check_place(&place("Paris", "FR", 48.86, 2.35), &mut alternates).is_ok());
+    assert_eq!(alternates.len(), 1);
+    assert!(geofence::check_country("GB").is_ok());
+}
+
+#[test]
+fn test_geofence_denial_is_catalogued() {
+    let diagnostic =
+        AppError::GeofenceDenied("London is outside".to_string()).to_diagnostic("ToolError");
+    assert!(diagnostic.error_message.starts_with("[E1011]"));
+    assert!(diagnostic.error_message.contains("London is outside"));
+}
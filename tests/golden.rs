@@ -0,0 +1,73 @@
+// Golden-file snapshot tests for the wire format
+//
+// Serialized tool responses, error diagnostics and the published Bedrock
+// tool schemas are compared against snapshots in tests/snapshots, so any
+// change to what clients receive shows up as a reviewable diff. After an
+// intentional change, review and accept the new snapshots with:
+//
+//     cargo insta review
+
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::open_meteo::OpenMeteoResponse;
+use aws_lambda_mcp::models::{
+    ContentBlock, ContentResponse, ResponseVersion, VersionedWeatherResponse, WeatherResponse,
+};
+use aws_lambda_mcp::registry::{self, bedrock_tool_schemas};
+use insta::assert_json_snapshot;
+use serde_json::{Value, json};
+use std::fs;
+
+#[test]
+fn test_bedrock_tool_schemas() {
+    for schema in bedrock_tool_schemas().unwrap() {
+        let name = schema["name"].as_str().unwrap().to_string();
+        assert_json_snapshot!(format!("schema_{name}"), schema);
+    }
+}
+
+fn forecast_fixture() -> OpenMeteoResponse {
+    let raw = fs::read_to_string("tests/fixtures/contracts/open_meteo_forecast.json").unwrap();
+    serde_json::from_str(&raw).unwrap()
+}
+
+#[test]
+fn test_weather_response() {
+    let response = serde_json::to_value(WeatherResponse::from(forecast_fixture())).unwrap();
+    assert_json_snapshot!("weather_response", response);
+}
+
+#[test]
+fn test_weather_response_v2() {
+    let versioned = VersionedWeatherResponse::negotiate(forecast_fixture(), ResponseVersion::V2);
+    let response = serde_json::to_value(versioned).unwrap();
+    assert_json_snapshot!("weather_response_v2", response);
+}
+
+#[tokio::test]
+async fn test_greeting_response() {
+    let tool = registry::find("get_personalized_greeting").unwrap();
+    let response = (tool.handler)(json!({"user_id": "ada@example.com"}))
+        .await
+        .unwrap();
+    assert_json_snapshot!("greeting_response", response);
+}
+
+#[test]
+fn test_content_response() {
+    let response = ContentResponse {
+        content: vec![
+            ContentBlock::text("Chart attached."),
+            ContentBlock::image(b"\x89PNG\r\n\x1a\n", "image/png").unwrap(),
+        ],
+    };
+    assert_json_snapshot!("content_response", serde_json::to_value(response).unwrap());
+}
+
+#[tokio::test]
+async fn test_unknown_tool_diagnostic() {
+    let diagnostic = route_tool("unknown_tool", json!({})).await.unwrap_err();
+    let diagnostic: Value = serde_json::to_value(diagnostic).unwrap();
+    assert_json_snapshot!("unknown_tool_diagnostic", diagnostic);
+}
@@ -0,0 +1,75 @@
+// Request body guard tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::guards::{BodyLimits, BodyRejection, check_body, check_text, check_value};
+use serde_json::{Value, json};
+
+const LIMITS: BodyLimits = BodyLimits {
+    max_bytes: 64,
+    max_depth: 3,
+};
+
+fn nested(depth: usize) -> String {
+    format!("{}{}", "[".repeat(depth), "]".repeat(depth))
+}
+
+#[test]
+fn test_text_within_limits() {
+    assert!(check_text(r#"{"params": {"arguments": {"a": 1}}}"#, LIMITS).is_ok());
+    assert!(check_text(&nested(3), LIMITS).is_ok());
+}
+
+#[test]
+fn test_text_too_large() {
+    let text = format!(r#"{{"a": "{}"}}"#, "x".repeat(64));
+    assert_eq!(
+        check_text(&text, LIMITS),
+        Err(BodyRejection::TooLarge { limit: 64 })
+    );
+}
+
+#[test]
+fn test_text_too_deep() {
+    assert_eq!(
+        check_text(&nested(4), LIMITS),
+        Err(BodyRejection::TooDeep { limit: 3 })
+    );
+}
+
+#[test]
+fn test_brackets_in_strings_are_not_nesting() {
+    assert!(check_text(r#"{"a": "[[[[{{{{\"]]]]"}"#, LIMITS).is_ok());
+}
+
+#[test]
+fn test_value_limits() {
+    let value: Value = serde_json::from_str(&nested(3)).unwrap();
+    assert!(check_value(&value, LIMITS).is_ok());
+
+    let value: Value = serde_json::from_str(&nested(4)).unwrap();
+    assert_eq!(
+        check_value(&value, LIMITS),
+        Err(BodyRejection::TooDeep { limit: 3 })
+    );
+
+    let value = json!({"a": "x".repeat(64)});
+    assert_eq!(
+        check_value(&value, LIMITS),
+        Err(BodyRejection::TooLarge { limit: 64 })
+    );
+}
+
+#[test]
+fn test_string_bodies_are_checked_as_text() {
+    assert_eq!(
+        check_body(&json!(nested(4)), LIMITS),
+        Err(BodyRejection::TooDeep { limit: 3 })
+    );
+    assert!(check_body(&json!({"jsonrpc": "2.0"}), LIMITS).is_ok());
+}
+
+#[test]
+fn test_rejection_status() {
+    assert_eq!(BodyRejection::TooLarge { limit: 1 }.status_code(), 413);
+    assert_eq!(BodyRejection::TooDeep { limit: 1 }.status_code(), 400);
+}
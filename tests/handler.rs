@@ -16,6 +16,17 @@ async fn test_route_tool_unknown() {
     }
 }
 
+#[tokio::test]
+async fn test_route_tool_unknown_suggests_closest_name() {
+    let err = route_tool("get_wether", json!({})).await.unwrap_err();
+    assert_eq!(err.error_type, "UnknownTool");
+    assert!(err.error_message.contains("Did you mean get_weather?"));
+    assert!(err.error_message.contains("Available tools: get_weather, "));
+
+    let err = route_tool("send_email", json!({})).await.unwrap_err();
+    assert!(!err.error_message.contains("Did you mean"));
+}
+
 #[tokio::test]
 async fn test_weather_argument_extraction() {
     // Simulate MCP request structure with arguments for get_weather
@@ -147,3 +158,12 @@ fn assert_successful_greeting(
         }
     }
 }
+
+#[tokio::test]
+async fn test_aws_costs_invalid_arguments() {
+    // A lone service name would be coerced into a list, but an object cannot be
+    let payload = json!({"services": {"name": "Amazon Simple Storage Service"}});
+    let err = route_tool("get_aws_costs", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "InvalidInput");
+}
@@ -0,0 +1,55 @@
+// Gateway header map tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::Headers;
+use serde_json::json;
+
+#[test]
+fn test_lookups_ignore_case() {
+    let headers = Headers::from([("Authorization", "Bearer abc")]);
+
+    assert_eq!(headers.get("authorization"), Some("Bearer abc"));
+    assert_eq!(headers.get("AUTHORIZATION"), Some("Bearer abc"));
+    assert!(headers.contains_key("Authorization"));
+    assert_eq!(headers.get("cookie"), None);
+}
+
+#[test]
+fn test_values_keep_their_order() {
+    let mut headers = Headers::new();
+    headers.append("Accept", "text/html");
+    headers.append("accept", "application/json");
+
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers.get("accept"), Some("text/html"));
+    assert_eq!(headers.get_all("ACCEPT"), ["text/html", "application/json"]);
+
+    headers.insert("Accept", "*/*");
+    assert_eq!(headers.get_all("accept"), ["*/*"]);
+    assert_eq!(headers.remove("accept"), ["*/*"]);
+    assert!(headers.is_empty());
+}
+
+#[test]
+fn test_deserialize_merges_case_variants_and_lists() {
+    let headers: Headers = serde_json::from_value(json!({
+        "X-Forwarded-For": "10.0.0.1",
+        "x-forwarded-for": ["10.0.0.2", "10.0.0.3"],
+        "Content-Type": "application/json"
+    }))
+    .unwrap();
+
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers.get_all("x-forwarded-for").len(), 3);
+    assert_eq!(headers.get("content-type"), Some("application/json"));
+}
+
+#[test]
+fn test_serialize_combines_repeated_values() {
+    let headers = Headers::from([("Accept", "text/html"), ("accept", "application/json")]);
+
+    assert_eq!(
+        serde_json::to_value(&headers).unwrap(),
+        json!({"accept": "text/html, application/json"})
+    );
+}
@@ -0,0 +1,48 @@
+// Public holidays tool tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::tools::holidays::parse_holidays;
+use serde_json::json;
+
+#[test]
+fn test_parse_holidays_sorts_by_date() {
+    let body = json!([
+        {"date": "2026-12-25", "localName": "Christmas Day", "name": "Christmas Day", "global": true},
+        {"date": "2026-01-26", "localName": "Australia Day", "name": "Australia Day", "global": true},
+        {"date": "2026-03-09", "localName": "Labour Day", "name": "Labour Day", "global": false}
+    ]);
+    let holidays = parse_holidays(body.to_string().as_bytes()).unwrap();
+
+    let dates: Vec<&str> = holidays.iter().map(|h| h.date.as_str()).collect();
+    assert_eq!(dates, ["2026-01-26", "2026-03-09", "2026-12-25"]);
+    assert!(holidays[0].nationwide);
+    assert!(!holidays[1].nationwide);
+    assert_eq!(holidays[1].local_name, "Labour Day");
+}
+
+#[test]
+fn test_parse_holidays_rejects_unexpected_body() {
+    assert!(parse_holidays(br#"{"status": 500}"#).is_err());
+}
+
+#[tokio::test]
+async fn test_invalid_country_code_is_rejected() {
+    let err = route_tool("get_public_holidays", json!({"country_code": "AUS"}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("two letters"));
+}
+
+#[tokio::test]
+async fn test_year_out_of_range_is_rejected() {
+    let err = route_tool(
+        "get_public_holidays",
+        json!({"country_code": "AU", "year": 1066}),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("Year must be between"));
+}
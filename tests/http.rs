@@ -0,0 +1,318 @@
+// HTTP helper, circuit breaker and egress policy tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::http::transport::Transport;
+use aws_lambda_mcp::http::{
+    CircuitBreaker, EgressPolicy, EndpointPool, Fault, FaultProfile, HTTP_CLIENT, Hedge,
+    retry_after, send, send_error,
+};
+use aws_lambda_mcp::models::error::AppError;
+use chrono::Utc;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::{Client, Url};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::LazyLock;
+use std::thread;
+use std::time::Duration;
+
+fn headers(retry_after: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(retry_after) {
+        headers.insert(RETRY_AFTER, value);
+    }
+    headers
+}
+
+#[test]
+fn test_retry_after_parsing() {
+    assert_eq!(retry_after(&headers("120")), Some(Duration::from_mins(2)));
+    assert_eq!(retry_after(&headers("soon")), None);
+    assert_eq!(retry_after(&HeaderMap::new()), None);
+
+    let date = (Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+    let delay = retry_after(&headers(&date)).unwrap_or_default();
+    assert!(delay > Duration::from_secs(55) && delay <= Duration::from_mins(1));
+}
+
+#[test]
+fn test_breaker_opens_on_rate_limit_and_failures() {
+    let breaker = CircuitBreaker::new("test", 2, Duration::from_secs(30));
+    assert!(breaker.open_for().is_none());
+
+    breaker.record_rate_limited(Some(Duration::from_secs(5)));
+    assert!(
+        breaker
+            .open_for()
+            .is_some_and(|left| left <= Duration::from_secs(5))
+    );
+
+    breaker.record_success();
+    assert!(breaker.open_for().is_none());
+
+    breaker.record_failure();
+    assert!(breaker.open_for().is_none(), "Below the failure threshold");
+    breaker.record_failure();
+    assert!(breaker.open_for().is_some());
+}
+
+#[test]
+fn test_rate_limited_error_includes_retry_hint() {
+    let error = AppError::RateLimited {
+        upstream: "Open-Meteo".to_string(),
+        retry_after: Some(Duration::from_secs(30)),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Rate limited by Open-Meteo, retry after 30 seconds"
+    );
+}
+
+#[test]
+fn test_egress_policy_allow_and_deny_rules() {
+    let policy = EgressPolicy::new(
+        &["open-meteo.com".to_string(), "*.example.org".to_string()],
+        &["internal.example.org".to_string()],
+    );
+    let check = |url: &str| policy.check(&Url::parse(url).unwrap());
+
+    assert!(check("https://api.open-meteo.com/v1/forecast").is_ok());
+    assert!(check("https://open-meteo.com/").is_ok());
+    assert!(check("https://feeds.example.org/rss").is_ok());
+    assert!(matches!(
+        check("https://internal.example.org/admin"),
+        Err(AppError::EgressDenied(_))
+    ));
+    assert!(check("https://notopen-meteo.com/").is_err());
+    assert!(check("https://evil.com/").is_err());
+    assert!(check("ftp://open-meteo.com/file").is_err());
+}
+
+#[test]
+fn test_egress_policy_denies_private_addresses() {
+    let policy = EgressPolicy::new(&[], &[]);
+    let check = |policy: &EgressPolicy, url: &str| policy.check(&Url::parse(url).unwrap());
+
+    for url in [
+        "http://127.0.0.1:9001/2018-06-01/runtime/invocation/next",
+        "http://169.254.169.254/latest/meta-data/",
+        "http://169.254.170.2/v2/credentials",
+        "http://10.0.0.8/",
+        "http://100.64.0.1/",
+        "http://0.0.0.0/",
+        "http://[::1]/",
+        "http://[fd00::1]/",
+        "http://[::ffff:127.0.0.1]/",
+        "http://localhost:9001/",
+        "http://api.localhost/",
+        "http://2130706433/",
+    ] {
+        assert!(
+            matches!(check(&policy, url), Err(AppError::EgressDenied(_))),
+            "{url} should be denied"
+        );
+    }
+    assert!(check(&policy, "https://1.1.1.1/").is_ok());
+    assert!(check(&policy, "https://[2606:4700::1111]/").is_ok());
+    assert!(policy.permits_address("93.184.216.34".parse().unwrap()));
+    assert!(!policy.permits_address("169.254.169.254".parse().unwrap()));
+
+    let internal = policy.allowing_private_networks();
+    assert!(check(&internal, "http://10.0.0.8/").is_ok());
+    assert!(internal.permits_address("127.0.0.1".parse().unwrap()));
+}
+
+#[tokio::test]
+async fn test_requests_to_the_runtime_api_are_denied() {
+    let error = send(HTTP_CLIENT.get("http://127.0.0.1:9001/2018-06-01/runtime/invocation/next"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        send_error(error, |e| AppError::WeatherApiError(e.to_string())),
+        AppError::EgressDenied(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_redirects_are_checked_against_the_egress_policy() {
+    static POLICY: LazyLock<EgressPolicy> = LazyLock::new(|| {
+        EgressPolicy::new(&[], &["169.254.169.254".to_string()]).allowing_private_networks()
+    });
+    // Answers one request with a redirect to the instance metadata endpoint
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let _ = stream.read(&mut request).unwrap();
+        stream
+            .write_all(
+                b"HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\n\
+                  Content-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .unwrap();
+    });
+
+    let client = Client::builder()
+        .redirect(POLICY.redirect_policy())
+        .build()
+        .unwrap();
+    let error = client
+        .get(format!("http://{address}/feed"))
+        .send()
+        .await
+        .unwrap_err();
+    assert!(error.is_redirect());
+    assert!(matches!(
+        send_error(error.into(), |e| AppError::WeatherApiError(e.to_string())),
+        AppError::EgressDenied(_)
+    ));
+}
+
+#[test]
+fn test_egress_policy_defaults_to_allow() {
+    let policy = EgressPolicy::new(&[], &["169.254.169.254".to_string()]);
+    assert!(
+        policy
+            .check(&Url::parse("https://example.com/").unwrap())
+            .is_ok()
+    );
+    assert!(
+        policy
+            .check(&Url::parse("http://169.254.169.254/latest").unwrap())
+            .is_err()
+    );
+}
+
+#[test]
+fn test_endpoint_pool_prefers_fast_healthy_endpoints() {
+    let pool = EndpointPool::new(
+        "test",
+        &[
+            "https://eu.example.com/".to_string(),
+            "https://us.example.com".to_string(),
+        ],
+        2,
+        Duration::from_mins(1),
+    );
+    assert_eq!(
+        pool.ordered(),
+        vec!["https://eu.example.com", "https://us.example.com"]
+    );
+
+    pool.record_success("https://eu.example.com", Duration::from_millis(400));
+    pool.record_success("https://us.example.com", Duration::from_millis(90));
+    assert_eq!(pool.ordered()[0], "https://us.example.com");
+
+    // Unhealthy endpoints drop to the back until they recover
+    pool.record_failure("https://us.example.com");
+    assert_eq!(pool.ordered()[0], "https://us.example.com");
+    pool.record_failure("https://us.example.com");
+    assert_eq!(pool.ordered()[0], "https://eu.example.com");
+
+    pool.record_success("https://us.example.com", Duration::from_millis(90));
+    assert_eq!(pool.ordered()[0], "https://us.example.com");
+}
+
+#[test]
+fn test_hedge_waits_for_enough_latencies() {
+    let hedge = Hedge::new("test");
+    for _ in 0..19 {
+        hedge.record(Duration::from_millis(100));
+    }
+    assert_eq!(hedge.budget(Duration::ZERO), None);
+
+    hedge.record(Duration::from_millis(100));
+    assert_eq!(
+        hedge.budget(Duration::ZERO),
+        Some(Duration::from_millis(100))
+    );
+}
+
+#[test]
+fn test_hedge_budget_is_p95_of_recent_latencies() {
+    let hedge = Hedge::new("test");
+    for millis in 1..=100 {
+        hedge.record(Duration::from_millis(millis * 10));
+    }
+    assert_eq!(
+        hedge.budget(Duration::ZERO),
+        Some(Duration::from_millis(950))
+    );
+    assert_eq!(
+        hedge.budget(Duration::from_secs(2)),
+        Some(Duration::from_secs(2))
+    );
+
+    // Old latencies leave the window as new ones arrive
+    for _ in 0..100 {
+        hedge.record(Duration::from_millis(40));
+    }
+    assert_eq!(
+        hedge.budget(Duration::from_millis(25)),
+        Some(Duration::from_millis(40))
+    );
+}
+
+#[test]
+fn test_transport_without_secrets_changes_nothing() {
+    let transport = Transport::new(None, None).unwrap();
+    assert!(!transport.has_identity());
+    assert_eq!(transport.extra_roots(), 0);
+    assert!(!transport.is_proxied());
+}
+
+#[test]
+fn test_transport_rejects_invalid_client_certificate() {
+    assert!(Transport::new(Some("not a certificate"), None).is_err());
+    let split = r#"{"certificate": "not a certificate", "private_key": "not a key"}"#;
+    assert!(Transport::new(Some(split), None).is_err());
+}
+
+#[test]
+fn test_transport_proxy() {
+    let no_proxy = vec!["10.0.0.0/8".to_string(), ".internal.example".to_string()];
+    let transport = Transport::new(None, None)
+        .unwrap()
+        .with_proxy("http://proxy.internal.example:3128", &no_proxy)
+        .unwrap();
+    assert!(transport.is_proxied());
+
+    let invalid = Transport::new(None, None)
+        .unwrap()
+        .with_proxy("http://exa mple:port", &[]);
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_fault_profile_parse() {
+    let profile = FaultProfile::parse("timeout=0.05; server_error=0.1,malformed=0.05").unwrap();
+    assert!(
+        profile
+            .probabilities
+            .iter()
+            .zip([0.05, 0.1, 0.05, 0.0])
+            .all(|(parsed, expected)| (parsed - expected).abs() < f64::EPSILON)
+    );
+    assert!(profile.is_active());
+    assert!(!FaultProfile::parse("").unwrap().is_active());
+
+    assert!(FaultProfile::parse("flaky=0.1").is_err());
+    assert!(FaultProfile::parse("timeout").is_err());
+    assert!(FaultProfile::parse("timeout=1.5").is_err());
+    assert!(FaultProfile::parse("timeout=0.6;slow=0.6").is_err());
+}
+
+#[test]
+fn test_fault_profile_pick() {
+    let profile = FaultProfile::parse("timeout=0.1;server_error=0.2;slow=0.3").unwrap();
+    assert_eq!(profile.pick(0.05), Some(Fault::Timeout));
+    assert_eq!(profile.pick(0.25), Some(Fault::ServerError));
+    // Malformed responses have no share, so the next band is slow
+    assert_eq!(profile.pick(0.35), Some(Fault::Slow));
+    assert_eq!(profile.pick(0.6), None);
+    assert_eq!(
+        FaultProfile::only(Fault::Malformed, 1.0).pick(0.99),
+        Some(Fault::Malformed)
+    );
+}
@@ -0,0 +1,64 @@
+// Signed identity context tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::identity::{IdentityContext, KeyRing};
+use aws_lambda_mcp::models::error::AppError;
+use serde_json::json;
+
+fn context() -> IdentityContext {
+    IdentityContext::new("jane@example.com".to_string(), "Jane".to_string())
+}
+
+#[test]
+fn test_identity_context_roundtrip() {
+    let ring = KeyRing::parse("single-key").unwrap();
+    let token = ring.sign(&context());
+    assert_eq!(ring.verify(&token).unwrap(), context());
+}
+
+#[test]
+fn test_identity_context_rejects_tampering_and_expiry() {
+    let ring = KeyRing::parse("single-key").unwrap();
+    let token = ring.sign(&context());
+
+    let forged = KeyRing::parse("other-key").unwrap();
+    assert!(matches!(
+        forged.verify(&token),
+        Err(AppError::Unauthorized(_))
+    ));
+
+    let (input, _) = token.rsplit_once('.').unwrap();
+    let tampered = format!("{input}.AAAA");
+    assert!(ring.verify(&tampered).is_err());
+
+    let expired = IdentityContext {
+        exp: 1,
+        ..context()
+    };
+    assert!(ring.verify(&ring.sign(&expired)).is_err());
+}
+
+#[test]
+fn test_key_ring_rotation_accepts_previous_key() {
+    let before = KeyRing::parse(r#"{"current": "v1", "keys": {"v1": "old"}}"#).unwrap();
+    let after = KeyRing::parse(r#"{"current": "v2", "keys": {"v1": "old", "v2": "new"}}"#).unwrap();
+
+    let issued_before = before.sign(&context());
+    assert!(after.verify(&issued_before).is_ok());
+    assert!(after.sign(&context()).starts_with("v2."));
+
+    assert!(KeyRing::parse(r#"{"current": "v3", "keys": {"v1": "old"}}"#).is_none());
+    assert!(KeyRing::parse("  ").is_none());
+}
+
+#[tokio::test]
+async fn test_unverifiable_identity_context_rejected() {
+    let err = route_tool(
+        "get_personalized_greeting",
+        json!({"identity_context": "v1.e30.AAAA", "user_name": "Mallory"}),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.error_type, "Unauthorized");
+}
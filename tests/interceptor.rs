@@ -2,7 +2,11 @@
 // Note: These tests focus on the public behavior and helper functions
 #![allow(clippy::expect_used, clippy::panic)]
 
-use aws_lambda_mcp::models::interceptor::InterceptorEvent;
+use aws_lambda_mcp::context::{parse_session_id, session_id_from_headers};
+use aws_lambda_mcp::models::Headers;
+use aws_lambda_mcp::models::interceptor::{
+    GatewayResponse, InterceptorDecision, InterceptorEvent, InterceptorResponse,
+};
 use aws_lambda_mcp::utils::strip_gateway_prefix;
 use std::collections::HashMap;
 
@@ -138,3 +142,74 @@ fn test_auth_header_extraction() {
 
     assert_eq!(token, Some("abc.def.ghi"));
 }
+
+#[test]
+fn test_interceptor_decision_serialization() {
+    let decision = InterceptorDecision {
+        tool: Some("send_notification".to_string()),
+        session_id: Some("conv-42".to_string()),
+        applied: false,
+        injected: vec!["auth_token", "session_id"],
+        stripped: vec!["user_id"],
+        blocked: Some("Unauthorized: send_notification requires one of the scopes".to_string()),
+    };
+    let record = serde_json::to_value(&decision).expect("decision should serialize");
+    assert_eq!(record["tool"], "send_notification");
+    assert_eq!(record["applied"], false);
+    assert_eq!(record["injected"][0], "auth_token");
+    assert_eq!(record["stripped"][0], "user_id");
+    assert_eq!(record["sessionId"], "conv-42");
+    assert!(record["blocked"].is_string());
+
+    let record =
+        serde_json::to_value(InterceptorDecision::default()).expect("decision should serialize");
+    assert!(record.get("tool").is_none());
+    assert!(record.get("sessionId").is_none());
+    assert!(record.get("blocked").is_none());
+    assert!(record.get("stripped").is_none());
+}
+
+#[test]
+fn test_session_id_from_headers() {
+    let names = [
+        "X-Amzn-Bedrock-AgentCore-Runtime-Session-Id".to_string(),
+        "Mcp-Session-Id".to_string(),
+    ];
+    let headers = Headers::from([
+        ("mcp-session-id".to_string(), "mcp-7".to_string()),
+        (
+            "x-amzn-bedrock-agentcore-runtime-session-id".to_string(),
+            " conv-42 ".to_string(),
+        ),
+    ]);
+    assert_eq!(
+        session_id_from_headers(&headers, &names).as_deref(),
+        Some("conv-42")
+    );
+
+    let headers = Headers::from([("Mcp-Session-Id".to_string(), "mcp-7".to_string())]);
+    assert_eq!(
+        session_id_from_headers(&headers, &names).as_deref(),
+        Some("mcp-7")
+    );
+
+    let headers = Headers::from([("Mcp-Session-Id".to_string(), "bad id\n".to_string())]);
+    assert_eq!(session_id_from_headers(&headers, &names), None);
+    assert_eq!(parse_session_id(&"a".repeat(257)), None);
+}
+
+#[test]
+fn test_gateway_response_replaces_request() {
+    let response = InterceptorResponse::respond(GatewayResponse {
+        status_code: 413,
+        headers: None,
+        body: Some(serde_json::json!({"jsonrpc": "2.0", "id": 1})),
+    });
+    let serialized = serde_json::to_value(&response).expect("Response should serialize");
+
+    assert_eq!(
+        serialized["mcp"]["transformedGatewayResponse"]["statusCode"],
+        413
+    );
+    assert!(serialized["mcp"].get("transformedGatewayRequest").is_none());
+}
@@ -0,0 +1,55 @@
+// Concurrency limit tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::limits::{ConcurrencyLimiter, InFlightTracker, parse_tool_limits};
+use aws_lambda_mcp::models::error::AppError;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[test]
+fn test_parse_tool_limits() {
+    let limits = parse_tool_limits("read_feed=4; query_logs = 1;bad;summarize_text=x");
+    assert_eq!(
+        limits,
+        HashMap::from([("read_feed".to_string(), 4), ("query_logs".to_string(), 1)])
+    );
+}
+
+#[tokio::test]
+async fn test_limiter_rejects_when_saturated() {
+    let limiter = ConcurrencyLimiter::new(
+        HashMap::from([("query_logs".to_string(), 1)]),
+        0,
+        Duration::from_millis(20),
+    );
+
+    let permit = limiter.acquire("query_logs").await.unwrap();
+    assert!(permit.is_some());
+    assert!(matches!(
+        limiter.acquire("query_logs").await,
+        Err(AppError::TooBusy(_))
+    ));
+
+    // Unlimited tools are never queued
+    assert!(limiter.acquire("get_weather").await.unwrap().is_none());
+
+    drop(permit);
+    assert!(limiter.acquire("query_logs").await.unwrap().is_some());
+}
+
+#[test]
+fn test_in_flight_tracker_sheds_beyond_threshold() {
+    let tracker = InFlightTracker::new(2);
+
+    let first = tracker.try_enter().unwrap();
+    let _second = tracker.try_enter().unwrap();
+    assert!(matches!(tracker.try_enter(), Err(AppError::Overloaded(_))));
+    assert_eq!(tracker.in_flight(), 2, "Shed invocations are not counted");
+
+    drop(first);
+    assert!(tracker.try_enter().is_ok());
+
+    let unlimited = InFlightTracker::new(0);
+    let _guards: Vec<_> = (0..10).map(|_| unlimited.try_enter().unwrap()).collect();
+    assert_eq!(unlimited.in_flight(), 10);
+}
@@ -0,0 +1,153 @@
+// Log format tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::logging::{self, LogFormat, LogLayer, LogLine};
+use chrono::{TimeZone, Utc};
+use lambda_runtime::tracing::dispatcher::{Dispatch, with_default};
+use lambda_runtime::tracing::{Level, info};
+use serde_json::{Value, json};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::Registry;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Collects written lines for inspection
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Captured {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+fn line(fields: &Value) -> LogLine<'static> {
+    let Value::Object(fields) = fields.clone() else {
+        return line(&json!({}));
+    };
+    LogLine {
+        timestamp: Utc.with_ymd_and_hms(2026, 10, 15, 9, 30, 0).unwrap(),
+        level: Level::WARN,
+        target: "aws_lambda_mcp::handler",
+        service: "weather-mcp",
+        fields,
+    }
+}
+
+fn sample() -> LogLine<'static> {
+    line(&json!({
+        "message": "Endpoint failed",
+        "tool": "get_weather",
+        "correlation_id": "req-1",
+        "tenant": "contoso",
+        "attempt": 2,
+    }))
+}
+
+#[test]
+fn test_format_parsing() {
+    assert_eq!(LogFormat::parse("OTEL"), Some(LogFormat::Otel));
+    assert_eq!(LogFormat::parse(" json "), Some(LogFormat::Lambda));
+    assert_eq!(LogFormat::parse("text"), Some(LogFormat::Plain));
+    assert_eq!(LogFormat::parse("xml"), None);
+}
+
+#[test]
+fn test_plain_lists_correlation_fields_first() {
+    assert_eq!(
+        LogFormat::Plain.render(sample()),
+        "2026-10-15T09:30:00.000Z  WARN Endpoint failed tool=get_weather correlation_id=req-1 tenant=contoso attempt=2"
+    );
+}
+
+#[test]
+fn test_lambda_nests_fields_for_metric_filters() {
+    let rendered: Value = serde_json::from_str(&LogFormat::Lambda.render(sample())).unwrap();
+    assert_eq!(
+        rendered,
+        json!({
+            "timestamp": "2026-10-15T09:30:00.000Z",
+            "level": "WARN",
+            "target": "aws_lambda_mcp::handler",
+            "fields": {
+                "message": "Endpoint failed",
+                "tool": "get_weather",
+                "correlation_id": "req-1",
+                "tenant": "contoso",
+                "attempt": 2,
+            },
+        })
+    );
+}
+
+#[test]
+fn test_otel_follows_log_data_model() {
+    let rendered: Value = serde_json::from_str(&LogFormat::Otel.render(sample())).unwrap();
+    assert_eq!(rendered["Timestamp"], "1792056600000000000");
+    assert_eq!(rendered["SeverityText"], "WARN");
+    assert_eq!(rendered["SeverityNumber"], 13);
+    assert_eq!(rendered["Body"], "Endpoint failed");
+    assert_eq!(
+        rendered["Attributes"],
+        json!({"tool": "get_weather", "correlation_id": "req-1", "tenant": "contoso", "attempt": 2})
+    );
+    assert_eq!(rendered["Resource"]["service.name"], "weather-mcp");
+    assert_eq!(
+        rendered["InstrumentationScope"]["Name"],
+        "aws_lambda_mcp::handler"
+    );
+}
+
+#[test]
+fn test_lines_carry_invocation_span_fields() {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber =
+        Registry::default().with(LogLayer::new(LogFormat::Lambda, move || writer.clone()));
+
+    with_default(&Dispatch::new(subscriber), || {
+        let span = logging::invocation_span("get_weather", "req-2");
+        let _entered = span.enter();
+        logging::record_tenant("contoso");
+        info!(location = "Sydney", "Routing tool call");
+    });
+
+    let lines = captured.lines();
+    assert_eq!(lines.len(), 1);
+    let rendered: Value = serde_json::from_str(&lines[0]).unwrap();
+    for (name, value) in [
+        ("tool", "get_weather"),
+        ("correlation_id", "req-2"),
+        ("tenant", "contoso"),
+        ("location", "Sydney"),
+        ("message", "Routing tool call"),
+    ] {
+        assert_eq!(rendered["fields"][name], value);
+    }
+}
+
+#[test]
+fn test_set_filter_returns_previous_directives() {
+    let previous = logging::set_filter("aws_lambda_mcp::tools=debug").unwrap();
+    assert_eq!(
+        logging::set_filter(" warn ").unwrap(),
+        "aws_lambda_mcp::tools=debug"
+    );
+    assert!(logging::set_filter("tools=verbose").is_err());
+    assert_eq!(logging::set_filter(&previous).unwrap(), "warn");
+}
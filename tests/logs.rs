@@ -0,0 +1,38 @@
+// Logs Insights query tool tests
+#![allow(clippy::unwrap_used)]
+#![cfg(feature = "query-logs")]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::tools::logs::sanitize_query;
+use serde_json::json;
+
+#[test]
+fn test_sanitize_query_accepts_pipelines() {
+    let query =
+        "  fields @timestamp, @message\n| filter @message like /ERROR/\n| sort @timestamp desc  ";
+    let sanitized = sanitize_query(query).unwrap();
+
+    assert!(sanitized.starts_with("fields"));
+    assert!(sanitized.ends_with("desc"));
+}
+
+#[test]
+fn test_sanitize_query_rejects_unsafe_input() {
+    assert!(sanitize_query("   ").is_err());
+    assert!(sanitize_query("SOURCE '/aws/other' | fields @message").is_err());
+    assert!(sanitize_query("fields @message | source logGroups()").is_err());
+    assert!(sanitize_query("fields @message\u{0}").is_err());
+    assert!(sanitize_query(&"a".repeat(10_001)).is_err());
+}
+
+#[tokio::test]
+async fn test_log_group_must_be_allowlisted() {
+    let payload = json!({
+        "log_groups": ["/aws/lambda/secret-service"],
+        "query": "fields @message"
+    });
+    let err = route_tool("query_logs", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("allowlist"));
+}
@@ -0,0 +1,84 @@
+// Session memoization tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::context::ToolContext;
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::memo::{self, FROM_SESSION_CACHE};
+use aws_lambda_mcp::registry;
+use serde_json::{Value, json};
+
+fn key(tool: &str, args: &Value) -> Option<String> {
+    let tool = registry::find(tool).unwrap();
+    memo::key(tool, args, &ToolContext::from_args(tool.name, args))
+}
+
+#[tokio::test]
+async fn test_repeated_call_in_session_is_answered_from_cache() {
+    let args = json!({"user_name": "Ada", "session_id": "memo-repeat"});
+
+    let first = route_tool("get_personalized_greeting", args.clone())
+        .await
+        .unwrap();
+    let second = route_tool("get_personalized_greeting", args).await.unwrap();
+
+    assert!(first["_meta"][FROM_SESSION_CACHE].is_null());
+    assert_eq!(second["greeting"], "Hello, Ada!");
+    assert_eq!(second["_meta"][FROM_SESSION_CACHE], true);
+}
+
+#[tokio::test]
+async fn test_calls_in_other_sessions_are_not_answered_from_cache() {
+    route_tool(
+        "get_personalized_greeting",
+        json!({"user_name": "Grace", "session_id": "memo-first"}),
+    )
+    .await
+    .unwrap();
+    let other = route_tool(
+        "get_personalized_greeting",
+        json!({"user_name": "Grace", "session_id": "memo-second"}),
+    )
+    .await
+    .unwrap();
+
+    assert!(other["_meta"][FROM_SESSION_CACHE].is_null());
+}
+
+#[test]
+fn test_key_ignores_token_but_not_arguments_or_caller() {
+    let args = json!({"location": "Sydney", "session_id": "memo-key", "user_id": "ada"});
+    let mut with_token = args.clone();
+    with_token["auth_token"] = json!("not-a-jwt");
+    let mut elsewhere = args.clone();
+    elsewhere["location"] = json!("Perth");
+    let mut other_caller = args.clone();
+    other_caller["user_id"] = json!("grace");
+
+    let first = key("get_weather", &args).unwrap();
+    assert!(first.starts_with("memo-key/get_weather/"));
+    assert_eq!(key("get_weather", &with_token), Some(first.clone()));
+    assert_ne!(key("get_weather", &elsewhere), Some(first.clone()));
+    assert_ne!(key("get_weather", &other_caller), Some(first));
+}
+
+#[test]
+fn test_calls_without_session_or_with_side_effects_are_not_memoized() {
+    assert!(key("get_weather", &json!({"location": "Sydney"})).is_none());
+    assert!(
+        key(
+            "send_notification",
+            &json!({"message": "hi", "session_id": "memo-effects"})
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn test_marker_keeps_other_metadata() {
+    let mut response = json!({"greeting": "Hello!", "_meta": {"invocationId": "abc"}});
+    memo::attach_marker(&mut response);
+    assert_eq!(
+        response["_meta"],
+        json!({"invocationId": "abc", FROM_SESSION_CACHE: true})
+    );
+}
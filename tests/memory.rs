@@ -0,0 +1,79 @@
+// User memory tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::context::ToolContext;
+use aws_lambda_mcp::memory::Interaction;
+use aws_lambda_mcp::tools::personalized::greeting;
+use common::token;
+use serde_json::json;
+
+#[test]
+fn test_interaction_from_call() {
+    let weather = Interaction::from_call("get_weather", &json!({"location": " Sydney "})).unwrap();
+    assert_eq!(weather.tool, "get_weather");
+    assert_eq!(weather.topic.as_deref(), Some("Sydney"));
+    assert_eq!(weather.describe().as_deref(), Some("Sydney weather"));
+
+    let holidays =
+        Interaction::from_call("get_public_holidays", &json!({"country_code": "AU"})).unwrap();
+    assert_eq!(holidays.describe().as_deref(), Some("AU public holidays"));
+
+    // Tools without a topic argument are remembered by subject alone
+    let feed =
+        Interaction::from_call("read_feed", &json!({"url": "https://example.com/feed"})).unwrap();
+    assert_eq!(feed.topic, None);
+    assert_eq!(feed.describe().as_deref(), Some("a news feed"));
+
+    // Long topics are cut short
+    let long =
+        Interaction::from_call("get_weather", &json!({"location": "x".repeat(500)})).unwrap();
+    assert_eq!(long.topic.unwrap().chars().count(), 80);
+
+    assert!(Interaction::from_call("admin_flush_cache", &json!({})).is_none());
+    assert!(Interaction::from_call("get_personalized_greeting", &json!({})).is_none());
+}
+
+#[test]
+fn test_greeting_mentions_last_interaction() {
+    assert_eq!(greeting("John", None), "Hello, John!");
+
+    let last = Interaction::from_call("get_weather", &json!({"location": "Sydney"})).unwrap();
+    assert_eq!(
+        greeting("John", Some(&last)),
+        "Welcome back, John — last time you asked about Sydney weather."
+    );
+
+    // A memory of a tool no longer remembered is not mentioned
+    let retired = Interaction {
+        tool: "get_tides".to_string(),
+        ..last
+    };
+    assert_eq!(greeting("John", Some(&retired)), "Hello, John!");
+}
+
+#[test]
+fn test_memory_needs_opt_in_and_identity() {
+    // USER_MEMORY is off by default
+    let context = ToolContext::from_args(
+        "get_personalized_greeting",
+        &json!({"user_id": "john@example.com"}),
+    );
+    assert!(context.memory().is_none());
+    assert!(ToolContext::default().memory().is_none());
+}
+
+#[test]
+fn test_memory_is_keyed_on_the_token_subject() {
+    let token = token(&json!({"sub": "jane@example.com"}));
+    let context = ToolContext::from_args(
+        "get_personalized_greeting",
+        &json!({"user_id": "john@example.com", "auth_token": token}),
+    );
+    assert_eq!(context.subject.as_deref(), Some("jane@example.com"));
+
+    // A user_id argument alone names no memory
+    let spoofed = ToolContext::from_args("get_weather", &json!({"user_id": "jane@example.com"}));
+    assert_eq!(spoofed.subject, None);
+}
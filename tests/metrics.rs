@@ -0,0 +1,91 @@
+// Content size metrics tests
+
+use aws_lambda_mcp::cache::CacheStore;
+use aws_lambda_mcp::metrics::{
+    ConnectionUse, ContentMetrics, HedgeOutcome, META_KEY, attach, estimate_tokens, observe_call,
+    observe_connection, observe_hedge, render_prometheus,
+};
+use serde_json::json;
+use std::time::Duration;
+
+#[test]
+fn test_estimate_tokens() {
+    assert_eq!(estimate_tokens(""), 0);
+    assert_eq!(estimate_tokens("hello"), 2);
+    assert_eq!(estimate_tokens("The quick brown fox."), 7);
+    assert_eq!(estimate_tokens(r#"{"a":1}"#), 7);
+
+    // Grows roughly linearly with prose length
+    let prose = "Summaries should stay short and focused. ".repeat(100);
+    let tokens = estimate_tokens(&prose);
+    assert!(
+        (1_000..=1_500).contains(&tokens),
+        "unexpected estimate: {tokens}"
+    );
+}
+
+#[test]
+fn test_metrics_exclude_auth_token() {
+    let args = json!({"location": "Sydney"});
+    let with_token = json!({"location": "Sydney", "auth_token": "Bearer abc.def.ghi"});
+
+    assert_eq!(
+        ContentMetrics::for_input(&args),
+        ContentMetrics::for_input(&with_token)
+    );
+}
+
+#[test]
+fn test_attach_adds_meta_to_objects_only() {
+    let metrics = ContentMetrics::for_input(&json!({})).with_output(&json!({"ok": true}));
+    assert_eq!(metrics.output_bytes, 11);
+
+    let mut response = json!({"ok": true});
+    attach(&mut response, &metrics);
+    assert_eq!(response[META_KEY]["content"]["output_bytes"], 11);
+
+    let mut list = json!([1, 2, 3]);
+    attach(&mut list, &metrics);
+    assert_eq!(list, json!([1, 2, 3]));
+}
+
+#[test]
+fn test_render_prometheus() {
+    observe_call("metrics_test_tool", true, Duration::from_millis(30));
+    observe_call("metrics_test_tool", false, Duration::from_secs(20));
+
+    let cache = CacheStore::<u8>::new("metrics-test", Duration::from_mins(1), 4);
+    cache.insert("a", 1);
+    let _ = cache.get("a");
+    let _ = cache.get("b");
+
+    observe_hedge("metrics-upstream", HedgeOutcome::Unhedged);
+    observe_hedge("metrics-upstream", HedgeOutcome::Hedge);
+    observe_connection("metrics.example.com", ConnectionUse::New);
+    observe_connection("metrics.example.com", ConnectionUse::Reused);
+    observe_connection("metrics.example.com", ConnectionUse::Reused);
+
+    let text = render_prometheus();
+    for line in [
+        "# TYPE mcp_tool_calls_total counter",
+        r#"mcp_tool_calls_total{tool="metrics_test_tool",outcome="ok"} 1"#,
+        r#"mcp_tool_calls_total{tool="metrics_test_tool",outcome="error"} 1"#,
+        r#"mcp_tool_duration_seconds_bucket{tool="metrics_test_tool",le="0.05"} 1"#,
+        r#"mcp_tool_duration_seconds_bucket{tool="metrics_test_tool",le="+Inf"} 2"#,
+        r#"mcp_tool_duration_seconds_count{tool="metrics_test_tool"} 2"#,
+        r#"mcp_cache_requests_total{cache="metrics-test",result="hit"} 1"#,
+        r#"mcp_cache_requests_total{cache="metrics-test",result="miss"} 1"#,
+        "# TYPE mcp_cache_entries gauge",
+        r#"mcp_upstream_hedged_requests_total{upstream="metrics-upstream",outcome="unhedged"} 1"#,
+        r#"mcp_upstream_hedged_requests_total{upstream="metrics-upstream",outcome="hedge"} 1"#,
+        r#"mcp_upstream_connections_total{host="metrics.example.com",connection="new"} 1"#,
+        r#"mcp_upstream_connections_total{host="metrics.example.com",connection="reused"} 2"#,
+        r#"mcp_cache_entries{cache="geocode"} 0"#,
+        r#"mcp_cache_removals_total{cache="geocode",reason="eviction"} 0"#,
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing {line} in:\n{text}"
+        );
+    }
+}
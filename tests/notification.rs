@@ -0,0 +1,48 @@
+// Notification tool tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::tools::notification::render_template;
+use common::token;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_render_template_substitutes_variables() {
+    let variables = HashMap::from([
+        ("name".to_string(), "Ada".to_string()),
+        ("count".to_string(), "3".to_string()),
+    ]);
+    let rendered = render_template("Hi {{ name }}, you have {{count}} alerts.", &variables);
+
+    assert_eq!(rendered.unwrap(), "Hi Ada, you have 3 alerts.");
+}
+
+#[test]
+fn test_render_template_rejects_missing_and_unclosed_placeholders() {
+    let variables = HashMap::new();
+
+    assert!(render_template("Hi {{name}}", &variables).is_err());
+    assert!(render_template("Hi {{name", &variables).is_err());
+    assert_eq!(
+        render_template("No placeholders", &variables).unwrap(),
+        "No placeholders"
+    );
+}
+
+#[tokio::test]
+async fn test_destination_must_be_allowlisted() {
+    let token = token(&json!({"sub": "user-1", "scp": "Notifications.Send"}));
+    let payload = json!({
+        "channel": "email",
+        "destination": "someone@example.com",
+        "message": "hello",
+        "auth_token": token
+    });
+    let err = route_tool("send_notification", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("allowlist"));
+}
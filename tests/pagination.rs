@@ -0,0 +1,72 @@
+// Cursor pagination tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::pagination::{
+    PageCursor, paginate, resolve_cursor, scope_for, sign_cursor, verify_cursor,
+};
+use serde_json::json;
+
+#[test]
+fn test_cursor_roundtrip_preserves_state() {
+    let scope = scope_for("query_logs", &["/aws/lambda/app", "fields @message"]);
+    let cursor = PageCursor {
+        offset: 40,
+        state: json!({"start": 1_700_000_000, "end": 1_700_003_600}),
+    };
+
+    let token = sign_cursor(&scope, &cursor).unwrap();
+    assert_eq!(verify_cursor(&scope, &token).unwrap(), cursor);
+    assert_eq!(resolve_cursor(&scope, None).unwrap(), PageCursor::default());
+}
+
+#[test]
+fn test_cursor_rejects_tampering_and_other_scopes() {
+    let scope = scope_for("read_feed", &["https://example.com/feed.xml"]);
+    let token = sign_cursor(
+        &scope,
+        &PageCursor {
+            offset: 10,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Swap in a payload for a different offset, keeping the original tag
+    let forged = sign_cursor(
+        &scope,
+        &PageCursor {
+            offset: 1000,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let tampered = format!(
+        "{}.{}",
+        forged.split_once('.').unwrap().0,
+        token.split_once('.').unwrap().1
+    );
+    assert!(verify_cursor(&scope, &tampered).is_err());
+
+    let other = scope_for("read_feed", &["https://example.com/other.xml"]);
+    assert!(verify_cursor(&other, &token).is_err());
+    assert!(resolve_cursor(&scope, Some("not-a-cursor")).is_err());
+}
+
+#[test]
+fn test_paginate_walks_all_pages() {
+    let scope = scope_for("test", &[]);
+    let items: Vec<u32> = (0..25).collect();
+
+    let mut cursor = PageCursor::default();
+    let mut seen = Vec::new();
+    loop {
+        let page = paginate(items.clone(), &scope, &cursor, 10).unwrap();
+        seen.extend(page.items);
+        match page.next_cursor {
+            Some(token) => cursor = verify_cursor(&scope, &token).unwrap(),
+            None => break,
+        }
+    }
+
+    assert_eq!(seen, items);
+}
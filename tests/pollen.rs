@@ -0,0 +1,67 @@
+// Pollen forecast tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::{PollenLevel, RiskLevel};
+use aws_lambda_mcp::tools::pollen::{PollenForecast, pollen_days};
+use serde_json::json;
+
+fn forecast(hourly: &serde_json::Value) -> PollenForecast {
+    serde_json::from_value(json!({
+        "hourly_units": {"time": "iso8601", "birch_pollen": "grains/m³"},
+        "hourly": hourly
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_reports_daily_peak_of_each_pollen_type() {
+    let days = pollen_days(&forecast(&json!({
+        "time": ["2026-04-02T00:00", "2026-04-02T12:00", "2026-04-03T00:00"],
+        "birch_pollen": [12.0, 140.5, 4.0],
+        "grass_pollen": [0.0, 0.0, 0.0],
+        "olive_pollen": [null, null, null]
+    })));
+
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].date, "2026-04-02");
+    assert_eq!(
+        days[0].pollen,
+        vec![
+            PollenLevel {
+                kind: "birch".to_string(),
+                peak: 140.5,
+                risk: RiskLevel::High,
+            },
+            PollenLevel {
+                kind: "grass".to_string(),
+                peak: 0.0,
+                risk: RiskLevel::None,
+            },
+        ]
+    );
+    assert_eq!(days[1].pollen[0].risk, RiskLevel::Low);
+}
+
+#[test]
+fn test_risk_thresholds_depend_on_pollen_type() {
+    let days = pollen_days(&forecast(&json!({
+        "time": ["2026-08-20T09:00"],
+        "birch_pollen": [25.0],
+        "ragweed_pollen": [25.0]
+    })));
+
+    let risks: Vec<_> = days[0].pollen.iter().map(|level| level.risk).collect();
+    assert_eq!(risks, vec![RiskLevel::Moderate, RiskLevel::High]);
+}
+
+#[test]
+fn test_region_without_pollen_data_has_no_levels() {
+    let days = pollen_days(&forecast(&json!({
+        "time": ["2026-04-02T00:00", "2026-04-02T01:00"],
+        "alder_pollen": [null, null],
+        "birch_pollen": [null, null],
+        "grass_pollen": [null, null]
+    })));
+
+    assert!(days.iter().all(|day| day.pollen.is_empty()));
+}
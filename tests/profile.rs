@@ -0,0 +1,63 @@
+// Profile hint tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::context::{ProfileHints, ToolContext};
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::UnitSystem;
+use common::token;
+use serde_json::{Value, json};
+
+#[test]
+fn test_hints_from_token_claims() {
+    let token = token(&json!({
+        "sub": "user-1",
+        "locale": "en-US",
+        "zoneinfo": "America/Chicago",
+        "units": "Imperial"
+    }));
+    let context = ToolContext::from_args("get_weather", &json!({"auth_token": token}));
+
+    assert_eq!(
+        context.hints,
+        ProfileHints {
+            locale: Some("en-US".to_string()),
+            timezone: Some("America/Chicago".to_string()),
+            units: Some(UnitSystem::Imperial),
+        }
+    );
+    assert_eq!(
+        Value::Object(context.hints.arguments()),
+        json!({"locale": "en-US", "timezone": "America/Chicago", "units": "imperial"})
+    );
+}
+
+#[test]
+fn test_invalid_hints_are_ignored() {
+    let token = token(&json!({"zoneinfo": "Mars/Olympus Mons", "units": "furlongs"}));
+    let context = ToolContext::from_args("get_weather", &json!({"auth_token": token}));
+    assert_eq!(context.hints, ProfileHints::default());
+
+    let context = ToolContext::from_args("get_weather", &json!({"auth_token": "not-a-jwt"}));
+    assert_eq!(context.hints, ProfileHints::default());
+}
+
+#[test]
+fn test_unit_system_parse() {
+    assert_eq!(UnitSystem::parse(" US "), Some(UnitSystem::Imperial));
+    assert_eq!(UnitSystem::parse("metric"), Some(UnitSystem::Metric));
+    assert_eq!(UnitSystem::parse("kelvin"), None);
+}
+
+#[tokio::test]
+async fn test_weather_rejects_invalid_timezone() {
+    let err = route_tool(
+        "get_weather",
+        json!({"location": "Berlin", "timezone": "Europe/Berlin; rm -rf"}),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("IANA timezone"));
+}
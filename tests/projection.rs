@@ -0,0 +1,101 @@
+// Response field projection tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::WeatherResponse;
+use aws_lambda_mcp::projection::Projection;
+use schemars::schema_for;
+use serde_json::{Value, json};
+
+fn parse(fields: &Value) -> Projection {
+    Projection::parse(fields).unwrap().unwrap()
+}
+
+#[test]
+fn test_parses_lists_and_comma_separated_paths() {
+    assert_eq!(
+        parse(&json!("daily.time, $.daily_units.time")),
+        parse(&json!(["daily.time", "daily_units.time"]))
+    );
+    assert_eq!(
+        parse(&json!(["alternates[*].name"])),
+        parse(&json!(["alternates.name"]))
+    );
+    assert!(Projection::parse(&json!([])).unwrap().is_none());
+    assert!(Projection::parse(&json!(" , ")).unwrap().is_none());
+    assert!(Projection::parse(&json!(["daily..time"])).is_err());
+    assert!(Projection::parse(&json!({"daily": true})).is_err());
+}
+
+#[test]
+fn test_keeps_selected_fields_through_lists() {
+    let mut response = json!({
+        "timezone": "GMT",
+        "daily": {"time": ["2024-01-01"], "temperature2mMax": [21.5]},
+        "alternates": [
+            {"name": "Paris", "country": "France"},
+            {"name": "Paris", "country": "United States"}
+        ],
+        "_meta": {"tokens": 42}
+    });
+    parse(&json!(["daily.temperature2mMax", "alternates.country"])).apply(&mut response);
+
+    assert_eq!(
+        response,
+        json!({
+            "daily": {"temperature2mMax": [21.5]},
+            "alternates": [{"country": "France"}, {"country": "United States"}],
+            "_meta": {"tokens": 42}
+        })
+    );
+}
+
+#[test]
+fn test_selecting_an_object_keeps_it_whole() {
+    let mut response = json!({"daily": {"time": [], "temperature2mMax": []}, "timezone": "GMT"});
+    parse(&json!(["daily", "daily.time"])).apply(&mut response);
+    assert_eq!(
+        response,
+        json!({"daily": {"time": [], "temperature2mMax": []}})
+    );
+}
+
+#[test]
+fn test_validates_paths_against_output_schema() {
+    let schema = schema_for!(WeatherResponse);
+    let schema = schema.as_value();
+
+    assert!(
+        parse(&json!(["daily.temperature2mMax", "alternates.name"]))
+            .validate(schema)
+            .is_ok()
+    );
+    let err = parse(&json!(["daily.temperature_max"]))
+        .validate(schema)
+        .unwrap_err();
+    assert!(err.to_string().contains("daily.temperature_max"));
+    assert!(err.to_string().contains("temperature2mMax"));
+}
+
+#[tokio::test]
+async fn test_call_returns_only_selected_fields() {
+    let response = route_tool(
+        "get_personalized_greeting",
+        json!({"user_name": "Ada", "fields": ["greeting"]}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response["greeting"], "Hello, Ada!");
+}
+
+#[tokio::test]
+async fn test_call_with_unknown_field_is_rejected() {
+    let err = route_tool(
+        "get_personalized_greeting",
+        json!({"user_name": "Ada", "fields": ["salutation"]}),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.error_type, "InvalidInput");
+    assert!(err.error_message.contains("salutation"));
+}
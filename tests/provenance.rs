@@ -0,0 +1,51 @@
+// Response source tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::cache::CacheStore;
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::provenance::{self, SESSION_CACHE_SOURCE, SOURCES_KEY};
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_scope_collects_sources_in_first_use_order() {
+    let cache: CacheStore<u32> = CacheStore::new("geocode", Duration::from_mins(1), 8);
+    let ((), sources) = provenance::scope(async {
+        provenance::record_cache(&cache);
+        provenance::record("open-meteo:forecast");
+        provenance::record_cache(&cache);
+    })
+    .await;
+    assert_eq!(sources, vec!["cache:geocode", "open-meteo:forecast"]);
+}
+
+#[test]
+fn test_recording_outside_a_scope_is_ignored() {
+    provenance::record("open-meteo:forecast");
+}
+
+#[test]
+fn test_attach_keeps_other_metadata() {
+    let mut response = json!({"greeting": "Hello!", "_meta": {"invocationId": "abc"}});
+    provenance::attach(&mut response, &["nager-date:holidays".to_string()]);
+    assert_eq!(
+        response["_meta"],
+        json!({"invocationId": "abc", "sources": ["nager-date:holidays"]})
+    );
+
+    let mut response = json!({"greeting": "Hello!"});
+    provenance::attach(&mut response, &[]);
+    assert_eq!(response, json!({"greeting": "Hello!"}));
+}
+
+#[tokio::test]
+async fn test_session_cache_answers_are_attributed() {
+    let args = json!({"user_name": "Ada", "session_id": "provenance-repeat"});
+    let first = route_tool("get_personalized_greeting", args.clone())
+        .await
+        .unwrap();
+    let second = route_tool("get_personalized_greeting", args).await.unwrap();
+
+    assert!(first["_meta"][SOURCES_KEY].is_null());
+    assert_eq!(second["_meta"][SOURCES_KEY], json!([SESSION_CACHE_SOURCE]));
+}
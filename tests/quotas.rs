@@ -0,0 +1,115 @@
+// Tenant quota tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::error::AppError;
+use aws_lambda_mcp::quotas::{self, DEFAULT_WARN_PERCENT, Quota};
+use aws_lambda_mcp::tenants::{TenantOverlay, TenantSettings};
+use aws_lambda_mcp::usage;
+use chrono::{Days, Utc};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn tenant(id: &str, quota: &Value) -> TenantSettings {
+    let overlay: TenantOverlay = serde_json::from_value(json!({"quota": quota})).unwrap();
+    TenantSettings::new(id, &overlay, HashMap::new())
+}
+
+fn calls(tenant: &str, count: usize) {
+    for _ in 0..count {
+        usage::record_invocation(Some(tenant), "get_weather");
+    }
+}
+
+#[test]
+fn test_quota_deserializes() {
+    let quota: Quota = serde_json::from_value(json!({"daily": 500})).unwrap();
+    assert_eq!(quota.daily, Some(500));
+    assert_eq!(quota.monthly, None);
+    assert_eq!(quota.warn_percent, DEFAULT_WARN_PERCENT);
+
+    assert!(serde_json::from_value::<Quota>(json!({"weekly": 5})).is_err());
+}
+
+#[tokio::test]
+async fn test_daily_quota_blocks_calls() {
+    let settings = tenant("tenant-quota-daily", &json!({"daily": 2}));
+    calls("tenant-quota-daily", 2);
+
+    let err = quotas::check(&settings, "get_weather").await.unwrap_err();
+    let tomorrow = Utc::now().date_naive() + Days::new(1);
+    assert!(matches!(
+        &err,
+        AppError::QuotaExceeded { period: "daily", limit: 2, resets_at }
+            if resets_at == &format!("{tomorrow}T00:00:00Z")
+    ));
+
+    let diagnostic = err.to_diagnostic("QuotaExceeded");
+    assert_eq!(diagnostic.error_type, "QuotaExceeded");
+    assert!(diagnostic.error_message.starts_with("[E3004]"));
+    assert!(diagnostic.error_message.contains(&tomorrow.to_string()));
+}
+
+#[tokio::test]
+async fn test_monthly_quota_blocks_calls() {
+    let settings = tenant("tenant-quota-monthly", &json!({"daily": 100, "monthly": 1}));
+    calls("tenant-quota-monthly", 1);
+
+    let err = quotas::check(&settings, "get_weather").await.unwrap_err();
+    assert!(matches!(
+        err,
+        AppError::QuotaExceeded {
+            period: "monthly",
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_quota_warns_before_blocking() {
+    let settings = tenant(
+        "tenant-quota-warn",
+        &json!({"daily": 10, "warn_percent": 50}),
+    );
+    calls("tenant-quota-warn", 4);
+    assert_eq!(quotas::check(&settings, "get_weather").await.unwrap(), None);
+
+    calls("tenant-quota-warn", 1);
+    let warning = quotas::check(&settings, "get_weather")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (warning.period, warning.used, warning.limit),
+        ("daily", 5, 10)
+    );
+
+    let mut response = json!({"greeting": "Hello"});
+    quotas::attach_warning(&mut response, &warning);
+    assert_eq!(response["_meta"]["quota"]["used"], 5);
+    assert_eq!(response["_meta"]["quota"]["resetsAt"], warning.resets_at);
+}
+
+#[tokio::test]
+async fn test_admin_tools_and_unlimited_tenants_pass() {
+    let settings = tenant("tenant-quota-admin", &json!({"daily": 1}));
+    calls("tenant-quota-admin", 1);
+    assert!(
+        quotas::check(&settings, "admin_cache_stats")
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let unlimited = TenantSettings::new(
+        "tenant-unlimited",
+        &TenantOverlay::default(),
+        HashMap::new(),
+    );
+    calls("tenant-unlimited", 3);
+    assert!(
+        quotas::check(&unlimited, "get_weather")
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
@@ -0,0 +1,89 @@
+// Gateway tool registration tests
+#![cfg(feature = "register-tools")]
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::registry::{
+    schema_definition, target_configuration, target_lambda_arn, tool_definition, tool_definitions,
+    tools,
+};
+use aws_sdk_bedrockagentcorecontrol::types::{SchemaType, ToolDefinition};
+use serde_json::json;
+
+#[test]
+fn test_every_tool_is_defined() {
+    let definitions = tool_definitions().unwrap();
+    let names: Vec<_> = definitions.iter().map(ToolDefinition::name).collect();
+    let registered: Vec<_> = tools().iter().map(|tool| tool.name).collect();
+    assert_eq!(names, registered);
+
+    let configuration =
+        target_configuration("arn:aws:lambda:us-east-1:1:function:mcp", definitions).unwrap();
+    assert_eq!(
+        target_lambda_arn(&configuration),
+        Some("arn:aws:lambda:us-east-1:1:function:mcp")
+    );
+}
+
+#[test]
+fn test_nested_schemas_are_kept() {
+    let tool = tool_definition(&json!({
+        "name": "get_weather",
+        "description": "Gets the weather",
+        "inputSchema": {
+            "type": "object",
+            "properties": {"location": {"type": "string", "description": "City"}},
+            "required": ["location"]
+        },
+        "outputSchema": {
+            "type": "object",
+            "properties": {
+                "daily": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {"max": {"type": ["number", "null"]}}
+                    }
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    let input = tool.input_schema().unwrap();
+    assert_eq!(input.required(), ["location"]);
+    assert_eq!(
+        input.properties().unwrap()["location"].description(),
+        Some("City")
+    );
+    let daily = &tool.output_schema().unwrap().properties().unwrap()["daily"];
+    assert_eq!(daily.r#type(), &SchemaType::Array);
+    let max = &daily.items().unwrap().properties().unwrap()["max"];
+    assert_eq!(max.r#type(), &SchemaType::Number);
+}
+
+#[test]
+fn test_unions_are_flattened() {
+    // Nullable references keep their non-null variant and outer description
+    let location = schema_definition(&json!({
+        "description": "Resolved place",
+        "anyOf": [{"type": "object", "properties": {"name": {"type": "string"}}}, {"type": "null"}]
+    }))
+    .unwrap();
+    assert_eq!(location.r#type(), &SchemaType::Object);
+    assert_eq!(location.description(), Some("Resolved place"));
+    assert!(location.properties().unwrap().contains_key("name"));
+
+    // Unions of objects have the properties of every variant
+    let block = schema_definition(&json!({"oneOf": [
+        {"type": "object", "properties": {"text": {"type": "string"}}, "required": ["text"]},
+        {"type": "object", "properties": {"data": {"type": "string"}}}
+    ]}))
+    .unwrap();
+    let properties = block.properties().unwrap();
+    assert!(properties.contains_key("text") && properties.contains_key("data"));
+    assert!(block.required().is_empty());
+
+    // Values of any type are described as objects
+    let any = schema_definition(&json!({"description": "Recorded value"})).unwrap();
+    assert_eq!(any.r#type(), &SchemaType::Object);
+}
@@ -0,0 +1,96 @@
+// Data residency tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::models::{AppError, RecordConsentRequest};
+use aws_lambda_mcp::residency::{
+    Placement, ResidencyRegion, Store, check_write, place, place_write, placements,
+};
+use aws_lambda_mcp::tools::consent::record_consent;
+use common::token;
+use serde_json::json;
+
+#[test]
+fn test_residency_is_read_from_claim() {
+    let context = ToolContext::from_args(
+        "get_weather",
+        &json!({"auth_token": token(&json!({"sub": "ada", "residency": " EU "}))}),
+    );
+    assert_eq!(context.residency.as_deref(), Some("eu"));
+
+    let context = ToolContext::from_args(
+        "get_weather",
+        &json!({"auth_token": token(&json!({"sub": "ada"}))}),
+    );
+    assert_eq!(context.residency, None);
+}
+
+#[test]
+fn test_region_deserializes() {
+    let region: ResidencyRegion = serde_json::from_value(json!({
+        "region": "eu-central-1",
+        "tables": {"audit": "mcp-eu-invocation-audit"}
+    }))
+    .unwrap();
+    assert_eq!(region.region, "eu-central-1");
+    assert_eq!(region.tables["audit"], "mcp-eu-invocation-audit");
+
+    assert!(
+        serde_json::from_value::<ResidencyRegion>(json!({"region": "eu-central-1", "bucket": "x"}))
+            .is_err()
+    );
+    let names: Vec<_> = Store::ALL.map(Store::name).into();
+    assert_eq!(names, ["preferences", "memory", "audit"]);
+}
+
+#[test]
+fn test_unconfigured_residency_is_never_written() {
+    // No tables or residencies are configured by default
+    assert_eq!(place(Store::Memory, None).unwrap(), None);
+    assert!(placements(Store::Audit).is_empty());
+
+    let error = place_write(Store::Audit, Some("eu")).unwrap_err();
+    assert!(matches!(error, AppError::ResidencyDenied(_)));
+    assert!(
+        error
+            .to_diagnostic("ToolError")
+            .error_message
+            .starts_with("[E1009]")
+    );
+}
+
+#[test]
+fn test_cross_region_writes_are_refused() {
+    let home = Placement {
+        region: None,
+        table: "user-preferences".to_string(),
+    };
+    let frankfurt = Placement {
+        region: Some("eu-central-1".to_string()),
+        ..home.clone()
+    };
+    assert!(check_write(None, &home).is_ok());
+    assert!(check_write(None, &frankfurt).is_ok());
+    assert!(matches!(
+        check_write(Some("eu"), &home),
+        Err(AppError::ResidencyDenied(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_consent_of_unplaced_resident_is_refused() {
+    let mut caller = ToolContext::from_args(
+        "record_consent",
+        &json!({"auth_token": token(&json!({"sub": "ada", "residency": "eu"}))}),
+    );
+    caller.verified_user_id = Some("ada@example.com".to_string());
+    let err = Box::pin(context::scope(
+        caller,
+        record_consent(RecordConsentRequest { granted: true }),
+    ))
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ResidencyDenied(_)));
+}
@@ -0,0 +1,83 @@
+// MCP roots tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::models::{ListRootsResult, Root};
+use aws_lambda_mcp::roots::{self, RootsFuture, RootsProvider};
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Lists one root per call made so far, so refetches are visible
+#[derive(Default)]
+struct CountingProvider {
+    calls: AtomicUsize,
+}
+
+impl RootsProvider for CountingProvider {
+    fn list_roots(&self) -> RootsFuture {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        Box::pin(async move {
+            Ok(ListRootsResult {
+                roots: (0..call)
+                    .map(|n| Root {
+                        uri: format!("file:///work/project-{n}"),
+                        name: None,
+                    })
+                    .collect(),
+            })
+        })
+    }
+}
+
+fn context_with(provider: Arc<CountingProvider>, args: &serde_json::Value) -> ToolContext {
+    ToolContext::from_args("get_weather", args).with_roots_provider(provider)
+}
+
+#[tokio::test]
+async fn test_roots_are_cached_per_session() {
+    let provider = Arc::new(CountingProvider::default());
+    let session = json!({"session_id": "roots-session-1"});
+
+    let first = context::scope(context_with(provider.clone(), &session), roots::current()).await;
+    let second = context::scope(context_with(provider.clone(), &session), roots::current()).await;
+    assert_eq!(first.len(), 1);
+    assert_eq!(second, first);
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+    assert!(!roots::handle_notification(
+        "notifications/tools/list_changed",
+        Some("roots-session-1")
+    ));
+    assert!(roots::handle_notification(
+        "notifications/roots/list_changed",
+        Some("roots-session-1")
+    ));
+    let changed = context::scope(context_with(provider.clone(), &session), roots::current()).await;
+    assert_eq!(changed.len(), 2);
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_roots_without_session_or_provider() {
+    assert!(roots::current().await.is_empty());
+
+    let provider = Arc::new(CountingProvider::default());
+    for _ in 0..2 {
+        context::scope(context_with(provider.clone(), &json!({})), roots::current()).await;
+    }
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_root_contains() {
+    let root = Root {
+        uri: "file:///work/app/".to_string(),
+        name: Some("app".to_string()),
+    };
+    assert!(root.contains("file:///work/app"));
+    assert!(root.contains("file:///work/app/src/main.rs"));
+    assert!(!root.contains("file:///work/application"));
+    assert!(!root.contains("file:///work/app/../secrets"));
+    assert!(!root.contains("file:///work"));
+}
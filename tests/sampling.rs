@@ -0,0 +1,115 @@
+// MCP sampling tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::context::{self, ToolContext};
+use aws_lambda_mcp::deadline;
+use aws_lambda_mcp::models::error::AppError;
+use aws_lambda_mcp::models::{
+    CreateMessageParams, CreateMessageResult, SamplingContent, SamplingMessage,
+};
+use aws_lambda_mcp::sampling::{Sampler, SamplingFuture, sample_text};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Replies with the prompt in upper case, after `delay`
+struct EchoSampler {
+    delay: Duration,
+}
+
+impl Sampler for EchoSampler {
+    fn create_message(&self, params: CreateMessageParams) -> SamplingFuture {
+        let delay = self.delay;
+        Box::pin(async move {
+            tokio::time::sleep(delay).await;
+            let SamplingContent::Text { text } = &params.messages[0].content else {
+                return Err(AppError::GenericError("expected text".to_string()));
+            };
+            Ok(CreateMessageResult {
+                role: "assistant".to_string(),
+                content: SamplingContent::Text {
+                    text: format!(" {} ", text.to_uppercase()),
+                },
+                model: "test-model".to_string(),
+                stop_reason: Some("endTurn".to_string()),
+            })
+        })
+    }
+}
+
+struct FailingSampler;
+
+impl Sampler for FailingSampler {
+    fn create_message(&self, _params: CreateMessageParams) -> SamplingFuture {
+        Box::pin(async { Err(AppError::GenericError("client refused".to_string())) })
+    }
+}
+
+fn context_with(sampler: Arc<dyn Sampler>) -> ToolContext {
+    ToolContext::from_args("daily_briefing", &json!({})).with_sampler(sampler)
+}
+
+#[tokio::test]
+async fn test_sample_text_uses_client_model() {
+    let sampler = Arc::new(EchoSampler {
+        delay: Duration::ZERO,
+    });
+    let text = context::scope(
+        context_with(sampler),
+        sample_text("Be brief.", "hello".into()),
+    )
+    .await;
+    assert_eq!(text.as_deref(), Some("HELLO"));
+}
+
+#[tokio::test]
+async fn test_sample_text_falls_back() {
+    // No sampler outside a native MCP transport
+    assert_eq!(sample_text("Be brief.", "hello".into()).await, None);
+
+    let failing = context::scope(
+        context_with(Arc::new(FailingSampler)),
+        sample_text("Be brief.", "hello".into()),
+    )
+    .await;
+    assert_eq!(failing, None);
+
+    // The wait is capped by the invocation deadline, less its safety margin
+    let slow = Arc::new(EchoSampler {
+        delay: Duration::from_secs(5),
+    });
+    let timed_out = deadline::scope(
+        Some(Instant::now() + Duration::from_millis(600)),
+        context::scope(context_with(slow), sample_text("Be brief.", "hello".into())),
+    )
+    .await;
+    assert_eq!(timed_out, None);
+}
+
+#[test]
+fn test_create_message_wire_format() {
+    let params = CreateMessageParams {
+        messages: vec![SamplingMessage::user("Summarize the weather")],
+        system_prompt: Some("Be brief.".to_string()),
+        max_tokens: 300,
+        temperature: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&params).unwrap(),
+        json!({
+            "messages": [{"role": "user", "content": {"type": "text", "text": "Summarize the weather"}}],
+            "systemPrompt": "Be brief.",
+            "maxTokens": 300
+        })
+    );
+
+    let result: CreateMessageResult = serde_json::from_value(json!({
+        "role": "assistant",
+        "content": {"type": "image", "data": "aGk=", "mimeType": "image/png"},
+        "model": "client-model",
+        "stopReason": "endTurn"
+    }))
+    .unwrap();
+    assert_eq!(result.text(), None);
+    assert_eq!(result.stop_reason.as_deref(), Some("endTurn"));
+}
@@ -0,0 +1,109 @@
+// WASM sandbox tests
+#![allow(clippy::unwrap_used)]
+#![cfg(feature = "sandbox")]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::models::AppError;
+use aws_lambda_mcp::sandbox::{Limits, compile, execute};
+use serde_json::json;
+
+const LIMITS: Limits = Limits {
+    max_memory_bytes: 1024 * 1024,
+    fuel: 1_000_000,
+    max_output_bytes: 1024,
+};
+
+/// Returns its arguments unchanged.
+const ECHO: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "run") (param $ptr i32) (param $len i32) (result i64)
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len)))))
+"#;
+
+/// Returns a fixed result stored in its data segment.
+fn returning(result: &str) -> String {
+    format!(
+        r#"(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "{}")
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "run") (param i32 i32) (result i64) (i64.const {})))"#,
+        result.replace('"', "\\\""),
+        result.len()
+    )
+}
+
+fn run(wat: &str, arguments: &serde_json::Value) -> Result<serde_json::Value, AppError> {
+    let module = compile(&wat::parse_str(wat).unwrap())?;
+    execute(&module, arguments, &LIMITS).map(|execution| execution.output)
+}
+
+#[test]
+fn test_module_receives_arguments_and_returns_object() {
+    let module = compile(&wat::parse_str(ECHO).unwrap()).unwrap();
+    let execution = execute(&module, &json!({"name": "Ada"}), &LIMITS).unwrap();
+    assert_eq!(execution.output, json!({"name": "Ada"}));
+    assert!(execution.fuel_used > 0 && execution.fuel_used < LIMITS.fuel);
+}
+
+#[test]
+fn test_module_with_imports_is_refused() {
+    let wat = r#"(module (import "env" "fetch" (func (param i32))))"#;
+    let err = compile(&wat::parse_str(wat).unwrap()).unwrap_err();
+    assert!(matches!(err, AppError::SandboxError(_)));
+    assert!(err.to_string().contains("env.fetch"), "{err}");
+}
+
+#[test]
+fn test_runaway_module_runs_out_of_fuel() {
+    let wat = r#"(module
+      (memory (export "memory") 1)
+      (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+      (func (export "run") (param i32 i32) (result i64) (loop $spin (br $spin)) (i64.const 0)))"#;
+    let err = run(wat, &json!({})).unwrap_err();
+    assert!(err.to_string().contains("ran out of fuel"), "{err}");
+}
+
+#[test]
+fn test_memory_beyond_limit_is_refused() {
+    // 32 pages of 64 KiB is twice the limit
+    let wat = r#"(module
+      (memory (export "memory") 32)
+      (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+      (func (export "run") (param i32 i32) (result i64) (i64.const 0)))"#;
+    assert!(matches!(
+        run(wat, &json!({})),
+        Err(AppError::SandboxError(_))
+    ));
+}
+
+#[test]
+fn test_module_reported_error_is_returned() {
+    let err = run(&returning(r#"{"error":"unknown unit"}"#), &json!({})).unwrap_err();
+    assert_eq!(err.to_string(), "Sandboxed tool error: unknown unit");
+}
+
+#[test]
+fn test_non_object_and_oversized_results_are_refused() {
+    let err = run(&returning("[1,2]"), &json!({})).unwrap_err();
+    assert!(err.to_string().contains("not a JSON object"), "{err}");
+
+    let large = format!(r#"{{"text":"{}"}}"#, "a".repeat(2048));
+    let err = run(&returning(&large), &json!({})).unwrap_err();
+    assert!(err.to_string().contains("byte limit"), "{err}");
+}
+
+#[tokio::test]
+async fn test_unlisted_tool_is_refused() {
+    let err = route_tool(
+        "run_sandboxed_tool",
+        json!({"tool": "not_configured", "arguments": {}}),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.error_message.contains("not on the allowlist"));
+}
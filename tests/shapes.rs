@@ -0,0 +1,100 @@
+// Response shape changelog tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::config::config;
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::shapes::{self, fingerprint, structure};
+use common::admin_token;
+use serde_json::json;
+
+#[test]
+fn test_structure_ignores_values() {
+    let sydney = json!({"name": "Sydney", "latitude": -33.87, "days": [{"max": 22.4}]});
+    let paris = json!({"name": "Paris", "latitude": 48.85, "days": [{"max": 14}, {"max": 15}]});
+
+    assert_eq!(structure(&sydney), structure(&paris));
+    assert_eq!(
+        structure(&sydney),
+        json!({"name": "string", "latitude": "number", "days": [{"max": "number"}]})
+    );
+}
+
+#[test]
+fn test_structure_keeps_distinct_item_shapes() {
+    let response = json!({"items": [{"a": 1}, {"a": 2, "b": true}, {"a": 3}]});
+    // Item structures are sorted by their JSON text, so order does not matter
+    assert_eq!(
+        structure(&response),
+        json!({"items": [{"a": "number", "b": "boolean"}, {"a": "number"}]})
+    );
+    let reordered = json!({"items": [{"a": 2, "b": true}, {"a": 1}]});
+    assert_eq!(structure(&reordered), structure(&response));
+}
+
+#[test]
+fn test_structure_skips_nulls_and_meta() {
+    let response = json!({"name": "Sydney", "note": null, "_meta": {"invocationId": "abc"}});
+    assert_eq!(structure(&response), json!({"name": "string"}));
+}
+
+#[test]
+fn test_fingerprint_changes_with_fields() {
+    let before = structure(&json!({"temperature": 21.5}));
+    let renamed = structure(&json!({"temperatureMax": 21.5}));
+
+    assert_eq!(fingerprint(&before).len(), 16);
+    assert_eq!(fingerprint(&before), fingerprint(&before.clone()));
+    assert_ne!(fingerprint(&before), fingerprint(&renamed));
+}
+
+#[tokio::test]
+async fn test_observed_shapes_are_listed_once() {
+    shapes::observe("read_feed", &json!({"items": [], "title": "One"})).await;
+    shapes::observe("read_feed", &json!({"items": [], "title": "Two"})).await;
+
+    let history = shapes::history("read_feed").await.unwrap();
+    let expected = fingerprint(&json!({"items": [], "title": "string"}));
+    let matching: Vec<_> = history
+        .shapes
+        .iter()
+        .filter(|shape| shape.fingerprint == expected)
+        .collect();
+    assert_eq!(matching.len(), 1);
+    assert_eq!(matching[0].version, config().shapes.version);
+    // Every shape in one container belongs to the same deployment
+    assert!(!history.changed);
+}
+
+#[tokio::test]
+async fn test_tool_versions_reports_tools() {
+    let payload = json!({"tool": "get_personalized_greeting", "auth_token": admin_token()});
+    route_tool(
+        "get_personalized_greeting",
+        json!({"user_id": "ada@example.com"}),
+    )
+    .await
+    .unwrap();
+    let response = route_tool("admin_tool_versions", payload).await.unwrap();
+
+    assert_eq!(response["version"], config().shapes.version.as_str());
+    assert_eq!(response["tools"][0]["tool"], "get_personalized_greeting");
+    assert!(
+        !response["tools"][0]["shapes"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_tool_versions_unknown_tool() {
+    let payload = json!({"tool": "nope", "auth_token": admin_token()});
+    let err = route_tool("admin_tool_versions", payload)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("Unknown tool: nope"));
+}
@@ -0,0 +1,74 @@
+// Service level objective tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::catalog::{INVALID_INPUT, WEATHER_UNAVAILABLE};
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::metrics::render_prometheus;
+use aws_lambda_mcp::registry::tools;
+use aws_lambda_mcp::slo::{Sli, Slo, burn_rate, events};
+use serde_json::json;
+use std::time::Duration;
+
+#[test]
+fn test_burn_rate() {
+    // 0.5% errors against a 99.5% objective burns the budget exactly on time
+    assert!((burn_rate(0.995, 5, 1_000) - 1.0).abs() < 1e-9);
+    assert!((burn_rate(0.99, 10, 100) - 10.0).abs() < 1e-9);
+    assert!((burn_rate(0.999, 1, 1) - 1_000.0).abs() < 1e-6);
+    assert!(burn_rate(0.99, 0, 100).abs() < f64::EPSILON);
+    assert!(burn_rate(0.99, 0, 0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_events() {
+    let slo = Slo::UPSTREAM;
+    let ok = Ok(json!({}));
+    assert_eq!(
+        events(&slo, &ok, Duration::from_millis(300)),
+        [(Sli::Availability, true), (Sli::Latency, true)]
+    );
+    assert_eq!(
+        events(&slo, &ok, Duration::from_secs(3)),
+        [(Sli::Availability, true), (Sli::Latency, false)]
+    );
+
+    // Upstream failures burn the availability budget only
+    let failed = Err(WEATHER_UNAVAILABLE.diagnostic("ToolError", ""));
+    assert_eq!(
+        events(&slo, &failed, Duration::from_secs(3)),
+        [(Sli::Availability, false)]
+    );
+
+    // Request errors are the caller's to fix
+    let rejected = Err(INVALID_INPUT.diagnostic("InvalidInput", "missing field"));
+    assert!(events(&slo, &rejected, Duration::ZERO).is_empty());
+}
+
+#[test]
+fn test_every_tool_declares_achievable_objectives() {
+    for tool in tools() {
+        for sli in Sli::ALL {
+            let objective = tool.slo.objective(sli);
+            assert!(
+                objective > 0.0 && objective < 1.0,
+                "{} {} objective {objective}",
+                tool.name,
+                sli.label()
+            );
+        }
+        assert!(tool.slo.latency > Duration::ZERO, "{}", tool.name);
+    }
+}
+
+#[tokio::test]
+async fn test_calls_are_counted_against_slo() {
+    route_tool("describe_server", json!({})).await.unwrap();
+
+    let text = render_prometheus();
+    assert!(text.contains("# TYPE mcp_slo_events_total counter"));
+    assert!(text.contains(
+        r#"mcp_slo_events_total{tool="describe_server",sli="availability",outcome="good"} "#
+    ));
+    assert!(text.contains(r#"mcp_slo_objective{tool="get_weather",sli="availability"} 0.995"#));
+    assert!(text.contains(r#"mcp_slo_burn_rate{tool="describe_server",sli="availability"} 0"#));
+}
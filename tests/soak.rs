@@ -0,0 +1,107 @@
+// Soak tests under injected upstream faults
+#![cfg(feature = "chaos")]
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::http::faults::{self, FaultInjector};
+use aws_lambda_mcp::http::{Fault, FaultProfile};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const INVOCATIONS: u64 = 2_000;
+
+/// Calls `get_weather` for a city the gazetteer resolves offline, so only
+/// the forecast request reaches the (faulty) upstream, and returns how many
+/// calls ended with each catalog code.
+async fn soak(invocations: u64) -> BTreeMap<String, u64> {
+    let mut outcomes = BTreeMap::new();
+    for _ in 0..invocations {
+        let code = match route_tool("get_weather", json!({"location": "Sydney"})).await {
+            Ok(_) => "ok".to_string(),
+            Err(diagnostic) => {
+                assert_eq!(diagnostic.error_type, "ToolError");
+                let code = diagnostic.error_message.get(1..6).unwrap_or_default();
+                assert!(
+                    diagnostic.error_message.starts_with(&format!("[{code}] ")),
+                    "uncatalogued error: {}",
+                    diagnostic.error_message
+                );
+                code.to_string()
+            }
+        };
+        *outcomes.entry(code).or_insert(0) += 1;
+    }
+    outcomes
+}
+
+#[test]
+fn test_fault_injector_matches_profile() {
+    let profile =
+        FaultProfile::parse("timeout=0.1;server_error=0.2;malformed=0.1;slow=0.1").unwrap();
+    let injector = FaultInjector::new(profile);
+    for _ in 0..100_000 {
+        injector.draw();
+    }
+    assert_eq!(injector.draws(), 100_000);
+    for (fault, expected) in [
+        (Fault::Timeout, 10_000),
+        (Fault::ServerError, 20_000),
+        (Fault::Malformed, 10_000),
+        (Fault::Slow, 10_000),
+    ] {
+        let injected = injector.injected(fault);
+        assert!(
+            injected.abs_diff(expected) < expected / 20,
+            "{fault} injected {injected} times, expected about {expected}"
+        );
+    }
+
+    // The same seed injects the same faults
+    let again = FaultInjector::new(profile);
+    let first: Vec<_> = (0..1_000).map(|_| again.draw()).collect();
+    let replay = FaultInjector::new(profile);
+    let second: Vec<_> = (0..1_000).map(|_| replay.draw()).collect();
+    assert_eq!(first, second);
+}
+
+// One test drives the shared Open-Meteo circuit breaker through each phase
+// in turn, as parallel tests would trip it for each other
+#[tokio::test]
+async fn test_soak_under_upstream_faults() {
+    // Malformed bodies arrive with a 200, so the breaker stays closed and
+    // every call reaches the upstream and fails to parse
+    let injector = faults::install(FaultProfile::only(Fault::Malformed, 1.0));
+    let outcomes = soak(INVOCATIONS).await;
+    assert_eq!(
+        outcomes,
+        BTreeMap::from([("E2002".to_string(), INVOCATIONS)])
+    );
+    assert_eq!(injector.draws(), INVOCATIONS);
+
+    // Timeouts and server errors trip the breaker, after which calls fail
+    // fast as rate limited without reaching the upstream
+    let injector = faults::install(FaultProfile {
+        delay: Duration::ZERO,
+        seed: 7,
+        ..FaultProfile::parse("timeout=0.5;server_error=0.5").unwrap()
+    });
+    let outcomes = soak(INVOCATIONS).await;
+    let requests = injector.draws();
+    assert!(requests > 0);
+    assert!(injector.injected(Fault::Timeout) > 0 || injector.injected(Fault::ServerError) > 0);
+    assert_eq!(outcomes.get("ok"), None);
+    let failed = outcomes.get("E2002").copied().unwrap_or_default();
+    assert!(failed > 0 && failed < 20, "{outcomes:?}");
+    assert_eq!(outcomes.get("E2010").copied(), Some(INVOCATIONS - failed));
+
+    // An open breaker keeps the upstream untouched however long the soak
+    let outcomes = soak(INVOCATIONS).await;
+    assert_eq!(
+        outcomes,
+        BTreeMap::from([("E2010".to_string(), INVOCATIONS)])
+    );
+    assert_eq!(injector.draws(), requests);
+
+    faults::clear();
+}
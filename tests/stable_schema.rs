@@ -0,0 +1,98 @@
+// Deterministic schema output tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::registry::{self, BedrockExporter, EXPORTERS, SchemaExporter, passes, render};
+use serde_json::{Value, json};
+
+// Every `required` list in a schema, at any depth
+fn required_lists(value: &Value, lists: &mut Vec<Vec<String>>) {
+    match value {
+        Value::Object(obj) => {
+            for (key, nested) in obj {
+                if key == "required"
+                    && let Some(names) = nested.as_array()
+                {
+                    lists.push(
+                        names
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .map(String::from)
+                            .collect(),
+                    );
+                }
+                required_lists(nested, lists);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                required_lists(item, lists);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn test_normalize_sorts_keys_and_required() {
+    let mut schema = json!({
+        "type": "object",
+        "required": ["when", "location", "when"],
+        "properties": {
+            "when": {"type": "string"},
+            "location": {
+                "type": "object",
+                "required": ["name", "country"],
+                "properties": {"name": {"type": "string"}, "country": {"type": "string"}}
+            }
+        }
+    });
+    passes::normalize(&mut schema);
+    assert_eq!(schema["required"], json!(["location", "when"]));
+    assert_eq!(
+        schema["properties"]["location"]["required"],
+        json!(["country", "name"])
+    );
+    let rendered = render(&schema).unwrap();
+    assert!(rendered.find("\"properties\"").unwrap() < rendered.find("\"required\"").unwrap());
+    assert!(rendered.find("\"location\"").unwrap() < rendered.find("\"when\"").unwrap());
+}
+
+#[test]
+fn test_normalize_keeps_other_arrays_in_order() {
+    let mut schema = json!({"enum": ["metric", "imperial"], "type": "string"});
+    passes::normalize(&mut schema);
+    assert_eq!(schema["enum"], json!(["metric", "imperial"]));
+}
+
+#[test]
+fn test_regeneration_is_byte_identical() {
+    for exporter in EXPORTERS {
+        let first = render(&exporter.document(registry::tools()).unwrap()).unwrap();
+        let second = render(&exporter.document(registry::tools()).unwrap()).unwrap();
+        assert_eq!(first, second, "{}", exporter.name());
+        assert!(first.ends_with('\n'), "{}", exporter.name());
+    }
+}
+
+#[test]
+fn test_tool_schema_json_is_up_to_date() {
+    let generated = render(&BedrockExporter.document(registry::tools()).unwrap()).unwrap();
+    let committed = std::fs::read_to_string("tool_schema.json").unwrap();
+    assert!(
+        generated == committed,
+        "tool_schema.json is stale; run `make schema`"
+    );
+}
+
+#[test]
+fn test_required_lists_are_sorted() {
+    for exporter in EXPORTERS {
+        let mut lists = Vec::new();
+        for descriptor in registry::tools() {
+            required_lists(&exporter.tool(descriptor).unwrap(), &mut lists);
+        }
+        for names in lists {
+            assert!(names.is_sorted(), "{}: {names:?}", exporter.name());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Summarization tool tests
+#![allow(clippy::unwrap_used)]
+#![cfg(feature = "summarize")]
+
+use aws_lambda_mcp::handler::route_tool;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_summarize_rejects_empty_text() {
+    let payload = json!({"text": "   "});
+    let err = route_tool("summarize_text", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("must not be empty"));
+}
+
+#[tokio::test]
+async fn test_summarize_rejects_oversized_input_before_invoking_model() {
+    // Default limit is 50,000 characters
+    let payload = json!({"text": "a".repeat(50_001)});
+    let err = route_tool("summarize_text", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("character limit"));
+}
+
+#[tokio::test]
+async fn test_summarize_invalid_arguments() {
+    let payload = json!({"params": {"arguments": {"content": "missing text field"}}});
+    let err = route_tool("summarize_text", payload).await.unwrap_err();
+
+    assert_eq!(err.error_type, "InvalidInput");
+}
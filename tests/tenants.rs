@@ -0,0 +1,106 @@
+// Tenant overlay tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::context::ToolContext;
+use aws_lambda_mcp::models::UnitSystem;
+use aws_lambda_mcp::tenants::{self, TenantOverlay, TenantSettings};
+use common::token;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn overlay(value: Value) -> TenantOverlay {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_overlay_deserializes() {
+    let overlay = overlay(json!({
+        "enabled_tools": ["get_weather"],
+        "tool_concurrency": {"get_weather": 4},
+        "api_keys": {"open_meteo": "team-a/open-meteo"},
+        "units": "imperial"
+    }));
+
+    assert_eq!(overlay.enabled_tools, Some(vec!["get_weather".to_string()]));
+    assert_eq!(overlay.tool_concurrency["get_weather"], 4);
+    assert_eq!(overlay.api_keys["open_meteo"], "team-a/open-meteo");
+    assert_eq!(overlay.units, Some(UnitSystem::Imperial));
+}
+
+#[test]
+fn test_overlay_rejects_unknown_fields() {
+    let result = serde_json::from_value::<TenantOverlay>(json!({"enabled_tool": ["get_weather"]}));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_enabled_tools_narrow_calls() {
+    let settings = TenantSettings::new(
+        "tenant-a",
+        &overlay(json!({"enabled_tools": ["get_weather"]})),
+        HashMap::new(),
+    );
+    assert!(settings.allows("get_weather"));
+    assert!(settings.check("get_weather").is_ok());
+
+    let err = settings.check("send_notification").unwrap_err();
+    assert!(err.to_string().contains("not enabled for this tenant"));
+
+    let open = TenantSettings::new("tenant-b", &TenantOverlay::default(), HashMap::new());
+    assert!(open.allows("send_notification"));
+}
+
+#[test]
+fn test_tenant_default_arguments() {
+    let settings = TenantSettings::new(
+        "tenant-a",
+        &overlay(json!({"units": "imperial"})),
+        HashMap::new(),
+    );
+    assert_eq!(
+        Value::Object(settings.arguments()),
+        json!({"units": "imperial"})
+    );
+
+    let empty = TenantSettings::new("tenant-b", &TenantOverlay::default(), HashMap::new());
+    assert!(empty.arguments().is_empty());
+}
+
+#[test]
+fn test_api_keys_are_not_logged() {
+    let settings = TenantSettings::new(
+        "tenant-a",
+        &TenantOverlay::default(),
+        HashMap::from([("open_meteo".to_string(), "s3cr3t-key".to_string())]),
+    );
+
+    assert_eq!(settings.api_key("open_meteo"), Some("s3cr3t-key"));
+    assert_eq!(settings.api_key("nager"), None);
+    let debug = format!("{settings:?}");
+    assert!(debug.contains("open_meteo"));
+    assert!(!debug.contains("s3cr3t-key"));
+}
+
+#[test]
+fn test_tenant_from_token_claims() {
+    let with_tenant = token(&json!({"sub": "user-1", "tid": " tenant-a "}));
+    let context = ToolContext::from_args("get_weather", &json!({"auth_token": with_tenant}));
+    assert_eq!(context.tenant.as_deref(), Some("tenant-a"));
+
+    let without_tenant = token(&json!({"sub": "user-1"}));
+    let context = ToolContext::from_args("get_weather", &json!({"auth_token": without_tenant}));
+    assert_eq!(context.tenant, None);
+}
+
+#[tokio::test]
+async fn test_settings_without_overlay() {
+    assert!(tenants::settings(None).await.unwrap().is_none());
+    assert!(
+        tenants::settings(Some("unknown-tenant"))
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
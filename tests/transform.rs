@@ -0,0 +1,53 @@
+// Response transform tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::AppError;
+use aws_lambda_mcp::transform::{self, Transform};
+use serde_json::json;
+
+#[test]
+fn test_transform_reshapes_response() {
+    let transform = Transform::compile(
+        "{location: city.name, readings: list[].{time: dt_txt, temperature: main.temp}}",
+    )
+    .unwrap();
+    let upstream = json!({
+        "city": {"name": "Sydney", "country": "AU"},
+        "list": [
+            {"dt_txt": "2025-01-01 00:00", "main": {"temp": 21.5, "humidity": 60}},
+            {"dt_txt": "2025-01-01 03:00", "main": {"temp": 23.0, "humidity": 55}}
+        ]
+    });
+    assert_eq!(
+        transform.apply(upstream).unwrap(),
+        json!({
+            "location": "Sydney",
+            "readings": [
+                {"time": "2025-01-01 00:00", "temperature": 21.5},
+                {"time": "2025-01-01 03:00", "temperature": 23.0}
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_invalid_expression_is_refused_when_compiled() {
+    let err = Transform::compile("list[?main.temp >").unwrap_err();
+    assert!(err.contains("invalid JMESPath expression"), "{err}");
+}
+
+#[test]
+fn test_failed_evaluation_is_a_transform_error() {
+    let transform = Transform::compile("length(count)").unwrap();
+    let err = transform.apply(json!({"count": 3})).unwrap_err();
+    assert!(matches!(err, AppError::TransformError(_)));
+}
+
+#[test]
+fn test_tools_without_transform_are_unchanged() {
+    let response = json!({"greeting": "Hello, Ada!"});
+    assert_eq!(
+        transform::apply("get_personalized_greeting", response.clone()).unwrap(),
+        response
+    );
+}
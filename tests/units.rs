@@ -0,0 +1,131 @@
+// Typed weather value and time tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::open_meteo::OpenMeteoResponse;
+use aws_lambda_mcp::models::{
+    Celsius, Centimeters, LocalTime, Millimeters, Temperature, UnitSystem, WeatherResponseV2,
+    WmoCode,
+};
+use serde_json::{Value, json};
+
+fn forecast() -> Value {
+    let raw = std::fs::read_to_string("tests/fixtures/contracts/open_meteo_forecast.json").unwrap();
+    serde_json::from_str(&raw).unwrap()
+}
+
+#[test]
+fn test_measurements_serialize_tagged_with_their_unit() {
+    let temperature: Celsius = serde_json::from_value(json!(-12.5)).unwrap();
+    assert!((temperature.get() + 12.5).abs() < f64::EPSILON);
+    assert_eq!(
+        serde_json::to_value(temperature).unwrap(),
+        json!({"value": -12.5, "unit": "°C"})
+    );
+    let amount = Millimeters::try_from(3.2).unwrap();
+    assert_eq!(
+        serde_json::to_value(amount).unwrap(),
+        json!({"value": 3.2, "unit": "mm"})
+    );
+
+    let tagged: Celsius = serde_json::from_value(json!({"value": 21.5, "unit": "°C"})).unwrap();
+    assert!((tagged.get() - 21.5).abs() < f64::EPSILON);
+    let err = serde_json::from_value::<Celsius>(json!({"value": 70.7, "unit": "°F"})).unwrap_err();
+    assert!(err.to_string().contains("°F"), "{err}");
+
+    let code: WmoCode = serde_json::from_value(json!(95)).unwrap();
+    assert_eq!(serde_json::to_value(code).unwrap(), json!(95));
+    assert_eq!(code.to_string(), "95");
+}
+
+#[test]
+fn test_implausible_values_are_rejected() {
+    assert!(serde_json::from_value::<WmoCode>(json!(9999)).is_err());
+    assert!(serde_json::from_value::<WmoCode>(json!(4)).is_err());
+    assert!(serde_json::from_value::<Celsius>(json!(1000.0)).is_err());
+    // A °F reading passed off as °C is caught
+    assert!(serde_json::from_value::<Celsius>(json!(95.0)).is_err());
+    assert!(Celsius::try_from(f64::NAN).is_err());
+    assert!(serde_json::from_value::<Millimeters>(json!(-1.0)).is_err());
+    assert!(serde_json::from_value::<Centimeters>(json!({"value": -1.0, "unit": "cm"})).is_err());
+}
+
+#[test]
+fn test_temperatures_convert_for_imperial_callers() {
+    let celsius = Celsius::try_from(20.0).unwrap();
+    let metric = Temperature::new(celsius, UnitSystem::Metric);
+    let imperial = Temperature::new(celsius, UnitSystem::Imperial);
+
+    assert_eq!(metric, Temperature::from(celsius));
+    assert!((imperial.get() - 68.0).abs() < 1e-9);
+    assert_eq!(imperial.unit(), "°F");
+    assert_eq!(imperial.to_string(), "68");
+    assert_eq!(
+        serde_json::to_value(imperial).unwrap(),
+        json!({"value": 68.0, "unit": "°F"})
+    );
+    let parsed: Temperature = serde_json::from_value(json!({"value": 68.0, "unit": "°F"})).unwrap();
+    assert_eq!(parsed, imperial);
+}
+
+#[test]
+fn test_imperial_response_converts_the_metric_forecast() {
+    let forecast: OpenMeteoResponse = serde_json::from_value(forecast()).unwrap();
+    let metric = WeatherResponseV2::new(forecast.clone(), UnitSystem::Metric);
+    let imperial = WeatherResponseV2::new(forecast, UnitSystem::Imperial);
+
+    assert_eq!(imperial.temperature_unit, "°F");
+    let (metric_max, imperial_max) = (
+        metric.days[0].temperature_max.get(),
+        imperial.days[0].temperature_max.get(),
+    );
+    assert!((imperial_max - (metric_max * 9.0 / 5.0 + 32.0)).abs() < 1e-9);
+    assert_eq!(imperial.days[0].temperature_max.unit(), "°F");
+}
+
+#[test]
+fn test_forecast_with_invalid_code_is_rejected() {
+    let mut forecast = forecast();
+    assert!(serde_json::from_value::<OpenMeteoResponse>(forecast.clone()).is_ok());
+
+    forecast["daily"]["weather_code"][0] = json!(9999);
+    let err = serde_json::from_value::<OpenMeteoResponse>(forecast).unwrap_err();
+    assert!(err.to_string().contains("9999"), "{err}");
+}
+
+#[test]
+fn test_schemas_describe_the_tagged_form() {
+    let schema = schemars::schema_for!(Celsius);
+    assert_eq!(schema.get("type"), Some(&json!("object")));
+    assert_eq!(
+        schema.get("properties").unwrap()["unit"],
+        json!({"const": "°C"})
+    );
+    assert_eq!(
+        schemars::schema_for!(WmoCode).get("type"),
+        Some(&json!("integer"))
+    );
+}
+
+#[test]
+fn test_local_times_keep_open_meteo_format() {
+    let time: LocalTime = serde_json::from_value(json!("2026-10-14T09:15")).unwrap();
+    assert_eq!(
+        serde_json::to_value(time).unwrap(),
+        json!("2026-10-14T09:15")
+    );
+    assert_eq!(time.date().to_string(), "2026-10-14");
+    assert_eq!(
+        time.with_offset(39600).to_rfc3339(),
+        "2026-10-14T09:15:00+11:00"
+    );
+
+    assert!(serde_json::from_value::<LocalTime>(json!("2026-10-14T09:15:30")).is_ok());
+    assert!(serde_json::from_value::<LocalTime>(json!("14/10/2026 09:15")).is_err());
+}
+
+#[test]
+fn test_forecast_with_invalid_date_is_rejected() {
+    let mut forecast = forecast();
+    forecast["daily"]["time"][0] = json!("2026-13-01");
+    assert!(serde_json::from_value::<OpenMeteoResponse>(forecast).is_err());
+}
@@ -0,0 +1,68 @@
+// Usage accounting tests
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::usage::{self, NO_TENANT};
+use chrono::Utc;
+use common::{admin_token, token};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_calls_are_counted_per_tenant() {
+    let caller = token(&json!({"sub": "user-1", "tid": "tenant-usage"}));
+    for _ in 0..2 {
+        route_tool(
+            "get_personalized_greeting",
+            json!({"user_id": "ada@example.com", "auth_token": caller}),
+        )
+        .await
+        .unwrap();
+    }
+
+    let payload = json!({"tenant": "tenant-usage", "auth_token": admin_token()});
+    let response = route_tool("admin_usage_report", payload).await.unwrap();
+
+    assert_eq!(response["period"], Utc::now().format("%Y-%m").to_string());
+    let tenants = response["tenants"].as_array().unwrap();
+    assert_eq!(tenants.len(), 1);
+    assert_eq!(tenants[0]["tenant"], "tenant-usage");
+    assert_eq!(tenants[0]["invocations"], 2);
+    assert_eq!(
+        tenants[0]["tools"],
+        json!([{"tool": "get_personalized_greeting", "invocations": 2, "upstream_calls": 0}])
+    );
+}
+
+#[tokio::test]
+async fn test_callers_without_tenant_are_counted() {
+    usage::record_invocation(None, "read_feed");
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let tenants = usage::report(Some(NO_TENANT), &today).await.unwrap();
+
+    assert_eq!(tenants.len(), 1);
+    assert!(
+        tenants[0]
+            .tools
+            .iter()
+            .any(|tool| tool.tool == "read_feed" && tool.invocations >= 1)
+    );
+}
+
+#[tokio::test]
+async fn test_admin_tools_are_not_counted() {
+    usage::record_invocation(Some("tenant-admin"), "admin_cache_stats");
+    let tenants = usage::report(Some("tenant-admin"), "").await.unwrap();
+    assert!(tenants.is_empty());
+}
+
+#[tokio::test]
+async fn test_usage_report_rejects_invalid_period() {
+    for period in ["2025-1", "January", "2025-13", "2025-01-32"] {
+        let payload = json!({"period": period, "auth_token": admin_token()});
+        let err = route_tool("admin_usage_report", payload).await.unwrap_err();
+        assert_eq!(err.error_type, "ToolError");
+        assert!(err.error_message.contains("Invalid period"), "{period}");
+    }
+}
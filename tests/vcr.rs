@@ -0,0 +1,66 @@
+// HTTP record/replay tests
+
+use aws_lambda_mcp::http::vcr::Fixture;
+use aws_lambda_mcp::http::{Vcr, VcrMode, passthrough};
+use reqwest::{Client, Url};
+use std::path::Path;
+
+const FIXTURES: &str = "tests/fixtures/http";
+const GEOCODE_URL: &str =
+    "https://geocoding-api.open-meteo.com/v1/search?name=Sydney&count=1&language=en&format=json";
+
+#[test]
+fn test_vcr_mode_parsing() {
+    assert_eq!(VcrMode::parse("Replay"), Some(VcrMode::Replay));
+    assert_eq!(VcrMode::parse(" record "), Some(VcrMode::Record));
+    assert_eq!(VcrMode::parse(""), Some(VcrMode::Off));
+    assert_eq!(VcrMode::parse("rewind"), None);
+}
+
+#[tokio::test]
+async fn test_replay_serves_recorded_fixture() -> anyhow::Result<()> {
+    let client = Client::new();
+    let vcr = Vcr::new(VcrMode::Replay, FIXTURES);
+
+    let response = vcr
+        .execute(&client, client.get(GEOCODE_URL).build()?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await?;
+    assert_eq!(body["results"][0]["timezone"], "Australia/Sydney");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_passthrough_keeps_body_unchanged() -> anyhow::Result<()> {
+    let client = Client::new();
+    let vcr = Vcr::new(VcrMode::Replay, FIXTURES);
+    let path = Fixture::path(Path::new(FIXTURES), "GET", &Url::parse(GEOCODE_URL)?);
+    let fixture: Fixture = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+
+    let response = vcr
+        .execute(&client, client.get(GEOCODE_URL).build()?)
+        .await?;
+    let body = passthrough(response).await?;
+    assert_eq!(body.get(), fixture.body.trim());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_without_fixture_fails() -> anyhow::Result<()> {
+    let client = Client::new();
+    let vcr = Vcr::new(VcrMode::Replay, FIXTURES);
+    let url = "https://geocoding-api.open-meteo.com/v1/search?name=Atlantis";
+
+    let error = vcr
+        .execute(&client, client.get(url).build()?)
+        .await
+        .err()
+        .map(|e| e.to_string())
+        .unwrap_or_default();
+    assert!(error.contains("No recorded fixture"), "unexpected: {error}");
+
+    let expected = Fixture::path(Path::new(FIXTURES), "GET", &Url::parse(url)?);
+    assert!(error.contains(&expected.display().to_string()));
+    Ok(())
+}
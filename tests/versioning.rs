@@ -0,0 +1,61 @@
+// Response versioning tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::models::open_meteo::OpenMeteoResponse;
+use aws_lambda_mcp::models::{ResponseVersion, Versioned, WeatherRequest, WeatherResponseV2};
+use serde_json::json;
+
+#[test]
+fn test_response_version_defaults_to_v1() {
+    let request: WeatherRequest = serde_json::from_value(json!({"location": "Sydney"})).unwrap();
+    assert_eq!(request.response_version, ResponseVersion::V1);
+
+    let request: WeatherRequest =
+        serde_json::from_value(json!({"location": "Sydney", "response_version": "v2"})).unwrap();
+    assert_eq!(request.response_version, ResponseVersion::V2);
+
+    assert!(
+        serde_json::from_value::<WeatherRequest>(
+            json!({"location": "Sydney", "response_version": "9"})
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_response_version_header_parsing() {
+    assert_eq!(ResponseVersion::parse(" V2 "), Some(ResponseVersion::V2));
+    assert_eq!(ResponseVersion::parse("1"), Some(ResponseVersion::V1));
+    assert_eq!(ResponseVersion::parse("latest"), None);
+}
+
+#[test]
+fn test_versioned_serializes_selected_shape() {
+    #[derive(serde::Serialize)]
+    struct Reading {
+        celsius: f64,
+    }
+    impl From<f64> for Reading {
+        fn from(celsius: f64) -> Self {
+            Self { celsius }
+        }
+    }
+
+    let v1 = Versioned::<f64, Reading>::negotiate(21.5, ResponseVersion::V1);
+    let v2 = Versioned::<f64, Reading>::negotiate(21.5, ResponseVersion::V2);
+    assert_eq!(serde_json::to_value(v1).unwrap(), json!(21.5));
+    assert_eq!(serde_json::to_value(v2).unwrap(), json!({"celsius": 21.5}));
+}
+
+#[test]
+fn test_v2_without_current_conditions() {
+    let raw = std::fs::read_to_string("tests/fixtures/contracts/open_meteo_forecast.json").unwrap();
+    let mut forecast: OpenMeteoResponse = serde_json::from_str(&raw).unwrap();
+    forecast.current = None;
+    forecast.current_units = None;
+
+    let response = serde_json::to_value(WeatherResponseV2::from(forecast)).unwrap();
+    assert!(response.get("current").is_none());
+    assert!(response.get("windSpeedUnit").is_none());
+    assert_eq!(response["days"].as_array().unwrap().len(), 7);
+}
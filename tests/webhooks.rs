@@ -0,0 +1,69 @@
+// Webhook notifier tests
+#![allow(clippy::unwrap_used)]
+
+use aws_lambda_mcp::webhooks::{self, WebhookEvent, WebhookPayload, sign};
+use serde_json::json;
+
+#[test]
+fn test_sign_matches_hmac_sha256() {
+    // RFC 4231 test case 2
+    assert_eq!(
+        sign("Jefe", "what do ya want for nothing?"),
+        "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    assert_ne!(sign("Jefe", "{}"), sign("other", "{}"));
+}
+
+#[test]
+fn test_payload_flattens_event() {
+    let payload = WebhookPayload::new(WebhookEvent::QuotaThreshold {
+        tenant: "tenant-a".to_string(),
+        period: "daily".to_string(),
+        used: 400,
+        limit: 500,
+        exceeded: false,
+        resets_at: "2025-01-02T00:00:00Z".to_string(),
+    });
+    let body = serde_json::to_value(&payload).unwrap();
+
+    assert_eq!(body["event"], "quota_threshold");
+    assert_eq!(body["tenant"], "tenant-a");
+    assert_eq!(body["exceeded"], false);
+    assert_eq!(body["id"].as_str().unwrap().len(), 32);
+    assert!(body["timestamp"].is_string());
+}
+
+#[test]
+fn test_event_names() {
+    let spike = WebhookEvent::ToolFailureSpike {
+        tool: "get_weather".to_string(),
+        failures: 5,
+        window_secs: 60,
+    };
+    let closed = WebhookEvent::CircuitBreaker {
+        upstream: "open-meteo".to_string(),
+        open: false,
+        open_secs: None,
+    };
+
+    assert_eq!(spike.name(), "tool_failure_spike");
+    assert_eq!(closed.name(), "circuit_breaker");
+    assert_eq!(
+        serde_json::to_value(&closed).unwrap(),
+        json!({"event": "circuit_breaker", "upstream": "open-meteo", "open": false})
+    );
+}
+
+#[tokio::test]
+async fn test_events_without_endpoints_are_dropped() {
+    // Without WEBHOOK_URLS nothing is queued or sent
+    for _ in 0..10 {
+        webhooks::record_failure("get_weather");
+    }
+    webhooks::emit(WebhookEvent::CircuitBreaker {
+        upstream: "nager".to_string(),
+        open: true,
+        open_secs: Some(30),
+    });
+    webhooks::retry_due();
+}
@@ -0,0 +1,51 @@
+// Workflow tool and schema validation tests
+#![allow(clippy::unwrap_used)]
+#![cfg(feature = "workflows")]
+
+use aws_lambda_mcp::handler::route_tool;
+use aws_lambda_mcp::tools::workflow::validate_input;
+use aws_lambda_mcp::utils::schema::validate;
+use serde_json::json;
+
+#[test]
+fn test_schema_validation() {
+    let schema = json!({
+        "type": "object",
+        "required": ["order_id"],
+        "additionalProperties": false,
+        "properties": {
+            "order_id": {"type": "string", "minLength": 3},
+            "priority": {"enum": ["low", "high"]},
+            "items": {"type": "array", "items": {"type": "integer", "minimum": 1}}
+        }
+    });
+
+    assert!(validate(&schema, &json!({"order_id": "A-100", "items": [1, 2]})).is_ok());
+    assert!(validate(&schema, &json!({"priority": "low"})).is_err());
+    assert!(validate(&schema, &json!({"order_id": "A"})).is_err());
+    assert!(validate(&schema, &json!({"order_id": "A-100", "priority": "urgent"})).is_err());
+    assert!(validate(&schema, &json!({"order_id": "A-100", "extra": true})).is_err());
+
+    let err = validate(&schema, &json!({"order_id": "A-100", "items": [1, 0]})).unwrap_err();
+    assert!(err.contains("$.items[1]"), "unexpected error: {err}");
+}
+
+#[test]
+fn test_workflow_input_must_be_object() {
+    assert_eq!(validate_input(None, json!(null)).unwrap(), "{}");
+    assert!(validate_input(None, json!([1, 2])).is_err());
+    assert!(validate_input(None, json!({"key": "value"})).is_ok());
+}
+
+#[tokio::test]
+async fn test_workflow_status_rejects_unlisted_execution() {
+    let payload = json!({
+        "execution_arn": "arn:aws:states:ap-southeast-2:123456789012:execution:Unknown:run-1"
+    });
+    let err = route_tool("get_workflow_status", payload)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.error_type, "ToolError");
+    assert!(err.error_message.contains("allowlisted"));
+}